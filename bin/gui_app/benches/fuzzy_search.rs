@@ -0,0 +1,35 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use gui_app::fuzzy_search::FuzzySearcher;
+
+// Roughly the size of a large library's file list
+fn get_corpus() -> Vec<String> {
+    let mut corpus = Vec::new();
+    for series in 0..50 {
+        for season in 1..=10 {
+            for episode in 1..=24 {
+                corpus.push(format!(
+                    "Series Number {}/Season {:02}/Series.Number.{}-S{:02}E{:02}-Episode Title (Part {}).[1080p].mkv",
+                    series, season, series, season, episode, episode % 3,
+                ));
+            }
+        }
+    }
+    corpus
+}
+
+fn bench_fuzzy_search(c: &mut Criterion) {
+    let corpus = get_corpus();
+    let mut group = c.benchmark_group("fuzzy_search");
+    group.throughput(Throughput::Elements(corpus.len() as u64));
+    for (name, search) in [("match", "number 42 s05e1"), ("no_match", "missing series")] {
+        let mut searcher = FuzzySearcher::new();
+        searcher.set_search(search);
+        group.bench_function(name, |b| b.iter(|| {
+            corpus.iter().filter(|entry| searcher.search(black_box(entry.as_str()))).count()
+        }));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_fuzzy_search);
+criterion_main!(benches);
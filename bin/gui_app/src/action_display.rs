@@ -0,0 +1,159 @@
+use app::file_intent::Action;
+use egui;
+use enum_map;
+use lazy_static::lazy_static;
+use serde;
+use std::sync::RwLock;
+use crate::i18n::tr;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ActionStyle {
+    // Tabs and menus list actions from the lowest position up
+    pub position: usize,
+    pub is_visible: bool,
+    // Labels without a color use the theme's text color
+    pub color: Option<egui::Color32>,
+}
+
+// Controls how actions are presented in the file list, context menus and stats
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ActionDisplay {
+    pub styles: enum_map::EnumMap<Action, ActionStyle>,
+}
+
+fn get_default_color(action: Action) -> Option<egui::Color32> {
+    match action {
+        Action::Rename => Some(egui::Color32::from_rgb(70, 130, 200)),
+        Action::Delete => Some(egui::Color32::from_rgb(200, 60, 60)),
+        Action::Complete => Some(egui::Color32::from_rgb(60, 160, 80)),
+        Action::Extra | Action::Archive => Some(egui::Color32::from_rgb(200, 120, 0)),
+        Action::Ignore | Action::Whitelist => None,
+    }
+}
+
+impl Default for ActionDisplay {
+    fn default() -> Self {
+        // Matches the order the tabs had before they could be rearranged
+        const DEFAULT_ORDER: [Action; 7] = [
+            Action::Complete, Action::Rename, Action::Delete, Action::Ignore,
+            Action::Whitelist, Action::Extra, Action::Archive,
+        ];
+        Self {
+            styles: enum_map::enum_map! {
+                action => ActionStyle {
+                    position: DEFAULT_ORDER.iter().position(|other| *other == action).unwrap_or(DEFAULT_ORDER.len()),
+                    is_visible: true,
+                    color: get_default_color(action),
+                },
+            },
+        }
+    }
+}
+
+impl ActionDisplay {
+    pub fn get_ordered_actions(&self) -> Vec<Action> {
+        let mut actions: Vec<Action> = Action::iterator().copied().collect();
+        actions.sort_by_key(|action| self.styles[*action].position);
+        actions
+    }
+
+    pub fn get_visible_actions(&self) -> Vec<Action> {
+        self.get_ordered_actions()
+            .into_iter()
+            .filter(|action| self.styles[*action].is_visible)
+            .collect()
+    }
+
+    pub fn get_label(&self, action: Action, text: impl Into<String>) -> egui::RichText {
+        let label = egui::RichText::new(text);
+        match self.styles[action].color {
+            Some(color) => label.color(color),
+            None => label,
+        }
+    }
+
+    // Swaps positions with the neighbouring action in the current order
+    fn move_action(&mut self, action: Action, is_up: bool) {
+        let mut actions = self.get_ordered_actions();
+        let index = match actions.iter().position(|other| *other == action) {
+            Some(index) => index,
+            None => return,
+        };
+        let other_index = match is_up {
+            true if index > 0 => index - 1,
+            false if index + 1 < actions.len() => index + 1,
+            _ => return,
+        };
+        actions.swap(index, other_index);
+        for (position, action) in actions.iter().enumerate() {
+            self.styles[*action].position = position;
+        }
+    }
+}
+
+lazy_static! {
+    static ref ACTION_DISPLAY: RwLock<ActionDisplay> = RwLock::new(ActionDisplay::default());
+}
+
+pub fn get_action_display() -> ActionDisplay {
+    ACTION_DISPLAY.read().map(|display| display.clone()).unwrap_or_default()
+}
+
+pub fn set_action_display(display: ActionDisplay) {
+    if let Ok(mut current) = ACTION_DISPLAY.write() {
+        *current = display;
+    }
+}
+
+pub fn render_action_display_settings(ui: &mut egui::Ui) {
+    let mut display = get_action_display();
+    let actions = display.get_ordered_actions();
+
+    egui::Grid::new("action_display")
+        .num_columns(4)
+        .striped(true)
+        .show(ui, |ui| {
+            ui.strong(tr("Action"));
+            ui.strong(tr("Order"));
+            ui.strong(tr("Visible"));
+            ui.strong(tr("Color"));
+            ui.end_row();
+
+            for (index, action) in actions.iter().enumerate() {
+                let action = *action;
+                ui.label(display.get_label(action, tr(action.to_str())));
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(index > 0, egui::Button::new("⏶")).clicked() {
+                        display.move_action(action, true);
+                    }
+                    if ui.add_enabled(index + 1 < actions.len(), egui::Button::new("⏷")).clicked() {
+                        display.move_action(action, false);
+                    }
+                });
+                ui.checkbox(&mut display.styles[action].is_visible, "");
+                ui.horizontal(|ui| {
+                    let style = &mut display.styles[action];
+                    let mut is_colored = style.color.is_some();
+                    if ui.checkbox(&mut is_colored, "").changed() {
+                        style.color = match is_colored {
+                            true => Some(get_default_color(action).unwrap_or(ui.visuals().text_color())),
+                            false => None,
+                        };
+                    }
+                    if let Some(color) = style.color.as_mut() {
+                        egui::color_picker::color_edit_button_srgba(ui, color, egui::color_picker::Alpha::Opaque);
+                    }
+                });
+                ui.end_row();
+            }
+        });
+
+    ui.separator();
+    if ui.button(tr("Reset to defaults")).clicked() {
+        display = ActionDisplay::default();
+    }
+
+    if display != get_action_display() {
+        set_action_display(display);
+    }
+}
@@ -0,0 +1,174 @@
+use app::app_folder::AppFolder;
+use app::file_intent::Action;
+use egui;
+use lazy_static::lazy_static;
+use std::sync::{Arc, Mutex};
+use crate::action_display::get_action_display;
+use crate::i18n::{tr, trf};
+
+// Changes the action of the focused file without going through the context menu
+struct ActionPopup {
+    folder: Arc<AppFolder>,
+    src: String,
+    search: String,
+    highlighted: usize,
+}
+
+lazy_static! {
+    static ref ACTION_POPUP: Mutex<Option<ActionPopup>> = Mutex::new(None);
+}
+
+pub fn open_action_popup(folder: &Arc<AppFolder>, src: &str) {
+    if let Ok(mut popup) = ACTION_POPUP.lock() {
+        *popup = Some(ActionPopup {
+            folder: folder.clone(),
+            src: src.to_string(),
+            search: String::new(),
+            highlighted: 0,
+        });
+    }
+}
+
+fn get_matching_actions(search: &str) -> Vec<Action> {
+    let search = search.trim().to_lowercase();
+    get_action_display().get_visible_actions()
+        .into_iter()
+        .filter(|action| {
+            search.is_empty() ||
+            tr(action.to_str()).to_lowercase().contains(search.as_str()) ||
+            action.to_str().to_lowercase().contains(search.as_str())
+        })
+        .collect()
+}
+
+// Digits pick an action directly so they are taken out before the search field types them
+fn take_digit_selection(ctx: &egui::Context) -> Option<usize> {
+    ctx.input_mut(|i| {
+        let digit = i.events.iter().find_map(|event| match event {
+            egui::Event::Text(text) => text.parse::<usize>().ok().filter(|digit| (1..=9).contains(digit)),
+            _ => None,
+        })?;
+        let text = digit.to_string();
+        i.events.retain(|event| !matches!(event, egui::Event::Text(other) if *other == text));
+        Some(digit-1)
+    })
+}
+
+fn apply_action(folder: &AppFolder, src: &str, action: Action) {
+    if folder.get_busy_lock().try_lock().is_err() {
+        return;
+    }
+    {
+        let mut files = folder.get_mut_files_blocking();
+        let mut files_iter = files.to_iter();
+        while let Some(mut file) = files_iter.next_mut() {
+            if file.get_src() == src {
+                if file.get_action() != action {
+                    file.set_action(action);
+                }
+                break;
+            }
+        }
+    }
+    folder.flush_file_changes_blocking();
+}
+
+fn render_action_rows(ui: &mut egui::Ui, popup: &ActionPopup, actions: &[Action]) -> Option<Action> {
+    let display = get_action_display();
+    let files = popup.folder.get_files_blocking();
+    let file = files.to_iter().find(|file| file.get_src() == popup.src)?;
+    let current_action = file.get_action();
+
+    let mut selected_action = None;
+    egui::Grid::new("action_popup_rows")
+        .num_columns(3)
+        .striped(true)
+        .show(ui, |ui| {
+            for (index, action) in actions.iter().enumerate() {
+                let action = *action;
+                let key = match index < 9 {
+                    true => format!("{}", index+1),
+                    false => String::new(),
+                };
+                ui.label(egui::RichText::new(key).monospace());
+                let mut label = display.get_label(action, tr(action.to_str()));
+                if action == current_action {
+                    label = label.strong();
+                }
+                if ui.selectable_label(index == popup.highlighted, label).clicked() {
+                    selected_action = Some(action);
+                }
+                match file.get_dest_for_action(action) {
+                    Some(dest) => ui.label(dest),
+                    None if action == Action::Archive => ui.label(egui::RichText::new(tr("Moved into the archive root")).italics()),
+                    None if action == Action::Delete => ui.label(egui::RichText::new(tr("Deleted")).italics()),
+                    None => ui.label(egui::RichText::new(tr("Kept in place")).italics()),
+                };
+                ui.end_row();
+            }
+        });
+    selected_action
+}
+
+pub fn render_action_popup(ctx: &egui::Context) {
+    let mut popup_guard = match ACTION_POPUP.lock() {
+        Ok(popup) => popup,
+        Err(_) => return,
+    };
+    let popup = match popup_guard.as_mut() {
+        Some(popup) => popup,
+        None => return,
+    };
+
+    let digit_selection = take_digit_selection(ctx);
+    let (is_up, is_down, is_enter, is_escape) = ctx.input_mut(|i| (
+        i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+        i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+        i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+        i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+    ));
+
+    let mut is_open = !is_escape;
+    let mut selected_action = None;
+    egui::Window::new(tr("Change action"))
+        .id(egui::Id::new("action_popup"))
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_TOP, [0.0, 80.0])
+        .open(&mut is_open)
+        .show(ctx, |ui| {
+            ui.label(trf("File '{}'", &[&popup.src]));
+            let res = ui.add(egui::TextEdit::singleline(&mut popup.search).hint_text(tr("Search actions")));
+            res.request_focus();
+
+            let actions = get_matching_actions(popup.search.as_str());
+            if actions.is_empty() {
+                ui.label(tr("No matching actions"));
+                return;
+            }
+            if is_up {
+                popup.highlighted = popup.highlighted.saturating_sub(1);
+            }
+            if is_down {
+                popup.highlighted += 1;
+            }
+            popup.highlighted = popup.highlighted.min(actions.len()-1);
+
+            selected_action = render_action_rows(ui, popup, actions.as_slice());
+            if let Some(index) = digit_selection {
+                selected_action = selected_action.or(actions.get(index).copied());
+            }
+            if is_enter {
+                selected_action = selected_action.or(actions.get(popup.highlighted).copied());
+            }
+            ui.separator();
+            ui.weak(tr("Press a number or Enter to apply, Escape to close"));
+        });
+
+    if let Some(action) = selected_action {
+        apply_action(&popup.folder, popup.src.as_str(), action);
+        *popup_guard = None;
+    } else if !is_open {
+        *popup_guard = None;
+    }
+}
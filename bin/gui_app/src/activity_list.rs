@@ -0,0 +1,97 @@
+use app::activity_log::{ActivityKind, ActivityLog};
+use egui;
+use egui_extras::{Column, TableBuilder};
+use enum_map;
+use crate::fuzzy_search::{FuzzySearcher, render_search_bar};
+use crate::i18n::tr;
+
+pub struct GuiActivityList {
+    folder_searcher: FuzzySearcher,
+    filters: enum_map::EnumMap<ActivityKind, bool>,
+}
+
+impl GuiActivityList {
+    pub fn new() -> Self {
+        Self {
+            folder_searcher: FuzzySearcher::new(),
+            filters: enum_map::enum_map! { _ => true },
+        }
+    }
+}
+
+impl Default for GuiActivityList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn render_activity_list(ui: &mut egui::Ui, gui: &mut GuiActivityList, activity_log: &ActivityLog) {
+    ui.horizontal_wrapped(|ui| {
+        for kind in ActivityKind::iterator() {
+            ui.checkbox(&mut gui.filters[*kind], tr(kind.to_str()));
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.label(tr("Folder"));
+        render_search_bar(ui, &mut gui.folder_searcher);
+    });
+    ui.separator();
+
+    let events = match activity_log.get_events().try_read() {
+        Ok(events) => events,
+        Err(_) => {
+            ui.spinner();
+            return;
+        },
+    };
+
+    if events.is_empty() {
+        ui.label(tr("No activity recorded"));
+        return;
+    }
+
+    let row_height = 18.0;
+    let cell_layout = egui::Layout::left_to_right(egui::Align::Center).with_cross_justify(false);
+    TableBuilder::new(ui)
+        .striped(true)
+        .resizable(true)
+        .cell_layout(cell_layout)
+        .column(Column::auto().resizable(true))
+        .column(Column::auto().resizable(true))
+        .column(Column::initial(150.0).resizable(true).clip(true))
+        .column(Column::remainder().resizable(false).clip(true))
+        .header(row_height, |mut header| {
+            header.col(|ui| { ui.strong(tr("Time")); });
+            header.col(|ui| { ui.strong(tr("Type")); });
+            header.col(|ui| { ui.strong(tr("Folder")); });
+            header.col(|ui| { ui.strong(tr("Message")); });
+        })
+        .body(|mut body| {
+            // Show most recent events first
+            for event in events.iter().rev() {
+                if !gui.filters[event.kind] {
+                    continue;
+                }
+                let folder = event.folder.as_deref().unwrap_or("");
+                if !gui.folder_searcher.search(folder) {
+                    continue;
+                }
+
+                body.row(row_height, |mut row| {
+                    row.col(|ui| {
+                        let timestamp = event.timestamp.with_timezone(&chrono::Local);
+                        ui.label(timestamp.format("%Y-%m-%d %H:%M:%S").to_string());
+                    });
+                    row.col(|ui| {
+                        ui.label(tr(event.kind.to_str()));
+                    });
+                    row.col(|ui| {
+                        ui.label(folder).on_hover_text(folder);
+                    });
+                    row.col(|ui| {
+                        ui.label(event.message.as_str()).on_hover_text(event.message.as_str());
+                    });
+                });
+            }
+        });
+}
@@ -1,16 +1,52 @@
 use app::app::App;
-use app::app_folder::FolderStatus;
+use app::app_folder::{AppFolder, FolderStatus};
+use app::library_lock::LockState;
+use app::metrics::get_metrics;
 use eframe;
 use egui;
 use enum_map;
+use serde_json;
 use std::sync::Arc;
 use tokio;
-use crate::helpers::render_invisible_width_widget;
-use crate::error_list::render_errors_list;
+use egui_dock::{DockArea, DockState};
+use crate::activity_list::GuiActivityList;
+use crate::dock::{DockTab, GuiTabViewer, create_default_dock_state, render_dock_tab_toggles};
+use crate::fuzzy_search::FuzzySearcher;
+use crate::i18n::{get_language, set_language, tr};
+use crate::keybindings::{Keybindings, save_keybindings, set_keybindings};
+use crate::action_display::{get_action_display, set_action_display};
+use crate::recent_folders::{get_recent_folders, set_recent_folders};
+use crate::diagnostics_window::render_diagnostics_window;
 use crate::settings_menu::{GuiSettings, render_settings_menu};
-use crate::app_folders_list::{GuiAppFoldersList, render_folders_list};
+use crate::shortcuts::handle_global_shortcuts;
+use crate::app_folders_list::GuiAppFoldersList;
 use crate::app_folder::{GuiAppFolder, render_app_folder};
 use crate::app_series_search::{GuiSeriesSearch, render_series_search};
+use crate::import_window::{GuiImport, render_import_window};
+use crate::metrics_panel::FRAME_RENDER;
+use crate::recovery_window::render_recovery_window;
+use crate::execute_confirmation::render_execute_confirmation;
+use crate::merge_confirmation::render_merge_confirmation;
+use crate::action_popup::render_action_popup;
+use crate::rebind_window::{GuiRebind, render_rebind_window, spawn_load_bindings};
+use crate::torrent_cleanup_window::{GuiTorrentCleanup, render_torrent_cleanup_window, spawn_load_torrent_cleanups};
+use crate::trash_window::{render_trash_window, spawn_load_trash_entries};
+use crate::watchlist_list::GuiWatchlist;
+use crate::calendar_list::GuiCalendar;
+use crate::toasts::{GuiToasts, ToastTarget, render_toasts};
+
+const DOCK_STATE_KEY: &str = "dock_state";
+const LANGUAGE_KEY: &str = "language";
+const KEYBINDINGS_KEY: &str = "keybindings";
+const ACTION_DISPLAY_KEY: &str = "action_display";
+const RECENT_FOLDERS_KEY: &str = "recent_folders";
+
+// Folders opened in their own window so they can be compared side-by-side
+struct DetachedFolder {
+    folder: Arc<AppFolder>,
+    gui: GuiAppFolder,
+    is_open: bool,
+}
 
 pub struct GuiApp {
     pub(crate) app: Arc<App>,
@@ -18,21 +54,72 @@ pub struct GuiApp {
     pub(crate) gui_app_folder: GuiAppFolder,
     pub(crate) gui_series_search: GuiSeriesSearch,
     gui_settings: GuiSettings,
+    gui_activity_list: GuiActivityList,
+    gui_import: GuiImport,
+    gui_rebind: GuiRebind,
+    gui_torrent_cleanup: GuiTorrentCleanup,
+    gui_watchlist: GuiWatchlist,
+    gui_calendar: GuiCalendar,
+    gui_toasts: GuiToasts,
+    episode_searcher: FuzzySearcher,
+    detached_folders: Vec<DetachedFolder>,
+    dock_state: DockState<DockTab>,
 
     is_force_refresh_thread_spawned: bool,
     is_gui_settings_opened: bool,
+    is_activity_opened: bool,
+    is_import_opened: bool,
+    is_trash_opened: bool,
+    is_rebind_opened: bool,
+    is_torrent_cleanup_opened: bool,
 }
 
 impl GuiApp {
-    pub fn new(app: Arc<App>) -> Self {
+    pub fn new(app: Arc<App>, storage: Option<&dyn eframe::Storage>) -> Self {
+        let dock_state = storage
+            .and_then(|storage| eframe::get_value(storage, DOCK_STATE_KEY))
+            .unwrap_or_else(create_default_dock_state);
+        if let Some(language) = storage.and_then(|storage| eframe::get_value(storage, LANGUAGE_KEY)) {
+            set_language(language);
+        }
+        let saved_keybindings = app.get_filter_rules().blocking_read().keybindings.clone();
+        match saved_keybindings.and_then(|value| serde_json::from_value::<Keybindings>(value).ok()) {
+            Some(keybindings) => set_keybindings(keybindings),
+            // Older versions kept them in the gui storage, moved into the config on the first start
+            None => if let Some(keybindings) = storage.and_then(|storage| eframe::get_value::<Keybindings>(storage, KEYBINDINGS_KEY)) {
+                save_keybindings(&app, &keybindings);
+                set_keybindings(keybindings);
+            },
+        }
+        if let Some(display) = storage.and_then(|storage| eframe::get_value(storage, ACTION_DISPLAY_KEY)) {
+            set_action_display(display);
+        }
+        if let Some(recent) = storage.and_then(|storage| eframe::get_value(storage, RECENT_FOLDERS_KEY)) {
+            set_recent_folders(recent);
+        }
         Self {
             app,
             gui_app_folders_list: GuiAppFoldersList::new(),
             gui_app_folder: GuiAppFolder::new(),
             gui_series_search: GuiSeriesSearch::new(),
             gui_settings: GuiSettings::new(),
+            gui_activity_list: GuiActivityList::new(),
+            gui_import: GuiImport::new(),
+            gui_rebind: GuiRebind::new(),
+            gui_torrent_cleanup: GuiTorrentCleanup::new(),
+            gui_watchlist: GuiWatchlist::new(),
+            gui_calendar: GuiCalendar::new(),
+            gui_toasts: GuiToasts::new(),
+            episode_searcher: FuzzySearcher::new(),
+            detached_folders: Vec::new(),
+            dock_state,
             is_force_refresh_thread_spawned: false,
             is_gui_settings_opened: false,
+            is_activity_opened: false,
+            is_import_opened: false,
+            is_trash_opened: false,
+            is_rebind_opened: false,
+            is_torrent_cleanup_opened: false,
         }
     }
 }
@@ -76,6 +163,11 @@ impl GuiApp {
                 }
                 old_busy_count = Some(new_busy_count);
 
+                // auto-refresh countdowns need to keep ticking
+                if let Some(watcher) = app.get_folder_watcher().read().await.as_ref() {
+                    is_refresh |= !watcher.get_pending().is_empty();
+                }
+
                 // cap maximum refresh rate at 10fps in background
                 if is_refresh {
                     ctx.request_repaint();
@@ -84,54 +176,230 @@ impl GuiApp {
                 tokio::time::sleep(duration).await;
             }
         });
+
+        // Refreshes run separately so a slow scan doesn't stall the ui refreshes
+        tokio::spawn({
+            let app = self.app.clone();
+            async move {
+                loop {
+                    app.refresh_library_lock().await;
+                    app.reload_config_if_changed().await;
+                    app.refresh_watched_folders().await;
+                    app.purge_trash_if_due().await;
+                    app.process_cleanup_queue_if_due().await;
+                    app.save_folder_summaries().await;
+                    let duration = tokio::time::Duration::from_millis(500);
+                    tokio::time::sleep(duration).await;
+                }
+            }
+        });
+    }
+}
+
+impl GuiApp {
+    // Transient events show up as toasts which lead to where they happened when clicked
+    fn render_toasts(&mut self, ctx: &egui::Context) {
+        self.gui_toasts.update(&self.app);
+        match render_toasts(ctx, &mut self.gui_toasts) {
+            Some(ToastTarget::Folder(folder_name)) => {
+                let index = self.app.get_folders().blocking_read().iter().position(|folder| folder.get_folder_name() == folder_name);
+                if let Some(index) = index {
+                    *self.app.get_selected_folder_index().blocking_write() = Some(index);
+                    self.gui_app_folders_list.is_focus_selected = true;
+                }
+            },
+            Some(ToastTarget::Activity) if self.dock_state.find_tab(&DockTab::Activity).is_none() => {
+                self.dock_state.push_to_focused_leaf(DockTab::Activity);
+            },
+            _ => {},
+        }
+    }
+
+    fn render_detached_folders(&mut self, ctx: &egui::Context) {
+        if let Some(index) = self.gui_app_folders_list.detach_request.take() {
+            let folder = self.app.get_folders().blocking_read().get(index).cloned();
+            if let Some(folder) = folder {
+                let is_detached = self.detached_folders.iter().any(|detached| Arc::ptr_eq(&detached.folder, &folder));
+                if !is_detached {
+                    self.detached_folders.push(DetachedFolder {
+                        folder,
+                        gui: GuiAppFolder::new(),
+                        is_open: true,
+                    });
+                }
+            }
+        }
+
+        let session = self.app.get_login_session().blocking_read().clone();
+        for detached in self.detached_folders.iter_mut() {
+            let folder = &detached.folder;
+            let gui = &mut detached.gui;
+            egui::Window::new(folder.get_folder_name())
+                .id(egui::Id::new(("detached_folder", folder.get_folder_path())))
+                .default_size([800.0, 600.0])
+                .vscroll(false)
+                .open(&mut detached.is_open)
+                .show(ctx, |ui| {
+                    render_app_folder(
+                        ui,
+                        &self.app, session.as_ref(),
+                        gui, folder,
+                    );
+                });
+
+            // The series search only operates on the selected folder
+            if gui.is_show_series_search || gui.series_search_request.is_some() {
+                let index = self.app.get_folders().blocking_read().iter().position(|other| Arc::ptr_eq(other, folder));
+                *self.app.get_selected_folder_index().blocking_write() = index;
+                self.gui_app_folder.is_show_series_search |= gui.is_show_series_search;
+                if let Some(search) = gui.series_search_request.take() {
+                    self.gui_app_folder.series_search_request = Some(search);
+                }
+                gui.is_show_series_search = false;
+            }
+        }
+        // Close windows for folders that were removed when the root folder was reloaded
+        let folders = self.app.get_folders().blocking_read();
+        self.detached_folders.retain(|detached| {
+            detached.is_open && folders.iter().any(|folder| Arc::ptr_eq(folder, &detached.folder))
+        });
     }
 }
 
 impl eframe::App for GuiApp {
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        let _timer = get_metrics().start_timer(FRAME_RENDER);
         self.gui_settings.update_frame(ctx, frame);
         self.setup_force_refresh_thread(ctx);
+        handle_global_shortcuts(ctx, &self.app, &mut self.gui_app_folders_list);
 
-        egui::SidePanel::left("Folders")
-            .resizable(true)
-            .show(ctx, |ui| {
-                render_invisible_width_widget(ui);
-                if let Ok(mut errors) = self.app.get_errors().try_write() {
-                    if !errors.is_empty() {
-                        egui::TopBottomPanel::bottom("app_error_list")
-                            .resizable(true)
-                            .show_inside(ui, |ui| {
-                                render_errors_list(ui, errors.as_mut());
-                            });
+        egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
+            egui::menu::bar(ui, |ui| {
+                ui.menu_button(tr("File"), |ui| {
+                    if ui.button(tr("Import download")).clicked() {
+                        self.is_import_opened = true;
+                        ui.close_menu();
                     }
-                } 
-                egui::CentralPanel::default()
-                    .frame(egui::Frame::none())
-                    .show_inside(ui, |ui| {
-                        render_folders_list(ui, &mut self.gui_app_folders_list, &self.app, &mut self.is_gui_settings_opened);
-                    });
+                    if ui.button(tr("Trash")).clicked() {
+                        self.is_trash_opened = true;
+                        spawn_load_trash_entries(&self.app);
+                        ui.close_menu();
+                    }
+                    if ui.button(tr("Batch rebind series")).clicked() {
+                        self.is_rebind_opened = true;
+                        spawn_load_bindings(&self.app);
+                        ui.close_menu();
+                    }
+                    let is_torrent_login = self.app.get_torrent_session().try_read().map(|session| session.is_some()).unwrap_or(false);
+                    let res = ui.add_enabled(is_torrent_login, egui::Button::new(tr("Clean up torrents")));
+                    if res.clicked() {
+                        self.is_torrent_cleanup_opened = true;
+                        spawn_load_torrent_cleanups(&self.app);
+                        ui.close_menu();
+                    }
+                    res.on_disabled_hover_text(tr("Not logged in to qBittorrent"));
+                    let is_not_busy = self.app.get_folders_busy_lock().try_lock().is_ok();
+                    let res = ui.add_enabled(is_not_busy, egui::Button::new(tr("Check library consistency")));
+                    if res.clicked() {
+                        tokio::spawn({
+                            let app = self.app.clone();
+                            async move {
+                                app.check_library_consistency().await
+                            }
+                        });
+                        ui.close_menu();
+                    }
+                    res.on_disabled_hover_text(tr("Folders are busy"));
+                });
+                ui.menu_button(tr("View"), |ui| {
+                    render_dock_tab_toggles(ui, &mut self.dock_state);
+                    ui.separator();
+                    if ui.button(tr("Reset layout")).clicked() {
+                        self.dock_state = create_default_dock_state();
+                        ui.close_menu();
+                    }
+                });
             });
+        });
 
-        egui::CentralPanel::default()
-            .show(ctx, |ui| {
-                let folders = self.app.get_folders().blocking_read();
-                let folder_index = *self.app.get_selected_folder_index().blocking_read();
-                let folder_index = match folder_index {
-                    Some(index) => index,
-                    None => {
-                        ui.label("No folder selected");
-                        return;
-                    },
-                };
-
-                let folder = folders[folder_index].clone();
-                drop(folders);
-
-                let session = self.app.get_login_session().blocking_read();
-                render_app_folder(ui, session.as_ref(), &mut self.gui_app_folder, &folder);
+        // Changes are refused while another instance owns the root folder
+        let lock_owner = match self.app.get_library_lock().get_state().try_read().as_deref() {
+            Ok(LockState::HeldByOther { owner, .. }) => Some(owner.clone()),
+            _ => None,
+        };
+        if let Some(owner) = lock_owner {
+            egui::TopBottomPanel::top("library_lock").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let message = format!(
+                        "{} (pid {}, {})",
+                        tr("Another instance is using this root folder, changes are disabled"),
+                        owner.pid, owner.hostname,
+                    );
+                    ui.colored_label(egui::Color32::from_rgb(230, 160, 40), message);
+                    let res = ui.button(tr("Take over lock"));
+                    if res.clicked() {
+                        tokio::spawn({
+                            let app = self.app.clone();
+                            async move {
+                                app.take_over_library_lock().await
+                            }
+                        });
+                    }
+                    res.on_hover_text(tr("Only use this if the other instance is no longer running"));
+                });
             });
+        }
+
+        let activity_tab = self.dock_state.find_tab(&DockTab::Activity);
+        self.is_activity_opened = activity_tab.is_some();
+        let mut viewer = GuiTabViewer {
+            app: &self.app,
+            gui_app_folders_list: &mut self.gui_app_folders_list,
+            gui_app_folder: &mut self.gui_app_folder,
+            gui_activity_list: &mut self.gui_activity_list,
+            gui_watchlist: &mut self.gui_watchlist,
+            gui_calendar: &mut self.gui_calendar,
+            episode_searcher: &mut self.episode_searcher,
+            is_gui_settings_opened: &mut self.is_gui_settings_opened,
+            is_activity_opened: &mut self.is_activity_opened,
+        };
+        DockArea::new(&mut self.dock_state)
+            .show_add_buttons(false)
+            .show(ctx, &mut viewer);
+
+        // The activity toggle in the folders list opens and closes the activity tab
+        match (activity_tab, self.is_activity_opened) {
+            (None, true) => self.dock_state.push_to_focused_leaf(DockTab::Activity),
+            (Some(_), false) => {
+                if let Some(location) = self.dock_state.find_tab(&DockTab::Activity) {
+                    self.dock_state.remove_tab(location);
+                }
+            },
+            _ => {},
+        }
 
-        egui::Window::new("Series Search")
+        self.render_detached_folders(ctx);
+
+        if let Some(search) = self.gui_app_folders_list.rebind_request.take() {
+            self.gui_app_folder.series_search_request = Some(search);
+        }
+
+        // Open the series search when a folder suggests a different series
+        if let Some(search) = self.gui_app_folder.series_search_request.take() {
+            self.gui_series_search.set_search_string(search.as_str());
+            self.gui_app_folder.is_show_series_search = true;
+            if self.app.get_login_session().blocking_read().is_some() {
+                tokio::spawn({
+                    let app = self.app.clone();
+                    async move {
+                        app.update_search_series(search).await
+                    }
+                });
+            }
+        }
+
+        egui::Window::new(tr("Series Search"))
+            .id(egui::Id::new("series_search"))
             .collapsible(false)
             .vscroll(false)
             .open(&mut self.gui_app_folder.is_show_series_search)
@@ -139,14 +407,92 @@ impl eframe::App for GuiApp {
                 render_series_search(ui, &mut self.gui_series_search, &self.app);
             });
         
-        egui::Window::new("Settings Menu")
+        egui::Window::new(tr("Import Download"))
+            .id(egui::Id::new("import_download"))
+            .collapsible(false)
+            .vscroll(false)
+            .default_size([600.0, 400.0])
+            .open(&mut self.is_import_opened)
+            .show(ctx, |ui| {
+                render_import_window(ui, &mut self.gui_import, &self.app);
+            });
+
+        egui::Window::new(tr("Trash"))
+            .id(egui::Id::new("trash"))
+            .collapsible(false)
+            .vscroll(false)
+            .default_size([600.0, 400.0])
+            .open(&mut self.is_trash_opened)
+            .show(ctx, |ui| {
+                render_trash_window(ui, &self.app);
+            });
+
+        egui::Window::new(tr("Batch Rebind"))
+            .id(egui::Id::new("rebind_series"))
+            .collapsible(false)
+            .vscroll(false)
+            .default_size([700.0, 500.0])
+            .open(&mut self.is_rebind_opened)
+            .show(ctx, |ui| {
+                render_rebind_window(ui, &mut self.gui_rebind, &self.app);
+            });
+
+        egui::Window::new(tr("Torrent Cleanup"))
+            .id(egui::Id::new("torrent_cleanup"))
+            .collapsible(false)
+            .vscroll(false)
+            .default_size([700.0, 400.0])
+            .open(&mut self.is_torrent_cleanup_opened)
+            .show(ctx, |ui| {
+                render_torrent_cleanup_window(ui, &mut self.gui_torrent_cleanup, &self.app);
+            });
+
+        // Interrupted executions stay on screen until each one is resolved
+        let is_interrupted = self.app.get_interrupted_executions().try_read().map(|journals| !journals.is_empty()).unwrap_or(false);
+        if is_interrupted {
+            egui::Window::new(tr("Interrupted Executions"))
+                .id(egui::Id::new("interrupted_executions"))
+                .collapsible(false)
+                .vscroll(false)
+                .default_size([600.0, 200.0])
+                .show(ctx, |ui| {
+                    render_recovery_window(ui, &self.app);
+                });
+        }
+
+        render_execute_confirmation(ctx);
+        render_merge_confirmation(ctx);
+        render_action_popup(ctx);
+        self.render_toasts(ctx);
+
+        // Startup problems stay on screen until they are fixed or dismissed
+        let is_diagnosed = self.app.get_diagnostics().try_read().map(|diagnostics| !diagnostics.is_empty()).unwrap_or(false);
+        if is_diagnosed {
+            egui::Window::new(tr("Diagnostics"))
+                .id(egui::Id::new("diagnostics"))
+                .collapsible(true)
+                .vscroll(true)
+                .default_size([600.0, 200.0])
+                .show(ctx, |ui| {
+                    render_diagnostics_window(ui, &self.app);
+                });
+        }
+
+        egui::Window::new(tr("Settings Menu"))
+            .id(egui::Id::new("settings_menu"))
             .collapsible(false)
             .vscroll(true)
             .hscroll(true)
             .open(&mut self.is_gui_settings_opened)
             .show(ctx, |ui| {
-                render_settings_menu(ui, ctx, &mut self.gui_settings);
+                render_settings_menu(ui, ctx, &mut self.gui_settings, &self.app);
             });
     }
-}
 
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, DOCK_STATE_KEY, &self.dock_state);
+        eframe::set_value(storage, LANGUAGE_KEY, &get_language());
+        eframe::set_value(storage, ACTION_DISPLAY_KEY, &get_action_display());
+        eframe::set_value(storage, RECENT_FOLDERS_KEY, &get_recent_folders());
+    }
+}
@@ -1,48 +1,50 @@
 use app::bookmarks::Bookmark;
 use egui;
+use crate::helpers::set_accessible_toggle;
+use crate::i18n::tr;
+use crate::icons::{Icon, IconTone, create_icon_image};
 
 pub fn render_file_bookmarks(ui: &mut egui::Ui, bookmark: &mut Bookmark) -> bool {
-    let height = ui.text_style_height(&egui::TextStyle::Monospace);
     let mut is_changed = false;
     ui.horizontal(|ui| {
         {
             let value = &mut bookmark.is_favourite;
-            let label = egui::RichText::new("★").strong().size(height).color(
-                match value {
-                    true => egui::Color32::GOLD,
-                    false => egui::Color32::LIGHT_GRAY,
-                }
-            );
-            let elem = egui::Label::new(label).sense(egui::Sense::click());
-            if ui.add(elem).clicked() {
+            let tone = match value {
+                true => IconTone::Highlight,
+                false => IconTone::Inactive,
+            };
+            let elem = create_icon_image(ui, Icon::Favourite, tone).sense(egui::Sense::click());
+            let res = ui.add(elem);
+            set_accessible_toggle(&res, tr("Favourite"), *value);
+            if res.clicked() {
                 *value = !*value;
                 is_changed = true;
             }
         }
         {
             let value = &mut bookmark.is_unread;
-            let label = egui::RichText::new("？").strong().size(height).color(
-                match value {
-                    true => egui::Color32::DARK_RED,
-                    false => egui::Color32::LIGHT_GRAY,
-                }
-            );
-            let elem = egui::Label::new(label).sense(egui::Sense::click());
-            if ui.add(elem).clicked() {
+            let tone = match value {
+                true => IconTone::Error,
+                false => IconTone::Inactive,
+            };
+            let elem = create_icon_image(ui, Icon::Unread, tone).sense(egui::Sense::click());
+            let res = ui.add(elem);
+            set_accessible_toggle(&res, tr("Unread"), *value);
+            if res.clicked() {
                 *value = !*value;
                 is_changed = true;
             }
         }
         {
             let value = &mut bookmark.is_read;
-            let label = egui::RichText::new("✔").strong().size(height).color(
-                match value {
-                    true => egui::Color32::DARK_GREEN,
-                    false => egui::Color32::LIGHT_GRAY,
-                }
-            );
-            let elem = egui::Label::new(label).sense(egui::Sense::click());
-            if ui.add(elem).clicked() {
+            let tone = match value {
+                true => IconTone::Success,
+                false => IconTone::Inactive,
+            };
+            let elem = create_icon_image(ui, Icon::Done, tone).sense(egui::Sense::click());
+            let res = ui.add(elem);
+            set_accessible_toggle(&res, tr("Read"), *value);
+            if res.clicked() {
                 *value = !*value;
                 is_changed = true;
             }
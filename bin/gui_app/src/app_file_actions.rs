@@ -1,53 +1,217 @@
 use app::app_file::MutableAppFile;
+use app::app_folder::AppFolder;
+use app::execution_outcomes::ExecutionOutcome;
 use app::file_intent::Action;
+use app::tvdb_cache::EpisodeKey;
 use egui;
-use lazy_static::lazy_static;
 use open as cross_open;
 use std::path::Path;
+use std::sync::Arc;
 use tokio;
+use crate::action_display::get_action_display;
+use crate::action_popup::open_action_popup;
+use crate::execute_confirmation::request_file_execution;
+use crate::helpers::set_accessible_label;
+use crate::i18n::{tr, trf};
+use crate::icons::{Icon, IconTone, create_icon_image, render_icon};
+use crate::keybindings::{FileClickAction, get_keybindings};
+use crate::shortcuts::ACTION_POPUP_SHORTCUT;
 
-lazy_static! {
-    static ref ACTION_SHORTCUTS: enum_map::EnumMap<Action, egui::KeyboardShortcut> = enum_map::enum_map!{
-        Action::Delete => egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::Delete),
-        Action::Ignore => egui::KeyboardShortcut::new(egui::Modifiers::ALT, egui::Key::I),
-        Action::Rename => egui::KeyboardShortcut::new(egui::Modifiers::ALT, egui::Key::R),
-        Action::Whitelist => egui::KeyboardShortcut::new(egui::Modifiers::ALT, egui::Key::W),
-        Action::Complete => egui::KeyboardShortcut::new(egui::Modifiers::ALT, egui::Key::C),
+// Opens the file manager with the file selected instead of just opening its parent folder
+#[cfg(target_os = "windows")]
+async fn reveal_in_file_manager(path: &Path) -> std::io::Result<()> {
+    let mut select_arg = std::ffi::OsString::from("/select,");
+    select_arg.push(path.as_os_str());
+    // explorer.exe returns a non-zero exit code even when it succeeds
+    tokio::process::Command::new("explorer").arg(select_arg).status().await?;
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+async fn reveal_in_file_manager(path: &Path) -> std::io::Result<()> {
+    tokio::process::Command::new("open").arg("-R").arg(path).status().await?;
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+async fn reveal_in_file_manager(path: &Path) -> std::io::Result<()> {
+    // Most linux file managers implement the freedesktop interface for selecting files
+    let uri: String = path.to_string_lossy()
+        .bytes()
+        .map(|b| match b {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect();
+    let uri = format!("file://{}", uri);
+    let status = tokio::process::Command::new("dbus-send")
+        .args([
+            "--session", "--print-reply", "--dest=org.freedesktop.FileManager1", "--type=method_call",
+            "/org/freedesktop/FileManager1", "org.freedesktop.FileManager1.ShowItems",
+        ])
+        .arg(format!("array:string:{}", uri))
+        .arg("string:")
+        .status()
+        .await;
+    match status {
+        Ok(status) if status.success() => Ok(()),
+        _ => cross_open::that(path.parent().unwrap_or(Path::new("."))),
+    }
+}
+
+fn open_file_on_disk(folder_path: &str, src: &str) {
+    let filename_path = Path::new(folder_path).join(src);
+    let filename_path_str = filename_path.to_string_lossy().to_string();
+    tokio::spawn(async move {
+        cross_open::that(filename_path_str)
+    });
+}
+
+fn reveal_file(folder_path: &str, src: &str) {
+    let filename_path = Path::new(folder_path).join(src);
+    // file managers expect absolute paths when selecting a file
+    let filename_path = std::path::absolute(filename_path.as_path()).unwrap_or(filename_path);
+    tokio::spawn(async move {
+        reveal_in_file_manager(filename_path.as_path()).await
+    });
+}
+
+// File managers and shells expect absolute paths
+fn get_absolute_path(folder_path: &str, path: &str) -> String {
+    let path = Path::new(folder_path).join(path);
+    std::path::absolute(path.as_path()).unwrap_or(path).to_string_lossy().to_string()
+}
+
+// The destination folder might not exist until the changes are executed so the closest existing parent is opened instead
+fn open_dest_folder(folder_path: &str, dest: &str) {
+    let dest_path = Path::new(folder_path).join(dest);
+    let mut dest_folder = dest_path.parent().map(|parent| parent.to_path_buf());
+    while let Some(path) = dest_folder.as_ref() {
+        if path.is_dir() {
+            break;
+        }
+        dest_folder = path.parent().map(|parent| parent.to_path_buf());
+    }
+    let dest_folder = match dest_folder {
+        Some(dest_folder) => dest_folder.to_string_lossy().to_string(),
+        None => return,
     };
+    tokio::spawn(async move {
+        cross_open::that(dest_folder)
+    });
 }
 
-pub fn check_file_shortcuts(ui: &mut egui::Ui, file: &mut MutableAppFile<'_>) {
+pub fn check_file_shortcuts(ui: &mut egui::Ui, folder: &Arc<AppFolder>, file: &mut MutableAppFile<'_>) {
+    if ui.input_mut(|i| i.consume_shortcut(&ACTION_POPUP_SHORTCUT)) {
+        open_action_popup(folder, file.get_src());
+        return;
+    }
+    let keybindings = get_keybindings();
     let current_action = file.get_action();
     for action in Action::iterator() {
         let action = *action;
         if action == current_action {
             continue;
         }
-        let shortcut = &ACTION_SHORTCUTS[action];
-        if ui.input_mut(|i| i.consume_shortcut(shortcut)) {
+        let shortcut = match keybindings.action_shortcuts.get(&action) {
+            Some(keybinding) => keybinding.to_shortcut(),
+            None => continue,
+        };
+        if ui.input_mut(|i| i.consume_shortcut(&shortcut)) {
             file.set_action(action);
         }
     }
 }
 
+pub fn get_execution_outcome_label(outcome: &ExecutionOutcome) -> String {
+    match outcome {
+        ExecutionOutcome::Applied => tr("Applied").to_string(),
+        ExecutionOutcome::Skipped(reason) => tr(reason.to_str()).to_string(),
+        ExecutionOutcome::Failed(error) => trf("Failed: {}", &[error]),
+    }
+}
+
+// Shows how the last execution went for the file until the folder is refreshed
+pub fn render_execution_outcome(ui: &mut egui::Ui, outcome: Option<&ExecutionOutcome>) {
+    let outcome = match outcome {
+        Some(outcome) => outcome,
+        None => return,
+    };
+    let (icon, tone) = match outcome {
+        ExecutionOutcome::Applied => (Icon::Done, IconTone::Success),
+        ExecutionOutcome::Skipped(_) => (Icon::Warning, IconTone::Highlight),
+        ExecutionOutcome::Failed(_) => (Icon::Warning, IconTone::Error),
+    };
+    let label = get_execution_outcome_label(outcome);
+    let res = render_icon(ui, icon, tone);
+    set_accessible_label(&res, egui::WidgetType::Label, label.as_str());
+    res.on_hover_text(label);
+}
+
+// Parsed files select every file of their episode, the others are selected on their own
+pub fn is_file_selected(
+    descriptor: &Option<EpisodeKey>, src: &str,
+    selected_descriptor: &Option<EpisodeKey>, selected_src: Option<&str>,
+) -> bool {
+    match descriptor {
+        Some(_) => descriptor == selected_descriptor,
+        None => selected_src == Some(src),
+    }
+}
+
+pub fn select_episode(folder: &Arc<AppFolder>, descriptor: EpisodeKey) {
+    *folder.get_selected_descriptor().blocking_write() = Some(descriptor);
+    *folder.get_selected_src().blocking_write() = None;
+}
+
+pub fn select_file(folder: &Arc<AppFolder>, descriptor: &Option<EpisodeKey>, src: &str) {
+    match descriptor {
+        Some(descriptor) => select_episode(folder, *descriptor),
+        None => {
+            *folder.get_selected_descriptor().blocking_write() = None;
+            *folder.get_selected_src().blocking_write() = Some(src.to_string());
+        },
+    }
+}
+
+pub fn clear_file_selection(folder: &Arc<AppFolder>) {
+    *folder.get_selected_descriptor().blocking_write() = None;
+    *folder.get_selected_src().blocking_write() = None;
+}
+
+pub fn check_file_double_click(res: &egui::Response, folder: &Arc<AppFolder>, file: &MutableAppFile<'_>) {
+    if !res.double_clicked() {
+        return;
+    }
+    match get_keybindings().file_double_click {
+        FileClickAction::Nothing => {},
+        FileClickAction::OpenOnDisk => open_file_on_disk(folder.get_folder_path(), file.get_src()),
+        // The first click of a double click toggles the selection so it needs to be restored
+        FileClickAction::OpenDetail => select_file(folder, file.get_src_descriptor(), file.get_src()),
+        FileClickAction::RevealInFileManager => reveal_file(folder.get_folder_path(), file.get_src()),
+    }
+}
+
 pub fn render_file_context_menu(
     ui: &mut egui::Ui,
-    folder_path: &str, file: &mut MutableAppFile<'_>, is_not_busy: bool,
+    folder: &Arc<AppFolder>, file: &mut MutableAppFile<'_>, is_not_busy: bool,
 ) {
+    let folder_path = folder.get_folder_path();
     let current_action = file.get_action();
-    if ui.button("Open file").clicked() {
-        tokio::spawn({
-            let src = file.get_src();
-            let filename_path = Path::new(folder_path).join(src);
-            let filename_path_str = filename_path.to_string_lossy().to_string();
-            async move {
-                cross_open::that(filename_path_str)
-            }
-        });
+    let image = create_icon_image(ui, Icon::Play, IconTone::Normal);
+    if ui.add(egui::Button::image_and_text(image, tr("Open with default player"))).clicked() {
+        open_file_on_disk(folder_path, file.get_src());
+        ui.close_menu();
+    }
+
+    let image = create_icon_image(ui, Icon::Reveal, IconTone::Normal);
+    if ui.add(egui::Button::image_and_text(image, tr("Reveal in file manager"))).clicked() {
+        reveal_file(folder_path, file.get_src());
         ui.close_menu();
     }
 
-    if ui.button("Open folder").clicked() {
+    let image = create_icon_image(ui, Icon::Folder, IconTone::Normal);
+    if ui.add(egui::Button::image_and_text(image, tr("Open folder"))).clicked() {
         tokio::spawn({
             let src = file.get_src();
             let filename_path = Path::new(folder_path).join(src);
@@ -59,21 +223,72 @@ pub fn render_file_context_menu(
         });
         ui.close_menu();
     }
+
+    let has_dest = (current_action.is_move() || current_action == Action::Archive) && !file.get_dest().is_empty();
+    if has_dest {
+        let image = create_icon_image(ui, Icon::Folder, IconTone::Normal);
+        if ui.add(egui::Button::image_and_text(image, tr("Open destination folder"))).clicked() {
+            open_dest_folder(folder_path, file.get_dest());
+            ui.close_menu();
+        }
+    }
+
+    ui.separator();
+
+    let image = create_icon_image(ui, Icon::Copy, IconTone::Normal);
+    if ui.add(egui::Button::image_and_text(image, tr("Copy source path"))).clicked() {
+        let path = get_absolute_path(folder_path, file.get_src());
+        ui.output_mut(|output| output.copied_text = path);
+        ui.close_menu();
+    }
+
+    if has_dest {
+        let image = create_icon_image(ui, Icon::Copy, IconTone::Normal);
+        if ui.add(egui::Button::image_and_text(image, tr("Copy destination path"))).clicked() {
+            let path = get_absolute_path(folder_path, file.get_dest());
+            ui.output_mut(|output| output.copied_text = path);
+            ui.close_menu();
+        }
+    }
     
     if !is_not_busy {
         return;
     }
 
     ui.separator();
+
+    // Fixes a single straggler without executing the rest of the folder
+    let is_executed = current_action.is_move() || matches!(current_action, Action::Delete | Action::Archive);
+    let is_dest_taken = file.get_is_dest_taken();
+    let res = ui.add_enabled(is_executed && !is_dest_taken, egui::Button::new(tr("Apply now")));
+    if res.clicked() {
+        request_file_execution(folder, file.get_src());
+        ui.close_menu();
+    }
+    res.on_hover_text(tr("Execute only the change of this file"))
+        .on_disabled_hover_ui(|ui| {
+            if !is_executed { ui.label(tr("File has no change to execute")); }
+            else            { ui.label(tr("Destination is taken by another file")); }
+        });
+
+    ui.separator();
+
+    let button = egui::Button::new(tr("Change action..."))
+        .shortcut_text(ui.ctx().format_shortcut(&ACTION_POPUP_SHORTCUT));
+    if ui.add(button).clicked() {
+        open_action_popup(folder, file.get_src());
+        ui.close_menu();
+    }
     
-    for action in Action::iterator() {
-        let action = *action;
+    let keybindings = get_keybindings();
+    for action in get_action_display().get_visible_actions() {
         if action == current_action {
             continue;
         }
-        let shortcut = &ACTION_SHORTCUTS[action];
-        let button = egui::Button::new(action.to_str())
-            .shortcut_text(ui.ctx().format_shortcut(shortcut));
+        let mut button = egui::Button::new(tr(action.to_str()));
+        if let Some(keybinding) = keybindings.action_shortcuts.get(&action) {
+            button = button.shortcut_text(ui.ctx().format_shortcut(&keybinding.to_shortcut()));
+        }
         if ui.add(button).clicked() {
             file.set_action(action);
             ui.close_menu();
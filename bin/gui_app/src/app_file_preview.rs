@@ -0,0 +1,139 @@
+use std::path::Path;
+use std::sync::Arc;
+use app::app_folder::AppFolder;
+use app::file_descriptor::{get_descriptor, get_absolute_descriptor};
+use app::file_intent::is_video_path;
+use app::thumbnail::{ThumbnailCache, ThumbnailStatus};
+use egui;
+use tokio;
+use crate::i18n::tr;
+
+fn render_thumbnail(ui: &mut egui::Ui, thumbnail_cache: &Arc<ThumbnailCache>, src_path: &str) {
+    let status = thumbnail_cache.get_entries().blocking_read().get(src_path).cloned();
+    match status {
+        None => {
+            tokio::spawn({
+                let thumbnail_cache = thumbnail_cache.clone();
+                let src_path = src_path.to_string();
+                async move {
+                    thumbnail_cache.load_thumbnail(src_path.as_str()).await
+                }
+            });
+            ui.spinner();
+        },
+        Some(ThumbnailStatus::Pending) => {
+            ui.spinner();
+        },
+        Some(ThumbnailStatus::Ready(thumbnail_path)) => {
+            let image = egui::Image::new(format!("file://{}", thumbnail_path))
+                .max_width(ui.available_width())
+                .maintain_aspect_ratio(true);
+            ui.add(image);
+        },
+        Some(ThumbnailStatus::Failed(message)) => {
+            ui.colored_label(egui::Color32::DARK_RED, message);
+        },
+    }
+}
+
+fn render_filename_breakdown(ui: &mut egui::Ui, filename: &str) {
+    egui::Grid::new("filename_breakdown")
+        .num_columns(2)
+        .striped(true)
+        .show(ui, |ui| {
+            if let Some(descriptor) = get_descriptor(filename) {
+                ui.strong(tr("Title"));
+                ui.label(descriptor.title.as_str());
+                ui.end_row();
+
+                ui.strong(tr("Season"));
+                ui.label(format!("{}", descriptor.season));
+                ui.end_row();
+
+                ui.strong(tr("Episode"));
+                ui.label(format!("{}", descriptor.episode));
+                ui.end_row();
+
+                if let Some(part) = descriptor.part {
+                    ui.strong(tr("Part"));
+                    ui.label(format!("{}", part));
+                    ui.end_row();
+                }
+
+                ui.strong(tr("Tags"));
+                ui.label(descriptor.tags.join(", "));
+                ui.end_row();
+
+                ui.strong(tr("Extension"));
+                ui.label(descriptor.extension.as_str());
+                ui.end_row();
+            } else if let Some(descriptor) = get_absolute_descriptor(filename) {
+                ui.strong(tr("Title"));
+                ui.label(descriptor.title.as_str());
+                ui.end_row();
+
+                ui.strong(tr("Absolute episode"));
+                ui.label(format!("{}", descriptor.episode));
+                ui.end_row();
+
+                ui.strong(tr("Tags"));
+                ui.label(descriptor.tags.join(", "));
+                ui.end_row();
+
+                ui.strong(tr("Extension"));
+                ui.label(descriptor.extension.as_str());
+                ui.end_row();
+            } else {
+                ui.label(tr("Filename has no episode descriptor"));
+                ui.end_row();
+            }
+        });
+}
+
+pub fn render_file_preview(ui: &mut egui::Ui, thumbnail_cache: &Arc<ThumbnailCache>, folder: &Arc<AppFolder>) {
+    let selected_descriptor = *folder.get_selected_descriptor().blocking_read();
+    let selected_descriptor = match selected_descriptor {
+        Some(descriptor) => descriptor,
+        None => {
+            ui.label(tr("No file selected"));
+            return;
+        },
+    };
+
+    // Preview the first video of the selected episode, ffmpeg can't take a thumbnail of subtitles or nfo files
+    let src = {
+        let files = folder.get_files_blocking();
+        let file = files.to_iter().find(|file| {
+            *file.get_src_descriptor() == Some(selected_descriptor) && is_video_path(file.get_src())
+        });
+        match file {
+            Some(file) => file.get_src().to_string(),
+            None => {
+                ui.label(tr("No video file selected"));
+                return;
+            },
+        }
+    };
+
+    let src_path = Path::new(folder.get_folder_path()).join(src.as_str());
+    render_thumbnail(ui, thumbnail_cache, src_path.to_string_lossy().as_ref());
+
+    let filename = Path::new(src.as_str())
+        .file_name()
+        .map(|filename| filename.to_string_lossy().to_string())
+        .unwrap_or(src.clone());
+    ui.push_id("file_preview", |ui| {
+        render_filename_breakdown(ui, filename.as_str());
+    });
+
+    let cache = folder.get_cache().blocking_read();
+    let ordering = folder.get_settings().blocking_read().get_ordering();
+    let overview = cache.as_ref().and_then(|cache| {
+        let index = cache.get_episode_index(&selected_descriptor, ordering)?;
+        cache.episodes.get(index)?.overview.clone()
+    });
+    if let Some(overview) = overview {
+        ui.strong(tr("Overview"));
+        ui.add(egui::Label::new(overview).wrap(true));
+    }
+}
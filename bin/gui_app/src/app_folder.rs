@@ -1,29 +1,51 @@
+use app::app::App;
 use app::app_folder::AppFolder;
-use app::file_intent::Action;
+use app::file_intent::{Action, DuplicatePolicy, TitlePolicy};
+use app::tvdb_cache::EpisodeOrdering;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tvdb::api::LoginSession;
 use tokio;
 use crate::fuzzy_search::FuzzySearcher;
+use crate::file_filters::FileFilters;
+use crate::app_file_actions::{clear_file_selection, is_file_selected};
+use crate::app_file_preview::render_file_preview;
+use crate::app_folder_basic_list::FileTable;
 use crate::app_folder_files_tab_list::{FileTab, render_files_tab_list};
 use crate::app_folder_episode_cache_list::render_episode_cache_list;
-use crate::helpers::render_invisible_width_widget;
-use crate::tvdb_tables::{render_series_table, render_episode_table};
+use crate::helpers::{format_file_size, render_invisible_width_widget};
+use crate::tvdb_tables::{render_series_table, render_episode_table, render_external_links};
 use crate::error_list::render_errors_list;
+use crate::permission_report::render_permission_report;
+use crate::skipped_files_report::{is_skipped_files, render_skipped_files_report};
+use crate::shortcuts::{EXECUTE_CHANGES_SHORTCUT, UPDATE_INTENTS_SHORTCUT};
+use crate::i18n::{tr, trf};
+use crate::execute_confirmation::request_folder_execution;
 
 pub struct GuiAppFolder {
     searcher: FuzzySearcher,
+    filters: FileFilters,
     selected_tab: FileTab,
+    file_table: FileTable,
     is_show_episode_cache: bool,
+    is_show_preview: bool,
     pub(crate) is_show_series_search: bool,
+    pub(crate) series_search_request: Option<String>,
+    dest_edits: HashMap<String, String>,
 }
 
 impl GuiAppFolder {
     pub fn new() -> Self {
         Self {
             searcher: FuzzySearcher::new(),
+            filters: FileFilters::new(),
             selected_tab: FileTab::FileAction(Action::Complete),
+            file_table: FileTable::new(),
             is_show_episode_cache: false,
+            is_show_preview: false,
             is_show_series_search: false,
+            series_search_request: None,
+            dest_edits: HashMap::new(),
         }
     }
 }
@@ -35,7 +57,7 @@ impl Default for GuiAppFolder {
 }
 
 fn render_folder_controls(
-    ui: &mut egui::Ui, session: Option<&Arc<LoginSession>>,
+    ui: &mut egui::Ui, app: &Arc<App>, session: Option<&Arc<LoginSession>>,
     gui: &mut GuiAppFolder, folder: &Arc<AppFolder>,
 ) {
     let is_not_busy = folder.get_busy_lock().try_lock().is_ok();
@@ -44,21 +66,24 @@ fn render_folder_controls(
 
     ui.horizontal(|ui| {
         ui.add_enabled_ui(is_cache_loaded && is_not_busy, |ui| {
-            let res = ui.button("Update file intents");
+            let button = egui::Button::new(tr("Update file intents"))
+                .shortcut_text(ui.ctx().format_shortcut(&UPDATE_INTENTS_SHORTCUT));
+            let res = ui.add(button);
             if res.clicked() {
                 let folder = folder.clone();
                 tokio::spawn(async move {
+                    folder.clear_execution_outcomes().await;
                     folder.update_file_intents().await
                 });
             }
             res.on_disabled_hover_ui(|ui| {
-                if !is_cache_loaded  { ui.label("Cache is unloaded"); } 
-                else if !is_not_busy { ui.label("Folder is busy"); }
+                if !is_cache_loaded  { ui.label(tr("Cache is unloaded")); } 
+                else if !is_not_busy { ui.label(tr("Folder is busy")); }
             });
         });
 
         ui.add_enabled_ui(is_not_busy, |ui| {
-            let res = ui.button("Load cache from file");
+            let res = ui.button(tr("Load cache from file"));
             if res.clicked() {
                 let folder = folder.clone();
                 tokio::spawn(async move {
@@ -67,15 +92,16 @@ fn render_folder_controls(
                 });
             };
             res.on_disabled_hover_ui(|ui| {
-                if !is_not_busy { ui.label("Folder is busy"); }
+                if !is_not_busy { ui.label(tr("Folder is busy")); }
             });
         });
         
         ui.add_enabled_ui(is_cache_loaded && is_not_busy && is_logged_in, |ui| {
-            let res = ui.button("Refresh cache from api");
+            let res = ui.button(tr("Refresh cache from api"));
             if res.clicked() {
                 if let Some(session) = session {
                     tokio::spawn({
+                        let app = app.clone();
                         let folder = folder.clone();
                         let session = session.clone();
                         async move {
@@ -84,82 +110,627 @@ fn render_folder_controls(
                                 folder.update_file_intents(),
                                 folder.save_cache_to_file(),
                             );
-                            Some(())
+                            // other folders bound to the series share the refreshed cache
+                            app.update_outdated_folders().await
                         }
                     });
                 }
             }
             res.on_disabled_hover_ui(|ui| {
-                if !is_cache_loaded   { ui.label("Cache is unloaded"); }
-                else if !is_not_busy  { ui.label("Folder is busy"); }
-                else if !is_logged_in { ui.label("Not logged in"); }
+                if !is_cache_loaded   { ui.label(tr("Cache is unloaded")); }
+                else if !is_not_busy  { ui.label(tr("Folder is busy")); }
+                else if !is_logged_in { ui.label(tr("Not logged in")); }
             });
         });
 
-        ui.add_enabled_ui(is_not_busy, |ui| {
-            let res = ui.button("Execute changes");
+        ui.add_enabled_ui(is_cache_loaded && is_not_busy, |ui| {
+            let res = ui.button(tr("Load anime mapping"));
             if res.clicked() {
-                let folder = folder.clone();
-                tokio::spawn(async move {
-                    folder.execute_file_changes().await;
-                    folder.update_file_intents().await
+                tokio::spawn({
+                    let folder = folder.clone();
+                    let client = app.get_client().blocking_read().clone();
+                    async move {
+                        folder.load_anime_mapping_from_anilist(client).await?;
+                        tokio::join!(
+                            folder.update_file_intents(),
+                            folder.save_anime_mapping_to_file(),
+                        );
+                        Some(())
+                    }
                 });
+            }
+            res.on_disabled_hover_ui(|ui| {
+                if !is_cache_loaded  { ui.label(tr("Cache is unloaded")); }
+                else if !is_not_busy { ui.label(tr("Folder is busy")); }
+            });
+        });
+
+        let subtitle_session = app.get_subtitle_session().blocking_read().clone();
+        let has_subtitle_languages = !folder.get_settings().blocking_read().subtitle_languages.is_empty();
+        let is_subtitles_ready = subtitle_session.is_some() && has_subtitle_languages;
+        ui.add_enabled_ui(is_cache_loaded && is_not_busy && is_subtitles_ready, |ui| {
+            let res = ui.button(tr("Fetch subtitles"));
+            if res.clicked() {
+                if let Some(session) = subtitle_session {
+                    tokio::spawn({
+                        let folder = folder.clone();
+                        async move {
+                            folder.fetch_missing_subtitles(session).await;
+                            folder.update_file_intents().await
+                        }
+                    });
+                }
+            }
+            res.on_disabled_hover_ui(|ui| {
+                if !is_cache_loaded             { ui.label(tr("Cache is unloaded")); }
+                else if !is_not_busy            { ui.label(tr("Folder is busy")); }
+                else if !has_subtitle_languages { ui.label(tr("No subtitle languages selected")); }
+                else                            { ui.label(tr("Not logged in to OpenSubtitles")); }
+            });
+        });
+
+        ui.add_enabled_ui(is_not_busy, |ui| {
+            let button = egui::Button::new(tr("Execute changes"))
+                .shortcut_text(ui.ctx().format_shortcut(&EXECUTE_CHANGES_SHORTCUT));
+            let res = ui.add(button);
+            if res.clicked() {
+                request_folder_execution(folder);
             };
             res.on_disabled_hover_ui(|ui| {
-                if !is_not_busy { ui.label("Folder is busy"); }
+                if !is_not_busy { ui.label(tr("Folder is busy")); }
             });
         });
 
-        if ui.button("Load bookmarks").clicked() {
+        let mut is_plan_locked = *folder.get_is_plan_locked().blocking_lock();
+        let res = ui.toggle_value(&mut is_plan_locked, tr("Lock plan"));
+        if res.changed() {
+            *folder.get_is_plan_locked().blocking_lock() = is_plan_locked;
+        }
+        res.on_hover_text(tr("Automatic refreshes keep the reviewed plan until it is executed or unlocked"));
+
+        if ui.button(tr("Load bookmarks")).clicked() {
             let folder = folder.clone();
             tokio::spawn(async move {
                 folder.load_bookmarks_from_file().await
             });
         }
 
-        ui.toggle_value(&mut gui.is_show_series_search, "Search series");
+        ui.toggle_value(&mut gui.is_show_series_search, tr("Search series"));
         ui.add_enabled_ui(is_cache_loaded, |ui| {
-            let res = ui.toggle_value(&mut gui.is_show_episode_cache, "Search episodes");
+            let res = ui.toggle_value(&mut gui.is_show_episode_cache, tr("Search episodes"));
             res.on_disabled_hover_ui(|ui| {
-                ui.label("Cache is unloaded");
+                ui.label(tr("Cache is unloaded"));
+            });
+        });
+    });
+
+    render_folder_disk_space(ui, folder);
+}
+
+fn render_folder_disk_space(ui: &mut egui::Ui, folder: &Arc<AppFolder>) {
+    // Skip while the folder is being updated rather than stall the ui
+    let file_tracker = match folder.get_file_tracker().try_read() {
+        Ok(file_tracker) => file_tracker,
+        Err(_) => return,
+    };
+    ui.horizontal(|ui| {
+        ui.label(trf("Reclaimable: {}", &[&format_file_size(file_tracker.get_reclaimable_size())]))
+            .on_hover_text(tr("Space freed by executing the pending deletes"));
+        ui.separator();
+        ui.label(trf("Whitelisted and extras: {}", &[&format_file_size(file_tracker.get_extras_size())]))
+            .on_hover_text(tr("Space used by files kept alongside the episodes"));
+    });
+}
+
+// Common languages supported by tvdb
+const EPISODE_LANGUAGES: [(&str, &str);14] = [
+    ("en", "English"), ("de", "German"), ("fr", "French"), ("es", "Spanish"),
+    ("it", "Italian"), ("nl", "Dutch"), ("pt", "Portuguese"), ("sv", "Swedish"),
+    ("pl", "Polish"), ("ru", "Russian"), ("ja", "Japanese"), ("zh", "Chinese"),
+    ("ko", "Korean"), ("da", "Danish"),
+];
+
+fn render_folder_language(ui: &mut egui::Ui, session: Option<&Arc<LoginSession>>, folder: &Arc<AppFolder>) {
+    let is_not_busy = folder.get_busy_lock().try_lock().is_ok();
+    let old_language = folder.get_settings().blocking_read().language.clone();
+    let mut new_language = old_language.clone();
+    let get_label = |language: Option<&str>| -> String {
+        match language {
+            None => tr("Default").to_string(),
+            Some(language) => match EPISODE_LANGUAGES.iter().find(|(abbreviation, _)| *abbreviation == language) {
+                Some((_, name)) => tr(name).to_string(),
+                None => language.to_string(),
+            },
+        }
+    };
+
+    ui.add_enabled_ui(is_not_busy, |ui| {
+        ui.horizontal(|ui| {
+            ui.strong(tr("Episode language"));
+            egui::ComboBox::from_id_source("episode_language")
+                .selected_text(get_label(new_language.as_deref()))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut new_language, None, get_label(None));
+                    for (abbreviation, name) in EPISODE_LANGUAGES.iter() {
+                        ui.selectable_value(&mut new_language, Some(abbreviation.to_string()), tr(name));
+                    }
+                });
+        });
+    });
+
+    if new_language == old_language {
+        return;
+    }
+
+    folder.get_settings().blocking_write().language = new_language.clone();
+    tokio::spawn({
+        let folder = folder.clone();
+        let session = session.cloned();
+        async move {
+            folder.save_settings_to_file().await;
+            let language = match new_language {
+                Some(language) => language,
+                None => return folder.update_file_intents().await,
+            };
+            let is_cached = match folder.get_cache().read().await.as_ref() {
+                Some(cache) => cache.translations.contains_key(&language),
+                None => true,
+            };
+            if !is_cached {
+                if let Some(session) = session {
+                    if folder.load_translation_from_api(session, language.as_str()).await.is_some() {
+                        folder.save_cache_to_file().await;
+                    }
+                }
+            }
+            folder.update_file_intents().await
+        }
+    });
+}
+
+fn render_folder_profile(ui: &mut egui::Ui, folder: &Arc<AppFolder>) {
+    let is_not_busy = folder.get_busy_lock().try_lock().is_ok();
+    let old_profile = folder.get_settings().blocking_read().profile.clone();
+    let mut new_profile = old_profile.clone();
+    let (names, default_profile) = {
+        let profiles = folder.get_profile_store().get_profiles().blocking_read();
+        let names: Vec<String> = profiles.profiles.keys().cloned().collect();
+        (names, profiles.default_profile.clone())
+    };
+    if names.is_empty() && old_profile.is_none() {
+        return;
+    }
+    let get_label = |profile: Option<&str>| -> String {
+        match profile {
+            None => trf("Default ({})", &[&default_profile.as_deref().unwrap_or(tr("None"))]),
+            Some(profile) => profile.to_string(),
+        }
+    };
+
+    ui.add_enabled_ui(is_not_busy, |ui| {
+        ui.horizontal(|ui| {
+            ui.strong(tr("Rule profile"));
+            egui::ComboBox::from_id_source("rule_profile")
+                .selected_text(get_label(new_profile.as_deref()))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut new_profile, None, get_label(None));
+                    for name in names.iter() {
+                        ui.selectable_value(&mut new_profile, Some(name.clone()), name.as_str());
+                    }
+                });
+        });
+    });
+
+    if new_profile == old_profile {
+        return;
+    }
+
+    folder.get_settings().blocking_write().profile = new_profile;
+    tokio::spawn({
+        let folder = folder.clone();
+        async move {
+            folder.save_settings_to_file().await;
+            folder.update_filter_rules().await;
+            folder.update_file_intents().await
+        }
+    });
+}
+
+fn render_folder_title_policy(ui: &mut egui::Ui, folder: &Arc<AppFolder>) {
+    let is_not_busy = folder.get_busy_lock().try_lock().is_ok();
+    let old_policy = folder.get_settings().blocking_read().title_policy;
+    let mut new_policy = old_policy;
+    let default_policy = folder.get_filter_rules().blocking_read().title_policy;
+    let get_label = |policy: Option<TitlePolicy>| -> String {
+        match policy {
+            None => trf("Default ({})", &[&tr(default_policy.to_str())]),
+            Some(policy) => tr(policy.to_str()).to_string(),
+        }
+    };
+
+    ui.add_enabled_ui(is_not_busy, |ui| {
+        ui.horizontal(|ui| {
+            ui.strong(tr("Missing titles"));
+            egui::ComboBox::from_id_source("title_policy")
+                .selected_text(get_label(new_policy))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut new_policy, None, get_label(None));
+                    for policy in TitlePolicy::iterator() {
+                        ui.selectable_value(&mut new_policy, Some(*policy), get_label(Some(*policy)));
+                    }
+                });
+        });
+    });
+
+    if new_policy == old_policy {
+        return;
+    }
+
+    folder.get_settings().blocking_write().title_policy = new_policy;
+    tokio::spawn({
+        let folder = folder.clone();
+        async move {
+            folder.save_settings_to_file().await;
+            folder.update_file_intents().await
+        }
+    });
+}
+
+fn render_folder_duplicate_policy(ui: &mut egui::Ui, folder: &Arc<AppFolder>) {
+    let is_not_busy = folder.get_busy_lock().try_lock().is_ok();
+    let old_policy = folder.get_settings().blocking_read().duplicate_policy;
+    let mut new_policy = old_policy;
+    let default_policy = folder.get_filter_rules().blocking_read().duplicate_policy;
+    let get_label = |policy: Option<DuplicatePolicy>| -> String {
+        match policy {
+            None => trf("Default ({})", &[&tr(default_policy.to_str())]),
+            Some(policy) => tr(policy.to_str()).to_string(),
+        }
+    };
+
+    ui.add_enabled_ui(is_not_busy, |ui| {
+        ui.horizontal(|ui| {
+            ui.strong(tr("Duplicate episodes"));
+            egui::ComboBox::from_id_source("duplicate_policy")
+                .selected_text(get_label(new_policy))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut new_policy, None, get_label(None));
+                    for policy in DuplicatePolicy::iterator() {
+                        ui.selectable_value(&mut new_policy, Some(*policy), get_label(Some(*policy)));
+                    }
+                });
+        });
+    });
+
+    if new_policy == old_policy {
+        return;
+    }
+
+    folder.get_settings().blocking_write().duplicate_policy = new_policy;
+    tokio::spawn({
+        let folder = folder.clone();
+        async move {
+            folder.save_settings_to_file().await;
+            folder.update_file_intents().await
+        }
+    });
+}
+
+fn render_folder_ordering(ui: &mut egui::Ui, folder: &Arc<AppFolder>) {
+    let is_not_busy = folder.get_busy_lock().try_lock().is_ok();
+    let old_ordering = folder.get_settings().blocking_read().get_ordering();
+    let mut new_ordering = old_ordering;
+
+    ui.add_enabled_ui(is_not_busy, |ui| {
+        ui.horizontal(|ui| {
+            ui.strong(tr("Episode order"));
+            egui::ComboBox::from_id_source("episode_ordering")
+                .selected_text(tr(new_ordering.to_str()))
+                .show_ui(ui, |ui| {
+                    for ordering in EpisodeOrdering::iterator() {
+                        ui.selectable_value(&mut new_ordering, *ordering, tr(ordering.to_str()));
+                    }
+                });
+        });
+    });
+
+    if new_ordering == old_ordering {
+        return;
+    }
+
+    // the aired order is the default so it isn't written to the settings
+    folder.get_settings().blocking_write().ordering = (new_ordering != EpisodeOrdering::Aired).then_some(new_ordering);
+    clear_file_selection(folder);
+    tokio::spawn({
+        let folder = folder.clone();
+        async move {
+            folder.save_settings_to_file().await;
+            folder.update_file_intents().await
+        }
+    });
+}
+
+fn render_folder_subtitle_languages(ui: &mut egui::Ui, folder: &Arc<AppFolder>) {
+    let is_not_busy = folder.get_busy_lock().try_lock().is_ok();
+    let old_languages = folder.get_settings().blocking_read().subtitle_languages.clone();
+    let mut new_languages = old_languages.clone();
+
+    ui.add_enabled_ui(is_not_busy, |ui| {
+        ui.collapsing(tr("Subtitle languages"), |ui| {
+            ui.horizontal_wrapped(|ui| {
+                for (abbreviation, name) in EPISODE_LANGUAGES.iter() {
+                    let mut is_selected = new_languages.iter().any(|language| language == abbreviation);
+                    if ui.checkbox(&mut is_selected, tr(name)).changed() {
+                        match is_selected {
+                            true => new_languages.push(abbreviation.to_string()),
+                            false => new_languages.retain(|language| language != abbreviation),
+                        }
+                    }
+                }
+            });
+        });
+    });
+
+    if new_languages == old_languages {
+        return;
+    }
+
+    folder.get_settings().blocking_write().subtitle_languages = new_languages;
+    tokio::spawn({
+        let folder = folder.clone();
+        async move {
+            folder.save_settings_to_file().await
+        }
+    });
+}
+
+fn render_folder_season_scope(ui: &mut egui::Ui, seasons: &[u32], folder: &Arc<AppFolder>) {
+    let is_not_busy = folder.get_busy_lock().try_lock().is_ok();
+    let old_scope = folder.get_settings().blocking_read().season_scope.clone();
+    let mut new_scope = old_scope.clone();
+
+    ui.add_enabled_ui(is_not_busy, |ui| {
+        ui.collapsing(tr("Seasons in folder"), |ui| {
+            ui.label(tr("Only the selected seasons are checked for missing episodes"));
+            ui.horizontal_wrapped(|ui| {
+                for season in seasons.iter() {
+                    let mut is_selected = new_scope.contains(season);
+                    if ui.checkbox(&mut is_selected, trf("Season {}", &[season])).changed() {
+                        match is_selected {
+                            true => new_scope.push(*season),
+                            false => new_scope.retain(|other| other != season),
+                        }
+                    }
+                }
             });
+            if ui.add_enabled(!new_scope.is_empty(), egui::Button::new(tr("All seasons"))).clicked() {
+                new_scope.clear();
+            }
         });
     });
+
+    if new_scope == old_scope {
+        return;
+    }
+
+    new_scope.sort_unstable();
+    folder.get_settings().blocking_write().season_scope = new_scope;
+    tokio::spawn({
+        let folder = folder.clone();
+        async move {
+            folder.save_settings_to_file().await;
+            folder.update_file_intents().await
+        }
+    });
 }
 
-fn render_folder_info(ui: &mut egui::Ui, folder: &Arc<AppFolder>) {
+fn render_folder_archive(ui: &mut egui::Ui, seasons: &[u32], folder: &Arc<AppFolder>) {
+    let is_not_busy = folder.get_busy_lock().try_lock().is_ok();
+    let (old_is_archived, old_seasons) = {
+        let settings = folder.get_settings().blocking_read();
+        (settings.is_archived, settings.archived_seasons.clone())
+    };
+    let mut new_is_archived = old_is_archived;
+    let mut new_seasons = old_seasons.clone();
+    let archive_path = folder.get_filter_rules().blocking_read().archive_path.clone();
+
+    ui.add_enabled_ui(is_not_busy, |ui| {
+        ui.collapsing(tr("Archive"), |ui| {
+            if archive_path.is_none() {
+                ui.label(tr("Set an archive path in the app config to archive files"));
+            }
+            ui.checkbox(&mut new_is_archived, tr("Archive whole folder"));
+            ui.add_enabled_ui(!new_is_archived, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    for season in seasons.iter() {
+                        let mut is_selected = new_seasons.contains(season);
+                        if ui.checkbox(&mut is_selected, trf("Season {}", &[season])).changed() {
+                            match is_selected {
+                                true => new_seasons.push(*season),
+                                false => new_seasons.retain(|other| other != season),
+                            }
+                        }
+                    }
+                });
+            });
+            let total_archived = folder.get_archived_files().blocking_read().len();
+            if total_archived > 0 {
+                ui.label(trf("{} files archived", &[&total_archived]));
+            }
+        });
+    });
+
+    if new_is_archived == old_is_archived && new_seasons == old_seasons {
+        return;
+    }
+
+    new_seasons.sort_unstable();
+    {
+        let mut settings = folder.get_settings().blocking_write();
+        settings.is_archived = new_is_archived;
+        settings.archived_seasons = new_seasons;
+    }
+    tokio::spawn({
+        let folder = folder.clone();
+        async move {
+            folder.save_settings_to_file().await;
+            folder.update_file_intents().await
+        }
+    });
+}
+
+// Unparsed files can be given a destination too, validate_dest decides whether it can be applied
+fn render_file_details(ui: &mut egui::Ui, dest_edits: &mut HashMap<String, String>, folder: &Arc<AppFolder>) {
+    let selected_descriptor = *folder.get_selected_descriptor().blocking_read();
+    let selected_src = folder.get_selected_src().blocking_read().clone();
+    if selected_descriptor.is_none() && selected_src.is_none() {
+        return;
+    }
+
+    ui.separator();
+    ui.heading(tr("Files"));
+    let is_not_busy = folder.get_busy_lock().try_lock().is_ok();
+    let overrides = folder.get_settings().blocking_read().dest_overrides.clone();
+    {
+        let mut files = folder.get_mut_files_blocking();
+        let mut files_iter = files.to_iter();
+        while let Some(mut file) = files_iter.next_mut() {
+            if !is_file_selected(file.get_src_descriptor(), file.get_src(), &selected_descriptor, selected_src.as_deref()) {
+                continue;
+            }
+
+            let src = file.get_src().to_string();
+            ui.push_id(src.as_str(), |ui| {
+                ui.strong(src.as_str());
+                ui.add_enabled_ui(is_not_busy, |ui| {
+                    let mut dest_edit_buffer = match dest_edits.get(&src) {
+                        Some(dest) => dest.clone(),
+                        None => file.get_dest().to_string(),
+                    };
+                    let elem = egui::TextEdit::singleline(&mut dest_edit_buffer);
+                    if ui.add_sized([ui.available_width(), 0.0], elem).changed() {
+                        dest_edits.insert(src.clone(), dest_edit_buffer);
+                    }
+                });
+
+                if let Some(dest) = dest_edits.get(&src).cloned() {
+                    if dest == file.get_dest() {
+                        dest_edits.remove(&src);
+                    } else {
+                        ui.horizontal(|ui| {
+                            match file.validate_dest(dest.as_str()) {
+                                Err(err) => {
+                                    ui.colored_label(egui::Color32::DARK_RED, err.to_string());
+                                },
+                                Ok(()) => if ui.add_enabled(is_not_busy, egui::Button::new(tr("Apply"))).clicked() {
+                                    if file.get_action() != Action::Rename {
+                                        file.set_action(Action::Rename);
+                                    }
+                                    file.set_dest(dest.clone());
+                                    dest_edits.remove(&src);
+                                    tokio::spawn({
+                                        let folder = folder.clone();
+                                        let src = src.clone();
+                                        async move {
+                                            folder.set_dest_override(src.as_str(), Some(dest)).await
+                                        }
+                                    });
+                                },
+                            }
+                            if ui.button(tr("Revert")).clicked() {
+                                dest_edits.remove(&src);
+                            }
+                        });
+                    }
+                }
+
+                if overrides.contains_key(&src) {
+                    ui.horizontal(|ui| {
+                        ui.label(tr("Manual override"));
+                        if ui.add_enabled(is_not_busy, egui::Button::new(tr("Clear override"))).clicked() {
+                            tokio::spawn({
+                                let folder = folder.clone();
+                                let src = src.clone();
+                                async move {
+                                    folder.set_dest_override(src.as_str(), None).await;
+                                    folder.update_file_intents().await
+                                }
+                            });
+                        }
+                    });
+                }
+            });
+        }
+    }
+    folder.flush_file_changes_blocking();
+}
+
+fn render_folder_info(ui: &mut egui::Ui, session: Option<&Arc<LoginSession>>, folder: &Arc<AppFolder>) {
     render_invisible_width_widget(ui);
 
     let cache = folder.get_cache().blocking_read();
     let cache = match cache.as_ref() {
         Some(cache) => cache,
         None => {
-            ui.label("No cache loaded");
+            ui.label(tr("No cache loaded"));
             return;
         },
     };
     
-    ui.heading("Series");
+    ui.heading(tr("Series"));
     ui.push_id("series_table", |ui| {
         render_series_table(ui, &cache.series);
     });
+    render_external_links(ui, &cache.series);
+    render_folder_profile(ui, folder);
+    render_folder_language(ui, session, folder);
+    render_folder_title_policy(ui, folder);
+    render_folder_duplicate_policy(ui, folder);
+    render_folder_ordering(ui, folder);
+    render_folder_subtitle_languages(ui, folder);
+    let ordering = folder.get_settings().blocking_read().get_ordering();
+    let mut seasons: Vec<u32> = (0..cache.episodes.len())
+        .filter_map(|index| Some(cache.get_episode_key(index, ordering)?.season))
+        .collect();
+    seasons.sort_unstable();
+    seasons.dedup();
+    render_folder_season_scope(ui, seasons.as_slice(), folder);
+    render_folder_archive(ui, seasons.as_slice(), folder);
+    if let Some(label) = folder.get_settings().blocking_read().torrent_label.as_ref() {
+        ui.horizontal(|ui| {
+            ui.strong(tr("Torrent label"));
+            ui.label(label);
+        });
+    }
+
+    if let Some(mapping) = folder.get_anime_mapping().blocking_read().as_ref() {
+        ui.collapsing(tr("Anime mapping"), |ui| {
+            for season in mapping.seasons.iter() {
+                let end = match season.end {
+                    u32::MAX => "".to_string(),
+                    end => end.to_string(),
+                };
+                ui.label(trf("Season {}: episodes {}-{}", &[&format!("{:02}", season.season), &season.start, &end]));
+            }
+        });
+    }
 
     ui.separator();
 
-    ui.heading("Episode");
+    ui.heading(tr("Episode"));
     let descriptor = *folder.get_selected_descriptor().blocking_read(); 
     let key = match descriptor {
         Some(key) => key,
         None => {
-            ui.label("No episode selected");
+            ui.label(tr("No episode selected"));
             return;
         },
     };
 
-    let episode_index = match cache.episode_cache.get(&key) {
-        Some(index) => *index,
+    let episode_index = match cache.get_episode_index(&key, ordering) {
+        Some(index) => index,
         None => {
-            ui.label("Episode not in cache");
+            ui.label(tr("Episode not in cache"));
             return;
         },
     };
@@ -167,7 +738,7 @@ fn render_folder_info(ui: &mut egui::Ui, folder: &Arc<AppFolder>) {
     let episode = match cache.episodes.get(episode_index) {
         Some(episode) => episode,   
         None => {
-            ui.colored_label(egui::Color32::DARK_RED, "Episode index out of range of episodes list");
+            ui.colored_label(egui::Color32::DARK_RED, tr("Episode index out of range of episodes list"));
             return;
         },
     };
@@ -178,7 +749,8 @@ fn render_folder_info(ui: &mut egui::Ui, folder: &Arc<AppFolder>) {
 }
 
 pub fn render_app_folder(
-    ui: &mut egui::Ui, session: Option<&Arc<LoginSession>>,
+    ui: &mut egui::Ui, 
+    app: &Arc<App>, session: Option<&Arc<LoginSession>>,
     gui: &mut GuiAppFolder, folder: &Arc<AppFolder>,
 ) {
     tokio::spawn({
@@ -188,18 +760,24 @@ pub fn render_app_folder(
         }
     });
 
-    egui::TopBottomPanel::top("folder_controls")
+    egui::TopBottomPanel::top(ui.id().with("folder_controls"))
         .resizable(false)
         .show_inside(ui, |ui| {
-            render_folder_controls(ui, session, gui, folder);
+            render_folder_controls(ui, app, session, gui, folder);
         });
     
-    egui::SidePanel::right("folder_info")
+    egui::SidePanel::right(ui.id().with("folder_info"))
         .resizable(true)
         .show_inside(ui, |ui| {
             ui.push_id("folder_info", |ui| {
                 egui::ScrollArea::vertical().show(ui, |ui| {
-                    render_folder_info(ui, folder);
+                    render_folder_info(ui, session, folder);
+                    render_file_details(ui, &mut gui.dest_edits, folder);
+                    ui.separator();
+                    ui.checkbox(&mut gui.is_show_preview, tr("Show preview"));
+                    if gui.is_show_preview {
+                        render_file_preview(ui, app.get_thumbnail_cache(), folder);
+                    }
                 });
             });
         });
@@ -207,9 +785,26 @@ pub fn render_app_folder(
     egui::CentralPanel::default()
         .frame(egui::Frame::none())
         .show_inside(ui, |ui| {
+            let is_permission_issues = folder.get_permission_issues().try_read().map(|issues| !issues.is_empty()).unwrap_or(false);
+            if is_permission_issues {
+                egui::TopBottomPanel::bottom(ui.id().with("folder_permission_report"))
+                    .resizable(false)
+                    .show_inside(ui, |ui| {
+                        render_permission_report(ui, folder);
+                    });
+            }
+
+            if is_skipped_files(folder) {
+                egui::TopBottomPanel::bottom(ui.id().with("folder_skipped_files_report"))
+                    .resizable(false)
+                    .show_inside(ui, |ui| {
+                        render_skipped_files_report(ui, folder);
+                    });
+            }
+
             if let Ok(mut errors) = folder.get_errors().try_write() {
                 if !errors.is_empty() {
-                    egui::TopBottomPanel::bottom("folder_error_list")
+                    egui::TopBottomPanel::bottom(ui.id().with("folder_error_list"))
                         .resizable(true)
                         .show_inside(ui, |ui| {
                             render_errors_list(ui, errors.as_mut());
@@ -226,7 +821,11 @@ pub fn render_app_folder(
                     ui.push_id(id, |ui| {
                         egui::ScrollArea::vertical().show(ui, |ui| {
                             if !gui.is_show_episode_cache {
-                                render_files_tab_list(ui, &mut gui.selected_tab, &mut gui.searcher, folder);
+                                render_files_tab_list(
+                                    ui, 
+                                    &mut gui.selected_tab, &mut gui.file_table, &mut gui.searcher, &mut gui.filters, &mut gui.series_search_request, 
+                                    folder,
+                                );
                             } else {
                                 render_episode_cache_list(ui, &mut gui.searcher, folder);
                             }
@@ -1,75 +1,281 @@
 use std::sync::Arc;
+use app::app_file::MutableAppFile;
 use app::file_intent::Action;
 use app::app_folder::AppFolder;
+use app::execution_outcomes::ExecutionOutcomes;
+use app::natural_sort::NaturalKey;
 use egui;
+use egui_extras::{TableBuilder, Column};
+use enum_map;
 use tokio;
+use crate::file_filters::FileFilters;
 use crate::fuzzy_search::{FuzzySearcher, render_search_bar};
 use crate::clipped_selectable::ClippedSelectableLabel;
-use crate::app_file_actions::{check_file_shortcuts, render_file_context_menu};
+use crate::app_file_actions::{
+    check_file_double_click, check_file_shortcuts, clear_file_selection, get_execution_outcome_label,
+    is_file_selected, render_execution_outcome, render_file_context_menu, select_file,
+};
+use crate::action_display::get_action_display;
 use crate::app_bookmarks::render_file_bookmarks;
+use crate::helpers::format_file_size;
+use crate::i18n::{tr, trf};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, enum_map::Enum)]
+pub enum FileColumn {
+    Source,
+    Destination,
+    Action,
+    Size,
+    Episode,
+    Confidence,
+    Status,
+}
+
+impl FileColumn {
+    pub fn iterator() -> std::slice::Iter<'static, Self> {
+        static COLUMNS: [FileColumn;7] = [
+            FileColumn::Source,
+            FileColumn::Destination,
+            FileColumn::Action,
+            FileColumn::Size,
+            FileColumn::Episode,
+            FileColumn::Confidence,
+            FileColumn::Status,
+        ];
+        COLUMNS.iter()
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            FileColumn::Source => "Source",
+            FileColumn::Destination => "Destination",
+            FileColumn::Action => "Action",
+            FileColumn::Size => "Size",
+            FileColumn::Episode => "Episode",
+            FileColumn::Confidence => "Confidence",
+            FileColumn::Status => "Status",
+        }
+    }
+}
+
+pub struct FileTable {
+    sort_column: FileColumn,
+    is_ascending: bool,
+    visible_columns: enum_map::EnumMap<FileColumn, bool>,
+}
+
+impl FileTable {
+    pub fn new() -> Self {
+        let mut visible_columns = enum_map::enum_map!{ _ => true };
+        visible_columns[FileColumn::Destination] = false;
+        visible_columns[FileColumn::Action] = false;
+        Self {
+            sort_column: FileColumn::Source,
+            is_ascending: true,
+            visible_columns,
+        }
+    }
+}
+
+impl Default for FileTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Filenames without a descriptor weren't matched at all
+// Matches with warnings need to be reviewed before they can be trusted
+fn get_confidence(file: &MutableAppFile<'_>) -> Option<&'static str> {
+    file.get_src_descriptor().as_ref()?;
+    if file.get_warnings().iter().all(|warning| !warning.is_review_required()) {
+        Some("High")
+    } else {
+        Some("Low")
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum SortKey {
+    Text(NaturalKey),
+    Number(u64),
+    Episode(Option<(u32, u32)>),
+}
+
+fn get_sort_key(file: &MutableAppFile<'_>, column: FileColumn, outcomes: &ExecutionOutcomes) -> SortKey {
+    match column {
+        FileColumn::Source => SortKey::Text(NaturalKey::new(file.get_src())),
+        FileColumn::Destination => SortKey::Text(NaturalKey::new(file.get_dest())),
+        FileColumn::Action => SortKey::Text(NaturalKey::new(file.get_action().to_str())),
+        FileColumn::Size => SortKey::Number(file.get_size()),
+        FileColumn::Episode => SortKey::Episode(file.get_src_descriptor().map(|key| (key.season, key.episode))),
+        FileColumn::Confidence => SortKey::Text(NaturalKey::new(get_confidence(file).unwrap_or(""))),
+        FileColumn::Status => {
+            let label = outcomes.get(file.get_src()).map(get_execution_outcome_label).unwrap_or_default();
+            SortKey::Text(NaturalKey::new(label.as_str()))
+        },
+    }
+}
+
+fn render_column_toggles(ui: &mut egui::Ui, table: &mut FileTable) {
+    ui.menu_button(tr("Columns"), |ui| {
+        for column in FileColumn::iterator() {
+            // Source is always shown so that files can still be selected
+            if *column == FileColumn::Source {
+                continue;
+            }
+            ui.checkbox(&mut table.visible_columns[*column], tr(column.to_str()));
+        }
+    });
+}
+
+fn render_sort_header(ui: &mut egui::Ui, table: &mut FileTable, column: FileColumn) {
+    let is_sorted = table.sort_column == column;
+    let label = match (is_sorted, table.is_ascending) {
+        (true, true) => format!("{} ⏶", tr(column.to_str())),
+        (true, false) => format!("{} ⏷", tr(column.to_str())),
+        (false, _) => tr(column.to_str()).to_string(),
+    };
+    if ui.selectable_label(is_sorted, egui::RichText::new(label).strong()).clicked() {
+        if is_sorted {
+            table.is_ascending = !table.is_ascending;
+        } else {
+            table.sort_column = column;
+            table.is_ascending = true;
+        }
+    }
+}
 
 pub fn render_files_basic_list(
-    ui: &mut egui::Ui, 
-    searcher: &mut FuzzySearcher, selected_action: Action, folder: &Arc<AppFolder>,
+    ui: &mut egui::Ui,
+    table: &mut FileTable, searcher: &mut FuzzySearcher, filters: &mut FileFilters, selected_action: Action, folder: &Arc<AppFolder>,
 ) {
     let file_tracker = folder.get_file_tracker().blocking_read();
     let mut files = folder.get_mut_files_blocking();
     let mut bookmarks = folder.get_bookmarks().blocking_write();
+    let outcomes = folder.get_execution_outcomes().blocking_read();
     let mut is_bookmarks_changed = false;
 
+    ui.horizontal(|ui| {
+        render_column_toggles(ui, table);
+    });
     render_search_bar(ui, searcher);
 
     if file_tracker.get_action_count()[selected_action] == 0 {
-        ui.heading(format!("No {}s", selected_action.to_str().to_lowercase()));
+        ui.heading(trf("No files with action {}", &[&tr(selected_action.to_str())]));
         return;
     }
 
+    // Gather the sort keys up front since files can only be accessed by index while rendering
+    let mut indices: Vec<(usize, SortKey)> = Vec::new();
+    {
+        let mut files_iter = files.to_iter();
+        let mut index = 0;
+        while let Some(file) = files_iter.next_mut() {
+            let is_filtered = filters.is_match(file.get_src(), file.get_src_descriptor().as_ref());
+            if file.get_action() == selected_action && is_filtered && searcher.search(file.get_src()) {
+                indices.push((index, get_sort_key(&file, table.sort_column, &outcomes)));
+            }
+            index += 1;
+        }
+    }
+    indices.sort_by(|(_, a), (_, b)| a.cmp(b));
+    if !table.is_ascending {
+        indices.reverse();
+    }
+
+    let columns: Vec<FileColumn> = FileColumn::iterator()
+        .filter(|column| table.visible_columns[**column] || **column == FileColumn::Source)
+        .copied()
+        .collect();
+
     let is_not_busy = folder.get_busy_lock().try_lock().is_ok();
     let selected_descriptor = *folder.get_selected_descriptor().blocking_read();
-    egui::ScrollArea::vertical().show(ui, |ui| {
-        let layout = egui::Layout::top_down(egui::Align::Min).with_cross_justify(true);
-        ui.with_layout(layout, |ui| {
-            let mut files_iter = files.to_iter();
-            while let Some(mut file) = files_iter.next_mut() {
-                let action = file.get_action();
-                if action != selected_action {
-                    continue;
-                }
-
-                if !searcher.search(file.get_src()) {
-                    continue;
+    let selected_src = folder.get_selected_src().blocking_read().clone();
+    let display = get_action_display();
+    let layout = egui::Layout::top_down(egui::Align::Min).with_cross_justify(true);
+    ui.with_layout(layout, |ui| {
+        let cell_layout = egui::Layout::top_down(egui::Align::Min).with_cross_justify(true);
+        let row_height = 18.0;
+        let mut builder = TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .cell_layout(cell_layout)
+            .column(Column::auto().resizable(false).clip(false));
+        for (i, column) in columns.iter().enumerate() {
+            let is_last = i == columns.len()-1;
+            builder = match (column, is_last) {
+                (_, true) => builder.column(Column::remainder().resizable(false).clip(true)),
+                (FileColumn::Source | FileColumn::Destination, false) => builder.column(Column::initial(300.0).resizable(true).clip(true)),
+                (_, false) => builder.column(Column::auto().resizable(true).clip(true)),
+            };
+        }
+        builder
+            .header(row_height, |mut header| {
+                header.col(|_| {});
+                for column in columns.iter() {
+                    header.col(|ui| render_sort_header(ui, table, *column));
                 }
-
-                ui.horizontal(|ui| {
-                    {
+            })
+            .body(|body| {
+                body.rows(row_height, indices.len(), |row_index, mut row| {
+                    let index = indices[row_index].0;
+                    let mut file = match files.get(index) {
+                        Some(file) => file,
+                        None => return,
+                    };
+                    row.col(|ui| {
                         let src = file.get_src();
                         let bookmark = bookmarks.get_mut_with_insert(src);
                         is_bookmarks_changed = render_file_bookmarks(ui, bookmark) || is_bookmarks_changed;
-                    }
-                    let layout = egui::Layout::top_down(egui::Align::Min).with_cross_justify(true);
-                    ui.with_layout(layout, |ui| {
-                        let src = file.get_src();
-                        let descriptor = file.get_src_descriptor();
-                        let is_selected = descriptor.is_some() && *descriptor == selected_descriptor;
-                        let elem = ClippedSelectableLabel::new(is_selected, src);
-                        let res = ui.add(elem);
-                        if res.clicked() {
-                            if is_selected {
-                                *folder.get_selected_descriptor().blocking_write() = None;
-                            } else {
-                                *folder.get_selected_descriptor().blocking_write() = *descriptor;
-                            }
-                        }
-                        if is_not_busy && res.hovered() {
-                            check_file_shortcuts(ui, &mut file);
-                        }
-                        res.context_menu(|ui| {
-                            render_file_context_menu(ui, folder.get_folder_path(), &mut file, is_not_busy);
-                        });
                     });
+                    for column in columns.iter() {
+                        row.col(|ui| match column {
+                            FileColumn::Source => {
+                                let src = file.get_src();
+                                let descriptor = file.get_src_descriptor();
+                                let is_selected = is_file_selected(descriptor, file.get_src(), &selected_descriptor, selected_src.as_deref());
+                                let elem = ClippedSelectableLabel::new(is_selected, src);
+                                let res = ui.add(elem);
+                                if res.clicked() {
+                                    if is_selected {
+                                        clear_file_selection(folder);
+                                    } else {
+                                        select_file(folder, descriptor, file.get_src());
+                                    }
+                                }
+                                check_file_double_click(&res, folder, &file);
+                                if is_not_busy && (res.hovered() || res.has_focus()) {
+                                    check_file_shortcuts(ui, folder, &mut file);
+                                }
+                                res.context_menu(|ui| {
+                                    render_file_context_menu(ui, folder, &mut file, is_not_busy);
+                                });
+                            },
+                            FileColumn::Destination => { ui.label(file.get_dest()); },
+                            FileColumn::Action => { ui.label(display.get_label(file.get_action(), tr(file.get_action().to_str()))); },
+                            FileColumn::Size => { ui.label(format_file_size(file.get_size())); },
+                            FileColumn::Episode => {
+                                if let Some(key) = file.get_src_descriptor() {
+                                    ui.label(format!("S{:02}E{:02}", key.season, key.episode));
+                                }
+                            },
+                            FileColumn::Confidence => {
+                                if let Some(confidence) = get_confidence(&file) {
+                                    ui.label(tr(confidence));
+                                }
+                            },
+                            FileColumn::Status => {
+                                if let Some(outcome) = outcomes.get(file.get_src()) {
+                                    ui.horizontal(|ui| {
+                                        render_execution_outcome(ui, Some(outcome));
+                                        ui.label(get_execution_outcome_label(outcome));
+                                    });
+                                }
+                            },
+                        });
+                    }
                 });
-            }
-        });
+            });
     });
 
     if is_bookmarks_changed {
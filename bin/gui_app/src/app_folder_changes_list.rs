@@ -0,0 +1,86 @@
+use std::sync::Arc;
+use app::app_folder::AppFolder;
+use app::plan_diff::PlanChangeKind;
+use egui;
+use egui_extras::{TableBuilder, Column};
+use crate::action_display::get_action_display;
+use crate::fuzzy_search::{FuzzySearcher, render_search_bar};
+use crate::i18n::{tr, trf};
+
+pub fn render_files_changes_list(
+    ui: &mut egui::Ui, 
+    searcher: &mut FuzzySearcher, folder: &Arc<AppFolder>,
+) {
+    let mut plan_changes = folder.get_plan_changes().blocking_write();
+
+    ui.horizontal(|ui| {
+        if ui.button(tr("Clear changes")).clicked() {
+            plan_changes.clear();
+        }
+    });
+
+    render_search_bar(ui, searcher);
+
+    if plan_changes.is_empty() {
+        ui.heading(tr("No changes since last refresh"));
+        return;
+    }
+
+    let display = get_action_display();
+    let layout = egui::Layout::top_down(egui::Align::Min).with_cross_justify(true);
+    ui.with_layout(layout, |ui| {
+        let cell_layout = egui::Layout::top_down(egui::Align::Min).with_cross_justify(true);
+        let row_height = 18.0;
+        TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .cell_layout(cell_layout)
+            .column(Column::auto().resizable(true).clip(true))
+            .column(Column::auto().resizable(true).clip(true))
+            .column(Column::remainder().resizable(false).clip(true))
+            .header(row_height, |mut header| {
+                header.col(|ui| { ui.strong(tr("Source")); });
+                header.col(|ui| { ui.strong(tr("Change")); });
+                header.col(|ui| { ui.strong(tr("Destination")); });
+            })
+            .body(|mut body| {
+                for change in plan_changes.iter() {
+                    if !searcher.search(change.src.as_str()) {
+                        continue;
+                    }
+
+                    body.row(row_height, |mut row| {
+                        row.col(|ui| {
+                            ui.label(change.src.as_str());
+                        });
+                        row.col(|ui| {
+                            match &change.kind {
+                                PlanChangeKind::Added => {
+                                    ui.colored_label(egui::Color32::DARK_GREEN, trf("New file ({})", &[&tr(change.action.to_str())]));
+                                },
+                                PlanChangeKind::Removed => {
+                                    ui.colored_label(egui::Color32::DARK_RED, tr("Removed"));
+                                },
+                                PlanChangeKind::Action(old_action) => {
+                                    ui.horizontal(|ui| {
+                                        ui.spacing_mut().item_spacing.x = 0.0;
+                                        ui.label(display.get_label(*old_action, tr(old_action.to_str())));
+                                        ui.label(" → ");
+                                        ui.label(display.get_label(change.action, tr(change.action.to_str())));
+                                    });
+                                },
+                                PlanChangeKind::Destination(old_dest) => {
+                                    ui.label(tr("Destination changed")).on_hover_ui(|ui| {
+                                        ui.label(trf("Previous: {}", &[old_dest]));
+                                    });
+                                },
+                            };
+                        });
+                        row.col(|ui| {
+                            ui.label(change.dest.as_str());
+                        });
+                    });
+                }
+            });
+    });
+}
@@ -4,7 +4,11 @@ use app::app_folder::AppFolder;
 use egui;
 use egui_extras::{TableBuilder, Column};
 use crate::clipped_selectable::ClippedSelectableLabel;
-use crate::app_file_actions::{check_file_shortcuts, render_file_context_menu};
+use crate::app_file_actions::{
+    check_file_double_click, check_file_shortcuts, clear_file_selection, is_file_selected, render_file_context_menu, select_file,
+};
+use crate::helpers::set_accessible_toggle;
+use crate::i18n::{tr, trf};
 
 pub fn render_files_conflicts_list(
     ui: &mut egui::Ui, 
@@ -14,6 +18,7 @@ pub fn render_files_conflicts_list(
     let mut files = folder.get_mut_files_blocking(); 
     let is_not_busy = folder.get_busy_lock().try_lock().is_ok();
     let selected_descriptor = *folder.get_selected_descriptor().blocking_read();
+    let selected_src = folder.get_selected_src().blocking_read().clone();
     
     // link the column widths across all of the tables
     let mut column_widths: Option<[f32;3]> = None;
@@ -66,8 +71,8 @@ pub fn render_files_conflicts_list(
             table
                 .header(row_height, |mut header| {
                     header.col(|_| {});
-                    header.col(|ui| { ui.strong("Source"); });
-                    header.col(|ui| { ui.strong("Destination"); });
+                    header.col(|ui| { ui.strong(tr("Source")); });
+                    header.col(|ui| { ui.strong(tr("Destination")); });
                 })
                 .body(|mut body| {
                     let mut render_entry = |index: usize| {
@@ -76,10 +81,12 @@ pub fn render_files_conflicts_list(
                         let mut current_column_widths: [f32;3] = [0.0,0.0,0.0];
                         body.row(row_height, |mut row| {
                             row.col(|ui| {
-                                if action == Action::Rename || action == Action::Delete {
+                                if action.is_move() || action == Action::Delete {
                                     ui.add_enabled_ui(is_not_busy, |ui| {
                                         let mut is_enabled = file.get_is_enabled();
-                                        if ui.checkbox(&mut is_enabled, "").clicked() {
+                                        let res = ui.checkbox(&mut is_enabled, "");
+                                        set_accessible_toggle(&res, trf("Enable {}", &[&file.get_src()]).as_str(), is_enabled);
+                                        if res.clicked() {
                                             file.set_is_enabled(is_enabled);
                                         }
                                     });
@@ -88,27 +95,28 @@ pub fn render_files_conflicts_list(
                             });
                             row.col(|ui| {
                                 let descriptor = file.get_src_descriptor();
-                                let is_selected = descriptor.is_some() && *descriptor == selected_descriptor;
+                                let is_selected = is_file_selected(descriptor, file.get_src(), &selected_descriptor, selected_src.as_deref());
                                 let src = file.get_src();
                                 let elem = ClippedSelectableLabel::new(is_selected, src);
                                 let res = ui.add(elem);
                                 if res.clicked() {
                                     if is_selected {
-                                        *folder.get_selected_descriptor().blocking_write() = None;
+                                        clear_file_selection(folder);
                                     } else {
-                                        *folder.get_selected_descriptor().blocking_write() = *descriptor;
+                                        select_file(folder, descriptor, file.get_src());
                                     }
                                 }
-                                if is_not_busy && res.hovered() {
-                                    check_file_shortcuts(ui, &mut file);
+                                check_file_double_click(&res, folder, &file);
+                                if is_not_busy && (res.hovered() || res.has_focus()) {
+                                    check_file_shortcuts(ui, folder, &mut file);
                                 }
                                 res.context_menu(|ui| {
-                                    render_file_context_menu(ui, folder.get_folder_path(), &mut file, is_not_busy);
+                                    render_file_context_menu(ui, folder, &mut file, is_not_busy);
                                 });
                                 current_column_widths[1] = ui.available_width();
                             });
                             row.col(|ui| {
-                                if action == Action::Rename {
+                                if action.is_move() {
                                     ui.add_enabled_ui(is_not_busy, |ui| {
                                         let mut dest_edit_buffer = file.get_dest().to_string();
                                         let elem = egui::TextEdit::singleline(&mut dest_edit_buffer);
@@ -140,6 +148,6 @@ pub fn render_files_conflicts_list(
     }
 
     if total_conflicts == 0 {
-        ui.heading("No conflicts");
+        ui.heading(tr("No conflicts"));
     }
 }
@@ -1,87 +1,139 @@
 use std::sync::Arc;
-use app::file_intent::Action;
+use app::app_file::MutableAppFile;
+use app::file_intent::{Action, DeleteReason};
 use app::app_folder::AppFolder;
+use app::execution_outcomes::ExecutionOutcome;
+use app::tvdb_cache::EpisodeKey;
 use egui;
+use crate::file_filters::FileFilters;
 use crate::fuzzy_search::{FuzzySearcher, render_search_bar};
 use crate::clipped_selectable::ClippedSelectableLabel;
-use crate::app_file_actions::{check_file_shortcuts, render_file_context_menu};
+use crate::app_file_actions::{
+    check_file_double_click, check_file_shortcuts, clear_file_selection, is_file_selected, render_execution_outcome,
+    render_file_context_menu, select_file,
+};
+use crate::helpers::{format_file_size, set_accessible_toggle};
+use crate::i18n::{tr, trf};
+
+fn render_delete_entry(
+    ui: &mut egui::Ui,
+    file: &mut MutableAppFile<'_>, folder: &Arc<AppFolder>,
+    is_not_busy: bool, selected_descriptor: Option<EpisodeKey>, selected_src: Option<&str>, outcome: Option<&ExecutionOutcome>,
+) {
+    ui.horizontal(|ui| {
+        let mut is_enabled = file.get_is_enabled();
+        ui.add_enabled_ui(is_not_busy, |ui| {
+            let res = ui.checkbox(&mut is_enabled, "");
+            set_accessible_toggle(&res, trf("Enable {}", &[&file.get_src()]).as_str(), is_enabled);
+            if res.clicked() {
+                file.set_is_enabled(is_enabled);
+            }
+        });
+        ui.label(format_file_size(file.get_size()));
+        render_execution_outcome(ui, outcome);
+
+        let layout = egui::Layout::top_down(egui::Align::Min).with_cross_justify(true);
+        ui.with_layout(layout, |ui| {
+            let src = file.get_src();
+            let descriptor = file.get_src_descriptor();
+            let is_selected = is_file_selected(descriptor, file.get_src(), &selected_descriptor, selected_src);
+            let elem = ClippedSelectableLabel::new(is_selected, src);
+            let res = ui.add(elem);
+            if res.clicked() {
+                if is_selected {
+                    clear_file_selection(folder);
+                } else {
+                    select_file(folder, descriptor, file.get_src());
+                }
+            }
+            check_file_double_click(&res, folder, file);
+            if is_not_busy && (res.hovered() || res.has_focus()) {
+                check_file_shortcuts(ui, folder, file);
+            }
+            res.context_menu(|ui| {
+                render_file_context_menu(ui, folder, file, is_not_busy);
+            });
+        });
+    });
+}
 
 pub fn render_files_delete_list(
     ui: &mut egui::Ui, 
-    searcher: &mut FuzzySearcher, folder: &Arc<AppFolder>,
+    searcher: &mut FuzzySearcher, filters: &mut FileFilters, folder: &Arc<AppFolder>,
 ) {
     let file_tracker = folder.get_file_tracker().blocking_read();
     let is_not_busy = folder.get_busy_lock().try_lock().is_ok();
     let selected_descriptor = *folder.get_selected_descriptor().blocking_read();
+    let selected_src = folder.get_selected_src().blocking_read().clone();
 
     let mut is_select_all = false;
     let mut is_deselect_all = false;
     ui.add_enabled_ui(is_not_busy, |ui| {
         ui.horizontal(|ui| {
-            is_select_all = ui.button("Select all").clicked();
-            is_deselect_all = ui.button("Deselect all").clicked();
+            is_select_all = ui.button(tr("Select all")).clicked();
+            is_deselect_all = ui.button(tr("Deselect all")).clicked();
         });
     });
 
     render_search_bar(ui, searcher);
 
     let mut files = folder.get_mut_files_blocking(); 
+    let outcomes = folder.get_execution_outcomes().blocking_read();
     if file_tracker.get_action_count()[Action::Delete] == 0 {
-        ui.heading(format!("No {}s", Action::Delete.to_str().to_lowercase()));
+        ui.heading(trf("No files with action {}", &[&tr(Action::Delete.to_str())]));
         return;
     }
 
+    // Files which were manually marked for deletion have no reason
+    let filter_rules = folder.get_filter_rules().blocking_read().clone();
+    let groups: Vec<Option<DeleteReason>> = DeleteReason::iterator()
+        .map(|reason| Some(*reason))
+        .chain(std::iter::once(None))
+        .collect();
+
     egui::ScrollArea::vertical().show(ui, |ui| {
         let layout = egui::Layout::top_down(egui::Align::Min).with_cross_justify(true);
         ui.with_layout(layout, |ui| {
-            let mut files_iter = files.to_iter();
-            while let Some(mut file) = files_iter.next_mut() {
-                let action = file.get_action();
-                if action != Action::Delete {
-                    continue;
+            for reason in groups {
+                let mut total_files = 0;
+                let mut files_iter = files.to_iter();
+                while let Some(file) = files_iter.next_mut() {
+                    if file.get_action() == Action::Delete && file.get_delete_reason() == reason {
+                        total_files += 1;
+                    }
                 }
-
-                if !searcher.search(file.get_src()) {
+                if total_files == 0 {
                     continue;
                 }
 
-                ui.horizontal(|ui| {
-                    let mut is_enabled = file.get_is_enabled();
-                    ui.add_enabled_ui(is_not_busy, |ui| {
-                        if ui.checkbox(&mut is_enabled, "").clicked() {
-                            file.set_is_enabled(is_enabled);
-                        }
-                    });
-                    if is_select_all {
-                        file.set_is_enabled(true);
-                    }
-                    if is_deselect_all {
-                        file.set_is_enabled(false);
-                    }
+                let policy = filter_rules.get_delete_policy(reason);
+                let reason_label = reason.map(|reason| reason.to_str()).unwrap_or("Manual");
+                let header = format!("{} ({}) - {}", tr(reason_label), total_files, tr(policy.to_str()));
+                egui::CollapsingHeader::new(header)
+                    .id_source(reason_label)
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        let mut files_iter = files.to_iter();
+                        while let Some(mut file) = files_iter.next_mut() {
+                            if file.get_action() != Action::Delete || file.get_delete_reason() != reason {
+                                continue;
+                            }
 
-                    let layout = egui::Layout::top_down(egui::Align::Min).with_cross_justify(true);
-                    ui.with_layout(layout, |ui| {
-                        let src = file.get_src();
-                        let descriptor = file.get_src_descriptor();
-                        let is_selected = descriptor.is_some() && *descriptor == selected_descriptor;
-                        let elem = ClippedSelectableLabel::new(is_selected, src);
-                        let res = ui.add(elem);
-                        if res.clicked() {
-                            if is_selected {
-                                *folder.get_selected_descriptor().blocking_write() = None;
-                            } else {
-                                *folder.get_selected_descriptor().blocking_write() = *descriptor;
+                            if !searcher.search(file.get_src()) || !filters.is_match(file.get_src(), file.get_src_descriptor().as_ref()) {
+                                continue;
                             }
+
+                            if is_select_all {
+                                file.set_is_enabled(true);
+                            }
+                            if is_deselect_all {
+                                file.set_is_enabled(false);
+                            }
+
+                            let outcome = outcomes.get(file.get_src());
+                            render_delete_entry(ui, &mut file, folder, is_not_busy, selected_descriptor, selected_src.as_deref(), outcome);
                         }
-                        if is_not_busy && res.hovered() {
-                            check_file_shortcuts(ui, &mut file);
-                        }
-                        res.context_menu(|ui| {
-                            render_file_context_menu(ui, folder.get_folder_path(), &mut file, is_not_busy);
-                        });
                     });
-
-                });
             }
         });
     });
@@ -1,10 +1,12 @@
 use app::app_folder::AppFolder;
-use app::tvdb_cache::EpisodeKey;
 use egui;
 use egui_extras::{Column, TableBuilder};
 use std::sync::Arc;
 use crate::fuzzy_search::{FuzzySearcher, render_search_bar};
+use crate::app_file_actions::{clear_file_selection, select_episode};
 use crate::clipped_selectable::ClippedSelectableLabel;
+use crate::i18n::tr;
+use crate::icons::{Icon, IconTone, render_icon};
 
 pub fn render_episode_cache_list(ui: &mut egui::Ui, searcher: &mut FuzzySearcher, folder: &Arc<AppFolder>) {
     render_search_bar(ui, searcher);
@@ -13,20 +15,27 @@ pub fn render_episode_cache_list(ui: &mut egui::Ui, searcher: &mut FuzzySearcher
     let cache = match cache.as_ref() {
         Some(cache) => cache,
         None => {
-            ui.label("No cache loaded");
+            ui.label(tr("No cache loaded"));
             return;
         },
     };
 
     let episodes = &cache.episodes;
     if episodes.is_empty() {
-        ui.label("No episodes available");
+        ui.label(tr("No episodes available"));
         return;
     }
     
     // Create a string that we can search for each episode
     let mut episode_name = String::new();
     let selected_descriptor = *folder.get_selected_descriptor().blocking_read();
+    let (season_scope, ordering) = {
+        let settings = folder.get_settings().blocking_read();
+        (settings.season_scope.clone(), settings.get_ordering())
+    };
+    // the column is only shown for series on the media server
+    let watch_state = folder.get_watch_state().blocking_read();
+    let watch_state = watch_state.as_ref();
     let row_height = 18.0;
     let cell_layout = egui::Layout::top_down(egui::Align::Min).with_cross_justify(true);
     TableBuilder::new(ui)
@@ -35,15 +44,27 @@ pub fn render_episode_cache_list(ui: &mut egui::Ui, searcher: &mut FuzzySearcher
         .cell_layout(cell_layout)
         .column(Column::remainder().resizable(true).clip(true))
         .column(Column::auto().resizable(false))
+        .columns(Column::auto().resizable(false), watch_state.is_some() as usize)
         .header(row_height, |mut header| {
-            header.col(|ui| { ui.strong("Name"); });
-            header.col(|ui| { ui.strong("First Aired"); });
+            header.col(|ui| { ui.strong(tr("Name")); });
+            header.col(|ui| { ui.strong(tr("First Aired")); });
+            if watch_state.is_some() {
+                header.col(|ui| { ui.strong(tr("Watched")); });
+            }
         })
         .body(|mut body| {
-            for entry in episodes {
+            for (index, entry) in episodes.iter().enumerate() {
                 use std::fmt::Write;
+                // episodes without a number in the folder's ordering can't be matched to a file
+                let descriptor = match cache.get_episode_key(index, ordering) {
+                    Some(descriptor) => descriptor,
+                    None => continue,
+                };
+                if !season_scope.is_empty() && !season_scope.contains(&descriptor.season) {
+                    continue;
+                }
                 episode_name.clear();
-                let _ = write!(episode_name, "S{:02}E{:02}", entry.season, entry.episode);
+                let _ = write!(episode_name, "S{:02}E{:02}", descriptor.season, descriptor.episode);
                 if let Some(name) = entry.name.as_deref() {
                     let _ = write!(episode_name, " {}", name);
                 }
@@ -55,23 +76,29 @@ pub fn render_episode_cache_list(ui: &mut egui::Ui, searcher: &mut FuzzySearcher
                     row.col(|ui| { 
                         let layout = egui::Layout::top_down(egui::Align::Min).with_cross_justify(true);
                         ui.with_layout(layout, |ui| {
-                            let descriptor = EpisodeKey { season: entry.season, episode: entry.episode };
                             let is_selected = Some(descriptor) == selected_descriptor;
                             let elem = ClippedSelectableLabel::new(is_selected, episode_name.as_str());
                             let res = ui.add(elem);
                             if res.clicked() {
                                 if is_selected {
-                                    *folder.get_selected_descriptor().blocking_write() = None;
+                                    clear_file_selection(folder);
                                 } else {
-                                    *folder.get_selected_descriptor().blocking_write() = Some(descriptor);
+                                    select_episode(folder, descriptor);
                                 }
                             }
                         });
                     });
                     row.col(|ui| {
-                        let label = entry.first_aired.as_deref().unwrap_or("Unknown");
+                        let label = entry.first_aired.as_deref().unwrap_or(tr("Unknown"));
                         ui.label(label);
                     });
+                    if let Some(watch_state) = watch_state {
+                        row.col(|ui| match watch_state.is_watched(&descriptor) {
+                            Some(true) => { render_icon(ui, Icon::Watched, IconTone::Success).on_hover_text(tr("Watched")); },
+                            Some(false) => { render_icon(ui, Icon::Watched, IconTone::Inactive).on_hover_text(tr("Not watched")); },
+                            None => { ui.weak("-").on_hover_text(tr("Not on the media server")); },
+                        });
+                    }
                 });
             }
         });
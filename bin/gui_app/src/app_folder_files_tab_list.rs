@@ -2,30 +2,34 @@ use app::app_folder::AppFolder;
 use app::file_intent::Action;
 use std::sync::Arc;
 
-use crate::app_folder_basic_list::render_files_basic_list;
+use crate::action_display::get_action_display;
+use crate::app_folder_basic_list::{FileTable, render_files_basic_list};
+use crate::app_folder_changes_list::render_files_changes_list;
 use crate::app_folder_conflict_list::render_files_conflicts_list;
 use crate::app_folder_delete_list::render_files_delete_list;
 use crate::app_folder_rename_list::render_files_rename_list;
+use crate::file_filters::{FileFilters, render_filter_chips};
 use crate::fuzzy_search::FuzzySearcher;
+use crate::helpers::format_file_size;
+use crate::i18n::{tr, trf};
 
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub enum FileTab {
     FileAction(Action),
     Conflicts,
-}
-
-lazy_static::lazy_static! {
-    static ref FILE_TABS: [FileTab;6] = [
-        FileTab::FileAction(Action::Complete), 
-        FileTab::FileAction(Action::Rename), 
-        FileTab::FileAction(Action::Delete), 
-        FileTab::FileAction(Action::Ignore), 
-        FileTab::FileAction(Action::Whitelist), 
-        FileTab::Conflicts
-    ];
+    Changes,
 }
 
 fn render_files_tab_bar(ui: &mut egui::Ui, selected_tab: &mut FileTab, folder: &Arc<AppFolder>) {
+    let display = get_action_display();
+    let mut tabs: Vec<FileTab> = display.get_visible_actions().into_iter().map(FileTab::FileAction).collect();
+    tabs.push(FileTab::Conflicts);
+    tabs.push(FileTab::Changes);
+    // the selected action may have just been hidden
+    if !tabs.contains(selected_tab) {
+        *selected_tab = tabs[0];
+    }
+
     let file_tracker = folder.get_file_tracker().blocking_read();
     let total_conflicts = {
         let mut total_conflicts = 0;
@@ -45,20 +49,27 @@ fn render_files_tab_bar(ui: &mut egui::Ui, selected_tab: &mut FileTab, folder: &
         total_conflicts
     };
 
+    let total_changes = folder.get_plan_changes().blocking_read().len();
+
     ui.horizontal(|ui| {
         let old_selected_tab = *selected_tab;
-        for tab in FILE_TABS.iter() {
+        for tab in tabs.iter() {
             let tab = *tab;
             let label = match tab {
-                FileTab::Conflicts => format!("Conflicts {}", total_conflicts),
+                FileTab::Conflicts => egui::RichText::new(format!("{} {}", tr("Conflicts"), total_conflicts)),
+                FileTab::Changes => egui::RichText::new(format!("{} {}", tr("Changes"), total_changes)),
                 FileTab::FileAction(action) => {
                     let count = file_tracker.get_action_count()[action];
-                    format!("{} {}", action.to_str(), count)
+                    display.get_label(action, format!("{} {}", tr(action.to_str()), count))
                 },
             };
 
             let is_selected = tab == old_selected_tab;
-            if ui.selectable_label(is_selected,label).clicked() {
+            let mut res = ui.selectable_label(is_selected,label);
+            if let FileTab::FileAction(action) = tab {
+                res = res.on_hover_text(format_file_size(file_tracker.get_action_size()[action]));
+            }
+            if res.clicked() {
                 *selected_tab = tab;
             }
         }
@@ -67,28 +78,38 @@ fn render_files_tab_bar(ui: &mut egui::Ui, selected_tab: &mut FileTab, folder: &
 
 pub fn render_files_tab_list(
     ui: &mut egui::Ui,
-    selected_tab: &mut FileTab, searcher: &mut FuzzySearcher, folder: &Arc<AppFolder>,
+    selected_tab: &mut FileTab, file_table: &mut FileTable, searcher: &mut FuzzySearcher, filters: &mut FileFilters,
+    series_search_request: &mut Option<String>, folder: &Arc<AppFolder>,
 ) {
     render_files_tab_bar(ui, selected_tab, folder);
+    if let FileTab::FileAction(action) = selected_tab {
+        let file_tracker = folder.get_file_tracker().blocking_read();
+        let count = file_tracker.get_action_count()[*action];
+        let size = file_tracker.get_action_size()[*action];
+        ui.label(trf("{}: {} files, {}", &[&tr(action.to_str()), &count, &format_file_size(size)]));
+        render_filter_chips(ui, filters, folder);
+    }
     ui.separator();
     
     let id = match selected_tab {
         FileTab::FileAction(action) => format!("file_list_{}", action.to_str().to_lowercase()),
         FileTab::Conflicts => "file_list_conflicts".to_string(),
+        FileTab::Changes => "file_list_changes".to_string(),
     };
     
     ui.push_id(id, |ui| {
         match selected_tab {
             FileTab::FileAction(action) => match action {
-                Action::Rename => render_files_rename_list(ui, searcher, folder),
-                Action::Delete => render_files_delete_list(ui, searcher, folder),
-                _ => render_files_basic_list(ui, searcher, *action, folder),
+                Action::Rename => render_files_rename_list(ui, searcher, filters, series_search_request, folder),
+                Action::Delete => render_files_delete_list(ui, searcher, filters, folder),
+                _ => render_files_basic_list(ui, file_table, searcher, filters, *action, folder),
             },
             FileTab::Conflicts => {
                 egui::ScrollArea::vertical().show(ui, |ui| {
                     render_files_conflicts_list(ui, folder);
                 });
             },
+            FileTab::Changes => render_files_changes_list(ui, searcher, folder),
         };
     });
 
@@ -1,119 +1,240 @@
+use std::path::Path;
 use std::sync::Arc;
 use app::file_intent::Action;
 use app::app_folder::AppFolder;
+use app::natural_sort::sort_natural_by;
 use egui;
 use egui_extras::{TableBuilder, Column};
+use app::file_intent::IntentWarning;
+use crate::file_filters::FileFilters;
 use crate::fuzzy_search::{FuzzySearcher, render_search_bar};
 use crate::clipped_selectable::ClippedSelectableLabel;
-use crate::app_file_actions::{check_file_shortcuts, render_file_context_menu};
+use crate::app_file_actions::{
+    check_file_double_click, check_file_shortcuts, clear_file_selection, is_file_selected, render_execution_outcome,
+    render_file_context_menu, select_file,
+};
+use crate::helpers::set_accessible_toggle;
+use crate::i18n::{tr, trf};
+use crate::icons::{Icon, IconTone, render_icon};
+
+// Renames are grouped by the folder they end up in, e.g. "Season 02"
+fn get_dest_folder(dest: &str) -> String {
+    Path::new(dest).parent().map(|parent| parent.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+fn render_mismatched_series_warnings(
+    ui: &mut egui::Ui,
+    series_search_request: &mut Option<String>, folder: &Arc<AppFolder>,
+) {
+    // Group files by the series their filename suggests
+    let mut suggested_series: Vec<(String, usize)> = Vec::new();
+    let mut total_outdated_titles = 0;
+    let mut total_out_of_bounds = 0;
+    let files = folder.get_files_blocking();
+    for file in files.to_iter() {
+        if file.get_action() != Action::Rename {
+            continue;
+        }
+        for warning in file.get_warnings() {
+            match warning {
+                IntentWarning::MismatchedSeries(name) => {
+                    match suggested_series.iter_mut().find(|(other, _)| other == name) {
+                        Some((_, count)) => *count += 1,
+                        None => suggested_series.push((name.clone(), 1)),
+                    }
+                },
+                IntentWarning::OutdatedTitle(_) => total_outdated_titles += 1,
+                IntentWarning::EpisodeOutOfBounds { .. } => total_out_of_bounds += 1,
+            }
+        }
+    }
+
+    for (name, count) in suggested_series {
+        ui.horizontal(|ui| {
+            render_icon(ui, Icon::Warning, IconTone::Highlight);
+            let label = trf("{} file(s) appear to belong to '{}'", &[&count, &name]);
+            ui.colored_label(IconTone::Highlight.get_color(ui.visuals()), label);
+            if ui.button(tr("Search series")).clicked() {
+                *series_search_request = Some(name);
+            }
+        });
+    }
+
+    if total_out_of_bounds > 0 {
+        ui.horizontal(|ui| {
+            render_icon(ui, Icon::Warning, IconTone::Highlight);
+            let label = trf("{} file(s) have episode numbers outside of the series, check for absolute numbering", &[&total_out_of_bounds]);
+            ui.colored_label(IconTone::Highlight.get_color(ui.visuals()), label);
+        });
+    }
+
+    if total_outdated_titles > 0 {
+        ui.horizontal(|ui| {
+            render_icon(ui, Icon::Warning, IconTone::Info);
+            let label = trf("{} file(s) have outdated episode titles", &[&total_outdated_titles]);
+            ui.colored_label(IconTone::Info.get_color(ui.visuals()), label);
+        });
+    }
+}
 
 pub fn render_files_rename_list(
     ui: &mut egui::Ui, 
-    searcher: &mut FuzzySearcher, folder: &Arc<AppFolder>,
+    searcher: &mut FuzzySearcher, filters: &mut FileFilters, series_search_request: &mut Option<String>,
+    folder: &Arc<AppFolder>,
 ) {
     let file_tracker = folder.get_file_tracker().blocking_read();
     let is_not_busy = folder.get_busy_lock().try_lock().is_ok();
     let selected_descriptor = *folder.get_selected_descriptor().blocking_read();
+    let selected_src = folder.get_selected_src().blocking_read().clone();
 
     let mut is_select_all = false;
     let mut is_deselect_all = false;
     ui.add_enabled_ui(is_not_busy, |ui| {
         ui.horizontal(|ui| {
-            is_select_all = ui.button("Select all").clicked();
-            is_deselect_all = ui.button("Deselect all").clicked();
+            is_select_all = ui.button(tr("Select all")).clicked();
+            is_deselect_all = ui.button(tr("Deselect all")).clicked();
         });
     });
 
+    render_mismatched_series_warnings(ui, series_search_request, folder);
     render_search_bar(ui, searcher);
 
     let mut files = folder.get_mut_files_blocking(); 
+    let outcomes = folder.get_execution_outcomes().blocking_read();
     if file_tracker.get_action_count()[Action::Rename] == 0 {
-        ui.heading("No renames");
+        ui.heading(tr("No renames"));
         return;
     }
    
-    let layout = egui::Layout::top_down(egui::Align::Min).with_cross_justify(true);
-    ui.with_layout(layout, |ui| {
-        let cell_layout = egui::Layout::top_down(egui::Align::Min).with_cross_justify(true);
-        let row_height = 18.0;
-        TableBuilder::new(ui)
-            .striped(true)
-            .resizable(true)
-            .cell_layout(cell_layout)
-            .column(Column::initial(0.0).resizable(false).clip(false))
-            .column(Column::auto().resizable(true).clip(true))
-            .column(Column::remainder().resizable(false).clip(true))
-            .header(row_height, |mut header| {
-                header.col(|_| {});
-                header.col(|ui| { ui.strong("Source"); });
-                header.col(|ui| { ui.strong("Destination"); });
-            })
-            .body(|mut body| {
-                let mut files_iter = files.to_iter();
-                while let Some(mut file) = files_iter.next_mut() {
-                    let action = file.get_action();
-                    if action != Action::Rename {
-                        continue;
-                    }
+    // Counted up front so the headers show how many renames land in each season folder
+    let mut groups: Vec<(String, usize)> = Vec::new();
+    {
+        let mut files_iter = files.to_iter();
+        while let Some(file) = files_iter.next_mut() {
+            if file.get_action() != Action::Rename {
+                continue;
+            }
+            if !searcher.search(file.get_src()) || !filters.is_match(file.get_src(), file.get_src_descriptor().as_ref()) {
+                continue;
+            }
+            let group = get_dest_folder(file.get_dest());
+            match groups.iter_mut().find(|(other, _)| *other == group) {
+                Some((_, count)) => *count += 1,
+                None => groups.push((group, 1)),
+            }
+        }
+    }
+    sort_natural_by(groups.as_mut_slice(), |(group, _)| group.as_str());
 
-                    if !searcher.search(file.get_src()) {
-                        continue;
-                    }
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        let layout = egui::Layout::top_down(egui::Align::Min).with_cross_justify(true);
+        ui.with_layout(layout, |ui| {
+            for (group, total_files) in groups {
+                let group_label = match group.is_empty() {
+                    true => tr("Folder root").to_string(),
+                    false => group.clone(),
+                };
+                egui::CollapsingHeader::new(format!("{} ({})", group_label, total_files))
+                    .id_source(("rename_group", group.as_str()))
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        let cell_layout = egui::Layout::top_down(egui::Align::Min).with_cross_justify(true);
+                        let row_height = 18.0;
+                        TableBuilder::new(ui)
+                            .striped(true)
+                            .resizable(true)
+                            .vscroll(false)
+                            .cell_layout(cell_layout)
+                            .column(Column::initial(0.0).resizable(false).clip(false))
+                            .column(Column::auto().resizable(true).clip(true))
+                            .column(Column::remainder().resizable(false).clip(true))
+                            .header(row_height, |mut header| {
+                                header.col(|_| {});
+                                header.col(|ui| { ui.strong(tr("Source")); });
+                                header.col(|ui| { ui.strong(tr("Destination")); });
+                            })
+                            .body(|mut body| {
+                                let mut files_iter = files.to_iter();
+                                while let Some(mut file) = files_iter.next_mut() {
+                                    let action = file.get_action();
+                                    if action != Action::Rename || get_dest_folder(file.get_dest()) != group {
+                                        continue;
+                                    }
 
-                    if is_select_all {
-                        file.set_is_enabled(true);
-                    }
-                    if is_deselect_all {
-                        file.set_is_enabled(false);
-                    }
+                                    if !searcher.search(file.get_src()) || !filters.is_match(file.get_src(), file.get_src_descriptor().as_ref()) {
+                                        continue;
+                                    }
 
-                    body.row(row_height, |mut row| {
-                        row.col(|ui| {
-                            ui.add_enabled_ui(is_not_busy, |ui| {
-                                let mut is_enabled = file.get_is_enabled();
-                                if ui.checkbox(&mut is_enabled, "").clicked() {
-                                    file.set_is_enabled(is_enabled);
-                                }
-                            });
-                        });
-                        row.col(|ui| {
-                            let descriptor = file.get_src_descriptor();
-                            let is_selected = descriptor.is_some() && *descriptor == selected_descriptor;
-                            let is_conflict = file.get_is_conflict();
-                            let src = file.get_src();
-                            let mut label = egui::RichText::new(src);
-                            if is_conflict {
-                                label = label.color(egui::Color32::DARK_RED)
-                            }
-                            let elem = ClippedSelectableLabel::new(is_selected, label);
-                            let res = ui.add(elem);
-                            if res.clicked() {
-                                if is_selected {
-                                    *folder.get_selected_descriptor().blocking_write() = None;
-                                } else {
-                                    *folder.get_selected_descriptor().blocking_write() = *descriptor;
-                                }
-                            }
-                            if is_not_busy && res.hovered() {
-                                check_file_shortcuts(ui, &mut file);
-                            }
-                            res.context_menu(|ui| {
-                                render_file_context_menu(ui, folder.get_folder_path(), &mut file, is_not_busy);
-                            });
-                        });
-                        row.col(|ui| {
-                            ui.add_enabled_ui(is_not_busy, |ui| {
-                                let mut dest_edit_buffer = file.get_dest().to_string();
-                                let elem = egui::TextEdit::singleline(&mut dest_edit_buffer);
-                                let res = ui.add_sized(ui.available_size(), elem);
-                                if res.changed() {
-                                    file.set_dest(dest_edit_buffer);
+                                    if is_select_all {
+                                        file.set_is_enabled(true);
+                                    }
+                                    if is_deselect_all {
+                                        file.set_is_enabled(false);
+                                    }
+
+                                    body.row(row_height, |mut row| {
+                                        row.col(|ui| {
+                                            ui.add_enabled_ui(is_not_busy, |ui| {
+                                                let mut is_enabled = file.get_is_enabled();
+                                                let res = ui.checkbox(&mut is_enabled, "");
+                                                set_accessible_toggle(&res, trf("Enable {}", &[&file.get_src()]).as_str(), is_enabled);
+                                                if res.clicked() {
+                                                    file.set_is_enabled(is_enabled);
+                                                }
+                                            });
+                                            render_execution_outcome(ui, outcomes.get(file.get_src()));
+                                        });
+                                        row.col(|ui| {
+                                            let descriptor = file.get_src_descriptor();
+                                            let is_selected = is_file_selected(descriptor, file.get_src(), &selected_descriptor, selected_src.as_deref());
+                                            let is_conflict = file.get_is_conflict();
+                                            let src = file.get_src();
+                                            let warnings = file.get_warnings();
+                                            let mut label = egui::RichText::new(src);
+                                            if is_conflict {
+                                                label = label.color(egui::Color32::DARK_RED)
+                                            } else if !warnings.is_empty() {
+                                                label = label.color(egui::Color32::from_rgb(200, 120, 0))
+                                            }
+                                            let elem = ClippedSelectableLabel::new(is_selected, label);
+                                            let mut res = ui.add(elem);
+                                            if !warnings.is_empty() {
+                                                res = res.on_hover_ui(|ui| {
+                                                    for warning in warnings {
+                                                        ui.label(warning.to_string());
+                                                    }
+                                                });
+                                            }
+                                            if res.clicked() {
+                                                if is_selected {
+                                                    clear_file_selection(folder);
+                                                } else {
+                                                    select_file(folder, descriptor, file.get_src());
+                                                }
+                                            }
+                                            check_file_double_click(&res, folder, &file);
+                                            if is_not_busy && (res.hovered() || res.has_focus()) {
+                                                check_file_shortcuts(ui, folder, &mut file);
+                                            }
+                                            res.context_menu(|ui| {
+                                                render_file_context_menu(ui, folder, &mut file, is_not_busy);
+                                            });
+                                        });
+                                        row.col(|ui| {
+                                            ui.add_enabled_ui(is_not_busy, |ui| {
+                                                let mut dest_edit_buffer = file.get_dest().to_string();
+                                                let elem = egui::TextEdit::singleline(&mut dest_edit_buffer);
+                                                let res = ui.add_sized(ui.available_size(), elem);
+                                                if res.changed() {
+                                                    file.set_dest(dest_edit_buffer);
+                                                }
+                                            });
+                                        });
+                                    });
                                 }
                             });
-                        });
                     });
-
-                }
-            });
+            }
+        });
     });
 }
@@ -1,32 +1,82 @@
 use app::app::App;
-use app::app_folder::FolderStatus;
+use app::app_folder::{AppFolder, FolderStatus, SeriesProgress};
+use app::folder_settings::FolderReview;
+use app::file_descriptor::get_folder_series_key;
+use app::file_intent::get_series_folder_name;
+use app::folder_watcher::PendingAutoRefresh;
+use app::other_media::FolderExclusion;
+use app::tvdb_cache::SeriesStatus;
 use egui;
+use tvdb::api::AccountUsage;
 use enum_map;
 use open as cross_open;
 use std::sync::Arc;
 use tokio;
-use crate::fuzzy_search::{FuzzySearcher, render_search_bar};
+use crate::fuzzy_search::{BackgroundSearcher, render_background_search_bar};
 use crate::clipped_selectable::ClippedSelectableLabel;
+use crate::helpers::{format_duration, set_accessible_label, set_accessible_toggle};
+use crate::i18n::{tr, trf};
+use crate::keybindings::{FolderClickAction, get_keybindings};
+use crate::merge_confirmation::request_folder_merge;
+use crate::icons::{Icon, IconTone, create_icon_image, get_icon_size, render_icon, render_icon_toggle};
+use crate::progress_bar::render_operation_progress;
+use crate::recent_folders::{push_recent_folder, render_recent_folders};
+use crate::execute_confirmation::{request_folder_execution, request_reviewed_execution, request_title_fixes};
 
-lazy_static::lazy_static! {
-    static ref FOLDER_STATUS_ICONS: enum_map::EnumMap<FolderStatus, egui::RichText> = enum_map::enum_map! {
-        FolderStatus::Unknown => egui::RichText::new("？").strong().color(egui::Color32::DARK_RED),
-        FolderStatus::Empty => egui::RichText::new("O").strong().color(egui::Color32::GRAY),
-        FolderStatus::Pending => egui::RichText::new("🖹").strong().color(egui::Color32::DARK_BLUE),
-        FolderStatus::Done => egui::RichText::new("✔").strong().color(egui::Color32::DARK_GREEN),
-    };
+fn get_folder_status_icon(status: FolderStatus) -> (Icon, IconTone) {
+    match status {
+        FolderStatus::Unknown => (Icon::Unknown, IconTone::Error),
+        FolderStatus::Empty => (Icon::Empty, IconTone::Inactive),
+        FolderStatus::Pending => (Icon::Pending, IconTone::Info),
+        FolderStatus::Done => (Icon::Done, IconTone::Success),
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum FolderSortOrder {
+    Name,
+    // Least complete first, folders without aired episodes last
+    Completion,
+}
+
+impl FolderSortOrder {
+    fn to_str(self) -> &'static str {
+        match self {
+            FolderSortOrder::Name => "Name",
+            FolderSortOrder::Completion => "Completion",
+        }
+    }
 }
 
 pub struct GuiAppFoldersList {
-    searcher: FuzzySearcher,
+    searcher: BackgroundSearcher,
     filters: enum_map::EnumMap<FolderStatus, bool>,
+    series_filters: enum_map::EnumMap<SeriesStatus, bool>,
+    is_missing_recent_only: bool,
+    is_show_reviewed: bool,
+    is_show_unreviewed: bool,
+    is_pinned_only: bool,
+    sort_order: FolderSortOrder,
+    pub(crate) detach_request: Option<usize>,
+    // Opens the series search for the folder once it is selected
+    pub(crate) rebind_request: Option<String>,
+    pub(crate) is_focus_selected: bool,
 }
 
 impl GuiAppFoldersList {
     pub fn new() -> Self {
         Self {
-            searcher: FuzzySearcher::new(),
+            searcher: BackgroundSearcher::new(),
             filters: enum_map::enum_map! { _ => true },
+            series_filters: enum_map::enum_map! { _ => true },
+            is_missing_recent_only: false,
+            is_show_reviewed: true,
+            is_show_unreviewed: true,
+            is_pinned_only: false,
+            sort_order: FolderSortOrder::Name,
+            detach_request: None,
+            rebind_request: None,
+            is_focus_selected: false,
         }
     }
 }
@@ -38,29 +88,269 @@ impl Default for GuiAppFoldersList {
 }
 
 fn render_folder_status(ui: &mut egui::Ui, status: FolderStatus, is_busy: bool) {
-    let height = ui.text_style_height(&egui::TextStyle::Monospace);
-    let size = egui::vec2(height, height);
     if !is_busy {
-        let icon = FOLDER_STATUS_ICONS[status].clone().size(height);
-        let elem = egui::Label::new(icon);
-        ui.add_sized(size, elem);
+        let (icon, tone) = get_folder_status_icon(status);
+        let res = render_icon(ui, icon, tone);
+        set_accessible_label(&res, egui::WidgetType::Label, tr(status.to_str()));
     } else {
-        let icon = egui::RichText::new("↻").strong().size(height);
-        let elem = egui::Label::new(icon);
         // The spinner forces a ui refresh which could be unnecessarily expensive
         // But it looks cool so I'm keeping it
         // let elem = egui::Spinner::new();
-        ui.add_sized(size, elem);
+        let res = render_icon(ui, Icon::Busy, IconTone::Normal);
+        set_accessible_label(&res, egui::WidgetType::Label, tr("Busy"));
+    }
+}
+
+fn render_exclusion_badge(ui: &mut egui::Ui, exclusion: FolderExclusion) {
+    let label = match exclusion {
+        FolderExclusion::Manual => tr("Marked as not a TV series").to_string(),
+        FolderExclusion::Detected(media) => trf("Detected as {}", &[&tr(media.to_str())]),
+    };
+    let res = render_icon(ui, Icon::Empty, IconTone::Inactive);
+    set_accessible_label(&res, egui::WidgetType::Label, label.as_str());
+    res.on_hover_text(label);
+}
+
+fn open_path(path: &str) {
+    tokio::spawn({
+        let path = path.to_string();
+        async move {
+            cross_open::that(path)
+        }
+    });
+}
+
+fn render_folder_context_menu(ui: &mut egui::Ui, app: &Arc<App>, gui: &mut GuiAppFoldersList, index: usize, folder: &Arc<AppFolder>) {
+    let is_not_busy = folder.get_busy_lock().try_lock().is_ok();
+    let series_name = folder.get_cache().blocking_read().as_ref().map(|cache| cache.series.name.clone());
+    let is_cache_loaded = series_name.is_some();
+
+    let image = create_icon_image(ui, Icon::NewWindow, IconTone::Normal);
+    if ui.add(egui::Button::image_and_text(image, tr("Open in new window"))).clicked() {
+        gui.detach_request = Some(index);
+        ui.close_menu();
+    }
+    let image = create_icon_image(ui, Icon::Folder, IconTone::Normal);
+    if ui.add(egui::Button::image_and_text(image, tr("Open folder"))).clicked() {
+        open_path(folder.get_folder_path());
+        ui.close_menu();
+    }
+    let image = create_icon_image(ui, Icon::Copy, IconTone::Normal);
+    if ui.add(egui::Button::image_and_text(image, tr("Copy folder path"))).clicked() {
+        let path = std::path::Path::new(folder.get_folder_path());
+        let path = std::path::absolute(path).unwrap_or(path.to_path_buf());
+        ui.output_mut(|output| output.copied_text = path.to_string_lossy().to_string());
+        ui.close_menu();
+    }
+
+    ui.separator();
+
+    let res = ui.add_enabled(is_cache_loaded && is_not_busy, egui::Button::new(tr("Update file intents")));
+    if res.clicked() {
+        tokio::spawn({
+            let folder = folder.clone();
+            async move {
+                folder.update_file_intents().await
+            }
+        });
+        ui.close_menu();
+    }
+    res.on_disabled_hover_ui(|ui| {
+        if !is_cache_loaded  { ui.label(tr("Cache is unloaded")); }
+        else if !is_not_busy { ui.label(tr("Folder is busy")); }
+    });
+
+    let res = ui.add_enabled(is_not_busy, egui::Button::new(tr("Execute changes")));
+    if res.clicked() {
+        request_folder_execution(folder);
+        ui.close_menu();
+    }
+    res.on_disabled_hover_ui(|ui| {
+        ui.label(tr("Folder is busy"));
+    });
+
+    let exclusion = *folder.get_exclusion().blocking_read();
+    let (label, is_series) = match exclusion {
+        Some(FolderExclusion::Manual) => (tr("Detect if TV series"), None),
+        Some(FolderExclusion::Detected(_)) => (tr("Treat as TV series"), Some(true)),
+        None => (tr("Not a TV series"), Some(false)),
+    };
+    let res = ui.add_enabled(is_not_busy, egui::Button::new(label));
+    if res.clicked() {
+        tokio::spawn({
+            let folder = folder.clone();
+            async move {
+                folder.set_is_series(is_series).await
+            }
+        });
+        ui.close_menu();
+    }
+    res.on_disabled_hover_ui(|ui| {
+        ui.label(tr("Folder is busy"));
+    });
+
+    let is_reviewed = folder.get_settings().blocking_read().review.is_some();
+    let label = match is_reviewed {
+        true => tr("Clear review"),
+        false => tr("Mark as reviewed"),
+    };
+    if ui.button(label).clicked() {
+        tokio::spawn({
+            let folder = folder.clone();
+            async move {
+                folder.set_reviewed(!is_reviewed).await
+            }
+        });
+        ui.close_menu();
+    }
+
+    let is_pinned = folder.get_settings().blocking_read().is_pinned;
+    let label = match is_pinned {
+        true => tr("Unpin"),
+        false => tr("Pin"),
+    };
+    let res = ui.button(label);
+    if res.clicked() {
+        tokio::spawn({
+            let folder = folder.clone();
+            async move {
+                folder.set_pinned(!is_pinned).await
+            }
+        });
+        ui.close_menu();
+    }
+    res.on_hover_text(tr("Pinned folders are skipped when refreshing all folders, executing automatically and checking the library"));
+
+    let res = ui.add_enabled(is_not_busy, egui::Button::new(tr("Rebind series")));
+    if res.clicked() {
+        // Start from the current series or fall back to the folder name for unbound folders
+        let search = series_name.unwrap_or_else(|| {
+            let name = std::path::Path::new(folder.get_folder_name()).file_name();
+            name.map(|name| name.to_string_lossy().to_string()).unwrap_or_default()
+        });
+        *app.get_selected_folder_index().blocking_write() = Some(index);
+        gui.rebind_request = Some(search);
+        ui.close_menu();
+    }
+    res.on_disabled_hover_ui(|ui| {
+        ui.label(tr("Folder is busy"));
+    });
+
+    ui.separator();
+
+    let data_files = [
+        (tr("Open cache file"), folder.get_series_path()),
+        (tr("Open bookmarks file"), folder.get_bookmarks_path()),
+    ];
+    for (label, path) in data_files {
+        let is_exists = std::path::Path::new(path).exists();
+        let res = ui.add_enabled(is_exists, egui::Button::new(label));
+        if res.clicked() {
+            open_path(path);
+            ui.close_menu();
+        }
+        res.on_disabled_hover_ui(|ui| {
+            ui.label(tr("File doesn't exist yet"));
+        });
+    }
+}
+
+fn render_folder_rename_menu(ui: &mut egui::Ui, app: &Arc<App>, folder: &Arc<AppFolder>) {
+    let names = {
+        let cache = folder.get_cache().blocking_read();
+        cache.as_ref().map(|cache| {
+            [false, true].map(|is_include_year| (is_include_year, get_series_folder_name(&cache.series, is_include_year)))
+        })
+    };
+    let names = match names {
+        Some(names) => names,
+        None => return,
+    };
+
+    let is_not_busy = folder.get_busy_lock().try_lock().is_ok();
+    ui.separator();
+    for (index_name, (is_include_year, name)) in names.iter().enumerate() {
+        // skip the year variant if the series has no air date
+        if index_name > 0 && *name == names[0].1 {
+            continue;
+        }
+        let is_same = name == folder.get_folder_name();
+        let res = ui.add_enabled(is_not_busy && !is_same, egui::Button::new(trf("Rename to '{}'", &[name])));
+        if res.clicked() {
+            tokio::spawn({
+                let app = app.clone();
+                let folder_name = folder.get_folder_name().to_string();
+                let is_include_year = *is_include_year;
+                async move {
+                    app.rename_folder(folder_name.as_str(), is_include_year).await
+                }
+            });
+            ui.close_menu();
+        }
+    }
+}
+
+fn render_folder_merge_menu(ui: &mut egui::Ui, app: &Arc<App>, index: usize, folders: &[Arc<AppFolder>]) {
+    let key = get_folder_series_key(folders[index].get_folder_name());
+    if key.is_empty() {
+        return;
+    }
+    // folders bound to different series are never offered, even if their names only differ by the year
+    let get_series_id = |folder: &AppFolder| folder.get_cache().blocking_read().as_ref().map(|cache| cache.series.id);
+    let series_id = get_series_id(&folders[index]);
+    let candidates: Vec<usize> = folders.iter()
+        .enumerate()
+        .filter(|(other_index, other)| *other_index != index && get_folder_series_key(other.get_folder_name()) == key)
+        .filter(|(_, other)| match (series_id, get_series_id(other)) {
+            (Some(series_id), Some(other_series_id)) => series_id == other_series_id,
+            _ => true,
+        })
+        .map(|(other_index, _)| other_index)
+        .collect();
+    if candidates.is_empty() {
+        return;
+    }
+
+    ui.separator();
+    let is_not_busy = folders[index].get_busy_lock().try_lock().is_ok();
+    for dest_index in candidates {
+        let label = trf("Merge into '{}'", &[&folders[dest_index].get_folder_name()]);
+        if ui.add_enabled(is_not_busy, egui::Button::new(label)).clicked() {
+            request_folder_merge(app, index, dest_index);
+            ui.close_menu();
+        }
     }
 }
 
+fn render_account_usage(ui: &mut egui::Ui, account_usage: &[AccountUsage]) {
+    egui::Grid::new("tvdb_account_usage")
+        .num_columns(3)
+        .striped(true)
+        .show(ui, |ui| {
+            ui.strong(tr("Api key"));
+            ui.strong(tr("Requests"));
+            ui.strong(tr("Rate limited"));
+            ui.end_row();
+            for usage in account_usage {
+                let label = egui::RichText::new(usage.key_hint.as_str());
+                match usage.is_active {
+                    true => ui.label(label.strong()),
+                    false => ui.label(label),
+                };
+                ui.label(usage.total_requests.to_string());
+                ui.label(usage.total_rate_limited.to_string());
+                ui.end_row();
+            }
+        });
+}
+
 fn render_folders_controls(
     ui: &mut egui::Ui, app: &Arc<App>,
-    is_show_settings: &mut bool, is_busy: bool
+    is_show_settings: &mut bool, is_show_activity: &mut bool, is_busy: bool
 ) {
     ui.horizontal(|ui| {
         ui.add_enabled_ui(!is_busy, |ui| {
-            let res = ui.button("Refresh all");
+            let res = ui.button(tr("Refresh all"));
             if res.clicked() {
                 tokio::spawn({
                     let app = app.clone();
@@ -70,10 +360,44 @@ fn render_folders_controls(
                 });
             }
             res.on_disabled_hover_ui(|ui| {
-                ui.label("Folders are busy");
+                ui.label(tr("Folders are busy"));
             });
 
-            let res = ui.button("Reload structure");
+            let res = ui.button(tr("Execute reviewed"));
+            if res.clicked() {
+                request_reviewed_execution(app);
+            }
+            res.on_hover_text(tr("Execute the changes of every pending folder marked as reviewed"))
+                .on_disabled_hover_ui(|ui| {
+                    ui.label(tr("Folders are busy"));
+                });
+
+            let res = ui.button(tr("Apply title fixes"));
+            if res.clicked() {
+                request_title_fixes(app);
+            }
+            res.on_hover_text(tr("Preview and apply the renames which only change the episode title of already renamed files"))
+                .on_disabled_hover_ui(|ui| {
+                    ui.label(tr("Folders are busy"));
+                });
+
+            if let Some(session) = app.get_media_server_session().blocking_read().as_ref() {
+                let res = ui.button(tr("Sync watch state"));
+                if res.clicked() {
+                    tokio::spawn({
+                        let app = app.clone();
+                        async move {
+                            app.update_watch_states().await
+                        }
+                    });
+                }
+                res.on_hover_text(trf("Read which episodes were watched on {}", &[&session.get_kind().to_str()]))
+                    .on_disabled_hover_ui(|ui| {
+                        ui.label(tr("Folders are busy"));
+                    });
+            }
+
+            let res = ui.button(tr("Reload structure"));
             if res.clicked() {
                 tokio::spawn({
                     let app = app.clone();
@@ -83,11 +407,11 @@ fn render_folders_controls(
                 });
             }
             res.on_disabled_hover_ui(|ui| {
-                ui.label("Folders are busy");
+                ui.label(tr("Folders are busy"));
             });
         });
 
-        if ui.button("Login").clicked() {
+        if ui.button(tr("Login")).clicked() {
             tokio::spawn({
                 let app = app.clone();
                 async move {
@@ -96,22 +420,70 @@ fn render_folders_controls(
             });
         }
 
-        let is_logged_in = app.get_login_session().blocking_read().is_some();
-        let login_icon = match is_logged_in {
-            true => egui::RichText::new("✔").strong().color(egui::Color32::DARK_GREEN),
-            false => egui::RichText::new("🗙").strong().color(egui::Color32::DARK_RED),
+        // an expired session is kept around so the user is asked to log in again instead
+        let session = app.get_login_session().blocking_read().clone();
+        let is_expired = session.as_ref().map(|session| session.is_expired());
+        let (login_icon, login_tone) = match is_expired {
+            Some(false) => (Icon::LoggedIn, IconTone::Success),
+            Some(true) => (Icon::LoggedOut, IconTone::Highlight),
+            None => (Icon::LoggedOut, IconTone::Error),
         };
-        ui.label(login_icon).on_hover_ui(|ui| {
-            if is_logged_in {
-                ui.label("Login successful");
-            } else {
-                ui.label("Logged out");
+        let login_label = match is_expired {
+            Some(false) => tr("Login successful"),
+            Some(true) => tr("Session expired and logging in again failed, press Login"),
+            None => tr("Logged out"),
+        };
+        let res = render_icon(ui, login_icon, login_tone);
+        set_accessible_label(&res, egui::WidgetType::Label, login_label);
+        let account_usage = session.map(|session| session.get_account_usage()).unwrap_or_default();
+        res.on_hover_ui(|ui| {
+            ui.label(login_label);
+            // usage is only worth showing once there are keys to switch between
+            if account_usage.len() > 1 {
+                render_account_usage(ui, account_usage.as_slice());
             }
         });
 
-        if ui.selectable_label(*is_show_settings, "⛭").clicked() {
+        render_trakt_status(ui, app);
+
+        if app.get_torrent_session().blocking_read().is_some() {
+            let res = ui.button(tr("Apply torrent labels"));
+            if res.clicked() {
+                tokio::spawn({
+                    let app = app.clone();
+                    async move {
+                        app.apply_torrent_labels().await
+                    }
+                });
+            }
+            res.on_hover_text(tr("Seed folder settings from the labels of their torrents"));
+        }
+
+        let mut is_watching = *app.get_is_watching_folders().blocking_read();
+        let res = ui.checkbox(&mut is_watching, tr("Watch folders"));
+        if res.changed() {
+            tokio::spawn({
+                let app = app.clone();
+                async move {
+                    app.set_watching_folders(is_watching).await
+                }
+            });
+        }
+        res.on_hover_text(tr("Refresh folders automatically once their files stop changing"));
+
+        let res = render_icon_toggle(ui, Icon::Settings, *is_show_settings);
+        set_accessible_toggle(&res, tr("Settings"), *is_show_settings);
+        if res.clicked() {
             *is_show_settings = !*is_show_settings;
         }
+        res.on_hover_text(tr("Settings"));
+
+        let res = render_icon_toggle(ui, Icon::Activity, *is_show_activity);
+        set_accessible_toggle(&res, tr("Activity"), *is_show_activity);
+        if res.clicked() {
+            *is_show_activity = !*is_show_activity;
+        }
+        res.on_hover_text(tr("Activity"));
     });
 }
 
@@ -141,39 +513,230 @@ fn render_folders_status_filter(
                 for (index, status) in FolderStatus::iterator().enumerate() {
                     let status = *status;
                     let flag = &mut filters[status];
-                    let checkbox = egui::Checkbox::new(flag, format!("{} ({})", status.to_str(), status_counts[status]));
+                    let checkbox = egui::Checkbox::new(flag, format!("{} ({})", tr(status.to_str()), status_counts[status]));
+                    ui.add(checkbox);
+                    if (index + 1) % total_columns == 0 {
+                        ui.end_row();
+                    }
+                }
+            });
+    });
+}
+
+fn render_folders_series_filter(
+    ui: &mut egui::Ui,
+    series_counts: &enum_map::EnumMap<SeriesStatus, usize>, total_missing_recent: usize,
+    gui: &mut GuiAppFoldersList,
+) {
+    let layout = egui::Layout::left_to_right(egui::Align::Min)
+        .with_main_justify(true)
+        .with_main_wrap(true);
+    ui.with_layout(layout, |ui| {
+        let total_columns = 2;
+        egui::Grid::new("series_filter_flags")
+            .num_columns(total_columns)
+            .striped(true)
+            .show(ui, |ui| {
+                for (index, status) in SeriesStatus::iterator().enumerate() {
+                    let status = *status;
+                    let flag = &mut gui.series_filters[status];
+                    let checkbox = egui::Checkbox::new(flag, format!("{} ({})", tr(status.to_str()), series_counts[status]));
                     ui.add(checkbox);
                     if (index + 1) % total_columns == 0 {
                         ui.end_row();
                     }
                 }
+                let label = format!("{} ({})", tr("Missing recent episodes"), total_missing_recent);
+                ui.checkbox(&mut gui.is_missing_recent_only, label);
+                ui.end_row();
             });
     });
 }
 
+fn render_folders_review_filter(
+    ui: &mut egui::Ui,
+    total_reviewed: usize, total_unreviewed: usize, total_pinned: usize,
+    gui: &mut GuiAppFoldersList,
+) {
+    let layout = egui::Layout::left_to_right(egui::Align::Min)
+        .with_main_justify(true)
+        .with_main_wrap(true);
+    ui.with_layout(layout, |ui| {
+        egui::Grid::new("review_filter_flags")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.checkbox(&mut gui.is_show_reviewed, format!("{} ({})", tr("Reviewed"), total_reviewed));
+                ui.checkbox(&mut gui.is_show_unreviewed, format!("{} ({})", tr("Unreviewed"), total_unreviewed));
+                ui.end_row();
+                ui.checkbox(&mut gui.is_pinned_only, format!("{} ({})", tr("Pinned only"), total_pinned));
+                ui.end_row();
+            });
+    });
+}
+
+fn render_folders_sort_order(ui: &mut egui::Ui, gui: &mut GuiAppFoldersList) {
+    ui.horizontal(|ui| {
+        ui.label(tr("Sort by"));
+        egui::ComboBox::from_id_source("folders_sort_order")
+            .selected_text(tr(gui.sort_order.to_str()))
+            .show_ui(ui, |ui| {
+                for order in [FolderSortOrder::Name, FolderSortOrder::Completion] {
+                    ui.selectable_value(&mut gui.sort_order, order, tr(order.to_str()));
+                }
+            });
+    });
+}
+
+// Ended series are greyed out and continuing series with missing recent episodes are flagged
+fn get_folder_label(ui: &egui::Ui, folder: &AppFolder, progress: &SeriesProgress, is_excluded: bool) -> egui::RichText {
+    let label = egui::RichText::new(folder.get_folder_name());
+    if is_excluded {
+        return label.italics().color(ui.visuals().weak_text_color());
+    }
+    match progress.status {
+        SeriesStatus::Ended => label.color(ui.visuals().weak_text_color()),
+        _ => label,
+    }
+}
+
+fn render_auto_refresh_badge(ui: &mut egui::Ui, pending: Option<&PendingAutoRefresh>) {
+    let pending = match pending {
+        Some(pending) => pending,
+        None => return,
+    };
+    let label = trf("Auto-refresh in {} after {} changes", &[&format_duration(pending.remaining), &pending.total_events]);
+    let res = render_icon(ui, Icon::Busy, IconTone::Info);
+    set_accessible_label(&res, egui::WidgetType::Label, label.as_str());
+    res.on_hover_text(label);
+}
+
+fn render_review_badge(ui: &mut egui::Ui, review: Option<&FolderReview>) {
+    let review = match review {
+        Some(review) => review,
+        None => return,
+    };
+    let reviewed_at = review.reviewed_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string();
+    let label = trf("Reviewed by {} on {}", &[&review.reviewer, &reviewed_at]);
+    let res = render_icon(ui, Icon::Done, IconTone::Info);
+    set_accessible_label(&res, egui::WidgetType::Label, label.as_str());
+    res.on_hover_text(label);
+}
+
+fn render_pinned_badge(ui: &mut egui::Ui, is_pinned: bool) {
+    if !is_pinned {
+        return;
+    }
+    let label = tr("Pinned, skipped by bulk operations");
+    let res = render_icon(ui, Icon::Pin, IconTone::Highlight);
+    set_accessible_label(&res, egui::WidgetType::Label, label);
+    res.on_hover_text(label);
+}
+
+fn render_series_badge(ui: &mut egui::Ui, progress: &SeriesProgress) {
+    if progress.total_missing_recent == 0 {
+        return;
+    }
+    let label = trf("{} recent episodes missing", &[&progress.total_missing_recent]);
+    let res = render_icon(ui, Icon::Warning, IconTone::Highlight);
+    set_accessible_label(&res, egui::WidgetType::Label, label.as_str());
+    res.on_hover_text(label);
+}
+
+// Drawn as a ring which fills up clockwise from the top
+fn render_completion_ring(ui: &mut egui::Ui, progress: &SeriesProgress) {
+    let completion = match progress.get_completion() {
+        Some(completion) => completion,
+        None => return,
+    };
+    let size = get_icon_size(ui);
+    let (rect, res) = ui.allocate_exact_size(egui::vec2(size, size), egui::Sense::hover());
+    let center = rect.center();
+    let radius = size * 0.5 - 2.0;
+    let tone = match completion >= 1.0 {
+        true => IconTone::Success,
+        false => IconTone::Info,
+    };
+    ui.painter().circle_stroke(center, radius, egui::Stroke::new(2.0, ui.visuals().weak_text_color().gamma_multiply(0.5)));
+    let total_points = ((completion * 32.0).ceil() as usize).max(1);
+    let points: Vec<egui::Pos2> = (0..=total_points)
+        .map(|index| {
+            let angle = std::f32::consts::TAU * completion * (index as f32 / total_points as f32) - std::f32::consts::FRAC_PI_2;
+            center + radius * egui::vec2(angle.cos(), angle.sin())
+        })
+        .collect();
+    ui.painter().add(egui::Shape::line(points, egui::Stroke::new(2.0, tone.get_color(ui.visuals()))));
+
+    let label = trf("{}% complete, {} of {} aired episodes", &[
+        &((completion * 100.0).floor() as u32), &progress.total_aired_present, &progress.total_aired,
+    ]);
+    set_accessible_label(&res, egui::WidgetType::Label, label.as_str());
+    res.on_hover_text(label);
+}
+
 pub fn render_folders_list(
     ui: &mut egui::Ui,
-    gui: &mut GuiAppFoldersList, app: &Arc<App>, is_show_settings: &mut bool,
+    gui: &mut GuiAppFoldersList, app: &Arc<App>, 
+    is_show_settings: &mut bool, is_show_activity: &mut bool,
 ) {
     let folders = app.get_folders().blocking_read();
     let is_busy = app.get_folders_busy_lock().try_lock().is_err();
     let mut status_counts: enum_map::EnumMap<FolderStatus, usize> = enum_map::enum_map! { _ => 0 };
+    let mut series_counts: enum_map::EnumMap<SeriesStatus, usize> = enum_map::enum_map! { _ => 0 };
+    let mut total_missing_recent = 0;
+    let mut total_reviewed = 0;
+    let mut total_excluded = 0;
+    let mut total_pinned = 0;
     for folder in folders.iter() {
-        let status = folder.get_folder_status_blocking();
+        if folder.get_settings().blocking_read().is_pinned {
+            total_pinned += 1;
+        }
+        // Folders which aren't tv series are listed but left out of the counts
+        if folder.get_exclusion().blocking_read().is_some() {
+            total_excluded += 1;
+            continue;
+        }
+        if folder.get_settings().blocking_read().review.is_some() {
+            total_reviewed += 1;
+        }
+        let status = folder.get_last_known_status_blocking();
         status_counts[status] += 1; 
+        let progress = *folder.get_series_progress().blocking_read();
+        series_counts[progress.status] += 1;
+        if progress.total_missing_recent > 0 {
+            total_missing_recent += 1;
+        }
     }
 
-    render_folders_controls(ui, app, is_show_settings, is_busy);
-    render_folders_progress_bar(ui, status_counts[FolderStatus::Done], folders.len());
+    let pending_refreshes = app.get_folder_watcher().blocking_read()
+        .as_ref()
+        .map(|watcher| watcher.get_pending())
+        .unwrap_or_default();
+
+    render_folders_controls(ui, app, is_show_settings, is_show_activity, is_busy);
+    if !pending_refreshes.is_empty() {
+        ui.label(trf("Auto-refresh pending for {} folders", &[&pending_refreshes.len()]));
+    }
+    render_folders_progress_bar(ui, status_counts[FolderStatus::Done], folders.len() - total_excluded);
+    render_operation_progress(ui, app);
     ui.separator();
     render_folders_status_filter(ui, &status_counts, &mut gui.filters);
-    render_search_bar(ui, &mut gui.searcher);
+    render_folders_series_filter(ui, &series_counts, total_missing_recent, gui);
+    render_folders_review_filter(ui, total_reviewed, folders.len() - total_excluded - total_reviewed, total_pinned, gui);
+    render_folders_sort_order(ui, gui);
+    if render_recent_folders(ui, app, folders.as_slice()) {
+        gui.is_focus_selected = true;
+    }
+    render_background_search_bar(ui, &mut gui.searcher);
+    gui.searcher.update(folders.len(), || {
+        folders.iter().map(|folder| folder.get_folder_name().to_string()).collect()
+    });
 
     if folders.is_empty() {
         if is_busy {
             ui.spinner();
         } else {
-            ui.label("No folders");
+            ui.label(tr("No folders"));
         }
         return;
     }
@@ -182,48 +745,163 @@ pub fn render_folders_list(
         let layout = egui::Layout::top_down(egui::Align::Min).with_cross_justify(true);
         ui.with_layout(layout, |ui| {
             let selected_index = *app.get_selected_folder_index().blocking_read();
-            for (index, folder) in folders.iter().enumerate() {
+            let mut visible_indices = Vec::new();
+            let search_matches: Vec<bool> = {
+                let results = gui.searcher.get_results();
+                folders.iter().map(|folder| results.as_ref().map(|results| results.is_match(folder.get_folder_name())).unwrap_or(true)).collect()
+            };
+            let mut sorted_indices: Vec<usize> = (0..folders.len()).collect();
+            if gui.sort_order == FolderSortOrder::Completion {
+                let completions: Vec<Option<f32>> = folders.iter()
+                    .map(|folder| folder.get_series_progress().blocking_read().get_completion())
+                    .collect();
+                // folders are already sorted by name so ties keep that order
+                sorted_indices.sort_by(|a, b| match (completions[*a], completions[*b]) {
+                    (Some(a), Some(b)) => a.total_cmp(&b),
+                    (a, b) => b.is_some().cmp(&a.is_some()),
+                });
+            }
+            for index in sorted_indices {
+                let folder = &folders[index];
                 let label = folder.get_folder_name();
-                if !gui.searcher.search(label) {
+                if !search_matches.get(index).copied().unwrap_or(true) {
                     continue;
                 }
 
-                let status = folder.get_folder_status_blocking();
-                if !gui.filters[status] {
+                // Excluded folders stay listed unless only folders with missing episodes are shown
+                let exclusion = *folder.get_exclusion().blocking_read();
+                let status = folder.get_last_known_status_blocking();
+                let progress = *folder.get_series_progress().blocking_read();
+                let (review, is_pinned) = {
+                    let settings = folder.get_settings().blocking_read();
+                    (settings.review.clone(), settings.is_pinned)
+                };
+                let is_shown = (is_pinned || !gui.is_pinned_only) && match exclusion {
+                    Some(_) => !gui.is_missing_recent_only,
+                    None => {
+                        let is_review_shown = match review.is_some() {
+                            true => gui.is_show_reviewed,
+                            false => gui.is_show_unreviewed,
+                        };
+                        gui.filters[status] && gui.series_filters[progress.status] && is_review_shown &&
+                            (!gui.is_missing_recent_only || progress.total_missing_recent > 0)
+                    },
+                };
+                if !is_shown {
                     continue;
                 }
+                visible_indices.push(index);
 
                 ui.horizontal(|ui| {
                     let is_busy = folder.get_busy_lock().try_lock().is_err();
-                    render_folder_status(ui, status, is_busy);
+                    render_pinned_badge(ui, is_pinned);
+                    match exclusion {
+                        Some(exclusion) => render_exclusion_badge(ui, exclusion),
+                        None => {
+                            render_folder_status(ui, status, is_busy);
+                            render_completion_ring(ui, &progress);
+                            render_series_badge(ui, &progress);
+                            render_review_badge(ui, review.as_ref());
+                        },
+                    }
+                    let pending = pending_refreshes.iter().find(|pending| pending.folder_name == label);
+                    render_auto_refresh_badge(ui, pending);
                     let layout = egui::Layout::top_down(egui::Align::Min).with_cross_justify(true);
                     ui.with_layout(layout, |ui| {
                         let is_selected = selected_index == Some(index);
-                        let elem = ClippedSelectableLabel::new(is_selected, folder.get_folder_name());
+                        let elem = ClippedSelectableLabel::new(is_selected, get_folder_label(ui, folder, &progress, exclusion.is_some()));
                         let res = ui.add(elem);
+                        // Move focus to folders selected with the keyboard so the focus order follows the selection
+                        if is_selected && gui.is_focus_selected {
+                            gui.is_focus_selected = false;
+                            res.request_focus();
+                            res.scroll_to_me(None);
+                        }
                         if res.clicked() {
                             let mut selected_index = app.get_selected_folder_index().blocking_write();
                             if !is_selected {
                                 *selected_index = Some(index);
+                                push_recent_folder(label);
                             } else {
                                 *selected_index = None;
                             }
                         }
-                        res.context_menu(|ui| {
-                            if ui.button("Open folder").clicked() {
-                                tokio::spawn({
-                                    let folder_path_str = folder.get_folder_path().to_string();
-                                    async move {
-                                        cross_open::that(folder_path_str)
+                        if res.double_clicked() {
+                            match get_keybindings().folder_double_click {
+                                FolderClickAction::Nothing => {},
+                                FolderClickAction::OpenOnDisk => {
+                                    tokio::spawn({
+                                        let folder_path_str = folder.get_folder_path().to_string();
+                                        async move {
+                                            cross_open::that(folder_path_str)
+                                        }
+                                    });
+                                },
+                                // The first click of a double click toggles the selection so it needs to be restored
+                                FolderClickAction::OpenDetail => {
+                                    *app.get_selected_folder_index().blocking_write() = Some(index);
+                                    push_recent_folder(label);
+                                },
+                                FolderClickAction::OpenInNewWindow => {
+                                    gui.detach_request = Some(index);
+                                },
+                                FolderClickAction::Execute => {
+                                    if folder.get_busy_lock().try_lock().is_ok() {
+                                        request_folder_execution(folder);
                                     }
-                                });
-                                ui.close_menu();
+                                },
                             }
+                        }
+                        res.context_menu(|ui| {
+                            render_folder_context_menu(ui, app, gui, index, folder);
+                            render_folder_rename_menu(ui, app, folder);
+                            render_folder_merge_menu(ui, app, index, folders.as_slice());
                         });
                     });
                 });
             }
+
+            // Skip the update while a refresh is picking its next folder rather than stall the ui
+            if let Ok(mut indices) = app.get_visible_folder_indices().try_write() {
+                if *indices != visible_indices {
+                    *indices = visible_indices;
+                }
+            }
         });
     });
 }
 
+fn render_trakt_status(ui: &mut egui::Ui, app: &Arc<App>) {
+    let trakt_sync = app.get_trakt_sync();
+    if trakt_sync.get_client_info().blocking_read().is_none() {
+        return;
+    }
+
+    if trakt_sync.get_session().blocking_read().is_some() {
+        let label = tr("Syncing collection to Trakt");
+        let res = render_icon(ui, Icon::Done, IconTone::Success);
+        set_accessible_label(&res, egui::WidgetType::Label, label);
+        res.on_hover_text(label);
+        return;
+    }
+
+    if let Some(device_code) = trakt_sync.get_device_code().blocking_read().as_ref() {
+        ui.label(trf("Enter code {} at", &[&device_code.user_code]));
+        ui.hyperlink(device_code.verification_url.as_str());
+        return;
+    }
+
+    let is_not_busy = trakt_sync.get_authorize_lock().try_lock().is_ok();
+    ui.add_enabled_ui(is_not_busy, |ui| {
+        let res = ui.button(tr("Connect Trakt"));
+        if res.clicked() {
+            tokio::spawn({
+                let app = app.clone();
+                async move {
+                    app.authorize_trakt().await
+                }
+            });
+        }
+        res.on_hover_text(tr("Mark renamed episodes as collected on Trakt"));
+    });
+}
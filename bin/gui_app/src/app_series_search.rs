@@ -8,6 +8,8 @@ use crate::fuzzy_search::{FuzzySearcher, render_search_bar};
 use crate::clipped_selectable::ClippedSelectableLabel;
 use crate::helpers::render_invisible_width_widget;
 use crate::tvdb_tables::render_series_table;
+use crate::i18n::tr;
+use crate::icons::{Icon, render_icon_toggle};
 
 pub struct GuiSeriesSearch {
     search_string: String,
@@ -21,6 +23,11 @@ impl GuiSeriesSearch {
             searcher: FuzzySearcher::new(),
         }
     }
+
+    pub fn set_search_string(&mut self, search: &str) {
+        self.search_string.clear();
+        self.search_string.push_str(search);
+    }
 }
 
 impl Default for GuiSeriesSearch {
@@ -44,13 +51,13 @@ fn render_series_search_list(
     let series = match series.as_ref() {
         Some(series) => series,
         None => {
-            ui.label("No search has been performed yet");
+            ui.label(tr("No search has been performed yet"));
             return;
         },
     };
 
     if series.is_empty() {
-        ui.label("Search gave no results");
+        ui.label(tr("Search gave no results"));
         return;
     }
     
@@ -79,24 +86,48 @@ fn render_series_search_list(
                 .striped(true)
                 .resizable(true)
                 .cell_layout(cell_layout)
+                .column(Column::auto().resizable(false))
                 .column(Column::remainder().resizable(true).clip(true))
                 .column(Column::auto().resizable(false))
                 .column(Column::auto().resizable(false))
                 .column(Column::auto().resizable(false))
                 .header(row_height, |mut header| {
-                    header.col(|ui| { ui.strong("Name"); });
-                    header.col(|ui| { ui.strong("Status"); });
-                    header.col(|ui| { ui.strong("First Aired"); });
+                    header.col(|ui| { ui.strong(""); });
+                    header.col(|ui| { ui.strong(tr("Name")); });
+                    header.col(|ui| { ui.strong(tr("Status")); });
+                    header.col(|ui| { ui.strong(tr("First Aired")); });
                     header.col(|ui| { ui.strong(""); });
                 })
                 .body(|mut body| {
                     let selected_index = *app.get_selected_series_index().blocking_read();
+                    let watchlist_ids = app.get_watchlist().get_series_ids_blocking();
                     for (index, entry) in series.iter().enumerate() {
                         if !gui.searcher.search(entry.name.as_str()) {
                             continue;
                         }
 
                         body.row(row_height, |mut row| {
+                            row.col(|ui| {
+                                let is_watched = watchlist_ids.contains(&entry.id);
+                                let res = render_icon_toggle(ui, Icon::Favourite, is_watched);
+                                if res.clicked() {
+                                    tokio::spawn({
+                                        let app = app.clone();
+                                        let series = entry.clone();
+                                        async move {
+                                            match is_watched {
+                                                true => app.remove_from_watchlist(series.id).await,
+                                                false => app.add_to_watchlist(&series).await,
+                                            }
+                                        }
+                                    });
+                                }
+                                let tooltip = match is_watched {
+                                    true => tr("Remove from watchlist"),
+                                    false => tr("Add to watchlist"),
+                                };
+                                res.on_hover_text(tooltip);
+                            });
                             row.col(|ui| { 
                                 let layout = egui::Layout::top_down(egui::Align::Min).with_cross_justify(true);
                                 ui.with_layout(layout, |ui| {
@@ -113,18 +144,19 @@ fn render_series_search_list(
                                 });
                             });
                             row.col(|ui| {
-                                let label = entry.status.as_deref().unwrap_or("Unknown");
+                                let label = entry.status.as_deref().unwrap_or(tr("Unknown"));
                                 ui.label(label);
                             });
                             row.col(|ui| {
-                                let label = entry.first_aired.as_deref().unwrap_or("Unknown");
+                                let label = entry.first_aired.as_deref().unwrap_or(tr("Unknown"));
                                 ui.label(label);
                             });
                             row.col(|ui| {
                                 ui.add_enabled_ui(is_series_selectable, |ui| {
-                                    let res = ui.button("Select");
+                                    let res = ui.button(tr("Select"));
                                     if res.clicked() {
                                         tokio::spawn({
+                                            let app = app.clone();
                                             let series_id = entry.id;
                                             let folder = folder.clone();
                                             let session = session.clone();
@@ -136,7 +168,7 @@ fn render_series_search_list(
                                                             folder.update_file_intents(),
                                                             folder.save_cache_to_file(),
                                                         );
-                                                        Some(())
+                                                        app.update_outdated_folders().await
                                                     } else {
                                                         None
                                                     }
@@ -147,9 +179,9 @@ fn render_series_search_list(
                                         });
                                     }
                                     res.on_disabled_hover_ui(|ui| {
-                                        if !is_logged_in            { ui.label("Not logged in"); }
-                                        else if !is_folder_selected { ui.label("No folder is selected"); }
-                                        else if !is_not_busy        { ui.label("Folder is busy"); }
+                                        if !is_logged_in            { ui.label(tr("Not logged in")); }
+                                        else if !is_folder_selected { ui.label(tr("No folder is selected")); }
+                                        else if !is_not_busy        { ui.label(tr("Folder is busy")); }
                                     });
                                 });
                             });
@@ -171,7 +203,7 @@ fn render_series_search_info_panel(
     let series_list = match series_list {
         Some(series_list) => series_list,
         None => {
-            ui.label("No series information");
+            ui.label(tr("No series information"));
             return;
         },
     };
@@ -179,7 +211,7 @@ fn render_series_search_info_panel(
     let selected_index = match selected_index {
         Some(index) => index,
         None => {
-            ui.label("No series selected");
+            ui.label(tr("No series selected"));
             return;
         },
     };
@@ -187,7 +219,7 @@ fn render_series_search_info_panel(
     let series = match series_list.get(selected_index) {
         Some(series) => series,
         None => {
-            ui.colored_label(egui::Color32::DARK_RED, "Series index is outside of bounds");
+            ui.colored_label(egui::Color32::DARK_RED, tr("Series index is outside of bounds"));
             return;
         },
     };
@@ -210,10 +242,10 @@ fn render_series_search_bar(
             let is_logged_in = app.get_login_session().blocking_read().is_some();
             let mut is_pressed = false;
             ui.add_enabled_ui(is_logged_in, |ui| {
-                let res = ui.button("Search");
+                let res = ui.button(tr("Search"));
                 is_pressed = res.clicked();
                 res.on_disabled_hover_ui(|ui| {
-                    ui.label("Not logged in");
+                    ui.label(tr("Not logged in"));
                 });
             });
 
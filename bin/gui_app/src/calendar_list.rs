@@ -0,0 +1,171 @@
+use app::app::App;
+use app::calendar::AgendaEntry;
+use chrono;
+use egui;
+use egui_extras::{Column, TableBuilder};
+use std::sync::Arc;
+use tokio;
+use crate::fuzzy_search::{FuzzySearcher, render_search_bar};
+use crate::i18n::tr;
+use crate::icons::{Icon, IconTone, render_icon};
+
+pub struct GuiCalendar {
+    searcher: FuzzySearcher,
+    days_before: u32,
+    days_after: u32,
+    is_missing_only: bool,
+    is_initial_update: bool,
+}
+
+impl GuiCalendar {
+    pub fn new() -> Self {
+        Self {
+            searcher: FuzzySearcher::new(),
+            days_before: 7,
+            days_after: 30,
+            is_missing_only: false,
+            is_initial_update: false,
+        }
+    }
+}
+
+impl Default for GuiCalendar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn request_agenda_update(gui: &GuiCalendar, app: &Arc<App>) {
+    let today = chrono::Local::now().date_naive();
+    let start = today - chrono::Duration::days(gui.days_before as i64);
+    let end = today + chrono::Duration::days(gui.days_after as i64);
+    tokio::spawn({
+        let app = app.clone();
+        async move {
+            app.update_agenda(start, end).await
+        }
+    });
+}
+
+fn render_episode_status(ui: &mut egui::Ui, entry: &AgendaEntry, today: chrono::NaiveDate) {
+    if entry.is_file_present {
+        render_icon(ui, Icon::Done, IconTone::Success).on_hover_text(tr("File present"));
+    } else if entry.air_date <= today {
+        render_icon(ui, Icon::Warning, IconTone::Error).on_hover_text(tr("Aired but missing"));
+    } else {
+        render_icon(ui, Icon::Pending, IconTone::Inactive).on_hover_text(tr("Upcoming"));
+    }
+    match entry.is_watched {
+        Some(true) => { render_icon(ui, Icon::Watched, IconTone::Success).on_hover_text(tr("Watched")); },
+        Some(false) => { render_icon(ui, Icon::Watched, IconTone::Inactive).on_hover_text(tr("Not watched")); },
+        None => {},
+    }
+}
+
+fn render_calendar_controls(ui: &mut egui::Ui, gui: &mut GuiCalendar, app: &Arc<App>, is_busy: bool) {
+    ui.horizontal_wrapped(|ui| {
+        ui.label(tr("Days before"));
+        ui.add(egui::DragValue::new(&mut gui.days_before).clamp_range(0..=365));
+        ui.label(tr("Days after"));
+        ui.add(egui::DragValue::new(&mut gui.days_after).clamp_range(0..=365));
+        ui.checkbox(&mut gui.is_missing_only, tr("Only missing"));
+        ui.add_enabled_ui(!is_busy, |ui| {
+            if ui.button(tr("Refresh")).clicked() {
+                request_agenda_update(gui, app);
+            }
+        });
+        if is_busy {
+            ui.spinner();
+        }
+    });
+    render_search_bar(ui, &mut gui.searcher);
+}
+
+pub fn render_calendar(ui: &mut egui::Ui, gui: &mut GuiCalendar, app: &Arc<App>) {
+    let is_busy = app.get_agenda_busy_lock().try_lock().is_err();
+    // Loading every folder is slow so the agenda is only built once the tab is first shown
+    if !gui.is_initial_update {
+        gui.is_initial_update = true;
+        request_agenda_update(gui, app);
+    }
+    render_calendar_controls(ui, gui, app, is_busy);
+    ui.separator();
+
+    let agenda = match app.get_agenda().try_read() {
+        Ok(agenda) => agenda,
+        Err(_) => {
+            ui.spinner();
+            return;
+        },
+    };
+    let agenda = match agenda.as_ref() {
+        Some(agenda) => agenda,
+        None => {
+            ui.label(tr("No episodes available"));
+            return;
+        },
+    };
+    if agenda.entries.is_empty() {
+        ui.label(tr("No episodes available"));
+        return;
+    }
+
+    let today = chrono::Local::now().date_naive();
+    let row_height = 18.0;
+    let cell_layout = egui::Layout::left_to_right(egui::Align::Center).with_cross_justify(false);
+    TableBuilder::new(ui)
+        .striped(true)
+        .resizable(true)
+        .cell_layout(cell_layout)
+        .column(Column::auto().resizable(true))
+        .column(Column::auto().resizable(false))
+        .column(Column::initial(200.0).resizable(true).clip(true))
+        .column(Column::auto().resizable(true))
+        .column(Column::remainder().resizable(true).clip(true))
+        .header(row_height, |mut header| {
+            header.col(|ui| { ui.strong(tr("Air date")); });
+            header.col(|ui| { ui.strong(""); });
+            header.col(|ui| { ui.strong(tr("Series")); });
+            header.col(|ui| { ui.strong(tr("Episode")); });
+            header.col(|ui| { ui.strong(tr("Title")); });
+        })
+        .body(|mut body| {
+            for entry in agenda.entries.iter() {
+                if gui.is_missing_only && entry.is_file_present {
+                    continue;
+                }
+                if !gui.searcher.search(entry.series_name.as_str()) {
+                    continue;
+                }
+
+                body.row(row_height, |mut row| {
+                    row.col(|ui| {
+                        let label = entry.air_date.format("%Y-%m-%d").to_string();
+                        if entry.air_date == today {
+                            ui.strong(label);
+                        } else {
+                            ui.label(label);
+                        }
+                    });
+                    row.col(|ui| {
+                        render_episode_status(ui, entry, today);
+                    });
+                    row.col(|ui| {
+                        let res = ui.link(entry.series_name.as_str()).on_hover_text(entry.folder_name.as_str());
+                        if res.clicked() {
+                            let index = app.get_folders().blocking_read()
+                                .iter()
+                                .position(|folder| folder.get_folder_name() == entry.folder_name);
+                            *app.get_selected_folder_index().blocking_write() = index;
+                        }
+                    });
+                    row.col(|ui| {
+                        ui.label(format!("S{:02}E{:02}", entry.descriptor.season, entry.descriptor.episode));
+                    });
+                    row.col(|ui| {
+                        ui.label(entry.episode_name.as_deref().unwrap_or(""));
+                    });
+                });
+            }
+        });
+}
@@ -0,0 +1,68 @@
+use app::app::App;
+use egui;
+use std::sync::Arc;
+use tokio;
+use crate::i18n::tr;
+use crate::icons::{Icon, IconTone, render_icon};
+
+pub fn render_diagnostics_window(ui: &mut egui::Ui, app: &Arc<App>) {
+    let diagnostics = match app.get_diagnostics().try_read() {
+        Ok(diagnostics) => diagnostics,
+        Err(_) => {
+            ui.spinner();
+            return;
+        },
+    };
+
+    let mut dismiss_index = None;
+    egui::Grid::new("diagnostics_list")
+        .num_columns(3)
+        .striped(true)
+        .show(ui, |ui| {
+            for (index, diagnostic) in diagnostics.iter().enumerate() {
+                render_icon(ui, Icon::Warning, IconTone::Error);
+                ui.vertical(|ui| {
+                    ui.strong(tr(diagnostic.kind.to_str()));
+                    ui.label(diagnostic.message.as_str());
+                    ui.weak(tr(diagnostic.suggestion));
+                });
+                if ui.button(tr("Dismiss")).clicked() {
+                    dismiss_index = Some(index);
+                }
+                ui.end_row();
+            }
+        });
+    drop(diagnostics);
+
+    ui.separator();
+    let is_not_busy = app.get_diagnostics_busy_lock().try_lock().is_ok();
+    ui.horizontal(|ui| {
+        ui.add_enabled_ui(is_not_busy, |ui| {
+            if ui.button(tr("Check again")).clicked() {
+                tokio::spawn({
+                    let app = app.clone();
+                    async move {
+                        app.run_diagnostics().await
+                    }
+                });
+            }
+        });
+        if ui.button(tr("Dismiss all")).clicked() {
+            tokio::spawn({
+                let app = app.clone();
+                async move {
+                    app.dismiss_all_diagnostics().await
+                }
+            });
+        }
+    });
+
+    if let Some(index) = dismiss_index {
+        tokio::spawn({
+            let app = app.clone();
+            async move {
+                app.dismiss_diagnostic(index).await
+            }
+        });
+    }
+}
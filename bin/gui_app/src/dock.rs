@@ -0,0 +1,190 @@
+use app::app::App;
+use app::app_folder::AppFolder;
+use app::metrics::get_metrics;
+use egui;
+use egui_dock::{DockState, NodeIndex, TabViewer};
+use serde;
+use std::sync::Arc;
+use crate::activity_list::{GuiActivityList, render_activity_list};
+use crate::app_folder::{GuiAppFolder, render_app_folder};
+use crate::app_folder_episode_cache_list::render_episode_cache_list;
+use crate::calendar_list::{GuiCalendar, render_calendar};
+use crate::app_folders_list::{GuiAppFoldersList, render_folders_list};
+use crate::error_list::render_errors_list;
+use crate::folder_stats::render_folder_stats;
+use crate::fuzzy_search::FuzzySearcher;
+use crate::helpers::render_invisible_width_widget;
+use crate::i18n::tr;
+use crate::metrics_panel::render_metrics_panel;
+use crate::watchlist_list::{GuiWatchlist, render_watchlist};
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DockTab {
+    Folders,
+    FolderView,
+    EpisodeBrowser,
+    Activity,
+    Stats,
+    Watchlist,
+    Calendar,
+    Metrics,
+}
+
+impl DockTab {
+    pub fn iterator() -> std::slice::Iter<'static, Self> {
+        static TABS: [DockTab;8] = [
+            DockTab::Folders,
+            DockTab::FolderView,
+            DockTab::EpisodeBrowser,
+            DockTab::Activity,
+            DockTab::Stats,
+            DockTab::Watchlist,
+            DockTab::Calendar,
+            DockTab::Metrics,
+        ];
+        TABS.iter()
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            DockTab::Folders => "Folders",
+            DockTab::FolderView => "Folder",
+            DockTab::EpisodeBrowser => "Episodes",
+            DockTab::Activity => "Activity",
+            DockTab::Stats => "Stats",
+            DockTab::Watchlist => "Watchlist",
+            DockTab::Calendar => "Calendar",
+            DockTab::Metrics => "Metrics",
+        }
+    }
+
+    // Each tab is timed separately to find which one slows down rendering
+    fn get_render_metric(&self) -> &'static str {
+        match self {
+            DockTab::Folders => "Render folders tab",
+            DockTab::FolderView => "Render folder tab",
+            DockTab::EpisodeBrowser => "Render episodes tab",
+            DockTab::Activity => "Render activity tab",
+            DockTab::Stats => "Render stats tab",
+            DockTab::Watchlist => "Render watchlist tab",
+            DockTab::Calendar => "Render calendar tab",
+            DockTab::Metrics => "Render metrics tab",
+        }
+    }
+}
+
+pub fn create_default_dock_state() -> DockState<DockTab> {
+    let mut dock_state = DockState::new(vec![DockTab::FolderView, DockTab::EpisodeBrowser]);
+    let surface = dock_state.main_surface_mut();
+    let [main_node, _] = surface.split_left(NodeIndex::root(), 0.75, vec![DockTab::Folders]);
+    surface.split_below(main_node, 0.75, vec![DockTab::Activity, DockTab::Stats, DockTab::Watchlist, DockTab::Calendar]);
+    dock_state
+}
+
+pub fn render_dock_tab_toggles(ui: &mut egui::Ui, dock_state: &mut DockState<DockTab>) {
+    for tab in DockTab::iterator() {
+        let location = dock_state.find_tab(tab);
+        let mut is_open = location.is_some();
+        if !ui.checkbox(&mut is_open, tr(tab.to_str())).changed() {
+            continue;
+        }
+        match location {
+            Some(location) => { dock_state.remove_tab(location); },
+            None => dock_state.push_to_focused_leaf(*tab),
+        }
+    }
+}
+
+fn get_selected_folder(app: &App) -> Option<Arc<AppFolder>> {
+    let index = (*app.get_selected_folder_index().blocking_read())?;
+    app.get_folders().blocking_read().get(index).cloned()
+}
+
+pub(crate) struct GuiTabViewer<'a> {
+    pub(crate) app: &'a Arc<App>,
+    pub(crate) gui_app_folders_list: &'a mut GuiAppFoldersList,
+    pub(crate) gui_app_folder: &'a mut GuiAppFolder,
+    pub(crate) gui_activity_list: &'a mut GuiActivityList,
+    pub(crate) gui_watchlist: &'a mut GuiWatchlist,
+    pub(crate) gui_calendar: &'a mut GuiCalendar,
+    pub(crate) episode_searcher: &'a mut FuzzySearcher,
+    pub(crate) is_gui_settings_opened: &'a mut bool,
+    pub(crate) is_activity_opened: &'a mut bool,
+}
+
+impl GuiTabViewer<'_> {
+    fn render_folders(&mut self, ui: &mut egui::Ui) {
+        render_invisible_width_widget(ui);
+        if let Ok(mut errors) = self.app.get_errors().try_write() {
+            if !errors.is_empty() {
+                egui::TopBottomPanel::bottom("app_error_list")
+                    .resizable(true)
+                    .show_inside(ui, |ui| {
+                        render_errors_list(ui, errors.as_mut());
+                    });
+            }
+        }
+        egui::CentralPanel::default()
+            .frame(egui::Frame::none())
+            .show_inside(ui, |ui| {
+                render_folders_list(
+                    ui, self.gui_app_folders_list, self.app,
+                    self.is_gui_settings_opened, self.is_activity_opened,
+                );
+            });
+    }
+
+    fn render_folder_view(&mut self, ui: &mut egui::Ui) {
+        let folder = match get_selected_folder(self.app) {
+            Some(folder) => folder,
+            None => {
+                ui.label(tr("No folder selected"));
+                return;
+            },
+        };
+
+        let session = self.app.get_login_session().blocking_read();
+        render_app_folder(
+            ui,
+            self.app, session.as_ref(),
+            self.gui_app_folder, &folder,
+        );
+    }
+
+    fn render_episode_browser(&mut self, ui: &mut egui::Ui) {
+        match get_selected_folder(self.app) {
+            Some(folder) => render_episode_cache_list(ui, self.episode_searcher, &folder),
+            None => { ui.label(tr("No folder selected")); },
+        }
+    }
+}
+
+impl TabViewer for GuiTabViewer<'_> {
+    type Tab = DockTab;
+
+    fn title(&mut self, tab: &mut Self::Tab) -> egui::WidgetText {
+        tr(tab.to_str()).into()
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, tab: &mut Self::Tab) {
+        let _timer = get_metrics().start_timer(tab.get_render_metric());
+        match tab {
+            DockTab::Folders => self.render_folders(ui),
+            DockTab::FolderView => self.render_folder_view(ui),
+            DockTab::EpisodeBrowser => self.render_episode_browser(ui),
+            DockTab::Activity => render_activity_list(ui, self.gui_activity_list, self.app.get_activity_log()),
+            DockTab::Stats => render_folder_stats(ui, self.app),
+            DockTab::Watchlist => render_watchlist(ui, self.gui_watchlist, self.app),
+            DockTab::Calendar => render_calendar(ui, self.gui_calendar, self.app),
+            DockTab::Metrics => render_metrics_panel(ui),
+        }
+    }
+
+    // These tabs manage their own scrolling
+    fn scroll_bars(&self, tab: &Self::Tab) -> [bool; 2] {
+        match tab {
+            DockTab::Folders | DockTab::FolderView | DockTab::EpisodeBrowser | DockTab::Activity | DockTab::Watchlist | DockTab::Calendar => [false, false],
+            DockTab::Stats | DockTab::Metrics => [true, true],
+        }
+    }
+}
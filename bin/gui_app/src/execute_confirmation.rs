@@ -0,0 +1,209 @@
+use app::app::App;
+use app::app_folder::AppFolder;
+use app::execution_summary::{ExecutionFilter, ExecutionSummary};
+use egui;
+use lazy_static::lazy_static;
+use std::sync::{Arc, Mutex};
+use tokio;
+use crate::helpers::format_file_size;
+use crate::i18n::{tr, trf};
+use crate::icons::{Icon, IconTone, render_icon};
+use crate::recent_folders::push_recent_folder;
+
+#[derive(Clone)]
+enum ExecuteRequest {
+    Folder(Arc<AppFolder>),
+    File(Arc<AppFolder>, String),
+    Reviewed(Arc<App>),
+    // Renames which only change the episode title, across every folder
+    TitleFixes(Arc<App>),
+}
+
+impl ExecuteRequest {
+    async fn get_summary(&self) -> ExecutionSummary {
+        match self {
+            ExecuteRequest::Folder(folder) => folder.get_execution_summary().await,
+            ExecuteRequest::File(folder, src) => folder.get_file_execution_summary(src.as_str()).await,
+            ExecuteRequest::Reviewed(app) => app.get_reviewed_execution_summary().await,
+            ExecuteRequest::TitleFixes(app) => app.get_title_fix_summary().await,
+        }
+    }
+
+    // A single file only has one kind of change so the filter doesn't apply to it
+    fn is_filterable(&self) -> bool {
+        !matches!(self, ExecuteRequest::File(..) | ExecuteRequest::TitleFixes(..))
+    }
+
+    // Bulk title fixes are always simulated first since they touch the whole library
+    fn is_preview_required(&self) -> bool {
+        matches!(self, ExecuteRequest::TitleFixes(..))
+    }
+
+    async fn execute(&self, filter: ExecutionFilter) {
+        match self {
+            ExecuteRequest::Folder(folder) => {
+                folder.execute_filtered_file_changes(filter).await;
+                folder.update_file_intents().await;
+            },
+            ExecuteRequest::File(folder, src) => {
+                folder.execute_file_change(src.as_str()).await;
+                folder.update_file_intents().await;
+            },
+            ExecuteRequest::Reviewed(app) => {
+                app.execute_reviewed_folders(filter).await;
+            },
+            ExecuteRequest::TitleFixes(app) => {
+                app.execute_title_fixes().await;
+            },
+        }
+    }
+}
+
+#[derive(Default)]
+struct ConfirmationState {
+    pending: Option<(ExecuteRequest, ExecutionSummary)>,
+    filter: ExecutionFilter,
+    is_skipped_for_session: bool,
+}
+
+lazy_static! {
+    static ref CONFIRMATION: Mutex<ConfirmationState> = Mutex::new(ConfirmationState::default());
+}
+
+// Executions which delete files wait for confirmation unless it was turned off for this session, previews always wait
+fn request_execution(request: ExecuteRequest) {
+    tokio::spawn(async move {
+        let summary = request.get_summary().await;
+        let is_skipped_for_session = CONFIRMATION.lock().map(|state| state.is_skipped_for_session).unwrap_or(false);
+        let is_confirmed = !request.is_preview_required() && (!summary.is_destructive() || is_skipped_for_session);
+        if is_confirmed {
+            request.execute(ExecutionFilter::default()).await;
+            return;
+        }
+        if let Ok(mut state) = CONFIRMATION.lock() {
+            state.pending = Some((request, summary));
+        }
+    });
+}
+
+pub fn request_folder_execution(folder: &Arc<AppFolder>) {
+    push_recent_folder(folder.get_folder_name());
+    request_execution(ExecuteRequest::Folder(folder.clone()));
+}
+
+pub fn request_file_execution(folder: &Arc<AppFolder>, src: &str) {
+    request_execution(ExecuteRequest::File(folder.clone(), src.to_string()));
+}
+
+pub fn request_reviewed_execution(app: &Arc<App>) {
+    request_execution(ExecuteRequest::Reviewed(app.clone()));
+}
+
+pub fn request_title_fixes(app: &Arc<App>) {
+    request_execution(ExecuteRequest::TitleFixes(app.clone()));
+}
+
+fn render_summary_row(ui: &mut egui::Ui, label: &str, total: usize, size: Option<u64>) {
+    if total == 0 {
+        return;
+    }
+    ui.strong(label);
+    match size {
+        Some(size) => ui.label(trf("{} files ({})", &[&total, &format_file_size(size)])),
+        None => ui.label(trf("{} files", &[&total])),
+    };
+    ui.end_row();
+}
+
+fn render_summary(ui: &mut egui::Ui, summary: &ExecutionSummary) {
+    egui::Grid::new("execution_summary")
+        .num_columns(2)
+        .striped(true)
+        .show(ui, |ui| {
+            if summary.total_folders > 1 {
+                ui.strong(tr("Folders"));
+                ui.label(format!("{}", summary.total_folders));
+                ui.end_row();
+            }
+            render_summary_row(ui, tr("Permanently deleted"), summary.total_permanent_deletes, Some(summary.permanent_delete_size));
+            render_summary_row(ui, tr("Moved to trash"), summary.total_trashed, Some(summary.trashed_size));
+            render_summary_row(ui, tr("Renamed"), summary.total_moves, Some(summary.move_size));
+            render_summary_row(ui, tr("Archived"), summary.total_archives, None);
+            render_summary_row(ui, tr("Skipped since the destination is taken"), summary.total_skipped_conflicts, None);
+            render_summary_row(ui, tr("Skipped since they failed repeatedly"), summary.total_skipped_failures, None);
+        });
+}
+
+// Only kinds which are part of the execution can be left out
+fn render_filter_checkbox(ui: &mut egui::Ui, is_included: &mut bool, total: usize, label: &str) {
+    if total > 0 {
+        ui.checkbox(is_included, label);
+    }
+}
+
+fn render_filter(ui: &mut egui::Ui, filter: &mut ExecutionFilter, summary: &ExecutionSummary) {
+    ui.horizontal(|ui| {
+        ui.strong(tr("Apply"));
+        render_filter_checkbox(ui, &mut filter.is_renames, summary.total_moves, tr("Renames"));
+        render_filter_checkbox(ui, &mut filter.is_deletes, summary.total_trashed + summary.total_permanent_deletes, tr("Deletes"));
+        render_filter_checkbox(ui, &mut filter.is_archives, summary.total_archives, tr("Archives"));
+    });
+}
+
+pub fn render_execute_confirmation(ctx: &egui::Context) {
+    let mut state = match CONFIRMATION.lock() {
+        Ok(state) => state,
+        Err(_) => return,
+    };
+    let (summary, is_filterable) = match state.pending.as_ref() {
+        Some((request, summary)) => (summary.clone(), request.is_filterable()),
+        None => return,
+    };
+
+    let mut is_open = true;
+    let mut is_confirmed = false;
+    let mut is_cancelled = false;
+    egui::Window::new(tr("Confirm Execution"))
+        .id(egui::Id::new("execute_confirmation"))
+        .collapsible(false)
+        .resizable(false)
+        .open(&mut is_open)
+        .show(ctx, |ui| {
+            if summary.is_destructive() {
+                ui.horizontal(|ui| {
+                    render_icon(ui, Icon::Warning, IconTone::Error);
+                    ui.label(tr("Executing these changes deletes files. Existing files are never overwritten."));
+                });
+            } else {
+                ui.label(tr("Only renames which change nothing but the episode title are applied, everything else stays pending."));
+            }
+            ui.separator();
+            render_summary(ui, &summary);
+            ui.separator();
+            if is_filterable {
+                render_filter(ui, &mut state.filter, &summary);
+                ui.separator();
+            }
+            if summary.is_destructive() {
+                ui.checkbox(&mut state.is_skipped_for_session, tr("Don't ask again for this session"));
+            }
+            let total_changes = summary.get_filtered(&state.filter).get_total_changes();
+            ui.horizontal(|ui| {
+                is_confirmed = ui.add_enabled(total_changes > 0, egui::Button::new(tr("Execute"))).clicked();
+                is_cancelled = ui.button(tr("Cancel")).clicked();
+            });
+        });
+
+    if is_confirmed {
+        let filter = std::mem::take(&mut state.filter);
+        if let Some((request, _)) = state.pending.take() {
+            tokio::spawn(async move {
+                request.execute(filter).await
+            });
+        }
+    } else if is_cancelled || !is_open {
+        state.pending = None;
+        state.filter = ExecutionFilter::default();
+        state.is_skipped_for_session = false;
+    }
+}
@@ -0,0 +1,151 @@
+use app::app_folder::AppFolder;
+use app::file_descriptor::find_tags;
+use app::tvdb_cache::EpisodeKey;
+use egui;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use crate::i18n::tr;
+
+// Only the most common tags are offered since every release group adds its own
+const MAX_TAG_CHIPS: usize = 12;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FileFilterChip {
+    Season(u32),
+    Extension(String),
+    Tag(String),
+}
+
+impl FileFilterChip {
+    fn get_label(&self) -> String {
+        match self {
+            FileFilterChip::Season(season) => format!("S{:02}", season),
+            FileFilterChip::Extension(extension) => format!(".{}", extension),
+            FileFilterChip::Tag(tag) => format!("[{}]", tag),
+        }
+    }
+
+    fn is_same_kind(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+fn get_extension(src: &str) -> Option<String> {
+    Path::new(src).extension().map(|extension| extension.to_string_lossy().to_lowercase())
+}
+
+// Chips of the same kind are alternatives while different kinds narrow the list down further
+// E.g. "S02" and ".srt" only keeps the subtitles of season 2
+pub struct FileFilters {
+    selected: Vec<FileFilterChip>,
+    // Parsing the tags of every file on every frame is too slow for large folders
+    tags_cache: HashMap<String, Vec<String>>,
+}
+
+impl Default for FileFilters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FileFilters {
+    pub fn new() -> Self {
+        Self {
+            selected: Vec::new(),
+            tags_cache: HashMap::new(),
+        }
+    }
+
+    fn get_tags(&mut self, src: &str) -> &[String] {
+        self.tags_cache
+            .entry(src.to_string())
+            .or_insert_with(|| {
+                let filename = Path::new(src).file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+                find_tags(filename.as_str())
+            })
+            .as_slice()
+    }
+
+    fn is_chip_match(&mut self, chip: &FileFilterChip, src: &str, descriptor: Option<&EpisodeKey>) -> bool {
+        match chip {
+            FileFilterChip::Season(season) => descriptor.map(|key| key.season == *season).unwrap_or(false),
+            FileFilterChip::Extension(extension) => get_extension(src).as_deref() == Some(extension.as_str()),
+            FileFilterChip::Tag(tag) => self.get_tags(src).iter().any(|other| other.eq_ignore_ascii_case(tag)),
+        }
+    }
+
+    pub fn is_match(&mut self, src: &str, descriptor: Option<&EpisodeKey>) -> bool {
+        if self.selected.is_empty() {
+            return true;
+        }
+        let selected = self.selected.clone();
+        let mut kinds: Vec<&FileFilterChip> = Vec::new();
+        for chip in selected.iter() {
+            if !kinds.iter().any(|other| other.is_same_kind(chip)) {
+                kinds.push(chip);
+            }
+        }
+        kinds.iter().all(|kind| {
+            selected
+                .iter()
+                .filter(|chip| chip.is_same_kind(kind))
+                .any(|chip| self.is_chip_match(chip, src, descriptor))
+        })
+    }
+
+    fn get_chip_counts(&mut self, folder: &AppFolder) -> Vec<(FileFilterChip, usize)> {
+        let mut counts: BTreeMap<FileFilterChip, usize> = BTreeMap::new();
+        let mut tag_counts: HashMap<String, usize> = HashMap::new();
+        let files = folder.get_files_blocking();
+        let total_files = files.to_iter().count();
+        // drop entries of files that were renamed or removed
+        if self.tags_cache.len() > total_files * 2 {
+            self.tags_cache.clear();
+        }
+        for file in files.to_iter() {
+            if let Some(key) = file.get_src_descriptor() {
+                *counts.entry(FileFilterChip::Season(key.season)).or_default() += 1;
+            }
+            if let Some(extension) = get_extension(file.get_src()) {
+                *counts.entry(FileFilterChip::Extension(extension)).or_default() += 1;
+            }
+            for tag in self.get_tags(file.get_src()) {
+                *tag_counts.entry(tag.to_uppercase()).or_default() += 1;
+            }
+        }
+
+        let mut tag_counts: Vec<(String, usize)> = tag_counts.into_iter().collect();
+        tag_counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let mut chips: Vec<(FileFilterChip, usize)> = counts.into_iter().collect();
+        chips.extend(tag_counts.into_iter().take(MAX_TAG_CHIPS).map(|(tag, count)| (FileFilterChip::Tag(tag), count)));
+        // selected chips stay visible even if no file has them anymore
+        for chip in self.selected.iter() {
+            if !chips.iter().any(|(other, _)| other == chip) {
+                chips.push((chip.clone(), 0));
+            }
+        }
+        chips
+    }
+}
+
+pub fn render_filter_chips(ui: &mut egui::Ui, filters: &mut FileFilters, folder: &AppFolder) {
+    let chips = filters.get_chip_counts(folder);
+    if chips.is_empty() {
+        return;
+    }
+    ui.horizontal_wrapped(|ui| {
+        for (chip, count) in chips {
+            let is_selected = filters.selected.contains(&chip);
+            let label = format!("{} ({})", chip.get_label(), count);
+            if ui.selectable_label(is_selected, label).clicked() {
+                match is_selected {
+                    true => filters.selected.retain(|other| *other != chip),
+                    false => filters.selected.push(chip),
+                }
+            }
+        }
+        if !filters.selected.is_empty() && ui.button(tr("Clear filters")).clicked() {
+            filters.selected.clear();
+        }
+    });
+}
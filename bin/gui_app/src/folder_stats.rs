@@ -0,0 +1,100 @@
+use app::app::App;
+use app::app_folder::FolderStatus;
+use app::file_intent::Action;
+use egui;
+use enum_map;
+use crate::action_display::get_action_display;
+use crate::helpers::format_file_size;
+use crate::i18n::tr;
+
+pub fn render_folder_stats(ui: &mut egui::Ui, app: &App) {
+    let mut status_counts: enum_map::EnumMap<FolderStatus, usize> = enum_map::enum_map! { _ => 0 };
+    let mut action_counts: enum_map::EnumMap<Action, usize> = enum_map::enum_map! { _ => 0 };
+    let mut action_sizes: enum_map::EnumMap<Action, u64> = enum_map::enum_map! { _ => 0 };
+    let mut total_busy = 0;
+    let mut total_reclaimable: u64 = 0;
+    let mut total_extras: u64 = 0;
+    let mut total_excluded = 0;
+
+    let folders = app.get_folders().blocking_read();
+    for folder in folders.iter() {
+        // Music and book folders would only inflate the counts
+        if folder.get_exclusion().blocking_read().is_some() {
+            total_excluded += 1;
+            continue;
+        }
+        if folder.get_busy_lock().try_lock().is_err() {
+            total_busy += 1;
+        }
+        status_counts[folder.get_last_known_status_blocking()] += 1;
+        // Skip folders that are being updated rather than stall the ui
+        if let Ok(file_tracker) = folder.get_file_tracker().try_read() {
+            for action in Action::iterator() {
+                action_counts[*action] += file_tracker.get_action_count()[*action];
+                action_sizes[*action] += file_tracker.get_action_size()[*action];
+            }
+            total_reclaimable += file_tracker.get_reclaimable_size();
+            total_extras += file_tracker.get_extras_size();
+        }
+    }
+
+    egui::Grid::new("folder_stats")
+        .num_columns(2)
+        .striped(true)
+        .show(ui, |ui| {
+            ui.strong(tr("Folders"));
+            ui.label(format!("{}", folders.len() - total_excluded));
+            ui.end_row();
+
+            ui.strong(tr("Not a TV series"));
+            ui.label(format!("{}", total_excluded));
+            ui.end_row();
+
+            ui.strong(tr("Busy"));
+            ui.label(format!("{}", total_busy));
+            ui.end_row();
+
+            for status in FolderStatus::iterator() {
+                ui.strong(tr(status.to_str()));
+                ui.label(format!("{}", status_counts[*status]));
+                ui.end_row();
+            }
+        });
+
+    ui.separator();
+
+    egui::Grid::new("file_stats")
+        .num_columns(3)
+        .striped(true)
+        .show(ui, |ui| {
+            ui.strong(tr("Action"));
+            ui.strong(tr("Files"));
+            ui.strong(tr("Size"));
+            ui.end_row();
+
+            let display = get_action_display();
+            for action in display.get_ordered_actions() {
+                ui.label(display.get_label(action, tr(action.to_str())));
+                ui.label(format!("{}", action_counts[action]));
+                ui.label(format_file_size(action_sizes[action]));
+                ui.end_row();
+            }
+        });
+
+    ui.separator();
+
+    egui::Grid::new("disk_space_stats")
+        .num_columns(2)
+        .striped(true)
+        .show(ui, |ui| {
+            ui.strong(tr("Reclaimable"))
+                .on_hover_text(tr("Space freed by executing the pending deletes"));
+            ui.label(format_file_size(total_reclaimable));
+            ui.end_row();
+
+            ui.strong(tr("Whitelisted and extras"))
+                .on_hover_text(tr("Space used by files kept alongside the episodes"));
+            ui.label(format_file_size(total_extras));
+            ui.end_row();
+        });
+}
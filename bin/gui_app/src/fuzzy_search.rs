@@ -1,4 +1,14 @@
 use egui;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use crate::i18n::tr;
+
+// Waits for a pause in typing before filtering so every keystroke doesn't start a search
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(200);
+// Results are published after each chunk so the first matches show up straight away
+const SEARCH_CHUNK_SIZE: usize = 512;
 
 pub struct FuzzySearcher {
     search_edit_line: String,
@@ -35,6 +45,11 @@ impl FuzzySearcher {
         }
     }
 
+    pub fn set_search(&mut self, search: &str) {
+        self.search_edit_line = search.to_string();
+        self.update_search_filtered();
+    }
+
     pub fn search(&mut self, input: &str) -> bool {
         if self.search_edit_line_filtered.is_empty() {
             return true;
@@ -61,7 +76,7 @@ pub fn render_search_bar(ui: &mut egui::Ui, search_bar: &mut FuzzySearcher) {
         .with_main_align(egui::Align::LEFT);
 
     ui.with_layout(layout, |ui| {
-        if ui.button("Clear").clicked() {
+        if ui.button(tr("Clear")).clicked() {
             search_bar.search_edit_line.clear();
             search_bar.update_search_filtered();
         }
@@ -77,3 +92,126 @@ pub fn render_search_bar(ui: &mut egui::Ui, search_bar: &mut FuzzySearcher) {
     });
 }
 
+
+pub struct SearchResults {
+    matches: HashSet<String>,
+    is_done: bool,
+}
+
+impl SearchResults {
+    pub fn is_match(&self, entry: &str) -> bool {
+        self.matches.contains(entry)
+    }
+}
+
+// Filters large lists on a worker thread, stale searches are abandoned as soon as the search changes
+pub struct BackgroundSearcher {
+    search_edit_line: String,
+    last_edit: Option<Instant>,
+    total_searched_entries: usize,
+    generation: Arc<AtomicU64>,
+    // None when there is nothing to search for so every entry matches
+    results: Arc<Mutex<Option<SearchResults>>>,
+}
+
+impl Default for BackgroundSearcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BackgroundSearcher {
+    pub fn new() -> Self {
+        Self {
+            search_edit_line: "".to_owned(),
+            last_edit: None,
+            total_searched_entries: 0,
+            generation: Arc::new(AtomicU64::new(0)),
+            results: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    fn is_search_pending(&self) -> bool {
+        self.last_edit.is_some()
+    }
+
+    pub fn is_searching(&self) -> bool {
+        self.is_search_pending() || self.get_results().as_ref().map(|results| !results.is_done).unwrap_or(false)
+    }
+
+    pub fn get_results(&self) -> MutexGuard<'_, Option<SearchResults>> {
+        self.results.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    // Entries are only gathered when a search is started
+    pub fn update(&mut self, total_entries: usize, get_entries: impl FnOnce() -> Vec<String>) {
+        let is_entries_changed = total_entries != self.total_searched_entries && !self.search_edit_line.is_empty();
+        match self.last_edit {
+            Some(last_edit) if last_edit.elapsed() >= SEARCH_DEBOUNCE => {},
+            None if is_entries_changed => {},
+            _ => return,
+        }
+        self.last_edit = None;
+        self.total_searched_entries = total_entries;
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        if self.search_edit_line.is_empty() {
+            *self.get_results() = None;
+            return;
+        }
+
+        let mut searcher = FuzzySearcher::new();
+        searcher.set_search(self.search_edit_line.as_str());
+        let entries = get_entries();
+        let current_generation = self.generation.clone();
+        let results = self.results.clone();
+        tokio::task::spawn_blocking(move || {
+            let total_chunks = entries.chunks(SEARCH_CHUNK_SIZE).len().max(1);
+            let mut chunks = entries.chunks(SEARCH_CHUNK_SIZE);
+            for index in 0..total_chunks {
+                let chunk = chunks.next().unwrap_or_default();
+                let matches: Vec<&String> = chunk.iter().filter(|entry| searcher.search(entry.as_str())).collect();
+                if current_generation.load(Ordering::SeqCst) != generation {
+                    return;
+                }
+                let mut results = results.lock().unwrap_or_else(|err| err.into_inner());
+                // the previous results are kept until the first chunk is done to avoid flickering
+                if index == 0 {
+                    *results = Some(SearchResults { matches: HashSet::new(), is_done: false });
+                }
+                if let Some(results) = results.as_mut() {
+                    results.matches.extend(matches.into_iter().cloned());
+                    results.is_done = index + 1 == total_chunks;
+                }
+            }
+        });
+    }
+}
+
+pub fn render_background_search_bar(ui: &mut egui::Ui, search_bar: &mut BackgroundSearcher) {
+    let layout = egui::Layout::right_to_left(egui::Align::Min)
+        .with_cross_justify(false)
+        .with_main_justify(false)
+        .with_main_wrap(false)
+        .with_main_align(egui::Align::LEFT);
+
+    ui.with_layout(layout, |ui| {
+        if ui.button(tr("Clear")).clicked() && !search_bar.search_edit_line.is_empty() {
+            search_bar.search_edit_line.clear();
+            // clearing doesn't need to wait for the debounce
+            search_bar.last_edit = Some(Instant::now() - SEARCH_DEBOUNCE);
+        }
+        if search_bar.is_searching() {
+            ui.spinner();
+            ui.ctx().request_repaint_after(Duration::from_millis(50));
+        }
+        let elem = egui::TextEdit::singleline(&mut search_bar.search_edit_line);
+        let size = egui::vec2(
+            ui.available_width(),
+            ui.spacing().interact_size.y,
+        );
+        let res = ui.add_sized(size, elem);
+        if res.changed() {
+            search_bar.last_edit = Some(Instant::now());
+        }
+    });
+}
@@ -9,3 +9,39 @@ pub fn render_invisible_width_widget(ui: &mut egui::Ui) {
     });
 }
 
+
+pub fn format_file_size(size: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = size as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len()-1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", size, UNITS[unit])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+pub fn format_duration(duration: std::time::Duration) -> String {
+    let seconds = duration.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, (seconds / 60) % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}h {:02}m {:02}s", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m {:02}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+// Icon only widgets need a readable label for screen readers
+pub fn set_accessible_label(res: &egui::Response, widget_type: egui::WidgetType, label: &str) {
+    res.widget_info(|| egui::WidgetInfo::labeled(widget_type, label));
+}
+
+pub fn set_accessible_toggle(res: &egui::Response, label: &str, is_selected: bool) {
+    res.widget_info(|| egui::WidgetInfo::selected(egui::WidgetType::Checkbox, is_selected, label));
+}
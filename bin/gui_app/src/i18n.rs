@@ -0,0 +1,74 @@
+use enum_map;
+use lazy_static::lazy_static;
+use serde;
+use serde_json;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Translations are keyed by the english text so untranslated strings fall back to english
+#[derive(serde::Serialize, serde::Deserialize, Debug, Copy, Clone, PartialEq, Eq, enum_map::Enum)]
+pub enum Language {
+    English,
+    German,
+}
+
+impl Language {
+    pub fn iterator() -> std::slice::Iter<'static, Self> {
+        static LANGUAGES: [Language;2] = [
+            Language::English,
+            Language::German,
+        ];
+        LANGUAGES.iter()
+    }
+
+    // Languages are listed in their own language so they can be found without understanding the current one
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+        }
+    }
+}
+
+lazy_static! {
+    static ref TRANSLATIONS: enum_map::EnumMap<Language, HashMap<String, String>> = enum_map::enum_map! {
+        Language::English => HashMap::new(),
+        Language::German => serde_json::from_str(include_str!("../locales/de.json"))
+            .expect("Embedded german translations are valid json"),
+    };
+}
+
+static CURRENT_LANGUAGE: AtomicUsize = AtomicUsize::new(0);
+
+pub fn get_language() -> Language {
+    let index = CURRENT_LANGUAGE.load(Ordering::Relaxed);
+    <Language as enum_map::Enum>::from_usize(index)
+}
+
+pub fn set_language(language: Language) {
+    let index = <Language as enum_map::Enum>::into_usize(language);
+    CURRENT_LANGUAGE.store(index, Ordering::Relaxed);
+}
+
+pub fn tr(text: &'static str) -> &'static str {
+    match TRANSLATIONS[get_language()].get(text) {
+        Some(translation) => translation.as_str(),
+        None => text,
+    }
+}
+
+// Substitutes each "{}" in the translated text with the next argument
+pub fn trf(text: &'static str, args: &[&dyn std::fmt::Display]) -> String {
+    let mut args = args.iter();
+    let mut output = String::new();
+    let mut parts = tr(text).split("{}").peekable();
+    while let Some(part) = parts.next() {
+        output.push_str(part);
+        if parts.peek().is_some() {
+            if let Some(arg) = args.next() {
+                output.push_str(arg.to_string().as_str());
+            }
+        }
+    }
+    output
+}
@@ -0,0 +1,132 @@
+use egui;
+use egui_extras::image::{load_svg_bytes_with_size, FitTo};
+use enum_map;
+use std::collections::HashMap;
+
+// Icons are drawn in white so they can be tinted to match the current theme
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, enum_map::Enum)]
+pub enum Icon {
+    Unknown,
+    Empty,
+    Pending,
+    Done,
+    Busy,
+    LoggedIn,
+    LoggedOut,
+    Settings,
+    Activity,
+    Favourite,
+    Unread,
+    Warning,
+    Play,
+    Folder,
+    Reveal,
+    NewWindow,
+    Copy,
+    Pin,
+    Watched,
+}
+
+impl Icon {
+    fn get_svg(&self) -> &'static [u8] {
+        match self {
+            Icon::Unknown => include_bytes!("../icons/unknown.svg"),
+            Icon::Empty => include_bytes!("../icons/empty.svg"),
+            Icon::Pending => include_bytes!("../icons/pending.svg"),
+            Icon::Done => include_bytes!("../icons/done.svg"),
+            Icon::Busy => include_bytes!("../icons/busy.svg"),
+            Icon::LoggedIn => include_bytes!("../icons/logged_in.svg"),
+            Icon::LoggedOut => include_bytes!("../icons/logged_out.svg"),
+            Icon::Settings => include_bytes!("../icons/settings.svg"),
+            Icon::Activity => include_bytes!("../icons/activity.svg"),
+            Icon::Favourite => include_bytes!("../icons/favourite.svg"),
+            Icon::Unread => include_bytes!("../icons/unread.svg"),
+            Icon::Warning => include_bytes!("../icons/warning.svg"),
+            Icon::Play => include_bytes!("../icons/play.svg"),
+            Icon::Folder => include_bytes!("../icons/folder.svg"),
+            Icon::Reveal => include_bytes!("../icons/reveal.svg"),
+            Icon::NewWindow => include_bytes!("../icons/new_window.svg"),
+            Icon::Copy => include_bytes!("../icons/copy.svg"),
+            Icon::Pin => include_bytes!("../icons/pin.svg"),
+            Icon::Watched => include_bytes!("../icons/watched.svg"),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum IconTone {
+    Normal,
+    Inactive,
+    Success,
+    Error,
+    Info,
+    Highlight,
+}
+
+impl IconTone {
+    // Dark colours are unreadable on the dark theme and vice versa
+    pub fn get_color(&self, visuals: &egui::Visuals) -> egui::Color32 {
+        match (self, visuals.dark_mode) {
+            (IconTone::Normal, _) => visuals.text_color(),
+            (IconTone::Inactive, _) => visuals.weak_text_color(),
+            (IconTone::Success, true) => egui::Color32::LIGHT_GREEN,
+            (IconTone::Success, false) => egui::Color32::DARK_GREEN,
+            (IconTone::Error, true) => egui::Color32::LIGHT_RED,
+            (IconTone::Error, false) => egui::Color32::DARK_RED,
+            (IconTone::Info, true) => egui::Color32::LIGHT_BLUE,
+            (IconTone::Info, false) => egui::Color32::DARK_BLUE,
+            (IconTone::Highlight, true) => egui::Color32::GOLD,
+            (IconTone::Highlight, false) => egui::Color32::from_rgb(200, 140, 0),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct IconTextures {
+    textures: HashMap<(Icon, u32), Option<egui::TextureHandle>>,
+}
+
+// Rasterise at the physical pixel size so icons stay sharp on high dpi displays
+fn get_icon_texture(ctx: &egui::Context, icon: Icon, size: f32) -> Option<egui::TextureId> {
+    let pixels = (size * ctx.pixels_per_point()).round().max(1.0) as u32;
+    let id = egui::Id::new("icon_textures");
+    let texture = ctx.memory(|memory| {
+        memory.data.get_temp::<IconTextures>(id)
+            .and_then(|cache| cache.textures.get(&(icon, pixels)).cloned())
+    });
+    let texture = match texture {
+        Some(texture) => texture,
+        None => {
+            let texture = load_svg_bytes_with_size(icon.get_svg(), FitTo::Size(pixels, pixels))
+                .ok()
+                .map(|image| ctx.load_texture(format!("icon_{:?}_{}", icon, pixels), image, egui::TextureOptions::LINEAR));
+            ctx.memory_mut(|memory| {
+                let cache = memory.data.get_temp_mut_or_default::<IconTextures>(id);
+                cache.textures.insert((icon, pixels), texture.clone());
+            });
+            texture
+        },
+    };
+    texture.map(|texture| texture.id())
+}
+
+pub fn get_icon_size(ui: &egui::Ui) -> f32 {
+    ui.text_style_height(&egui::TextStyle::Body)
+}
+
+pub fn create_icon_image<'a>(ui: &egui::Ui, icon: Icon, tone: IconTone) -> egui::Image<'a> {
+    let size = get_icon_size(ui);
+    let texture_id = get_icon_texture(ui.ctx(), icon, size).unwrap_or_default();
+    egui::Image::from_texture(egui::load::SizedTexture::new(texture_id, egui::vec2(size, size)))
+        .tint(tone.get_color(ui.visuals()))
+}
+
+pub fn render_icon(ui: &mut egui::Ui, icon: Icon, tone: IconTone) -> egui::Response {
+    let image = create_icon_image(ui, icon, tone);
+    ui.add(image)
+}
+
+pub fn render_icon_toggle(ui: &mut egui::Ui, icon: Icon, is_selected: bool) -> egui::Response {
+    let image = create_icon_image(ui, icon, IconTone::Normal);
+    ui.add(egui::ImageButton::new(image).selected(is_selected))
+}
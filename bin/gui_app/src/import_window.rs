@@ -0,0 +1,146 @@
+use app::app::App;
+use egui;
+use std::sync::Arc;
+use tokio;
+use crate::i18n::{tr, trf};
+use crate::progress_bar::render_operation_progress;
+
+pub struct GuiImport {
+    source_path: String,
+}
+
+impl GuiImport {
+    pub fn new() -> Self {
+        Self {
+            source_path: "".to_string(),
+        }
+    }
+}
+
+impl Default for GuiImport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn render_import_source(ui: &mut egui::Ui, gui: &mut GuiImport, app: &Arc<App>) {
+    // Downloads can be dropped onto the window instead of typing their path
+    let dropped_path = ui.ctx().input(|i| i.raw.dropped_files.iter().find_map(|file| file.path.clone()));
+    if let Some(path) = dropped_path {
+        gui.source_path = path.to_string_lossy().to_string();
+    }
+
+    let is_logged_in = app.get_login_session().blocking_read().is_some();
+    let layout = egui::Layout::right_to_left(egui::Align::Min)
+        .with_cross_justify(false)
+        .with_main_justify(false)
+        .with_main_wrap(false)
+        .with_main_align(egui::Align::LEFT);
+    ui.with_layout(layout, |ui| {
+        let mut is_pressed = false;
+        ui.add_enabled_ui(is_logged_in, |ui| {
+            let res = ui.button(tr("Plan import"));
+            is_pressed = res.clicked();
+            res.on_disabled_hover_ui(|ui| {
+                ui.label(tr("Not logged in"));
+            });
+        });
+
+        let elem = egui::TextEdit::singleline(&mut gui.source_path).hint_text(tr("Path to download"));
+        let size = egui::vec2(
+            ui.available_width(),
+            ui.spacing().interact_size.y,
+        );
+        let line_res = ui.add_sized(size, elem);
+
+        let is_entered = line_res.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        if is_logged_in && (is_pressed || is_entered) {
+            tokio::spawn({
+                let source_path = gui.source_path.clone();
+                let app = app.clone();
+                async move {
+                    app.plan_import(source_path).await
+                }
+            });
+        }
+    });
+}
+
+fn render_import_plan(ui: &mut egui::Ui, app: &Arc<App>) {
+    let mut plan = app.get_import_plan().blocking_write();
+    let plan = match plan.as_mut() {
+        Some(plan) => plan,
+        None => {
+            ui.label(tr("No import has been planned yet"));
+            return;
+        },
+    };
+
+    ui.horizontal(|ui| {
+        ui.strong(tr("Search"));
+        ui.label(plan.search_name.as_str());
+    });
+
+    ui.strong(tr("Series"));
+    if plan.candidates.is_empty() {
+        ui.label(tr("Search gave no results"));
+    }
+    egui::ScrollArea::vertical()
+        .id_source("import_candidates")
+        .max_height(ui.available_height() * 0.5)
+        .show(ui, |ui| {
+            let layout = egui::Layout::top_down(egui::Align::Min).with_cross_justify(true);
+            ui.with_layout(layout, |ui| {
+                for (index, candidate) in plan.candidates.iter().enumerate() {
+                    let folder_label = match candidate.is_new_folder {
+                        true => trf("New folder '{}'", &[&candidate.folder_name]),
+                        false => trf("Existing folder '{}'", &[&candidate.folder_name]),
+                    };
+                    let first_aired = candidate.series.first_aired.as_deref().unwrap_or(tr("Unknown"));
+                    let label = format!("{} ({}) → {}", candidate.series.name, first_aired, folder_label);
+                    let is_selected = plan.selected_candidate == Some(index);
+                    if ui.selectable_label(is_selected, label).clicked() {
+                        plan.selected_candidate = Some(index);
+                    }
+                }
+            });
+        });
+
+    ui.separator();
+    ui.strong(tr("Files"));
+    egui::ScrollArea::vertical()
+        .id_source("import_files")
+        .max_height(ui.available_height() - ui.spacing().interact_size.y * 2.0)
+        .show(ui, |ui| {
+            for file in plan.files.iter() {
+                ui.label(file.as_str());
+            }
+        });
+
+    ui.separator();
+    let is_selected = plan.get_selected_candidate().is_some();
+    ui.add_enabled_ui(is_selected && !plan.files.is_empty(), |ui| {
+        if ui.button(tr("Import")).clicked() {
+            tokio::spawn({
+                let app = app.clone();
+                async move {
+                    app.execute_import().await
+                }
+            });
+        }
+    });
+}
+
+pub fn render_import_window(ui: &mut egui::Ui, gui: &mut GuiImport, app: &Arc<App>) {
+    let is_not_busy = app.get_import_busy_lock().try_lock().is_ok();
+    ui.add_enabled_ui(is_not_busy, |ui| {
+        render_import_source(ui, gui, app);
+    });
+    ui.separator();
+    if !is_not_busy {
+        ui.spinner();
+        render_operation_progress(ui, app);
+        return;
+    }
+    render_import_plan(ui, app);
+}
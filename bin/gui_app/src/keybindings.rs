@@ -0,0 +1,266 @@
+use app::app::App;
+use app::file_intent::Action;
+use egui;
+use lazy_static::lazy_static;
+use serde;
+use serde_json;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use tokio;
+use crate::i18n::tr;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all="snake_case")]
+pub enum FolderClickAction {
+    Nothing,
+    #[default]
+    OpenOnDisk,
+    OpenDetail,
+    OpenInNewWindow,
+    Execute,
+}
+
+impl FolderClickAction {
+    pub fn iterator() -> std::slice::Iter<'static, Self> {
+        static ACTIONS: [FolderClickAction;5] = [
+            FolderClickAction::Nothing,
+            FolderClickAction::OpenOnDisk,
+            FolderClickAction::OpenDetail,
+            FolderClickAction::OpenInNewWindow,
+            FolderClickAction::Execute,
+        ];
+        ACTIONS.iter()
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            FolderClickAction::Nothing => "Nothing",
+            FolderClickAction::OpenOnDisk => "Open on disk",
+            FolderClickAction::OpenDetail => "Open detail",
+            FolderClickAction::OpenInNewWindow => "Open in new window",
+            FolderClickAction::Execute => "Execute changes",
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all="snake_case")]
+pub enum FileClickAction {
+    Nothing,
+    #[default]
+    OpenOnDisk,
+    OpenDetail,
+    RevealInFileManager,
+}
+
+impl FileClickAction {
+    pub fn iterator() -> std::slice::Iter<'static, Self> {
+        static ACTIONS: [FileClickAction;4] = [
+            FileClickAction::Nothing,
+            FileClickAction::OpenOnDisk,
+            FileClickAction::OpenDetail,
+            FileClickAction::RevealInFileManager,
+        ];
+        ACTIONS.iter()
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            FileClickAction::Nothing => "Nothing",
+            FileClickAction::OpenOnDisk => "Open on disk",
+            FileClickAction::OpenDetail => "Open detail",
+            FileClickAction::RevealInFileManager => "Reveal in file manager",
+        }
+    }
+}
+
+// egui::KeyboardShortcut isn't serialisable so we store its parts instead
+#[derive(serde::Serialize, serde::Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Keybinding {
+    pub modifiers: egui::Modifiers,
+    pub key: egui::Key,
+}
+
+impl Keybinding {
+    pub fn new(modifiers: egui::Modifiers, key: egui::Key) -> Self {
+        Self { modifiers, key }
+    }
+
+    pub fn to_shortcut(&self) -> egui::KeyboardShortcut {
+        egui::KeyboardShortcut::new(self.modifiers, self.key)
+    }
+}
+
+fn get_default_action_shortcuts() -> HashMap<Action, Keybinding> {
+    HashMap::from([
+        (Action::Delete, Keybinding::new(egui::Modifiers::NONE, egui::Key::Delete)),
+        (Action::Ignore, Keybinding::new(egui::Modifiers::ALT, egui::Key::I)),
+        (Action::Rename, Keybinding::new(egui::Modifiers::ALT, egui::Key::R)),
+        (Action::Whitelist, Keybinding::new(egui::Modifiers::ALT, egui::Key::W)),
+        (Action::Complete, Keybinding::new(egui::Modifiers::ALT, egui::Key::C)),
+    ])
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Keybindings {
+    #[serde(default)]
+    pub folder_double_click: FolderClickAction,
+    #[serde(default)]
+    pub file_double_click: FileClickAction,
+    // Actions without a shortcut have been unbound by the user
+    #[serde(default="get_default_action_shortcuts")]
+    pub action_shortcuts: HashMap<Action, Keybinding>,
+}
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        Self {
+            folder_double_click: FolderClickAction::default(),
+            file_double_click: FileClickAction::default(),
+            action_shortcuts: get_default_action_shortcuts(),
+        }
+    }
+}
+
+lazy_static! {
+    static ref KEYBINDINGS: RwLock<Keybindings> = RwLock::new(Keybindings::default());
+}
+
+pub fn get_keybindings() -> Keybindings {
+    KEYBINDINGS.read().map(|keybindings| keybindings.clone()).unwrap_or_default()
+}
+
+pub fn set_keybindings(keybindings: Keybindings) {
+    if let Ok(mut current) = KEYBINDINGS.write() {
+        *current = keybindings;
+    }
+}
+
+pub struct GuiKeybindings {
+    recording_action: Option<Action>,
+}
+
+impl GuiKeybindings {
+    pub fn new() -> Self {
+        Self {
+            recording_action: None,
+        }
+    }
+}
+
+impl Default for GuiKeybindings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Waits for the next key press so that any key combination can be bound
+fn record_keybinding(ui: &mut egui::Ui) -> Option<Option<Keybinding>> {
+    ui.input_mut(|i| {
+        let event = i.events.iter().find_map(|event| match event {
+            egui::Event::Key { key, pressed: true, modifiers, .. } => Some((*key, *modifiers)),
+            _ => None,
+        });
+        let (key, modifiers) = event?;
+        i.consume_key(modifiers, key);
+        match key {
+            egui::Key::Escape => Some(None),
+            _ => Some(Some(Keybinding::new(modifiers, key))),
+        }
+    })
+}
+
+fn render_action_shortcuts(ui: &mut egui::Ui, gui: &mut GuiKeybindings, keybindings: &mut Keybindings) {
+    egui::Grid::new("action_shortcuts")
+        .num_columns(3)
+        .striped(true)
+        .show(ui, |ui| {
+            for action in Action::iterator() {
+                let action = *action;
+                ui.label(tr(action.to_str()));
+
+                let is_recording = gui.recording_action == Some(action);
+                let label = match (is_recording, keybindings.action_shortcuts.get(&action)) {
+                    (true, _) => tr("Press a key...").to_string(),
+                    (false, Some(keybinding)) => ui.ctx().format_shortcut(&keybinding.to_shortcut()),
+                    (false, None) => tr("Unbound").to_string(),
+                };
+                if ui.selectable_label(is_recording, label).clicked() {
+                    gui.recording_action = match is_recording {
+                        true => None,
+                        false => Some(action),
+                    };
+                } else if is_recording {
+                    if let Some(keybinding) = record_keybinding(ui) {
+                        // Escape cancels without changing the current shortcut
+                        if let Some(keybinding) = keybinding {
+                            keybindings.action_shortcuts.retain(|_, other| *other != keybinding);
+                            keybindings.action_shortcuts.insert(action, keybinding);
+                        }
+                        gui.recording_action = None;
+                    }
+                }
+
+                if ui.button(tr("Clear")).clicked() {
+                    keybindings.action_shortcuts.remove(&action);
+                }
+                ui.end_row();
+            }
+        });
+}
+
+pub fn render_keybindings_settings(ui: &mut egui::Ui, gui: &mut GuiKeybindings, app: &Arc<App>) {
+    let mut keybindings = get_keybindings();
+
+    egui::Grid::new("double_click_actions")
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.strong(tr("Double click folder"));
+            egui::ComboBox::from_id_source("folder_double_click")
+                .selected_text(tr(keybindings.folder_double_click.to_str()))
+                .show_ui(ui, |ui| {
+                    for option in FolderClickAction::iterator() {
+                        ui.selectable_value(&mut keybindings.folder_double_click, *option, tr(option.to_str()));
+                    }
+                });
+            ui.end_row();
+
+            ui.strong(tr("Double click file"));
+            egui::ComboBox::from_id_source("file_double_click")
+                .selected_text(tr(keybindings.file_double_click.to_str()))
+                .show_ui(ui, |ui| {
+                    for option in FileClickAction::iterator() {
+                        ui.selectable_value(&mut keybindings.file_double_click, *option, tr(option.to_str()));
+                    }
+                });
+            ui.end_row();
+        });
+
+    ui.separator();
+    ui.strong(tr("File action shortcuts"));
+    render_action_shortcuts(ui, gui, &mut keybindings);
+
+    ui.separator();
+    if ui.button(tr("Reset to defaults")).clicked() {
+        keybindings = Keybindings::default();
+        gui.recording_action = None;
+    }
+
+    if keybindings != get_keybindings() {
+        save_keybindings(app, &keybindings);
+        set_keybindings(keybindings);
+    }
+}
+
+pub fn save_keybindings(app: &Arc<App>, keybindings: &Keybindings) {
+    let value = match serde_json::to_value(keybindings) {
+        Ok(value) => value,
+        Err(_) => return,
+    };
+    tokio::spawn({
+        let app = app.clone();
+        async move {
+            app.set_keybindings(value).await
+        }
+    });
+}
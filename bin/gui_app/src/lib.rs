@@ -1,14 +1,33 @@
 pub mod fuzzy_search;
+pub mod file_filters;
 pub mod clipped_selectable;
 pub mod helpers;
+pub mod i18n;
+pub mod icons;
 pub mod error_list;
+pub mod toasts;
+pub mod permission_report;
+pub mod skipped_files_report;
+pub mod activity_list;
 pub mod tvdb_tables;
 pub mod frame_history;
+pub mod metrics_panel;
 pub mod settings_menu;
+pub mod shortcuts;
+pub mod keybindings;
+pub mod action_display;
+pub mod action_popup;
+pub mod profiles_settings;
+pub mod network_settings;
+pub mod folder_stats;
+pub mod progress_bar;
+pub mod dock;
 
 pub mod app_bookmarks;
 pub mod app_file_actions;
+pub mod app_file_preview;
 pub mod app_folder_basic_list;
+pub mod app_folder_changes_list;
 pub mod app_folder_conflict_list;
 pub mod app_folder_delete_list;
 pub mod app_folder_rename_list;
@@ -16,7 +35,18 @@ pub mod app_folder_files_tab_list;
 pub mod app_folder_episode_cache_list;
 pub mod app_folder;
 
+pub mod recent_folders;
 pub mod app_folders_list;
 pub mod app_series_search;
+pub mod import_window;
+pub mod watchlist_list;
+pub mod calendar_list;
+pub mod recovery_window;
+pub mod diagnostics_window;
+pub mod trash_window;
+pub mod rebind_window;
+pub mod execute_confirmation;
+pub mod merge_confirmation;
+pub mod torrent_cleanup_window;
 
 pub mod app;
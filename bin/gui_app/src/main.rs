@@ -5,6 +5,7 @@
 )]
 
 use app::app::App;
+use app::daemon;
 use gui_app::app::GuiApp;
 use std::path::Path;
 use std::sync::Arc;
@@ -32,24 +33,61 @@ impl eframe::App for FailedGuiApp {
 }
 
 fn print_usage() {
-    println!("Usage: gui_app <folder_path> [config_path]");
+    println!("Usage: gui_app [--daemon] <folder_path | ssh://[user@]host[:port]/path> [config_path]");
+    println!("  --daemon  Runs without a gui, the folder and config paths can also be given by");
+    println!("            TORRENT_RENAMER_ROOT and TORRENT_RENAMER_CONFIG");
+}
+
+// Headless mode for running in a container next to the torrent client
+async fn run_daemon(root_path: Option<String>, config_path: String) -> Result<(), eframe::Error> {
+    let root_path = match root_path.or_else(|| std::env::var("TORRENT_RENAMER_ROOT").ok()) {
+        Some(root_path) => root_path,
+        None => {
+            print_usage();
+            return Ok(());
+        },
+    };
+    let app = match App::new(config_path.as_str()).await {
+        Ok(app) => Arc::new(app),
+        Err(err) => {
+            println!("Failed to create application: {}", err);
+            std::process::exit(1);
+        },
+    };
+    let settings = match app.get_filter_rules().read().await.daemon.with_env_overrides() {
+        Ok(settings) => settings,
+        Err(message) => {
+            println!("Invalid daemon settings: {}", message);
+            std::process::exit(1);
+        },
+    };
+    daemon::run_daemon(app, root_path, settings).await;
+    Ok(())
 }
 
 #[tokio::main]
 async fn main() -> Result<(), eframe::Error> {
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() <= 1 {
+    let mut args: Vec<String> = std::env::args().collect();
+    if args.contains(&"--help".to_owned()) || args.contains(&"-h".to_owned()) {
         print_usage();
         return Ok(());
-    };
+    }
 
-    if args.contains(&"--help".to_owned()) || args.contains(&"-h".to_owned()) {
+    let is_daemon = args.contains(&"--daemon".to_owned());
+    args.retain(|arg| arg != "--daemon");
+    let default_config_path = std::env::var("TORRENT_RENAMER_CONFIG")
+        .unwrap_or_else(|_| Path::new("./res").to_string_lossy().to_string());
+    if is_daemon {
+        let config_path = args.get(2).cloned().unwrap_or(default_config_path);
+        return run_daemon(args.get(1).cloned(), config_path).await;
+    }
+
+    if args.len() <= 1 {
         print_usage();
         return Ok(());
-    }
+    };
     
     let root_path = &args[1];
-    let default_config_path = Path::new("./res").to_string_lossy().to_string();
     let config_path = args.get(2).unwrap_or(&default_config_path);
 
     let native_options = eframe::NativeOptions::default();
@@ -61,7 +99,9 @@ async fn main() -> Result<(), eframe::Error> {
             native_options, 
             Box::new({
                 let root_path = root_path.clone();
-                move |_| {
+                move |cc| {
+                    egui_extras::install_image_loaders(&cc.egui_ctx);
+
                     let app = match app {
                         Ok(app) => Arc::new(app),
                         Err(err) => {
@@ -77,11 +117,12 @@ async fn main() -> Result<(), eframe::Error> {
                                 app.load_folders(root_path),
                                 app.login(),
                             );
+                            app.run_diagnostics().await;
                             res_0.or(res_1)
                         }
                     });
 
-                    let gui = GuiApp::new(app);
+                    let gui = GuiApp::new(app, cc.storage);
                     Box::new(gui)
                 }
             }),
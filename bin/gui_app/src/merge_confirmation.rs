@@ -0,0 +1,81 @@
+use app::app::App;
+use app::folder_merge::MergePlan;
+use egui;
+use lazy_static::lazy_static;
+use std::sync::{Arc, Mutex};
+use tokio;
+use crate::i18n::{tr, trf};
+use crate::icons::{Icon, IconTone, render_icon};
+
+struct PendingMerge {
+    app: Arc<App>,
+    plan: MergePlan,
+}
+
+lazy_static! {
+    static ref PENDING_MERGE: Mutex<Option<PendingMerge>> = Mutex::new(None);
+}
+
+// Folders which can't be merged report why in the errors instead of asking
+pub fn request_folder_merge(app: &Arc<App>, src_index: usize, dest_index: usize) {
+    let app = app.clone();
+    tokio::spawn(async move {
+        let plan = match app.get_merge_plan(src_index, dest_index).await {
+            Some(plan) => plan,
+            None => return,
+        };
+        if let Ok(mut pending) = PENDING_MERGE.lock() {
+            *pending = Some(PendingMerge { app, plan });
+        }
+    });
+}
+
+pub fn render_merge_confirmation(ctx: &egui::Context) {
+    let mut pending = match PENDING_MERGE.lock() {
+        Ok(pending) => pending,
+        Err(_) => return,
+    };
+    let plan = match pending.as_ref() {
+        Some(merge) => merge.plan.clone(),
+        None => return,
+    };
+
+    let mut is_open = true;
+    let mut is_confirmed = false;
+    let mut is_cancelled = false;
+    egui::Window::new(tr("Confirm Merge"))
+        .id(egui::Id::new("merge_confirmation"))
+        .collapsible(false)
+        .open(&mut is_open)
+        .show(ctx, |ui| {
+            ui.label(trf("Moves {} files from '{}' into '{}'", &[&plan.get_total_moves(), &plan.src_name, &plan.dest_name]));
+            let trashed_files = plan.get_trashed_files();
+            if !trashed_files.is_empty() {
+                ui.separator();
+                ui.horizontal(|ui| {
+                    render_icon(ui, Icon::Warning, IconTone::Error);
+                    ui.label(trf("{} duplicates are moved to the trash", &[&trashed_files.len()]));
+                });
+                egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                    for path in trashed_files.iter() {
+                        ui.label(path.as_str());
+                    }
+                });
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                is_confirmed = ui.button(tr("Merge")).clicked();
+                is_cancelled = ui.button(tr("Cancel")).clicked();
+            });
+        });
+
+    if is_confirmed {
+        if let Some(PendingMerge { app, plan }) = pending.take() {
+            tokio::spawn(async move {
+                app.merge_folders(&plan).await
+            });
+        }
+    } else if is_cancelled || !is_open {
+        *pending = None;
+    }
+}
@@ -0,0 +1,116 @@
+use app::metrics::get_metrics;
+use egui;
+use egui_extras::{Column, TableBuilder};
+use std::time::Duration;
+use crate::i18n::tr;
+
+// Recorded by the gui around each frame and each tab
+pub const FRAME_RENDER: &str = "Frame render";
+
+fn format_millis(duration: Duration) -> String {
+    format!("{:.2} ms", duration.as_secs_f64() * 1e3)
+}
+
+fn render_timers(ui: &mut egui::Ui) {
+    let timers = get_metrics().get_timers();
+    let row_height = 18.0;
+    let cell_layout = egui::Layout::left_to_right(egui::Align::Center).with_cross_justify(false);
+    TableBuilder::new(ui)
+        .vscroll(false)
+        .striped(true)
+        .resizable(true)
+        .cell_layout(cell_layout)
+        .column(Column::initial(150.0).resizable(true).clip(true))
+        .columns(Column::auto().resizable(true), 4)
+        .column(Column::remainder().resizable(false))
+        .header(row_height, |mut header| {
+            header.col(|ui| { ui.strong(tr("Name")); });
+            header.col(|ui| { ui.strong(tr("Count")); });
+            header.col(|ui| { ui.strong(tr("Mean")); });
+            header.col(|ui| { ui.strong(tr("Min")); });
+            header.col(|ui| { ui.strong(tr("Max")); });
+            header.col(|ui| { ui.strong(tr("Last")); });
+        })
+        .body(|mut body| {
+            for (name, stats) in timers.iter() {
+                body.row(row_height, |mut row| {
+                    row.col(|ui| { ui.label(tr(name)); });
+                    row.col(|ui| { ui.label(stats.count.to_string()); });
+                    row.col(|ui| { ui.label(format_millis(stats.get_mean())); });
+                    row.col(|ui| { ui.label(format_millis(stats.min)); });
+                    row.col(|ui| { ui.label(format_millis(stats.max)); });
+                    row.col(|ui| { ui.label(format_millis(stats.last)); });
+                });
+            }
+        });
+}
+
+fn render_hits(ui: &mut egui::Ui) {
+    let hits = get_metrics().get_hits();
+    let row_height = 18.0;
+    let cell_layout = egui::Layout::left_to_right(egui::Align::Center).with_cross_justify(false);
+    TableBuilder::new(ui)
+        .vscroll(false)
+        .striped(true)
+        .resizable(true)
+        .cell_layout(cell_layout)
+        .column(Column::initial(150.0).resizable(true).clip(true))
+        .columns(Column::auto().resizable(true), 2)
+        .column(Column::remainder().resizable(false))
+        .header(row_height, |mut header| {
+            header.col(|ui| { ui.strong(tr("Name")); });
+            header.col(|ui| { ui.strong(tr("Hits")); });
+            header.col(|ui| { ui.strong(tr("Misses")); });
+            header.col(|ui| { ui.strong(tr("Hit rate")); });
+        })
+        .body(|mut body| {
+            for (name, stats) in hits.iter() {
+                body.row(row_height, |mut row| {
+                    row.col(|ui| { ui.label(tr(name)); });
+                    row.col(|ui| { ui.label(stats.hits.to_string()); });
+                    row.col(|ui| { ui.label(stats.misses.to_string()); });
+                    row.col(|ui| {
+                        let rate = stats.get_hit_rate().map(|rate| format!("{:.1}%", rate * 100.0));
+                        ui.label(rate.unwrap_or_else(|| "-".to_string()));
+                    });
+                });
+            }
+        });
+}
+
+fn render_counters(ui: &mut egui::Ui) {
+    egui::Grid::new("metric_counters")
+        .num_columns(2)
+        .striped(true)
+        .show(ui, |ui| {
+            for (name, count) in get_metrics().get_counters() {
+                ui.label(tr(name));
+                ui.label(count.to_string());
+                ui.end_row();
+            }
+        });
+}
+
+pub fn render_metrics_panel(ui: &mut egui::Ui) {
+    ui.horizontal(|ui| {
+        let frame_time = get_metrics().get_timers()
+            .into_iter()
+            .find(|(name, _)| *name == FRAME_RENDER)
+            .map(|(_, stats)| stats.get_mean());
+        if let Some(frame_time) = frame_time {
+            ui.label(format!("{}: {}", tr("Mean frame time"), format_millis(frame_time)));
+        }
+        if ui.button(tr("Reset")).clicked() {
+            get_metrics().reset();
+        }
+    });
+    ui.separator();
+    ui.strong(tr("Timings"));
+    ui.push_id("timers", render_timers);
+    ui.separator();
+    ui.strong(tr("Caches"));
+    ui.push_id("hits", render_hits);
+    ui.separator();
+    ui.strong(tr("Counters"));
+    render_counters(ui);
+}
@@ -0,0 +1,117 @@
+use app::app::App;
+use app::http_client::{ConnectionTest, NetworkSettings};
+use egui;
+use std::sync::{Arc, Mutex};
+use tokio;
+use crate::i18n::{tr, trf};
+
+enum ConnectionTestState {
+    Running,
+    Done(Result<ConnectionTest, String>),
+}
+
+pub struct GuiNetworkSettings {
+    // Edits are kept until they are saved or reverted
+    draft: Option<NetworkSettings>,
+    connection_test: Arc<Mutex<Option<ConnectionTestState>>>,
+}
+
+impl GuiNetworkSettings {
+    pub fn new() -> Self {
+        Self {
+            draft: None,
+            connection_test: Arc::new(Mutex::new(None)),
+        }
+    }
+}
+
+impl Default for GuiNetworkSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Empty fields are left out of the config
+fn render_optional_text(ui: &mut egui::Ui, value: &mut Option<String>, hint: &str) {
+    let mut text = value.clone().unwrap_or_default();
+    if ui.add(egui::TextEdit::singleline(&mut text).hint_text(hint).desired_width(300.0)).changed() {
+        *value = Some(text).filter(|text| !text.trim().is_empty());
+    }
+}
+
+fn render_connection_test(ui: &mut egui::Ui, gui: &GuiNetworkSettings) {
+    let state = gui.connection_test.lock().unwrap_or_else(|err| err.into_inner());
+    match state.as_ref() {
+        None => {},
+        Some(ConnectionTestState::Running) => {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label(tr("Testing connection"));
+            });
+        },
+        Some(ConnectionTestState::Done(Ok(test))) => {
+            let message = trf("Connected in {} ms, the api answered with {}", &[&test.latency.as_millis(), &test.status]);
+            ui.label(message);
+        },
+        Some(ConnectionTestState::Done(Err(err))) => {
+            ui.colored_label(ui.visuals().error_fg_color, err.as_str());
+        },
+    }
+}
+
+pub fn render_network_settings(ui: &mut egui::Ui, gui: &mut GuiNetworkSettings, app: &Arc<App>) {
+    let saved = app.get_filter_rules().blocking_read().network.clone();
+    let draft = gui.draft.get_or_insert_with(|| saved.clone());
+
+    egui::Grid::new("network_settings")
+        .num_columns(2)
+        .striped(true)
+        .show(ui, |ui| {
+            ui.strong(tr("Proxy"));
+            render_optional_text(ui, &mut draft.proxy_url, "socks5://127.0.0.1:1080");
+            ui.end_row();
+
+            ui.strong(tr("CA certificates"));
+            render_optional_text(ui, &mut draft.ca_certificate_path, tr("Path to a PEM file"));
+            ui.end_row();
+        });
+    ui.weak(tr("Proxies can be http, https or socks5 urls, and certificates are trusted on top of the system ones"));
+
+    ui.separator();
+    let draft = draft.clone();
+    let is_changed = draft != saved;
+    let is_testing = matches!(*gui.connection_test.lock().unwrap_or_else(|err| err.into_inner()), Some(ConnectionTestState::Running));
+    ui.horizontal(|ui| {
+        if ui.add_enabled(is_changed, egui::Button::new(tr("Save"))).clicked() {
+            tokio::spawn({
+                let app = app.clone();
+                let draft = draft.clone();
+                async move {
+                    app.set_network_settings(draft).await
+                }
+            });
+        }
+        if ui.add_enabled(is_changed, egui::Button::new(tr("Revert"))).clicked() {
+            gui.draft = None;
+        }
+        let res = ui.add_enabled(!is_testing, egui::Button::new(tr("Test connection")));
+        if res.clicked() {
+            *gui.connection_test.lock().unwrap_or_else(|err| err.into_inner()) = Some(ConnectionTestState::Running);
+            tokio::spawn({
+                let app = app.clone();
+                let connection_test = gui.connection_test.clone();
+                let ctx = ui.ctx().clone();
+                async move {
+                    let result = app.test_network_settings(&draft).await;
+                    *connection_test.lock().unwrap_or_else(|err| err.into_inner()) = Some(ConnectionTestState::Done(result));
+                    ctx.request_repaint();
+                }
+            });
+        }
+        res.on_hover_text(tr("Tries the settings above against the tvdb api without saving them"));
+    });
+    if is_changed {
+        ui.label(tr("Unsaved changes"));
+    }
+    render_connection_test(ui, gui);
+}
@@ -0,0 +1,80 @@
+use app::app_folder::AppFolder;
+use app::permissions::PermissionIssue;
+use egui;
+use egui_extras::{Column, TableBuilder};
+use std::sync::Arc;
+use tokio;
+use crate::i18n::{tr, trf};
+use crate::icons::{Icon, IconTone, render_icon};
+
+fn render_permission_controls(ui: &mut egui::Ui, issues: &[PermissionIssue], folder: &Arc<AppFolder>) -> bool {
+    let mut is_dismissed = false;
+    let is_not_busy = folder.get_busy_lock().try_lock().is_ok();
+    let total_fixable = issues.iter().filter(|issue| issue.cause.is_fixable()).count();
+    ui.horizontal(|ui| {
+        render_icon(ui, Icon::Warning, IconTone::Error);
+        ui.strong(trf("{} changes were denied due to permissions", &[&issues.len()]));
+        ui.add_enabled_ui(is_not_busy && total_fixable > 0, |ui| {
+            let res = ui.button(tr("Clear read-only attributes"));
+            if res.clicked() {
+                tokio::spawn({
+                    let folder = folder.clone();
+                    async move {
+                        folder.clear_read_only_permission_issues().await
+                    }
+                });
+            }
+            res.on_hover_text(tr("Execute the changes again afterwards"))
+                .on_disabled_hover_ui(|ui| {
+                    if !is_not_busy { ui.label(tr("Folder is busy")); }
+                    else { ui.label(tr("None of the changes are blocked by a read-only attribute")); }
+                });
+        });
+        if ui.button(tr("Dismiss")).clicked() {
+            is_dismissed = true;
+        }
+    });
+    is_dismissed
+}
+
+pub fn render_permission_report(ui: &mut egui::Ui, folder: &Arc<AppFolder>) {
+    let mut issues = match folder.get_permission_issues().try_write() {
+        Ok(issues) => issues,
+        Err(_) => return,
+    };
+    if issues.is_empty() {
+        return;
+    }
+
+    if render_permission_controls(ui, issues.as_slice(), folder) {
+        issues.clear();
+        return;
+    }
+
+    let row_height = 18.0;
+    let cell_layout = egui::Layout::left_to_right(egui::Align::Center).with_cross_justify(false);
+    TableBuilder::new(ui)
+        .striped(true)
+        .resizable(true)
+        .cell_layout(cell_layout)
+        .column(Column::initial(250.0).resizable(true).clip(true))
+        .column(Column::auto().resizable(true))
+        .column(Column::remainder().resizable(false).clip(true))
+        .max_scroll_height(150.0)
+        .header(row_height, |mut header| {
+            header.col(|ui| { ui.strong(tr("File")); });
+            header.col(|ui| { ui.strong(tr("Cause")); });
+            header.col(|ui| { ui.strong(tr("Fix")); });
+        })
+        .body(|mut body| {
+            for issue in issues.iter() {
+                body.row(row_height, |mut row| {
+                    row.col(|ui| { ui.label(issue.src.as_str()); });
+                    row.col(|ui| {
+                        ui.label(tr(issue.cause.to_str())).on_hover_text(issue.blocking_path.to_string_lossy());
+                    });
+                    row.col(|ui| { ui.label(tr(issue.cause.get_hint())); });
+                });
+            }
+        });
+}
@@ -0,0 +1,217 @@
+use app::app::App;
+use app::file_intent::TitlePolicy;
+use app::rule_profiles::{RuleProfile, RuleProfiles};
+use egui;
+use std::sync::Arc;
+use tokio;
+use crate::i18n::tr;
+
+pub struct GuiProfiles {
+    // Edits are kept until they are saved or reverted
+    draft: Option<RuleProfiles>,
+    selected_profile: Option<String>,
+    new_profile_name: String,
+}
+
+impl GuiProfiles {
+    pub fn new() -> Self {
+        Self {
+            draft: None,
+            selected_profile: None,
+            new_profile_name: String::new(),
+        }
+    }
+}
+
+impl Default for GuiProfiles {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn render_optional_bool(ui: &mut egui::Ui, id: &str, value: &mut Option<bool>) {
+    let get_label = |value: Option<bool>| match value {
+        None => tr("Inherit"),
+        Some(true) => tr("Yes"),
+        Some(false) => tr("No"),
+    };
+    egui::ComboBox::from_id_source(id)
+        .selected_text(get_label(*value))
+        .show_ui(ui, |ui| {
+            for option in [None, Some(true), Some(false)] {
+                ui.selectable_value(value, option, get_label(option));
+            }
+        });
+}
+
+// One entry per line, empty lines are dropped when the profiles are saved
+fn render_optional_list(ui: &mut egui::Ui, value: &mut Option<Vec<String>>) {
+    ui.vertical(|ui| {
+        let mut is_override = value.is_some();
+        if ui.checkbox(&mut is_override, tr("Override")).changed() {
+            *value = match is_override {
+                true => Some(Vec::new()),
+                false => None,
+            };
+        }
+        if let Some(entries) = value.as_mut() {
+            let mut text = entries.join("\n");
+            if ui.add(egui::TextEdit::multiline(&mut text).desired_rows(3)).changed() {
+                *entries = text.split('\n').map(|entry| entry.to_string()).collect();
+            }
+        }
+    });
+}
+
+fn render_profile_editor(ui: &mut egui::Ui, name: &str, names: &[String], profile: &mut RuleProfile) {
+    egui::Grid::new("rule_profile_editor")
+        .num_columns(2)
+        .striped(true)
+        .show(ui, |ui| {
+            ui.strong(tr("Inherits"));
+            egui::ComboBox::from_id_source("profile_inherits")
+                .selected_text(profile.inherits.as_deref().unwrap_or(tr("None")))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut profile.inherits, None, tr("None"));
+                    for other in names.iter().filter(|other| other.as_str() != name) {
+                        ui.selectable_value(&mut profile.inherits, Some(other.clone()), other.as_str());
+                    }
+                });
+            ui.end_row();
+
+            ui.strong(tr("Missing titles"));
+            egui::ComboBox::from_id_source("profile_title_policy")
+                .selected_text(profile.title_policy.map(|policy| tr(policy.to_str())).unwrap_or(tr("Inherit")))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut profile.title_policy, None, tr("Inherit"));
+                    for policy in TitlePolicy::iterator() {
+                        ui.selectable_value(&mut profile.title_policy, Some(*policy), tr(policy.to_str()));
+                    }
+                });
+            ui.end_row();
+
+            ui.strong(tr("Minimum video size (MB)"));
+            ui.horizontal(|ui| {
+                let mut is_override = profile.min_video_size_mb.is_some();
+                if ui.checkbox(&mut is_override, tr("Override")).changed() {
+                    profile.min_video_size_mb = if is_override { Some(0) } else { None };
+                }
+                if let Some(size) = profile.min_video_size_mb.as_mut() {
+                    ui.add(egui::DragValue::new(size));
+                }
+            });
+            ui.end_row();
+
+            ui.strong(tr("Strip combining marks"));
+            render_optional_bool(ui, "profile_strip_combining_marks", &mut profile.strip_combining_marks);
+            ui.end_row();
+
+            ui.strong(tr("Classify extras"));
+            render_optional_bool(ui, "profile_classify_extras", &mut profile.classify_extras);
+            ui.end_row();
+
+            ui.strong(tr("Write metadata tags"));
+            render_optional_bool(ui, "profile_write_metadata_tags", &mut profile.write_metadata_tags);
+            ui.end_row();
+
+            ui.strong(tr("Blacklisted extensions"));
+            render_optional_list(ui, &mut profile.blacklist_extensions);
+            ui.end_row();
+
+            ui.strong(tr("Whitelisted tags"));
+            render_optional_list(ui, &mut profile.whitelist_tags);
+            ui.end_row();
+        });
+}
+
+fn get_cleaned_profiles(profiles: &RuleProfiles) -> RuleProfiles {
+    let mut profiles = profiles.clone();
+    let clean = |entries: &mut Option<Vec<String>>| {
+        if let Some(entries) = entries.as_mut() {
+            entries.retain(|entry| !entry.trim().is_empty());
+        }
+    };
+    for profile in profiles.profiles.values_mut() {
+        clean(&mut profile.blacklist_extensions);
+        clean(&mut profile.whitelist_tags);
+    }
+    profiles
+}
+
+pub fn render_profiles_settings(ui: &mut egui::Ui, gui: &mut GuiProfiles, app: &Arc<App>) {
+    let saved = app.get_profile_store().get_profiles().blocking_read().clone();
+    let draft = gui.draft.get_or_insert_with(|| saved.clone());
+    let names: Vec<String> = draft.profiles.keys().cloned().collect();
+
+    ui.horizontal(|ui| {
+        ui.strong(tr("Default profile"));
+        egui::ComboBox::from_id_source("default_rule_profile")
+            .selected_text(draft.default_profile.as_deref().unwrap_or(tr("None")))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut draft.default_profile, None, tr("None"));
+                for name in names.iter() {
+                    ui.selectable_value(&mut draft.default_profile, Some(name.clone()), name.as_str());
+                }
+            });
+    });
+
+    ui.horizontal(|ui| {
+        ui.text_edit_singleline(&mut gui.new_profile_name);
+        let name = gui.new_profile_name.trim().to_string();
+        let is_valid = !name.is_empty() && !draft.profiles.contains_key(&name);
+        if ui.add_enabled(is_valid, egui::Button::new(tr("Add profile"))).clicked() {
+            draft.profiles.insert(name.clone(), RuleProfile::default());
+            gui.selected_profile = Some(name);
+            gui.new_profile_name.clear();
+        }
+    });
+
+    ui.separator();
+    ui.horizontal_wrapped(|ui| {
+        for name in names.iter() {
+            let is_selected = gui.selected_profile.as_deref() == Some(name.as_str());
+            if ui.selectable_label(is_selected, name.as_str()).clicked() {
+                gui.selected_profile = Some(name.clone());
+            }
+        }
+    });
+
+    let selected_profile = gui.selected_profile.clone();
+    if let Some(name) = selected_profile {
+        match draft.profiles.get_mut(&name) {
+            Some(profile) => {
+                ui.separator();
+                ui.heading(name.as_str());
+                render_profile_editor(ui, name.as_str(), names.as_slice(), profile);
+                if ui.button(tr("Delete profile")).clicked() {
+                    draft.profiles.remove(&name);
+                    if draft.default_profile.as_deref() == Some(name.as_str()) {
+                        draft.default_profile = None;
+                    }
+                    gui.selected_profile = None;
+                }
+            },
+            None => gui.selected_profile = None,
+        }
+    }
+
+    ui.separator();
+    let cleaned = get_cleaned_profiles(draft);
+    let is_changed = cleaned != saved;
+    ui.horizontal(|ui| {
+        if ui.add_enabled(is_changed, egui::Button::new(tr("Save"))).clicked() {
+            tokio::spawn({
+                let app = app.clone();
+                async move {
+                    app.set_rule_profiles(cleaned).await
+                }
+            });
+        }
+        if ui.add_enabled(is_changed, egui::Button::new(tr("Revert"))).clicked() {
+            gui.draft = None;
+        }
+    });
+    if is_changed {
+        ui.label(tr("Unsaved changes"));
+    }
+}
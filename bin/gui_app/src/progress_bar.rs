@@ -0,0 +1,69 @@
+use app::app::App;
+use app::progress::{ProgressSnapshot, ProgressUnit};
+use egui;
+use std::sync::Arc;
+use crate::helpers::{format_duration, format_file_size};
+use crate::i18n::{tr, trf};
+
+fn format_amount(unit: ProgressUnit, amount: u64) -> String {
+    match unit {
+        ProgressUnit::Bytes => format_file_size(amount),
+        ProgressUnit::Folders => amount.to_string(),
+    }
+}
+
+fn format_throughput(unit: ProgressUnit, throughput: f64) -> String {
+    match unit {
+        ProgressUnit::Bytes => format!("{}/s", format_file_size(throughput as u64)),
+        ProgressUnit::Folders => trf("{} folders/min", &[&format!("{:.1}", throughput * 60.0)]),
+    }
+}
+
+fn render_progress_snapshot(ui: &mut egui::Ui, snapshot: &ProgressSnapshot) -> bool {
+    let text = format!(
+        "{}: {}/{}",
+        tr(snapshot.name),
+        format_amount(snapshot.unit, snapshot.finished),
+        format_amount(snapshot.unit, snapshot.total),
+    );
+    let elem = egui::ProgressBar::new(snapshot.get_fraction())
+        .text(text)
+        .desired_width(ui.available_width())
+        .desired_height(ui.spacing().interact_size.y);
+    ui.add(elem);
+
+    ui.horizontal_wrapped(|ui| {
+        let throughput = snapshot.throughput
+            .map(|throughput| format_throughput(snapshot.unit, throughput))
+            .unwrap_or_else(|| "-".to_string());
+        let eta = snapshot.eta.map(format_duration).unwrap_or_else(|| "-".to_string());
+        ui.label(trf("Speed: {}", &[&throughput]));
+        ui.separator();
+        ui.label(trf("ETA: {}", &[&eta]));
+        ui.separator();
+        ui.label(trf("Elapsed: {}", &[&format_duration(snapshot.elapsed)]));
+        if !snapshot.is_cancellable {
+            return false;
+        }
+        ui.separator();
+        let res = ui.add_enabled(!snapshot.is_cancelled, egui::Button::new(tr("Cancel")));
+        res.on_hover_text(tr("Stops after the file currently being processed"))
+            .on_disabled_hover_text(tr("Cancelling"))
+            .clicked()
+    }).inner
+}
+
+// Shows nothing unless a long running operation is reporting its progress
+pub fn render_operation_progress(ui: &mut egui::Ui, app: &Arc<App>) {
+    let snapshot = match app.get_progress().try_read() {
+        Ok(progress) => progress.as_ref().map(|progress| progress.get_snapshot()),
+        Err(_) => None,
+    };
+    if let Some(snapshot) = snapshot {
+        if render_progress_snapshot(ui, &snapshot) {
+            if let Some(progress) = app.get_progress().blocking_read().as_ref() {
+                progress.cancel();
+            }
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use app::app::App;
+use app::rebind::SeriesBinding;
+use egui;
+use egui_extras::{Column, TableBuilder};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio;
+use crate::i18n::{tr, trf};
+use crate::progress_bar::render_operation_progress;
+
+pub struct GuiRebind {
+    selected_folders: HashSet<String>,
+}
+
+impl GuiRebind {
+    pub fn new() -> Self {
+        Self {
+            selected_folders: HashSet::new(),
+        }
+    }
+}
+
+impl Default for GuiRebind {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn spawn_load_bindings(app: &Arc<App>) {
+    tokio::spawn({
+        let app = app.clone();
+        async move {
+            app.load_bindings().await
+        }
+    });
+}
+
+fn format_confidence(confidence: f32) -> String {
+    format!("{:.0}%", confidence*100.0)
+}
+
+fn render_bindings_table(ui: &mut egui::Ui, gui: &mut GuiRebind, bindings: &[SeriesBinding]) {
+    let row_height = 18.0;
+    let cell_layout = egui::Layout::left_to_right(egui::Align::Center).with_cross_justify(false);
+    let max_height = ui.available_height();
+    TableBuilder::new(ui)
+        .striped(true)
+        .resizable(true)
+        .cell_layout(cell_layout)
+        .max_scroll_height(max_height)
+        .column(Column::auto().resizable(false))
+        .column(Column::initial(200.0).resizable(true).clip(true))
+        .column(Column::initial(200.0).resizable(true).clip(true))
+        .column(Column::remainder().resizable(false))
+        .header(row_height, |mut header| {
+            header.col(|ui| { ui.strong(""); });
+            header.col(|ui| { ui.strong(tr("Folder")); });
+            header.col(|ui| { ui.strong(tr("Series")); });
+            header.col(|ui| { ui.strong(tr("Confidence")); });
+        })
+        .body(|body| {
+            body.rows(row_height, bindings.len(), |index, mut row| {
+                let binding = &bindings[index];
+                row.col(|ui| {
+                    let mut is_selected = gui.selected_folders.contains(&binding.folder_name);
+                    if ui.checkbox(&mut is_selected, "").changed() {
+                        match is_selected {
+                            true => gui.selected_folders.insert(binding.folder_name.clone()),
+                            false => gui.selected_folders.remove(&binding.folder_name),
+                        };
+                    }
+                });
+                row.col(|ui| { ui.label(binding.folder_name.as_str()); });
+                row.col(|ui| {
+                    match binding.series.as_ref() {
+                        Some(series) => ui.label(format!("{} ({})", series.name, series.id)),
+                        None => ui.label(egui::RichText::new(tr("Not bound")).italics()),
+                    };
+                });
+                row.col(|ui| { ui.label(format_confidence(binding.confidence)); });
+            });
+        });
+}
+
+fn render_bindings(ui: &mut egui::Ui, gui: &mut GuiRebind, app: &Arc<App>) {
+    let bindings = app.get_bindings().blocking_read();
+    let bindings = match bindings.as_ref() {
+        Some(bindings) => bindings,
+        None => {
+            ui.label(tr("Bindings haven't been loaded yet"));
+            if ui.button(tr("Refresh")).clicked() {
+                spawn_load_bindings(app);
+            }
+            return;
+        },
+    };
+    // folders removed since the bindings were loaded can't be rebound
+    gui.selected_folders.retain(|folder_name| bindings.iter().any(|binding| &binding.folder_name == folder_name));
+
+    let is_logged_in = app.get_login_session().blocking_read().is_some();
+    ui.horizontal(|ui| {
+        if ui.button(tr("Refresh")).clicked() {
+            spawn_load_bindings(app);
+        }
+        if ui.button(tr("Select all")).clicked() {
+            gui.selected_folders = bindings.iter().map(|binding| binding.folder_name.clone()).collect();
+        }
+        if ui.button(tr("Select none")).clicked() {
+            gui.selected_folders.clear();
+        }
+        ui.separator();
+        ui.add_enabled_ui(is_logged_in && !gui.selected_folders.is_empty(), |ui| {
+            let res = ui.button(trf("Search {} folders", &[&gui.selected_folders.len()]));
+            if res.clicked() {
+                // keep the order of the table so the plan lists the weakest bindings first
+                let folder_names: Vec<String> = bindings.iter()
+                    .filter(|binding| gui.selected_folders.contains(&binding.folder_name))
+                    .map(|binding| binding.folder_name.clone())
+                    .collect();
+                tokio::spawn({
+                    let app = app.clone();
+                    async move {
+                        app.plan_rebind(folder_names).await
+                    }
+                });
+            }
+            res.on_disabled_hover_ui(|ui| {
+                match is_logged_in {
+                    true => ui.label(tr("No folders selected")),
+                    false => ui.label(tr("Not logged in")),
+                };
+            });
+        });
+    });
+    ui.separator();
+
+    if bindings.is_empty() {
+        ui.label(tr("No folders"));
+        return;
+    }
+    render_bindings_table(ui, gui, bindings.as_slice());
+}
+
+// Returns false once the plan is discarded or applied
+fn render_rebind_plan(ui: &mut egui::Ui, gui: &mut GuiRebind, app: &Arc<App>) -> bool {
+    let mut plan = app.get_rebind_plan().blocking_write();
+    let plan_ref = match plan.as_mut() {
+        Some(plan) => plan,
+        None => return false,
+    };
+
+    let total_changed = plan_ref.get_total_changed();
+    let mut is_discarded = false;
+    ui.horizontal(|ui| {
+        ui.add_enabled_ui(total_changed > 0, |ui| {
+            if ui.button(trf("Rebind {} folders", &[&total_changed])).clicked() {
+                gui.selected_folders.clear();
+                tokio::spawn({
+                    let app = app.clone();
+                    async move {
+                        app.execute_rebind().await
+                    }
+                });
+            }
+        });
+        is_discarded = ui.button(tr("Discard")).clicked();
+    });
+    ui.separator();
+
+    egui::ScrollArea::vertical()
+        .id_source("rebind_plan")
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            for entry in plan_ref.entries.iter_mut() {
+                ui.horizontal(|ui| {
+                    ui.strong(entry.folder_name.as_str());
+                    ui.label(trf("Search '{}'", &[&entry.search_name]));
+                });
+                if entry.candidates.is_empty() {
+                    ui.label(tr("Search gave no results"));
+                    ui.separator();
+                    continue;
+                }
+                let format_candidate = |index: usize| -> String {
+                    let candidate = &entry.candidates[index];
+                    let first_aired = candidate.series.first_aired.as_deref().unwrap_or(tr("Unknown"));
+                    let mut label = format!(
+                        "{} ({}) {}",
+                        candidate.series.name, first_aired, format_confidence(candidate.confidence),
+                    );
+                    if Some(candidate.series.id) == entry.current_series_id {
+                        label.push_str(format!(" - {}", tr("Current")).as_str());
+                    }
+                    label
+                };
+                let selected_label = entry.selected_candidate.map(format_candidate).unwrap_or_default();
+                let mut selected_candidate = entry.selected_candidate;
+                egui::ComboBox::from_id_source(("rebind_candidate", entry.folder_name.as_str()))
+                    .width(ui.available_width())
+                    .selected_text(selected_label)
+                    .show_ui(ui, |ui| {
+                        for index in 0..entry.candidates.len() {
+                            ui.selectable_value(&mut selected_candidate, Some(index), format_candidate(index));
+                        }
+                    });
+                entry.selected_candidate = selected_candidate;
+                ui.separator();
+            }
+        });
+
+    if is_discarded {
+        *plan = None;
+        return false;
+    }
+    true
+}
+
+pub fn render_rebind_window(ui: &mut egui::Ui, gui: &mut GuiRebind, app: &Arc<App>) {
+    let is_not_busy = app.get_rebind_busy_lock().try_lock().is_ok();
+    if !is_not_busy {
+        ui.spinner();
+        render_operation_progress(ui, app);
+        return;
+    }
+    if !render_rebind_plan(ui, gui, app) {
+        render_bindings(ui, gui, app);
+    }
+}
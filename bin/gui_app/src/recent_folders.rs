@@ -0,0 +1,75 @@
+use app::app::App;
+use app::app_folder::AppFolder;
+use egui;
+use lazy_static::lazy_static;
+use serde;
+use std::sync::{Arc, RwLock};
+use crate::i18n::tr;
+
+const MAX_RECENT_FOLDERS: usize = 8;
+
+// Folders which were last opened or executed, most recent first
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecentFolders {
+    pub folder_names: Vec<String>,
+}
+
+impl RecentFolders {
+    pub fn push(&mut self, folder_name: &str) {
+        self.folder_names.retain(|other| other != folder_name);
+        self.folder_names.insert(0, folder_name.to_string());
+        self.folder_names.truncate(MAX_RECENT_FOLDERS);
+    }
+}
+
+lazy_static! {
+    static ref RECENT_FOLDERS: RwLock<RecentFolders> = RwLock::new(RecentFolders::default());
+}
+
+pub fn get_recent_folders() -> RecentFolders {
+    RECENT_FOLDERS.read().map(|recent| recent.clone()).unwrap_or_default()
+}
+
+pub fn set_recent_folders(recent: RecentFolders) {
+    if let Ok(mut current) = RECENT_FOLDERS.write() {
+        *current = recent;
+    }
+}
+
+pub fn push_recent_folder(folder_name: &str) {
+    if let Ok(mut recent) = RECENT_FOLDERS.write() {
+        recent.push(folder_name);
+    }
+}
+
+// Returns whether a folder was selected so the folders list can scroll to it
+pub fn render_recent_folders(ui: &mut egui::Ui, app: &Arc<App>, folders: &[Arc<AppFolder>]) -> bool {
+    let recent = get_recent_folders();
+    // folders removed since they were opened are left out until the root folder has them again
+    let entries: Vec<(usize, &str)> = recent.folder_names.iter()
+        .filter_map(|folder_name| {
+            let index = folders.iter().position(|folder| folder.get_folder_name() == folder_name)?;
+            Some((index, folder_name.as_str()))
+        })
+        .collect();
+    if entries.is_empty() {
+        return false;
+    }
+
+    let mut is_selected = false;
+    egui::CollapsingHeader::new(tr("Recent"))
+        .id_source("recent_folders")
+        .default_open(true)
+        .show(ui, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                for (index, folder_name) in entries {
+                    if ui.small_button(folder_name).clicked() {
+                        *app.get_selected_folder_index().blocking_write() = Some(index);
+                        push_recent_folder(folder_name);
+                        is_selected = true;
+                    }
+                }
+            });
+        });
+    is_selected
+}
@@ -0,0 +1,95 @@
+use app::app::App;
+use chrono;
+use egui;
+use egui_extras::{Column, TableBuilder};
+use std::sync::Arc;
+use tokio;
+use crate::i18n::{tr, trf};
+use crate::icons::{Icon, IconTone, render_icon};
+
+enum RecoveryAction {
+    Resume,
+    Rollback,
+    Discard,
+}
+
+fn spawn_recovery_action(app: &Arc<App>, folder_name: String, action: RecoveryAction) {
+    tokio::spawn({
+        let app = app.clone();
+        async move {
+            match action {
+                RecoveryAction::Resume => app.resume_execution(folder_name.as_str()).await,
+                RecoveryAction::Rollback => app.rollback_execution(folder_name.as_str()).await,
+                RecoveryAction::Discard => app.discard_execution(folder_name.as_str()).await,
+            }
+        }
+    });
+}
+
+pub fn render_recovery_window(ui: &mut egui::Ui, app: &Arc<App>) {
+    let journals = match app.get_interrupted_executions().try_read() {
+        Ok(journals) => journals,
+        Err(_) => {
+            ui.spinner();
+            return;
+        },
+    };
+
+    ui.horizontal(|ui| {
+        render_icon(ui, Icon::Warning, IconTone::Error);
+        ui.label(tr("The app stopped before these executions finished. Resume them to apply the remaining changes or roll them back to restore the original files."));
+    });
+    ui.separator();
+
+    let mut selected_action = None;
+    let row_height = 18.0;
+    let cell_layout = egui::Layout::left_to_right(egui::Align::Center).with_cross_justify(false);
+    TableBuilder::new(ui)
+        .striped(true)
+        .resizable(true)
+        .cell_layout(cell_layout)
+        .column(Column::initial(200.0).resizable(true).clip(true))
+        .column(Column::auto().resizable(true))
+        .column(Column::auto().resizable(true))
+        .column(Column::remainder().resizable(false))
+        .header(row_height, |mut header| {
+            header.col(|ui| { ui.strong(tr("Folder")); });
+            header.col(|ui| { ui.strong(tr("Started")); });
+            header.col(|ui| { ui.strong(tr("Progress")); });
+            header.col(|ui| { ui.strong(""); });
+        })
+        .body(|mut body| {
+            for journal in journals.iter() {
+                body.row(row_height, |mut row| {
+                    row.col(|ui| { ui.label(journal.get_folder_name()); });
+                    row.col(|ui| {
+                        let created = journal.get_created().with_timezone(&chrono::Local);
+                        ui.label(created.format("%Y-%m-%d %H:%M:%S").to_string());
+                    });
+                    row.col(|ui| {
+                        let total_done = journal.get_total_done();
+                        let total_operations = journal.get_operations().len();
+                        ui.label(trf("{} of {} done", &[&total_done, &total_operations]));
+                    });
+                    row.col(|ui| {
+                        if ui.button(tr("Resume")).clicked() {
+                            selected_action = Some((journal.get_folder_name().to_string(), RecoveryAction::Resume));
+                        }
+                        if ui.button(tr("Rollback")).clicked() {
+                            selected_action = Some((journal.get_folder_name().to_string(), RecoveryAction::Rollback));
+                        }
+                        let res = ui.button(tr("Discard"));
+                        if res.clicked() {
+                            selected_action = Some((journal.get_folder_name().to_string(), RecoveryAction::Discard));
+                        }
+                        res.on_hover_text(tr("Leave the files as they are"));
+                    });
+                });
+            }
+        });
+
+    drop(journals);
+    if let Some((folder_name, action)) = selected_action {
+        spawn_recovery_action(app, folder_name, action);
+    }
+}
@@ -1,12 +1,22 @@
+use app::app::App;
 use eframe;
 use egui;
 use enum_map;
+use std::sync::Arc;
 use crate::frame_history::FrameHistory;
 use crate::helpers::render_invisible_width_widget;
+use crate::i18n::{Language, get_language, set_language, tr};
+use crate::keybindings::{GuiKeybindings, render_keybindings_settings};
+use crate::action_display::render_action_display_settings;
+use crate::profiles_settings::{GuiProfiles, render_profiles_settings};
+use crate::network_settings::{GuiNetworkSettings, render_network_settings};
 
 pub struct GuiSettings {
     selected_option: GuiSettingsOption,
     frame_history: FrameHistory,
+    keybindings: GuiKeybindings,
+    profiles: GuiProfiles,
+    network: GuiNetworkSettings,
 }
 
 impl GuiSettings {
@@ -14,6 +24,9 @@ impl GuiSettings {
         Self {
             selected_option: GuiSettingsOption::Settings,
             frame_history: FrameHistory::default(),
+            keybindings: GuiKeybindings::new(),
+            profiles: GuiProfiles::new(),
+            network: GuiNetworkSettings::new(),
         }
     }
 
@@ -31,14 +44,39 @@ impl Default for GuiSettings {
 #[derive(PartialEq, Eq, Copy, Clone, enum_map::Enum)]
 enum GuiSettingsOption {
     Settings,
+    Keybindings,
+    Actions,
+    Profiles,
+    Network,
     Inspection,
     Memory,
 }
 
-pub fn render_settings_menu(ui: &mut egui::Ui, ctx: &egui::Context, gui: &mut GuiSettings) {
+fn render_language_select(ui: &mut egui::Ui) {
+    let mut language = get_language();
+    ui.horizontal(|ui| {
+        ui.strong(tr("Language"));
+        egui::ComboBox::from_id_source("gui_language")
+            .selected_text(language.to_str())
+            .show_ui(ui, |ui| {
+                for option in Language::iterator() {
+                    ui.selectable_value(&mut language, *option, option.to_str());
+                }
+            });
+    });
+    if language != get_language() {
+        set_language(language);
+    }
+}
+
+pub fn render_settings_menu(ui: &mut egui::Ui, ctx: &egui::Context, gui: &mut GuiSettings, app: &Arc<App>) {
     lazy_static::lazy_static! {
         static ref MENU_ITEMS: enum_map::EnumMap<GuiSettingsOption, &'static str> = enum_map::enum_map! {
             GuiSettingsOption::Settings => "🔧 Settings",
+            GuiSettingsOption::Keybindings => "⌨ Keybindings",
+            GuiSettingsOption::Actions => "🎨 Actions",
+            GuiSettingsOption::Profiles => "📋 Profiles",
+            GuiSettingsOption::Network => "🌐 Network",
             GuiSettingsOption::Inspection => "🔍 Inspection",
             GuiSettingsOption::Memory => "📝 Memory",
         };
@@ -51,12 +89,16 @@ pub fn render_settings_menu(ui: &mut egui::Ui, ctx: &egui::Context, gui: &mut Gu
             ui.with_layout(layout, |ui| {
                 egui::ScrollArea::vertical().show(ui, |ui| {
                     let mut render_label = |item: GuiSettingsOption| {
-                        let label = MENU_ITEMS[item];
+                        let label = tr(MENU_ITEMS[item]);
                         if ui.selectable_label(gui.selected_option == item, label).clicked() {
                             gui.selected_option = item;
                         }
                     };
                     render_label(GuiSettingsOption::Settings);
+                    render_label(GuiSettingsOption::Keybindings);
+                    render_label(GuiSettingsOption::Actions);
+                    render_label(GuiSettingsOption::Profiles);
+                    render_label(GuiSettingsOption::Network);
                     render_label(GuiSettingsOption::Inspection);
                     render_label(GuiSettingsOption::Memory);
 
@@ -71,7 +113,15 @@ pub fn render_settings_menu(ui: &mut egui::Ui, ctx: &egui::Context, gui: &mut Gu
         egui::ScrollArea::vertical().show(ui, |ui| {
             render_invisible_width_widget(ui);
             match gui.selected_option {
-                GuiSettingsOption::Settings => ctx.settings_ui(ui),
+                GuiSettingsOption::Settings => {
+                    render_language_select(ui);
+                    ui.separator();
+                    ctx.settings_ui(ui);
+                },
+                GuiSettingsOption::Keybindings => render_keybindings_settings(ui, &mut gui.keybindings, app),
+                GuiSettingsOption::Actions => render_action_display_settings(ui),
+                GuiSettingsOption::Profiles => render_profiles_settings(ui, &mut gui.profiles, app),
+                GuiSettingsOption::Network => render_network_settings(ui, &mut gui.network, app),
                 GuiSettingsOption::Inspection => ctx.inspection_ui(ui),
                 GuiSettingsOption::Memory => ctx.memory_ui(ui),
             };
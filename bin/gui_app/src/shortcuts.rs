@@ -0,0 +1,74 @@
+use app::app::App;
+use app::app_folder::AppFolder;
+use egui;
+use lazy_static::lazy_static;
+use std::sync::Arc;
+use tokio;
+use crate::app_folders_list::GuiAppFoldersList;
+use crate::execute_confirmation::request_folder_execution;
+
+// Application wide shortcuts so that the main flows can be performed without a mouse
+lazy_static! {
+    pub static ref PREVIOUS_FOLDER_SHORTCUT: egui::KeyboardShortcut = egui::KeyboardShortcut::new(egui::Modifiers::CTRL, egui::Key::ArrowUp);
+    pub static ref NEXT_FOLDER_SHORTCUT: egui::KeyboardShortcut = egui::KeyboardShortcut::new(egui::Modifiers::CTRL, egui::Key::ArrowDown);
+    pub static ref UPDATE_INTENTS_SHORTCUT: egui::KeyboardShortcut = egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::F5);
+    pub static ref EXECUTE_CHANGES_SHORTCUT: egui::KeyboardShortcut = egui::KeyboardShortcut::new(egui::Modifiers::CTRL, egui::Key::Enter);
+    pub static ref ACTION_POPUP_SHORTCUT: egui::KeyboardShortcut = egui::KeyboardShortcut::new(egui::Modifiers::CTRL, egui::Key::Space);
+}
+
+fn get_selected_folder(app: &App) -> Option<Arc<AppFolder>> {
+    let index = (*app.get_selected_folder_index().blocking_read())?;
+    app.get_folders().blocking_read().get(index).cloned()
+}
+
+fn select_adjacent_folder(app: &App, gui: &mut GuiAppFoldersList, is_next: bool) {
+    let total_folders = app.get_folders().blocking_read().len();
+    if total_folders == 0 {
+        return;
+    }
+    let mut selected_index = app.get_selected_folder_index().blocking_write();
+    let new_index = match (*selected_index, is_next) {
+        (None, true) => 0,
+        (None, false) => total_folders-1,
+        (Some(index), true) => (index+1).min(total_folders-1),
+        (Some(index), false) => index.saturating_sub(1),
+    };
+    *selected_index = Some(new_index);
+    gui.is_focus_selected = true;
+}
+
+pub fn handle_global_shortcuts(ctx: &egui::Context, app: &Arc<App>, gui: &mut GuiAppFoldersList) {
+    if ctx.input_mut(|i| i.consume_shortcut(&PREVIOUS_FOLDER_SHORTCUT)) {
+        select_adjacent_folder(app, gui, false);
+    }
+    if ctx.input_mut(|i| i.consume_shortcut(&NEXT_FOLDER_SHORTCUT)) {
+        select_adjacent_folder(app, gui, true);
+    }
+
+    let is_update_intents = ctx.input_mut(|i| i.consume_shortcut(&UPDATE_INTENTS_SHORTCUT));
+    let is_execute_changes = ctx.input_mut(|i| i.consume_shortcut(&EXECUTE_CHANGES_SHORTCUT));
+    if !is_update_intents && !is_execute_changes {
+        return;
+    }
+
+    let folder = match get_selected_folder(app) {
+        Some(folder) => folder,
+        None => return,
+    };
+    if folder.get_busy_lock().try_lock().is_err() {
+        return;
+    }
+    let is_cache_loaded = folder.get_cache().blocking_read().is_some();
+    if !is_execute_changes && !is_cache_loaded {
+        return;
+    }
+
+    if is_execute_changes {
+        request_folder_execution(&folder);
+        return;
+    }
+    tokio::spawn(async move {
+        folder.clear_execution_outcomes().await;
+        folder.update_file_intents().await
+    });
+}
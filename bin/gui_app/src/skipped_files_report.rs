@@ -0,0 +1,85 @@
+use app::app_folder::AppFolder;
+use app::execution_skips::FailedFile;
+use egui;
+use egui_extras::{Column, TableBuilder};
+use std::sync::Arc;
+use tokio;
+use crate::i18n::{tr, trf};
+use crate::icons::{Icon, IconTone, render_icon};
+
+fn spawn_retry(folder: &Arc<AppFolder>, src: Option<String>) {
+    tokio::spawn({
+        let folder = folder.clone();
+        async move {
+            match src {
+                Some(src) => folder.retry_skipped_file(src.as_str()).await,
+                None => folder.retry_skipped_files().await,
+            }
+        }
+    });
+}
+
+pub fn is_skipped_files(folder: &Arc<AppFolder>) -> bool {
+    let max_failures = match folder.get_filter_rules().try_read() {
+        Ok(rules) => rules.get_max_execution_failures(),
+        Err(_) => return false,
+    };
+    folder.get_execution_skips().try_read().map(|skips| !skips.get_skipped(max_failures).is_empty()).unwrap_or(false)
+}
+
+pub fn render_skipped_files_report(ui: &mut egui::Ui, folder: &Arc<AppFolder>) {
+    let max_failures = match folder.get_filter_rules().try_read() {
+        Ok(rules) => rules.get_max_execution_failures(),
+        Err(_) => return,
+    };
+    let skipped: Vec<FailedFile> = match folder.get_execution_skips().try_read() {
+        Ok(skips) => skips.get_skipped(max_failures).into_iter().cloned().collect(),
+        Err(_) => return,
+    };
+    if skipped.is_empty() {
+        return;
+    }
+
+    ui.horizontal(|ui| {
+        render_icon(ui, Icon::Warning, IconTone::Highlight);
+        ui.strong(trf("{} files are skipped in executions since their changes failed repeatedly", &[&skipped.len()]));
+        if ui.button(tr("Retry all")).on_hover_text(tr("Include the files in the next execution again")).clicked() {
+            spawn_retry(folder, None);
+        }
+    });
+
+    let row_height = 18.0;
+    let cell_layout = egui::Layout::left_to_right(egui::Align::Center).with_cross_justify(false);
+    TableBuilder::new(ui)
+        .striped(true)
+        .resizable(true)
+        .cell_layout(cell_layout)
+        .column(Column::initial(250.0).resizable(true).clip(true))
+        .column(Column::auto().resizable(true))
+        .column(Column::initial(250.0).resizable(true).clip(true))
+        .column(Column::remainder().resizable(false))
+        .max_scroll_height(150.0)
+        .header(row_height, |mut header| {
+            header.col(|ui| { ui.strong(tr("File")); });
+            header.col(|ui| { ui.strong(tr("Failures")); });
+            header.col(|ui| { ui.strong(tr("Last error")); });
+            header.col(|ui| { ui.strong(""); });
+        })
+        .body(|mut body| {
+            for file in skipped.iter() {
+                body.row(row_height, |mut row| {
+                    row.col(|ui| { ui.label(file.src.as_str()); });
+                    row.col(|ui| { ui.label(format!("{}", file.failures)); });
+                    row.col(|ui| {
+                        let last_failed = file.last_failed.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string();
+                        ui.label(file.last_error.as_str()).on_hover_text(last_failed);
+                    });
+                    row.col(|ui| {
+                        if ui.button(tr("Retry")).clicked() {
+                            spawn_retry(folder, Some(file.src.clone()));
+                        }
+                    });
+                });
+            }
+        });
+}
@@ -0,0 +1,303 @@
+use app::activity_log::ActivityKind;
+use app::app::App;
+use egui;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use crate::i18n::{tr, trf};
+use crate::icons::{Icon, IconTone, render_icon};
+
+const TOAST_DURATION: Duration = Duration::from_secs(5);
+// Errors stay up longer since they usually need to be acted upon
+const ERROR_TOAST_DURATION: Duration = Duration::from_secs(10);
+const MAX_TOASTS: usize = 5;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ToastSeverity {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl ToastSeverity {
+    fn get_icon(&self) -> (Icon, IconTone) {
+        match self {
+            ToastSeverity::Info => (Icon::Unread, IconTone::Info),
+            ToastSeverity::Success => (Icon::Done, IconTone::Success),
+            ToastSeverity::Warning => (Icon::Warning, IconTone::Highlight),
+            ToastSeverity::Error => (Icon::Warning, IconTone::Error),
+        }
+    }
+
+    fn get_duration(&self) -> Duration {
+        match self {
+            ToastSeverity::Error => ERROR_TOAST_DURATION,
+            _ => TOAST_DURATION,
+        }
+    }
+}
+
+// Where clicking a toast takes the user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToastTarget {
+    Folder(String),
+    Activity,
+}
+
+struct Toast {
+    severity: ToastSeverity,
+    title: String,
+    message: String,
+    target: Option<ToastTarget>,
+    created: Instant,
+}
+
+struct ProgressState {
+    name: &'static str,
+    is_cancelled: bool,
+}
+
+// Transient events are picked up by comparing against what was seen on the previous frame
+// Nothing is shown for the state which is already there on startup
+pub struct GuiToasts {
+    toasts: Vec<Toast>,
+    total_activity_events: Option<usize>,
+    total_app_errors: Option<usize>,
+    total_folder_errors: HashMap<String, usize>,
+    is_logged_in: Option<bool>,
+    is_torrent_logged_in: Option<bool>,
+    progress: Option<ProgressState>,
+    last_config_notice: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl GuiToasts {
+    pub fn new() -> Self {
+        Self {
+            toasts: Vec::new(),
+            total_activity_events: None,
+            total_app_errors: None,
+            total_folder_errors: HashMap::new(),
+            is_logged_in: None,
+            is_torrent_logged_in: None,
+            progress: None,
+            last_config_notice: None,
+        }
+    }
+}
+
+impl Default for GuiToasts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GuiToasts {
+    pub fn push(&mut self, severity: ToastSeverity, title: &str, message: &str, target: Option<ToastTarget>) {
+        self.toasts.push(Toast {
+            severity,
+            title: title.to_string(),
+            message: message.to_string(),
+            target,
+            created: Instant::now(),
+        });
+        // the oldest toasts make way so a burst of events doesn't cover the window
+        if self.toasts.len() > MAX_TOASTS {
+            self.toasts.remove(0);
+        }
+    }
+
+    // Locks which are busy are checked again on the next frame
+    pub fn update(&mut self, app: &Arc<App>) {
+        self.check_activity(app);
+        self.check_errors(app);
+        self.check_logins(app);
+        self.check_progress(app);
+        self.check_config_notice(app);
+    }
+
+    fn check_activity(&mut self, app: &Arc<App>) {
+        let activity_log = app.get_activity_log();
+        let events = match activity_log.get_events().try_read() {
+            Ok(events) => events,
+            Err(_) => return,
+        };
+        // old events are trimmed from the front so the total ever pushed is tracked
+        let total_trimmed = activity_log.get_total_trimmed();
+        let total_events = total_trimmed + events.len();
+        let total_seen = self.total_activity_events.replace(total_events).unwrap_or(total_events);
+        let new_events: Vec<_> = events.iter().skip(total_seen.saturating_sub(total_trimmed)).cloned().collect();
+        drop(events);
+        for event in new_events {
+            // settings are changed by the user, config reloads are shown separately
+            if event.kind == ActivityKind::Settings {
+                continue;
+            }
+            let severity = match event.kind {
+                ActivityKind::Execute => ToastSeverity::Success,
+                _ => ToastSeverity::Info,
+            };
+            let target = match event.folder.as_ref() {
+                Some(folder) => ToastTarget::Folder(folder.clone()),
+                None => ToastTarget::Activity,
+            };
+            self.push(severity, tr(event.kind.to_str()), event.message.as_str(), Some(target));
+        }
+    }
+
+    fn check_errors(&mut self, app: &Arc<App>) {
+        if let Ok(errors) = app.get_errors().try_read() {
+            let total_seen = self.total_app_errors.replace(errors.len()).unwrap_or(errors.len());
+            let new_errors: Vec<String> = errors.iter().skip(total_seen).cloned().collect();
+            drop(errors);
+            for error in new_errors {
+                self.push(ToastSeverity::Error, tr("Error"), error.as_str(), None);
+            }
+        }
+
+        let folders = match app.get_folders().try_read() {
+            Ok(folders) => folders.clone(),
+            Err(_) => return,
+        };
+        let is_first_check = self.total_folder_errors.is_empty();
+        let mut new_errors = Vec::new();
+        for folder in folders.iter() {
+            let errors = match folder.get_errors().try_read() {
+                Ok(errors) => errors,
+                Err(_) => continue,
+            };
+            let folder_name = folder.get_folder_name();
+            // dismissed errors shrink the list so only growth is new
+            let total_seen = match self.total_folder_errors.insert(folder_name.to_string(), errors.len()) {
+                Some(total_seen) => total_seen,
+                None if is_first_check => errors.len(),
+                None => 0,
+            };
+            for error in errors.iter().skip(total_seen) {
+                new_errors.push((folder_name.to_string(), error.clone()));
+            }
+        }
+        self.total_folder_errors.retain(|folder_name, _| folders.iter().any(|folder| folder.get_folder_name() == folder_name));
+        // a refresh of all folders can fail the same way everywhere, so those are grouped into one toast
+        let total_folders = new_errors.iter().map(|(folder_name, _)| folder_name).collect::<HashSet<_>>().len();
+        if total_folders > 1 {
+            let (folder_name, error) = &new_errors[0];
+            let message = trf("{} folders reported errors, e.g. {}", &[&total_folders, error]);
+            self.push(ToastSeverity::Error, tr("Error"), message.as_str(), Some(ToastTarget::Folder(folder_name.clone())));
+            return;
+        }
+        for (folder_name, error) in new_errors {
+            self.push(ToastSeverity::Error, folder_name.as_str(), error.as_str(), Some(ToastTarget::Folder(folder_name.clone())));
+        }
+    }
+
+    fn check_logins(&mut self, app: &Arc<App>) {
+        if let Ok(session) = app.get_login_session().try_read() {
+            // expired sessions already tried logging in again with the stored credentials
+            let is_expired = session.as_ref().map(|session| session.is_expired()).unwrap_or(false);
+            let is_logged_in = session.is_some() && !is_expired;
+            if self.is_logged_in.replace(is_logged_in) == Some(true) && !is_logged_in {
+                let message = match is_expired {
+                    true => tr("TVDB session expired and logging in again failed, press Login to retry"),
+                    false => tr("Logged out of TVDB, log in again to refresh series"),
+                };
+                self.push(ToastSeverity::Warning, tr("Login"), message, None);
+            }
+        }
+        if let Ok(session) = app.get_torrent_session().try_read() {
+            let is_logged_in = session.is_some();
+            if self.is_torrent_logged_in.replace(is_logged_in) == Some(true) && !is_logged_in {
+                self.push(ToastSeverity::Warning, tr("Login"), tr("Logged out of qBittorrent"), None);
+            }
+        }
+    }
+
+    fn check_progress(&mut self, app: &Arc<App>) {
+        let progress = match app.get_progress().try_read() {
+            Ok(progress) => progress.as_ref().map(|progress| progress.get_snapshot()),
+            Err(_) => return,
+        };
+        let progress = progress.map(|snapshot| ProgressState {
+            name: snapshot.name,
+            is_cancelled: snapshot.is_cancelled,
+        });
+        // a finished operation is either cleared or replaced by the next one
+        let previous = match std::mem::replace(&mut self.progress, progress) {
+            Some(previous) => previous,
+            None => return,
+        };
+        if self.progress.as_ref().map(|progress| progress.name) == Some(previous.name) {
+            return;
+        }
+        match previous.is_cancelled {
+            true => self.push(ToastSeverity::Warning, tr(previous.name), tr("Cancelled"), None),
+            false => self.push(ToastSeverity::Success, tr(previous.name), tr("Finished"), None),
+        }
+    }
+
+    fn check_config_notice(&mut self, app: &Arc<App>) {
+        let notice = match app.get_config_notice().try_read() {
+            Ok(notice) => notice.clone(),
+            Err(_) => return,
+        };
+        let notice = match notice {
+            Some(notice) => notice,
+            None => return,
+        };
+        if self.last_config_notice.replace(notice.timestamp) == Some(notice.timestamp) {
+            return;
+        }
+        self.push(ToastSeverity::Info, tr("Config reloaded"), notice.message.as_str(), Some(ToastTarget::Activity));
+    }
+}
+
+// Returns the target of a clicked toast, toasts are dismissed once clicked
+pub fn render_toasts(ctx: &egui::Context, gui: &mut GuiToasts) -> Option<ToastTarget> {
+    let now = Instant::now();
+    gui.toasts.retain(|toast| now.duration_since(toast.created) < toast.severity.get_duration());
+    if gui.toasts.is_empty() {
+        return None;
+    }
+
+    let mut clicked_index = None;
+    let mut dismissed_index = None;
+    egui::Area::new("toasts")
+        .anchor(egui::Align2::RIGHT_BOTTOM, [-10.0, -10.0])
+        .show(ctx, |ui| {
+            // newest at the bottom closest to the anchor
+            for (index, toast) in gui.toasts.iter().enumerate() {
+                let res = egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_max_width(360.0);
+                    ui.horizontal(|ui| {
+                        let (icon, tone) = toast.severity.get_icon();
+                        render_icon(ui, icon, tone);
+                        ui.strong(toast.title.as_str());
+                        if ui.small_button("x").on_hover_text(tr("Dismiss")).clicked() {
+                            dismissed_index = Some(index);
+                        }
+                    });
+                    ui.label(toast.message.as_str());
+                    if let Some(target) = toast.target.as_ref() {
+                        let label = match target {
+                            ToastTarget::Folder(folder_name) => trf("Click to open '{}'", &[folder_name]),
+                            ToastTarget::Activity => tr("Click to open the activity log").to_string(),
+                        };
+                        ui.weak(label);
+                    }
+                }).response;
+                let res = res.interact(egui::Sense::click());
+                if toast.target.is_some() && res.clicked() && dismissed_index.is_none() {
+                    clicked_index = Some(index);
+                }
+            }
+        });
+    // keep fading toasts up to date without waiting for input
+    ctx.request_repaint_after(Duration::from_millis(250));
+
+    if let Some(index) = dismissed_index {
+        gui.toasts.remove(index);
+        return None;
+    }
+    let index = clicked_index?;
+    gui.toasts.remove(index).target
+}
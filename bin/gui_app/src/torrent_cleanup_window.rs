@@ -0,0 +1,318 @@
+use app::app::App;
+use app::cleanup_queue::{QueuedCleanup, SeedTargets};
+use app::torrent_cleanup::TorrentCleanup;
+use egui;
+use egui_extras::{Column, TableBuilder};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio;
+use crate::i18n::{tr, trf};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum CleanupTab {
+    Finished,
+    Seeding,
+    Queue,
+}
+
+pub struct GuiTorrentCleanup {
+    selected_tab: CleanupTab,
+    selected_hashes: HashSet<String>,
+}
+
+impl GuiTorrentCleanup {
+    pub fn new() -> Self {
+        Self {
+            selected_tab: CleanupTab::Finished,
+            selected_hashes: HashSet::new(),
+        }
+    }
+}
+
+impl Default for GuiTorrentCleanup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn spawn_load_torrent_cleanups(app: &Arc<App>) {
+    tokio::spawn({
+        let app = app.clone();
+        async move {
+            app.load_torrent_cleanups().await
+        }
+    });
+}
+
+fn render_cleanups_table(ui: &mut egui::Ui, gui: &mut GuiTorrentCleanup, cleanups: &[TorrentCleanup]) {
+    let row_height = 18.0;
+    let cell_layout = egui::Layout::left_to_right(egui::Align::Center).with_cross_justify(false);
+    let max_height = ui.available_height();
+    TableBuilder::new(ui)
+        .striped(true)
+        .resizable(true)
+        .cell_layout(cell_layout)
+        .max_scroll_height(max_height)
+        .column(Column::auto().resizable(false))
+        .column(Column::initial(250.0).resizable(true).clip(true))
+        .column(Column::auto().resizable(true))
+        .column(Column::auto().resizable(true))
+        .column(Column::remainder().resizable(false).clip(true))
+        .header(row_height, |mut header| {
+            header.col(|ui| { ui.strong(""); });
+            header.col(|ui| { ui.strong(tr("Torrent")); });
+            header.col(|ui| { ui.strong(tr("State")); });
+            header.col(|ui| { ui.strong(tr("Videos")); });
+            header.col(|ui| { ui.strong(tr("Release folder")); });
+        })
+        .body(|body| {
+            body.rows(row_height, cleanups.len(), |index, mut row| {
+                let cleanup = &cleanups[index];
+                row.col(|ui| {
+                    let mut is_selected = gui.selected_hashes.contains(&cleanup.hash);
+                    if ui.checkbox(&mut is_selected, "").changed() {
+                        match is_selected {
+                            true => gui.selected_hashes.insert(cleanup.hash.clone()),
+                            false => gui.selected_hashes.remove(&cleanup.hash),
+                        };
+                    }
+                });
+                row.col(|ui| { ui.label(cleanup.name.as_str()); });
+                row.col(|ui| { ui.label(cleanup.state.as_str()); });
+                row.col(|ui| { ui.label(format!("{}", cleanup.total_videos)); });
+                row.col(|ui| {
+                    match cleanup.release_path.as_ref() {
+                        Some(path) => ui.label(path.to_string_lossy()),
+                        None => ui.label(egui::RichText::new(tr("Nothing to remove")).italics()),
+                    };
+                });
+            });
+        });
+}
+
+fn format_seed_time(seeding_time: u64) -> String {
+    format!("{:.1} h", seeding_time as f32 / 3600.0)
+}
+
+fn render_seeding_torrents(ui: &mut egui::Ui, app: &Arc<App>) {
+    let torrents = app.get_seeding_torrents().blocking_read();
+    ui.horizontal(|ui| {
+        if ui.button(tr("Refresh")).clicked() {
+            spawn_load_torrent_cleanups(app);
+        }
+        ui.label(tr("Complete torrents which are still seeding, queued torrents are cleaned up once they reach their seed targets"));
+    });
+    ui.separator();
+    if torrents.is_empty() {
+        ui.label(tr("No seeding torrents"));
+        return;
+    }
+
+    let row_height = 18.0;
+    let cell_layout = egui::Layout::left_to_right(egui::Align::Center).with_cross_justify(false);
+    let max_height = ui.available_height();
+    TableBuilder::new(ui)
+        .striped(true)
+        .resizable(true)
+        .cell_layout(cell_layout)
+        .max_scroll_height(max_height)
+        .column(Column::initial(300.0).resizable(true).clip(true))
+        .column(Column::auto().resizable(true))
+        .column(Column::auto().resizable(true))
+        .column(Column::remainder().resizable(false))
+        .header(row_height, |mut header| {
+            header.col(|ui| { ui.strong(tr("Torrent")); });
+            header.col(|ui| { ui.strong(tr("Ratio")); });
+            header.col(|ui| { ui.strong(tr("Seed time")); });
+            header.col(|ui| { ui.strong(""); });
+        })
+        .body(|body| {
+            body.rows(row_height, torrents.len(), |index, mut row| {
+                let torrent = &torrents[index];
+                row.col(|ui| { ui.label(torrent.name.as_str()); });
+                row.col(|ui| { ui.label(format!("{:.2}", torrent.ratio)); });
+                row.col(|ui| { ui.label(format_seed_time(torrent.seeding_time)); });
+                row.col(|ui| {
+                    if ui.button(tr("Queue cleanup")).clicked() {
+                        tokio::spawn({
+                            let app = app.clone();
+                            let hash = torrent.hash.clone();
+                            async move {
+                                app.queue_torrent_cleanup(hash.as_str()).await
+                            }
+                        });
+                    }
+                });
+            });
+        });
+}
+
+// Unset targets are shown as unchecked
+fn render_optional_target(ui: &mut egui::Ui, value: &mut Option<f32>, default: f32, speed: f64) -> bool {
+    let mut is_set = value.is_some();
+    let mut is_changed = ui.checkbox(&mut is_set, "").changed();
+    if is_changed {
+        *value = is_set.then_some(default);
+    }
+    if let Some(value) = value.as_mut() {
+        is_changed |= ui.add(egui::DragValue::new(value).speed(speed).clamp_range(0.0..=10000.0)).changed();
+    }
+    is_changed
+}
+
+fn render_cleanup_queue(ui: &mut egui::Ui, app: &Arc<App>) {
+    let entries: Vec<QueuedCleanup> = app.get_cleanup_queue().get_entries().blocking_read().clone();
+    ui.horizontal(|ui| {
+        ui.label(trf("{} torrents waiting for their seed targets", &[&entries.len()]));
+        ui.add_enabled_ui(!entries.is_empty(), |ui| {
+            if ui.button(tr("Check now")).clicked() {
+                tokio::spawn({
+                    let app = app.clone();
+                    async move {
+                        app.process_cleanup_queue().await
+                    }
+                });
+            }
+        });
+    });
+    ui.separator();
+    if entries.is_empty() {
+        ui.label(tr("No torrents are queued for cleanup"));
+        return;
+    }
+
+    let row_height = 20.0;
+    let cell_layout = egui::Layout::left_to_right(egui::Align::Center).with_cross_justify(false);
+    let max_height = ui.available_height();
+    TableBuilder::new(ui)
+        .striped(true)
+        .resizable(true)
+        .cell_layout(cell_layout)
+        .max_scroll_height(max_height)
+        .column(Column::initial(250.0).resizable(true).clip(true))
+        .column(Column::auto().resizable(true))
+        .column(Column::auto().resizable(true))
+        .column(Column::auto().resizable(true))
+        .column(Column::auto().resizable(true))
+        .column(Column::remainder().resizable(false))
+        .header(row_height, |mut header| {
+            header.col(|ui| { ui.strong(tr("Torrent")); });
+            header.col(|ui| { ui.strong(tr("Ratio")); });
+            header.col(|ui| { ui.strong(tr("Seed time")); });
+            header.col(|ui| { ui.strong(tr("Target ratio")); });
+            header.col(|ui| { ui.strong(tr("Target hours")); });
+            header.col(|ui| { ui.strong(""); });
+        })
+        .body(|body| {
+            body.rows(row_height, entries.len(), |index, mut row| {
+                let entry = &entries[index];
+                let mut targets: SeedTargets = entry.targets;
+                let mut is_changed = false;
+                row.col(|ui| {
+                    ui.label(entry.name.as_str()).on_hover_text(entry.content_path.as_str());
+                });
+                row.col(|ui| {
+                    ui.label(entry.ratio.map(|ratio| format!("{:.2}", ratio)).unwrap_or_default());
+                });
+                row.col(|ui| {
+                    ui.label(entry.seeding_time.map(format_seed_time).unwrap_or_default());
+                });
+                row.col(|ui| {
+                    is_changed |= render_optional_target(ui, &mut targets.min_ratio, 1.0, 0.05);
+                });
+                row.col(|ui| {
+                    is_changed |= render_optional_target(ui, &mut targets.min_seed_hours, 24.0, 1.0);
+                });
+                row.col(|ui| {
+                    if ui.button(tr("Remove")).on_hover_text(tr("Keep the torrent and its files")).clicked() {
+                        tokio::spawn({
+                            let app = app.clone();
+                            let hash = entry.hash.clone();
+                            async move {
+                                app.remove_queued_cleanup(hash.as_str()).await
+                            }
+                        });
+                    }
+                });
+                if is_changed {
+                    tokio::spawn({
+                        let app = app.clone();
+                        let hash = entry.hash.clone();
+                        async move {
+                            app.set_queued_cleanup_targets(hash.as_str(), targets).await
+                        }
+                    });
+                }
+            });
+        });
+}
+
+pub fn render_torrent_cleanup_window(ui: &mut egui::Ui, gui: &mut GuiTorrentCleanup, app: &Arc<App>) {
+    let is_not_busy = app.get_torrent_cleanup_busy_lock().try_lock().is_ok();
+    if !is_not_busy {
+        ui.spinner();
+        return;
+    }
+    ui.horizontal(|ui| {
+        ui.selectable_value(&mut gui.selected_tab, CleanupTab::Finished, tr("Finished"));
+        ui.selectable_value(&mut gui.selected_tab, CleanupTab::Seeding, tr("Seeding"));
+        ui.selectable_value(&mut gui.selected_tab, CleanupTab::Queue, tr("Deferred cleanup"));
+    });
+    ui.separator();
+    match gui.selected_tab {
+        CleanupTab::Finished => render_finished_torrents(ui, gui, app),
+        CleanupTab::Seeding => render_seeding_torrents(ui, app),
+        CleanupTab::Queue => render_cleanup_queue(ui, app),
+    }
+}
+
+fn render_finished_torrents(ui: &mut egui::Ui, gui: &mut GuiTorrentCleanup, app: &Arc<App>) {
+    let cleanups = app.get_torrent_cleanups().blocking_read();
+    let cleanups = match cleanups.as_ref() {
+        Some(cleanups) => cleanups,
+        None => {
+            ui.label(tr("Torrents haven't been checked yet"));
+            if ui.button(tr("Refresh")).clicked() {
+                spawn_load_torrent_cleanups(app);
+            }
+            return;
+        },
+    };
+    // torrents which were removed since are no longer selectable
+    gui.selected_hashes.retain(|hash| cleanups.iter().any(|cleanup| &cleanup.hash == hash));
+
+    ui.label(tr("Finished torrents which no longer seed and whose videos have all been renamed into the library"));
+    ui.horizontal(|ui| {
+        if ui.button(tr("Refresh")).clicked() {
+            spawn_load_torrent_cleanups(app);
+        }
+        if ui.button(tr("Select all")).clicked() {
+            gui.selected_hashes = cleanups.iter().map(|cleanup| cleanup.hash.clone()).collect();
+        }
+        if ui.button(tr("Select none")).clicked() {
+            gui.selected_hashes.clear();
+        }
+        ui.separator();
+        let res = ui.add_enabled(
+            !gui.selected_hashes.is_empty(),
+            egui::Button::new(trf("Clean up {} torrents", &[&gui.selected_hashes.len()])),
+        );
+        if res.clicked() {
+            let hashes: Vec<String> = gui.selected_hashes.drain().collect();
+            tokio::spawn({
+                let app = app.clone();
+                async move {
+                    app.execute_torrent_cleanups(hashes).await
+                }
+            });
+        }
+        res.on_hover_text(tr("Removes the torrents from qBittorrent and moves their release folders into the trash"));
+    });
+    ui.separator();
+
+    if cleanups.is_empty() {
+        ui.label(tr("No torrents to clean up"));
+        return;
+    }
+    render_cleanups_table(ui, gui, cleanups.as_slice());
+}
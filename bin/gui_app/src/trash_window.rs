@@ -0,0 +1,131 @@
+use app::app::App;
+use app::trash::get_expired_trash_entries;
+use chrono;
+use egui;
+use egui_extras::{Column, TableBuilder};
+use std::sync::Arc;
+use tokio;
+use crate::helpers::format_file_size;
+use crate::i18n::{tr, trf};
+
+pub fn spawn_load_trash_entries(app: &Arc<App>) {
+    tokio::spawn({
+        let app = app.clone();
+        async move {
+            app.load_trash_entries().await
+        }
+    });
+}
+
+pub fn render_trash_window(ui: &mut egui::Ui, app: &Arc<App>) {
+    let is_not_busy = app.get_trash_busy_lock().try_lock().is_ok();
+    let entries = match app.get_trash_entries().try_read() {
+        Ok(entries) => entries,
+        Err(_) => {
+            ui.spinner();
+            return;
+        },
+    };
+    let entries = match entries.as_ref() {
+        Some(entries) => entries,
+        None if !is_not_busy => {
+            ui.spinner();
+            return;
+        },
+        None => {
+            ui.label(tr("Trash hasn't been scanned yet"));
+            if ui.button(tr("Refresh")).clicked() {
+                spawn_load_trash_entries(app);
+            }
+            return;
+        },
+    };
+
+    let retention = app.get_filter_rules().blocking_read().trash_retention.clone();
+    let expired = get_expired_trash_entries(entries.as_slice(), &retention, chrono::Utc::now());
+    let total_size: u64 = entries.iter().map(|entry| entry.size).sum();
+    let reclaimable_size: u64 = expired.iter().map(|entry| entry.size).sum();
+
+    ui.horizontal(|ui| {
+        ui.label(trf("{} entries using {}", &[&entries.len(), &format_file_size(total_size)]));
+        ui.separator();
+        ui.label(trf("{} reclaimable by the retention policy", &[&format_file_size(reclaimable_size)]));
+    });
+    ui.horizontal(|ui| {
+        ui.add_enabled_ui(is_not_busy, |ui| {
+            if ui.button(tr("Refresh")).clicked() {
+                spawn_load_trash_entries(app);
+            }
+            let res = ui.add_enabled(!expired.is_empty(), egui::Button::new(tr("Purge expired")));
+            if res.clicked() {
+                tokio::spawn({
+                    let app = app.clone();
+                    async move {
+                        app.purge_expired_trash().await
+                    }
+                });
+            }
+            res.on_hover_text(tr("Set \"trash_retention\" in the app config to purge the trash automatically"));
+        });
+    });
+    ui.separator();
+
+    if entries.is_empty() {
+        ui.label(tr("Trash is empty"));
+        return;
+    }
+
+    let mut selected_purge = None;
+    let row_height = 18.0;
+    let cell_layout = egui::Layout::left_to_right(egui::Align::Center).with_cross_justify(false);
+    TableBuilder::new(ui)
+        .striped(true)
+        .resizable(true)
+        .cell_layout(cell_layout)
+        .column(Column::initial(200.0).resizable(true).clip(true))
+        .column(Column::auto().resizable(true))
+        .column(Column::auto().resizable(true))
+        .column(Column::remainder().resizable(false))
+        .header(row_height, |mut header| {
+            header.col(|ui| { ui.strong(tr("Deleted")); });
+            header.col(|ui| { ui.strong(tr("Files")); });
+            header.col(|ui| { ui.strong(tr("Size")); });
+            header.col(|ui| { ui.strong(""); });
+        })
+        .body(|mut body| {
+            for entry in entries.iter().rev() {
+                let is_expired = expired.iter().any(|other| other.name == entry.name);
+                body.row(row_height, |mut row| {
+                    row.col(|ui| {
+                        let label = match entry.created {
+                            Some(created) => created.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string(),
+                            None => entry.name.clone(),
+                        };
+                        let label = egui::RichText::new(label);
+                        match is_expired {
+                            true => ui.label(label.color(ui.visuals().weak_text_color())).on_hover_text(tr("Expired")),
+                            false => ui.label(label),
+                        };
+                    });
+                    row.col(|ui| { ui.label(format!("{}", entry.total_files)); });
+                    row.col(|ui| { ui.label(format_file_size(entry.size)); });
+                    row.col(|ui| {
+                        let res = ui.add_enabled(is_not_busy, egui::Button::new(tr("Purge")));
+                        if res.clicked() {
+                            selected_purge = Some(entry.name.clone());
+                        }
+                        res.on_hover_text(tr("Permanently delete the files in this entry"));
+                    });
+                });
+            }
+        });
+
+    if let Some(name) = selected_purge {
+        tokio::spawn({
+            let app = app.clone();
+            async move {
+                app.purge_trash_entries(vec![name]).await
+            }
+        });
+    }
+}
@@ -1,6 +1,8 @@
+use app::external_ids::get_external_links;
 use egui;
 use tvdb::models::{Series, Episode};
 use open as cross_open;
+use crate::i18n::{tr, trf};
 
 const IMDB_PREFIX: &str = "https://www.imdb.com/title";
 
@@ -13,43 +15,43 @@ pub fn render_series_table(ui: &mut egui::Ui, series: &Series) {
             .num_columns(2)
             .striped(true)
             .show(ui, |ui| {
-                ui.strong("ID");
+                ui.strong(tr("ID"));
                 ui.label(format!("{}", series.id));
                 ui.end_row();
 
-                ui.strong("Name");
+                ui.strong(tr("Name"));
                 let gui_label = egui::Label::new(series.name.as_str()).wrap(true);
                 ui.add(gui_label);
                 ui.end_row();
 
-                ui.strong("Status");
-                let label = series.status.as_deref().unwrap_or("Unknown");
+                ui.strong(tr("Status"));
+                let label = series.status.as_deref().unwrap_or(tr("Unknown"));
                 ui.label(label);
                 ui.end_row();
 
-                ui.strong("Air date");
-                let label = series.first_aired.as_deref().unwrap_or("Unknown");
+                ui.strong(tr("Air date"));
+                let label = series.first_aired.as_deref().unwrap_or(tr("Unknown"));
                 ui.label(label);
                 ui.end_row();
 
-                ui.strong("Genre");
+                ui.strong(tr("Genre"));
                 let label = match &series.genre {
-                    None => "Unknown".to_string(),
+                    None => tr("Unknown").to_string(),
                     Some(genres) => genres.join(","),
                 };
                 let gui_label = egui::Label::new(label).wrap(true);
                 ui.add(gui_label);
                 ui.end_row();
 
-                ui.strong("Overview");
-                let label = series.overview.as_deref().unwrap_or("Unknown");
+                ui.strong(tr("Overview"));
+                let label = series.overview.as_deref().unwrap_or(tr("Unknown"));
                 let gui_label = egui::Label::new(label).wrap(true);
                 ui.add(gui_label);
                 ui.end_row();
 
                 if let Some(id) = series.imdb_id.as_ref() {
                     if !id.is_empty() {
-                        ui.strong("IMDB");
+                        ui.strong(tr("IMDB"));
                         let link_url = format!("{}/{}", IMDB_PREFIX, id);
                         if ui.link(link_url.as_str()).clicked() {
                             tokio::spawn(async move {
@@ -63,6 +65,19 @@ pub fn render_series_table(ui: &mut egui::Ui, series: &Series) {
     });
 }
 
+pub fn render_external_links(ui: &mut egui::Ui, series: &Series) {
+    ui.horizontal_wrapped(|ui| {
+        for (provider, link_url) in get_external_links(series) {
+            let res = ui.link(trf("Open on {}", &[&provider.to_str()])).on_hover_text(link_url.as_str());
+            if res.clicked() {
+                tokio::spawn(async move {
+                    cross_open::that(link_url)
+                });
+            }
+        }
+    });
+}
+
 pub fn render_episode_table(ui: &mut egui::Ui, episode: &Episode) {
     let layout = egui::Layout::left_to_right(egui::Align::Min)
         .with_main_justify(true)
@@ -72,32 +87,44 @@ pub fn render_episode_table(ui: &mut egui::Ui, episode: &Episode) {
             .num_columns(2)
             .striped(true)
             .show(ui, |ui| {
-                ui.strong("ID");
+                ui.strong(tr("ID"));
                 ui.label(format!("{}", episode.id));
                 ui.end_row();
 
-                ui.strong("Index");
+                ui.strong(tr("Index"));
                 ui.label(format!("S{:02}E{:02}", episode.season, episode.episode));
                 ui.end_row();
 
-                ui.strong("Name");
-                ui.label(episode.name.as_deref().unwrap_or("None"));
+                if let (Some(season), Some(number)) = (episode.dvd_season, episode.dvd_episode_number) {
+                    ui.strong(tr("DVD index"));
+                    ui.label(format!("S{:02}E{:02}", season, number));
+                    ui.end_row();
+                }
+
+                if let Some(absolute_number) = episode.absolute_number {
+                    ui.strong(tr("Absolute number"));
+                    ui.label(format!("{}", absolute_number));
+                    ui.end_row();
+                }
+
+                ui.strong(tr("Name"));
+                ui.label(episode.name.as_deref().unwrap_or(tr("None")));
                 ui.end_row();
 
-                ui.strong("Air date"); 
-                let label = episode.first_aired.as_deref().unwrap_or("Unknown");
+                ui.strong(tr("Air date")); 
+                let label = episode.first_aired.as_deref().unwrap_or(tr("Unknown"));
                 ui.label(label);
                 ui.end_row();
 
-                ui.strong("Overview");
-                let label = episode.overview.as_deref().unwrap_or("Unknown");
+                ui.strong(tr("Overview"));
+                let label = episode.overview.as_deref().unwrap_or(tr("Unknown"));
                 let gui_label = egui::Label::new(label).wrap(true);
                 ui.add(gui_label);
                 ui.end_row();
 
                 if let Some(id) = episode.imdb_id.as_ref() {
                     if !id.is_empty() {
-                        ui.strong("IMDB");
+                        ui.strong(tr("IMDB"));
                         let link_url = format!("{}/{}", IMDB_PREFIX, id);
                         if ui.link(link_url.as_str()).clicked() {
                             tokio::spawn(async move {
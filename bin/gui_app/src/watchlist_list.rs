@@ -0,0 +1,141 @@
+use app::app::App;
+use app::app_folder::AppFolder;
+use app::file_descriptor::get_folder_series_key;
+use egui;
+use egui_extras::{Column, TableBuilder};
+use std::sync::Arc;
+use tokio;
+use tvdb::models::Series;
+use crate::fuzzy_search::{FuzzySearcher, render_search_bar};
+use crate::i18n::tr;
+use crate::icons::{Icon, IconTone, render_icon};
+
+pub struct GuiWatchlist {
+    searcher: FuzzySearcher,
+}
+
+impl GuiWatchlist {
+    pub fn new() -> Self {
+        Self {
+            searcher: FuzzySearcher::new(),
+        }
+    }
+}
+
+impl Default for GuiWatchlist {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Mirrors how the importer picks a folder so the list shows where episodes will end up
+fn find_series_folder<'a>(folders: &'a [Arc<AppFolder>], series: &Series) -> Option<&'a Arc<AppFolder>> {
+    let bound_folder = folders.iter().find(|folder| {
+        folder.get_cache().try_read()
+            .map(|cache| cache.as_ref().map(|cache| cache.series.id) == Some(series.id))
+            .unwrap_or(false)
+    });
+    if bound_folder.is_some() {
+        return bound_folder;
+    }
+    let series_key = get_folder_series_key(series.name.as_str());
+    folders.iter().find(|folder| get_folder_series_key(folder.get_folder_name()) == series_key)
+}
+
+pub fn render_watchlist(ui: &mut egui::Ui, gui: &mut GuiWatchlist, app: &Arc<App>) {
+    let is_logged_in = app.get_login_session().blocking_read().is_some();
+    let is_not_busy = app.get_folders_busy_lock().try_lock().is_ok();
+    ui.horizontal(|ui| {
+        render_search_bar(ui, &mut gui.searcher);
+        ui.add_enabled_ui(is_logged_in && is_not_busy, |ui| {
+            let res = ui.button(tr("Create missing folders"));
+            if res.clicked() {
+                tokio::spawn({
+                    let app = app.clone();
+                    async move {
+                        app.create_watchlist_folders().await
+                    }
+                });
+            }
+            res.on_disabled_hover_ui(|ui| {
+                if !is_logged_in  { ui.label(tr("Not logged in")); }
+                else if !is_not_busy { ui.label(tr("Folders are busy")); }
+            });
+        });
+    });
+    ui.separator();
+
+    let entries = match app.get_watchlist().get_entries().try_read() {
+        Ok(entries) => entries,
+        Err(_) => {
+            ui.spinner();
+            return;
+        },
+    };
+
+    if entries.is_empty() {
+        ui.label(tr("No series in watchlist"));
+        return;
+    }
+
+    let folders = app.get_folders().blocking_read();
+    let row_height = 18.0;
+    let cell_layout = egui::Layout::left_to_right(egui::Align::Center).with_cross_justify(false);
+    TableBuilder::new(ui)
+        .striped(true)
+        .resizable(true)
+        .cell_layout(cell_layout)
+        .column(Column::initial(200.0).resizable(true).clip(true))
+        .column(Column::auto().resizable(true))
+        .column(Column::auto().resizable(true))
+        .column(Column::remainder().resizable(true).clip(true))
+        .column(Column::auto().resizable(false))
+        .header(row_height, |mut header| {
+            header.col(|ui| { ui.strong(tr("Name")); });
+            header.col(|ui| { ui.strong(tr("Status")); });
+            header.col(|ui| { ui.strong(tr("First Aired")); });
+            header.col(|ui| { ui.strong(tr("Folder")); });
+            header.col(|ui| { ui.strong(""); });
+        })
+        .body(|mut body| {
+            for entry in entries.iter() {
+                let series = &entry.series;
+                if !gui.searcher.search(series.name.as_str()) {
+                    continue;
+                }
+
+                body.row(row_height, |mut row| {
+                    row.col(|ui| { ui.label(series.name.as_str()); });
+                    row.col(|ui| { ui.label(series.status.as_deref().unwrap_or(tr("Unknown"))); });
+                    row.col(|ui| { ui.label(series.first_aired.as_deref().unwrap_or(tr("Unknown"))); });
+                    row.col(|ui| {
+                        match find_series_folder(folders.as_slice(), series) {
+                            Some(folder) => {
+                                render_icon(ui, Icon::Folder, IconTone::Success);
+                                let res = ui.link(folder.get_folder_name());
+                                if res.clicked() {
+                                    let index = folders.iter().position(|other| Arc::ptr_eq(other, folder));
+                                    *app.get_selected_folder_index().blocking_write() = index;
+                                }
+                            },
+                            None => {
+                                render_icon(ui, Icon::Folder, IconTone::Inactive);
+                                ui.label(tr("No folder"));
+                            },
+                        }
+                    });
+                    row.col(|ui| {
+                        if ui.button(tr("Remove")).clicked() {
+                            tokio::spawn({
+                                let app = app.clone();
+                                let series_id = series.id;
+                                async move {
+                                    app.remove_from_watchlist(series_id).await
+                                }
+                            });
+                        }
+                    });
+                });
+            }
+        });
+}
@@ -0,0 +1,143 @@
+use reqwest;
+use serde;
+use serde_json;
+use thiserror;
+
+use crate::models::Media;
+
+const BASE_URL: &str = "https://graphql.anilist.co";
+
+const MEDIA_FIELDS: &str = r"
+    id
+    idMal
+    title { romaji english native }
+    format
+    status
+    episodes
+    startDate { year month day }
+    synonyms
+    relations { edges { relationType node { id format status episodes } } }
+";
+
+#[derive(serde::Serialize)]
+struct RequestBody<'a, T: serde::Serialize> {
+    query: &'a str,
+    variables: T,
+}
+
+#[derive(serde::Deserialize)]
+struct ResponseBody<T> {
+    data: Option<T>,
+    errors: Option<Vec<ErrorBody>>,
+}
+
+#[derive(serde::Deserialize)]
+struct ErrorBody {
+    message: String,
+}
+
+#[derive(serde::Deserialize)]
+struct PageData {
+    #[serde(rename="Page")]
+    page: MediaPage,
+}
+
+#[derive(serde::Deserialize)]
+struct MediaPage {
+    media: Vec<Media>,
+}
+
+#[derive(serde::Deserialize)]
+struct MediaData {
+    #[serde(rename="Media")]
+    media: Media,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("request failure: {}", .0)]
+    RequestFailure(reqwest::Error),
+    #[error("unexpected response: code={} body={}", .0, .1)]
+    UnexpectedResponse(reqwest::StatusCode, String),
+    #[error("json encode error: {}", .0)]
+    JsonEncode(serde_json::Error),
+    #[error("json decode error: {}", .0)]
+    JsonDecode(serde_json::Error),
+    #[error("missing data in response")]
+    MissingData,
+}
+
+async fn send_query<T, V>(client: &reqwest::Client, query: &str, variables: V) -> Result<T, ApiError> 
+where 
+    T: serde::de::DeserializeOwned, 
+    V: serde::Serialize,
+{
+    let body = RequestBody { query, variables };
+    let res = client
+        .post(BASE_URL)
+        .header("Content-Type", "application/json")
+        .header("Accept", "application/json")
+        .body(serde_json::to_string(&body).map_err(ApiError::JsonEncode)?)
+        .send()
+        .await
+        .map_err(ApiError::RequestFailure)?;
+
+    let status = res.status();
+    let body = res.text().await.map_err(ApiError::RequestFailure)?;
+    let response_body: Result<ResponseBody<T>, serde_json::Error> = serde_json::from_str(body.as_str());
+    if !status.is_success() {
+        let error = match response_body.ok().and_then(|value| value.errors) {
+            Some(errors) => errors.into_iter().map(|e| e.message).collect::<Vec<String>>().join(", "),
+            None => body,
+        };
+        return Err(ApiError::UnexpectedResponse(status, error));
+    }
+
+    let response_body = response_body.map_err(ApiError::JsonDecode)?;
+    response_body.data.ok_or(ApiError::MissingData)
+}
+
+pub async fn search_anime(client: &reqwest::Client, name: &str) -> Result<Vec<Media>, ApiError> {
+    let query = format!(
+        "query ($search: String) {{ Page(perPage: 20) {{ media(search: $search, type: ANIME) {{ {} }} }} }}",
+        MEDIA_FIELDS,
+    );
+    let variables = serde_json::json!({ "search": name });
+    let data: PageData = send_query(client, query.as_str(), variables).await?;
+    Ok(data.page.media)
+}
+
+pub async fn get_anime(client: &reqwest::Client, id: u32) -> Result<Media, ApiError> {
+    let query = format!(
+        "query ($id: Int) {{ Media(id: $id, type: ANIME) {{ {} }} }}",
+        MEDIA_FIELDS,
+    );
+    let variables = serde_json::json!({ "id": id });
+    let data: MediaData = send_query(client, query.as_str(), variables).await?;
+    Ok(data.media)
+}
+
+// Follow the sequel chain of a series so that each entry can be treated as a season
+pub async fn get_anime_seasons(client: &reqwest::Client, id: u32) -> Result<Vec<Media>, ApiError> {
+    const MAX_SEASONS: usize = 32;
+
+    let mut media = get_anime(client, id).await?;
+    // Walk back to the first season
+    for _ in 0..MAX_SEASONS {
+        match media.get_prequel_id() {
+            Some(prequel_id) => media = get_anime(client, prequel_id).await?,
+            None => break,
+        }
+    }
+
+    let mut seasons = Vec::new();
+    for _ in 0..MAX_SEASONS {
+        let sequel_id = media.get_sequel_id();
+        seasons.push(media);
+        match sequel_id {
+            Some(sequel_id) => media = get_anime(client, sequel_id).await?,
+            None => break,
+        }
+    }
+    Ok(seasons)
+}
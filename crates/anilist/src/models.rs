@@ -0,0 +1,83 @@
+use serde;
+use serde_with;
+
+#[serde_with::skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub struct MediaTitle {
+    pub romaji: Option<String>,
+    pub english: Option<String>,
+    pub native: Option<String>,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub struct FuzzyDate {
+    pub year: Option<u32>,
+    pub month: Option<u32>,
+    pub day: Option<u32>,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub struct MediaRelationNode {
+    pub id: u32,
+    pub format: Option<String>,
+    pub status: Option<String>,
+    pub episodes: Option<u32>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub struct MediaRelationEdge {
+    #[serde(rename="relationType")]
+    pub relation_type: String,
+    pub node: MediaRelationNode,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub struct MediaRelations {
+    pub edges: Vec<MediaRelationEdge>,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub struct Media {
+    pub id: u32,
+    #[serde(rename="idMal")]
+    pub mal_id: Option<u32>,
+    pub title: MediaTitle,
+    pub format: Option<String>,
+    pub status: Option<String>,
+    pub episodes: Option<u32>,
+    #[serde(rename="startDate")]
+    pub start_date: Option<FuzzyDate>,
+    pub synonyms: Option<Vec<String>>,
+    pub relations: Option<MediaRelations>,
+}
+
+impl Media {
+    // Airing series without an episode count have no known end, so nothing can follow them
+    pub fn is_open_ended(&self) -> bool {
+        !matches!(self.episodes, Some(episodes) if episodes > 0)
+    }
+
+    // Announced sequels have no episodes yet and aren't treated as a season
+    pub fn get_sequel_id(&self) -> Option<u32> {
+        if self.is_open_ended() {
+            return None;
+        }
+        let relations = self.relations.as_ref()?;
+        relations.edges
+            .iter()
+            .filter(|edge| edge.node.status.as_deref() != Some("NOT_YET_RELEASED"))
+            .find(|edge| edge.relation_type == "SEQUEL" && edge.node.format.as_deref() == self.format.as_deref())
+            .map(|edge| edge.node.id)
+    }
+
+    pub fn get_prequel_id(&self) -> Option<u32> {
+        let relations = self.relations.as_ref()?;
+        relations.edges
+            .iter()
+            .find(|edge| edge.relation_type == "PREQUEL" && edge.node.format.as_deref() == self.format.as_deref())
+            .map(|edge| edge.node.id)
+    }
+}
@@ -0,0 +1,150 @@
+use app::app::App;
+use app::file_descriptor::get_descriptor;
+use app::file_intent::{get_file_intent, FilterRules};
+use app::folder_settings::FolderSettings;
+use app::tvdb_cache::TvdbCache;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use serde_json::json;
+use std::path::Path;
+use std::time::SystemTime;
+use tempfile::TempDir;
+use tvdb::models::{Episode, Series};
+
+const TOTAL_SEASONS: u32 = 8;
+const TOTAL_EPISODES: u32 = 24;
+const APP_CONFIG: &str = include_str!("../../../res/app_config.json");
+
+// Mix of the naming schemes seen in torrents along with extras, subtitles and junk
+fn get_corpus() -> Vec<(String, u64)> {
+    const VIDEO_SIZE: u64 = 700*1024*1024;
+    let mut corpus = Vec::new();
+    for season in 1..=TOTAL_SEASONS {
+        for episode in 1..=TOTAL_EPISODES {
+            let absolute = (season-1)*TOTAL_EPISODES + episode;
+            let filename = match (season + episode) % 6 {
+                0 => format!("Example.Show.S{:02}E{:02}.1080p.WEB.h264-GROUP.mkv", season, episode),
+                1 => format!("Example Show - {}x{:02} - Episode Title [720p].mp4", season, episode),
+                2 => format!("Season {}/Example_Show_S{:02}E{:02}_2160p_HDR10_DV.mkv", season, season, episode),
+                3 => format!("[SubGroup] Example Show - {:03} [1080p].mkv", absolute),
+                4 => format!("Example.Show.Season.{}.Episode.{}.EXTENDED.avi", season, episode),
+                _ => format!("Example Show/S{:02}/E{:02} - Episode Title.mkv", season, episode),
+            };
+            corpus.push((filename, VIDEO_SIZE));
+            if episode % 4 == 0 {
+                corpus.push((format!("Subs/Example.Show.S{:02}E{:02}.en.srt", season, episode), 40*1024));
+            }
+        }
+        corpus.push((format!("Season {}/Featurettes/Behind the Scenes.mkv", season), 200*1024*1024));
+        corpus.push((format!("Season {}/sample.mkv", season), 5*1024*1024));
+        corpus.push((format!("Season {}/RARBG.txt", season), 64));
+        corpus.push((format!("Season {}/Example.Show.S{:02}.nfo", season, season), 2*1024));
+    }
+    corpus
+}
+
+fn get_series() -> Series {
+    serde_json::from_value(json!({
+        "id": 1234,
+        "seriesName": "Example Show",
+        "firstAired": "2010-01-01",
+    })).unwrap()
+}
+
+fn get_episodes() -> Vec<Episode> {
+    let mut episodes = Vec::new();
+    for season in 1..=TOTAL_SEASONS {
+        for episode in 1..=TOTAL_EPISODES {
+            let absolute = (season-1)*TOTAL_EPISODES + episode;
+            episodes.push(serde_json::from_value(json!({
+                "id": absolute,
+                "airedSeason": season,
+                "airedEpisodeNumber": episode,
+                "absoluteNumber": absolute,
+                "episodeName": format!("Episode Title {}", absolute),
+            })).unwrap());
+        }
+    }
+    episodes
+}
+
+fn bench_get_descriptor(c: &mut Criterion) {
+    let corpus = get_corpus();
+    let mut group = c.benchmark_group("get_descriptor");
+    group.throughput(Throughput::Elements(corpus.len() as u64));
+    group.bench_function("corpus", |b| b.iter(|| {
+        for (path, _) in corpus.iter() {
+            black_box(get_descriptor(black_box(path.as_str())));
+        }
+    }));
+    group.finish();
+}
+
+fn bench_get_file_intent(c: &mut Criterion) {
+    let corpus = get_corpus();
+    let rules: FilterRules = serde_json::from_str(APP_CONFIG).unwrap();
+    let cache = TvdbCache::new(get_series(), get_episodes());
+    let settings = FolderSettings::default();
+    let mut group = c.benchmark_group("get_file_intent");
+    group.throughput(Throughput::Elements(corpus.len() as u64));
+    group.bench_function("corpus", |b| b.iter(|| {
+        for (path, size) in corpus.iter() {
+            black_box(get_file_intent(black_box(path.as_str()), *size, &rules, &cache, None, &settings));
+        }
+    }));
+    group.finish();
+}
+
+fn create_folder(folder_path: &Path) {
+    std::fs::create_dir_all(folder_path).unwrap();
+    std::fs::write(folder_path.join("series.json"), serde_json::to_string(&get_series()).unwrap()).unwrap();
+    std::fs::write(folder_path.join("episodes.json"), serde_json::to_string(&get_episodes()).unwrap()).unwrap();
+    std::fs::write(folder_path.join("bookmarks.json"), "[]").unwrap();
+    // Scanning only reads the size from the metadata so sparse files are enough
+    for (path, size) in get_corpus() {
+        let path = folder_path.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::File::create(path).unwrap().set_len(size).unwrap();
+    }
+}
+
+fn touch_files(folder_path: &Path) {
+    let modified = SystemTime::now();
+    for (path, _) in get_corpus() {
+        let file = std::fs::File::options().write(true).open(folder_path.join(path)).unwrap();
+        file.set_modified(modified).unwrap();
+    }
+}
+
+fn bench_folder_scan(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    std::fs::write(config.path().join("app_config.json"), APP_CONFIG).unwrap();
+    let folder_path = root.path().join("Example Show");
+    create_folder(folder_path.as_path());
+
+    let folder = runtime.block_on(async {
+        let app = App::new(config.path().to_str().unwrap()).await.unwrap();
+        app.load_folders(root.path().to_str().unwrap().to_string()).await.unwrap();
+        let folder = app.get_folders().read().await[0].clone();
+        folder.perform_initial_load().await;
+        folder
+    });
+
+    let mut group = c.benchmark_group("folder_scan");
+    group.throughput(Throughput::Elements(get_corpus().len() as u64));
+    // Unchanged files are served from the scan index like a watcher refresh would be
+    group.bench_function("rescan", |b| b.iter(|| {
+        runtime.block_on(folder.update_file_intents())
+    }));
+    // Touching every file makes the scan index miss so every intent is recomputed
+    group.bench_function("full_scan", |b| b.iter_batched(
+        || touch_files(folder_path.as_path()),
+        |_| runtime.block_on(folder.update_file_intents()),
+        BatchSize::PerIteration,
+    ));
+    group.finish();
+}
+
+criterion_group!(benches, bench_get_descriptor, bench_get_file_intent, bench_folder_scan);
+criterion_main!(benches);
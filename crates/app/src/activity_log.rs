@@ -0,0 +1,183 @@
+use chrono;
+use enum_map;
+use serde;
+use serde_json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::RwLock;
+use crate::atomic_file::{get_backup_path, write_atomic};
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Eq, PartialEq, Copy, Clone, enum_map::Enum)]
+pub enum ActivityKind {
+    Refresh,
+    Execute,
+    Cache,
+    Settings,
+    Login,
+}
+
+impl ActivityKind {
+    pub fn iterator() -> std::slice::Iter<'static, Self> {
+        static KINDS: [ActivityKind;5] = [
+            ActivityKind::Refresh,
+            ActivityKind::Execute,
+            ActivityKind::Cache,
+            ActivityKind::Settings,
+            ActivityKind::Login,
+        ];
+        KINDS.iter()
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            ActivityKind::Refresh => "Refresh",
+            ActivityKind::Execute => "Execute",
+            ActivityKind::Cache => "Cache",
+            ActivityKind::Settings => "Settings",
+            ActivityKind::Login => "Login",
+        }
+    }
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct ActivityEvent {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub kind: ActivityKind,
+    pub folder: Option<String>,
+    pub message: String,
+}
+
+// Only the most recent events are kept, the file is trimmed once it holds a batch more than that
+const MAX_EVENTS: usize = 5000;
+const TRIM_BATCH_EVENTS: usize = 500;
+
+// Events are appended to a json lines file so that the history persists across sessions
+pub struct ActivityLog {
+    path: String,
+    events: RwLock<Vec<ActivityEvent>>,
+    // Events dropped from the front of the list, so readers can keep track of which events they have already seen
+    total_trimmed: AtomicUsize,
+}
+
+fn serialize_events<'a>(events: impl Iterator<Item = &'a ActivityEvent>) -> Result<String, std::io::Error> {
+    let mut data = String::new();
+    for event in events {
+        data.push_str(serde_json::to_string(event).map_err(std::io::Error::from)?.as_str());
+        data.push('\n');
+    }
+    Ok(data)
+}
+
+impl ActivityLog {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            events: RwLock::new(Vec::new()),
+            total_trimmed: AtomicUsize::new(0),
+        }
+    }
+
+    pub async fn load_from_file(&self) -> Result<(), std::io::Error> {
+        let data = match tokio::fs::read_to_string(self.path.as_str()).await {
+            Ok(data) => data,
+            // only the backup is left if a rewrite was interrupted
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                match tokio::fs::read_to_string(get_backup_path(std::path::Path::new(self.path.as_str()))).await {
+                    Ok(data) => data,
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+                    Err(err) => return Err(err),
+                }
+            },
+            Err(err) => return Err(err),
+        };
+        // Skip over corrupted lines since each line is written separately
+        let mut events: Vec<ActivityEvent> = data
+            .lines()
+            .filter_map(|line| serde_json::from_str::<ActivityEvent>(line).ok())
+            .collect();
+        events.drain(..events.len().saturating_sub(MAX_EVENTS));
+        *self.events.write().await = events;
+        Ok(())
+    }
+
+    pub async fn push(&self, kind: ActivityKind, folder: Option<&str>, message: String) -> Result<(), std::io::Error> {
+        let event = ActivityEvent {
+            timestamp: chrono::Utc::now(),
+            kind,
+            folder: folder.map(|folder| folder.to_string()),
+            message,
+        };
+
+        let mut line = serde_json::to_string(&event).map_err(std::io::Error::from)?;
+        line.push('\n');
+        let mut events = self.events.write().await;
+        events.push(event);
+
+        // the file is rewritten in batches rather than on every push
+        if events.len() > MAX_EVENTS + TRIM_BATCH_EVENTS {
+            let total_trimmed = events.len() - MAX_EVENTS;
+            let data = serialize_events(events.iter().skip(total_trimmed))?;
+            write_atomic(self.path.as_str(), data).await?;
+            events.drain(..total_trimmed);
+            self.total_trimmed.fetch_add(total_trimmed, Ordering::Relaxed);
+            return Ok(());
+        }
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path.as_str())
+            .await?;
+        file.write_all(line.as_bytes()).await
+    }
+
+    // The whole log is rewritten atomically so a failed write doesn't lose the history
+    pub async fn rename_folder(&self, old_folder: &str, new_folder: &str) -> Result<(), std::io::Error> {
+        let mut events = self.events.write().await;
+        let renamed_events: Vec<ActivityEvent> = events.iter()
+            .cloned()
+            .map(|mut event| {
+                if event.folder.as_deref() == Some(old_folder) {
+                    event.folder = Some(new_folder.to_string());
+                }
+                event
+            })
+            .collect();
+        let data = serialize_events(renamed_events.iter())?;
+
+        write_atomic(self.path.as_str(), data).await?;
+
+        *events = renamed_events;
+        Ok(())
+    }
+
+    pub fn get_events(&self) -> &RwLock<Vec<ActivityEvent>> {
+        &self.events
+    }
+
+    // Only changes while the events are locked for writing
+    pub fn get_total_trimmed(&self) -> usize {
+        self.total_trimmed.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn old_events_are_trimmed() {
+        let folder = tempfile::TempDir::new().unwrap();
+        let path = folder.path().join("activity_log.jsonl");
+        let log = ActivityLog::new(path.to_str().unwrap());
+        for index in 0..=MAX_EVENTS + TRIM_BATCH_EVENTS {
+            log.push(ActivityKind::Refresh, None, index.to_string()).await.unwrap();
+        }
+        let events = log.get_events().read().await;
+        assert_eq!(events.len(), MAX_EVENTS);
+        assert_eq!(events.last().unwrap().message, (MAX_EVENTS + TRIM_BATCH_EVENTS).to_string());
+        assert_eq!(log.get_total_trimmed() + events.len(), MAX_EVENTS + TRIM_BATCH_EVENTS + 1);
+        assert_eq!(std::fs::read_to_string(path).unwrap().lines().count(), MAX_EVENTS);
+    }
+}
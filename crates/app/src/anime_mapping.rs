@@ -0,0 +1,118 @@
+use anilist::models::Media;
+use serde;
+use serde_json;
+use crate::tvdb_cache::{EpisodeKey, EpisodeOrdering, TvdbCache};
+
+// Maps a range of absolute episode numbers onto a season
+// Modelled after the entries in https://github.com/Anime-Lists/anime-lists
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct SeasonMapping {
+    pub season: u32,
+    pub start: u32,
+    pub end: u32,
+    pub episode_offset: u32,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct AnimeMapping {
+    pub anilist_id: Option<u32>,
+    pub seasons: Vec<SeasonMapping>,
+}
+
+impl AnimeMapping {
+    // Each entry in the sequel chain is treated as the next season
+    // The chain ends at the first season with an unknown episode count since it extends forever
+    pub fn from_anilist_seasons(seasons: &[Media]) -> Self {
+        let mut mappings = Vec::new();
+        let mut total_episodes: u32 = 0;
+        for (index, media) in seasons.iter().enumerate() {
+            let start = match total_episodes.checked_add(1) {
+                Some(start) => start,
+                None => break,
+            };
+            let end = match media.episodes {
+                Some(episodes) if episodes > 0 => total_episodes.saturating_add(episodes),
+                _ => u32::MAX,
+            };
+            mappings.push(SeasonMapping {
+                season: index as u32 + 1,
+                start,
+                end,
+                episode_offset: total_episodes,
+            });
+            if media.is_open_ended() {
+                break;
+            }
+            total_episodes = end;
+        }
+
+        Self {
+            anilist_id: seasons.first().map(|media| media.id),
+            seasons: mappings,
+        }
+    }
+
+    pub fn get_episode_key(&self, absolute_episode: u32) -> Option<EpisodeKey> {
+        self.seasons
+            .iter()
+            .find(|mapping| absolute_episode >= mapping.start && absolute_episode <= mapping.end)
+            .map(|mapping| EpisodeKey {
+                season: mapping.season,
+                episode: absolute_episode - mapping.episode_offset,
+            })
+    }
+}
+
+// The user provided mapping takes precedence over the absolute numbering from tvdb
+pub fn get_absolute_episode_key(absolute_episode: u32, mapping: Option<&AnimeMapping>, cache: &TvdbCache, ordering: EpisodeOrdering) -> Option<EpisodeKey> {
+    if let Some(key) = mapping.and_then(|mapping| mapping.get_episode_key(absolute_episode)) {
+        return Some(key);
+    }
+    let index = cache.absolute_cache.get(&absolute_episode)?;
+    cache.get_episode_key(*index, ordering)
+}
+
+pub fn deserialize_anime_mapping(data: &str) -> Result<AnimeMapping, serde_json::Error> {
+    serde_json::from_str(data)
+}
+
+pub fn serialize_anime_mapping(mapping: &AnimeMapping) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(mapping)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anilist::models::MediaTitle;
+
+    fn season(id: u32, episodes: Option<u32>) -> Media {
+        Media {
+            id,
+            mal_id: None,
+            title: MediaTitle { romaji: None, english: None, native: None },
+            format: Some("TV".to_string()),
+            status: None,
+            episodes,
+            start_date: None,
+            synonyms: None,
+            relations: None,
+        }
+    }
+
+    #[test]
+    fn seasons_after_open_ended_season_are_dropped() {
+        let mapping = AnimeMapping::from_anilist_seasons(&[season(1, Some(12)), season(2, None), season(3, Some(12))]);
+        assert_eq!(mapping.seasons.len(), 2);
+        assert_eq!(mapping.get_episode_key(12), Some(EpisodeKey { season: 1, episode: 12 }));
+        assert_eq!(mapping.get_episode_key(13), Some(EpisodeKey { season: 2, episode: 1 }));
+        assert_eq!(mapping.get_episode_key(u32::MAX), Some(EpisodeKey { season: 2, episode: u32::MAX - 12 }));
+    }
+
+    #[test]
+    fn saturated_episode_counts_do_not_overflow() {
+        let mapping = AnimeMapping::from_anilist_seasons(&[season(1, Some(u32::MAX)), season(2, Some(12))]);
+        assert_eq!(mapping.seasons.len(), 1);
+        assert_eq!(mapping.get_episode_key(1), Some(EpisodeKey { season: 1, episode: 1 }));
+    }
+}
@@ -1,12 +1,55 @@
+use chrono;
 use reqwest;
 use serde;
 use serde_json;
 use tokio;
 use tokio::sync::{RwLock, Mutex};
+use mediaserver::api::MediaServerSession;
+use opensubtitles::api::SubtitleSession;
+use qbittorrent::api::TorrentSession;
+use qbittorrent::models::Torrent;
 use tvdb::api::LoginSession;
 use tvdb::models::Series;
-use crate::file_intent::FilterRules;
-use crate::app_folder::AppFolder;
+use crate::activity_log::{ActivityKind, ActivityLog};
+use crate::calendar::{Agenda, get_folder_agenda};
+use crate::cleanup_queue::{CleanupQueue, QueuedCleanup, SeedTargets, get_cleanup_check_interval};
+use crate::diagnostics::{Diagnostic, DiagnosticKind, check_clock_skew, check_root_path};
+use crate::config_reload::{CONFIG_FILENAME, ConfigNotice, ConfigStamp, get_config_changes, get_config_stamp, save_config_value};
+use crate::http_client::{ConnectionTest, NetworkSettings, create_client, save_network_settings, test_connection};
+use crate::execution_journal::{ExecutionJournal, load_journals, move_without_overwrite};
+use crate::folder_merge::{MergeFile, MergePlan, MergeStep, plan_merge_steps};
+use crate::execution_summary::{ExecutionFilter, ExecutionSummary};
+use crate::file_descriptor::get_folder_series_key;
+use crate::folder_watcher::{DEFAULT_AUTO_REFRESH_DELAY_SECS, FolderWatcher};
+use crate::file_intent::{Action, FilterRules, IntentWarning, get_series_folder_name, is_extension_listed};
+use crate::import::{ImportCandidate, ImportPlan, get_download_file_path, get_download_files, get_series_name_from_download, move_file};
+use crate::app_folder::{AppFolder, FolderServices, FolderStatus};
+use crate::metrics::{MEDIA_SERVER_REQUEST, OPENSUBTITLES_REQUEST, QBITTORRENT_REQUEST, TVDB_REQUEST, get_metrics};
+use crate::progress::{ProgressTracker, ProgressUnit};
+use crate::folder_summary::FolderSummaryStore;
+use crate::rebind::{RebindEntry, RebindPlan, SeriesBinding, get_binding_confidence, get_rebind_candidates};
+use crate::refresh_queue::RefreshQueue;
+use crate::remux::remux_to_mkv;
+use crate::rule_profiles::{ProfileStore, RuleProfiles};
+use crate::thumbnail::ThumbnailCache;
+use crate::trash::{
+    TRASH_FOLDER_NAME, TrashEntry, get_expired_trash_entries, get_trash_entry_name, get_trash_path, 
+    check_trash_move, load_trash_entries, move_to_trash, purge_trash_entry,
+};
+use crate::torrent_cleanup::{
+    TorrentCleanup, get_handled_videos, get_release_folder, is_content_removable, is_release_folder_redundant, 
+    is_torrent_finished,
+};
+use crate::torrent_labels::{find_label_rule, get_folder_labels};
+use crate::trakt_sync::TraktSync;
+use crate::library_lock::{LibraryLock, LockState};
+use crate::library_layout::{find_series_folders, list_local_dir};
+use crate::natural_sort::{natural_cmp, sort_natural_by};
+use crate::remote_fs::{RemoteHost, parse_remote_url};
+use crate::tvdb_cache::SeriesCacheStore;
+use crate::watchlist::{Watchlist, sort_by_watchlist};
+use std::collections::{HashMap, HashSet};
+use std::path;
 use std::sync::Arc;
 use thiserror;
 
@@ -14,8 +57,23 @@ use thiserror;
 pub struct Credentials {
     #[serde(rename="credentials")]
     pub login_info: tvdb::api::LoginInfo,     
+    // Extra api keys which are switched to when the main one is rate limited
+    #[serde(default)]
+    pub fallback_logins: Vec<tvdb::api::LoginInfo>,
     // TODO: Reuse tokens if possible to avoid login requests on startup
     pub token: Option<String>,
+    // Subtitles can only be fetched if an api key is given
+    #[serde(default)]
+    pub opensubtitles: Option<opensubtitles::api::LoginInfo>,
+    // Renamed episodes are only synced to trakt if a client is given
+    #[serde(default)]
+    pub trakt: Option<trakt::api::ClientInfo>,
+    // Torrent labels are only routed to folder rules if the web ui is given
+    #[serde(default)]
+    pub qbittorrent: Option<qbittorrent::api::LoginInfo>,
+    // Watched episodes are only shown if a Plex or Jellyfin server is given
+    #[serde(default)]
+    pub media_server: Option<mediaserver::api::LoginInfo>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -26,54 +84,211 @@ pub enum AppInitError {
     JsonDecodeFilterRules(serde_json::Error),
 }
 
+async fn get_merge_files(folder: &AppFolder) -> Vec<MergeFile> {
+    folder.get_files().await
+        .to_iter()
+        .map(|file| MergeFile {
+            src: file.get_src().to_string(),
+            descriptor: *file.get_src_descriptor(),
+            size: file.get_size(),
+        })
+        .collect()
+}
+
 pub struct App {
-    filter_rules: Arc<FilterRules>,
+    filter_rules: RwLock<Arc<FilterRules>>,
     config_path: String,
+    activity_log: Arc<ActivityLog>,
+    cache_store: Arc<SeriesCacheStore>,
+    watchlist: Watchlist,
+    thumbnail_cache: Arc<ThumbnailCache>,
+    profile_store: Arc<ProfileStore>,
+    trakt_sync: Arc<TraktSync>,
+    library_lock: Arc<LibraryLock>,
+    summary_store: Arc<FolderSummaryStore>,
 
-    client: Arc<reqwest::Client>,
+    client: RwLock<Arc<reqwest::Client>>,
     login_session: RwLock<Option<Arc<LoginSession>>>,
+    subtitle_session: RwLock<Option<Arc<SubtitleSession>>>,
+    torrent_session: RwLock<Option<Arc<TorrentSession>>>,
+    media_server_session: RwLock<Option<Arc<MediaServerSession>>>,
     
     root_path: RwLock<String>,
+    remote: RwLock<Option<Arc<RemoteHost>>>,
     folders: RwLock<Vec<Arc<AppFolder>>>,
     selected_folder_index: RwLock<Option<usize>>,
+    // Folders shown by the filters of the folders list are refreshed first
+    visible_folder_indices: RwLock<Vec<usize>>,
     folders_busy_lock: Mutex<()>,
 
     series: RwLock<Option<Vec<Series>>>,
     selected_series_index: RwLock<Option<usize>>,
     series_busy_lock: Mutex<()>,
 
+    import_plan: RwLock<Option<ImportPlan>>,
+    import_busy_lock: Mutex<()>,
+
+    bindings: RwLock<Option<Vec<SeriesBinding>>>,
+    rebind_plan: RwLock<Option<RebindPlan>>,
+    rebind_busy_lock: Mutex<()>,
+
+    agenda: RwLock<Option<Agenda>>,
+    agenda_busy_lock: Mutex<()>,
+
+    interrupted_executions: RwLock<Vec<ExecutionJournal>>,
+    progress: RwLock<Option<Arc<ProgressTracker>>>,
+
+    is_watching_folders: RwLock<bool>,
+    folder_watcher: RwLock<Option<FolderWatcher>>,
+
+    diagnostics: RwLock<Vec<Diagnostic>>,
+    diagnostics_busy_lock: Mutex<()>,
+
+    config_stamp: Mutex<Option<ConfigStamp>>,
+    config_notice: RwLock<Option<ConfigNotice>>,
+
+    trash_entries: RwLock<Option<Vec<TrashEntry>>>,
+    trash_busy_lock: Mutex<()>,
+    last_trash_purge: Mutex<Option<std::time::Instant>>,
+
+    torrent_cleanups: RwLock<Option<Vec<TorrentCleanup>>>,
+    // Complete torrents which are still seeding and can be queued for a deferred cleanup
+    seeding_torrents: RwLock<Vec<Torrent>>,
+    torrent_cleanup_busy_lock: Mutex<()>,
+    cleanup_queue: CleanupQueue,
+    last_cleanup_check: Mutex<Option<std::time::Instant>>,
+
     errors: RwLock<Vec<String>>,
 }
 
 impl App {
     pub async fn new(config_path: &str) -> Result<App, AppInitError> {
-        let filter_rules_str = tokio::fs::read_to_string(format!("{}/app_config.json", config_path)).await;
+        let filter_rules_str = tokio::fs::read_to_string(format!("{}/{}", config_path, CONFIG_FILENAME)).await;
         let filter_rules_str = filter_rules_str.map_err(AppInitError::IOFilterRulesLoad)?;
         let filter_rules: FilterRules = serde_json::from_str(filter_rules_str.as_str())
             .map_err(AppInitError::JsonDecodeFilterRules)?;
 
+        let mut errors = Vec::new();
+        let activity_log = ActivityLog::new(format!("{}/activity_log.jsonl", config_path).as_str());
+        if let Err(err) = activity_log.load_from_file().await {
+            errors.push(format!("IO error while reading activity log: {}", err));
+        }
+
+        let cleanup_queue = CleanupQueue::new(format!("{}/cleanup_queue.json", config_path).as_str());
+        if let Err(err) = cleanup_queue.load_from_file().await {
+            errors.push(format!("IO error while reading cleanup queue: {}", err));
+        }
+        let watchlist = Watchlist::new(format!("{}/watchlist.json", config_path).as_str());
+        if let Err(err) = watchlist.load_from_file().await {
+            errors.push(format!("IO error while reading watchlist: {}", err));
+        }
+
+        let profile_store = ProfileStore::new(format!("{}/profiles.json", config_path).as_str());
+        if let Err(err) = profile_store.load_from_file().await {
+            errors.push(format!("IO error while reading rule profiles: {}", err));
+        }
+
+        let thumbnail_cache = ThumbnailCache::new(format!("{}/thumbnails", config_path).as_str(), filter_rules.get_ffmpeg_path());
+
+        let config_stamp = get_config_stamp(path::Path::new(config_path).join(CONFIG_FILENAME).as_path()).await;
+        // A broken proxy or certificate is reported but shouldn't keep the app from starting
+        let client = match create_client(&filter_rules.network).await {
+            Ok(client) => client,
+            Err(err) => {
+                errors.push(format!("Couldn't apply the network settings, using a direct connection: {}", err));
+                reqwest::Client::new()
+            },
+        };
+        let client = Arc::new(client);
+        let trakt_sync = TraktSync::new(format!("{}/trakt_token.json", config_path).as_str(), client.clone());
+
+        let is_watching_folders = filter_rules.watch_folders;
         Ok(App {
-            filter_rules: Arc::new(filter_rules),
+            filter_rules: RwLock::new(Arc::new(filter_rules)),
             config_path: config_path.to_string(),
+            activity_log: Arc::new(activity_log),
+            cache_store: Arc::new(SeriesCacheStore::default()),
+            watchlist,
+            thumbnail_cache: Arc::new(thumbnail_cache),
+            profile_store: Arc::new(profile_store),
+            trakt_sync: Arc::new(trakt_sync),
+            library_lock: Arc::new(LibraryLock::new()),
+            summary_store: Arc::new(FolderSummaryStore::new(format!("{}/folder_summaries.json", config_path).as_str())),
 
-            client: Arc::new(reqwest::Client::new()),
+            client: RwLock::new(client),
             login_session: RwLock::new(None),
+            subtitle_session: RwLock::new(None),
+            torrent_session: RwLock::new(None),
+            media_server_session: RwLock::new(None),
             
             root_path: RwLock::new(".".to_string()),
+            remote: RwLock::new(None),
             folders: RwLock::new(Vec::new()),
             selected_folder_index: RwLock::new(None),
+            visible_folder_indices: RwLock::new(Vec::new()),
             folders_busy_lock: Mutex::new(()),
 
             series: RwLock::new(None),
             selected_series_index: RwLock::new(None),
             series_busy_lock: Mutex::new(()),
 
-            errors: RwLock::new(Vec::new()),
+            import_plan: RwLock::new(None),
+            import_busy_lock: Mutex::new(()),
+
+            bindings: RwLock::new(None),
+            rebind_plan: RwLock::new(None),
+            rebind_busy_lock: Mutex::new(()),
+
+            agenda: RwLock::new(None),
+            agenda_busy_lock: Mutex::new(()),
+
+            interrupted_executions: RwLock::new(Vec::new()),
+            progress: RwLock::new(None),
+
+            is_watching_folders: RwLock::new(is_watching_folders),
+            folder_watcher: RwLock::new(None),
+
+            diagnostics: RwLock::new(Vec::new()),
+            diagnostics_busy_lock: Mutex::new(()),
+
+            config_stamp: Mutex::new(config_stamp),
+            config_notice: RwLock::new(None),
+
+            trash_entries: RwLock::new(None),
+            trash_busy_lock: Mutex::new(()),
+            last_trash_purge: Mutex::new(None),
+
+            torrent_cleanups: RwLock::new(None),
+            seeding_torrents: RwLock::new(Vec::new()),
+            torrent_cleanup_busy_lock: Mutex::new(()),
+            cleanup_queue,
+            last_cleanup_check: Mutex::new(None),
+
+            errors: RwLock::new(errors),
         })
     }
 }
 
 impl App {
+    async fn start_progress(&self, name: &'static str, unit: ProgressUnit, total: u64) -> Arc<ProgressTracker> {
+        let progress = Arc::new(ProgressTracker::new(name, unit, total));
+        *self.progress.write().await = Some(progress.clone());
+        progress
+    }
+
+    async fn start_cancellable_progress(&self, name: &'static str, unit: ProgressUnit, total: u64) -> Arc<ProgressTracker> {
+        let progress = Arc::new(ProgressTracker::new_cancellable(name, unit, total));
+        *self.progress.write().await = Some(progress.clone());
+        progress
+    }
+
+    async fn push_activity(&self, kind: ActivityKind, message: String) {
+        if let Err(err) = self.activity_log.push(kind, None, message).await {
+            let message = format!("IO error while writing activity log: {}", err);
+            self.errors.write().await.push(message);
+        }
+    }
+
     pub async fn login(&self) -> Option<()> {
         let credentials_str = tokio::fs::read_to_string(format!("{}/credentials.json", self.config_path.as_str())).await;
         
@@ -94,11 +309,14 @@ impl App {
                 return None;
             },
         };
-        let token = tvdb::api::login(self.client.as_ref(), &credentials.login_info).await;
+        let base_url = self.filter_rules.read().await.tvdb_base_url.clone().unwrap_or(tvdb::api::BASE_URL.to_string());
+        let base_url = base_url.as_str();
+        let token = get_metrics().time(TVDB_REQUEST, tvdb::api::login(self.get_http_client().await.as_ref(), base_url, &credentials.login_info)).await;
         let token = match token {
             Ok(token) => token,
             Err(err) => {
                 let message = format!("Login failed at tvdb api: {}", err);
+                self.push_activity(ActivityKind::Login, message.clone()).await;
                 self.errors.write().await.push(message);
                 // If login failed at this point it's possible credentials were invalidated externally
                 *self.login_session.write().await = None;
@@ -106,157 +324,2274 @@ impl App {
             },
         };
 
-        let session = LoginSession::new(self.client.clone(), &token);
+        let mut accounts = vec![credentials.login_info.clone()];
+        accounts.extend(credentials.fallback_logins.iter().cloned());
+        let session = LoginSession::new(self.get_http_client().await, base_url, &token).with_accounts(&accounts);
         *self.login_session.write().await = Some(Arc::new(session));
+        self.push_activity(ActivityKind::Login, "Logged in to tvdb api".to_string()).await;
+        if let Some(login_info) = credentials.opensubtitles.as_ref() {
+            self.login_opensubtitles(login_info).await;
+        }
+        if let Some(client_info) = credentials.trakt.as_ref() {
+            self.login_trakt(client_info).await;
+        }
+        if let Some(login_info) = credentials.qbittorrent.as_ref() {
+            if self.login_qbittorrent(login_info).await.is_some() {
+                self.apply_torrent_labels().await;
+            }
+        }
+        if let Some(login_info) = credentials.media_server.as_ref() {
+            if self.login_media_server(login_info).await.is_some() {
+                self.update_watch_states().await;
+            }
+        }
         Some(())
     }
 
-    pub fn get_login_session(&self) -> &RwLock<Option<Arc<LoginSession>>> {
-        &self.login_session
+    async fn login_media_server(&self, login_info: &mediaserver::api::LoginInfo) -> Option<()> {
+        let server_name = login_info.kind.to_str();
+        match get_metrics().time(MEDIA_SERVER_REQUEST, mediaserver::api::login(self.get_http_client().await, login_info)).await {
+            Ok(session) => {
+                *self.media_server_session.write().await = Some(Arc::new(session));
+                self.push_activity(ActivityKind::Login, format!("Logged in to {}", server_name)).await;
+                Some(())
+            },
+            Err(err) => {
+                let message = format!("Login failed at {}: {}", server_name, err);
+                self.push_activity(ActivityKind::Login, message.clone()).await;
+                self.errors.write().await.push(message);
+                *self.media_server_session.write().await = None;
+                None
+            },
+        }
     }
 
-    pub async fn load_folders_from_existing_root_path(&self) -> Option<()> {
-        let path = self.root_path.read().await.clone();
-        self.load_folders(path).await
+    // Episodes watched on the media server are shown in the episode list and calendar
+    // Intents are updated since the watch state can keep seasons out of the archive
+    pub async fn update_watch_states(&self) -> Option<()> {
+        let session = match self.media_server_session.read().await.clone() {
+            Some(session) => session,
+            None => {
+                let message = "Login to a media server is required to sync the watch state";
+                self.errors.write().await.push(message.to_string());
+                return None;
+            },
+        };
+        let server_name = session.get_kind().to_str();
+        let library_index = match get_metrics().time(MEDIA_SERVER_REQUEST, session.get_library_index()).await {
+            Ok(library_index) => library_index,
+            Err(err) => {
+                let message = format!("Api error while fetching the library from {}: {}", server_name, err);
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
+
+        let is_archive_affected = self.filter_rules.read().await.keep_unwatched_seasons;
+        let folders = self.folders.read().await.clone();
+        let mut total_synced = 0;
+        for folder in folders.iter() {
+            folder.perform_initial_load().await;
+            if folder.load_watch_state(session.as_ref(), &library_index).await.is_none() {
+                continue;
+            }
+            if folder.get_watch_state().read().await.is_none() {
+                continue;
+            }
+            total_synced += 1;
+            if is_archive_affected && !*folder.get_is_plan_locked().lock().await && !folder.is_pinned().await {
+                folder.update_file_intents().await;
+            }
+        }
+        self.push_activity(ActivityKind::Refresh, format!("Synced the watch state of {} folders from {}", total_synced, server_name)).await;
+        Some(())
     }
 
-    pub async fn load_folders(&self, root_path: String) -> Option<()> {
-        let _busy_lock = self.folders_busy_lock.lock().await;
-        // NOTE: If for some reason the folder load failed we can still reattempt 
-        *self.root_path.write().await = root_path.clone();
+    async fn login_qbittorrent(&self, login_info: &qbittorrent::api::LoginInfo) -> Option<()> {
+        match get_metrics().time(QBITTORRENT_REQUEST, qbittorrent::api::login(self.get_http_client().await, login_info)).await {
+            Ok(session) => {
+                *self.torrent_session.write().await = Some(Arc::new(session));
+                self.push_activity(ActivityKind::Login, "Logged in to qBittorrent".to_string()).await;
+                Some(())
+            },
+            Err(err) => {
+                let message = format!("Login failed at qBittorrent: {}", err);
+                self.push_activity(ActivityKind::Login, message.clone()).await;
+                self.errors.write().await.push(message);
+                *self.torrent_session.write().await = None;
+                None
+            },
+        }
+    }
 
-        let mut new_folders = Vec::new();
-        let entries = tokio::fs::read_dir(root_path.as_str()).await; 
-        let mut entries = match entries {
-            Ok(entries) => entries,
+    // Seeds the settings of folders from the labels of the torrents inside them
+    pub async fn apply_torrent_labels(&self) -> Option<()> {
+        let filter_rules = self.filter_rules.read().await.clone();
+        if filter_rules.label_rules.is_empty() {
+            return Some(());
+        }
+        let session = self.torrent_session.read().await.clone()?;
+        let torrents = match get_metrics().time(QBITTORRENT_REQUEST, session.get_torrents()).await {
+            Ok(torrents) => torrents,
             Err(err) => {
-                let message = format!("Error on loading folders from '{}': {}", root_path.as_str(), err);
+                let message = format!("Api error while fetching torrents from qBittorrent: {}", err);
                 self.errors.write().await.push(message);
                 return None;
             },
         };
 
-        loop {
-            let entry_opt = match entries.next_entry().await {
-                Ok(entry_opt) => entry_opt,
-                Err(err) => {
-                    let message = format!("Error during iteraton when getting next entry from folder '{}': {}", root_path.as_str(), err);
-                    self.errors.write().await.push(message);
-                    return None;
-                },
+        let folders = self.folders.read().await.clone();
+        for folder in folders {
+            let labels = get_folder_labels(torrents.as_slice(), path::Path::new(folder.get_folder_path()));
+            let rule = match find_label_rule(filter_rules.label_rules.as_slice(), labels.as_slice()) {
+                Some(rule) => rule,
+                None => continue,
             };
+            folder.perform_initial_load().await;
+            if folder.is_excluded().await || folder.is_pinned().await {
+                continue;
+            }
+            if folder.apply_label_rule(rule).await {
+                folder.refresh_file_intents(None).await;
+            }
+        }
+        Some(())
+    }
 
-            let entry = match entry_opt {
-                Some(entry) => entry,
-                None => break,
-            };
+    pub async fn load_torrent_cleanups(&self) -> Option<()> {
+        let _busy_lock = self.torrent_cleanup_busy_lock.lock().await;
+        self.reload_torrent_cleanups().await
+    }
+
+    // Finished torrents whose videos are all in the library, the client's paths must be the same as ours to tell
+    async fn reload_torrent_cleanups(&self) -> Option<()> {
+        *self.torrent_cleanups.write().await = None;
+        self.seeding_torrents.write().await.clear();
+        self.check_local_root("clean up torrents").await?;
+        let session = match self.torrent_session.read().await.clone() {
+            Some(session) => session,
+            None => {
+                let message = "Login to qBittorrent is required to clean up torrents";
+                self.errors.write().await.push(message.to_string());
+                return None;
+            },
+        };
+        let torrents = match get_metrics().time(QBITTORRENT_REQUEST, session.get_torrents()).await {
+            Ok(torrents) => torrents,
+            Err(err) => {
+                let message = format!("Api error while fetching torrents from qBittorrent: {}", err);
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
+
+        let root_path = self.root_path.read().await.clone();
+        let folder_paths: Vec<String> = self.folders.read().await
+            .iter()
+            .map(|folder| folder.get_folder_path().to_string())
+            .collect();
+        let protected_paths: Vec<&path::Path> = std::iter::once(root_path.as_str())
+            .chain(folder_paths.iter().map(|path| path.as_str()))
+            .map(path::Path::new)
+            .collect();
 
-            let path = entry.path();
-            let file_type = match entry.file_type().await {
-                Ok(file_type) => file_type,
+        let queued_hashes: Vec<String> = self.cleanup_queue.get_entries().read().await.iter().map(|entry| entry.hash.clone()).collect();
+        let mut seeding_torrents: Vec<Torrent> = torrents.iter()
+            .filter(|torrent| torrent.is_complete() && torrent.is_seeding() && !queued_hashes.contains(&torrent.hash))
+            .cloned()
+            .collect();
+        sort_natural_by(seeding_torrents.as_mut_slice(), |torrent| torrent.name.as_str());
+        *self.seeding_torrents.write().await = seeding_torrents;
+
+        let mut cleanups = Vec::new();
+        for torrent in torrents.iter().filter(|torrent| is_torrent_finished(torrent)) {
+            // a save path we can't see is on another machine, its files would all look missing
+            let save_path = path::Path::new(torrent.save_path.as_str());
+            if torrent.save_path.is_empty() || !save_path.is_dir() {
+                continue;
+            }
+            let files = match get_metrics().time(QBITTORRENT_REQUEST, session.get_torrent_files(torrent.hash.as_str())).await {
+                Ok(files) => files,
                 Err(err) => {
-                    let path_str = path.to_str().unwrap_or(root_path.as_str());
-                    let message = format!("Error during iteration when getting file type from folder '{}': {}", path_str, err);
+                    let message = format!("Api error while fetching files of torrent '{}' from qBittorrent: {}", torrent.name, err);
                     self.errors.write().await.push(message);
-                    return None;
+                    continue;
                 },
             };
+            let total_videos = match get_handled_videos(save_path, files.as_slice(), |path| path.exists()) {
+                Some(total_videos) => total_videos,
+                None => continue,
+            };
+            let release_path = match get_release_folder(torrent, protected_paths.as_slice()) {
+                Some(path) if path.is_dir() => {
+                    let release_path = path.clone();
+                    let is_redundant = tokio::task::spawn_blocking(move || is_release_folder_redundant(release_path.as_path())).await.unwrap_or(false);
+                    Some(path).filter(|_| is_redundant)
+                },
+                _ => None,
+            };
+            cleanups.push(TorrentCleanup {
+                hash: torrent.hash.clone(),
+                name: torrent.name.clone(),
+                state: torrent.state.clone(),
+                total_videos,
+                release_path,
+            });
+        }
+        sort_natural_by(cleanups.as_mut_slice(), |cleanup| cleanup.name.as_str());
+        *self.torrent_cleanups.write().await = Some(cleanups);
+        Some(())
+    }
+
+    // Removes the torrents from the client and moves their release folders into the trash
+    pub async fn execute_torrent_cleanups(&self, hashes: Vec<String>) -> Option<()> {
+        let _busy_lock = self.torrent_cleanup_busy_lock.lock().await;
+        let session = self.torrent_session.read().await.clone()?;
+        let cleanups: Vec<TorrentCleanup> = self.torrent_cleanups.read().await
+            .as_ref()?
+            .iter()
+            .filter(|cleanup| hashes.contains(&cleanup.hash))
+            .cloned()
+            .collect();
+        if cleanups.is_empty() {
+            return None;
+        }
 
-            if !file_type.is_dir() {
+        // torrents are only removed once their release folder is known to fit in the trash
+        let root_path = self.root_path.read().await.clone();
+        let trash_entry_name = get_trash_entry_name(&chrono::Utc::now());
+        let mut trash_moves: Vec<(path::PathBuf, path::PathBuf)> = Vec::new();
+        let mut cleanup_hashes: Vec<&str> = Vec::new();
+        for cleanup in cleanups.iter() {
+            let release_path = match cleanup.release_path.as_ref() {
+                Some(path) => path,
+                None => {
+                    cleanup_hashes.push(cleanup.hash.as_str());
+                    continue;
+                },
+            };
+            let folder_name = release_path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+            let trash_path = path::Path::new(root_path.as_str())
+                .join(TRASH_FOLDER_NAME)
+                .join(trash_entry_name.as_str())
+                .join(folder_name.as_str());
+            let res = match trash_moves.iter().any(|(_, other)| *other == trash_path) {
+                true => Err(std::io::Error::new(std::io::ErrorKind::AlreadyExists, "Another release folder has the same name")),
+                false => check_trash_move(release_path.as_path(), trash_path.as_path()).await,
+            };
+            if let Err(err) = res {
+                let message = format!("Kept torrent '{}' in qBittorrent since its release folder can't be trashed: {}", cleanup.name, err);
+                self.errors.write().await.push(message);
                 continue;
             }
+            cleanup_hashes.push(cleanup.hash.as_str());
+            trash_moves.push((release_path.clone(), trash_path));
+        }
+        if cleanup_hashes.is_empty() {
+            return None;
+        }
 
-            if let Some(path) = path.to_str() {
-                let folder = AppFolder::new(root_path.as_str(), path, self.filter_rules.clone());
-                new_folders.push(Arc::new(folder));
+        // the client must let go of the files before they are moved
+        if let Err(err) = get_metrics().time(QBITTORRENT_REQUEST, session.delete_torrents(cleanup_hashes.as_slice(), false)).await {
+            let message = format!("Api error while removing torrents from qBittorrent: {}", err);
+            self.errors.write().await.push(message);
+            return None;
+        }
+
+        let mut total_trashed = 0;
+        for (release_path, trash_path) in trash_moves.iter() {
+            match move_to_trash(release_path.as_path(), trash_path.as_path()).await {
+                Ok(()) => total_trashed += 1,
+                Err(err) => {
+                    let message = format!("IO error while trashing release folder '{}': {}", release_path.to_string_lossy(), err);
+                    self.errors.write().await.push(message);
+                },
             }
         }
-        
-        new_folders.sort_by(|a, b| {
-            let a = a.as_ref();
-            let b = b.as_ref();
-            let a_name = a.get_folder_name();
-            let b_name = b.get_folder_name();
-            a_name.partial_cmp(b_name).unwrap_or(std::cmp::Ordering::Equal)
-        });
 
-        let (mut folders, mut selected_folder_index) = tokio::join!(
-            self.folders.write(),
-            self.selected_folder_index.write(),
-        );
-        *folders = new_folders;
-        *selected_folder_index = None;
+        let message = format!("Removed {} torrents from qBittorrent and trashed {} release folders", cleanup_hashes.len(), total_trashed);
+        self.push_activity(ActivityKind::Execute, message).await;
+        self.reload_torrent_cleanups().await
+    }
+
+    // Seeded files are kept until the client reports the seed targets as met
+    pub async fn queue_torrent_cleanup(&self, hash: &str) -> Option<()> {
+        let _busy_lock = self.torrent_cleanup_busy_lock.lock().await;
+        let torrent = self.seeding_torrents.read().await.iter().find(|torrent| torrent.hash == hash).cloned()?;
+        let targets = self.filter_rules.read().await.seed_targets;
+        if let Err(err) = self.cleanup_queue.add(QueuedCleanup::new(&torrent, targets)).await {
+            let message = format!("IO error while queueing cleanup of torrent '{}': {}", torrent.name, err);
+            self.errors.write().await.push(message);
+            return None;
+        }
+        self.seeding_torrents.write().await.retain(|other| other.hash != hash);
+        self.push_activity(ActivityKind::Settings, format!("Queued cleanup of torrent '{}' once it is done seeding", torrent.name)).await;
         Some(())
     }
 
-    pub async fn update_search_series(&self, search: String) -> Option<()> {
-        let _busy_lock = self.series_busy_lock.lock().await;
-        let login_session = self.login_session.read().await;
-        let session = match login_session.as_ref() {
-            Some(session) => session,
-            None => {
-                let message = "Login session is required to update the series search results";
-                self.errors.write().await.push(message.to_string());
-                return None;
-            },
-        };
-        let search_results = match session.search_series(&search).await {
-            Ok(results) => results,
+    pub async fn set_queued_cleanup_targets(&self, hash: &str, targets: SeedTargets) -> Option<()> {
+        if let Err(err) = self.cleanup_queue.set_targets(hash, targets).await {
+            let message = format!("IO error while updating cleanup queue: {}", err);
+            self.errors.write().await.push(message);
+            return None;
+        }
+        Some(())
+    }
+
+    pub async fn remove_queued_cleanup(&self, hash: &str) -> Option<()> {
+        if let Err(err) = self.cleanup_queue.remove(&[hash]).await {
+            let message = format!("IO error while updating cleanup queue: {}", err);
+            self.errors.write().await.push(message);
+            return None;
+        }
+        Some(())
+    }
+
+    // Polled periodically, only checks the client once the interval in the app config has passed
+    pub async fn process_cleanup_queue_if_due(&self) -> Option<()> {
+        if self.cleanup_queue.get_entries().read().await.is_empty() || self.torrent_session.read().await.is_none() {
+            return None;
+        }
+        if self.root_path.read().await.is_empty() || self.remote.read().await.is_some() {
+            return None;
+        }
+        self.library_lock.check().await.ok()?;
+        let interval = get_cleanup_check_interval(self.filter_rules.read().await.cleanup_check_interval_secs);
+        let is_due = self.last_cleanup_check.lock().await
+            .map(|last_check| last_check.elapsed() >= interval)
+            .unwrap_or(true);
+        if !is_due || self.torrent_cleanup_busy_lock.try_lock().is_err() {
+            return None;
+        }
+        self.process_cleanup_queue().await
+    }
+
+    // Removes queued torrents which met their seed targets from the client and moves their files into the trash
+    pub async fn process_cleanup_queue(&self) -> Option<()> {
+        let _busy_lock = self.torrent_cleanup_busy_lock.lock().await;
+        *self.last_cleanup_check.lock().await = Some(std::time::Instant::now());
+        self.check_local_root("clean up torrents").await?;
+        let session = self.torrent_session.read().await.clone()?;
+        let torrents = match get_metrics().time(QBITTORRENT_REQUEST, session.get_torrents()).await {
+            Ok(torrents) => torrents,
             Err(err) => {
-                let message = format!("Failed to get series search results due to api error: {}", err);
+                let message = format!("Api error while fetching torrents from qBittorrent: {}", err);
                 self.errors.write().await.push(message);
                 return None;
             },
         };
+        if let Err(err) = self.cleanup_queue.update_progress(torrents.as_slice()).await {
+            let message = format!("IO error while updating cleanup queue: {}", err);
+            self.errors.write().await.push(message);
+        }
 
-        let (mut series, mut series_index) = tokio::join!(
-            self.series.write(),
-            self.selected_series_index.write(),
-        );
-        *series = Some(search_results);
-        *series_index = None;
+        let entries = self.cleanup_queue.get_entries().read().await.clone();
+        let mut finished = Vec::new();
+        let mut missing = Vec::new();
+        for entry in entries.iter() {
+            match torrents.iter().find(|torrent| torrent.hash == entry.hash) {
+                Some(torrent) if entry.targets.is_met(torrent) => finished.push((entry, torrent)),
+                Some(_) => {},
+                // removed from the client by hand, so there is nothing left to wait for
+                None => missing.push(entry),
+            }
+        }
+        if finished.is_empty() && missing.is_empty() {
+            return Some(());
+        }
+
+        // finished torrents stay queued until their files can be moved into the trash
+        let root_path = self.root_path.read().await.clone();
+        let folder_paths: Vec<String> = self.folders.read().await
+            .iter()
+            .map(|folder| folder.get_folder_path().to_string())
+            .collect();
+        let folder_paths: Vec<&path::Path> = folder_paths.iter().map(path::Path::new).collect();
+        let trash_entry_name = get_trash_entry_name(&chrono::Utc::now());
+        let mut trash_moves: Vec<(&str, path::PathBuf, path::PathBuf)> = Vec::new();
+        let mut removable = Vec::new();
+        for (entry, torrent) in finished.into_iter() {
+            let content_path = path::Path::new(entry.content_path.as_str());
+            if !content_path.exists() {
+                removable.push(entry);
+                continue;
+            }
+            let save_path = path::Path::new(torrent.save_path.as_str());
+            if !is_content_removable(content_path, save_path, path::Path::new(root_path.as_str()), folder_paths.as_slice()) {
+                let message = format!("Kept the files of torrent '{}' since they aren't a release of their own outside of the library", entry.name);
+                self.errors.write().await.push(message);
+                removable.push(entry);
+                continue;
+            }
+            let name = content_path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+            let trash_path = path::Path::new(root_path.as_str())
+                .join(TRASH_FOLDER_NAME)
+                .join(trash_entry_name.as_str())
+                .join(name.as_str());
+            let res = match trash_moves.iter().any(|(_, _, other)| *other == trash_path) {
+                true => Err(std::io::Error::new(std::io::ErrorKind::AlreadyExists, "Another torrent has the same name")),
+                false => check_trash_move(content_path, trash_path.as_path()).await,
+            };
+            if let Err(err) = res {
+                let message = format!("Kept torrent '{}' in qBittorrent since its files can't be trashed: {}", entry.name, err);
+                self.errors.write().await.push(message);
+                continue;
+            }
+            trash_moves.push((entry.name.as_str(), content_path.to_path_buf(), trash_path));
+            removable.push(entry);
+        }
+        let finished = removable;
+
+        let finished_hashes: Vec<&str> = finished.iter().map(|entry| entry.hash.as_str()).collect();
+        if !finished_hashes.is_empty() {
+            // the client must let go of the files before they are moved
+            if let Err(err) = get_metrics().time(QBITTORRENT_REQUEST, session.delete_torrents(finished_hashes.as_slice(), false)).await {
+                let message = format!("Api error while removing torrents from qBittorrent: {}", err);
+                self.errors.write().await.push(message);
+                return None;
+            }
+        }
+
+        let mut total_trashed = 0;
+        for (name, content_path, trash_path) in trash_moves.iter() {
+            match move_to_trash(content_path.as_path(), trash_path.as_path()).await {
+                Ok(()) => total_trashed += 1,
+                Err(err) => {
+                    let message = format!("IO error while trashing files of torrent '{}': {}", name, err);
+                    self.errors.write().await.push(message);
+                },
+            }
+        }
+
+        let removed_hashes: Vec<&str> = finished.iter().chain(missing.iter()).map(|entry| entry.hash.as_str()).collect();
+        if let Err(err) = self.cleanup_queue.remove(removed_hashes.as_slice()).await {
+            let message = format!("IO error while updating cleanup queue: {}", err);
+            self.errors.write().await.push(message);
+        }
+        if !finished.is_empty() {
+            let message = format!("Cleaned up {} torrents which met their seed targets and trashed {} of their files", finished.len(), total_trashed);
+            self.push_activity(ActivityKind::Execute, message).await;
+        }
+        if !missing.is_empty() {
+            let message = format!("Dropped {} queued cleanups since their torrents are no longer in qBittorrent", missing.len());
+            self.push_activity(ActivityKind::Execute, message).await;
+        }
         Some(())
     }
 
-    pub async fn update_file_intents_for_all_folders(&self) -> Option<()> {
-        // Allow the folder to be read while it is busy
-        // Disallow load_folders(...) while we are performing an update on all folders
-        let _busy_lock = self.folders_busy_lock.lock().await;
-        {
-            let folders = self.folders.read().await;
-            for folder in folders.iter() {
-                let res = folder.perform_initial_load().await;
-                // Initial load already occured, we therefore just rescan the folder
-                if res.is_none() {
-                    folder.update_file_intents().await;
-                }
-            }
+    async fn login_trakt(&self, client_info: &trakt::api::ClientInfo) -> Option<()> {
+        if let Err(message) = self.trakt_sync.login(client_info).await {
+            self.push_activity(ActivityKind::Login, message.clone()).await;
+            self.errors.write().await.push(message);
+            return None;
+        }
+        if self.trakt_sync.get_session().read().await.is_some() {
+            self.push_activity(ActivityKind::Login, "Logged in to trakt api".to_string()).await;
         }
         Some(())
     }
 
-    pub fn get_folders_busy_lock(&self) -> &Mutex<()> {
-        &self.folders_busy_lock
+    pub async fn authorize_trakt(&self) -> Option<()> {
+        if let Err(message) = self.trakt_sync.authorize().await {
+            self.push_activity(ActivityKind::Login, message.clone()).await;
+            self.errors.write().await.push(message);
+            return None;
+        }
+        if self.trakt_sync.get_session().read().await.is_some() {
+            self.push_activity(ActivityKind::Login, "Authorized trakt api".to_string()).await;
+        }
+        Some(())
     }
 
-    pub fn get_folders(&self) -> &RwLock<Vec<Arc<AppFolder>>> {
-        &self.folders
+    async fn login_opensubtitles(&self, login_info: &opensubtitles::api::LoginInfo) -> Option<()> {
+        let base_url = opensubtitles::api::BASE_URL;
+        // anonymous sessions only need the api key
+        let token = match login_info.username.is_some() {
+            true => match get_metrics().time(OPENSUBTITLES_REQUEST, opensubtitles::api::login(self.get_http_client().await.as_ref(), base_url, login_info)).await {
+                Ok(token) => Some(token),
+                Err(err) => {
+                    let message = format!("Login failed at opensubtitles api: {}", err);
+                    self.push_activity(ActivityKind::Login, message.clone()).await;
+                    self.errors.write().await.push(message);
+                    *self.subtitle_session.write().await = None;
+                    return None;
+                },
+            },
+            false => None,
+        };
+        let session = SubtitleSession::new(self.get_http_client().await, base_url, login_info.api_key.as_str(), token.as_ref());
+        *self.subtitle_session.write().await = Some(Arc::new(session));
+        self.push_activity(ActivityKind::Login, "Logged in to opensubtitles api".to_string()).await;
+        Some(())
     }
 
-    pub fn get_selected_folder_index(&self) -> &RwLock<Option<usize>> {
-        &self.selected_folder_index 
+    pub fn get_client(&self) -> &RwLock<Arc<reqwest::Client>> {
+        &self.client
     }
 
-    pub fn get_series(&self) -> &RwLock<Option<Vec<Series>>> {
-        &self.series
+    async fn get_http_client(&self) -> Arc<reqwest::Client> {
+        self.client.read().await.clone()
     }
 
-    pub fn get_selected_series_index(&self) -> &RwLock<Option<usize>> {
-        &self.selected_series_index
+    pub fn get_is_watching_folders(&self) -> &RwLock<bool> {
+        &self.is_watching_folders
     }
 
-    pub fn get_series_busy_lock(&self) -> &Mutex<()> {
-        &self.series_busy_lock
+    pub fn get_folder_watcher(&self) -> &RwLock<Option<FolderWatcher>> {
+        &self.folder_watcher
+    }
+
+    pub fn get_thumbnail_cache(&self) -> &Arc<ThumbnailCache> {
+        &self.thumbnail_cache
+    }
+
+    pub fn get_login_session(&self) -> &RwLock<Option<Arc<LoginSession>>> {
+        &self.login_session
+    }
+
+    // Folders pick up the new profiles straight away
+    pub async fn set_rule_profiles(&self, profiles: RuleProfiles) -> Option<()> {
+        if let Err(err) = self.profile_store.save(profiles).await {
+            let message = format!("IO error while saving rule profiles: {}", err);
+            self.errors.write().await.push(message);
+            return None;
+        }
+        self.push_activity(ActivityKind::Settings, "Saved rule profiles".to_string()).await;
+
+        let folders = self.folders.read().await.clone();
+        let tasks = folders.into_iter().map(|folder| async move {
+            folder.update_filter_rules().await;
+            // unloaded folders resolve their rules once they are opened
+            if folder.get_cache().read().await.is_some() {
+                folder.update_file_intents().await;
+            }
+        });
+        futures::future::join_all(tasks).await;
+        Some(())
+    }
+
+    // Written to the app config and applied through the same path as edits made outside of the app
+    pub async fn set_network_settings(&self, settings: NetworkSettings) -> Option<()> {
+        let config_path = path::Path::new(self.config_path.as_str()).join(CONFIG_FILENAME);
+        if let Err(err) = save_network_settings(config_path.as_path(), &settings).await {
+            let message = format!("Couldn't save network settings to '{}': {}", config_path.to_string_lossy(), err);
+            self.errors.write().await.push(message);
+            return None;
+        }
+        self.reload_config().await
+    }
+
+    // Only the gui reads these so the folders don't need the new rules
+    pub async fn set_keybindings(&self, keybindings: serde_json::Value) -> Option<()> {
+        let config_path = path::Path::new(self.config_path.as_str()).join(CONFIG_FILENAME);
+        if let Err(err) = save_config_value(config_path.as_path(), "keybindings", keybindings.clone()).await {
+            let message = format!("Couldn't save keybindings to '{}': {}", config_path.to_string_lossy(), err);
+            self.errors.write().await.push(message);
+            return None;
+        }
+        let mut filter_rules = self.filter_rules.write().await;
+        let mut new_rules = filter_rules.as_ref().clone();
+        new_rules.keybindings = Some(keybindings);
+        *filter_rules = Arc::new(new_rules);
+        Some(())
+    }
+
+    // Settings which weren't saved yet can be tried out against the tvdb api
+    pub async fn test_network_settings(&self, settings: &NetworkSettings) -> Result<ConnectionTest, String> {
+        let base_url = self.filter_rules.read().await.tvdb_base_url.clone().unwrap_or(tvdb::api::BASE_URL.to_string());
+        test_connection(settings, base_url.as_str()).await
+    }
+
+    pub fn get_profile_store(&self) -> &Arc<ProfileStore> {
+        &self.profile_store
+    }
+
+    pub fn get_torrent_session(&self) -> &RwLock<Option<Arc<TorrentSession>>> {
+        &self.torrent_session
+    }
+
+    pub fn get_media_server_session(&self) -> &RwLock<Option<Arc<MediaServerSession>>> {
+        &self.media_server_session
+    }
+
+    pub fn get_diagnostics(&self) -> &RwLock<Vec<Diagnostic>> {
+        &self.diagnostics
+    }
+
+    pub fn get_diagnostics_busy_lock(&self) -> &Mutex<()> {
+        &self.diagnostics_busy_lock
+    }
+
+    async fn get_folder_services(&self) -> FolderServices {
+        FolderServices {
+            activity_log: self.activity_log.clone(),
+            cache_store: self.cache_store.clone(),
+            profile_store: self.profile_store.clone(),
+            trakt_sync: self.trakt_sync.clone(),
+            library_lock: self.library_lock.clone(),
+            summary_store: self.summary_store.clone(),
+            remote: self.remote.read().await.clone(),
+        }
+    }
+
+    pub fn get_remote(&self) -> &RwLock<Option<Arc<RemoteHost>>> {
+        &self.remote
+    }
+
+    // Operations which create or move whole folders only work on local root folders
+    async fn check_local_root(&self, operation: &str) -> Option<()> {
+        if let Some(remote) = self.remote.read().await.as_ref() {
+            let message = format!("Couldn't {} since the root folder is on the remote host '{}'", operation, remote.get_destination());
+            self.errors.write().await.push(message);
+            return None;
+        }
+        Some(())
+    }
+
+    pub fn get_library_lock(&self) -> &Arc<LibraryLock> {
+        &self.library_lock
+    }
+
+    // Called periodically to keep the lock alive and to pick it up once the other instance exits
+    pub async fn refresh_library_lock(&self) {
+        let was_held_by_other = matches!(*self.library_lock.get_state().read().await, LockState::HeldByOther { .. });
+        if let Err(err) = self.library_lock.refresh().await {
+            let message = format!("IO error while refreshing library lock: {}", err);
+            self.errors.write().await.push(message);
+            return;
+        }
+        let is_held = matches!(*self.library_lock.get_state().read().await, LockState::Held { .. });
+        if was_held_by_other && is_held {
+            self.push_activity(ActivityKind::Refresh, "Acquired library lock after the other instance released it".to_string()).await;
+        }
+    }
+
+    pub async fn take_over_library_lock(&self) -> Option<()> {
+        if let Err(err) = self.library_lock.take_over().await {
+            let message = format!("IO error while taking over library lock: {}", err);
+            self.errors.write().await.push(message);
+            return None;
+        }
+        self.push_activity(ActivityKind::Refresh, "Took over library lock from another instance".to_string()).await;
+        Some(())
+    }
+
+    // Mutating operations bail out with a clear message instead of racing another instance
+    async fn check_library_lock(&self) -> Option<()> {
+        if let Err(message) = self.library_lock.check().await {
+            self.errors.write().await.push(message);
+            return None;
+        }
+        Some(())
+    }
+
+    // Polled so that edits made with any editor are picked up, including ones which replace the file
+    pub async fn reload_config_if_changed(&self) -> Option<()> {
+        let config_path = path::Path::new(self.config_path.as_str()).join(CONFIG_FILENAME);
+        let stamp = get_config_stamp(config_path.as_path()).await?;
+        {
+            let mut config_stamp = self.config_stamp.lock().await;
+            if *config_stamp == Some(stamp) {
+                return Some(());
+            }
+            *config_stamp = Some(stamp);
+        }
+        self.reload_config().await
+    }
+
+    pub async fn reload_config(&self) -> Option<()> {
+        let config_path = path::Path::new(self.config_path.as_str()).join(CONFIG_FILENAME);
+        let new_rules = match tokio::fs::read_to_string(config_path.as_path()).await {
+            Ok(data) => serde_json::from_str::<FilterRules>(data.as_str()).map_err(|err| err.to_string()),
+            Err(err) => Err(err.to_string()),
+        };
+        // A half written or invalid config keeps the previous one running
+        let new_rules = match new_rules {
+            Ok(new_rules) => Arc::new(new_rules),
+            Err(err) => {
+                let message = format!("Couldn't reload config from '{}', keeping the previous config: {}", config_path.to_string_lossy(), err);
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
+        let old_rules = self.filter_rules.read().await.clone();
+        let changes = get_config_changes(&old_rules, &new_rules);
+        if !changes.is_changed {
+            return Some(());
+        }
+        *self.filter_rules.write().await = new_rules.clone();
+
+        let folders = self.folders.read().await.clone();
+        for folder in folders.iter() {
+            folder.set_base_rules(new_rules.clone()).await;
+        }
+        self.thumbnail_cache.set_ffmpeg_path(new_rules.get_ffmpeg_path()).await;
+
+        let mut notes = Vec::new();
+        if changes.is_watcher_changed {
+            *self.is_watching_folders.write().await = new_rules.watch_folders;
+            if self.remote.read().await.is_none() {
+                let root_path = self.root_path.read().await.clone();
+                self.restart_folder_watcher(root_path.as_str()).await;
+            }
+            notes.push("restarted folder watcher");
+        }
+        if changes.is_network_changed {
+            match create_client(&new_rules.network).await {
+                Ok(client) => {
+                    let client = Arc::new(client);
+                    *self.client.write().await = client.clone();
+                    self.trakt_sync.set_client(client).await;
+                    notes.push("applied network settings");
+                },
+                Err(err) => {
+                    let message = format!("Couldn't apply the network settings, keeping the previous ones: {}", err);
+                    self.errors.write().await.push(message);
+                },
+            }
+        }
+        // sessions keep the client they were created with
+        if changes.is_provider_changed || changes.is_network_changed {
+            self.login().await;
+            notes.push("logged in again");
+        }
+        if changes.is_ssh_changed && self.remote.read().await.is_some() {
+            self.load_folders_from_existing_root_path().await;
+            notes.push("reconnected to remote host");
+        } else if changes.is_library_depth_changed {
+            self.load_folders_from_existing_root_path().await;
+            notes.push("reloaded folders");
+        }
+        if changes.is_label_rules_changed {
+            self.apply_torrent_labels().await;
+        }
+        if changes.is_http_address_changed {
+            notes.push("the http address applies after a restart");
+        }
+
+        // Loaded folders are rescanned with the new rules
+        for folder in folders.iter() {
+            if folder.get_folder_status().await != FolderStatus::Unknown {
+                folder.refresh_file_intents(None).await;
+            }
+        }
+
+        let message = match notes.is_empty() {
+            true => "Reloaded config".to_string(),
+            false => format!("Reloaded config, {}", notes.join(", ")),
+        };
+        self.push_activity(ActivityKind::Settings, message.clone()).await;
+        *self.config_notice.write().await = Some(ConfigNotice {
+            timestamp: chrono::Utc::now(),
+            message,
+        });
+        Some(())
+    }
+
+    pub fn get_config_notice(&self) -> &RwLock<Option<ConfigNotice>> {
+        &self.config_notice
+    }
+
+    pub fn get_trakt_sync(&self) -> &Arc<TraktSync> {
+        &self.trakt_sync
+    }
+
+    pub fn get_subtitle_session(&self) -> &RwLock<Option<Arc<SubtitleSession>>> {
+        &self.subtitle_session
+    }
+
+    // Finds problems at startup which would otherwise fail later with obscure errors
+    pub async fn run_diagnostics(&self) -> Option<()> {
+        let _busy_lock = self.diagnostics_busy_lock.lock().await;
+        let mut diagnostics = Vec::new();
+
+        let root_path = self.root_path.read().await.clone();
+        if self.remote.read().await.is_none() {
+            diagnostics.extend(check_root_path(root_path.as_str()).await);
+        }
+
+        let credentials_path = format!("{}/credentials.json", self.config_path.as_str());
+        let credentials = match tokio::fs::read_to_string(credentials_path.as_str()).await {
+            Ok(data) => serde_json::from_str::<Credentials>(data.as_str()).map_err(|err| err.to_string()),
+            Err(err) => Err(err.to_string()),
+        };
+        match credentials {
+            Err(err) => diagnostics.push(Diagnostic::new(
+                DiagnosticKind::Credentials,
+                format!("Credentials couldn't be loaded from '{}': {}", credentials_path, err),
+                "Copy \"res/example-credentials.json\" to \"credentials.json\" and fill in your tvdb api key",
+            )),
+            Ok(_) if self.login_session.read().await.is_none() => diagnostics.push(Diagnostic::new(
+                DiagnosticKind::Credentials,
+                "Credentials were rejected by the tvdb api".to_string(),
+                "Check the api key, user key and username in the tvdb dashboard and press login again",
+            )),
+            Ok(_) => {},
+        }
+
+        let folders = self.folders.read().await.clone();
+        let problems = futures::future::join_all(folders.iter().map(|folder| folder.check_data_files())).await;
+        for problem in problems.into_iter().flatten() {
+            diagnostics.push(Diagnostic::new(
+                DiagnosticKind::Cache,
+                problem,
+                "Delete the file and refresh the folder from the api to recreate it",
+            ));
+        }
+
+        let base_url = self.filter_rules.read().await.tvdb_base_url.clone().unwrap_or(tvdb::api::BASE_URL.to_string());
+        let base_url = base_url.as_str();
+        diagnostics.extend(check_clock_skew(self.get_http_client().await.as_ref(), base_url).await);
+
+        *self.diagnostics.write().await = diagnostics;
+        Some(())
+    }
+
+    pub async fn dismiss_diagnostic(&self, index: usize) -> Option<()> {
+        let mut diagnostics = self.diagnostics.write().await;
+        if index >= diagnostics.len() {
+            return None;
+        }
+        diagnostics.remove(index);
+        Some(())
+    }
+
+    pub async fn dismiss_all_diagnostics(&self) {
+        self.diagnostics.write().await.clear();
+    }
+
+    pub async fn load_folders_from_existing_root_path(&self) -> Option<()> {
+        let path = self.root_path.read().await.clone();
+        self.load_folders(path).await
+    }
+
+    pub async fn load_folders(&self, root_path: String) -> Option<()> {
+        let _busy_lock = self.folders_busy_lock.lock().await;
+        // NOTE: If for some reason the folder load failed we can still reattempt 
+        *self.root_path.write().await = root_path.clone();
+        let ssh_path = self.filter_rules.read().await.get_ssh_path().to_string();
+        let remote = parse_remote_url(root_path.as_str()).map(|url| {
+            Arc::new(RemoteHost::new(url, ssh_path.as_str(), self.config_path.as_str()))
+        });
+        *self.remote.write().await = remote.clone();
+        let mut new_folders = match remote.as_ref() {
+            // The lock file can't be shared with instances on other machines
+            Some(remote) => {
+                self.library_lock.release().await;
+                self.read_remote_folders(remote).await?
+            },
+            None => {
+                if let Err(err) = self.library_lock.acquire(root_path.as_str()).await {
+                    let message = format!("IO error while acquiring library lock in '{}': {}", root_path.as_str(), err);
+                    self.errors.write().await.push(message);
+                }
+                if let Err(message) = self.library_lock.check().await {
+                    self.errors.write().await.push(message);
+                }
+                self.read_local_folders(root_path.as_str()).await?
+            },
+        };
+        
+        sort_natural_by(new_folders.as_mut_slice(), |folder| folder.get_folder_name());
+
+        let (mut folders, mut selected_folder_index) = tokio::join!(
+            self.folders.write(),
+            self.selected_folder_index.write(),
+        );
+        // Folders which are still executing from before the reload have journals that aren't interrupted
+        let executing_folders: Vec<String> = folders
+            .iter()
+            .filter(|folder| folder.get_busy_lock().try_lock().is_err())
+            .map(|folder| folder.get_folder_name().to_string())
+            .collect();
+        self.preload_folder_summaries(root_path.as_str(), new_folders.as_slice()).await;
+        let total_folders = new_folders.len();
+        *folders = new_folders;
+        *selected_folder_index = None;
+        drop((folders, selected_folder_index));
+        self.push_activity(ActivityKind::Refresh, format!("Loaded {} folders from '{}'", total_folders, root_path)).await;
+        match remote.is_some() {
+            // Remote executions aren't journaled and there are no local file events to watch
+            true => *self.folder_watcher.write().await = None,
+            false => {
+                self.load_interrupted_executions(root_path.as_str(), executing_folders.as_slice()).await;
+                self.restart_folder_watcher(root_path.as_str()).await;
+            },
+        }
+        drop(_busy_lock);
+        self.apply_torrent_labels().await;
+        Some(())
+    }
+
+    async fn preload_folder_summaries(&self, root_path: &str, folders: &[Arc<AppFolder>]) {
+        let mut summaries = match self.summary_store.load_from_file(root_path).await {
+            Ok(summaries) => summaries,
+            Err(err) => {
+                let message = format!("IO error while loading folder summaries: {}", err);
+                self.errors.write().await.push(message);
+                return;
+            },
+        };
+        let folder_names: HashSet<&str> = folders.iter().map(|folder| folder.get_folder_name()).collect();
+        self.summary_store.retain_folders(&folder_names).await;
+        for folder in folders.iter() {
+            if let Some(summary) = summaries.remove(folder.get_folder_name()) {
+                folder.set_preloaded_summary(summary).await;
+            }
+        }
+    }
+
+    pub async fn save_folder_summaries(&self) {
+        if let Err(err) = self.summary_store.save_if_changed().await {
+            let message = format!("IO error while saving folder summaries: {}", err);
+            self.errors.write().await.push(message);
+        }
+    }
+
+    async fn read_remote_folders(&self, remote: &Arc<RemoteHost>) -> Option<Vec<Arc<AppFolder>>> {
+        let depth = self.filter_rules.read().await.library_depth;
+        let folder_names = match remote.list_folders(depth).await {
+            Ok(folder_names) => folder_names,
+            Err(message) => {
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
+        let mut new_folders = Vec::new();
+        for folder_name in folder_names {
+            let data_path = remote.get_data_path(folder_name.as_str());
+            if let Err(err) = tokio::fs::create_dir_all(data_path.as_path()).await {
+                let message = format!("IO error while creating local data folder '{}': {}", data_path.to_string_lossy(), err);
+                self.errors.write().await.push(message);
+                continue;
+            }
+            let folder_path = remote.get_folder_path(folder_name.as_str());
+            let folder = AppFolder::new(remote.get_root_path(), folder_path.as_str(), self.filter_rules.read().await.clone(), self.get_folder_services().await);
+            new_folders.push(Arc::new(folder));
+        }
+        Some(new_folders)
+    }
+
+    async fn read_local_folders(&self, root_path: &str) -> Option<Vec<Arc<AppFolder>>> {
+        let depth = self.filter_rules.read().await.library_depth;
+        let folder_names = tokio::task::spawn_blocking({
+            let root_path = path::PathBuf::from(root_path);
+            move || find_series_folders(depth, |folder_name| list_local_dir(root_path.as_path(), folder_name))
+        }).await.unwrap_or_else(|err| Err(std::io::Error::other(err)));
+        let folder_names = match folder_names {
+            Ok(folder_names) => folder_names,
+            Err(err) => {
+                let message = format!("Error on loading folders from '{}': {}", root_path, err);
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
+
+        let mut new_folders = Vec::new();
+        for folder_name in folder_names {
+            let path = path::Path::new(root_path).join(folder_name.as_str());
+            if let Some(path) = path.to_str() {
+                let folder = AppFolder::new(root_path, path, self.filter_rules.read().await.clone(), self.get_folder_services().await);
+                new_folders.push(Arc::new(folder));
+            }
+        }
+        Some(new_folders)
+    }
+
+    async fn restart_folder_watcher(&self, root_path: &str) {
+        let mut folder_watcher = self.folder_watcher.write().await;
+        // The old watcher has to be dropped first so its events don't leak into the new one
+        *folder_watcher = None;
+        if !*self.is_watching_folders.read().await {
+            return;
+        }
+        let delay_secs = self.filter_rules.read().await.auto_refresh_delay_secs.unwrap_or(DEFAULT_AUTO_REFRESH_DELAY_SECS);
+        match FolderWatcher::new(root_path, std::time::Duration::from_secs(delay_secs)) {
+            Ok(watcher) => {
+                watcher.set_folder_names(self.folders.read().await.iter().map(|folder| folder.get_folder_name()));
+                *folder_watcher = Some(watcher);
+            },
+            Err(err) => {
+                let message = format!("Error while watching folders in '{}': {}", root_path, err);
+                self.errors.write().await.push(message);
+            },
+        }
+    }
+
+    pub async fn set_watching_folders(&self, is_watching: bool) {
+        *self.is_watching_folders.write().await = is_watching;
+        let root_path = self.root_path.read().await.clone();
+        self.restart_folder_watcher(root_path.as_str()).await;
+    }
+
+    // Refreshes folders whose files have stopped changing
+    pub async fn refresh_watched_folders(&self) -> Option<()> {
+        // Wait for reloads and refreshes of all folders to finish before taking any folders
+        let _busy_lock = self.folders_busy_lock.try_lock().ok()?;
+        let folder_names = self.folder_watcher.read().await.as_ref()?.take_due_folders();
+        let folders = self.folders.read().await;
+        for folder_name in folder_names {
+            let folder = match folders.iter().find(|folder| folder.get_folder_name() == folder_name) {
+                Some(folder) => folder,
+                // Folders added to the root folder show up after reloading the structure
+                None => continue,
+            };
+            if folder.get_busy_lock().try_lock().is_err() {
+                if let Some(watcher) = self.folder_watcher.read().await.as_ref() {
+                    watcher.postpone(folder_name.as_str());
+                }
+                continue;
+            }
+            let res = folder.perform_initial_load().await;
+            if res.is_none() {
+                folder.refresh_file_intents(None).await;
+            }
+        }
+        Some(())
+    }
+
+    async fn load_interrupted_executions(&self, root_path: &str, executing_folders: &[String]) {
+        let journals = match load_journals(root_path).await {
+            Ok(journals) => journals,
+            Err(err) => {
+                let message = format!("IO error while reading execution journals: {}", err);
+                self.errors.write().await.push(message);
+                return;
+            },
+        };
+
+        let mut interrupted_executions = Vec::new();
+        for journal in journals {
+            match journal {
+                Ok(journal) => {
+                    if !executing_folders.iter().any(|name| name == journal.get_folder_name()) {
+                        interrupted_executions.push(journal);
+                    }
+                },
+                Err((path, err)) => {
+                    let message = format!("IO error while reading execution journal '{}': {}", path.to_string_lossy(), err);
+                    self.errors.write().await.push(message);
+                },
+            }
+        }
+        *self.interrupted_executions.write().await = interrupted_executions;
+    }
+
+    // Journals are looked up by folder since the list changes while actions are running
+    async fn take_interrupted_execution(&self, folder_name: &str) -> Option<(ExecutionJournal, Option<Arc<AppFolder>>)> {
+        let mut interrupted_executions = self.interrupted_executions.write().await;
+        let index = interrupted_executions.iter().position(|journal| journal.get_folder_name() == folder_name)?;
+        let journal = interrupted_executions.remove(index);
+        drop(interrupted_executions);
+        let folder = self.folders.read().await
+            .iter()
+            .find(|folder| folder.get_folder_name() == journal.get_folder_name())
+            .cloned();
+        Some((journal, folder))
+    }
+
+    // Journals which couldn't be fully applied are kept so the user can try again
+    async fn finish_interrupted_execution(&self, journal: ExecutionJournal, errors: Vec<String>, message: String) {
+        let is_failed = !errors.is_empty();
+        self.errors.write().await.extend(errors);
+        if is_failed {
+            self.interrupted_executions.write().await.push(journal);
+        } else if let Err(err) = journal.remove().await {
+            let message = format!("IO error while removing execution journal: {}", err);
+            self.errors.write().await.push(message);
+        }
+        self.push_activity(ActivityKind::Execute, message).await;
+    }
+
+    pub async fn resume_execution(&self, folder_name: &str) -> Option<()> {
+        self.check_library_lock().await?;
+        let (mut journal, folder) = self.take_interrupted_execution(folder_name).await?;
+        {
+            let _busy_lock = match folder.as_ref() {
+                Some(folder) => Some(folder.get_busy_lock().lock().await),
+                None => None,
+            };
+            let errors = journal.resume().await;
+            let message = format!(
+                "Resumed interrupted execution of '{}' with {} errors",
+                journal.get_folder_name(), errors.len(),
+            );
+            self.finish_interrupted_execution(journal, errors, message).await;
+        }
+        if let Some(folder) = folder {
+            folder.update_file_intents().await;
+        }
+        Some(())
+    }
+
+    pub async fn rollback_execution(&self, folder_name: &str) -> Option<()> {
+        self.check_library_lock().await?;
+        let (mut journal, folder) = self.take_interrupted_execution(folder_name).await?;
+        {
+            let _busy_lock = match folder.as_ref() {
+                Some(folder) => Some(folder.get_busy_lock().lock().await),
+                None => None,
+            };
+            let errors = journal.rollback().await;
+            let message = format!(
+                "Rolled back interrupted execution of '{}' with {} errors",
+                journal.get_folder_name(), errors.len(),
+            );
+            self.finish_interrupted_execution(journal, errors, message).await;
+        }
+        if let Some(folder) = folder {
+            folder.update_file_intents().await;
+        }
+        Some(())
+    }
+
+    // Leaves the files as they are
+    pub async fn discard_execution(&self, folder_name: &str) -> Option<()> {
+        self.check_library_lock().await?;
+        let (journal, _) = self.take_interrupted_execution(folder_name).await?;
+        let message = format!("Discarded interrupted execution of '{}'", journal.get_folder_name());
+        self.finish_interrupted_execution(journal, Vec::new(), message).await;
+        Some(())
+    }
+
+    pub async fn load_trash_entries(&self) -> Option<()> {
+        self.check_local_root("browse the trash").await?;
+        let _busy_lock = self.trash_busy_lock.lock().await;
+        self.reload_trash_entries().await
+    }
+
+    async fn reload_trash_entries(&self) -> Option<()> {
+        let root_path = self.root_path.read().await.clone();
+        match load_trash_entries(root_path.as_str()).await {
+            Ok(entries) => {
+                *self.trash_entries.write().await = Some(entries);
+                Some(())
+            },
+            Err(err) => {
+                let message = format!("IO error while reading the trash: {}", err);
+                self.errors.write().await.push(message);
+                None
+            },
+        }
+    }
+
+    // Purges the trash entries which are past the retention limits in the filter rules
+    pub async fn purge_expired_trash(&self) -> Option<()> {
+        self.check_library_lock().await?;
+        self.check_local_root("purge the trash").await?;
+        let _busy_lock = self.trash_busy_lock.lock().await;
+        *self.last_trash_purge.lock().await = Some(std::time::Instant::now());
+        let retention = self.filter_rules.read().await.trash_retention.clone();
+        self.reload_trash_entries().await?;
+        let names: Vec<String> = {
+            let entries = self.trash_entries.read().await;
+            let entries = entries.as_deref().unwrap_or_default();
+            get_expired_trash_entries(entries, &retention, chrono::Utc::now())
+                .iter()
+                .map(|entry| entry.name.clone())
+                .collect()
+        };
+        self.remove_trash_entries(names).await
+    }
+
+    pub async fn purge_trash_entries(&self, names: Vec<String>) -> Option<()> {
+        self.check_library_lock().await?;
+        self.check_local_root("purge the trash").await?;
+        let _busy_lock = self.trash_busy_lock.lock().await;
+        self.remove_trash_entries(names).await
+    }
+
+    async fn remove_trash_entries(&self, names: Vec<String>) -> Option<()> {
+        if names.is_empty() {
+            return Some(());
+        }
+        let root_path = self.root_path.read().await.clone();
+        let sizes: HashMap<String, u64> = self.trash_entries.read().await
+            .iter()
+            .flatten()
+            .map(|entry| (entry.name.clone(), entry.size))
+            .collect();
+        let mut total_purged = 0;
+        let mut total_size = 0;
+        let mut errors = Vec::new();
+        for name in names {
+            match purge_trash_entry(root_path.as_str(), name.as_str()).await {
+                Ok(()) => {
+                    total_purged += 1;
+                    total_size += sizes.get(&name).copied().unwrap_or(0);
+                },
+                Err(err) => errors.push(format!("IO error while purging trash entry '{}': {}", name, err)),
+            }
+        }
+        self.errors.write().await.extend(errors);
+        if total_purged > 0 {
+            let message = format!("Purged {} trash entries freeing {} MB", total_purged, total_size / (1024 * 1024));
+            self.push_activity(ActivityKind::Execute, message).await;
+        }
+        self.reload_trash_entries().await
+    }
+
+    // Polled periodically, the trash is only purged once a retention limit is set
+    pub async fn purge_trash_if_due(&self) -> Option<()> {
+        let retention = self.filter_rules.read().await.trash_retention.clone();
+        if !retention.is_enabled() || self.root_path.read().await.is_empty() || self.remote.read().await.is_some() {
+            return None;
+        }
+        // avoid flooding the errors while another instance owns the root folder
+        self.library_lock.check().await.ok()?;
+        let is_due = self.last_trash_purge.lock().await
+            .map(|last_purge| last_purge.elapsed() >= retention.get_purge_interval())
+            .unwrap_or(true);
+        if !is_due || self.trash_busy_lock.try_lock().is_err() {
+            return None;
+        }
+        self.purge_expired_trash().await
+    }
+
+    // Executes the changes which were enabled automatically, busy folders are left for the next pass
+    pub async fn execute_pending_folders(&self) -> Option<()> {
+        let folders = self.folders.read().await.clone();
+        let mut total_executed = 0;
+        for folder in folders.iter() {
+            if folder.is_excluded().await || folder.is_pinned().await {
+                continue;
+            }
+            if folder.get_folder_status().await != FolderStatus::Pending || folder.get_busy_lock().try_lock().is_err() {
+                continue;
+            }
+            let has_enabled_changes = folder.get_files().await
+                .to_iter()
+                .any(|file| file.get_is_enabled() && (file.get_action().is_move() || matches!(file.get_action(), Action::Delete | Action::Archive)));
+            if !has_enabled_changes {
+                continue;
+            }
+            folder.execute_file_changes().await;
+            folder.update_file_intents().await;
+            total_executed += 1;
+        }
+        if total_executed > 0 {
+            self.push_activity(ActivityKind::Execute, format!("Automatically executed changes in {} folders", total_executed)).await;
+        }
+        Some(())
+    }
+
+    async fn get_reviewed_folders(&self) -> Vec<Arc<AppFolder>> {
+        let folders = self.folders.read().await.clone();
+        let mut reviewed_folders = Vec::new();
+        for folder in folders {
+            let is_reviewed = folder.get_settings().read().await.review.is_some();
+            if is_reviewed && !folder.is_excluded().await && !folder.is_pinned().await && folder.get_folder_status().await == FolderStatus::Pending {
+                reviewed_folders.push(folder);
+            }
+        }
+        reviewed_folders
+    }
+
+    pub async fn get_reviewed_execution_summary(&self) -> ExecutionSummary {
+        let mut summary = ExecutionSummary::default();
+        for folder in self.get_reviewed_folders().await {
+            summary.merge(&folder.get_execution_summary().await);
+        }
+        summary
+    }
+
+    // Executes the changes in the filter for the folders which were marked as reviewed, busy folders are left for the next pass
+    pub async fn execute_reviewed_folders(&self, filter: ExecutionFilter) -> Option<()> {
+        self.check_library_lock().await?;
+        let mut total_executed = 0;
+        for folder in self.get_reviewed_folders().await.iter() {
+            if folder.get_busy_lock().try_lock().is_err() {
+                continue;
+            }
+            folder.execute_filtered_file_changes(filter).await;
+            folder.update_file_intents().await;
+            total_executed += 1;
+        }
+        if total_executed > 0 {
+            self.push_activity(ActivityKind::Execute, format!("Executed changes in {} reviewed folders", total_executed)).await;
+        }
+        Some(())
+    }
+
+    // Folders with pending changes which bulk title fixes may apply to
+    async fn get_title_fix_folders(&self) -> Vec<Arc<AppFolder>> {
+        let folders = self.folders.read().await.clone();
+        let mut title_fix_folders = Vec::new();
+        for folder in folders {
+            if !folder.is_excluded().await && !folder.is_pinned().await && folder.get_folder_status().await == FolderStatus::Pending {
+                title_fix_folders.push(folder);
+            }
+        }
+        title_fix_folders
+    }
+
+    // Simulates applying only the renames which change nothing but the episode title
+    pub async fn get_title_fix_summary(&self) -> ExecutionSummary {
+        let mut summary = ExecutionSummary::default();
+        for folder in self.get_title_fix_folders().await {
+            let folder_summary = folder.get_filtered_execution_summary(ExecutionFilter::title_fixes()).await;
+            if folder_summary.get_total_changes() > 0 || folder_summary.total_skipped_conflicts > 0 {
+                summary.merge(&folder_summary);
+            }
+        }
+        summary
+    }
+
+    // Every other change in the library stays pending, busy folders are left for the next pass
+    pub async fn execute_title_fixes(&self) -> Option<()> {
+        self.check_library_lock().await?;
+        let mut total_executed = 0;
+        for folder in self.get_title_fix_folders().await.iter() {
+            if folder.get_filtered_execution_summary(ExecutionFilter::title_fixes()).await.get_total_changes() == 0 {
+                continue;
+            }
+            if folder.get_busy_lock().try_lock().is_err() {
+                continue;
+            }
+            folder.execute_filtered_file_changes(ExecutionFilter::title_fixes()).await;
+            folder.update_file_intents().await;
+            total_executed += 1;
+        }
+        if total_executed > 0 {
+            self.push_activity(ActivityKind::Execute, format!("Applied episode title fixes in {} folders", total_executed)).await;
+        }
+        Some(())
+    }
+
+    // Rescans folders still holding an older copy of a series cache that another folder refreshed
+    pub async fn update_outdated_folders(&self) -> Option<()> {
+        let folders = self.folders.read().await.clone();
+        let mut total_updated = 0;
+        for folder in folders.iter() {
+            // locked plans pick up the shared cache once they are executed or unlocked
+            if !folder.is_cache_shared_outdated().await || *folder.get_is_plan_locked().lock().await || folder.is_pinned().await {
+                continue;
+            }
+            folder.update_file_intents().await;
+            folder.save_cache_to_file().await;
+            total_updated += 1;
+        }
+        if total_updated > 0 {
+            self.push_activity(ActivityKind::Cache, format!("Updated {} folders sharing a refreshed series cache", total_updated)).await;
+        }
+        Some(())
+    }
+
+    pub async fn update_search_series(&self, search: String) -> Option<()> {
+        let _busy_lock = self.series_busy_lock.lock().await;
+        let login_session = self.login_session.read().await;
+        let session = match login_session.as_ref() {
+            Some(session) => session,
+            None => {
+                let message = "Login session is required to update the series search results";
+                self.errors.write().await.push(message.to_string());
+                return None;
+            },
+        };
+        let mut search_results = match get_metrics().time(TVDB_REQUEST, session.search_series(&search)).await {
+            Ok(results) => results,
+            Err(err) => {
+                let message = format!("Failed to get series search results due to api error: {}", err);
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
+        let watchlist_ids = self.watchlist.get_series_ids().await;
+        sort_by_watchlist(search_results.as_mut_slice(), &watchlist_ids, |series| series);
+
+        let (mut series, mut series_index) = tokio::join!(
+            self.series.write(),
+            self.selected_series_index.write(),
+        );
+        *series = Some(search_results);
+        *series_index = None;
+        Some(())
+    }
+
+    pub async fn update_file_intents_for_all_folders(&self) -> Option<()> {
+        // Allow the folder to be read while it is busy
+        // Disallow load_folders(...) while we are performing an update on all folders
+        let _busy_lock = self.folders_busy_lock.lock().await;
+        {
+            let folders = self.folders.read().await;
+            let progress = self.start_cancellable_progress("Refresh", ProgressUnit::Folders, folders.len() as u64).await;
+            let cancel = progress.get_cancel_token();
+            let mut queue = RefreshQueue::new(folders.len());
+            while !progress.is_cancelled() {
+                let index = {
+                    let selected_index = *self.selected_folder_index.read().await;
+                    let visible_indices = self.visible_folder_indices.read().await;
+                    queue.pop(selected_index, visible_indices.as_slice())
+                };
+                let folder = match index.and_then(|index| folders.get(index)) {
+                    Some(folder) => folder,
+                    None => break,
+                };
+                let res = folder.perform_initial_load().await;
+                // Initial load already occured, we therefore just rescan the folder
+                // Pinned folders are still loaded once so they can be shown
+                if res.is_none() && !folder.is_pinned().await {
+                    folder.clear_execution_outcomes().await;
+                    folder.refresh_file_intents(cancel).await;
+                }
+                progress.add(1);
+            }
+        }
+        *self.progress.write().await = None;
+        self.save_folder_summaries().await;
+        Some(())
+    }
+
+    // Folders are loaded first since air dates come from each folder's cache
+    pub async fn update_agenda(&self, start: chrono::NaiveDate, end: chrono::NaiveDate) -> Option<()> {
+        let _agenda_busy_lock = self.agenda_busy_lock.lock().await;
+        let mut entries = Vec::new();
+        {
+            let _busy_lock = self.folders_busy_lock.lock().await;
+            let folders = self.folders.read().await;
+            for folder in folders.iter() {
+                folder.perform_initial_load().await;
+                entries.extend(get_folder_agenda(folder, start, end).await);
+            }
+        }
+        entries.sort_by(|a, b| {
+            a.air_date.cmp(&b.air_date)
+                .then_with(|| natural_cmp(a.series_name.as_str(), b.series_name.as_str()))
+                .then_with(|| (a.descriptor.season, a.descriptor.episode).cmp(&(b.descriptor.season, b.descriptor.episode)))
+        });
+        *self.agenda.write().await = Some(Agenda { start, end, entries });
+        Some(())
+    }
+
+    // Refreshes caches that still list aired episodes with placeholder titles so files get their real titles
+    pub async fn check_library_consistency(&self) -> Option<()> {
+        let session = self.login_session.read().await.clone();
+        let today = chrono::Local::now().date_naive();
+        let _busy_lock = self.folders_busy_lock.lock().await;
+        let folders = self.folders.read().await;
+
+        let mut total_refreshed = 0;
+        let mut total_outdated = 0;
+        let progress = self.start_progress("Consistency check", ProgressUnit::Folders, folders.len() as u64).await;
+        for folder in folders.iter() {
+            if folder.is_pinned().await {
+                progress.add(1);
+                continue;
+            }
+            if folder.perform_initial_load().await.is_none() {
+                folder.refresh_file_intents(None).await;
+            }
+            let has_placeholders = folder.get_cache().read().await
+                .as_ref()
+                .map(|cache| cache.has_aired_placeholder_titles(today))
+                .unwrap_or(false);
+            if let (true, Some(session)) = (has_placeholders, session.as_ref()) {
+                if folder.refresh_cache_from_api(session.clone()).await.is_some() {
+                    total_refreshed += 1;
+                    tokio::join!(
+                        folder.refresh_file_intents(None),
+                        folder.save_cache_to_file(),
+                    );
+                }
+            }
+            total_outdated += folder.get_files().await
+                .to_iter()
+                .filter(|file| file.get_warnings().iter().any(|warning| matches!(warning, IntentWarning::OutdatedTitle(_))))
+                .count();
+            progress.add(1);
+        }
+
+        let total_folders = folders.len();
+        drop(folders);
+        *self.progress.write().await = None;
+        // folders checked before another folder of the same series was refreshed
+        self.update_outdated_folders().await;
+        let message = format!(
+            "Checked {} folders, refreshed {} caches with placeholder titles and found {} files with outdated titles",
+            total_folders, total_refreshed, total_outdated,
+        );
+        self.push_activity(ActivityKind::Refresh, message).await;
+        Some(())
+    }
+
+    // Looked up by name since earlier renames, merges or imports may have moved the folder in the sorted list
+    pub async fn rename_folder(&self, folder_name: &str, is_include_year: bool) -> Option<()> {
+        self.check_library_lock().await?;
+        self.check_local_root("rename folders").await?;
+        let _busy_lock = self.folders_busy_lock.lock().await;
+        let folder = self.folders.read().await.iter().find(|folder| folder.get_folder_name() == folder_name).cloned();
+        let folder = match folder {
+            Some(folder) => folder,
+            None => {
+                let message = format!("Couldn't rename folder '{}' since it no longer exists", folder_name);
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
+        // the journal refers to files by their path below the old folder name
+        let is_interrupted = self.interrupted_executions.read().await.iter().any(|journal| journal.get_folder_name() == folder_name);
+        if is_interrupted {
+            let message = format!("Couldn't rename folder '{}' since its interrupted execution must be resumed or rolled back first", folder_name);
+            self.errors.write().await.push(message);
+            return None;
+        }
+        let _folder_busy_lock = match folder.get_busy_lock().try_lock() {
+            Ok(lock) => lock,
+            Err(_) => {
+                let message = format!("Couldn't rename folder '{}' since it is busy", folder.get_folder_name());
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
+
+        let new_folder_name = {
+            let cache = folder.get_cache().read().await;
+            match cache.as_ref() {
+                Some(cache) => get_series_folder_name(&cache.series, is_include_year),
+                None => {
+                    let message = format!("Couldn't rename folder '{}' since its cache is unloaded", folder.get_folder_name());
+                    self.errors.write().await.push(message);
+                    return None;
+                },
+            }
+        };
+        let old_folder_name = folder.get_folder_name().to_string();
+        if new_folder_name.is_empty() {
+            return Some(());
+        }
+        // folders below genre or letter folders are renamed in place
+        let new_folder_name = match old_folder_name.rsplit_once('/') {
+            Some((parent, _)) => format!("{}/{}", parent, new_folder_name),
+            None => new_folder_name,
+        };
+        if new_folder_name == old_folder_name {
+            return Some(());
+        }
+
+        let root_path = self.root_path.read().await.clone();
+        let old_path = path::Path::new(folder.get_folder_path());
+        let new_path = path::Path::new(root_path.as_str()).join(new_folder_name.as_str());
+        // renames that only change the case refer to the same folder on case insensitive filesystems
+        let is_case_change = new_folder_name.to_lowercase() == old_folder_name.to_lowercase();
+        if !is_case_change && tokio::fs::try_exists(new_path.as_path()).await.unwrap_or(true) {
+            let message = format!("Couldn't rename folder '{}' since '{}' already exists", old_folder_name, new_folder_name);
+            self.errors.write().await.push(message);
+            return None;
+        }
+
+        let res = match is_case_change {
+            true => {
+                let tmp_path = path::Path::new(root_path.as_str()).join(format!("{}.rename", old_folder_name));
+                match tokio::fs::rename(old_path, tmp_path.as_path()).await {
+                    Ok(()) => match tokio::fs::rename(tmp_path.as_path(), new_path.as_path()).await {
+                        Ok(()) => Ok(()),
+                        // restore the original folder if the target turned out to be a different folder
+                        Err(err) => {
+                            let _ = tokio::fs::rename(tmp_path.as_path(), old_path).await;
+                            Err(err)
+                        },
+                    },
+                    Err(err) => Err(err),
+                }
+            },
+            false => tokio::fs::rename(old_path, new_path.as_path()).await,
+        };
+        if let Err(err) = res {
+            let message = format!("IO error while renaming folder '{}' to '{}': {}", old_folder_name, new_folder_name, err);
+            self.errors.write().await.push(message);
+            return None;
+        }
+
+        let new_path_str = new_path.to_string_lossy().to_string();
+        let new_folder = Arc::new(folder.take_with_folder_path(new_path_str.as_str()).await);
+        {
+            let (mut folders, mut selected_folder_index) = tokio::join!(
+                self.folders.write(),
+                self.selected_folder_index.write(),
+            );
+            let selected_folder = selected_folder_index.and_then(|index| folders.get(index).cloned());
+            if let Some(index) = folders.iter().position(|other| Arc::ptr_eq(other, &folder)) {
+                folders[index] = new_folder.clone();
+            }
+            sort_natural_by(folders.as_mut_slice(), |folder| folder.get_folder_name());
+            // keep the same folder selected after it moves in the sorted list
+            let selected_folder = match selected_folder {
+                Some(selected_folder) if Arc::ptr_eq(&selected_folder, &folder) => Some(new_folder.clone()),
+                selected_folder => selected_folder,
+            };
+            *selected_folder_index = selected_folder.and_then(|selected_folder| {
+                folders.iter().position(|other| Arc::ptr_eq(other, &selected_folder))
+            });
+            if let Some(watcher) = self.folder_watcher.read().await.as_ref() {
+                watcher.set_folder_names(folders.iter().map(|folder| folder.get_folder_name()));
+            }
+        }
+
+        if let Err(err) = self.activity_log.rename_folder(old_folder_name.as_str(), new_folder_name.as_str()).await {
+            let message = format!("IO error while updating activity log: {}", err);
+            self.errors.write().await.push(message);
+        }
+        self.push_activity(ActivityKind::Execute, format!("Renamed folder '{}' to '{}'", old_folder_name, new_folder_name)).await;
+        Some(())
+    }
+
+    // Only folders bound to the same series are merged, e.g. "Doctor Who" and "Doctor Who (2005)" are different series
+    pub async fn get_merge_plan(&self, src_index: usize, dest_index: usize) -> Option<MergePlan> {
+        if src_index == dest_index {
+            return None;
+        }
+        let (src_folder, dest_folder) = {
+            let folders = self.folders.read().await;
+            (folders.get(src_index)?.clone(), folders.get(dest_index)?.clone())
+        };
+        self.create_merge_plan(&src_folder, &dest_folder).await
+    }
+
+    async fn create_merge_plan(&self, src_folder: &AppFolder, dest_folder: &AppFolder) -> Option<MergePlan> {
+        // intents are needed to find which episodes are duplicated
+        src_folder.perform_initial_load().await;
+        dest_folder.perform_initial_load().await;
+        let src_name = src_folder.get_folder_name().to_string();
+        let dest_name = dest_folder.get_folder_name().to_string();
+        let src_series_id = src_folder.get_cache().read().await.as_ref().map(|cache| cache.series.id);
+        let dest_series_id = dest_folder.get_cache().read().await.as_ref().map(|cache| cache.series.id);
+        if src_series_id.is_none() || src_series_id != dest_series_id {
+            let message = format!("Couldn't merge folder '{}' into '{}' since they aren't bound to the same series", src_name, dest_name);
+            self.errors.write().await.push(message);
+            return None;
+        }
+
+        let src_files = get_merge_files(src_folder).await;
+        let dest_files = get_merge_files(dest_folder).await;
+        Some(MergePlan {
+            src_name,
+            dest_name,
+            steps: plan_merge_steps(src_files.as_slice(), dest_files.as_slice()),
+        })
+    }
+
+    // Moves the contents of one folder into another, keeping the larger file when both have the same episode
+    // The plan is made again so nothing is trashed which wasn't listed when the merge was confirmed
+    pub async fn merge_folders(&self, plan: &MergePlan) -> Option<()> {
+        self.check_library_lock().await?;
+        self.check_local_root("merge folders").await?;
+        let _busy_lock = self.folders_busy_lock.lock().await;
+        let (src_folder, dest_folder) = {
+            let folders = self.folders.read().await;
+            let find_folder = |name: &str| folders.iter().find(|folder| folder.get_folder_name() == name).cloned();
+            match (find_folder(plan.src_name.as_str()), find_folder(plan.dest_name.as_str())) {
+                (Some(src_folder), Some(dest_folder)) if !Arc::ptr_eq(&src_folder, &dest_folder) => (src_folder, dest_folder),
+                _ => {
+                    drop(folders);
+                    let message = format!("Couldn't merge folder '{}' into '{}' since one of them no longer exists", plan.src_name, plan.dest_name);
+                    self.errors.write().await.push(message);
+                    return None;
+                },
+            }
+        };
+        if self.create_merge_plan(&src_folder, &dest_folder).await.as_ref() != Some(plan) {
+            let message = format!("Couldn't merge folder '{}' into '{}' since their files changed after the merge was confirmed", plan.src_name, plan.dest_name);
+            self.errors.write().await.push(message);
+            return None;
+        }
+
+        let src_name = plan.src_name.as_str();
+        let dest_name = plan.dest_name.as_str();
+        let root_path = self.root_path.read().await.clone();
+        let trash_entry_name = get_trash_entry_name(&chrono::Utc::now());
+        let mut total_moved = 0;
+        let mut total_duplicates = 0;
+        let is_failed;
+        {
+            let (src_busy_lock, dest_busy_lock) = (src_folder.get_busy_lock().try_lock(), dest_folder.get_busy_lock().try_lock());
+            if src_busy_lock.is_err() || dest_busy_lock.is_err() {
+                let message = format!("Couldn't merge folder '{}' into '{}' since they are busy", src_name, dest_name);
+                self.errors.write().await.push(message);
+                return None;
+            }
+
+            let src_path = path::Path::new(src_folder.get_folder_path());
+            let dest_path = path::Path::new(dest_folder.get_folder_path());
+            let mut errors = Vec::new();
+            for step in plan.steps.iter() {
+                let src = match step {
+                    MergeStep::TrashSource(src) => {
+                        total_duplicates += 1;
+                        let trash_path = get_trash_path(root_path.as_str(), trash_entry_name.as_str(), src_name, src.as_str());
+                        if let Err(err) = move_to_trash(src_path.join(src.as_str()).as_path(), trash_path.as_path()).await {
+                            errors.push(format!("IO error while trashing duplicate '{}': {}", src, err));
+                        }
+                        continue;
+                    },
+                    MergeStep::Replace { src, dest_src } => {
+                        total_duplicates += 1;
+                        let trash_path = get_trash_path(root_path.as_str(), trash_entry_name.as_str(), dest_name, dest_src.as_str());
+                        if let Err(err) = move_to_trash(dest_path.join(dest_src.as_str()).as_path(), trash_path.as_path()).await {
+                            errors.push(format!("IO error while trashing duplicate '{}': {}", dest_src, err));
+                            continue;
+                        }
+                        src
+                    },
+                    MergeStep::Move(src) => src,
+                };
+
+                // a different file of the destination may already sit at the same path
+                let file_path = src_path.join(src.as_str());
+                let new_file_path = dest_path.join(src.as_str());
+                match move_without_overwrite(file_path.as_path(), new_file_path.as_path()).await {
+                    Ok(()) => total_moved += 1,
+                    Err(err) => errors.push(format!("IO error while moving '{}' into '{}': {}", src, dest_name, err)),
+                }
+            }
+
+            // whatever is left over is kept in the trash rather than deleted outright
+            // episodes which failed to move stay in the source folder so they aren't trashed with it
+            is_failed = !errors.is_empty();
+            if !is_failed {
+                let trash_folder = path::Path::new(root_path.as_str())
+                    .join(TRASH_FOLDER_NAME)
+                    .join(trash_entry_name.as_str())
+                    .join(src_name);
+                if let Err(err) = move_to_trash(src_path, trash_folder.as_path()).await {
+                    errors.push(format!("IO error while removing merged folder '{}': {}", src_name, err));
+                }
+            }
+            self.errors.write().await.extend(errors);
+        }
+
+        if is_failed {
+            src_folder.update_file_intents().await;
+        } else {
+            let (mut folders, mut selected_folder_index) = tokio::join!(
+                self.folders.write(),
+                self.selected_folder_index.write(),
+            );
+            folders.retain(|folder| !Arc::ptr_eq(folder, &src_folder));
+            *selected_folder_index = folders.iter().position(|folder| Arc::ptr_eq(folder, &dest_folder));
+        }
+
+        dest_folder.update_file_intents().await;
+        let message = format!(
+            "Merged folder '{}' into '{}' with {} files moved and {} duplicates trashed", 
+            src_name, dest_name, total_moved, total_duplicates,
+        );
+        self.push_activity(ActivityKind::Execute, message).await;
+        Some(())
+    }
+
+    // Prefer folders already bound to the series, then folders with a matching name, before creating a new one
+    async fn get_import_candidate(&self, series: Series) -> ImportCandidate {
+        let folders = self.folders.read().await;
+        for folder in folders.iter() {
+            let cache = folder.get_cache().read().await;
+            if cache.as_ref().map(|cache| cache.series.id) == Some(series.id) {
+                let folder_name = folder.get_folder_name().to_string();
+                return ImportCandidate { series, folder_name, is_new_folder: false };
+            }
+        }
+
+        let series_key = get_folder_series_key(series.name.as_str());
+        let existing_folder = folders.iter().find(|folder| {
+            // folders which haven't been loaded yet are matched by name alone
+            let is_other_series = folder.get_cache().try_read()
+                .map(|cache| cache.as_ref().map(|cache| cache.series.id != series.id).unwrap_or(false))
+                .unwrap_or(true);
+            let is_excluded = folder.get_exclusion().try_read()
+                .map(|exclusion| exclusion.is_some())
+                .unwrap_or(true);
+            !is_other_series && !is_excluded && get_folder_series_key(folder.get_folder_name()) == series_key
+        });
+        if let Some(folder) = existing_folder {
+            let folder_name = folder.get_folder_name().to_string();
+            return ImportCandidate { series, folder_name, is_new_folder: false };
+        }
+
+        // A different series can have the same name so the year is used to tell them apart
+        let mut folder_name = get_series_folder_name(&series, false);
+        if folders.iter().any(|folder| folder.get_folder_name() == folder_name) {
+            folder_name = get_series_folder_name(&series, true);
+        }
+        ImportCandidate { series, folder_name, is_new_folder: true }
+    }
+
+    // Adds a folder to the sorted list while keeping the same folder selected
+    async fn insert_folder(&self, root_path: &str, folder_name: &str) -> Arc<AppFolder> {
+        let (mut folders, mut selected_folder_index) = tokio::join!(
+            self.folders.write(),
+            self.selected_folder_index.write(),
+        );
+        if let Some(folder) = folders.iter().find(|folder| folder.get_folder_name() == folder_name) {
+            return folder.clone();
+        }
+
+        let folder_path = path::Path::new(root_path).join(folder_name).to_string_lossy().to_string();
+        let folder = AppFolder::new(root_path, folder_path.as_str(), self.filter_rules.read().await.clone(), self.get_folder_services().await);
+        let folder = Arc::new(folder);
+        let selected_folder = selected_folder_index.and_then(|index| folders.get(index).cloned());
+        folders.push(folder.clone());
+        sort_natural_by(folders.as_mut_slice(), |folder| folder.get_folder_name());
+        *selected_folder_index = selected_folder.and_then(|selected_folder| {
+            folders.iter().position(|other| Arc::ptr_eq(other, &selected_folder))
+        });
+        folder
+    }
+
+    pub async fn plan_import(&self, source_path: String) -> Option<()> {
+        self.check_local_root("import downloads").await?;
+        let _busy_lock = self.import_busy_lock.lock().await;
+        *self.import_plan.write().await = None;
+
+        let path = path::Path::new(source_path.as_str());
+        if !tokio::fs::try_exists(path).await.unwrap_or(false) {
+            let message = format!("Couldn't import '{}' since it doesn't exist", source_path);
+            self.errors.write().await.push(message);
+            return None;
+        }
+        let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        let search_name = match get_series_name_from_download(name.as_str()) {
+            Some(search_name) => search_name,
+            None => {
+                let message = format!("Couldn't find a series name in '{}'", name);
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
+
+        let session = match self.login_session.read().await.as_ref() {
+            Some(session) => session.clone(),
+            None => {
+                let message = "Login session is required to match imported downloads";
+                self.errors.write().await.push(message.to_string());
+                return None;
+            },
+        };
+        let search_results = match get_metrics().time(TVDB_REQUEST, session.search_series(&search_name)).await {
+            Ok(results) => results,
+            Err(err) => {
+                let message = format!("Failed to match '{}' due to api error: {}", search_name, err);
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
+
+        let mut candidates = Vec::new();
+        for series in search_results {
+            candidates.push(self.get_import_candidate(series).await);
+        }
+        let watchlist_ids = self.watchlist.get_series_ids().await;
+        sort_by_watchlist(candidates.as_mut_slice(), &watchlist_ids, |candidate| &candidate.series);
+        let files = get_download_files(path);
+        *self.import_plan.write().await = Some(ImportPlan {
+            source_path,
+            search_name,
+            files,
+            selected_candidate: if candidates.is_empty() { None } else { Some(0) },
+            candidates,
+        });
+        Some(())
+    }
+
+    pub async fn execute_import(&self) -> Option<()> {
+        self.check_library_lock().await?;
+        self.check_local_root("import downloads").await?;
+        let _busy_lock = self.import_busy_lock.lock().await;
+        let plan = self.import_plan.read().await.clone()?;
+        let candidate = match plan.get_selected_candidate() {
+            Some(candidate) => candidate.clone(),
+            None => {
+                let message = format!("Couldn't import '{}' since no series was selected", plan.source_path);
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
+
+        let root_path = self.root_path.read().await.clone();
+        let source_path = path::Path::new(plan.source_path.as_str());
+        let folder_path = path::Path::new(root_path.as_str()).join(candidate.folder_name.as_str());
+        let mut total_bytes = 0;
+        for file in plan.files.iter() {
+            let src = get_download_file_path(source_path, file.as_str());
+            total_bytes += tokio::fs::metadata(src).await.map(|metadata| metadata.len()).unwrap_or(0);
+        }
+        let progress = self.start_progress("Import", ProgressUnit::Bytes, total_bytes).await;
+
+        let mut imported_files = Vec::new();
+        for file in plan.files.iter() {
+            let src = get_download_file_path(source_path, file.as_str());
+            let dest = folder_path.join(file.as_str());
+            if tokio::fs::try_exists(dest.as_path()).await.unwrap_or(true) {
+                let message = format!("Couldn't import '{}' since it already exists in '{}'", file, candidate.folder_name);
+                self.errors.write().await.push(message);
+                continue;
+            }
+            match move_file(src.as_path(), dest.as_path(), progress.as_ref()).await {
+                Ok(()) => imported_files.push(dest),
+                Err(err) => {
+                    let message = format!("IO error while importing '{}': {}", file, err);
+                    self.errors.write().await.push(message);
+                },
+            }
+        }
+        *self.progress.write().await = None;
+        *self.import_plan.write().await = None;
+        self.remux_imported_files(imported_files.as_slice(), candidate.folder_name.as_str()).await;
+
+        let folder = {
+            let _folders_busy_lock = self.folders_busy_lock.lock().await;
+            let folder = self.insert_folder(root_path.as_str(), candidate.folder_name.as_str()).await;
+            let folders = self.folders.read().await;
+            *self.selected_folder_index.write().await = folders.iter().position(|other| Arc::ptr_eq(other, &folder));
+            folder
+        };
+
+        let message = format!("Imported {} files from '{}' into '{}'", imported_files.len(), plan.source_path, candidate.folder_name);
+        self.push_activity(ActivityKind::Execute, message).await;
+
+        // bind new folders and unbound folders with a matching name to the chosen series
+        if !candidate.is_new_folder {
+            folder.perform_initial_load().await;
+        }
+        if folder.get_cache().read().await.is_none() {
+            let session = self.login_session.read().await.clone()?;
+            folder.load_cache_from_api(session, candidate.series.id).await?;
+            folder.save_cache_to_file().await;
+        }
+        folder.update_file_intents().await?;
+        self.update_outdated_folders().await
+    }
+
+    // Legacy containers are normalised before the folder is scanned so the plan only sees the mkv files
+    async fn remux_imported_files(&self, files: &[path::PathBuf], folder_name: &str) {
+        let filter_rules = self.filter_rules.read().await.clone();
+        let remux_files: Vec<&path::PathBuf> = files
+            .iter()
+            .filter(|file| {
+                let extension = file.extension().map(|extension| extension.to_string_lossy()).unwrap_or_default();
+                is_extension_listed(filter_rules.remux_extensions.as_slice(), extension.as_ref())
+            })
+            .collect();
+        if remux_files.is_empty() {
+            return;
+        }
+
+        let mut total_bytes = 0;
+        for file in remux_files.iter() {
+            total_bytes += tokio::fs::metadata(file).await.map(|metadata| metadata.len()).unwrap_or(0);
+        }
+        let progress = self.start_progress("Remux", ProgressUnit::Bytes, total_bytes).await;
+        let mut total_remuxed = 0;
+        for file in remux_files {
+            match remux_to_mkv(filter_rules.get_ffmpeg_path(), file.as_path(), progress.as_ref()).await {
+                Ok(_) => total_remuxed += 1,
+                Err(err) => {
+                    let message = format!("Couldn't remux '{}': {}", file.to_string_lossy(), err);
+                    self.errors.write().await.push(message);
+                },
+            }
+        }
+        *self.progress.write().await = None;
+        if total_remuxed > 0 {
+            let message = format!("Remuxed {} imported files into mkv in '{}'", total_remuxed, folder_name);
+            self.push_activity(ActivityKind::Execute, message).await;
+        }
+    }
+
+    pub async fn load_bindings(&self) -> Option<()> {
+        let _busy_lock = self.rebind_busy_lock.lock().await;
+        self.reload_bindings().await
+    }
+
+    // Lowest confidence first since those are the folders most likely bound to the wrong series
+    async fn reload_bindings(&self) -> Option<()> {
+        let _busy_lock = self.folders_busy_lock.lock().await;
+        let folders = self.folders.read().await.clone();
+        let progress = self.start_progress("Bindings", ProgressUnit::Folders, folders.len() as u64).await;
+        let mut bindings = Vec::new();
+        for folder in folders.iter() {
+            folder.perform_initial_load().await;
+            progress.add(1);
+            if folder.is_excluded().await {
+                continue;
+            }
+            let series = folder.get_cache().read().await.as_ref().map(|cache| cache.series.clone());
+            let confidence = series.as_ref().map(|series| get_binding_confidence(folder.get_folder_name(), series)).unwrap_or(0.0);
+            bindings.push(SeriesBinding {
+                folder_name: folder.get_folder_name().to_string(),
+                series,
+                confidence,
+            });
+        }
+        *self.progress.write().await = None;
+        bindings.sort_by(|a, b| {
+            a.confidence.total_cmp(&b.confidence)
+                .then_with(|| natural_cmp(a.folder_name.as_str(), b.folder_name.as_str()))
+        });
+        *self.bindings.write().await = Some(bindings);
+        Some(())
+    }
+
+    pub async fn plan_rebind(&self, folder_names: Vec<String>) -> Option<()> {
+        let _busy_lock = self.rebind_busy_lock.lock().await;
+        *self.rebind_plan.write().await = None;
+        let session = match self.login_session.read().await.as_ref() {
+            Some(session) => session.clone(),
+            None => {
+                let message = "Login session is required to search series for rebinding folders";
+                self.errors.write().await.push(message.to_string());
+                return None;
+            },
+        };
+
+        let current_ids: HashMap<String, u32> = self.bindings.read().await
+            .iter()
+            .flatten()
+            .filter_map(|binding| Some((binding.folder_name.clone(), binding.series.as_ref()?.id)))
+            .collect();
+        let progress = self.start_progress("Rebind search", ProgressUnit::Folders, folder_names.len() as u64).await;
+        let mut entries = Vec::new();
+        for folder_name in folder_names {
+            let search_name = get_folder_series_key(folder_name.as_str());
+            let search_results = get_metrics().time(TVDB_REQUEST, session.search_series(&search_name)).await;
+            progress.add(1);
+            let search_results = match search_results {
+                Ok(results) => results,
+                Err(err) => {
+                    let message = format!("Failed to search series for '{}' due to api error: {}", folder_name, err);
+                    self.errors.write().await.push(message);
+                    continue;
+                },
+            };
+            let candidates = get_rebind_candidates(folder_name.as_str(), search_results);
+            entries.push(RebindEntry {
+                current_series_id: current_ids.get(&folder_name).copied(),
+                selected_candidate: if candidates.is_empty() { None } else { Some(0) },
+                folder_name,
+                search_name,
+                candidates,
+            });
+        }
+        *self.progress.write().await = None;
+        *self.rebind_plan.write().await = Some(RebindPlan { entries });
+        Some(())
+    }
+
+    pub async fn execute_rebind(&self) -> Option<()> {
+        let _busy_lock = self.rebind_busy_lock.lock().await;
+        let plan = self.rebind_plan.read().await.clone()?;
+        let session = match self.login_session.read().await.as_ref() {
+            Some(session) => session.clone(),
+            None => {
+                let message = "Login session is required to rebind folders";
+                self.errors.write().await.push(message.to_string());
+                return None;
+            },
+        };
+
+        let entries: Vec<&RebindEntry> = plan.entries.iter().filter(|entry| entry.is_changed()).collect();
+        let mut total_rebound = 0;
+        {
+            let _folders_busy_lock = self.folders_busy_lock.lock().await;
+            let folders = self.folders.read().await.clone();
+            let progress = self.start_progress("Rebind", ProgressUnit::Folders, entries.len() as u64).await;
+            for entry in entries {
+                let folder = folders.iter().find(|folder| folder.get_folder_name() == entry.folder_name);
+                let candidate = entry.get_selected_candidate();
+                if let (Some(folder), Some(candidate)) = (folder, candidate) {
+                    if folder.load_cache_from_api(session.clone(), candidate.series.id).await.is_some() {
+                        tokio::join!(
+                            folder.update_file_intents(),
+                            folder.save_cache_to_file(),
+                        );
+                        total_rebound += 1;
+                    }
+                }
+                progress.add(1);
+            }
+            *self.progress.write().await = None;
+        }
+
+        *self.rebind_plan.write().await = None;
+        self.update_outdated_folders().await;
+        if total_rebound > 0 {
+            self.push_activity(ActivityKind::Cache, format!("Rebound {} folders to another series", total_rebound)).await;
+        }
+        self.reload_bindings().await
+    }
+
+    pub async fn add_to_watchlist(&self, series: &Series) -> Option<()> {
+        if let Err(err) = self.watchlist.add(series).await {
+            let message = format!("IO error while adding '{}' to watchlist: {}", series.name, err);
+            self.errors.write().await.push(message);
+            return None;
+        }
+        self.push_activity(ActivityKind::Settings, format!("Added '{}' to watchlist", series.name)).await;
+        Some(())
+    }
+
+    pub async fn remove_from_watchlist(&self, series_id: u32) -> Option<()> {
+        if let Err(err) = self.watchlist.remove(series_id).await {
+            let message = format!("IO error while removing series {} from watchlist: {}", series_id, err);
+            self.errors.write().await.push(message);
+            return None;
+        }
+        self.push_activity(ActivityKind::Settings, format!("Removed series {} from watchlist", series_id)).await;
+        Some(())
+    }
+
+    // Creates empty folders bound to their series so episodes can be dropped in as they air
+    pub async fn create_watchlist_folders(&self) -> Option<()> {
+        self.check_library_lock().await?;
+        self.check_local_root("create watchlist folders").await?;
+        let session = match self.login_session.read().await.as_ref() {
+            Some(session) => session.clone(),
+            None => {
+                let message = "Login session is required to create watchlist folders";
+                self.errors.write().await.push(message.to_string());
+                return None;
+            },
+        };
+
+        let entries = self.watchlist.get_entries().read().await.clone();
+        let root_path = self.root_path.read().await.clone();
+        let mut new_folders = Vec::new();
+        {
+            let _busy_lock = self.folders_busy_lock.lock().await;
+            for entry in entries {
+                let candidate = self.get_import_candidate(entry.series).await;
+                if !candidate.is_new_folder {
+                    continue;
+                }
+                let folder_path = path::Path::new(root_path.as_str()).join(candidate.folder_name.as_str());
+                if let Err(err) = tokio::fs::create_dir(folder_path.as_path()).await {
+                    let message = format!("IO error while creating folder '{}': {}", candidate.folder_name, err);
+                    self.errors.write().await.push(message);
+                    continue;
+                }
+                let folder = self.insert_folder(root_path.as_str(), candidate.folder_name.as_str()).await;
+                new_folders.push((folder, candidate));
+            }
+        }
+
+        let total_created = new_folders.len();
+        for (folder, candidate) in new_folders {
+            if folder.load_cache_from_api(session.clone(), candidate.series.id).await.is_none() {
+                continue;
+            }
+            tokio::join!(
+                folder.update_file_intents(),
+                folder.save_cache_to_file(),
+            );
+        }
+        self.push_activity(ActivityKind::Execute, format!("Created {} folders for watchlist", total_created)).await;
+        Some(())
+    }
+
+    pub fn get_watchlist(&self) -> &Watchlist {
+        &self.watchlist
+    }
+
+    pub fn get_agenda(&self) -> &RwLock<Option<Agenda>> {
+        &self.agenda
+    }
+
+    pub fn get_agenda_busy_lock(&self) -> &Mutex<()> {
+        &self.agenda_busy_lock
+    }
+
+    pub fn get_cache_store(&self) -> &Arc<SeriesCacheStore> {
+        &self.cache_store
+    }
+
+    pub fn get_trash_entries(&self) -> &RwLock<Option<Vec<TrashEntry>>> {
+        &self.trash_entries
+    }
+
+    pub fn get_trash_busy_lock(&self) -> &Mutex<()> {
+        &self.trash_busy_lock
+    }
+
+    pub fn get_interrupted_executions(&self) -> &RwLock<Vec<ExecutionJournal>> {
+        &self.interrupted_executions
+    }
+
+    pub fn get_progress(&self) -> &RwLock<Option<Arc<ProgressTracker>>> {
+        &self.progress
+    }
+
+    pub fn get_import_plan(&self) -> &RwLock<Option<ImportPlan>> {
+        &self.import_plan
+    }
+
+    pub fn get_import_busy_lock(&self) -> &Mutex<()> {
+        &self.import_busy_lock
+    }
+
+    pub fn get_bindings(&self) -> &RwLock<Option<Vec<SeriesBinding>>> {
+        &self.bindings
+    }
+
+    pub fn get_rebind_plan(&self) -> &RwLock<Option<RebindPlan>> {
+        &self.rebind_plan
+    }
+
+    pub fn get_rebind_busy_lock(&self) -> &Mutex<()> {
+        &self.rebind_busy_lock
+    }
+
+    pub fn get_torrent_cleanups(&self) -> &RwLock<Option<Vec<TorrentCleanup>>> {
+        &self.torrent_cleanups
+    }
+
+    pub fn get_seeding_torrents(&self) -> &RwLock<Vec<Torrent>> {
+        &self.seeding_torrents
+    }
+
+    pub fn get_cleanup_queue(&self) -> &CleanupQueue {
+        &self.cleanup_queue
+    }
+
+    pub fn get_torrent_cleanup_busy_lock(&self) -> &Mutex<()> {
+        &self.torrent_cleanup_busy_lock
+    }
+
+    pub fn get_root_path(&self) -> &RwLock<String> {
+        &self.root_path
+    }
+
+    pub fn get_filter_rules(&self) -> &RwLock<Arc<FilterRules>> {
+        &self.filter_rules
+    }
+
+    pub fn get_folders_busy_lock(&self) -> &Mutex<()> {
+        &self.folders_busy_lock
+    }
+
+    pub fn get_folders(&self) -> &RwLock<Vec<Arc<AppFolder>>> {
+        &self.folders
+    }
+
+    pub fn get_selected_folder_index(&self) -> &RwLock<Option<usize>> {
+        &self.selected_folder_index 
+    }
+
+    pub fn get_visible_folder_indices(&self) -> &RwLock<Vec<usize>> {
+        &self.visible_folder_indices
+    }
+
+    pub fn get_series(&self) -> &RwLock<Option<Vec<Series>>> {
+        &self.series
+    }
+
+    pub fn get_selected_series_index(&self) -> &RwLock<Option<usize>> {
+        &self.selected_series_index
+    }
+
+    pub fn get_series_busy_lock(&self) -> &Mutex<()> {
+        &self.series_busy_lock
+    }
+
+    pub fn get_activity_log(&self) -> &Arc<ActivityLog> {
+        &self.activity_log
     }
 
     pub fn get_errors(&self) -> &RwLock<Vec<String>> {
@@ -1,6 +1,6 @@
 use std::collections::{HashMap,HashSet};
 use tokio::sync::{RwLockReadGuard, RwLockWriteGuard};
-use crate::file_intent::Action;
+use crate::file_intent::{Action, DeleteReason, DestError, IntentWarning, validate_dest};
 use crate::tvdb_cache::EpisodeKey;
 
 pub(crate) struct AppFile {
@@ -9,12 +9,18 @@ pub(crate) struct AppFile {
     pub(crate) action: Action,
     pub(crate) dest: String,
     pub(crate) is_enabled: bool,
+    pub(crate) warnings: Vec<IntentWarning>,
+    pub(crate) delete_reason: Option<DeleteReason>,
+    pub(crate) size: u64,
 }
 
 pub struct FileTracker {
     pending_writes: HashMap<String, HashSet<usize>>,
     existing_sources: HashMap<String, usize>,
+    // Files with an enabled move which free up their source path once executed
+    moving_sources: HashSet<usize>,
     action_count: enum_map::EnumMap<Action, usize>,
+    action_size: enum_map::EnumMap<Action, u64>,
 }
 
 // We queue all our changes to our files so we can iterate over them while submitting changes
@@ -63,13 +69,19 @@ pub struct ImmutableAppFileIterator<'a> {
 }
 
 impl AppFile {
-    pub(crate) fn new(src: String, src_descriptor: Option<EpisodeKey>, action: Action, dest: String) -> Self {
+    pub(crate) fn new(
+        src: String, src_descriptor: Option<EpisodeKey>, action: Action, dest: String, 
+        warnings: Vec<IntentWarning>, delete_reason: Option<DeleteReason>, size: u64,
+    ) -> Self {
         Self {
             src,
             src_descriptor,
             action,
             dest,
             is_enabled: false,
+            warnings,
+            delete_reason,
+            size,
         }
     }
 }
@@ -79,14 +91,18 @@ impl FileTracker {
         Self {
             pending_writes: HashMap::new(),
             existing_sources: HashMap::new(),
+            moving_sources: HashSet::new(),
             action_count: enum_map::enum_map!{ _ => 0 },
+            action_size: enum_map::enum_map!{ _ => 0 },
         }
     }
 
     pub(crate) fn clear(&mut self) {
         self.pending_writes.clear();
         self.existing_sources.clear();
+        self.moving_sources.clear();
         self.action_count.clear();
+        self.action_size.clear();
     }
 
     pub(crate) fn insert_existing_source(&mut self, src: &str, index: usize) {
@@ -96,33 +112,38 @@ impl FileTracker {
     fn add_pending_write(&mut self, dest: &str, index: usize) {
         let entries = match self.pending_writes.get_mut(dest) {
             Some(entries) => entries,
-            None => self.pending_writes.entry(dest.to_string()).or_insert(HashSet::new()),
+            None => self.pending_writes.entry(dest.to_string()).or_default(),
         };
         entries.insert(index);
+        self.moving_sources.insert(index);
     }
 
     fn remove_pending_write(&mut self, dest: &str, index: usize) {
         let entries = match self.pending_writes.get_mut(dest) {
             Some(entries) => entries,
-            None => self.pending_writes.entry(dest.to_string()).or_insert(HashSet::new()),
+            None => self.pending_writes.entry(dest.to_string()).or_default(),
         };
         entries.remove(&index);
+        self.moving_sources.remove(&index);
     }
 
-    fn check_if_write_conflicts(&self, dest: &str) -> bool {
+    // Files which end up at the destination, including a file already there which isn't moved away
+    fn count_writes(&self, dest: &str) -> usize {
         let mut total_files = 0;
-        if self.existing_sources.get(dest).is_some() {
-            total_files += 1;
-        }
-        // NOTE: Exit early to avoid extra table lookup
-        if total_files > 1 {
-            return true;
+        // chains and swaps are allowed since the execution order moves the existing file away first
+        if let Some(index) = self.existing_sources.get(dest) {
+            if !self.moving_sources.contains(index) {
+                total_files += 1;
+            }
         }
         if let Some(entries) = self.pending_writes.get(dest) {
             total_files += entries.len();
         } 
+        total_files
+    }
 
-        total_files > 1
+    fn check_if_write_conflicts(&self, dest: &str) -> bool {
+        self.count_writes(dest) > 1
     }
 
     pub fn get_pending_writes(&self) -> &HashMap<String, HashSet<usize>> {
@@ -140,6 +161,24 @@ impl FileTracker {
     pub fn get_action_count_mut(&mut self) -> &mut enum_map::EnumMap<Action, usize> {
         &mut self.action_count
     }
+
+    pub fn get_action_size(&self) -> &enum_map::EnumMap<Action, u64> {
+        &self.action_size
+    }
+
+    pub fn get_action_size_mut(&mut self) -> &mut enum_map::EnumMap<Action, u64> {
+        &mut self.action_size
+    }
+
+    // Space freed by executing the pending deletes
+    pub fn get_reclaimable_size(&self) -> u64 {
+        self.action_size[Action::Delete]
+    }
+
+    // Space used by files kept alongside the episodes
+    pub fn get_extras_size(&self) -> u64 {
+        self.action_size[Action::Whitelist] + self.action_size[Action::Extra]
+    }
 }
 
 pub(crate) fn flush_file_changes_acquired(
@@ -167,20 +206,23 @@ pub(crate) fn flush_file_changes_acquired(
 
                 file_tracker.action_count[old_action] -= 1usize;
                 file_tracker.action_count[new_action] += 1usize;
+                file_tracker.action_size[old_action] -= file.size;
+                file_tracker.action_size[new_action] += file.size;
 
                 if !file.is_enabled {
                     continue;
                 };
 
-                if old_action != Action::Rename && new_action != Action::Rename {
+                if !old_action.is_move() && !new_action.is_move() {
                     continue;
                 }
 
-                if old_action == Action::Rename {
+                if old_action.is_move() {
                     file_tracker.remove_pending_write(file.dest.as_str(), index);
-                } else {
+                }
+                if new_action.is_move() {
                     file_tracker.add_pending_write(file.dest.as_str(), index);
-                };
+                }
                 total_changes += 1;
             },
             FileChange::IsEnabled(index, new_is_enabled) => {
@@ -198,7 +240,7 @@ pub(crate) fn flush_file_changes_acquired(
                     continue;
                 }
 
-                if file.action != Action::Rename {
+                if !file.action.is_move() {
                     continue;
                 }
 
@@ -221,7 +263,7 @@ pub(crate) fn flush_file_changes_acquired(
                 }
 
                 // We perform a .clear() and .push_str(...) to avoid a short lived clone
-                if !file.is_enabled || file.action != Action::Rename {
+                if !file.is_enabled || !file.action.is_move() {
                     file.dest.clear();
                     file.dest.push_str(new_dest.as_str());
                     continue
@@ -358,17 +400,50 @@ macro_rules! generate_app_file_getters {
                 self.file.dest.as_str()
             }
 
+            // Mirrors set_action(...), files which don't move keep their place
+            pub fn get_dest_for_action(&self, action: Action) -> Option<&str> {
+                if !action.is_move() {
+                    return None;
+                }
+                if !self.file.action.is_move() && self.file.dest.is_empty() {
+                    return Some(self.file.src.as_str());
+                }
+                Some(self.file.dest.as_str())
+            }
+
             pub fn get_is_enabled(&self) -> bool {
                 self.file.is_enabled
             }
 
+            pub fn get_warnings(&self) -> &[IntentWarning] {
+                self.file.warnings.as_slice()
+            }
+
+            pub fn get_delete_reason(&self) -> Option<DeleteReason> {
+                self.file.delete_reason
+            }
+
+            pub fn get_size(&self) -> u64 {
+                self.file.size
+            }
+
             pub fn get_is_conflict(&self) -> bool {
                 let file = &self.file;
-                if !file.is_enabled || file.action != Action::Rename {
+                if !file.is_enabled || !file.action.is_move() {
                     return false;
                 }
                 self.file_tracker.check_if_write_conflicts(file.dest.as_str())
             }
+
+            // Disabled files aren't pending writes yet, so any other write to their destination is a conflict
+            pub fn get_is_dest_taken(&self) -> bool {
+                let file = &self.file;
+                if !file.action.is_move() {
+                    return false;
+                }
+                let total_own_writes = if file.is_enabled { 1 } else { 0 };
+                self.file_tracker.count_writes(file.dest.as_str()) > total_own_writes
+            }
         }
     }
 }
@@ -380,7 +455,7 @@ impl MutableAppFile<'_> {
     pub fn set_action(&mut self, new_action: Action) {
         self.change_queue.push(FileChange::SetAction(self.index, new_action));
         // Automatically set destination to src is not set
-        if self.file.action != Action::Rename && new_action == Action::Rename && self.file.dest.is_empty() {
+        if !self.file.action.is_move() && new_action.is_move() && self.file.dest.is_empty() {
             self.change_queue.push(FileChange::Destination(self.index, self.file.src.to_owned())); 
         }
         // Automatically disable enabled if we are deleting it
@@ -398,4 +473,17 @@ impl MutableAppFile<'_> {
         let change = FileChange::Destination(self.index, new_dest);
         self.change_queue.push(change);
     }
+
+    pub fn validate_dest(&self, dest: &str) -> Result<(), DestError> {
+        validate_dest(dest)?;
+        let tracker = self.file_tracker;
+        let is_existing = tracker.existing_sources.get(dest)
+            .map(|index| *index != self.index && !tracker.moving_sources.contains(index))
+            .unwrap_or(false);
+        let is_pending = tracker.pending_writes.get(dest).map(|entries| entries.iter().any(|index| *index != self.index)).unwrap_or(false);
+        if is_existing || is_pending {
+            return Err(DestError::Collision);
+        }
+        Ok(())
+    }
 }
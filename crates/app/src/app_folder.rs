@@ -1,29 +1,82 @@
 use async_recursion;
+use chrono;
 use enum_map;
 use futures;
 use serde_json;
+use std::collections::{HashMap, HashSet};
 use std::path;
 use std::sync::Arc;
 use tokio::sync::{Mutex, RwLock};
 use tokio;
-use tvdb::api::LoginSession;
+use tokio_util::sync::CancellationToken;
+use mediaserver::api::MediaServerSession;
+use opensubtitles::api::{SubtitleQuery, SubtitleSession};
+use opensubtitles::hash::compute_file_hash;
+use tvdb::api::{ApiError, LoginSession};
 use tvdb::models::{Episode, Series};
 use walkdir;
+use crate::atomic_file::{LoadError, Loaded, encode_data, load_with_backup, write_atomic};
 use crate::app_file::{
     AppFile, FileChange, 
-    MutableAppFileList, ImmutableAppFileList, 
+    MutableAppFileList, ImmutableAppFile, ImmutableAppFileList, 
     FileTracker, 
     flush_file_changes_acquired,
 };
+use crate::activity_log::{ActivityKind, ActivityLog};
+use crate::anime_mapping::{AnimeMapping, deserialize_anime_mapping, serialize_anime_mapping};
+use crate::archive::{ArchivedFile, deserialize_archived_files, get_archive_path, is_archive_flagged, serialize_archived_files};
+use crate::calendar::{get_aired_episode_counts, get_total_missing_recent_episodes, is_episode_file};
 use crate::bookmarks::{BookmarkTable, deserialize_bookmarks, serialize_bookmarks};
-use crate::file_intent::{FilterRules, Action, get_file_intent};
-use crate::tvdb_cache::{EpisodeKey, TvdbCache};
-
+use crate::companion::group_companion_files;
+use crate::execution_journal::{ExecutionJournal, JournalOperation};
+use crate::execution_outcomes::{ExecutionOutcome, ExecutionOutcomes, SkipReason};
+use crate::execution_skips::{ExecutionSkipList, deserialize_execution_skips, serialize_execution_skips};
+use crate::execution_summary::{ExecutionFilter, ExecutionSummary};
+use crate::external_ids::apply_remote_ids;
+use crate::duplicates::mark_duplicate_losers;
+use crate::file_intent::{FilterRules, Action, DeleteReason, DeletePolicy, DuplicatePolicy, get_file_intent, is_video_path};
+use crate::folder_summary::{FolderSummary, FolderSummaryStore};
+use crate::folder_settings::{FolderReview, FolderSettings, deserialize_folder_settings, serialize_folder_settings};
+use crate::metrics::{
+    ANILIST_REQUEST, DELETES_EXECUTED, EPISODE_CACHE, EXECUTION_ERRORS, FOLDER_SCAN, MEDIA_SERVER_REQUEST, OPENSUBTITLES_REQUEST, RENAMES_EXECUTED, TVDB_REQUEST,
+    get_metrics,
+};
+use crate::move_planner::{MoveStep, plan_moves};
+use crate::permissions::{PermissionIssue, clear_read_only, diagnose_permission_error};
+use crate::plan_diff::{PlanChange, PlanSnapshot, deserialize_plan_snapshot, serialize_plan_snapshot};
+use crate::rule_profiles::ProfileStore;
+use crate::metadata_tags::{EpisodeTags, is_taggable_path, write_episode_tags};
+use crate::scan_index::{FileStamp, ScanContext, ScanEntries, ScanIndex};
+use crate::subtitles::{get_subtitle_path, parse_imdb_id, pick_best_subtitle};
+use crate::torrent_labels::LabelRule;
+use crate::trakt_sync::TraktSync;
+use crate::library_lock::LibraryLock;
+use crate::natural_sort::sort_natural_by;
+use crate::other_media::{FolderExclusion, MAX_SAMPLED_FILES, detect_other_media};
+use crate::remote_fs::RemoteHost;
+use crate::trash::{get_trash_entry_name, get_trash_path};
+use crate::watch_state::WatchState;
+use crate::tvdb_cache::{EpisodeKey, EpisodeOrdering, EpisodeTranslations, SeriesCacheKey, SeriesCacheStore, SeriesStatus, TvdbCache};
+
+const PATH_STR_ANIME_MAPPING: &str = "anime_mapping.json";
+const PATH_STR_ARCHIVE: &str = "archive.json";
 const PATH_STR_BOOKMARKS: &str = "bookmarks.json";
 const PATH_STR_EPISODES_DATA: &str = "episodes.json";
+const PATH_STR_EXECUTION_SKIPS: &str = "execution_skips.json";
+const PATH_STR_PLAN_SNAPSHOT: &str = "plan_snapshot.json";
 const PATH_STR_SERIES_DATA: &str = "series.json";
-
-#[derive(Debug, Eq, PartialEq, Copy, Clone, enum_map::Enum)]
+const PATH_STR_SETTINGS: &str = "folder_settings.json";
+const PATH_STR_TRANSLATIONS_DATA: &str = "translations.json";
+
+type DataFileCheck = fn(&str) -> Result<(), serde_json::Error>;
+// Data files kept at the top of each folder which aren't part of the library itself
+pub(crate) const FOLDER_DATA_FILENAMES: [&str; 9] = [
+    PATH_STR_ANIME_MAPPING, PATH_STR_ARCHIVE, PATH_STR_BOOKMARKS, PATH_STR_EPISODES_DATA,
+    PATH_STR_EXECUTION_SKIPS, PATH_STR_PLAN_SNAPSHOT, PATH_STR_SERIES_DATA, PATH_STR_SETTINGS, PATH_STR_TRANSLATIONS_DATA,
+];
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Eq, PartialEq, Copy, Clone, enum_map::Enum)]
+#[serde(rename_all="snake_case")]
 pub enum FolderStatus {
     Unknown,
     Empty,
@@ -52,38 +105,111 @@ impl FolderStatus {
     }
 }
 
+// Used to triage which folders need attention in a large library
+#[derive(Debug, Default, Copy, Clone)]
+pub struct SeriesProgress {
+    pub status: SeriesStatus,
+    pub total_missing_recent: usize,
+    pub total_aired_present: usize,
+    pub total_aired: usize,
+}
+
+impl SeriesProgress {
+    // None until an episode has aired, between 0 and 1 otherwise
+    pub fn get_completion(&self) -> Option<f32> {
+        match self.total_aired {
+            0 => None,
+            total_aired => Some(self.total_aired_present.min(total_aired) as f32 / total_aired as f32),
+        }
+    }
+}
+
+// Shared by every folder in the root folder
+#[derive(Clone)]
+pub struct FolderServices {
+    pub activity_log: Arc<ActivityLog>,
+    pub cache_store: Arc<SeriesCacheStore>,
+    pub profile_store: Arc<ProfileStore>,
+    pub trakt_sync: Arc<TraktSync>,
+    pub library_lock: Arc<LibraryLock>,
+    pub summary_store: Arc<FolderSummaryStore>,
+    // Set when the root folder is on another machine
+    pub remote: Option<Arc<RemoteHost>>,
+}
+
 pub struct AppFolder {
+    root_path: String,
     folder_path: String,
     folder_name: String,
     bookmarks_path: String,
     series_path: String,
     episodes_path: String,
-
-    filter_rules: Arc<FilterRules>,
-    cache: RwLock<Option<TvdbCache>>,
+    anime_mapping_path: String,
+    settings_path: String,
+    translations_path: String,
+    archive_path: String,
+    execution_skips_path: String,
+    plan_snapshot_path: String,
+
+    base_rules: RwLock<Arc<FilterRules>>,
+    // The base rules with the folder's profile layered on top
+    filter_rules: RwLock<Arc<FilterRules>>,
+    profile_store: Arc<ProfileStore>,
+    activity_log: Arc<ActivityLog>,
+    settings: RwLock<FolderSettings>,
+    cache: RwLock<Option<Arc<TvdbCache>>>,
+    cache_store: Arc<SeriesCacheStore>,
+    trakt_sync: Arc<TraktSync>,
+    library_lock: Arc<LibraryLock>,
+    summary_store: Arc<FolderSummaryStore>,
+    remote: Option<Arc<RemoteHost>>,
+    anime_mapping: RwLock<Option<AnimeMapping>>,
+    series_progress: RwLock<SeriesProgress>,
 
     file_list: RwLock<Vec<AppFile>>,
     file_tracker: RwLock<FileTracker>,
     change_queue: RwLock<Vec<FileChange>>,
 
     bookmarks: RwLock<BookmarkTable>,
+    archived_files: RwLock<Vec<ArchivedFile>>,
+    execution_skips: RwLock<ExecutionSkipList>,
+    execution_outcomes: RwLock<ExecutionOutcomes>,
+    watch_state: RwLock<Option<WatchState>>,
+
+    plan_snapshot: RwLock<Option<PlanSnapshot>>,
+    plan_changes: RwLock<Vec<PlanChange>>,
 
     errors: RwLock<Vec<String>>,
+    permission_issues: RwLock<Vec<PermissionIssue>>,
+    scan_index: Mutex<ScanIndex>,
     busy_lock: Mutex<()>,
     selected_descriptor: RwLock<Option<EpisodeKey>>,
+    // Files without a descriptor are selected by their source instead
+    selected_src: RwLock<Option<String>>,
     is_initial_load: Mutex<bool>,
     is_file_count_init: Mutex<bool>,
+    // Reviewed plans are kept until they are executed or unlocked
+    is_plan_locked: Mutex<bool>,
+    // Status from the previous session which is shown until the folder is loaded
+    last_known_status: Mutex<Option<FolderStatus>>,
+    // Set for folders which aren't tv series, like music or books, so they are left alone
+    exclusion: RwLock<Option<FolderExclusion>>,
 }
 
 impl AppFolder {
-    pub fn new(root_path: &str, folder_path: &str, filter_rules: Arc<FilterRules>) -> Self {
+    pub fn new(root_path: &str, folder_path: &str, filter_rules: Arc<FilterRules>, services: FolderServices) -> Self {
+        let FolderServices { activity_log, cache_store, profile_store, trakt_sync, library_lock, summary_store, remote } = services;
         let folder_name = match path::Path::new(folder_path).strip_prefix(root_path) {
             Ok(name) => name.to_string_lossy().to_string(), 
             Err(_) => folder_path.to_string(),
         }.replace(std::path::MAIN_SEPARATOR, "/");
 
+        let data_path = match remote.as_ref() {
+            Some(remote) => remote.get_data_path(folder_name.as_str()),
+            None => path::PathBuf::from(folder_path),
+        };
         let get_filepath = |filename: &str| -> String {
-            path::Path::new(folder_path)
+            data_path
                 .join(filename)
                 .to_string_lossy()
                 .to_string()
@@ -93,41 +219,92 @@ impl AppFolder {
         let series_path = get_filepath(PATH_STR_SERIES_DATA);
         let episodes_path = get_filepath(PATH_STR_EPISODES_DATA);
         let bookmarks_path = get_filepath(PATH_STR_BOOKMARKS);
+        let anime_mapping_path = get_filepath(PATH_STR_ANIME_MAPPING);
+        let settings_path = get_filepath(PATH_STR_SETTINGS);
+        let translations_path = get_filepath(PATH_STR_TRANSLATIONS_DATA);
+        let archive_path = get_filepath(PATH_STR_ARCHIVE);
+        let execution_skips_path = get_filepath(PATH_STR_EXECUTION_SKIPS);
+        let plan_snapshot_path = get_filepath(PATH_STR_PLAN_SNAPSHOT);
 
         Self {
+            root_path: root_path.to_string(),
             folder_path: folder_path.to_string(),
             folder_name,
             series_path,
             episodes_path,
             bookmarks_path,
-
-            filter_rules,
+            anime_mapping_path,
+            settings_path,
+            translations_path,
+            archive_path,
+            execution_skips_path,
+            plan_snapshot_path,
+
+            filter_rules: RwLock::new(filter_rules.clone()),
+            base_rules: RwLock::new(filter_rules),
+            profile_store,
+            activity_log,
+            settings: RwLock::new(FolderSettings::default()),
             cache: RwLock::new(None),
+            cache_store,
+            trakt_sync,
+            library_lock,
+            summary_store,
+            remote,
+            anime_mapping: RwLock::new(None),
+            series_progress: RwLock::new(SeriesProgress::default()),
 
             file_list: RwLock::new(Vec::new()),
             file_tracker: RwLock::new(FileTracker::new()),
             change_queue:RwLock::new(Vec::new()),
 
             bookmarks: RwLock::new(BookmarkTable::new()),
+            archived_files: RwLock::new(Vec::new()),
+            execution_skips: RwLock::new(ExecutionSkipList::default()),
+            execution_outcomes: RwLock::new(ExecutionOutcomes::default()),
+            watch_state: RwLock::new(None),
+
+            plan_snapshot: RwLock::new(None),
+            plan_changes: RwLock::new(Vec::new()),
 
             errors: RwLock::new(Vec::new()),
+            permission_issues: RwLock::new(Vec::new()),
+            scan_index: Mutex::new(ScanIndex::default()),
             busy_lock: Mutex::new(()),
             selected_descriptor: RwLock::new(None),
+            selected_src: RwLock::new(None),
             is_initial_load: Mutex::new(false),
             is_file_count_init: Mutex::new(false),
+            is_plan_locked: Mutex::new(false),
+            last_known_status: Mutex::new(None),
+            exclusion: RwLock::new(None),
         }
     }
 }
 
+// Everything besides the file itself which is needed to work out its intent
+struct IntentSources<'a> {
+    cache: &'a TvdbCache,
+    anime_mapping: Option<&'a AnimeMapping>,
+    settings: &'a FolderSettings,
+    rules: &'a FilterRules,
+}
+
 #[async_recursion::async_recursion]
-async fn recursive_search_file_intents(root_path: &str, curr_folder: &str, cache: &TvdbCache, intents: &mut Vec<AppFile>, rules: &FilterRules) -> Result<(), std::io::Error> {
+async fn recursive_search_file_intents(
+    root_path: &str, curr_folder: &str, sources: &IntentSources<'_>,
+    intents: &mut Vec<AppFile>, scan_entries: &mut ScanEntries, cancel: Option<&CancellationToken>,
+) -> Result<(), std::io::Error> {
     let mut entries = tokio::fs::read_dir(curr_folder).await?;
     while let Some(entry) = entries.next_entry().await? {
+        if is_cancelled(cancel) {
+            return Ok(());
+        }
         let file_type = entry.file_type().await?;
         if file_type.is_dir() {
             let path = entry.path();
             if let Some(sub_folder) = path.to_str() {
-                recursive_search_file_intents(root_path, sub_folder, cache, intents, rules).await?;
+                recursive_search_file_intents(root_path, sub_folder, sources, intents, scan_entries, cancel).await?;
             };
             continue;
         }
@@ -140,14 +317,9 @@ async fn recursive_search_file_intents(root_path: &str, curr_folder: &str, cache
             };
 
             if let Some(rel_path) = rel_path.to_str() {
-                let intent = get_file_intent(rel_path, rules, cache);
-                let app_file = AppFile::new(
-                    rel_path.to_string().replace(std::path::MAIN_SEPARATOR, "/"),
-                    intent.descriptor,
-                    intent.action,
-                    intent.dest.replace(std::path::MAIN_SEPARATOR, "/"),
-                );
-                intents.push(app_file);
+                let metadata = entry.metadata().await?;
+                let stamp = FileStamp { size: metadata.len(), modified: metadata.modified().ok() };
+                push_file_intent(rel_path, stamp, sources, intents, scan_entries);
             }
             continue;
         }
@@ -155,6 +327,82 @@ async fn recursive_search_file_intents(root_path: &str, curr_folder: &str, cache
     Ok(())
 }
 
+fn is_cancelled(cancel: Option<&CancellationToken>) -> bool {
+    cancel.map(|token| token.is_cancelled()).unwrap_or(false)
+}
+
+fn push_file_intent(
+    rel_path: &str, stamp: FileStamp, sources: &IntentSources<'_>,
+    intents: &mut Vec<AppFile>, scan_entries: &mut ScanEntries,
+) {
+    let size = stamp.size;
+    let intent = scan_entries.get_intent(rel_path, stamp, || {
+        get_file_intent(rel_path, size, sources.rules, sources.cache, sources.anime_mapping, sources.settings)
+    });
+    let app_file = AppFile::new(
+        rel_path.to_string().replace(std::path::MAIN_SEPARATOR, "/"),
+        intent.descriptor,
+        intent.action,
+        intent.dest.replace(std::path::MAIN_SEPARATOR, "/"),
+        intent.warnings,
+        intent.delete_reason,
+        size,
+    );
+    intents.push(app_file);
+}
+
+fn get_episode_names(episodes: &[Episode]) -> std::collections::HashMap<u32, String> {
+    episodes
+        .iter()
+        .filter_map(|episode| {
+            let name = episode.name.as_ref()?;
+            if name.is_empty() {
+                return None;
+            }
+            Some((episode.id, name.clone()))
+        })
+        .collect()
+}
+
+fn record_execution_metrics(total_renames: usize, total_deletes: usize, total_errors: usize) {
+    let metrics = get_metrics();
+    metrics.add_count(RENAMES_EXECUTED, total_renames as u64);
+    metrics.add_count(DELETES_EXECUTED, total_deletes as u64);
+    metrics.add_count(EXECUTION_ERRORS, total_errors as u64);
+}
+
+// Which files an execution applies to
+#[derive(Clone, Copy)]
+enum ExecutionScope<'a> {
+    Enabled(ExecutionFilter),
+    // A single file applied on its own, whether or not it is enabled
+    File(&'a str),
+}
+
+impl ExecutionScope<'_> {
+    fn is_included(&self, file: &ImmutableAppFile<'_>) -> bool {
+        match self {
+            ExecutionScope::Enabled(filter) => file.get_is_enabled() && filter.is_file_included(file.get_action(), file.get_src(), file.get_dest()),
+            ExecutionScope::File(src) => file.get_src() == *src,
+        }
+    }
+
+    fn is_conflict(&self, file: &ImmutableAppFile<'_>) -> bool {
+        match self {
+            ExecutionScope::Enabled(_) => file.get_is_conflict(),
+            ExecutionScope::File(_) => file.get_is_dest_taken(),
+        }
+    }
+
+    // Applying a single file retries it even if it is on the skip list
+    fn is_skipped(&self, file: &ImmutableAppFile<'_>, skips: &ExecutionSkipList, max_failures: u32) -> bool {
+        match self {
+            ExecutionScope::Enabled(_) => skips.is_skipped(file.get_src(), max_failures),
+            ExecutionScope::File(_) => false,
+        }
+    }
+}
+
 fn check_folder_empty(path: &path::Path) -> bool {
     for entry in walkdir::WalkDir::new(path).into_iter().flatten() {
         if entry.file_type().is_file() {
@@ -165,6 +413,55 @@ fn check_folder_empty(path: &path::Path) -> bool {
 }
 
 impl AppFolder {
+    // Moves all loaded state into a folder at the new path after the folder was renamed on disk
+    // The caller is expected to hold the busy lock so that nothing changes while the state is moved
+    pub(crate) async fn take_with_folder_path(&self, folder_path: &str) -> AppFolder {
+        let services = FolderServices {
+            activity_log: self.activity_log.clone(),
+            cache_store: self.cache_store.clone(),
+            profile_store: self.profile_store.clone(),
+            trakt_sync: self.trakt_sync.clone(),
+            library_lock: self.library_lock.clone(),
+            summary_store: self.summary_store.clone(),
+            remote: self.remote.clone(),
+        };
+        let folder = AppFolder::new(self.root_path.as_str(), folder_path, self.base_rules.read().await.clone(), services);
+        *folder.settings.write().await = std::mem::take(&mut *self.settings.write().await);
+        *folder.filter_rules.write().await = self.filter_rules.read().await.clone();
+        *folder.cache.write().await = self.cache.write().await.take();
+        *folder.anime_mapping.write().await = self.anime_mapping.write().await.take();
+        *folder.series_progress.write().await = *self.series_progress.read().await;
+        *folder.file_list.write().await = std::mem::take(&mut *self.file_list.write().await);
+        *folder.file_tracker.write().await = std::mem::replace(&mut *self.file_tracker.write().await, FileTracker::new());
+        *folder.change_queue.write().await = std::mem::take(&mut *self.change_queue.write().await);
+        *folder.bookmarks.write().await = std::mem::take(&mut *self.bookmarks.write().await);
+        *folder.archived_files.write().await = std::mem::take(&mut *self.archived_files.write().await);
+        *folder.execution_skips.write().await = std::mem::take(&mut *self.execution_skips.write().await);
+        *folder.execution_outcomes.write().await = std::mem::take(&mut *self.execution_outcomes.write().await);
+        *folder.watch_state.write().await = self.watch_state.write().await.take();
+        *folder.plan_snapshot.write().await = self.plan_snapshot.write().await.take();
+        *folder.plan_changes.write().await = std::mem::take(&mut *self.plan_changes.write().await);
+        *folder.errors.write().await = std::mem::take(&mut *self.errors.write().await);
+        *folder.permission_issues.write().await = std::mem::take(&mut *self.permission_issues.write().await);
+        // scan entries are relative to the folder so they stay valid after the rename
+        *folder.scan_index.lock().await = std::mem::take(&mut *self.scan_index.lock().await);
+        *folder.selected_descriptor.write().await = *self.selected_descriptor.read().await;
+        *folder.selected_src.write().await = self.selected_src.write().await.take();
+        *folder.is_initial_load.lock().await = *self.is_initial_load.lock().await;
+        *folder.is_file_count_init.lock().await = *self.is_file_count_init.lock().await;
+        *folder.is_plan_locked.lock().await = *self.is_plan_locked.lock().await;
+        *folder.last_known_status.lock().await = *self.last_known_status.lock().await;
+        *folder.exclusion.write().await = *self.exclusion.read().await;
+        folder
+    }
+
+    async fn push_activity(&self, kind: ActivityKind, message: String) {
+        if let Err(err) = self.activity_log.push(kind, Some(self.folder_name.as_str()), message).await {
+            let message = format!("IO error while writing activity log: {}", err);
+            self.errors.write().await.push(message);
+        }
+    }
+
     pub async fn perform_initial_load(&self) -> Option<()> {
         {
             let mut is_loaded = self.is_initial_load.lock().await;
@@ -173,9 +470,17 @@ impl AppFolder {
             }
             *is_loaded = true;
         }
+        self.load_settings_from_file().await;
+        if self.update_exclusion().await {
+            self.update_summary().await;
+            return Some(());
+        }
         let (res_0, res_1) = tokio::join!(
             async {
+                self.load_archived_files_from_file().await;
+                self.load_execution_skips_from_file().await;
                 self.load_cache_from_file().await?;
+                self.load_anime_mapping_from_file().await;
                 self.update_file_intents().await
             },
             self.load_bookmarks_from_file(),
@@ -202,7 +507,7 @@ impl AppFolder {
             return FolderStatus::Empty;
         }
 
-        let pending_count = action_count[Action::Delete] + action_count[Action::Rename];
+        let pending_count = action_count[Action::Delete] + action_count[Action::Rename] + action_count[Action::Extra] + action_count[Action::Archive];
         if pending_count > 0 {
             return FolderStatus::Pending;
         }
@@ -210,6 +515,89 @@ impl AppFolder {
         FolderStatus::Done
     }
 
+    pub fn get_last_known_status_blocking(&self) -> FolderStatus {
+        match self.get_folder_status_blocking() {
+            FolderStatus::Unknown => self.last_known_status.blocking_lock().unwrap_or(FolderStatus::Unknown),
+            status => status,
+        }
+    }
+
+    // Shows the folder as it was in the previous session without loading its episode cache
+    pub async fn set_preloaded_summary(&self, summary: FolderSummary) {
+        *self.last_known_status.lock().await = Some(summary.status);
+        *self.series_progress.write().await = SeriesProgress {
+            status: summary.series_status,
+            total_missing_recent: summary.total_missing_recent,
+            total_aired_present: summary.total_aired_present,
+            total_aired: summary.total_aired,
+        };
+        *self.exclusion.write().await = summary.exclusion;
+    }
+
+    async fn update_summary(&self) {
+        let progress = *self.series_progress.read().await;
+        let summary = FolderSummary {
+            status: self.get_folder_status().await,
+            series_status: progress.status,
+            total_missing_recent: progress.total_missing_recent,
+            total_aired_present: progress.total_aired_present,
+            total_aired: progress.total_aired,
+            exclusion: *self.exclusion.read().await,
+        };
+        self.summary_store.update(self.folder_name.as_str(), summary).await;
+    }
+
+    // Folders which were already bound to a series are only excluded by hand
+    async fn update_exclusion(&self) -> bool {
+        let is_series = self.settings.read().await.is_series;
+        let exclusion = match is_series {
+            Some(true) => None,
+            Some(false) => Some(FolderExclusion::Manual),
+            // listing a remote folder is too slow to do for every folder in the root folder
+            None if self.remote.is_some() => None,
+            None if tokio::fs::try_exists(self.series_path.as_str()).await.unwrap_or(false) => None,
+            None => {
+                let folder_path = self.folder_path.clone();
+                let media = tokio::task::spawn_blocking(move || {
+                    let filenames: Vec<String> = walkdir::WalkDir::new(folder_path.as_str())
+                        .follow_links(false)
+                        .into_iter()
+                        .flatten()
+                        .filter(|entry| entry.file_type().is_file())
+                        .take(MAX_SAMPLED_FILES)
+                        .map(|entry| entry.file_name().to_string_lossy().to_string())
+                        .filter(|filename| !FOLDER_DATA_FILENAMES.contains(&filename.as_str()))
+                        .collect();
+                    detect_other_media(filenames.iter().map(|filename| filename.as_str()))
+                }).await.ok().flatten();
+                media.map(FolderExclusion::Detected)
+            },
+        };
+        *self.exclusion.write().await = exclusion;
+        exclusion.is_some()
+    }
+
+    // Unset detects music and book folders again
+    pub async fn set_is_series(&self, is_series: Option<bool>) -> Option<()> {
+        self.settings.write().await.is_series = is_series;
+        self.save_settings_to_file().await?;
+        if self.update_exclusion().await {
+            self.update_summary().await;
+            return Some(());
+        }
+        // the episode cache was never loaded while the folder was excluded
+        *self.is_initial_load.lock().await = false;
+        self.perform_initial_load().await
+    }
+
+    pub fn get_exclusion(&self) -> &RwLock<Option<FolderExclusion>> {
+        &self.exclusion
+    }
+
+    pub async fn is_excluded(&self) -> bool {
+        self.exclusion.read().await.is_some()
+    }
+
     pub async fn get_folder_status(&self) -> FolderStatus {
         if !*self.is_file_count_init.lock().await {
             return FolderStatus::Unknown; 
@@ -229,7 +617,7 @@ impl AppFolder {
             return FolderStatus::Empty;
         }
 
-        let pending_count = action_count[Action::Delete] + action_count[Action::Rename];
+        let pending_count = action_count[Action::Delete] + action_count[Action::Rename] + action_count[Action::Extra] + action_count[Action::Archive];
         if pending_count > 0 {
             return FolderStatus::Pending;
         }
@@ -238,28 +626,57 @@ impl AppFolder {
     }
     
     pub async fn load_bookmarks_from_file(&self) -> Option<()> {
-        let bookmarks_data = tokio::fs::read_to_string(self.bookmarks_path.as_str()).await;
-        if let Err(err) = bookmarks_data.as_ref() {
-            let message = format!("IO while reading bookmarks: {}", err);
-            self.errors.write().await.push(message);
-        }
-
-        let bookmarks_data = bookmarks_data.as_ref().ok()?;
-
-        let bookmarks = match deserialize_bookmarks(bookmarks_data.as_str()) {
+        let bookmarks = match load_with_backup(self.bookmarks_path.as_str(), deserialize_bookmarks).await {
             Ok(bookmarks) => bookmarks,
-            Err(err) => {
+            Err(LoadError::IO(err)) => {
+                let message = format!("IO while reading bookmarks: {}", err);
+                self.errors.write().await.push(message);
+                return None;
+            },
+            Err(LoadError::Decode(err)) => {
                 let message = format!("JSON decoding error reading bookmarks from file: {}", err); 
                 self.errors.write().await.push(message);
                 return None;
             },
         };
+        self.warn_if_restored(&bookmarks, "bookmarks").await;
+
+        *self.bookmarks.write().await = bookmarks.value;
+        Some(())
+    }
+
+    async fn warn_if_restored<T>(&self, loaded: &Loaded<T>, name: &str) {
+        if loaded.is_restored {
+            let message = format!("Restored {} from backup since the file was missing or corrupted", name);
+            self.errors.write().await.push(message);
+        }
+    }
+
+    // Features which read or write the media itself only work on local folders
+    async fn check_local_folder(&self, feature: &str) -> Option<()> {
+        if let Some(remote) = self.remote.as_ref() {
+            let message = format!("Couldn't {} since the folder is on the remote host '{}'", feature, remote.get_destination());
+            self.errors.write().await.push(message);
+            return None;
+        }
+        Some(())
+    }
+
+    pub fn get_remote(&self) -> Option<&Arc<RemoteHost>> {
+        self.remote.as_ref()
+    }
 
-        *self.bookmarks.write().await = bookmarks;
+    // Another instance working on the same root folder would overwrite our changes and vice versa
+    async fn check_library_lock(&self) -> Option<()> {
+        if let Err(message) = self.library_lock.check().await {
+            self.errors.write().await.push(message);
+            return None;
+        }
         Some(())
     }
 
     pub async fn save_bookmarks_to_file(&self) -> Option<()> {
+        self.check_library_lock().await?;
         let bookmarks_data = {
             let bookmarks = self.bookmarks.read().await;
             serialize_bookmarks(&bookmarks)
@@ -272,7 +689,7 @@ impl AppFolder {
         }
 
         let bookmarks_data = bookmarks_data.as_ref().ok()?;
-        let res = tokio::fs::write(self.bookmarks_path.as_str(), bookmarks_data).await;
+        let res = write_atomic(self.bookmarks_path.as_str(), bookmarks_data).await;
 
         if let Err(err) = res {
             let message = format!("IO error while writing bookmarks to file: {}", err);
@@ -282,162 +699,942 @@ impl AppFolder {
         Some(())
     }
 
-    pub async fn update_file_intents(&self) -> Option<()> {
-        let _busy_lock = self.busy_lock.lock().await;
-
-        let mut new_file_list = Vec::<AppFile>::new();
-        {
-            let cache_guard = self.cache.read().await;
-            let cache = match cache_guard.as_ref() {
-                Some(cache) => cache,
-                None => {
-                    let message = "Couldn't update file intents since cache is unloaded";
-                    self.errors.write().await.push(message.to_string()); 
-                    return None;
-                },
-            };
-            let res = recursive_search_file_intents(
-                self.folder_path.as_str(), self.folder_path.as_str(), cache, 
-                &mut new_file_list, &self.filter_rules,
-            ).await;
-            if let Err(err) = res {
-                let message = format!("IO error while reading files for intent update: {}", err);
+    pub async fn load_archived_files_from_file(&self) -> Option<()> {
+        let archived_files = match load_with_backup(self.archive_path.as_str(), deserialize_archived_files).await {
+            Ok(archived_files) => archived_files,
+            // Nothing has been archived from this folder yet
+            Err(LoadError::IO(err)) if err.kind() == std::io::ErrorKind::NotFound => {
+                self.archived_files.write().await.clear();
+                return None;
+            },
+            Err(LoadError::IO(err)) => {
+                let message = format!("IO error while reading archived files: {}", err);
                 self.errors.write().await.push(message);
                 return None;
-            }
-        }
+            },
+            Err(LoadError::Decode(err)) => {
+                let message = format!("JSON decoding error reading archived files from file: {}", err);
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
+        self.warn_if_restored(&archived_files, "archived files").await;
 
-        new_file_list.sort_unstable_by(|a,b| {
-            let a_name = a.src.as_str();
-            let b_name = b.src.as_str();
-            a_name.partial_cmp(b_name).unwrap_or(std::cmp::Ordering::Equal)
-        });
-        
-        {
-            let mut file_list = self.file_list.write().await;
-            let mut file_tracker = self.file_tracker.write().await;
+        *self.archived_files.write().await = archived_files.value;
+        Some(())
+    }
 
-            *file_list = new_file_list;
-            file_tracker.clear();
+    async fn save_archived_files_to_file(&self) -> Option<()> {
+        let archived_data = {
+            let archived_files = self.archived_files.read().await;
+            serialize_archived_files(archived_files.as_slice())
+        };
 
-            // seed conflict table
-            for (index, file) in file_list.iter().enumerate() {
-                file_tracker.insert_existing_source(file.src.as_str(), index);
-                let action_count = file_tracker.get_action_count_mut();
-                action_count[file.action] += 1usize;
-            }
-        }
+        let archived_data = match archived_data {
+            Ok(data) => data,
+            Err(err) => {
+                let message = format!("JSON encoding error writing archived files to file: {}", err);
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
 
-        {
-            // automatically enable renames
-            let mut files = self.get_mut_files().await;
-            let mut files_iter = files.to_iter();
-            while let Some(mut file) = files_iter.next_mut() {
-                if file.get_action() == Action::Rename {
-                    file.set_is_enabled(true);
-                }
-            }
+        if let Err(err) = write_atomic(self.archive_path.as_str(), archived_data).await {
+            let message = format!("IO error while writing archived files to file: {}", err);
+            self.errors.write().await.push(message);
+            return None;
         }
-        
-        self.flush_file_changes().await;
-        *self.is_file_count_init.lock().await = true;
         Some(())
     }
 
-    pub async fn load_cache_from_file(&self) -> Option<()> {
-        let _busy_lock = self.busy_lock.lock().await;
+    pub async fn load_execution_skips_from_file(&self) -> Option<()> {
+        let skips = match load_with_backup(self.execution_skips_path.as_str(), deserialize_execution_skips).await {
+            Ok(skips) => skips,
+            // None of the changes in this folder have failed yet
+            Err(LoadError::IO(err)) if err.kind() == std::io::ErrorKind::NotFound => {
+                self.execution_skips.write().await.clear();
+                return None;
+            },
+            Err(LoadError::IO(err)) => {
+                let message = format!("IO error while reading execution skips: {}", err);
+                self.errors.write().await.push(message);
+                return None;
+            },
+            Err(LoadError::Decode(err)) => {
+                let message = format!("JSON decoding error reading execution skips from file: {}", err);
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
+        self.warn_if_restored(&skips, "execution skips").await;
 
-        let (series_data, episodes_data) = tokio::join!(
-            tokio::fs::read_to_string(self.series_path.as_str()),
-            tokio::fs::read_to_string(self.episodes_path.as_str())
-        );
-        
-        if let Err(err) = series_data.as_ref() {
-            let message = format!("IO error while reading series cache: {}", err);
-            self.errors.write().await.push(message);
-        }
+        *self.execution_skips.write().await = skips.value;
+        Some(())
+    }
+
+    async fn save_execution_skips_to_file(&self) -> Option<()> {
+        let skips_data = {
+            let skips = self.execution_skips.read().await;
+            serialize_execution_skips(&skips)
+        };
+
+        let skips_data = match skips_data {
+            Ok(data) => data,
+            Err(err) => {
+                let message = format!("JSON encoding error writing execution skips to file: {}", err);
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
 
-        if let Err(err) = episodes_data.as_ref() {
-            let message = format!("IO error while reading episodes cache: {}", err);
+        if let Err(err) = write_atomic(self.execution_skips_path.as_str(), skips_data).await {
+            let message = format!("IO error while writing execution skips to file: {}", err);
             self.errors.write().await.push(message);
+            return None;
         }
+        Some(())
+    }
 
-        let series_data = series_data.as_ref().ok()?;
-        let episodes_data = episodes_data.as_ref().ok()?;
-
-        let series: Series = match serde_json::from_str(series_data.as_str()) {
-            Ok(series) => series,
-            Err(err) => {
-                let message = format!("JSON decoding error reading series from file: {}", err);
+    // Only read for the first scan of the session, later scans compare against the snapshot in memory
+    async fn load_plan_snapshot_from_file(&self) -> Option<PlanSnapshot> {
+        let snapshot = match load_with_backup(self.plan_snapshot_path.as_str(), deserialize_plan_snapshot).await {
+            Ok(snapshot) => snapshot,
+            // The folder hasn't been scanned before
+            Err(LoadError::IO(err)) if err.kind() == std::io::ErrorKind::NotFound => return None,
+            Err(LoadError::IO(err)) => {
+                let message = format!("IO error while reading plan snapshot: {}", err);
+                self.errors.write().await.push(message);
+                return None;
+            },
+            Err(LoadError::Decode(err)) => {
+                let message = format!("JSON decoding error reading plan snapshot from file: {}", err);
                 self.errors.write().await.push(message);
                 return None;
             },
         };
+        self.warn_if_restored(&snapshot, "plan snapshot").await;
+        Some(snapshot.value)
+    }
 
-        let episodes: Vec<Episode> = match serde_json::from_str(episodes_data.as_str()) {
-            Ok(episodes) => episodes,
+    async fn save_plan_snapshot_to_file(&self) -> Option<()> {
+        let snapshot_data = match self.plan_snapshot.read().await.as_ref() {
+            Some(snapshot) => serialize_plan_snapshot(snapshot),
+            None => return None,
+        };
+
+        let snapshot_data = match snapshot_data {
+            Ok(data) => data,
             Err(err) => {
-                let message = format!("JSON decoding error reading episodes from file: {}", err);
+                let message = format!("JSON encoding error writing plan snapshot to file: {}", err);
                 self.errors.write().await.push(message);
                 return None;
             },
         };
 
-        let mut cache = self.cache.write().await;
-        *cache = Some(TvdbCache::new(series, episodes));
+        if let Err(err) = write_atomic(self.plan_snapshot_path.as_str(), snapshot_data).await {
+            let message = format!("IO error while writing plan snapshot to file: {}", err);
+            self.errors.write().await.push(message);
+            return None;
+        }
         Some(())
     }
 
-    pub async fn load_cache_from_api(&self, session: Arc<LoginSession>, series_id: u32) -> Option<()> {
-        let _busy_lock = self.busy_lock.lock().await;
+    // Skipped files are executed again once the user has resolved whatever blocked them
+    pub async fn retry_skipped_file(&self, src: &str) -> Option<()> {
+        if !self.execution_skips.write().await.remove(src) {
+            return None;
+        }
+        self.save_execution_skips_to_file().await
+    }
 
-        let (series_res, episodes_res) = tokio::join!(
-            session.get_series(series_id),
-            session.get_episodes(series_id),
-        );
+    pub async fn retry_skipped_files(&self) -> Option<()> {
+        self.execution_skips.write().await.clear();
+        self.save_execution_skips_to_file().await
+    }
 
-        let series = match series_res {
-            Ok(series) => series,
-            Err(err) => {
-                let message = format!("Api error while fetching series: {}", err);
+    // Outcomes are kept through the rescan after an execution and dropped by refreshing the folder by hand
+    pub async fn clear_execution_outcomes(&self) {
+        self.execution_outcomes.write().await.clear();
+    }
+
+    // Episodes which were moved into the archive root still count as being in the library
+    pub async fn get_archived_episodes(&self) -> HashSet<EpisodeKey> {
+        self.archived_files.read().await
+            .iter()
+            .filter(|file| is_video_path(file.src.as_str()))
+            .filter_map(|file| file.descriptor)
+            .collect()
+    }
+
+    pub async fn load_settings_from_file(&self) -> Option<()> {
+        let settings = match load_with_backup(self.settings_path.as_str(), deserialize_folder_settings).await {
+            Ok(settings) => settings,
+            // Use the default settings if the folder has never been configured
+            Err(LoadError::IO(err)) if err.kind() == std::io::ErrorKind::NotFound => {
+                *self.settings.write().await = FolderSettings::default();
+                self.update_filter_rules().await;
+                return None;
+            },
+            Err(LoadError::IO(err)) => {
+                let message = format!("IO error while reading folder settings: {}", err);
+                self.errors.write().await.push(message);
+                return None;
+            },
+            Err(LoadError::Decode(err)) => {
+                let message = format!("JSON decoding error reading folder settings from file: {}", err);
                 self.errors.write().await.push(message);
                 return None;
             },
         };
+        self.warn_if_restored(&settings, "folder settings").await;
 
-        let episodes = match episodes_res {
-            Ok(episodes) => episodes,
+        *self.settings.write().await = settings.value;
+        self.update_filter_rules().await;
+        Some(())
+    }
+
+    // Falls back to the base rules if the profile can't be resolved
+    pub async fn update_filter_rules(&self) {
+        let profile = self.settings.read().await.profile.clone();
+        let base_rules = self.base_rules.read().await.clone();
+        let rules = self.profile_store.get_profiles().read().await.resolve(&base_rules, profile.as_deref());
+        let rules = match rules {
+            Ok(rules) => Arc::new(rules),
+            Err(message) => {
+                self.errors.write().await.push(message);
+                base_rules
+            },
+        };
+        *self.filter_rules.write().await = rules;
+    }
+
+    // The next scan picks up the new rules since they are part of the scan context
+    pub async fn set_base_rules(&self, rules: Arc<FilterRules>) {
+        *self.base_rules.write().await = rules;
+        self.update_filter_rules().await;
+    }
+
+    // Returns whether the rule changed the settings
+    pub async fn apply_label_rule(&self, rule: &LabelRule) -> bool {
+        let is_changed = rule.apply(&mut *self.settings.write().await);
+        if is_changed {
+            self.update_filter_rules().await;
+            self.save_settings_to_file().await;
+            self.push_activity(ActivityKind::Settings, format!("Applied rule for torrent label '{}'", rule.label)).await;
+        }
+        is_changed
+    }
+
+    pub async fn save_settings_to_file(&self) -> Option<()> {
+        self.check_library_lock().await?;
+        let settings_data = {
+            let settings = self.settings.read().await;
+            serialize_folder_settings(&settings)
+        };
+
+        let settings_data = match settings_data {
+            Ok(data) => data,
             Err(err) => {
-                let message = format!("Api error while fetching episodes: {}", err);
+                let message = format!("JSON encoding error writing folder settings to file: {}", err);
                 self.errors.write().await.push(message);
                 return None;
             },
         };
 
-        let mut cache = self.cache.write().await;
-        *cache = Some(TvdbCache::new(series, episodes));
+        if let Err(err) = write_atomic(self.settings_path.as_str(), settings_data).await {
+            let message = format!("IO error while writing folder settings to file: {}", err);
+            self.errors.write().await.push(message);
+            return None;
+        }
+        self.push_activity(ActivityKind::Settings, "Saved folder settings".to_string()).await;
         Some(())
     }
 
-    pub async fn refresh_cache_from_api(&self, session: Arc<LoginSession>) -> Option<()> {
-        let series_id = {
-            let cache_guard = self.cache.read().await;
-            match cache_guard.as_ref() {
-                Some(cache) => cache.series.id,
-                None => {
-                    let message = "Couldn't refresh cache since it requires an existing loaded cache".to_string();
-                    self.errors.write().await.push(message);
-                    return None;
-                },
+    pub async fn set_reviewed(&self, is_reviewed: bool) -> Option<()> {
+        {
+            let mut settings = self.settings.write().await;
+            if settings.review.is_some() == is_reviewed {
+                return Some(());
             }
-        };
-        self.load_cache_from_api(session, series_id).await
+            settings.review = is_reviewed.then(FolderReview::new);
+        }
+        self.save_settings_to_file().await
     }
 
-    pub async fn save_cache_to_file(&self) -> Option<()> {
-        let _busy_lock = self.busy_lock.lock().await;
+    pub async fn set_pinned(&self, is_pinned: bool) -> Option<()> {
+        {
+            let mut settings = self.settings.write().await;
+            if settings.is_pinned == is_pinned {
+                return Some(());
+            }
+            settings.is_pinned = is_pinned;
+        }
+        self.save_settings_to_file().await
+    }
+
+    pub async fn is_pinned(&self) -> bool {
+        self.settings.read().await.is_pinned
+    }
+
+    pub async fn set_dest_override(&self, src: &str, dest: Option<String>) -> Option<()> {
+        {
+            let mut settings = self.settings.write().await;
+            match dest {
+                Some(dest) => settings.dest_overrides.insert(src.to_string(), dest),
+                None => settings.dest_overrides.remove(src),
+            };
+        }
+        self.save_settings_to_file().await
+    }
+
+    pub async fn load_anime_mapping_from_file(&self) -> Option<()> {
+        let mapping = match load_with_backup(self.anime_mapping_path.as_str(), deserialize_anime_mapping).await {
+            Ok(mapping) => mapping,
+            // Most folders aren't anime and won't have a mapping
+            Err(LoadError::IO(err)) if err.kind() == std::io::ErrorKind::NotFound => {
+                *self.anime_mapping.write().await = None;
+                return None;
+            },
+            Err(LoadError::IO(err)) => {
+                let message = format!("IO error while reading anime mapping: {}", err);
+                self.errors.write().await.push(message);
+                return None;
+            },
+            Err(LoadError::Decode(err)) => {
+                let message = format!("JSON decoding error reading anime mapping from file: {}", err);
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
+        self.warn_if_restored(&mapping, "anime mapping").await;
+
+        *self.anime_mapping.write().await = Some(mapping.value);
+        Some(())
+    }
+
+    pub async fn save_anime_mapping_to_file(&self) -> Option<()> {
+        self.check_library_lock().await?;
+        let mapping_data = {
+            let mapping = self.anime_mapping.read().await;
+            let mapping = match mapping.as_ref() {
+                Some(mapping) => mapping,
+                None => {
+                    let message = "Couldn't save anime mapping to file since it is unloaded".to_string();
+                    self.errors.write().await.push(message);
+                    return None;
+                },
+            };
+            match serialize_anime_mapping(mapping) {
+                Ok(data) => data,
+                Err(err) => {
+                    let message = format!("JSON encoding error writing anime mapping to file: {}", err);
+                    self.errors.write().await.push(message);
+                    return None;
+                },
+            }
+        };
+
+        if let Err(err) = write_atomic(self.anime_mapping_path.as_str(), mapping_data).await {
+            let message = format!("IO error while writing anime mapping to file: {}", err);
+            self.errors.write().await.push(message);
+            return None;
+        }
+        Some(())
+    }
+
+    // Downloads subtitles in the folder's preferred languages for renamed episodes which lack them
+    pub async fn fetch_missing_subtitles(&self, session: Arc<SubtitleSession>) -> Option<()> {
+        self.check_library_lock().await?;
+        self.check_local_folder("fetch subtitles").await?;
+        let _busy_lock = self.busy_lock.lock().await;
+
+        let languages = self.settings.read().await.subtitle_languages.clone();
+        if languages.is_empty() {
+            let message = "Couldn't fetch subtitles since no subtitle languages were selected for the folder".to_string();
+            self.errors.write().await.push(message);
+            return None;
+        }
+        let imdb_id = match self.cache.read().await.as_ref() {
+            Some(cache) => cache.series.imdb_id.as_deref().and_then(parse_imdb_id),
+            None => {
+                let message = "Couldn't fetch subtitles since it requires an existing loaded cache".to_string();
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
+
+        // only episodes that are already at their destination so the subtitle matches the final name
+        let episodes: Vec<(String, EpisodeKey)> = {
+            let files = self.get_files().await;
+            files.to_iter()
+                .filter(|file| file.get_action() == Action::Complete && is_video_path(file.get_src()))
+                .filter_map(|file| file.get_src_descriptor().map(|key| (file.get_src().to_string(), key)))
+                .collect()
+        };
+
+        let mut total_downloaded = 0;
+        'episodes: for (src, key) in episodes {
+            let video_path = path::Path::new(self.folder_path.as_str()).join(src.as_str());
+            let mut missing_languages = Vec::new();
+            for language in languages.iter() {
+                let subtitle_path = get_subtitle_path(video_path.as_path(), language.as_str());
+                if !tokio::fs::try_exists(subtitle_path).await.unwrap_or(true) {
+                    missing_languages.push(language.clone());
+                }
+            }
+            if missing_languages.is_empty() {
+                continue;
+            }
+
+            let moviehash = {
+                let video_path = video_path.clone();
+                tokio::task::spawn_blocking(move || compute_file_hash(video_path.as_path()).ok()).await.ok().flatten()
+            };
+            let mut subtitles = Vec::new();
+            let queries = [
+                SubtitleQuery { moviehash, languages: missing_languages.clone(), ..Default::default() },
+                SubtitleQuery {
+                    parent_imdb_id: imdb_id,
+                    season_number: Some(key.season),
+                    episode_number: Some(key.episode),
+                    languages: missing_languages.clone(),
+                    ..Default::default()
+                },
+            ];
+            for query in queries.iter() {
+                if query.moviehash.is_none() && query.parent_imdb_id.is_none() {
+                    continue;
+                }
+                let is_found = missing_languages.iter().all(|language| pick_best_subtitle(subtitles.as_slice(), language).is_some());
+                if is_found {
+                    break;
+                }
+                match get_metrics().time(OPENSUBTITLES_REQUEST, session.search_subtitles(query)).await {
+                    Ok(results) => subtitles.extend(results),
+                    Err(err) => {
+                        let is_rate_limited = matches!(err, opensubtitles::api::ApiError::RateLimited(_));
+                        let message = format!("Api error while searching subtitles for '{}': {}", src, err);
+                        self.errors.write().await.push(message);
+                        if is_rate_limited { break 'episodes; }
+                        continue 'episodes;
+                    },
+                }
+            }
+
+            for language in missing_languages.iter() {
+                let file = match pick_best_subtitle(subtitles.as_slice(), language.as_str()) {
+                    Some(file) => file,
+                    None => continue,
+                };
+                let subtitle = match session.get_download_link(file.file_id).await {
+                    Ok(link) => get_metrics().time(OPENSUBTITLES_REQUEST, session.download_subtitle(&link)).await,
+                    Err(err) => Err(err),
+                };
+                let subtitle = match subtitle {
+                    Ok(subtitle) => subtitle,
+                    Err(err) => {
+                        // download quotas are shared by every episode
+                        let is_rate_limited = matches!(err, opensubtitles::api::ApiError::RateLimited(_));
+                        let message = format!("Api error while downloading '{}' subtitle for '{}': {}", language, src, err);
+                        self.errors.write().await.push(message);
+                        if is_rate_limited { break 'episodes; }
+                        continue;
+                    },
+                };
+                let subtitle_path = get_subtitle_path(video_path.as_path(), language.as_str());
+                match tokio::fs::write(subtitle_path.as_path(), subtitle).await {
+                    Ok(()) => total_downloaded += 1,
+                    Err(err) => {
+                        let message = format!("IO error while writing subtitle '{}': {}", subtitle_path.to_string_lossy(), err);
+                        self.errors.write().await.push(message);
+                    },
+                }
+            }
+        }
+
+        self.push_activity(ActivityKind::Cache, format!("Downloaded {} subtitles from opensubtitles", total_downloaded)).await;
+        Some(())
+    }
+
+    pub async fn load_anime_mapping_from_anilist(&self, client: Arc<reqwest::Client>) -> Option<()> {
+        let _busy_lock = self.busy_lock.lock().await;
+
+        let series_name = {
+            let cache_guard = self.cache.read().await;
+            match cache_guard.as_ref() {
+                Some(cache) => cache.series.name.clone(),
+                None => {
+                    let message = "Couldn't load anime mapping since it requires an existing loaded cache".to_string();
+                    self.errors.write().await.push(message);
+                    return None;
+                },
+            }
+        };
+
+        let search_results = match get_metrics().time(ANILIST_REQUEST, anilist::api::search_anime(client.as_ref(), series_name.as_str())).await {
+            Ok(results) => results,
+            Err(err) => {
+                let message = format!("Api error while searching anilist: {}", err);
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
+
+        let media = search_results
+            .iter()
+            .find(|media| media.format.as_deref() == Some("TV"))
+            .or(search_results.first());
+        let media = match media {
+            Some(media) => media,
+            None => {
+                let message = format!("No anilist entries found for series '{}'", series_name);
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
 
-        let (series_str, episodes_str) = {
+        let seasons = match get_metrics().time(ANILIST_REQUEST, anilist::api::get_anime_seasons(client.as_ref(), media.id)).await {
+            Ok(seasons) => seasons,
+            Err(err) => {
+                let message = format!("Api error while fetching anilist seasons: {}", err);
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
+
+        let total_seasons = seasons.len();
+        *self.anime_mapping.write().await = Some(AnimeMapping::from_anilist_seasons(seasons.as_slice()));
+        self.push_activity(ActivityKind::Cache, format!("Loaded anime mapping with {} seasons from anilist", total_seasons)).await;
+        Some(())
+    }
+
+    // Automatic refreshes from the watcher or of all folders leave a locked plan alone
+    pub async fn refresh_file_intents(&self, cancel: Option<&CancellationToken>) -> Option<()> {
+        if *self.is_plan_locked.lock().await {
+            return None;
+        }
+        self.scan_file_intents(cancel).await
+    }
+
+    pub async fn update_file_intents(&self) -> Option<()> {
+        self.scan_file_intents(None).await
+    }
+
+    // A cancelled scan leaves the previous intents in place
+    async fn scan_file_intents(&self, cancel: Option<&CancellationToken>) -> Option<()> {
+        let _busy_lock = self.busy_lock.lock().await;
+        if is_cancelled(cancel) || self.is_excluded().await {
+            return None;
+        }
+        let _timer = get_metrics().start_timer(FOLDER_SCAN);
+        self.adopt_shared_cache().await;
+
+        let mut new_file_list = Vec::<AppFile>::new();
+        {
+            let cache_guard = self.cache.read().await;
+            let cache = match cache_guard.as_ref() {
+                Some(cache) => cache,
+                None => {
+                    let message = "Couldn't update file intents since cache is unloaded";
+                    self.errors.write().await.push(message.to_string()); 
+                    return None;
+                },
+            };
+            let anime_mapping = self.anime_mapping.read().await;
+            let settings = self.settings.read().await;
+            let mut scan_index = self.scan_index.lock().await;
+            let rules = self.filter_rules.read().await.clone();
+            let mut scan_entries = scan_index.begin_scan(ScanContext::new(cache, &settings, &rules, anime_mapping.as_ref()));
+            let sources = IntentSources {
+                cache,
+                anime_mapping: anime_mapping.as_ref(),
+                settings: &settings,
+                rules: &rules,
+            };
+            let res = match self.remote.as_ref() {
+                Some(remote) => remote.list_files(self.folder_name.as_str()).await.map(|files| {
+                    for file in files.into_iter().take_while(|_| !is_cancelled(cancel)) {
+                        let stamp = FileStamp { size: file.size, modified: file.modified };
+                        push_file_intent(file.rel_path.as_str(), stamp, &sources, &mut new_file_list, &mut scan_entries);
+                    }
+                }),
+                None => recursive_search_file_intents(
+                    self.folder_path.as_str(), self.folder_path.as_str(), 
+                    &sources, &mut new_file_list, &mut scan_entries, cancel,
+                ).await.map_err(|err| format!("IO error while reading files for intent update: {}", err)),
+            };
+            if is_cancelled(cancel) {
+                scan_index.abort_scan(scan_entries);
+                return None;
+            }
+            match res.is_ok() {
+                true => scan_index.finish_scan(scan_entries),
+                false => scan_index.clear(),
+            }
+            drop(scan_index);
+            if let Err(message) = res {
+                self.errors.write().await.push(message);
+                return None;
+            }
+
+            for file in new_file_list.iter_mut() {
+                if let Some(dest) = settings.dest_overrides.get(&file.src) {
+                    file.action = Action::Rename;
+                    file.dest = dest.clone();
+                }
+            }
+            if settings.duplicate_policy.unwrap_or(rules.duplicate_policy) == DuplicatePolicy::KeepLargest {
+                mark_duplicate_losers(new_file_list.as_mut_slice(), rules.get_delete_policy(Some(DeleteReason::DuplicateLoser)));
+            }
+            group_companion_files(new_file_list.as_mut_slice(), rules.companion_suffixes.as_slice(), &settings.dest_overrides);
+
+            // only files which are already correctly named are archived
+            if let Some(archive_root) = rules.archive_path.as_deref() {
+                let watch_state = self.watch_state.read().await;
+                let is_season_unwatched = |descriptor: Option<&EpisodeKey>| match (rules.keep_unwatched_seasons, watch_state.as_ref(), descriptor) {
+                    (true, Some(watch_state), Some(key)) => watch_state.is_season_unwatched(key.season),
+                    _ => false,
+                };
+                for file in new_file_list.iter_mut() {
+                    let descriptor = file.src_descriptor.as_ref();
+                    if file.action == Action::Complete && is_archive_flagged(&settings, descriptor) && !is_season_unwatched(descriptor) {
+                        file.action = Action::Archive;
+                        file.dest = get_archive_path(archive_root, self.folder_name.as_str(), file.src.as_str()).to_string_lossy().to_string();
+                    }
+                }
+            }
+
+            let mut present_episodes: HashSet<EpisodeKey> = new_file_list
+                .iter()
+                .filter(|file| is_episode_file(file.action, file.src.as_str()))
+                .filter_map(|file| file.src_descriptor)
+                .collect();
+            present_episodes.extend(self.get_archived_episodes().await);
+            let today = chrono::Local::now().date_naive();
+            let (total_aired_present, total_aired) = get_aired_episode_counts(cache, &present_episodes, &settings, today);
+            *self.series_progress.write().await = SeriesProgress {
+                status: cache.get_series_status(),
+                total_missing_recent: get_total_missing_recent_episodes(cache, &present_episodes, &settings, today),
+                total_aired_present,
+                total_aired,
+            };
+        }
+
+        sort_natural_by(new_file_list.as_mut_slice(), |file| file.src.as_str());
+
+        // overrides of files which were renamed, moved or deleted since would never apply again
+        let is_overrides_pruned = {
+            let mut settings = self.settings.write().await;
+            let total_overrides = settings.dest_overrides.len();
+            if total_overrides > 0 {
+                let sources: HashSet<&str> = new_file_list.iter().map(|file| file.src.as_str()).collect();
+                settings.dest_overrides.retain(|src, _| sources.contains(src.as_str()));
+            }
+            settings.dest_overrides.len() != total_overrides
+        };
+        if is_overrides_pruned {
+            self.save_settings_to_file().await;
+        }
+
+        // compare against the previously computed intents to find what changed since the last refresh
+        let saved_snapshot = match self.plan_snapshot.read().await.is_none() {
+            true => self.load_plan_snapshot_from_file().await,
+            false => None,
+        };
+        let (total_changes, is_snapshot_changed) = {
+            let mut plan_snapshot = self.plan_snapshot.write().await;
+            let mut plan_changes = self.plan_changes.write().await;
+            let is_refresh = plan_snapshot.is_some();
+            let new_snapshot = PlanSnapshot::new(new_file_list.as_slice());
+            let old_snapshot = plan_snapshot.as_ref().or(saved_snapshot.as_ref());
+            *plan_changes = match old_snapshot {
+                Some(snapshot) => snapshot.get_changes(new_file_list.as_slice()),
+                None => Vec::new(),
+            };
+            let is_snapshot_changed = old_snapshot != Some(&new_snapshot);
+            *plan_snapshot = Some(new_snapshot);
+            (is_refresh.then_some(plan_changes.len()), is_snapshot_changed)
+        };
+        if is_snapshot_changed {
+            self.save_plan_snapshot_to_file().await;
+        }
+        // avoid flooding the activity log with the initial load of every folder
+        if let Some(total_changes) = total_changes {
+            let total_files = new_file_list.len();
+            self.push_activity(ActivityKind::Refresh, format!("Refreshed {} files with {} changes", total_files, total_changes)).await;
+        }
+        
+        {
+            let mut file_list = self.file_list.write().await;
+            let mut file_tracker = self.file_tracker.write().await;
+
+            *file_list = new_file_list;
+            file_tracker.clear();
+
+            // seed conflict table
+            for (index, file) in file_list.iter().enumerate() {
+                file_tracker.insert_existing_source(file.src.as_str(), index);
+                let action_count = file_tracker.get_action_count_mut();
+                action_count[file.action] += 1usize;
+                let action_size = file_tracker.get_action_size_mut();
+                action_size[file.action] += file.size;
+            }
+        }
+
+        {
+            // automatically enable renames unless they need to be reviewed
+            // archives were already asked for by flagging the folder or season
+            let mut files = self.get_mut_files().await;
+            let mut files_iter = files.to_iter();
+            while let Some(mut file) = files_iter.next_mut() {
+                let is_rename = file.get_action().is_move() && file.get_warnings().iter().all(|warning| !warning.is_review_required());
+                if is_rename || file.get_action() == Action::Archive {
+                    file.set_is_enabled(true);
+                }
+            }
+        }
+        
+        self.flush_file_changes().await;
+        *self.is_file_count_init.lock().await = true;
+        self.update_summary().await;
+        Some(())
+    }
+
+    // Picks up the cache another folder bound to the same series refreshed
+    async fn adopt_shared_cache(&self) {
+        let mut cache = self.cache.write().await;
+        let key = match cache.as_ref() {
+            Some(cache) => SeriesCacheKey::new(cache),
+            None => return,
+        };
+        if let Some(shared_cache) = self.cache_store.get(&key) {
+            *cache = Some(shared_cache);
+        }
+    }
+
+    pub async fn is_cache_shared_outdated(&self) -> bool {
+        let cache = self.cache.read().await;
+        let cache = match cache.as_ref() {
+            Some(cache) => cache,
+            None => return false,
+        };
+        match self.cache_store.get(&SeriesCacheKey::new(cache)) {
+            Some(shared_cache) => !Arc::ptr_eq(cache, &shared_cache),
+            None => false,
+        }
+    }
+
+    // Returns the files which exist but can't be parsed or restored without loading them into the folder
+    pub async fn check_data_files(&self) -> Vec<String> {
+        let checks: [(&str, DataFileCheck); 8] = [
+            (self.series_path.as_str(), |data| serde_json::from_str::<Series>(data).map(|_| ())),
+            (self.episodes_path.as_str(), |data| serde_json::from_str::<Vec<Episode>>(data).map(|_| ())),
+            (self.settings_path.as_str(), |data| deserialize_folder_settings(data).map(|_| ())),
+            (self.anime_mapping_path.as_str(), |data| deserialize_anime_mapping(data).map(|_| ())),
+            (self.bookmarks_path.as_str(), |data| deserialize_bookmarks(data).map(|_| ())),
+            (self.archive_path.as_str(), |data| deserialize_archived_files(data).map(|_| ())),
+            (self.execution_skips_path.as_str(), |data| deserialize_execution_skips(data).map(|_| ())),
+            (self.plan_snapshot_path.as_str(), |data| deserialize_plan_snapshot(data).map(|_| ())),
+        ];
+        let mut problems = Vec::new();
+        for (path, parse) in checks {
+            // files with an intact backup are restored when they are loaded
+            match load_with_backup(path, parse).await {
+                Ok(_) => {},
+                Err(LoadError::IO(err)) if err.kind() == std::io::ErrorKind::NotFound => {},
+                Err(LoadError::IO(err)) => problems.push(format!("'{}' couldn't be read: {}", path, err)),
+                Err(LoadError::Decode(err)) => problems.push(format!("'{}' is corrupted: {}", path, err)),
+            }
+        }
+        problems
+    }
+
+    pub async fn load_cache_from_file(&self) -> Option<()> {
+        let _busy_lock = self.busy_lock.lock().await;
+
+        let (series_res, episodes_res) = tokio::join!(
+            load_with_backup(self.series_path.as_str(), |data: &str| serde_json::from_str::<Series>(data)),
+            load_with_backup(self.episodes_path.as_str(), |data: &str| serde_json::from_str::<Vec<Episode>>(data)),
+        );
+        get_metrics().record_hit(EPISODE_CACHE, series_res.is_ok() && episodes_res.is_ok());
+
+        if let Err(err) = series_res.as_ref() {
+            let message = match err {
+                LoadError::IO(err) => format!("IO error while reading series cache: {}", err),
+                LoadError::Decode(err) => format!("JSON decoding error reading series from file: {}", err),
+            };
+            self.errors.write().await.push(message);
+        }
+
+        if let Err(err) = episodes_res.as_ref() {
+            let message = match err {
+                LoadError::IO(err) => format!("IO error while reading episodes cache: {}", err),
+                LoadError::Decode(err) => format!("JSON decoding error reading episodes from file: {}", err),
+            };
+            self.errors.write().await.push(message);
+        }
+
+        let series = series_res.ok()?;
+        let episodes = episodes_res.ok()?;
+        self.warn_if_restored(&series, "series cache").await;
+        self.warn_if_restored(&episodes, "episodes cache").await;
+
+        let translations = match load_with_backup(self.translations_path.as_str(), |data: &str| serde_json::from_str::<EpisodeTranslations>(data)).await {
+            Ok(translations) => {
+                self.warn_if_restored(&translations, "translations cache").await;
+                translations.value
+            },
+            // Translations are only stored if a folder language has been selected
+            Err(LoadError::IO(err)) if err.kind() == std::io::ErrorKind::NotFound => EpisodeTranslations::new(),
+            Err(LoadError::IO(err)) => {
+                let message = format!("IO error while reading translations cache: {}", err);
+                self.errors.write().await.push(message);
+                EpisodeTranslations::new()
+            },
+            Err(LoadError::Decode(err)) => {
+                let message = format!("JSON decoding error reading translations from file: {}", err);
+                self.errors.write().await.push(message);
+                EpisodeTranslations::new()
+            },
+        };
+        let (series, episodes) = (series.value, episodes.value);
+
+        let mut cache = TvdbCache::new(series, episodes);
+        cache.translations = translations;
+        *self.cache.write().await = Some(self.cache_store.share(cache));
+        Some(())
+    }
+
+    // Series which aren't on the media server have no watch state
+    pub async fn load_watch_state(&self, session: &MediaServerSession, library_index: &HashMap<u32, String>) -> Option<()> {
+        let cache = self.cache.read().await.clone()?;
+        let key = match library_index.get(&cache.series.id) {
+            Some(key) => key,
+            None => {
+                *self.watch_state.write().await = None;
+                return Some(());
+            },
+        };
+        let states = match get_metrics().time(MEDIA_SERVER_REQUEST, session.get_episode_watch_states(key.as_str())).await {
+            Ok(states) => states,
+            Err(err) => {
+                let message = format!("Couldn't read watch state from {}: {}", session.get_kind().to_str(), err);
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
+        let ordering = self.settings.read().await.get_ordering();
+        *self.watch_state.write().await = Some(WatchState::from_cache(states.as_slice(), &cache, ordering));
+        Some(())
+    }
+
+    pub async fn load_cache_from_api(&self, session: Arc<LoginSession>, series_id: u32) -> Option<()> {
+        let _busy_lock = self.busy_lock.lock().await;
+
+        let (series_res, episodes_res, remote_ids_res) = tokio::join!(
+            get_metrics().time(TVDB_REQUEST, session.get_series(series_id)),
+            get_metrics().time(TVDB_REQUEST, session.get_episodes(series_id)),
+            get_metrics().time(TVDB_REQUEST, session.get_series_remote_ids(series_id)),
+        );
+
+        let mut series = match series_res {
+            Ok(series) => series,
+            Err(err) => {
+                let message = format!("Api error while fetching series: {}", err);
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
+
+        let episodes = match episodes_res {
+            Ok(episodes) => episodes,
+            Err(err) => {
+                let message = format!("Api error while fetching episodes: {}", err);
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
+
+        match remote_ids_res {
+            Ok(remote_ids) => apply_remote_ids(&mut series, remote_ids.as_slice()),
+            // mirrors and proxies don't always have the endpoint, the series is still usable without the ids
+            Err(ApiError::UnexpectedResponse(reqwest::StatusCode::NOT_FOUND, _)) => {},
+            Err(err) => {
+                let message = format!("Api error while fetching remote ids of series: {}", err);
+                self.errors.write().await.push(message);
+            },
+        }
+
+        let mut cache = TvdbCache::new(series, episodes);
+        let language = self.settings.read().await.language.clone();
+        if let Some(language) = language {
+            match get_metrics().time(TVDB_REQUEST, session.get_episodes_in_language(series_id, language.as_str())).await {
+                Ok(episodes) => {
+                    cache.translations.insert(language, get_episode_names(episodes.as_slice()));
+                },
+                Err(err) => {
+                    let message = format!("Api error while fetching episode translations for '{}': {}", language, err);
+                    self.errors.write().await.push(message);
+                },
+            }
+        }
+        let message = format!("Loaded series '{}' ({}) with {} episodes from api", cache.series.name, series_id, cache.episodes.len());
+        *self.cache.write().await = Some(self.cache_store.publish(cache));
+        self.push_activity(ActivityKind::Cache, message).await;
+        Some(())
+    }
+
+    pub async fn load_translation_from_api(&self, session: Arc<LoginSession>, language: &str) -> Option<()> {
+        let _busy_lock = self.busy_lock.lock().await;
+
+        let series_id = match self.cache.read().await.as_ref() {
+            Some(cache) => cache.series.id,
+            None => {
+                let message = "Couldn't fetch translations since it requires an existing loaded cache".to_string();
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
+
+        let episodes = match get_metrics().time(TVDB_REQUEST, session.get_episodes_in_language(series_id, language)).await {
+            Ok(episodes) => episodes,
+            Err(err) => {
+                let message = format!("Api error while fetching episode translations for '{}': {}", language, err);
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
+
+        {
+            let mut cache = self.cache.write().await;
+            let mut new_cache = TvdbCache::clone(cache.as_ref()?);
+            new_cache.translations.insert(language.to_string(), get_episode_names(episodes.as_slice()));
+            *cache = Some(self.cache_store.publish(new_cache));
+        }
+        self.push_activity(ActivityKind::Cache, format!("Loaded episode translations for '{}' from api", language)).await;
+        Some(())
+    }
+
+    pub async fn refresh_cache_from_api(&self, session: Arc<LoginSession>) -> Option<()> {
+        let series_id = {
+            let cache_guard = self.cache.read().await;
+            match cache_guard.as_ref() {
+                Some(cache) => cache.series.id,
+                None => {
+                    let message = "Couldn't refresh cache since it requires an existing loaded cache".to_string();
+                    self.errors.write().await.push(message);
+                    return None;
+                },
+            }
+        };
+        self.load_cache_from_api(session, series_id).await
+    }
+
+    pub async fn save_cache_to_file(&self) -> Option<()> {
+        self.check_library_lock().await?;
+        let _busy_lock = self.busy_lock.lock().await;
+
+        let (series_str, episodes_str, translations_str) = {
             let cache_guard = self.cache.read().await;
             let cache = match cache_guard.as_ref() {
                 Some(cache) => cache,
@@ -463,12 +1660,36 @@ impl AppFolder {
                     return None;
                 },
             };
-            (series_str, episodes_str)
+            let translations_str = match serde_json::to_string_pretty(&cache.translations) {
+                Ok(data) => data,
+                Err(err) => {
+                    let message = format!("JSON encode error when saving translations cache: {}", err);
+                    self.errors.write().await.push(message);
+                    return None;
+                },
+            };
+            (series_str, episodes_str, translations_str)
         };
 
-        let (res_0, res_1) = tokio::join!(
-            tokio::fs::write(self.series_path.as_str(), series_str),
-            tokio::fs::write(self.episodes_path.as_str(), episodes_str),
+        let is_compressed = self.filter_rules.read().await.compress_caches;
+        let encoded = (
+            encode_data(series_str, is_compressed),
+            encode_data(episodes_str, is_compressed),
+            encode_data(translations_str, is_compressed),
+        );
+        let (series_data, episodes_data, translations_data) = match encoded {
+            (Ok(series), Ok(episodes), Ok(translations)) => (series, episodes, translations),
+            (Err(err), _, _) | (_, Err(err), _) | (_, _, Err(err)) => {
+                let message = format!("Compression error when saving cache: {}", err);
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
+
+        let (res_0, res_1, res_2) = tokio::join!(
+            write_atomic(self.series_path.as_str(), series_data),
+            write_atomic(self.episodes_path.as_str(), episodes_data),
+            write_atomic(self.translations_path.as_str(), translations_data),
         );
 
         if let Err(err) = res_0.as_ref() {
@@ -480,67 +1701,451 @@ impl AppFolder {
             let message = format!("IO error while saving episodes cache: {}", err);
             self.errors.write().await.push(message);
         }
+
+        if let Err(err) = res_2.as_ref() {
+            let message = format!("IO error while saving translations cache: {}", err);
+            self.errors.write().await.push(message);
+        }
         
-        if res_0.is_err() || res_1.is_err() {
+        if res_0.is_err() || res_1.is_err() || res_2.is_err() {
             return None;
         }
         Some(())
     }
 
+    pub async fn get_execution_summary(&self) -> ExecutionSummary {
+        self.get_filtered_execution_summary(ExecutionFilter::default()).await
+    }
+
+    // Simulates what executing with the filter would apply
+    pub async fn get_filtered_execution_summary(&self, filter: ExecutionFilter) -> ExecutionSummary {
+        self.get_scope_execution_summary(ExecutionScope::Enabled(filter)).await
+    }
+
+    pub async fn get_file_execution_summary(&self, src: &str) -> ExecutionSummary {
+        self.get_scope_execution_summary(ExecutionScope::File(src)).await
+    }
+
+    async fn get_scope_execution_summary(&self, scope: ExecutionScope<'_>) -> ExecutionSummary {
+        let rules = self.filter_rules.read().await.clone();
+        let has_archive_path = rules.archive_path.is_some();
+        let max_failures = rules.get_max_execution_failures();
+        let mut summary = ExecutionSummary::default();
+        let skips = self.execution_skips.read().await;
+        let files = self.get_files().await;
+        for file in files.to_iter().filter(|file| scope.is_included(file)) {
+            if scope.is_skipped(&file, &skips, max_failures) {
+                summary.total_skipped_failures += 1;
+                continue;
+            }
+            let delete_policy = rules.get_delete_policy(file.get_delete_reason());
+            summary.add_file(file.get_action(), delete_policy, scope.is_conflict(&file), has_archive_path, file.get_size());
+        }
+        summary.total_folders = 1;
+        summary
+    }
+
     pub async fn execute_file_changes(&self) {
-        let _busy_lock = self.busy_lock.lock().await;
+        self.execute_filtered_file_changes(ExecutionFilter::default()).await
+    }
+
+    // Only applies the enabled changes of the kinds in the filter, the others stay pending
+    pub async fn execute_filtered_file_changes(&self, filter: ExecutionFilter) {
+        self.execute_scope_changes(ExecutionScope::Enabled(filter)).await
+    }
 
-        use std::pin::Pin;
-        use std::future::Future;
-        type F = Pin<Box<dyn Future<Output = Result<(), std::io::Error>> + Send>>;
+    // Applies the intent of one file right away, leaving the rest of the plan as is
+    pub async fn execute_file_change(&self, src: &str) {
+        self.execute_scope_changes(ExecutionScope::File(src)).await
+    }
 
-        let mut tasks = Vec::<F>::new();
+    async fn execute_scope_changes(&self, scope: ExecutionScope<'_>) {
+        if self.check_library_lock().await.is_none() {
+            return;
+        }
+        let _busy_lock = self.busy_lock.lock().await;
+        // the reviewed plan is used up by executing all of it
+        if let ExecutionScope::Enabled(filter) = scope {
+            if filter.is_everything() {
+                *self.is_plan_locked.lock().await = false;
+            }
+        }
+
+        let rules = self.filter_rules.read().await.clone();
+        let max_failures = rules.get_max_execution_failures();
+        let mut operations = Vec::new();
+        // the file in the folder each operation is for, failures are tracked by it
+        let mut operation_sources = Vec::new();
+        let mut moves = Vec::new();
+        let mut tag_targets = Vec::new();
+        let mut collected = Vec::new();
+        // archived files are recorded once their operation went through
+        let mut archives: HashMap<usize, ArchivedFile> = HashMap::new();
+        let mut total_unarchived = 0;
+        let mut total_renames = 0;
+        let mut total_deletes = 0;
+        let mut total_skipped = 0;
+        let mut outcomes = ExecutionOutcomes::default();
+        // renamed files are shown under their new name once the folder is rescanned
+        let mut final_paths = HashMap::new();
+        let trash_entry_name = get_trash_entry_name(&chrono::Utc::now());
+        // journal paths are relative to the root folder
+        let get_root_relative = |src: &str| path::Path::new(self.folder_name.as_str()).join(src).to_string_lossy().to_string();
         {
+            let skips = self.execution_skips.read().await;
             let files = self.get_files().await;
             for file in files.to_iter() {
-                if !file.get_is_enabled() {
+                if !scope.is_included(&file) {
+                    continue;
+                }
+                if scope.is_skipped(&file, &skips, max_failures) {
+                    outcomes.insert(file.get_src(), ExecutionOutcome::Skipped(SkipReason::RepeatedFailures));
+                    total_skipped += 1;
                     continue;
                 }
 
                 if file.get_action() == Action::Delete {
-                    let src = path::Path::new(&self.folder_path).join(file.get_src());
-                    tasks.push(Box::pin({
-                        async move {
-                            tokio::fs::remove_file(src).await
-                        }
-                    }));
+                    let src = get_root_relative(file.get_src());
+                    match rules.get_delete_policy(file.get_delete_reason()) {
+                        DeletePolicy::Trash => {
+                            let trash_path = get_trash_path(
+                                "", trash_entry_name.as_str(), 
+                                self.folder_name.as_str(), file.get_src(),
+                            );
+                            operations.push(JournalOperation::new_move(src.as_str(), trash_path.to_string_lossy().as_ref()));
+                        },
+                        // Files that are manually marked for deletion use the permanent policy
+                        DeletePolicy::Permanent | DeletePolicy::Ignore => {
+                            operations.push(JournalOperation::new_delete(src.as_str()));
+                        },
+                    };
+                    operation_sources.push(Some(file.get_src().to_string()));
+                    total_deletes += 1;
+                    continue;
+                }
+
+                if file.get_action() == Action::Archive {
+                    let archive_root = match rules.archive_path.as_deref() {
+                        Some(archive_root) => archive_root,
+                        None => {
+                            outcomes.insert(file.get_src(), ExecutionOutcome::Skipped(SkipReason::NoArchivePath));
+                            total_unarchived += 1;
+                            continue;
+                        },
+                    };
+                    let archive_path = get_archive_path(archive_root, self.folder_name.as_str(), file.get_src()).to_string_lossy().to_string();
+                    archives.insert(operations.len(), ArchivedFile {
+                        src: file.get_src().to_string(),
+                        archive_path: archive_path.clone(),
+                        descriptor: *file.get_src_descriptor(),
+                        size: file.get_size(),
+                        archived: chrono::Utc::now(),
+                    });
+                    // the archive root is absolute so the journal leaves it as is
+                    operations.push(JournalOperation::new_move(get_root_relative(file.get_src()).as_str(), archive_path.as_str()));
+                    operation_sources.push(Some(file.get_src().to_string()));
+                    continue;
+                }
+
+                if file.get_action().is_move() && scope.is_conflict(&file) {
+                    outcomes.insert(file.get_src(), ExecutionOutcome::Skipped(SkipReason::DestinationTaken));
                     continue;
                 }
 
-                if file.get_action() == Action::Rename && !file.get_is_conflict() {
-                    tasks.push(Box::pin({
-                        let src = path::Path::new(&self.folder_path).join(file.get_src());
-                        let dest = path::Path::new(&self.folder_path).join(file.get_dest());
-                        async move {
-                            let parent_dir = dest.parent().expect("Invalid filepath");
-                            tokio::fs::create_dir_all(parent_dir).await?;
-                            tokio::fs::rename(src, dest).await
+                if file.get_action().is_move() {
+                    moves.push(MoveStep::new(file.get_src(), file.get_dest()));
+                    final_paths.insert(file.get_src().to_string(), file.get_dest().to_string());
+                    if let Some(key) = file.get_src_descriptor() {
+                        if rules.write_metadata_tags && is_taggable_path(path::Path::new(file.get_dest())) {
+                            tag_targets.push((file.get_dest().to_string(), *key));
                         }
-                    }));
+                        if is_video_path(file.get_dest()) {
+                            collected.push((file.get_dest().to_string(), *key));
+                        }
+                    }
+                    total_renames += 1;
                     continue;
                 }
             }
         }
-        
-        {
-            let mut errors = self.errors.write().await;
-            for res in futures::future::join_all(tasks).await.into_iter() {
-                if let Err(err) = res {
-                    let message = format!("IO error while executing file changes: {}", err);
-                    errors.push(message);
-                };
+
+        // moves run one at a time since chains and swaps depend on the previous move
+        let move_sources: HashSet<&str> = moves.iter().map(|step| step.src.as_str()).collect();
+        for step in plan_moves(moves.as_slice()) {
+            operations.push(JournalOperation::new_move(
+                get_root_relative(step.src.as_str()).as_str(),
+                get_root_relative(step.dest.as_str()).as_str(),
+            ));
+            // moves out of a temporary name belong to a file whose first move is already tracked
+            operation_sources.push(Some(step.src.clone()).filter(|src| move_sources.contains(src.as_str())));
+        }
+
+        if total_skipped > 0 {
+            let message = format!("Skipped {} files whose changes failed {} times in a row, retry them once they are resolved", total_skipped, max_failures);
+            self.errors.write().await.push(message);
+        }
+
+        if total_unarchived > 0 {
+            let message = format!("Couldn't archive {} files since no archive path is set in the app config", total_unarchived);
+            self.errors.write().await.push(message);
+        }
+
+        if let Some(remote) = self.remote.clone() {
+            let failures = self.execute_remote_operations(remote.as_ref(), operations.as_slice(), total_renames, total_deletes).await;
+            match failures {
+                Some(failures) => {
+                    for (index, _) in failures.iter() {
+                        archives.remove(index);
+                    }
+                    outcomes.add_operation_results(operation_sources.as_slice(), failures.as_slice(), &final_paths);
+                    self.update_execution_skips(operation_sources.as_slice(), failures.as_slice(), max_failures).await;
+                },
+                None => archives.clear(),
             }
+            *self.execution_outcomes.write().await = outcomes;
+            self.record_archived_files(archives.into_values().collect()).await;
+            return;
         }
 
+        let total_operations = operations.len();
+        let mut journal = match ExecutionJournal::create(self.root_path.as_str(), self.folder_name.as_str(), operations, rules.compress_caches).await {
+            Ok(journal) => journal,
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+                let message = "An interrupted execution of this folder must be resumed or rolled back first".to_string();
+                self.errors.write().await.push(message);
+                return;
+            },
+            Err(err) => {
+                let message = format!("IO error while writing execution journal: {}", err);
+                self.errors.write().await.push(message);
+                return;
+            },
+        };
+
+        let mut failures = Vec::new();
+        let mut permission_issues = Vec::new();
+        for index in 0..total_operations {
+            if let Err(err) = journal.execute_operation(index).await {
+                let operation = &journal.get_operations()[index];
+                archives.remove(&index);
+                failures.push((index, err.to_string()));
+                // Permission errors are grouped into a report with their likely cause instead
+                if err.kind() == std::io::ErrorKind::PermissionDenied {
+                    let src = journal.get_path(operation.src.as_str());
+                    let dest = operation.dest.as_deref().map(|dest| journal.get_path(dest));
+                    let issue = diagnose_permission_error(operation.src.as_str(), src.as_path(), dest.as_deref()).await;
+                    permission_issues.push(issue);
+                    continue;
+                }
+                let message = format!("IO error while executing file changes on '{}': {}", operation.src, err);
+                self.errors.write().await.push(message);
+                continue;
+            }
+            if let Err(err) = journal.mark_done(index).await {
+                let message = format!("IO error while updating execution journal: {}", err);
+                self.errors.write().await.push(message);
+            }
+        }
+        if let Err(err) = journal.remove().await {
+            let message = format!("IO error while removing execution journal: {}", err);
+            self.errors.write().await.push(message);
+        }
+        if !permission_issues.is_empty() {
+            let message = format!("{} file changes were denied due to permissions, see the permissions report", permission_issues.len());
+            self.errors.write().await.push(message);
+        }
+        *self.permission_issues.write().await = permission_issues;
+        let total_errors = failures.len();
+        self.push_activity(ActivityKind::Execute, format!(
+            "Executed {} renames and {} deletes with {} errors", 
+            total_renames, total_deletes, total_errors,
+        )).await;
+        record_execution_metrics(total_renames, total_deletes, total_errors);
+        outcomes.add_operation_results(operation_sources.as_slice(), failures.as_slice(), &final_paths);
+        *self.execution_outcomes.write().await = outcomes;
+        self.update_execution_skips(operation_sources.as_slice(), failures.as_slice(), max_failures).await;
+        self.record_archived_files(archives.into_values().collect()).await;
+
+        self.write_metadata_tags(tag_targets.as_slice()).await;
+        self.sync_trakt_collection(collected.as_slice()).await;
         // Automatically delete empty folders
         self.delete_empty_folders().await;
     }
 
+    // Runs server side in one ssh session, metadata tags and trakt sync need the files to be local so they are skipped
+    // Returns the operations which didn't go through with their errors, or none if the session itself failed
+    async fn execute_remote_operations(&self, remote: &RemoteHost, operations: &[JournalOperation], total_renames: usize, total_deletes: usize) -> Option<Vec<(usize, String)>> {
+        let failures = match remote.execute_operations(operations).await {
+            Ok(failures) => failures,
+            Err(message) => {
+                self.errors.write().await.push(message);
+                return None;
+            },
+        };
+        for (index, error) in failures.iter() {
+            let src = operations.get(*index).map(|operation| operation.src.as_str()).unwrap_or_default();
+            let message = format!("Remote error while executing file changes on '{}': {}", src, error);
+            self.errors.write().await.push(message);
+        }
+        self.push_activity(ActivityKind::Execute, format!(
+            "Executed {} renames and {} deletes on '{}' with {} errors",
+            total_renames, total_deletes, remote.get_destination(), failures.len(),
+        )).await;
+        record_execution_metrics(total_renames, total_deletes, failures.len());
+        if let Err(message) = remote.delete_empty_folders(self.folder_name.as_str()).await {
+            self.errors.write().await.push(message);
+        }
+        Some(failures)
+    }
+
+    // Failures only count while they happen in a row, so a change that goes through clears them
+    async fn update_execution_skips(&self, operation_sources: &[Option<String>], failures: &[(usize, String)], max_failures: u32) {
+        let now = chrono::Utc::now();
+        let mut is_changed = false;
+        let mut newly_skipped = Vec::new();
+        {
+            let mut skips = self.execution_skips.write().await;
+            for (index, src) in operation_sources.iter().enumerate() {
+                let src = match src {
+                    Some(src) => src.as_str(),
+                    None => continue,
+                };
+                match failures.iter().find(|(failed_index, _)| *failed_index == index) {
+                    Some((_, error)) => {
+                        skips.record_failure(src, error.as_str(), now);
+                        is_changed = true;
+                        if max_failures > 0 && skips.get_failures(src) == max_failures {
+                            newly_skipped.push(src.to_string());
+                        }
+                    },
+                    None => is_changed |= skips.remove(src),
+                }
+            }
+        }
+        for src in newly_skipped {
+            let message = format!("'{}' is skipped in future executions after failing {} times in a row", src, max_failures);
+            self.errors.write().await.push(message);
+        }
+        if is_changed {
+            self.save_execution_skips_to_file().await;
+        }
+    }
+
+    async fn record_archived_files(&self, mut archived: Vec<ArchivedFile>) {
+        if archived.is_empty() {
+            return;
+        }
+        sort_natural_by(archived.as_mut_slice(), |file| file.src.as_str());
+        let total_archived = archived.len();
+        {
+            let mut archived_files = self.archived_files.write().await;
+            // a file archived again replaces its old stub
+            archived_files.retain(|file| archived.iter().all(|other| other.archive_path != file.archive_path));
+            archived_files.extend(archived);
+        }
+        self.save_archived_files_to_file().await;
+        let archive_root = self.filter_rules.read().await.archive_path.clone().unwrap_or_default();
+        self.push_activity(ActivityKind::Execute, format!("Archived {} files to '{}'", total_archived, archive_root)).await;
+    }
+
+    async fn sync_trakt_collection(&self, renames: &[(String, EpisodeKey)]) {
+        if renames.is_empty() || self.trakt_sync.get_session().read().await.is_none() {
+            return;
+        }
+        let cache = match self.cache.read().await.clone() {
+            Some(cache) => cache,
+            None => return,
+        };
+        let ordering = self.settings.read().await.get_ordering();
+        // only episodes whose rename went through are in the library
+        // trakt only knows the aired order
+        let mut keys = Vec::new();
+        for (dest, key) in renames {
+            let dest = path::Path::new(self.folder_path.as_str()).join(dest);
+            if !tokio::fs::try_exists(dest).await.unwrap_or(false) {
+                continue;
+            }
+            let aired_key = match ordering {
+                EpisodeOrdering::Aired => Some(*key),
+                _ => cache.get_episode_index(key, ordering).and_then(|index| cache.get_episode_key(index, EpisodeOrdering::Aired)),
+            };
+            if let Some(aired_key) = aired_key.filter(|aired_key| !keys.contains(aired_key)) {
+                keys.push(aired_key);
+            }
+        }
+        let series_id = cache.series.id;
+        match self.trakt_sync.add_to_collection(series_id, keys.as_slice()).await {
+            Ok(total_added) => self.push_activity(ActivityKind::Execute, format!(
+                "Added {} episodes to trakt collection", total_added,
+            )).await,
+            Err(message) => self.errors.write().await.push(message),
+        }
+    }
+
+    async fn write_metadata_tags(&self, targets: &[(String, EpisodeKey)]) {
+        if targets.is_empty() {
+            return;
+        }
+        let cache = match self.cache.read().await.clone() {
+            Some(cache) => cache,
+            None => return,
+        };
+        let (ordering, language) = {
+            let settings = self.settings.read().await;
+            (settings.get_ordering(), settings.language.clone())
+        };
+        let mut total_tagged = 0;
+        for (dest, key) in targets {
+            let path = path::Path::new(self.folder_path.as_str()).join(dest);
+            // Moves which failed were already reported
+            if !tokio::fs::try_exists(path.as_path()).await.unwrap_or(false) {
+                continue;
+            }
+            let tags = EpisodeTags::new(&cache, *key, ordering, language.as_deref());
+            match write_episode_tags(self.base_rules.read().await.get_ffmpeg_path(), path.as_path(), &tags).await {
+                Ok(()) => total_tagged += 1,
+                Err(err) => {
+                    let message = format!("Couldn't write metadata tags into '{}': {}", dest, err);
+                    self.errors.write().await.push(message);
+                },
+            }
+        }
+        if total_tagged > 0 {
+            self.push_activity(ActivityKind::Execute, format!("Wrote metadata tags into {} files", total_tagged)).await;
+        }
+    }
+
+    // Clears the read-only attribute on everything blocking the reported changes so they can be executed again
+    pub async fn clear_read_only_permission_issues(&self) -> Option<()> {
+        self.check_library_lock().await?;
+        let _busy_lock = self.busy_lock.lock().await;
+        let issues = self.permission_issues.read().await.clone();
+        let mut remaining_issues = Vec::new();
+        let mut cleared_paths = HashSet::new();
+        for issue in issues {
+            if !issue.cause.is_fixable() {
+                remaining_issues.push(issue);
+                continue;
+            }
+            // multiple files can be blocked by the same folder
+            if cleared_paths.contains(&issue.blocking_path) {
+                continue;
+            }
+            match clear_read_only(issue.blocking_path.as_path()).await {
+                Ok(()) => { cleared_paths.insert(issue.blocking_path.clone()); },
+                Err(err) => {
+                    let message = format!("IO error while clearing read-only attribute on '{}': {}", issue.blocking_path.to_string_lossy(), err);
+                    self.errors.write().await.push(message);
+                    remaining_issues.push(issue);
+                },
+            }
+        }
+        let total_cleared = cleared_paths.len();
+        *self.permission_issues.write().await = remaining_issues;
+        self.push_activity(ActivityKind::Execute, format!("Cleared read-only attribute on {} paths", total_cleared)).await;
+        Some(())
+    }
+
     async fn delete_empty_folders(&self) {
         let mut tasks = Vec::new();
 
@@ -566,20 +2171,46 @@ impl AppFolder {
             });
         }
 
-        let mut errors = self.errors.write().await;
-        for res in futures::future::join_all(tasks).await.into_iter() {
-            if let Err(err) = res {
-                let message = format!("IO error while deleting empty folders: {}", err);
-                errors.push(message);
-            };
+        let mut total_deleted = 0;
+        {
+            let mut errors = self.errors.write().await;
+            for res in futures::future::join_all(tasks).await.into_iter() {
+                match res {
+                    Ok(()) => total_deleted += 1,
+                    Err(err) => {
+                        let message = format!("IO error while deleting empty folders: {}", err);
+                        errors.push(message);
+                    },
+                };
+            }
+        }
+        if total_deleted > 0 {
+            self.push_activity(ActivityKind::Execute, format!("Automatically deleted {} empty folders", total_deleted)).await;
         }
     }
     
     // getters
+    pub fn get_filter_rules(&self) -> &RwLock<Arc<FilterRules>> {
+        &self.filter_rules
+    }
+
+    pub fn get_profile_store(&self) -> &Arc<ProfileStore> {
+        &self.profile_store
+    }
+
     pub fn get_folder_path(&self) -> &str {
         self.folder_path.as_str() 
     }
 
+    // Data files of remote folders are kept locally so these always point at the local copy
+    pub fn get_series_path(&self) -> &str {
+        self.series_path.as_str()
+    }
+
+    pub fn get_bookmarks_path(&self) -> &str {
+        self.bookmarks_path.as_str()
+    }
+
     pub fn get_folder_name(&self) -> &str {
         self.folder_name.as_str() 
     }
@@ -588,6 +2219,14 @@ impl AppFolder {
         &self.file_tracker
     }
 
+    pub fn get_plan_changes(&self) -> &RwLock<Vec<PlanChange>> {
+        &self.plan_changes
+    }
+
+    pub fn get_is_plan_locked(&self) -> &Mutex<bool> {
+        &self.is_plan_locked
+    }
+
     pub fn get_busy_lock(&self) -> &Mutex<()> {
         &self.busy_lock
     }
@@ -596,51 +2235,87 @@ impl AppFolder {
         &self.errors
     }
 
+    pub fn get_permission_issues(&self) -> &RwLock<Vec<PermissionIssue>> {
+        &self.permission_issues
+    }
+
     pub fn get_selected_descriptor(&self) -> &RwLock<Option<EpisodeKey>> {
         &self.selected_descriptor
     }
 
-    pub fn get_cache(&self) -> &RwLock<Option<TvdbCache>> {
+    pub fn get_selected_src(&self) -> &RwLock<Option<String>> {
+        &self.selected_src
+    }
+
+    pub fn get_cache(&self) -> &RwLock<Option<Arc<TvdbCache>>> {
         &self.cache
     }
 
+    pub fn get_settings(&self) -> &RwLock<FolderSettings> {
+        &self.settings
+    }
+
+    pub fn get_series_progress(&self) -> &RwLock<SeriesProgress> {
+        &self.series_progress
+    }
+
+    pub fn get_anime_mapping(&self) -> &RwLock<Option<AnimeMapping>> {
+        &self.anime_mapping
+    }
+
     pub fn get_bookmarks(&self) -> &RwLock<BookmarkTable> {
         &self.bookmarks
     }
 
-    pub async fn get_files(&self) -> ImmutableAppFileList {
+    pub fn get_execution_skips(&self) -> &RwLock<ExecutionSkipList> {
+        &self.execution_skips
+    }
+
+    pub fn get_execution_outcomes(&self) -> &RwLock<ExecutionOutcomes> {
+        &self.execution_outcomes
+    }
+
+    pub fn get_watch_state(&self) -> &RwLock<Option<WatchState>> {
+        &self.watch_state
+    }
+
+    pub fn get_archived_files(&self) -> &RwLock<Vec<ArchivedFile>> {
+        &self.archived_files
+    }
+
+    pub async fn get_files(&self) -> ImmutableAppFileList<'_> {
         let file_list = self.file_list.read().await;
         let file_tracker = self.file_tracker.read().await;
         ImmutableAppFileList::new(file_list, file_tracker)
     }
 
-    pub async fn get_mut_files(&self) -> MutableAppFileList {
+    pub async fn get_mut_files(&self) -> MutableAppFileList<'_> {
         let file_list = self.file_list.read().await;
         let file_tracker = self.file_tracker.read().await;
         let change_queue = self.change_queue.write().await;
         MutableAppFileList::new(file_list, file_tracker, change_queue)
     }
     
-    pub fn get_files_blocking(&self) -> ImmutableAppFileList {
+    pub fn get_files_blocking(&self) -> ImmutableAppFileList<'_> {
         let file_list = self.file_list.blocking_read();
         let file_tracker = self.file_tracker.blocking_read();
         ImmutableAppFileList::new(file_list, file_tracker)
     }
 
-    pub fn get_mut_files_blocking(&self) -> MutableAppFileList {
+    pub fn get_mut_files_blocking(&self) -> MutableAppFileList<'_> {
         let file_list = self.file_list.blocking_read();
         let file_tracker = self.file_tracker.blocking_read();
         let change_queue = self.change_queue.blocking_write();
         MutableAppFileList::new(file_list, file_tracker, change_queue)
     }
     
-    pub fn get_files_try_blocking(&self) -> Option<ImmutableAppFileList> {
+    pub fn get_files_try_blocking(&self) -> Option<ImmutableAppFileList<'_>> {
         let file_list = self.file_list.try_read().ok()?;
         let file_tracker = self.file_tracker.try_read().ok()?;
         Some(ImmutableAppFileList::new(file_list, file_tracker))
     }
 
-    pub fn get_mut_files_try_blocking(&self) -> Option<MutableAppFileList> {
+    pub fn get_mut_files_try_blocking(&self) -> Option<MutableAppFileList<'_>> {
         let file_list = self.file_list.try_read().ok()?;
         let file_tracker = self.file_tracker.try_read().ok()?;
         let change_queue = self.change_queue.try_write().ok()?;
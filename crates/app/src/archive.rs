@@ -0,0 +1,36 @@
+use chrono;
+use serde;
+use serde_json;
+use std::path::{Path, PathBuf};
+use crate::folder_settings::FolderSettings;
+use crate::tvdb_cache::EpisodeKey;
+
+// Stub left in the folder for every file moved into the archive root
+// E.g. <folder>/archive.json
+#[serde_with::skip_serializing_none]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct ArchivedFile {
+    // Relative to the folder when the file was archived
+    pub src: String,
+    pub archive_path: String,
+    pub descriptor: Option<EpisodeKey>,
+    pub size: u64,
+    pub archived: chrono::DateTime<chrono::Utc>,
+}
+
+// Archived files are kept under the folder name so folders with the same file names don't collide
+pub fn get_archive_path(archive_root: &str, folder_name: &str, src: &str) -> PathBuf {
+    Path::new(archive_root).join(folder_name).join(src)
+}
+
+pub(crate) fn is_archive_flagged(settings: &FolderSettings, descriptor: Option<&EpisodeKey>) -> bool {
+    settings.is_archived || descriptor.map(|key| settings.archived_seasons.contains(&key.season)).unwrap_or(false)
+}
+
+pub fn deserialize_archived_files(data: &str) -> Result<Vec<ArchivedFile>, serde_json::Error> {
+    serde_json::from_str(data)
+}
+
+pub fn serialize_archived_files(files: &[ArchivedFile]) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(files)
+}
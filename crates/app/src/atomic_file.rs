@@ -0,0 +1,143 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+use zstd;
+
+pub const BACKUP_SUFFIX: &str = ".bak";
+const TMP_SUFFIX: &str = ".tmp";
+// Every zstd frame starts with this, which json and jsonl never do
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+const ZSTD_LEVEL: i32 = 3;
+
+#[derive(Debug)]
+pub enum LoadError<E> {
+    IO(std::io::Error),
+    Decode(E),
+}
+
+pub struct Loaded<T> {
+    pub value: T,
+    // The file was missing or corrupted so the previous version was used instead
+    pub is_restored: bool,
+}
+
+fn get_sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut sibling = path.as_os_str().to_os_string();
+    sibling.push(suffix);
+    PathBuf::from(sibling)
+}
+
+pub fn get_backup_path(path: &Path) -> PathBuf {
+    get_sibling_path(path, BACKUP_SUFFIX)
+}
+
+// E.g. "series.json.bak" => "series.json"
+pub fn get_backup_source(filename: &str) -> Option<&str> {
+    filename.strip_suffix(BACKUP_SUFFIX).filter(|source| !source.is_empty())
+}
+
+pub fn encode_data(data: String, is_compressed: bool) -> Result<Vec<u8>, std::io::Error> {
+    match is_compressed {
+        true => zstd::encode_all(data.as_bytes(), ZSTD_LEVEL),
+        false => Ok(data.into_bytes()),
+    }
+}
+
+// Plain files are passed through so that files written before compression was enabled still load
+pub fn decode_data(data: Vec<u8>) -> Result<String, std::io::Error> {
+    let data = match data.starts_with(&ZSTD_MAGIC) {
+        true => zstd::decode_all(data.as_slice())?,
+        false => data,
+    };
+    String::from_utf8(data).map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+}
+
+// Appended files can end with a frame that was cut off, everything before it is kept
+pub fn decode_partial_data(data: Vec<u8>) -> String {
+    if !data.starts_with(&ZSTD_MAGIC) {
+        return String::from_utf8_lossy(data.as_slice()).to_string();
+    }
+    let mut decoded = Vec::new();
+    if let Ok(mut decoder) = zstd::Decoder::new(data.as_slice()) {
+        let _ = decoder.read_to_end(&mut decoded);
+    }
+    String::from_utf8_lossy(decoded.as_slice()).to_string()
+}
+
+pub async fn read_to_string(path: impl AsRef<Path>) -> Result<String, std::io::Error> {
+    decode_data(tokio::fs::read(path).await?)
+}
+
+// Renames are only durable once the folder holding them is flushed, this isn't supported on windows
+async fn sync_parent_folder(path: &Path) {
+    #[cfg(unix)]
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        if let Ok(folder) = tokio::fs::File::open(parent).await {
+            let _ = folder.sync_all().await;
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
+// The new data is flushed to a temporary file before it replaces the old file, which is kept as a backup
+pub async fn write_atomic(path: impl AsRef<Path>, data: impl AsRef<[u8]>) -> Result<(), std::io::Error> {
+    let path = path.as_ref();
+    let tmp_path = get_sibling_path(path, TMP_SUFFIX);
+    {
+        let mut file = tokio::fs::File::create(tmp_path.as_path()).await?;
+        file.write_all(data.as_ref()).await?;
+        file.sync_all().await?;
+    }
+    match tokio::fs::rename(path, get_backup_path(path)).await {
+        Ok(()) => {},
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {},
+        Err(err) => return Err(err),
+    }
+    tokio::fs::rename(tmp_path.as_path(), path).await?;
+    sync_parent_folder(path).await;
+    Ok(())
+}
+
+// Falls back to the backup if the file is missing or can't be decoded, otherwise the original error is returned
+pub async fn load_with_backup<T, E>(
+    path: impl AsRef<Path>, decode: impl Fn(&str) -> Result<T, E>,
+) -> Result<Loaded<T>, LoadError<E>> {
+    let path = path.as_ref();
+    let err = match read_to_string(path).await {
+        Ok(data) => match decode(data.as_str()) {
+            Ok(value) => return Ok(Loaded { value, is_restored: false }),
+            Err(err) => LoadError::Decode(err),
+        },
+        Err(err) => LoadError::IO(err),
+    };
+    // a crash between the two renames leaves only the backup
+    let backup = read_to_string(get_backup_path(path)).await;
+    match backup.ok().and_then(|data| decode(data.as_str()).ok()) {
+        Some(value) => Ok(Loaded { value, is_restored: true }),
+        None => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_and_compressed_data_decode() {
+        let data = "{\"id\": 1234}".to_string();
+        assert_eq!(decode_data(encode_data(data.clone(), false).unwrap()).unwrap(), data);
+        let compressed = encode_data(data.clone(), true).unwrap();
+        assert!(compressed.starts_with(&ZSTD_MAGIC));
+        assert_eq!(decode_data(compressed).unwrap(), data);
+    }
+
+    #[test]
+    fn cut_off_frames_are_dropped() {
+        let mut data = encode_data("header\n".to_string(), true).unwrap();
+        data.extend(encode_data("0\n".to_string(), true).unwrap());
+        let last_frame = encode_data("1\n".to_string(), true).unwrap();
+        data.extend(&last_frame[..last_frame.len()-2]);
+        assert!(decode_partial_data(data).starts_with("header\n0\n"));
+    }
+}
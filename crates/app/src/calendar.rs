@@ -0,0 +1,117 @@
+use chrono;
+use std::collections::HashSet;
+use crate::app_folder::AppFolder;
+use crate::file_intent::{Action, is_video_path};
+use crate::folder_settings::FolderSettings;
+use crate::tvdb_cache::{EpisodeKey, TvdbCache};
+
+// Episodes which aired within this many days are expected to have a file for continuing series
+pub const RECENT_EPISODE_DAYS: i64 = 30;
+
+#[derive(Debug, Clone)]
+pub struct AgendaEntry {
+    pub air_date: chrono::NaiveDate,
+    pub folder_name: String,
+    pub series_name: String,
+    pub descriptor: EpisodeKey,
+    pub episode_name: Option<String>,
+    pub is_file_present: bool,
+    // Only known for series on the media server
+    pub is_watched: Option<bool>,
+}
+
+// Episodes airing between the start and end dates across all bound folders
+#[derive(Debug, Clone)]
+pub struct Agenda {
+    pub start: chrono::NaiveDate,
+    pub end: chrono::NaiveDate,
+    pub entries: Vec<AgendaEntry>,
+}
+
+pub fn parse_air_date(date: &str) -> Option<chrono::NaiveDate> {
+    chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+}
+
+// Files that are about to be deleted, like samples, don't count as having the episode
+pub(crate) fn is_episode_file(action: Action, src: &str) -> bool {
+    action != Action::Delete && is_video_path(src)
+}
+
+pub(crate) fn get_total_missing_recent_episodes(cache: &TvdbCache, present_episodes: &HashSet<EpisodeKey>, settings: &FolderSettings, today: chrono::NaiveDate) -> usize {
+    let start = today - chrono::Duration::days(RECENT_EPISODE_DAYS);
+    let ordering = settings.get_ordering();
+    cache.episodes
+        .iter()
+        .enumerate()
+        .filter_map(|(index, episode)| {
+            let descriptor = cache.get_episode_key(index, ordering)?;
+            let air_date = parse_air_date(episode.first_aired.as_deref()?)?;
+            (air_date >= start && air_date <= today).then_some(descriptor)
+        })
+        // specials are rarely released alongside the season so they aren't expected
+        .filter(|descriptor| descriptor.season > 0)
+        .filter(|descriptor| settings.is_season_in_scope(descriptor.season))
+        .filter(|descriptor| !present_episodes.contains(descriptor))
+        .count()
+}
+
+// Returns how many of the episodes which already aired have a file, out of all aired episodes
+pub(crate) fn get_aired_episode_counts(cache: &TvdbCache, present_episodes: &HashSet<EpisodeKey>, settings: &FolderSettings, today: chrono::NaiveDate) -> (usize, usize) {
+    let ordering = settings.get_ordering();
+    let aired_episodes: Vec<EpisodeKey> = cache.episodes
+        .iter()
+        .enumerate()
+        .filter_map(|(index, episode)| {
+            let descriptor = cache.get_episode_key(index, ordering)?;
+            let air_date = parse_air_date(episode.first_aired.as_deref()?)?;
+            (air_date <= today).then_some(descriptor)
+        })
+        // specials are left out so collectors of the main seasons can reach 100%
+        .filter(|descriptor| descriptor.season > 0)
+        .filter(|descriptor| settings.is_season_in_scope(descriptor.season))
+        .collect();
+    let total_present = aired_episodes.iter().filter(|descriptor| present_episodes.contains(descriptor)).count();
+    (total_present, aired_episodes.len())
+}
+
+pub(crate) async fn get_folder_agenda(folder: &AppFolder, start: chrono::NaiveDate, end: chrono::NaiveDate) -> Vec<AgendaEntry> {
+    let cache = folder.get_cache().read().await;
+    let cache = match cache.as_ref() {
+        Some(cache) => cache,
+        None => return Vec::new(),
+    };
+
+    let mut present_episodes: HashSet<EpisodeKey> = folder.get_files().await
+        .to_iter()
+        .filter(|file| is_episode_file(file.get_action(), file.get_src()))
+        .filter_map(|file| *file.get_src_descriptor())
+        .collect();
+    present_episodes.extend(folder.get_archived_episodes().await);
+
+    let watch_state = folder.get_watch_state().read().await;
+    let settings = folder.get_settings().read().await;
+    let ordering = settings.get_ordering();
+    cache.episodes
+        .iter()
+        .enumerate()
+        .filter_map(|(index, episode)| {
+            let descriptor = cache.get_episode_key(index, ordering)?;
+            if !settings.is_season_in_scope(descriptor.season) {
+                return None;
+            }
+            let air_date = parse_air_date(episode.first_aired.as_deref()?)?;
+            if air_date < start || air_date > end {
+                return None;
+            }
+            Some(AgendaEntry {
+                air_date,
+                folder_name: folder.get_folder_name().to_string(),
+                series_name: cache.series.name.clone(),
+                descriptor,
+                episode_name: cache.get_episode_name(index, settings.language.as_deref()).map(|name| name.to_string()),
+                is_file_present: present_episodes.contains(&descriptor),
+                is_watched: watch_state.as_ref().and_then(|watch_state| watch_state.is_watched(&descriptor)),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,171 @@
+use chrono;
+use qbittorrent::models::Torrent;
+use serde;
+use serde_json;
+use tokio::sync::RwLock;
+use crate::atomic_file::{LoadError, load_with_backup, write_atomic};
+use crate::natural_sort::sort_natural_by;
+
+const DEFAULT_CLEANUP_CHECK_INTERVAL_SECS: u64 = 10 * 60;
+const SECS_PER_HOUR: f32 = 60.0 * 60.0;
+
+// Read from "seed_targets" in the app config, queued torrents are cleaned up once either target is reached
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct SeedTargets {
+    #[serde(default)]
+    pub min_ratio: Option<f32>,
+    #[serde(default)]
+    pub min_seed_hours: Option<f32>,
+}
+
+impl SeedTargets {
+    // Without targets the torrent is cleaned up once the client stops seeding it
+    pub fn is_met(&self, torrent: &Torrent) -> bool {
+        if !torrent.is_complete() {
+            return false;
+        }
+        if self.min_ratio.is_none() && self.min_seed_hours.is_none() {
+            return !torrent.is_seeding();
+        }
+        let is_ratio_met = self.min_ratio.map(|ratio| torrent.ratio >= ratio).unwrap_or(false);
+        let is_seed_time_met = self.min_seed_hours.map(|hours| torrent.seeding_time as f32 >= hours*SECS_PER_HOUR).unwrap_or(false);
+        is_ratio_met || is_seed_time_met
+    }
+}
+
+pub fn get_cleanup_check_interval(interval_secs: Option<u64>) -> std::time::Duration {
+    std::time::Duration::from_secs(interval_secs.unwrap_or(DEFAULT_CLEANUP_CHECK_INTERVAL_SECS).max(1))
+}
+
+// A torrent kept for seeding whose files are deleted once its seed targets are met
+#[serde_with::skip_serializing_none]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct QueuedCleanup {
+    pub hash: String,
+    pub name: String,
+    pub content_path: String,
+    pub queued: chrono::DateTime<chrono::Utc>,
+    pub targets: SeedTargets,
+    // As of the last check so the queue shows how far along each torrent is
+    pub ratio: Option<f32>,
+    pub seeding_time: Option<u64>,
+}
+
+impl QueuedCleanup {
+    pub fn new(torrent: &Torrent, targets: SeedTargets) -> Self {
+        Self {
+            hash: torrent.hash.clone(),
+            name: torrent.name.clone(),
+            content_path: torrent.content_path.clone(),
+            queued: chrono::Utc::now(),
+            targets,
+            ratio: Some(torrent.ratio),
+            seeding_time: Some(torrent.seeding_time),
+        }
+    }
+}
+
+// E.g. <config>/cleanup_queue.json
+pub struct CleanupQueue {
+    path: String,
+    entries: RwLock<Vec<QueuedCleanup>>,
+}
+
+impl CleanupQueue {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            entries: RwLock::new(Vec::new()),
+        }
+    }
+
+    pub async fn load_from_file(&self) -> Result<(), std::io::Error> {
+        let entries = match load_with_backup(self.path.as_str(), |data: &str| serde_json::from_str::<Vec<QueuedCleanup>>(data)).await {
+            Ok(entries) => entries.value,
+            Err(LoadError::IO(err)) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(LoadError::IO(err)) => return Err(err),
+            Err(LoadError::Decode(err)) => return Err(err.into()),
+        };
+        *self.entries.write().await = entries;
+        Ok(())
+    }
+
+    async fn save_to_file(&self, entries: &[QueuedCleanup]) -> Result<(), std::io::Error> {
+        let data = serde_json::to_string_pretty(entries).map_err(std::io::Error::from)?;
+        write_atomic(self.path.as_str(), data).await
+    }
+
+    // Changes are only kept once they are written to disk
+    async fn update(&self, update: impl FnOnce(&mut Vec<QueuedCleanup>)) -> Result<(), std::io::Error> {
+        let mut entries = self.entries.write().await;
+        let mut new_entries = entries.clone();
+        update(&mut new_entries);
+        sort_natural_by(new_entries.as_mut_slice(), |entry| entry.name.as_str());
+        self.save_to_file(new_entries.as_slice()).await?;
+        *entries = new_entries;
+        Ok(())
+    }
+
+    // Queueing a torrent again replaces its targets
+    pub async fn add(&self, entry: QueuedCleanup) -> Result<(), std::io::Error> {
+        self.update(|entries| {
+            entries.retain(|other| other.hash != entry.hash);
+            entries.push(entry);
+        }).await
+    }
+
+    pub async fn remove(&self, hashes: &[&str]) -> Result<(), std::io::Error> {
+        self.update(|entries| entries.retain(|entry| !hashes.contains(&entry.hash.as_str()))).await
+    }
+
+    pub async fn set_targets(&self, hash: &str, targets: SeedTargets) -> Result<(), std::io::Error> {
+        self.update(|entries| {
+            if let Some(entry) = entries.iter_mut().find(|entry| entry.hash == hash) {
+                entry.targets = targets;
+            }
+        }).await
+    }
+
+    pub async fn update_progress(&self, torrents: &[Torrent]) -> Result<(), std::io::Error> {
+        self.update(|entries| {
+            for entry in entries.iter_mut() {
+                if let Some(torrent) = torrents.iter().find(|torrent| torrent.hash == entry.hash) {
+                    entry.ratio = Some(torrent.ratio);
+                    entry.seeding_time = Some(torrent.seeding_time);
+                }
+            }
+        }).await
+    }
+
+    pub fn get_entries(&self) -> &RwLock<Vec<QueuedCleanup>> {
+        &self.entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn get_torrent(state: &str, ratio: f32, seeding_time: u64) -> Torrent {
+        serde_json::from_value(json!({
+            "hash": "abc", "name": "Example.Show.S01", "state": state,
+            "progress": 1.0, "ratio": ratio, "seeding_time": seeding_time,
+        })).unwrap()
+    }
+
+    #[test]
+    fn either_target_is_enough() {
+        let targets = SeedTargets { min_ratio: Some(1.0), min_seed_hours: Some(24.0) };
+        assert!(!targets.is_met(&get_torrent("uploading", 0.5, 3600)));
+        assert!(targets.is_met(&get_torrent("uploading", 1.2, 3600)));
+        assert!(targets.is_met(&get_torrent("uploading", 0.5, 24*3600)));
+    }
+
+    #[test]
+    fn no_targets_waits_for_client() {
+        let targets = SeedTargets::default();
+        assert!(!targets.is_met(&get_torrent("stalledUP", 5.0, 0)));
+        assert!(targets.is_met(&get_torrent("pausedUP", 0.0, 0)));
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use crate::app_file::AppFile;
+use crate::file_intent::{Action, VIDEO_EXTENSIONS};
+
+// Suffixes appended to the stem of an episode, "*" matches a single segment like a subtitle language
+pub fn get_default_companion_suffixes() -> Vec<String> {
+    [
+        ".srt", ".*.srt", ".ass", ".*.ass", ".ssa", ".*.ssa", ".sub", ".idx",
+        ".nfo", ".jpg", "-thumb.jpg", ".png", "-thumb.png",
+    ]
+    .iter()
+    .map(|suffix| suffix.to_string())
+    .collect()
+}
+
+fn is_suffix_match(pattern: &str, suffix: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == suffix,
+        Some((head, tail)) => {
+            let segment = match suffix.strip_prefix(head) {
+                Some(rest) => rest,
+                None => return false,
+            };
+            // try every split so that the wildcard can't swallow the remaining pattern
+            segment.char_indices()
+                .map(|(i, _)| i)
+                .chain(std::iter::once(segment.len()))
+                .take_while(|i| !segment[..*i].contains('.'))
+                .skip(1)
+                .any(|i| is_suffix_match(tail, &segment[i..]))
+        },
+    }
+}
+
+fn split_parent(path: &str) -> (&str, &str) {
+    match path.rsplit_once('/') {
+        Some((parent, filename)) => (parent, filename),
+        None => ("", path),
+    }
+}
+
+fn split_extension(path: &str) -> (&str, &str) {
+    let (_, filename) = split_parent(path);
+    match filename.rfind('.') {
+        Some(index) if index > 0 => path.split_at(path.len() - filename.len() + index),
+        _ => (path, ""),
+    }
+}
+
+fn is_episode(file: &AppFile) -> bool {
+    if file.src_descriptor.is_none() {
+        return false;
+    }
+    if file.action != Action::Rename && file.action != Action::Complete {
+        return false;
+    }
+    let (_, extension) = split_extension(file.src.as_str());
+    let extension = extension.trim_start_matches('.').to_lowercase();
+    VIDEO_EXTENSIONS.contains(&extension.as_str())
+}
+
+// Companion files are moved along with their episode so that players still pick them up after renaming
+pub(crate) fn group_companion_files(files: &mut [AppFile], suffixes: &[String], skip_sources: &HashMap<String, String>) {
+    let suffixes: Vec<String> = suffixes.iter().map(|suffix| suffix.to_lowercase()).collect();
+
+    let mut episodes: HashMap<String, usize> = HashMap::new();
+    for (index, file) in files.iter().enumerate() {
+        if is_episode(file) {
+            let (stem, _) = split_extension(file.src.as_str());
+            episodes.insert(stem.to_lowercase(), index);
+        }
+    }
+    if episodes.is_empty() {
+        return;
+    }
+
+    let mut companions: Vec<(usize, usize, usize)> = Vec::new();
+    for (index, file) in files.iter().enumerate() {
+        if is_episode(file) || skip_sources.contains_key(&file.src) {
+            continue;
+        }
+        // files that are explicitly handled by other rules keep their intent
+        if matches!(file.action, Action::Delete | Action::Whitelist | Action::Extra) {
+            continue;
+        }
+
+        let (_, filename) = split_parent(file.src.as_str());
+        // stems include the parent folder so companions only bind to episodes in the same folder
+        // prefer the longest stem so that "a.b.srt" binds to episode "a.b" before "a"
+        let split = filename.char_indices()
+            .filter(|(_, c)| *c == '.' || *c == '-')
+            .map(|(i, _)| file.src.len() - filename.len() + i)
+            .rev()
+            .find_map(|i| {
+                let episode = episodes.get(&file.src[..i].to_lowercase())?;
+                let suffix = file.src[i..].to_lowercase();
+                suffixes.iter().any(|pattern| is_suffix_match(pattern, suffix.as_str())).then_some((*episode, i))
+            });
+        if let Some((episode, split)) = split {
+            companions.push((index, episode, split));
+        }
+    }
+
+    for (index, episode, split) in companions {
+        let (episode_descriptor, new_stem) = {
+            let episode = &files[episode];
+            let new_path = match episode.action {
+                Action::Rename => episode.dest.as_str(),
+                _ => episode.src.as_str(),
+            };
+            let (new_stem, _) = split_extension(new_path);
+            (episode.src_descriptor, new_stem.to_string())
+        };
+
+        let file = &mut files[index];
+        let dest = format!("{}{}", new_stem, &file.src[split..]);
+        file.src_descriptor = episode_descriptor;
+        file.warnings.clear();
+        file.delete_reason = None;
+        if dest == file.src {
+            file.action = Action::Complete;
+            file.dest.clear();
+        } else {
+            file.action = Action::Rename;
+            file.dest = dest;
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use chrono;
+use serde;
+use serde_json;
+use std::path::Path;
+use crate::atomic_file::write_atomic;
+use crate::file_intent::FilterRules;
+
+pub const CONFIG_FILENAME: &str = "app_config.json";
+
+// Editors often rewrite a file within the same second so the size is compared too
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfigStamp {
+    modified: Option<std::time::SystemTime>,
+    size: u64,
+}
+
+pub async fn get_config_stamp(path: &Path) -> Option<ConfigStamp> {
+    let metadata = tokio::fs::metadata(path).await.ok()?;
+    Some(ConfigStamp {
+        modified: metadata.modified().ok(),
+        size: metadata.len(),
+    })
+}
+
+// Shown briefly by the gui when changes made outside of it are picked up
+#[derive(Debug, Clone)]
+pub struct ConfigNotice {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub message: String,
+}
+
+// Rules apply on the next scan, everything else needs a part of the app to be restarted
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConfigChanges {
+    pub is_changed: bool,
+    pub is_watcher_changed: bool,
+    pub is_provider_changed: bool,
+    pub is_ffmpeg_changed: bool,
+    pub is_label_rules_changed: bool,
+    pub is_ssh_changed: bool,
+    pub is_http_address_changed: bool,
+    pub is_library_depth_changed: bool,
+    pub is_network_changed: bool,
+}
+
+fn is_value_changed<T: serde::Serialize>(old: &T, new: &T) -> bool {
+    serde_json::to_value(old).ok() != serde_json::to_value(new).ok()
+}
+
+pub fn get_config_changes(old: &FilterRules, new: &FilterRules) -> ConfigChanges {
+    ConfigChanges {
+        is_changed: is_value_changed(old, new),
+        is_watcher_changed: old.watch_folders != new.watch_folders || old.auto_refresh_delay_secs != new.auto_refresh_delay_secs,
+        is_provider_changed: old.tvdb_base_url != new.tvdb_base_url,
+        is_ffmpeg_changed: old.ffmpeg_path != new.ffmpeg_path,
+        is_label_rules_changed: is_value_changed(&old.label_rules, &new.label_rules),
+        is_ssh_changed: old.ssh_path != new.ssh_path,
+        is_http_address_changed: old.daemon.http_address != new.daemon.http_address,
+        is_library_depth_changed: old.library_depth != new.library_depth,
+        is_network_changed: old.network != new.network,
+    }
+}
+
+// Replaces a single top level key so the rest of a hand edited config is left as it was
+pub async fn save_config_value(config_path: &Path, key: &str, value: serde_json::Value) -> Result<(), String> {
+    let data = tokio::fs::read_to_string(config_path).await.map_err(|err| err.to_string())?;
+    let mut config: serde_json::Value = serde_json::from_str(data.as_str()).map_err(|err| err.to_string())?;
+    let config_object = config.as_object_mut().ok_or("config isn't a json object")?;
+    config_object.insert(key.to_string(), value);
+    let data = serde_json::to_string_pretty(&config).map_err(|err| err.to_string())?;
+    write_atomic(config_path, data).await.map_err(|err| err.to_string())
+}
@@ -0,0 +1,223 @@
+use serde;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use crate::app::App;
+use crate::http_api::serve_http_api;
+use crate::metrics::{DELETES_EXECUTED, ERRORS_LOGGED, EXECUTION_ERRORS, RENAMES_EXECUTED, get_metrics};
+
+const DEFAULT_REFRESH_INTERVAL_SECS: u64 = 15 * 60;
+const WATCHER_POLL_MILLIS: u64 = 500;
+const LOG_POLL_MILLIS: u64 = 1000;
+
+// Read from "daemon" in the app config, each field can be overridden by an environment variable
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default)]
+pub struct DaemonSettings {
+    // TORRENT_RENAMER_REFRESH_SECS, all folders are rescanned this often on top of the folder watcher
+    #[serde(default)]
+    pub refresh_interval_secs: Option<u64>,
+    // TORRENT_RENAMER_AUTO_EXECUTE, only changes which don't need a review are enabled automatically
+    #[serde(default)]
+    pub auto_execute: bool,
+    // TORRENT_RENAMER_HTTP_ADDRESS, e.g. "0.0.0.0:8080"
+    #[serde(default)]
+    pub http_address: Option<SocketAddr>,
+    // TORRENT_RENAMER_API_TOKEN, required as a bearer token by the refresh and execute routes
+    // Without a token the http api only binds to loopback addresses
+    #[serde(default)]
+    pub api_token: Option<String>,
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+impl DaemonSettings {
+    pub fn with_env_overrides(&self) -> Result<Self, String> {
+        let mut settings = self.clone();
+        let get_env = |key: &str| std::env::var(key).ok().filter(|value| !value.trim().is_empty());
+        if let Some(value) = get_env("TORRENT_RENAMER_REFRESH_SECS") {
+            let secs = value.trim().parse().map_err(|_| format!("TORRENT_RENAMER_REFRESH_SECS must be a number of seconds: '{}'", value))?;
+            settings.refresh_interval_secs = Some(secs);
+        }
+        if let Some(value) = get_env("TORRENT_RENAMER_AUTO_EXECUTE") {
+            settings.auto_execute = parse_bool(value.as_str()).ok_or(format!("TORRENT_RENAMER_AUTO_EXECUTE must be true or false: '{}'", value))?;
+        }
+        if let Some(value) = get_env("TORRENT_RENAMER_HTTP_ADDRESS") {
+            let address = value.trim().parse().map_err(|_| format!("TORRENT_RENAMER_HTTP_ADDRESS must be an address like 0.0.0.0:8080: '{}'", value))?;
+            settings.http_address = Some(address);
+        }
+        if let Some(value) = get_env("TORRENT_RENAMER_API_TOKEN") {
+            settings.api_token = Some(value.trim().to_string());
+        }
+        Ok(settings)
+    }
+
+    pub fn get_api_token(&self) -> Option<&str> {
+        self.api_token.as_deref().map(str::trim).filter(|token| !token.is_empty())
+    }
+
+    pub fn get_refresh_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.refresh_interval_secs.unwrap_or(DEFAULT_REFRESH_INTERVAL_SECS).max(1))
+    }
+}
+
+// Everything the gui would show is printed to stdout instead so it ends up in the container logs
+async fn print_new_logs(app: &App, total_printed_events: &mut usize) {
+    {
+        let activity_log = app.get_activity_log();
+        let events = activity_log.get_events().read().await;
+        // counted from the first event ever pushed since old events are trimmed from the front
+        let total_trimmed = activity_log.get_total_trimmed();
+        for event in events.iter().skip(total_printed_events.saturating_sub(total_trimmed)) {
+            let folder = event.folder.as_ref().map(|folder| format!(" [{}]", folder)).unwrap_or_default();
+            println!("{} {}{}: {}", event.timestamp.format("%Y-%m-%dT%H:%M:%SZ"), event.kind.to_str(), folder, event.message);
+        }
+        *total_printed_events = total_trimmed + events.len();
+    }
+
+    let timestamp = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ");
+    let mut total_errors = 0;
+    for message in std::mem::take(&mut *app.get_errors().write().await) {
+        println!("{} Error: {}", timestamp, message);
+        total_errors += 1;
+    }
+    let folders = app.get_folders().read().await.clone();
+    for folder in folders.iter() {
+        for message in std::mem::take(&mut *folder.get_errors().write().await) {
+            println!("{} Error [{}]: {}", timestamp, folder.get_folder_name(), message);
+            total_errors += 1;
+        }
+    }
+    get_metrics().add_count(ERRORS_LOGGED, total_errors);
+}
+
+// Settings are reread so config reloads apply without a restart, except for the http address
+async fn get_current_settings(app: &App, fallback: &DaemonSettings) -> DaemonSettings {
+    let settings = app.get_filter_rules().read().await.daemon.with_env_overrides();
+    settings.unwrap_or_else(|_| fallback.clone())
+}
+
+async fn refresh_library(app: &App, settings: &DaemonSettings) {
+    // Picks up folders which were added since the last refresh
+    app.load_folders_from_existing_root_path().await;
+    app.update_file_intents_for_all_folders().await;
+    if settings.auto_execute {
+        app.execute_pending_folders().await;
+    }
+}
+
+// Runs until ctrl-c or SIGTERM without any gui
+pub async fn run_daemon(app: Arc<App>, root_path: String, settings: DaemonSettings) {
+    println!(
+        "Starting daemon on '{}' with refreshes every {}s, auto execution {}",
+        root_path, settings.get_refresh_interval().as_secs(),
+        if settings.auto_execute { "enabled" } else { "disabled" },
+    );
+    // Counters are exported from the start so rates can be computed before the first execution
+    for name in [RENAMES_EXECUTED, DELETES_EXECUTED, EXECUTION_ERRORS, ERRORS_LOGGED] {
+        get_metrics().add_count(name, 0);
+    }
+    // Only events from this run are printed
+    let mut total_printed_events = {
+        let activity_log = app.get_activity_log();
+        let events = activity_log.get_events().read().await;
+        activity_log.get_total_trimmed() + events.len()
+    };
+    app.set_watching_folders(true).await;
+    tokio::join!(
+        app.load_folders(root_path),
+        app.login(),
+    );
+    app.run_diagnostics().await;
+    for diagnostic in app.get_diagnostics().read().await.iter() {
+        println!("Diagnostic: {} ({})", diagnostic.message, diagnostic.suggestion);
+    }
+
+    let mut tasks = Vec::new();
+    tasks.push(tokio::spawn({
+        let app = app.clone();
+        async move {
+            loop {
+                print_new_logs(&app, &mut total_printed_events).await;
+                tokio::time::sleep(std::time::Duration::from_millis(LOG_POLL_MILLIS)).await;
+            }
+        }
+    }));
+    tasks.push(tokio::spawn({
+        let app = app.clone();
+        let settings = settings.clone();
+        async move {
+            loop {
+                app.refresh_library_lock().await;
+                app.reload_config_if_changed().await;
+                app.refresh_watched_folders().await;
+                app.purge_trash_if_due().await;
+                app.process_cleanup_queue_if_due().await;
+                app.save_folder_summaries().await;
+                let settings = get_current_settings(&app, &settings).await;
+                if settings.auto_execute {
+                    app.execute_pending_folders().await;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(WATCHER_POLL_MILLIS)).await;
+            }
+        }
+    }));
+    tasks.push(tokio::spawn({
+        let app = app.clone();
+        let settings = settings.clone();
+        async move {
+            loop {
+                let settings = get_current_settings(&app, &settings).await;
+                tokio::time::sleep(settings.get_refresh_interval()).await;
+                let settings = get_current_settings(&app, &settings).await;
+                refresh_library(&app, &settings).await;
+            }
+        }
+    }));
+    if let Some(address) = settings.http_address {
+        tasks.push(tokio::spawn({
+            let app = app.clone();
+            let api_token = settings.get_api_token().map(str::to_string);
+            async move {
+                if api_token.is_none() && !address.ip().is_loopback() {
+                    println!("Error: HTTP api on '{}' needs an api token since it isn't a loopback address", address);
+                    return;
+                }
+                if let Err(err) = serve_http_api(app, address, api_token).await {
+                    println!("Error: HTTP api on '{}' stopped: {}", address, err);
+                }
+            }
+        }));
+    }
+
+    wait_for_shutdown().await;
+    println!("Stopping daemon");
+    for task in tasks {
+        task.abort();
+    }
+    app.get_library_lock().release().await;
+}
+
+async fn wait_for_shutdown() {
+    #[cfg(unix)]
+    {
+        let mut terminate = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(terminate) => terminate,
+            Err(_) => {
+                let _ = tokio::signal::ctrl_c().await;
+                return;
+            },
+        };
+        // Containers are stopped with SIGTERM
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {},
+            _ = terminate.recv() => {},
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = tokio::signal::ctrl_c().await;
+}
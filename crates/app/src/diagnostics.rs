@@ -0,0 +1,98 @@
+use chrono;
+use reqwest;
+use std::path::Path;
+
+// Clocks further apart than this cause tokens to be rejected as expired or not yet valid
+const MAX_CLOCK_SKEW_SECS: i64 = 5*60;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum DiagnosticKind {
+    RootPath,
+    Credentials,
+    Cache,
+    Clock,
+}
+
+impl DiagnosticKind {
+    pub fn iterator() -> std::slice::Iter<'static, Self> {
+        static KINDS: [DiagnosticKind;4] = [
+            DiagnosticKind::RootPath,
+            DiagnosticKind::Credentials,
+            DiagnosticKind::Cache,
+            DiagnosticKind::Clock,
+        ];
+        KINDS.iter()
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            DiagnosticKind::RootPath => "Root folder",
+            DiagnosticKind::Credentials => "Credentials",
+            DiagnosticKind::Cache => "Cache",
+            DiagnosticKind::Clock => "Clock",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    pub message: String,
+    pub suggestion: &'static str,
+}
+
+impl Diagnostic {
+    pub fn new(kind: DiagnosticKind, message: String, suggestion: &'static str) -> Self {
+        Self { kind, message, suggestion }
+    }
+}
+
+pub async fn check_root_path(root_path: &str) -> Option<Diagnostic> {
+    let metadata = match tokio::fs::metadata(root_path).await {
+        Ok(metadata) => metadata,
+        Err(err) => return Some(Diagnostic::new(
+            DiagnosticKind::RootPath,
+            format!("Root folder '{}' couldn't be opened: {}", root_path, err),
+            "Check that the drive is mounted or pick another root folder",
+        )),
+    };
+    if !metadata.is_dir() {
+        return Some(Diagnostic::new(
+            DiagnosticKind::RootPath,
+            format!("Root folder '{}' is not a folder", root_path),
+            "Pick a folder which contains the series folders",
+        ));
+    }
+
+    // Permissions can't be read reliably across platforms so a file is written instead
+    let probe_path = Path::new(root_path).join(".torrent_renamer_write_check");
+    let res = match tokio::fs::write(probe_path.as_path(), b"").await {
+        Ok(()) => tokio::fs::remove_file(probe_path.as_path()).await,
+        Err(err) => Err(err),
+    };
+    match res {
+        Ok(()) => None,
+        Err(err) => Some(Diagnostic::new(
+            DiagnosticKind::RootPath,
+            format!("Root folder '{}' is not writable: {}", root_path, err),
+            "Give your user write access to the folder or run the app as the torrent client's user",
+        )),
+    }
+}
+
+// The Date header of any response is enough to compare against the local clock
+pub async fn check_clock_skew(client: &reqwest::Client, url: &str) -> Option<Diagnostic> {
+    let res = client.head(url).send().await.ok()?;
+    let date = res.headers().get(reqwest::header::DATE)?.to_str().ok()?;
+    let server_time = chrono::DateTime::parse_from_rfc2822(date).ok()?;
+    let skew = chrono::Utc::now().timestamp() - server_time.timestamp();
+    if skew.abs() <= MAX_CLOCK_SKEW_SECS {
+        return None;
+    }
+    let direction = if skew > 0 { "ahead of" } else { "behind" };
+    Some(Diagnostic::new(
+        DiagnosticKind::Clock,
+        format!("System clock is {} seconds {} the server", skew.abs(), direction),
+        "Enable automatic time synchronisation, otherwise login tokens are rejected",
+    ))
+}
@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+use crate::app_file::AppFile;
+use crate::file_intent::{Action, DeletePolicy, DeleteReason, is_video_path};
+
+// Copies of an episode which rename to the same destination only keep the largest one
+// The other copies get the delete policy for duplicates, ties keep the copy which is already in place
+pub(crate) fn mark_duplicate_losers(files: &mut [AppFile], policy: DeletePolicy) {
+    let mut groups: HashMap<String, Vec<usize>> = HashMap::new();
+    for (index, file) in files.iter().enumerate() {
+        let path = match file.action {
+            Action::Rename => file.dest.as_str(),
+            Action::Complete => file.src.as_str(),
+            _ => continue,
+        };
+        if file.src_descriptor.is_none() || !is_video_path(path) {
+            continue;
+        }
+        groups.entry(path.to_lowercase()).or_default().push(index);
+    }
+
+    for indices in groups.into_values().filter(|indices| indices.len() > 1) {
+        let winner = indices.iter()
+            .copied()
+            .max_by_key(|index| (files[*index].size, files[*index].action == Action::Complete, std::cmp::Reverse(*index)));
+        for index in indices.into_iter().filter(|index| Some(*index) != winner) {
+            let file = &mut files[index];
+            file.delete_reason = Some(DeleteReason::DuplicateLoser);
+            file.dest.clear();
+            file.action = match policy {
+                DeletePolicy::Ignore => Action::Ignore,
+                DeletePolicy::Permanent | DeletePolicy::Trash => Action::Delete,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tvdb_cache::EpisodeKey;
+
+    fn create_file(src: &str, action: Action, dest: &str, size: u64) -> AppFile {
+        let descriptor = Some(EpisodeKey { season: 1, episode: 1 });
+        AppFile::new(src.to_string(), descriptor, action, dest.to_string(), Vec::new(), None, size)
+    }
+
+    #[test]
+    fn only_largest_copy_is_kept() {
+        let mut files = [
+            create_file("Season 01/Show-S01E01.mkv", Action::Complete, "", 100),
+            create_file("a/Show.S01E01.1080p.mkv", Action::Rename, "Season 01/Show-S01E01.mkv", 300),
+            create_file("b/Show.S01E01.720p.mkv", Action::Rename, "Season 01/Show-S01E01.mkv", 200),
+            create_file("a/Show.S01E01.1080p.srt", Action::Rename, "Season 01/Show-S01E01.srt", 1),
+        ];
+        mark_duplicate_losers(&mut files, DeletePolicy::Trash);
+        let actions: Vec<(Action, Option<DeleteReason>)> = files.iter().map(|file| (file.action, file.delete_reason)).collect();
+        assert_eq!(actions, [
+            (Action::Delete, Some(DeleteReason::DuplicateLoser)),
+            (Action::Rename, None),
+            (Action::Delete, Some(DeleteReason::DuplicateLoser)),
+            (Action::Rename, None),
+        ]);
+        assert!(files[0].dest.is_empty());
+    }
+}
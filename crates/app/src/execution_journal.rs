@@ -0,0 +1,371 @@
+use chrono;
+use serde;
+use serde_json;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+use crate::atomic_file::{decode_partial_data, encode_data};
+
+// Executions write a journal into the root folder before touching any files
+// E.g. <root>/.journal/<folder>.jsonl, with nested folders like "Drama/Show" flattened into "Drama%2FShow.jsonl"
+// The first line lists every operation and each following line is the index of a completed operation
+// Compressed journals write each line as its own zstd frame so lines can still be appended
+pub const JOURNAL_FOLDER_NAME: &str = ".journal";
+const JOURNAL_EXTENSION: &str = "jsonl";
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Eq, PartialEq, Copy, Clone)]
+pub enum JournalAction {
+    Move,
+    Delete,
+}
+
+// Paths are relative to the root folder so the journal survives the library being moved
+#[serde_with::skip_serializing_none]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct JournalOperation {
+    pub action: JournalAction,
+    pub src: String,
+    pub dest: Option<String>,
+}
+
+impl JournalOperation {
+    pub fn new_move(src: &str, dest: &str) -> Self {
+        Self {
+            action: JournalAction::Move,
+            src: src.to_string(),
+            dest: Some(dest.to_string()),
+        }
+    }
+
+    pub fn new_delete(src: &str) -> Self {
+        Self {
+            action: JournalAction::Delete,
+            src: src.to_string(),
+            dest: None,
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+struct JournalHeader {
+    created: chrono::DateTime<chrono::Utc>,
+    folder_name: String,
+    operations: Vec<JournalOperation>,
+}
+
+pub struct ExecutionJournal {
+    root_path: String,
+    path: PathBuf,
+    header: JournalHeader,
+    is_done: Vec<bool>,
+    // The operation after the last marked one might have been applied right before the app stopped
+    in_flight_index: Option<usize>,
+    file: Option<tokio::fs::File>,
+    is_compressed: bool,
+}
+
+// Journals are only looked up at the top of the journal folder, the folder name itself is kept in the header
+fn get_journal_filename(folder_name: &str) -> String {
+    let mut filename = String::with_capacity(folder_name.len());
+    for c in folder_name.chars() {
+        match c {
+            '%' => filename.push_str("%25"),
+            '/' => filename.push_str("%2F"),
+            '\\' => filename.push_str("%5C"),
+            c => filename.push(c),
+        }
+    }
+    filename
+}
+
+pub fn get_journal_path(root_path: &str, folder_name: &str) -> PathBuf {
+    Path::new(root_path)
+        .join(JOURNAL_FOLDER_NAME)
+        .join(format!("{}.{}", get_journal_filename(folder_name), JOURNAL_EXTENSION))
+}
+
+// A file that isn't part of the plan could be sitting at the destination
+pub async fn move_without_overwrite(src: &Path, dest: &Path) -> Result<(), std::io::Error> {
+    // case only renames point to the same file on case insensitive filesystems
+    let is_case_change = src.to_string_lossy().to_lowercase() == dest.to_string_lossy().to_lowercase();
+    if !is_case_change && tokio::fs::try_exists(dest).await? {
+        return Err(std::io::Error::new(std::io::ErrorKind::AlreadyExists, "destination already exists"));
+    }
+    if let Some(parent_dir) = dest.parent() {
+        tokio::fs::create_dir_all(parent_dir).await?;
+    }
+    match tokio::fs::rename(src, dest).await {
+        Err(err) if err.kind() == std::io::ErrorKind::CrossesDevices => copy_across_devices(src, dest).await,
+        res => res,
+    }
+}
+
+// Archives usually live on another disk where a rename isn't possible
+// The copy is only renamed into place once it is complete so an interrupted copy is never mistaken for the file
+async fn copy_across_devices(src: &Path, dest: &Path) -> Result<(), std::io::Error> {
+    let mut partial_name = dest.file_name().unwrap_or_default().to_os_string();
+    partial_name.push(".partial");
+    let partial_path = dest.with_file_name(partial_name);
+    if let Err(err) = tokio::fs::copy(src, partial_path.as_path()).await {
+        let _ = tokio::fs::remove_file(partial_path.as_path()).await;
+        return Err(err);
+    }
+    tokio::fs::rename(partial_path.as_path(), dest).await?;
+    tokio::fs::remove_file(src).await
+}
+
+impl ExecutionJournal {
+    // Fails if an earlier execution of the folder was interrupted and hasn't been resolved yet
+    pub async fn create(root_path: &str, folder_name: &str, operations: Vec<JournalOperation>, is_compressed: bool) -> Result<Self, std::io::Error> {
+        let path = get_journal_path(root_path, folder_name);
+        if let Some(parent_dir) = path.parent() {
+            tokio::fs::create_dir_all(parent_dir).await?;
+        }
+        let header = JournalHeader {
+            created: chrono::Utc::now(),
+            folder_name: folder_name.to_string(),
+            operations,
+        };
+        let mut data = serde_json::to_string(&header).map_err(std::io::Error::from)?;
+        data.push('\n');
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create_new(true)
+            .append(true)
+            .open(path.as_path())
+            .await?;
+        file.write_all(encode_data(data, is_compressed)?.as_slice()).await?;
+        // Nothing is executed until every operation is safely on disk
+        file.sync_all().await?;
+
+        let is_done = vec![false; header.operations.len()];
+        Ok(Self {
+            root_path: root_path.to_string(),
+            path,
+            header,
+            is_done,
+            in_flight_index: None,
+            file: Some(file),
+            is_compressed,
+        })
+    }
+
+    pub async fn load_from_file(root_path: &str, path: &Path) -> Result<Self, std::io::Error> {
+        let data = decode_partial_data(tokio::fs::read(path).await?);
+        let mut lines = data.lines();
+        let header_line = lines.next().unwrap_or("");
+        let header: JournalHeader = serde_json::from_str(header_line).map_err(std::io::Error::from)?;
+        let mut is_done = vec![false; header.operations.len()];
+        // The last line may have been cut off so only well formed indices are used
+        for index in lines.filter_map(|line| line.trim().parse::<usize>().ok()) {
+            if let Some(is_done) = is_done.get_mut(index) {
+                *is_done = true;
+            }
+        }
+        // Operations are executed in order and each mark is synced before the next one starts
+        let in_flight_index = match is_done.iter().rposition(|is_done| *is_done) {
+            Some(index) => index + 1,
+            None => 0,
+        };
+        let in_flight_index = (in_flight_index < is_done.len()).then_some(in_flight_index);
+        Ok(Self {
+            root_path: root_path.to_string(),
+            path: path.to_path_buf(),
+            header,
+            is_done,
+            in_flight_index,
+            file: None,
+            is_compressed: false,
+        })
+    }
+
+    pub async fn mark_done(&mut self, index: usize) -> Result<(), std::io::Error> {
+        self.is_done[index] = true;
+        if let Some(file) = self.file.as_mut() {
+            file.write_all(encode_data(format!("{}\n", index), self.is_compressed)?.as_slice()).await?;
+            file.sync_data().await?;
+        }
+        Ok(())
+    }
+
+    pub async fn remove(mut self) -> Result<(), std::io::Error> {
+        self.file = None;
+        tokio::fs::remove_file(self.path.as_path()).await
+    }
+
+    pub fn get_path(&self, relative_path: &str) -> PathBuf {
+        Path::new(self.root_path.as_str()).join(relative_path)
+    }
+
+    pub async fn execute_operation(&self, index: usize) -> Result<(), std::io::Error> {
+        let operation = &self.header.operations[index];
+        let src = self.get_path(operation.src.as_str());
+        match (operation.action, operation.dest.as_deref()) {
+            (JournalAction::Move, Some(dest)) => move_without_overwrite(src.as_path(), self.get_path(dest).as_path()).await,
+            (JournalAction::Delete, _) => tokio::fs::remove_file(src).await,
+            (JournalAction::Move, None) => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "move is missing a destination")),
+        }
+    }
+
+    // Completed operations might not be marked if the app stopped right after them
+    // Only the in flight operation is checked on disk since swaps bring back the sources of earlier moves
+    async fn is_operation_applied(&self, index: usize) -> bool {
+        if self.is_done[index] {
+            return true;
+        }
+        if self.in_flight_index != Some(index) {
+            return false;
+        }
+        let operation = &self.header.operations[index];
+        let is_src_exists = tokio::fs::try_exists(self.get_path(operation.src.as_str())).await.unwrap_or(true);
+        if is_src_exists {
+            return false;
+        }
+        match operation.dest.as_deref() {
+            Some(dest) => tokio::fs::try_exists(self.get_path(dest)).await.unwrap_or(false),
+            None => true,
+        }
+    }
+
+    // Executes the remaining operations in their original order
+    pub async fn resume(&mut self) -> Vec<String> {
+        let mut errors = Vec::new();
+        for index in 0..self.header.operations.len() {
+            if self.is_operation_applied(index).await {
+                self.is_done[index] = true;
+                continue;
+            }
+            let operation = &self.header.operations[index];
+            match self.execute_operation(index).await {
+                Ok(()) => self.is_done[index] = true,
+                Err(err) => errors.push(format!("IO error while resuming operation on '{}': {}", operation.src, err)),
+            }
+        }
+        errors
+    }
+
+    // Undoes the applied operations in reverse order so chains and swaps unwind correctly
+    pub async fn rollback(&mut self) -> Vec<String> {
+        let mut errors = Vec::new();
+        for index in (0..self.header.operations.len()).rev() {
+            if !self.is_operation_applied(index).await {
+                continue;
+            }
+            let operation = &self.header.operations[index];
+            let dest = match operation.dest.as_deref() {
+                Some(dest) => dest,
+                None => {
+                    errors.push(format!("Cannot restore '{}' since it was permanently deleted", operation.src));
+                    continue;
+                },
+            };
+            let src = self.get_path(operation.src.as_str());
+            match move_without_overwrite(self.get_path(dest).as_path(), src.as_path()).await {
+                Ok(()) => self.is_done[index] = false,
+                Err(err) => errors.push(format!("IO error while restoring '{}': {}", operation.src, err)),
+            }
+        }
+        errors
+    }
+
+    pub fn get_created(&self) -> &chrono::DateTime<chrono::Utc> {
+        &self.header.created
+    }
+
+    pub fn get_folder_name(&self) -> &str {
+        self.header.folder_name.as_str()
+    }
+
+    pub fn get_operations(&self) -> &[JournalOperation] {
+        self.header.operations.as_slice()
+    }
+
+    pub fn get_total_done(&self) -> usize {
+        self.is_done.iter().filter(|is_done| **is_done).count()
+    }
+}
+
+// Any journal left in the root folder belongs to an execution that never finished
+pub async fn load_journals(root_path: &str) -> Result<Vec<Result<ExecutionJournal, (PathBuf, std::io::Error)>>, std::io::Error> {
+    let journal_folder = Path::new(root_path).join(JOURNAL_FOLDER_NAME);
+    let mut entries = match tokio::fs::read_dir(journal_folder).await {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+
+    let mut journals = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some(JOURNAL_EXTENSION) {
+            continue;
+        }
+        let journal = ExecutionJournal::load_from_file(root_path, path.as_path()).await;
+        journals.push(journal.map_err(|err| (path, err)));
+    }
+    journals.sort_by(|a, b| match (a, b) {
+        (Ok(a), Ok(b)) => a.get_folder_name().cmp(b.get_folder_name()),
+        (Ok(_), Err(_)) => std::cmp::Ordering::Less,
+        (Err(_), Ok(_)) => std::cmp::Ordering::Greater,
+        (Err(a), Err(b)) => a.0.cmp(&b.0),
+    });
+    Ok(journals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(root: &Path, name: &str) -> String {
+        std::fs::read_to_string(root.join(name)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn interrupted_swap_is_rolled_back() {
+        let root = tempfile::TempDir::new().unwrap();
+        let root_path = root.path().to_str().unwrap();
+        std::fs::write(root.path().join("a.mkv"), "a").unwrap();
+        std::fs::write(root.path().join("b.mkv"), "b").unwrap();
+        let operations = vec![
+            JournalOperation::new_move("a.mkv", "a.mkv.tmp"),
+            JournalOperation::new_move("b.mkv", "a.mkv"),
+            JournalOperation::new_move("a.mkv.tmp", "b.mkv"),
+        ];
+        let mut journal = ExecutionJournal::create(root_path, "Show", operations, false).await.unwrap();
+        journal.execute_operation(0).await.unwrap();
+        journal.mark_done(0).await.unwrap();
+        // stopped before the second move was marked
+        journal.execute_operation(1).await.unwrap();
+        drop(journal);
+
+        let mut journal = ExecutionJournal::load_from_file(root_path, get_journal_path(root_path, "Show").as_path()).await.unwrap();
+        assert_eq!(journal.get_total_done(), 1);
+        assert!(journal.rollback().await.is_empty());
+        assert_eq!(read(root.path(), "a.mkv"), "a");
+        assert_eq!(read(root.path(), "b.mkv"), "b");
+        assert!(!root.path().join("a.mkv.tmp").exists());
+    }
+
+    #[tokio::test]
+    async fn nested_folder_journal_is_recovered() {
+        let root = tempfile::TempDir::new().unwrap();
+        let root_path = root.path().to_str().unwrap();
+        std::fs::create_dir_all(root.path().join("Drama/Show")).unwrap();
+        std::fs::write(root.path().join("Drama/Show/a.mkv"), "a").unwrap();
+        let operations = vec![JournalOperation::new_move("Drama/Show/a.mkv", "Drama/Show/Show S01E01.mkv")];
+        let journal = ExecutionJournal::create(root_path, "Drama/Show", operations.clone(), false).await.unwrap();
+        journal.execute_operation(0).await.unwrap();
+        drop(journal);
+        assert!(root.path().join(JOURNAL_FOLDER_NAME).join("Drama%2FShow.jsonl").exists());
+
+        let mut journals = load_journals(root_path).await.unwrap();
+        assert_eq!(journals.len(), 1);
+        let mut journal = journals.remove(0).unwrap();
+        assert_eq!(journal.get_folder_name(), "Drama/Show");
+        assert!(journal.rollback().await.is_empty());
+        assert_eq!(read(root.path(), "Drama/Show/a.mkv"), "a");
+        journal.remove().await.unwrap();
+
+        // the folder can be executed again once the interrupted execution is resolved
+        assert!(ExecutionJournal::create(root_path, "Drama/Show", operations, false).await.is_ok());
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    RepeatedFailures,
+    DestinationTaken,
+    NoArchivePath,
+}
+
+impl SkipReason {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            SkipReason::RepeatedFailures => "Skipped since it failed repeatedly",
+            SkipReason::DestinationTaken => "Skipped since the destination is taken",
+            SkipReason::NoArchivePath => "Skipped since no archive path is set",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExecutionOutcome {
+    Applied,
+    Skipped(SkipReason),
+    Failed(String),
+}
+
+// What happened to each file in the last execution, kept until the folder is refreshed by hand
+// Keyed by where the file is afterwards so renamed files still show their outcome
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExecutionOutcomes {
+    outcomes: HashMap<String, ExecutionOutcome>,
+}
+
+impl ExecutionOutcomes {
+    pub fn get(&self, path: &str) -> Option<&ExecutionOutcome> {
+        self.outcomes.get(path)
+    }
+
+    pub fn insert(&mut self, path: &str, outcome: ExecutionOutcome) {
+        self.outcomes.insert(path.to_string(), outcome);
+    }
+
+    // Operations without a source, e.g. moves out of a temporary name, belong to an earlier operation
+    pub fn add_operation_results(
+        &mut self,
+        operation_sources: &[Option<String>], failures: &[(usize, String)],
+        final_paths: &HashMap<String, String>,
+    ) {
+        for (index, src) in operation_sources.iter().enumerate() {
+            let src = match src {
+                Some(src) => src.as_str(),
+                None => continue,
+            };
+            match failures.iter().find(|(failed_index, _)| *failed_index == index) {
+                Some((_, error)) => self.insert(src, ExecutionOutcome::Failed(error.clone())),
+                None => {
+                    let path = final_paths.get(src).map(|path| path.as_str()).unwrap_or(src);
+                    self.insert(path, ExecutionOutcome::Applied);
+                },
+            }
+        }
+    }
+
+    pub fn get_total_failed(&self) -> usize {
+        self.outcomes.values().filter(|outcome| matches!(outcome, ExecutionOutcome::Failed(_))).count()
+    }
+
+    pub fn clear(&mut self) {
+        self.outcomes.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.outcomes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renamed_files_keep_their_outcome() {
+        let mut outcomes = ExecutionOutcomes::default();
+        let sources = [Some("a.mkv".to_string()), None, Some("b.mkv".to_string()), Some("c.nfo".to_string())];
+        let failures = [(2, "access denied".to_string())];
+        let final_paths = HashMap::from([("a.mkv".to_string(), "Season 01/a.mkv".to_string())]);
+        outcomes.add_operation_results(&sources, &failures, &final_paths);
+        outcomes.insert("d.mkv", ExecutionOutcome::Skipped(SkipReason::DestinationTaken));
+
+        assert_eq!(outcomes.get("Season 01/a.mkv"), Some(&ExecutionOutcome::Applied));
+        assert_eq!(outcomes.get("a.mkv"), None);
+        assert_eq!(outcomes.get("b.mkv"), Some(&ExecutionOutcome::Failed("access denied".to_string())));
+        assert_eq!(outcomes.get("c.nfo"), Some(&ExecutionOutcome::Applied));
+        assert_eq!(outcomes.get_total_failed(), 1);
+    }
+}
@@ -0,0 +1,106 @@
+use chrono;
+use serde;
+use serde_json;
+
+pub const DEFAULT_MAX_EXECUTION_FAILURES: u32 = 3;
+
+// A file whose change failed to execute, e.g. since it is locked by another program
+// E.g. <folder>/execution_skips.json
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct FailedFile {
+    // Relative to the folder
+    pub src: String,
+    pub failures: u32,
+    pub last_error: String,
+    pub last_failed: chrono::DateTime<chrono::Utc>,
+}
+
+// Files which failed too many times in a row are left out of executions until they are retried
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExecutionSkipList {
+    files: Vec<FailedFile>,
+}
+
+impl ExecutionSkipList {
+    pub fn get_files(&self) -> &[FailedFile] {
+        self.files.as_slice()
+    }
+
+    pub fn get_failures(&self, src: &str) -> u32 {
+        self.files.iter().find(|file| file.src == src).map(|file| file.failures).unwrap_or(0)
+    }
+
+    // A limit of 0 never skips files
+    pub fn is_skipped(&self, src: &str, max_failures: u32) -> bool {
+        max_failures > 0 && self.get_failures(src) >= max_failures
+    }
+
+    pub fn get_skipped(&self, max_failures: u32) -> Vec<&FailedFile> {
+        self.files.iter().filter(|file| self.is_skipped(file.src.as_str(), max_failures)).collect()
+    }
+
+    pub fn record_failure(&mut self, src: &str, error: &str, now: chrono::DateTime<chrono::Utc>) {
+        match self.files.iter_mut().find(|file| file.src == src) {
+            Some(file) => {
+                file.failures += 1;
+                file.last_error = error.to_string();
+                file.last_failed = now;
+            },
+            None => self.files.push(FailedFile {
+                src: src.to_string(),
+                failures: 1,
+                last_error: error.to_string(),
+                last_failed: now,
+            }),
+        }
+    }
+
+    // Returns whether the file had failed before, only consecutive failures count
+    pub fn remove(&mut self, src: &str) -> bool {
+        let total_files = self.files.len();
+        self.files.retain(|file| file.src != src);
+        self.files.len() != total_files
+    }
+
+    pub fn clear(&mut self) {
+        self.files.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+pub fn deserialize_execution_skips(data: &str) -> Result<ExecutionSkipList, serde_json::Error> {
+    let files: Vec<FailedFile> = serde_json::from_str(data)?;
+    Ok(ExecutionSkipList { files })
+}
+
+pub fn serialize_execution_skips(skips: &ExecutionSkipList) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(&skips.files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn files_are_skipped_after_repeated_failures() {
+        let mut skips = ExecutionSkipList::default();
+        let now = chrono::Utc::now();
+        skips.record_failure("a.mkv", "locked", now);
+        skips.record_failure("a.mkv", "access denied", now);
+        assert!(!skips.is_skipped("a.mkv", 3));
+        skips.record_failure("a.mkv", "access denied", now);
+        assert!(skips.is_skipped("a.mkv", 3));
+        assert!(!skips.is_skipped("a.mkv", 0));
+        assert_eq!(skips.get_skipped(3)[0].last_error, "access denied");
+
+        let data = serialize_execution_skips(&skips).unwrap();
+        assert_eq!(deserialize_execution_skips(data.as_str()).unwrap(), skips);
+
+        assert!(skips.remove("a.mkv"));
+        assert!(!skips.remove("a.mkv"));
+        assert_eq!(skips.get_failures("a.mkv"), 0);
+    }
+}
@@ -0,0 +1,200 @@
+use crate::file_descriptor::is_title_only_change;
+use crate::file_intent::{Action, DeletePolicy};
+
+// Kinds of changes an execution applies, so renames can run now and deletes later
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutionFilter {
+    pub is_renames: bool,
+    pub is_deletes: bool,
+    pub is_archives: bool,
+    // Only renames of already renamed files which fix up the episode title
+    pub is_title_only: bool,
+}
+
+impl Default for ExecutionFilter {
+    fn default() -> Self {
+        Self {
+            is_renames: true,
+            is_deletes: true,
+            is_archives: true,
+            is_title_only: false,
+        }
+    }
+}
+
+impl ExecutionFilter {
+    pub fn is_action_included(&self, action: Action) -> bool {
+        match action {
+            Action::Delete => self.is_deletes,
+            Action::Archive => self.is_archives,
+            action if action.is_move() => self.is_renames,
+            _ => false,
+        }
+    }
+
+    pub fn title_fixes() -> Self {
+        Self {
+            is_renames: true,
+            is_deletes: false,
+            is_archives: false,
+            is_title_only: true,
+        }
+    }
+
+    pub fn is_file_included(&self, action: Action, src: &str, dest: &str) -> bool {
+        if !self.is_action_included(action) {
+            return false;
+        }
+        !self.is_title_only || (action == Action::Rename && is_title_only_change(src, dest))
+    }
+
+    pub fn is_everything(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+// What executing the enabled changes of one or more folders will do, shown before anything is deleted
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ExecutionSummary {
+    pub total_folders: usize,
+    pub total_moves: usize,
+    pub move_size: u64,
+    pub total_archives: usize,
+    pub total_trashed: usize,
+    pub trashed_size: u64,
+    pub total_permanent_deletes: usize,
+    pub permanent_delete_size: u64,
+    // Moves never overwrite, so moves onto a taken destination are skipped instead
+    pub total_skipped_conflicts: usize,
+    // Files on the skip list after failing too many times in a row
+    pub total_skipped_failures: usize,
+}
+
+impl ExecutionSummary {
+    // Mirrors how the changes are turned into operations when a folder is executed
+    pub fn add_file(&mut self, action: Action, delete_policy: DeletePolicy, is_conflict: bool, has_archive_path: bool, size: u64) {
+        match action {
+            Action::Delete => match delete_policy {
+                DeletePolicy::Trash => {
+                    self.total_trashed += 1;
+                    self.trashed_size += size;
+                },
+                // Files that are manually marked for deletion use the permanent policy
+                DeletePolicy::Permanent | DeletePolicy::Ignore => {
+                    self.total_permanent_deletes += 1;
+                    self.permanent_delete_size += size;
+                },
+            },
+            Action::Archive if has_archive_path => self.total_archives += 1,
+            action if action.is_move() && is_conflict => self.total_skipped_conflicts += 1,
+            action if action.is_move() => {
+                self.total_moves += 1;
+                self.move_size += size;
+            },
+            _ => {},
+        }
+    }
+
+    pub fn merge(&mut self, other: &ExecutionSummary) {
+        self.total_folders += other.total_folders;
+        self.total_moves += other.total_moves;
+        self.move_size += other.move_size;
+        self.total_archives += other.total_archives;
+        self.total_trashed += other.total_trashed;
+        self.trashed_size += other.trashed_size;
+        self.total_permanent_deletes += other.total_permanent_deletes;
+        self.permanent_delete_size += other.permanent_delete_size;
+        self.total_skipped_conflicts += other.total_skipped_conflicts;
+        self.total_skipped_failures += other.total_skipped_failures;
+    }
+
+    // Leaves out the changes which the filter skips
+    pub fn get_filtered(&self, filter: &ExecutionFilter) -> ExecutionSummary {
+        let mut summary = self.clone();
+        if !filter.is_renames {
+            summary.total_moves = 0;
+            summary.move_size = 0;
+            summary.total_skipped_conflicts = 0;
+        }
+        if !filter.is_deletes {
+            summary.total_trashed = 0;
+            summary.trashed_size = 0;
+            summary.total_permanent_deletes = 0;
+            summary.permanent_delete_size = 0;
+        }
+        if !filter.is_archives {
+            summary.total_archives = 0;
+        }
+        summary
+    }
+
+    pub fn get_total_changes(&self) -> usize {
+        self.total_moves + self.total_archives + self.total_trashed + self.total_permanent_deletes
+    }
+
+    pub fn is_destructive(&self) -> bool {
+        self.total_trashed > 0 || self.total_permanent_deletes > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deletes_are_split_by_policy() {
+        let mut summary = ExecutionSummary::default();
+        summary.add_file(Action::Delete, DeletePolicy::Trash, false, false, 10);
+        summary.add_file(Action::Delete, DeletePolicy::Permanent, false, false, 20);
+        summary.add_file(Action::Delete, DeletePolicy::Ignore, false, false, 30);
+        assert_eq!((summary.total_trashed, summary.trashed_size), (1, 10));
+        assert_eq!((summary.total_permanent_deletes, summary.permanent_delete_size), (2, 50));
+        assert!(summary.is_destructive());
+    }
+
+    #[test]
+    fn conflicting_moves_are_skipped() {
+        let mut summary = ExecutionSummary::default();
+        summary.add_file(Action::Rename, DeletePolicy::Trash, false, false, 5);
+        summary.add_file(Action::Rename, DeletePolicy::Trash, true, false, 5);
+        summary.add_file(Action::Archive, DeletePolicy::Trash, false, false, 5);
+        summary.add_file(Action::Complete, DeletePolicy::Trash, false, false, 5);
+        assert_eq!((summary.total_moves, summary.move_size), (1, 5));
+        assert_eq!(summary.total_skipped_conflicts, 1);
+        assert_eq!(summary.total_archives, 0);
+        assert!(!summary.is_destructive());
+    }
+
+    #[test]
+    fn filter_skips_deletes() {
+        let filter = ExecutionFilter { is_deletes: false, ..ExecutionFilter::default() };
+        assert!(filter.is_action_included(Action::Rename));
+        assert!(filter.is_action_included(Action::Extra));
+        assert!(!filter.is_action_included(Action::Delete));
+        assert!(!filter.is_action_included(Action::Complete));
+        assert!(!filter.is_everything());
+
+        let mut summary = ExecutionSummary::default();
+        summary.add_file(Action::Rename, DeletePolicy::Trash, false, false, 5);
+        summary.add_file(Action::Delete, DeletePolicy::Trash, false, false, 10);
+        let filtered = summary.get_filtered(&filter);
+        assert_eq!(filtered.get_total_changes(), 1);
+        assert!(!filtered.is_destructive());
+    }
+
+    #[test]
+    fn title_fixes_keep_everything_but_the_title() {
+        let filter = ExecutionFilter::title_fixes();
+        let is_included = |src: &str, dest: &str| filter.is_file_included(Action::Rename, src, dest);
+        assert!(is_included("Season 01/Show-S01E01-TBA.mkv", "Season 01/Show-S01E01-Pilot.mkv"));
+        assert!(is_included("Season 01/Show-S01E01-pt1.[1080p].mkv", "Season 01/Show-S01E01-Pilot-pt1.[1080p].mkv"));
+        assert!(is_included("Season 01/Show-S01E01-Pilot.mkv", "Season 01/Show-S01E01.mkv"));
+        assert!(!is_included("Season 01/Show-S01E01-Pilot.mkv", "Season 01/Show-S01E02-Pilot.mkv"));
+        assert!(!is_included("Season 01/Show-S01E01-Pilot.mkv", "Season 02/Show-S01E01-Pilot.mkv"));
+        assert!(!is_included("Season 01/Show-S01E01-Pilot.mkv", "Season 01/Other-S01E01-Pilot.mkv"));
+        assert!(!is_included("Season 01/Show-S01E01-Pilot.avi", "Season 01/Show-S01E01-Title.mkv"));
+        assert!(!is_included("Show.S01E01.720p.mkv", "Season 01/Show-S01E01-Pilot.mkv"));
+        assert!(!filter.is_file_included(Action::Delete, "Season 01/Show-S01E01-TBA.mkv", "Season 01/Show-S01E01-Pilot.mkv"));
+        assert!(!filter.is_everything());
+    }
+}
@@ -0,0 +1,102 @@
+use tvdb::models::{RemoteId, Series};
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum ExternalProvider {
+    Tvdb,
+    Imdb,
+    Tmdb,
+}
+
+impl ExternalProvider {
+    pub fn iterator() -> std::slice::Iter<'static, Self> {
+        static PROVIDERS: [ExternalProvider;3] = [
+            ExternalProvider::Tvdb,
+            ExternalProvider::Imdb,
+            ExternalProvider::Tmdb,
+        ];
+        PROVIDERS.iter()
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            ExternalProvider::Tvdb => "TVDB",
+            ExternalProvider::Imdb => "IMDB",
+            ExternalProvider::Tmdb => "TMDB",
+        }
+    }
+
+    // Remote ids name their source differently depending on the api version
+    fn is_source_name(&self, source_name: &str) -> bool {
+        let names: &[&str] = match self {
+            ExternalProvider::Tvdb => &["tvdb", "thetvdb"],
+            ExternalProvider::Imdb => &["imdb", "imdb.com"],
+            ExternalProvider::Tmdb => &["tmdb", "themoviedb", "themoviedb.com"],
+        };
+        names.iter().any(|name| name.eq_ignore_ascii_case(source_name.trim()))
+    }
+
+    pub fn get_url(&self, id: &str) -> String {
+        match self {
+            ExternalProvider::Tvdb => format!("https://thetvdb.com/?tab=series&id={}", id),
+            ExternalProvider::Imdb => format!("https://www.imdb.com/title/{}", id),
+            ExternalProvider::Tmdb => format!("https://www.themoviedb.org/tv/{}", id),
+        }
+    }
+}
+
+// Kept in the series cache so the series can be looked up on another provider without searching for it again
+pub fn apply_remote_ids(series: &mut Series, remote_ids: &[RemoteId]) {
+    for remote_id in remote_ids.iter().filter(|remote_id| !remote_id.id.trim().is_empty()) {
+        let id = Some(remote_id.id.trim().to_string());
+        if ExternalProvider::Imdb.is_source_name(remote_id.source_name.as_str()) {
+            series.imdb_id = id;
+        } else if ExternalProvider::Tmdb.is_source_name(remote_id.source_name.as_str()) {
+            series.tmdb_id = id;
+        }
+    }
+}
+
+pub fn get_external_id(series: &Series, provider: ExternalProvider) -> Option<String> {
+    let id = match provider {
+        ExternalProvider::Tvdb => return Some(series.id.to_string()),
+        ExternalProvider::Imdb => series.imdb_id.as_deref(),
+        ExternalProvider::Tmdb => series.tmdb_id.as_deref(),
+    };
+    id.filter(|id| !id.is_empty()).map(|id| id.to_string())
+}
+
+pub fn get_external_links(series: &Series) -> Vec<(ExternalProvider, String)> {
+    ExternalProvider::iterator()
+        .filter_map(|provider| Some((*provider, provider.get_url(get_external_id(series, *provider)?.as_str()))))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn get_series() -> Series {
+        serde_json::from_value(json!({ "id": 81189, "seriesName": "Example Show", "imdbId": "" })).unwrap()
+    }
+
+    #[test]
+    fn remote_ids_are_matched_by_source() {
+        let mut series = get_series();
+        let remote_ids: Vec<RemoteId> = serde_json::from_value(json!([
+            { "id": "tt0903747", "sourceName": "IMDB" },
+            { "id": "1396", "sourceName": "TheMovieDB.com" },
+            { "id": "", "sourceName": "TMDB" },
+            { "id": "12345", "sourceName": "Wikidata" },
+        ])).unwrap();
+        apply_remote_ids(&mut series, remote_ids.as_slice());
+        assert_eq!(series.imdb_id.as_deref(), Some("tt0903747"));
+        assert_eq!(series.tmdb_id.as_deref(), Some("1396"));
+    }
+
+    #[test]
+    fn links_skip_missing_ids() {
+        let links = get_external_links(&get_series());
+        assert_eq!(links, vec![(ExternalProvider::Tvdb, "https://thetvdb.com/?tab=series&id=81189".to_string())]);
+    }
+}
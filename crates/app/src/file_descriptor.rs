@@ -1,12 +1,24 @@
 use regex::Regex;
 use lazy_static::lazy_static;
 use crate::transliterate::transliterate;
+use std::borrow::Cow;
+use unicode_normalization::{IsNormalized, UnicodeNormalization, char::is_combining_mark, is_nfc_quick};
 
 #[derive(Debug)]
 pub struct FileDescriptor {
     pub title: String,
     pub season: u32,
     pub episode: u32,
+    // Split episodes like "S01E05a" or "S01E05 Part 2"
+    pub part: Option<u32>,
+    pub tags: Vec<String>,
+    pub extension: String,
+}
+
+#[derive(Debug)]
+pub struct AbsoluteFileDescriptor {
+    pub title: String,
+    pub episode: u32,
     pub tags: Vec<String>,
     pub extension: String,
 }
@@ -23,12 +35,131 @@ pub fn find_tags(tags_str: &str) -> Vec<String> {
         .collect()
 }
 
+const NUMBER_WORDS: [&str; 20] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+    "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen", "twenty",
+];
+const ORDINAL_WORDS: [&str; 10] = [
+    "first", "second", "third", "fourth", "fifth", "sixth", "seventh", "eighth", "ninth", "tenth",
+];
+// Season and part numbers never get large enough to need anything past L
+const ROMAN_NUMERALS: [(u32, &str); 7] = [
+    (50, "L"), (40, "XL"), (10, "X"), (9, "IX"), (5, "V"), (4, "IV"), (1, "I"),
+];
+
+fn get_roman_numeral(mut number: u32) -> String {
+    let mut numeral = String::new();
+    for (digit, symbol) in ROMAN_NUMERALS {
+        while number >= digit {
+            numeral.push_str(symbol);
+            number -= digit;
+        }
+    }
+    numeral
+}
+
+fn parse_roman_numeral(value: &str) -> Option<u32> {
+    let value = value.to_ascii_uppercase();
+    let mut remaining = value.as_str();
+    let mut number = 0;
+    for (digit, symbol) in ROMAN_NUMERALS {
+        while let Some(rest) = remaining.strip_prefix(symbol) {
+            remaining = rest;
+            number += digit;
+        }
+    }
+    // only the canonical form is accepted so other letter runs like "IXI" aren't read as numbers
+    let is_canonical = remaining.is_empty() && number > 0 && get_roman_numeral(number) == value;
+    is_canonical.then_some(number)
+}
+
+// Miniseries and anime releases write seasons as "Season Two", "Second Season" or "Part II"
+pub fn parse_season_number(value: &str) -> Option<u32> {
+    if let Ok(number) = value.parse() {
+        return Some(number);
+    }
+    let word = value.to_ascii_lowercase();
+    if let Some(index) = NUMBER_WORDS.iter().position(|other| *other == word) {
+        return Some(index as u32 + 1);
+    }
+    if let Some(index) = ORDINAL_WORDS.iter().position(|other| *other == word) {
+        return Some(index as u32 + 1);
+    }
+    parse_roman_numeral(value)
+}
+
+// Season folders like "Season 3", "S03", "Season Two", "Part III" or "Second Season"
+pub fn get_folder_season(folder_name: &str) -> Option<u32> {
+    lazy_static! {
+        static ref FOLDER_SEASON_REGEXES: Vec<Regex> = vec![
+            Regex::new(r"(?i)^(?:season|series|part)[\s\._\-]*([a-z0-9]+)$").unwrap(),
+            Regex::new(r"(?i)^s(\d+)$").unwrap(),
+            Regex::new(r"(?i)^([a-z]+)[\s\._\-]+season$").unwrap(),
+        ];
+    }
+    FOLDER_SEASON_REGEXES
+        .iter()
+        .find_map(|re| re.captures(folder_name.trim()))
+        .and_then(|res| parse_season_number(&res[1]))
+}
+
+// Releases of the same episode in different qualities, e.g. ["2160p", "HDR"] for "Show.S01E01.4K.HDR10.mkv"
+pub fn get_quality_tags(filename: &str) -> Vec<String> {
+    lazy_static! {
+        static ref RESOLUTION_REGEX: Regex = Regex::new(r"(?i)(?:^|[^a-z0-9])(480p|576p|720p|1080p|2160p|4k|uhd)(?:[^a-z0-9]|$)").unwrap();
+        static ref DOLBY_VISION_REGEX: Regex = Regex::new(r"(?i)(?:^|[^a-z0-9])(dv|dovi|dolby[\s\._\-]?vision)(?:[^a-z0-9]|$)").unwrap();
+        static ref HDR_REGEX: Regex = Regex::new(r"(?i)(?:^|[^a-z0-9])hdr(?:10\+?)?(?:[^a-z0-9]|$)").unwrap();
+    }
+    let mut tags = Vec::new();
+    if let Some(res) = RESOLUTION_REGEX.captures(filename) {
+        let resolution = res[1].to_ascii_lowercase();
+        match resolution.as_str() {
+            "4k" | "uhd" => tags.push("2160p".to_string()),
+            _ => tags.push(resolution),
+        }
+    }
+    if DOLBY_VISION_REGEX.is_match(filename) {
+        tags.push("DV".to_string());
+    }
+    if HDR_REGEX.is_match(filename) {
+        tags.push("HDR".to_string());
+    }
+    tags
+}
+
+// Reads the part of a split episode from the text after its episode number, e.g. "a" in "S01E05a" or "Part.2"
+pub fn get_episode_part(rest: &str) -> Option<u32> {
+    lazy_static! {
+        static ref PART_LETTER_REGEX: Regex = Regex::new(r"^([a-dA-D])(?:[^a-zA-Z]|$)").unwrap();
+        static ref PART_REGEX: Regex = Regex::new(r"(?i)\b(?:part|pt)[\s\._\-]*([a-z0-9]+)\b").unwrap();
+    }
+    if let Some(res) = PART_LETTER_REGEX.captures(rest) {
+        let letter = res[1].to_ascii_lowercase().chars().next()?;
+        return Some(letter as u32 - 'a' as u32 + 1);
+    }
+    PART_REGEX.captures(rest).and_then(|res| parse_season_number(&res[1]))
+}
+
+// Episodes listed separately for each part have titles like "Title (1)", "Title (Part 2)" or "Title, Part Two"
+pub fn get_title_part(title: &str) -> Option<u32> {
+    lazy_static! {
+        static ref TITLE_PART_REGEXES: Vec<Regex> = vec![
+            Regex::new(r"(?i)\((?:part|pt)?[\s\.]*([a-z0-9]+)\)\s*$").unwrap(),
+            Regex::new(r"(?i)\b(?:part|pt)[\s\.]*([a-z0-9]+)\s*$").unwrap(),
+        ];
+    }
+    TITLE_PART_REGEXES
+        .iter()
+        .find_map(|re| re.captures(title.trim()))
+        .and_then(|res| parse_season_number(&res[1]))
+}
 
 pub fn get_descriptor(filename: &str) -> Option<FileDescriptor> {
     lazy_static! {
         static ref SEASON_EPISODE_EXT_REGEXES: Vec<Regex> = vec![
             Regex::new(format!("{}{}{}", TITLE_PATTERN, r"[Ss](\d+)\s*[Ee](\d+)(.*)", EXT_PATTERN).as_str()).unwrap(),
             Regex::new(format!("{}{}{}", TITLE_PATTERN, r"[Ss]eason\s*(\d+)\s*[Ee]pisode\s*(\d+)(.*)", EXT_PATTERN).as_str()).unwrap(),
+            Regex::new(format!("{}{}{}", TITLE_PATTERN, r"(?:[Ss]eason|[Pp]art)[\s\._]*([a-zA-Z]+)[\s\._\-]*[Ee](?:p(?:isode)?)?[\s\._]*(\d+)(.*)", EXT_PATTERN).as_str()).unwrap(),
             Regex::new(format!("{}{}{}", TITLE_PATTERN, r"(\d+)\s*x\s*(\d+)(.*)", EXT_PATTERN).as_str()).unwrap(),
             Regex::new(format!("{}{}{}", TITLE_PATTERN, r"[^\w]+(\d)(\d\d)[^\w]+(.*)", EXT_PATTERN).as_str()).unwrap(),
         ];
@@ -36,10 +167,15 @@ pub fn get_descriptor(filename: &str) -> Option<FileDescriptor> {
 
     for re in SEASON_EPISODE_EXT_REGEXES.iter() {
         if let Some(res) = re.captures(filename) {
+            let season = match parse_season_number(&res[2]) {
+                Some(season) => season,
+                None => continue,
+            };
             return Some(FileDescriptor {
                 title: res[1].to_string(),
-                season: res[2].parse().unwrap_or(0),
+                season,
                 episode: res[3].parse().unwrap_or(0),
+                part: get_episode_part(&res[4]),
                 tags: find_tags(&res[4]),
                 extension: res[5].to_string(),
             });
@@ -48,6 +184,58 @@ pub fn get_descriptor(filename: &str) -> Option<FileDescriptor> {
     None
 }
 
+// Files inside a season folder sometimes only have an episode number, e.g. "Season Two/Show - Episode 05.mkv"
+pub fn get_folder_descriptor(folder_name: &str, filename: &str) -> Option<FileDescriptor> {
+    lazy_static! {
+        static ref EPISODE_EXT_REGEX: Regex = Regex::new(
+            format!("{}{}{}", TITLE_PATTERN, r"\b[Ee](?:p(?:isode)?)?[\s\._]*(\d+)(.*)", EXT_PATTERN).as_str()
+        ).unwrap();
+    }
+
+    let season = get_folder_season(folder_name)?;
+    let res = EPISODE_EXT_REGEX.captures(filename)?;
+    Some(FileDescriptor {
+        title: res[1].to_string(),
+        season,
+        episode: res[2].parse().ok()?,
+        part: get_episode_part(&res[3]),
+        tags: find_tags(&res[3]),
+        extension: res[4].to_string(),
+    })
+}
+
+// Fansub releases use absolute numbering, e.g. "[Group] Title - 13 [1080p].mkv"
+pub fn get_absolute_descriptor(filename: &str) -> Option<AbsoluteFileDescriptor> {
+    lazy_static! {
+        static ref ABSOLUTE_EPISODE_EXT_REGEX: Regex = Regex::new(
+            format!("{}{}", r"^(?:\[[^\]]*\][\s_]*)?([^\[\]\(\)]+?)[\s_]+-[\s_]+(\d{1,4})(?:v\d+)?([^\d].*)?", EXT_PATTERN).as_str()
+        ).unwrap();
+    }
+
+    let res = ABSOLUTE_EPISODE_EXT_REGEX.captures(filename)?;
+    Some(AbsoluteFileDescriptor {
+        title: res[1].to_string(),
+        episode: res[2].parse().ok()?,
+        tags: res.get(3).map(|x| find_tags(x.as_str())).unwrap_or_default(),
+        extension: res[4].to_string(),
+    })
+}
+
+// Titles fetched on different platforms can be in NFD instead of NFC form
+// Most filenames are already composed so they are only copied when something changes
+pub fn normalize_title(value: &str, is_strip_combining_marks: bool) -> Cow<'_, str> {
+    if !is_strip_combining_marks {
+        return match is_nfc_quick(value.chars()) {
+            IsNormalized::Yes => Cow::Borrowed(value),
+            _ => Cow::Owned(value.nfc().collect()),
+        };
+    }
+    if value.is_ascii() {
+        return Cow::Borrowed(value);
+    }
+    Cow::Owned(value.nfd().filter(|c| !is_combining_mark(*c)).nfc().collect())
+}
+
 pub fn clean_series_name(value: &str) -> String {
     lazy_static! {
         static ref TAG_REGEX: Regex = Regex::new(r"[\[\(]([a-zA-Z0-9]{2,})[\]\)]").unwrap();
@@ -55,11 +243,10 @@ pub fn clean_series_name(value: &str) -> String {
         static ref REPLACE_REGEX: Regex = Regex::new(r"[^a-zA-Z0-9]+").unwrap();
     }
     
-    let mut new_value: String = TAG_REGEX.replace_all(value, "").to_string();
-    new_value = REMOVE_REGEX.replace_all(new_value.as_str(), "").to_string();
-    new_value = REPLACE_REGEX.replace_all(new_value.as_str(), " ").to_string();
-    new_value = new_value.trim().replace(' ', ".").to_string();
-    new_value
+    let value = TAG_REGEX.replace_all(value, "");
+    let value = REMOVE_REGEX.replace_all(value.as_ref(), "");
+    let value = REPLACE_REGEX.replace_all(value.as_ref(), " ");
+    value.trim().replace(' ', ".")
 }
 
 pub fn clean_episode_title(value: &str) -> String {
@@ -69,10 +256,114 @@ pub fn clean_episode_title(value: &str) -> String {
         static ref REPLACE_REGEX: Regex = Regex::new(r"[^a-zA-Z0-9]+").unwrap();
     }
 
-    let mut new_value: String = REMOVE_REGEX.replace_all(value, "").to_string();
-    new_value = REMOVE_TAGS.replace_all(new_value.as_str(), "").to_string();
-    new_value = transliterate(new_value.as_str());
-    new_value = REPLACE_REGEX.replace_all(new_value.as_str(), " ").to_string();
-    new_value = new_value.trim().replace(' ', ".").to_string();
-    new_value
+    let value = REMOVE_REGEX.replace_all(value, "");
+    let value = REMOVE_TAGS.replace_all(value.as_ref(), "");
+    let value = transliterate(value.as_ref());
+    let value = REPLACE_REGEX.replace_all(value.as_str(), " ");
+    value.trim().replace(' ', ".")
+}
+
+// Extracts the episode title from filenames in our renamed format, e.g. "Show-S01E01-Title-pt1.[EXTENDED].mkv"
+pub fn get_embedded_episode_title(filename: &str) -> Option<String> {
+    lazy_static! {
+        static ref EMBEDDED_TITLE_REGEX: Regex = Regex::new(r"-[Ss]\d+[Ee]\d+-(.+?)(-pt\d+)?(\.\[[^\]]*\])*\.[^\.]+$").unwrap();
+        static ref PART_SUFFIX_REGEX: Regex = Regex::new(r"^pt\d+$").unwrap();
+    }
+    let captures = EMBEDDED_TITLE_REGEX.captures(filename)?;
+    // untitled split episodes only have the part suffix, e.g. "Show-S01E01-pt1.mkv"
+    Some(captures.get(1)?.as_str())
+        .filter(|title| !PART_SUFFIX_REGEX.is_match(title))
+        .map(|title| title.to_string())
+}
+
+// Splits a path in our renamed format around the episode title, e.g. ("Season 01/Show-S01E01", "Title", "-pt1.mkv")
+fn split_episode_title(path: &str) -> Option<(&str, Option<&str>, String)> {
+    lazy_static! {
+        static ref TITLE_SPLIT_REGEX: Regex = Regex::new(r"^(.*-[Ss]\d+[Ee]\d+)(?:-(.+?))?((?:-pt\d+)?(?:\.\[[^\]]*\])*\.[^\.]+)$").unwrap();
+        static ref PART_SUFFIX_REGEX: Regex = Regex::new(r"^pt\d+$").unwrap();
+    }
+    let captures = TITLE_SPLIT_REGEX.captures(path)?;
+    let prefix = captures.get(1)?.as_str();
+    let suffix = captures.get(3)?.as_str();
+    match captures.get(2).map(|title| title.as_str()) {
+        // untitled split episodes only have the part suffix
+        Some(title) if PART_SUFFIX_REGEX.is_match(title) => Some((prefix, None, format!("-{}{}", title, suffix))),
+        title => Some((prefix, title, suffix.to_string())),
+    }
+}
+
+// Whether a rename keeps the series, season, episode, part, tags and extension of an already renamed file
+pub fn is_title_only_change(src: &str, dest: &str) -> bool {
+    let (src_prefix, src_title, src_suffix) = match split_episode_title(src) {
+        Some(parts) => parts,
+        None => return false,
+    };
+    let (dest_prefix, dest_title, dest_suffix) = match split_episode_title(dest) {
+        Some(parts) => parts,
+        None => return false,
+    };
+    src_prefix == dest_prefix && src_suffix == dest_suffix && src_title != dest_title
+}
+
+// Articles and conjunctions would let unrelated names like "The Office" and "The Wire" match
+const SERIES_NAME_STOPWORDS: [&str; 6] = ["the", "a", "an", "and", "of", "to"];
+
+fn get_series_name_tokens(value: &str) -> Vec<String> {
+    lazy_static! {
+        static ref TOKEN_REGEX: Regex = Regex::new(r"[a-zA-Z0-9]+").unwrap();
+    }
+    let tokens: Vec<String> = TOKEN_REGEX.find_iter(transliterate(value).as_str())
+        .map(|x| x.as_str().to_ascii_lowercase())
+        .collect();
+    // names made only of stopwords are compared as they are
+    let is_all_stopwords = tokens.iter().all(|token| SERIES_NAME_STOPWORDS.contains(&token.as_str()));
+    if is_all_stopwords {
+        return tokens;
+    }
+    tokens.into_iter().filter(|token| !SERIES_NAME_STOPWORDS.contains(&token.as_str())).collect()
+}
+
+// Fraction of words in the shorter name that also appear in the longer name, ignoring stopwords
+pub fn get_series_name_similarity(a: &str, b: &str) -> f32 {
+    let a_tokens = get_series_name_tokens(a);
+    let b_tokens = get_series_name_tokens(b);
+    let total_tokens = a_tokens.len().min(b_tokens.len());
+    if total_tokens == 0 {
+        return 1.0;
+    }
+    let total_shared = a_tokens.iter().filter(|token| b_tokens.contains(token)).count().min(total_tokens);
+    total_shared as f32 / total_tokens as f32
+}
+
+// Get the series name embedded in a filename in a human readable form
+pub fn get_readable_title(title: &str) -> String {
+    lazy_static! {
+        static ref REPLACE_REGEX: Regex = Regex::new(r"[\.\-_\s]+").unwrap();
+    }
+    REPLACE_REGEX.replace_all(title, " ").trim().to_string()
+}
+
+// Folders of the same series often only differ by a trailing year, e.g. "Show" and "Show (2019)"
+pub fn get_folder_series_key(folder_name: &str) -> String {
+    lazy_static! {
+        static ref YEAR_REGEX: Regex = Regex::new(r"[\(\[]?\b(19|20)\d\d\b[\)\]]?\s*$").unwrap();
+        static ref REPLACE_REGEX: Regex = Regex::new(r"[^a-zA-Z0-9]+").unwrap();
+    }
+    // nested folders like "Drama/Show" are keyed by the series folder alone
+    let folder_name = folder_name.rsplit('/').next().unwrap_or(folder_name);
+    let name = YEAR_REGEX.replace(folder_name.trim(), "");
+    REPLACE_REGEX.replace_all(name.as_ref(), " ").trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stopwords_are_ignored_in_series_names() {
+        assert_eq!(get_series_name_similarity("The Office", "The Wire"), 0.0);
+        assert_eq!(get_series_name_similarity("Office", "The Office (US)"), 1.0);
+        assert_eq!(get_series_name_similarity("Law and Order", "Law & Order"), 1.0);
+        assert_eq!(get_series_name_similarity("The The", "The The"), 1.0);
+    }
 }
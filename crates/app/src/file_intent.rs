@@ -1,30 +1,70 @@
-use crate::tvdb_cache::{EpisodeKey, TvdbCache};
-use crate::file_descriptor::{get_descriptor, clean_episode_title, clean_series_name};
+use crate::tvdb_cache::{EpisodeKey, EpisodeOrdering, TvdbCache, is_placeholder_title};
+use crate::anime_mapping::{AnimeMapping, get_absolute_episode_key};
+use crate::companion::get_default_companion_suffixes;
+use crate::atomic_file::get_backup_source;
+use crate::folder_settings::FolderSettings;
+use crate::torrent_labels::LabelRule;
+use crate::daemon::DaemonSettings;
+use crate::http_client::NetworkSettings;
+use crate::trash::TrashRetention;
+use crate::execution_skips::DEFAULT_MAX_EXECUTION_FAILURES;
+use crate::cleanup_queue::SeedTargets;
+use crate::file_descriptor::{
+    FileDescriptor, get_descriptor, get_absolute_descriptor, get_folder_descriptor, clean_episode_title, clean_series_name, 
+    get_embedded_episode_title, get_quality_tags, get_readable_title, get_series_name_similarity, get_title_part, normalize_title,
+};
 use enum_map;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Write;
 use std::path::Path;
 use serde;
+use tvdb::models::Series;
 
-#[derive(Debug, Eq, PartialEq, Copy, Clone, enum_map::Enum)]
+pub(crate) const VIDEO_EXTENSIONS: [&str; 10] = ["mkv", "mp4", "avi", "m4v", "mov", "wmv", "webm", "flv", "mpg", "ts"];
+
+pub(crate) fn is_video_extension(extension: &str) -> bool {
+    VIDEO_EXTENSIONS.iter().any(|video| video.eq_ignore_ascii_case(extension))
+}
+
+pub fn is_video_path(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .map(|extension| is_video_extension(extension.to_string_lossy().as_ref()))
+        .unwrap_or(false)
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Eq, PartialEq, Hash, Copy, Clone, enum_map::Enum)]
+#[serde(rename_all="snake_case")]
 pub enum Action {
     Rename,
     Complete,
     Ignore,
     Delete,
     Whitelist,
+    Extra,
+    Archive,
 }
 
 impl Action {
     pub fn iterator() -> std::slice::Iter<'static, Self> {
-        static ACTIONS: [Action;5] = [
+        static ACTIONS: [Action;7] = [
             Action::Rename,
             Action::Delete,
             Action::Ignore,
             Action::Whitelist,
             Action::Complete,
+            Action::Extra,
+            Action::Archive,
         ];
         ACTIONS.iter() 
     }
 
+    // Actions which move the file to its destination when executed
+    pub fn is_move(&self) -> bool {
+        matches!(self, Action::Rename | Action::Extra)
+    }
+
     pub fn to_str(&self) -> &'static str {
         match self {
             Action::Complete => "Complete",
@@ -32,15 +72,309 @@ impl Action {
             Action::Delete => "Delete",
             Action::Ignore => "Ignore",
             Action::Whitelist => "Whitelist",
+            Action::Extra => "Extra",
+            Action::Archive => "Archive",
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Eq, PartialEq, Hash, Copy, Clone, enum_map::Enum)]
+#[serde(rename_all="snake_case")]
+pub enum DeleteReason {
+    MissingExtension,
+    MissingFilename,
+    BlacklistExtension,
+    SmallVideo,
+    ExtrasFolder,
+    // A larger copy of the same episode is kept instead
+    DuplicateLoser,
+}
+
+impl DeleteReason {
+    pub fn iterator() -> std::slice::Iter<'static, Self> {
+        static REASONS: [DeleteReason;6] = [
+            DeleteReason::BlacklistExtension,
+            DeleteReason::SmallVideo,
+            DeleteReason::ExtrasFolder,
+            DeleteReason::DuplicateLoser,
+            DeleteReason::MissingExtension,
+            DeleteReason::MissingFilename,
+        ];
+        REASONS.iter()
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            DeleteReason::MissingExtension => "Missing extension",
+            DeleteReason::MissingFilename => "Missing filename",
+            DeleteReason::BlacklistExtension => "Blacklisted extension",
+            DeleteReason::SmallVideo => "Small video",
+            DeleteReason::ExtrasFolder => "Extras folder",
+            DeleteReason::DuplicateLoser => "Smaller duplicate",
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Eq, PartialEq, Copy, Clone, Default)]
+#[serde(rename_all="snake_case")]
+pub enum DeletePolicy {
+    #[default]
+    Permanent,
+    Trash,
+    // Keep the file but mark it as ignored instead
+    Ignore,
+}
+
+impl DeletePolicy {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            DeletePolicy::Permanent => "Permanent",
+            DeletePolicy::Trash => "Trash",
+            DeletePolicy::Ignore => "Ignore",
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Eq, PartialEq, Copy, Clone, Default)]
+#[serde(rename_all="snake_case")]
+pub enum TitlePolicy {
+    // Rename without a title when the episode has none yet
+    #[default]
+    OmitMissing,
+    // Leave the file alone until the episode has a title
+    SkipMissing,
+    // Rename with a placeholder which the consistency check picks up once the title exists
+    PlaceholderMissing,
+    AlwaysOmit,
+}
+
+impl TitlePolicy {
+    pub fn iterator() -> std::slice::Iter<'static, Self> {
+        static POLICIES: [TitlePolicy;4] = [
+            TitlePolicy::OmitMissing,
+            TitlePolicy::SkipMissing,
+            TitlePolicy::PlaceholderMissing,
+            TitlePolicy::AlwaysOmit,
+        ];
+        POLICIES.iter()
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            TitlePolicy::OmitMissing => "Omit missing titles",
+            TitlePolicy::SkipMissing => "Skip until titled",
+            TitlePolicy::PlaceholderMissing => "Use placeholder title",
+            TitlePolicy::AlwaysOmit => "Always omit titles",
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Eq, PartialEq, Copy, Clone, Default)]
+#[serde(rename_all="snake_case")]
+pub enum DuplicatePolicy {
+    // Copies of the same episode rename to the same destination and are flagged as conflicts
+    #[default]
+    Conflict,
+    // The resolution and HDR format are added to the filename so e.g. a 1080p and a 2160p copy can both be kept
+    KeepQualities,
+    // Only the largest copy is kept and the others are deleted with the "duplicate_loser" delete policy
+    KeepLargest,
+}
+
+impl DuplicatePolicy {
+    pub fn iterator() -> std::slice::Iter<'static, Self> {
+        static POLICIES: [DuplicatePolicy;3] = [
+            DuplicatePolicy::Conflict,
+            DuplicatePolicy::KeepQualities,
+            DuplicatePolicy::KeepLargest,
+        ];
+        POLICIES.iter()
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            DuplicatePolicy::Conflict => "Flag as conflict",
+            DuplicatePolicy::KeepQualities => "Keep each quality",
+            DuplicatePolicy::KeepLargest => "Keep the largest copy",
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Eq, PartialEq, Hash, Copy, Clone)]
+#[serde(rename_all="snake_case")]
+pub enum WhitelistReason {
+    WhitelistFolder,
+    WhitelistFilename,
+    ExtrasFolder,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Eq, PartialEq, Copy, Clone, Default)]
+#[serde(rename_all="snake_case")]
+pub enum WhitelistPolicy {
+    // Leave the file where it is
+    #[default]
+    Exempt,
+    // Move the file into the keep folder so it doesn't clutter the season folders
+    Keep,
+}
+
+const DEFAULT_KEEP_FOLDER: &str = "Keep";
+
+const PLACEHOLDER_EPISODE_TITLE: &str = "TBA";
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Eq, PartialEq, Copy, Clone)]
+#[serde(rename_all="snake_case")]
+pub enum ExtrasAction {
+    Delete,
+    Whitelist,
+    // Process the folder contents like any other file
+    Disabled,
+}
+
+// Common junk subfolders shipped with releases which would otherwise need to be whitelisted one by one
+const DEFAULT_EXTRAS_FOLDERS: [(&str, ExtrasAction); 8] = [
+    ("proof", ExtrasAction::Delete),
+    ("proofs", ExtrasAction::Delete),
+    ("screens", ExtrasAction::Delete),
+    ("screenshots", ExtrasAction::Delete),
+    ("sample", ExtrasAction::Delete),
+    ("samples", ExtrasAction::Delete),
+    ("extras", ExtrasAction::Whitelist),
+    ("featurettes", ExtrasAction::Whitelist),
+];
+
+// Plex recognises extras placed inside these subfolders of the series folder
+const EXTRAS_CATEGORIES: [(&str, &[&str]); 6] = [
+    ("Behind The Scenes", &["behind the scenes", "making of"]),
+    ("Deleted Scenes", &["deleted scene"]),
+    ("Featurettes", &["featurette"]),
+    ("Interviews", &["interview"]),
+    ("Trailers", &["trailer", "teaser"]),
+    ("Shorts", &["short film"]),
+];
+
+fn get_extras_category(filename: &str) -> Option<&'static str> {
+    let filename: String = filename
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+    let filename = filename.split_whitespace().collect::<Vec<&str>>().join(" ");
+    EXTRAS_CATEGORIES.iter()
+        .find(|(_, keywords)| keywords.iter().any(|keyword| filename.contains(keyword)))
+        .map(|(category, _)| *category)
+}
+
+// Below this fraction of shared words the filename is considered to name a different series
+const MIN_SERIES_NAME_SIMILARITY: f32 = 0.5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntentWarning {
+    // Contains the series name suggested by the filename
+    MismatchedSeries(String),
+    // Contains the episode title in the filename which no longer matches the cache
+    OutdatedTitle(String),
+    // The episode is past the last season or episode in the cache, usually absolute numbering or a bad parse
+    EpisodeOutOfBounds { key: EpisodeKey, last_season: u32, last_episode: u32 },
+}
+
+impl IntentWarning {
+    // Outdated titles are corrected by the planned rename so they don't need to be reviewed
+    pub fn is_review_required(&self) -> bool {
+        match self {
+            IntentWarning::MismatchedSeries(_) => true,
+            IntentWarning::OutdatedTitle(_) => false,
+            IntentWarning::EpisodeOutOfBounds { .. } => true,
+        }
+    }
+}
+
+impl std::fmt::Display for IntentWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntentWarning::MismatchedSeries(name) => write!(f, "Filename suggests a different series: '{}'", name),
+            IntentWarning::OutdatedTitle(title) => write!(f, "Filename has an outdated episode title: '{}'", title),
+            IntentWarning::EpisodeOutOfBounds { key, last_season, .. } if key.season > *last_season => {
+                write!(f, "S{:02}E{:02} is past the last season of the series (season {})", key.season, key.episode, last_season)
+            },
+            IntentWarning::EpisodeOutOfBounds { key, last_episode, .. } => {
+                write!(f, "S{:02}E{:02} is past the last episode of season {} (episode {})", key.season, key.episode, key.season, last_episode)
+            },
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DestError {
+    Empty,
+    IllegalCharacter(char),
+    AbsolutePath,
+    ParentFolder,
+    Collision,
+}
+
+impl std::fmt::Display for DestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DestError::Empty => write!(f, "Destination is empty"),
+            DestError::IllegalCharacter(c) => write!(f, "Destination contains illegal character {:?}", c),
+            DestError::AbsolutePath => write!(f, "Destination must be relative to the folder"),
+            DestError::ParentFolder => write!(f, "Destination can't leave the folder"),
+            DestError::Collision => write!(f, "Destination collides with another file"),
+        }
+    }
+}
+
+// Characters which aren't allowed in filenames on windows
+const ILLEGAL_DEST_CHARACTERS: [char; 7] = ['<', '>', ':', '"', '|', '?', '*'];
+
+pub fn validate_dest(dest: &str) -> Result<(), DestError> {
+    if dest.trim().is_empty() {
+        return Err(DestError::Empty);
+    }
+    if let Some(c) = dest.chars().find(|c| ILLEGAL_DEST_CHARACTERS.contains(c) || c.is_control()) {
+        return Err(DestError::IllegalCharacter(c));
+    }
+    if dest.starts_with('/') || dest.starts_with('\\') {
+        return Err(DestError::AbsolutePath);
+    }
+    if dest.split(['/', '\\']).any(|component| component == "..") {
+        return Err(DestError::ParentFolder);
+    }
+    Ok(())
+}
+
+// Folder names use the readable series name rather than the dotted style used for filenames
+pub fn get_series_folder_name(series: &Series, is_include_year: bool) -> String {
+    let name: String = series.name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' => ' ',
+            c if ILLEGAL_DEST_CHARACTERS.contains(&c) || c.is_control() => ' ',
+            c => c,
+        })
+        .collect();
+    // windows doesn't allow folder names to end with a dot
+    let name = name.split_whitespace().collect::<Vec<&str>>().join(" ");
+    let name = name.trim_end_matches('.').to_string();
+
+    let year = series.first_aired
+        .as_deref()
+        .and_then(|date| date.get(..4))
+        .filter(|year| year.chars().all(|c| c.is_ascii_digit()));
+    match year {
+        Some(year) if is_include_year && !name.ends_with(format!("({})", year).as_str()) => format!("{} ({})", name, year),
+        _ => name,
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct FileIntent {
     pub action: Action,
     pub dest: String,
     pub descriptor: Option<EpisodeKey>,
+    pub warnings: Vec<IntentWarning>,
+    pub delete_reason: Option<DeleteReason>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
@@ -49,56 +383,323 @@ pub struct FilterRules {
     pub whitelist_folders: Vec<String>,
     pub whitelist_filenames: Vec<String>,
     pub whitelist_tags: Vec<String>,
+    #[serde(default)]
+    pub strip_combining_marks: bool,
+    #[serde(default)]
+    pub delete_policies: HashMap<DeleteReason, DeletePolicy>,
+    #[serde(default)]
+    pub whitelist_policies: HashMap<WhitelistReason, WhitelistPolicy>,
+    // Whitelisted files with the keep policy are moved here, defaults to "Keep"
+    #[serde(default)]
+    pub keep_folder: Option<String>,
+    #[serde(default)]
+    pub min_video_size_mb: Option<u64>,
+    #[serde(default)]
+    pub ffmpeg_path: Option<String>,
+    // Used for root folders given as "ssh://user@host:port/path"
+    #[serde(default)]
+    pub ssh_path: Option<String>,
+    // Imported files with these extensions are remuxed into mkv, e.g. [".avi", ".ts"]
+    #[serde(default)]
+    pub remux_extensions: Vec<String>,
+    // Writes the show, season, episode and title into renamed mkv and mp4 files
+    #[serde(default)]
+    pub write_metadata_tags: bool,
+    // Overrides the built in extras folder rules, folder names are case insensitive
+    #[serde(default)]
+    pub extras_folders: HashMap<String, ExtrasAction>,
+    // Moves featurettes, deleted scenes, etc... into plex extras folders instead of applying extras folder rules
+    #[serde(default)]
+    pub classify_extras: bool,
+    #[serde(default="get_default_companion_suffixes")]
+    pub companion_suffixes: Vec<String>,
+    // Folders can override this in their settings
+    #[serde(default)]
+    pub title_policy: TitlePolicy,
+    // Folders can override this in their settings
+    #[serde(default)]
+    pub duplicate_policy: DuplicatePolicy,
+    // Refreshes folders automatically once their files stop changing
+    #[serde(default)]
+    pub watch_folders: bool,
+    #[serde(default)]
+    pub auto_refresh_delay_secs: Option<u64>,
+    // Defaults to the public tvdb api, can point at a proxy or a mock server
+    #[serde(default)]
+    pub tvdb_base_url: Option<String>,
+    // Seeds the settings of folders from the labels of their torrents
+    #[serde(default)]
+    pub label_rules: Vec<LabelRule>,
+    // Archived files are moved into "<archive_path>/<folder name>/", e.g. a folder on a slower disk
+    #[serde(default)]
+    pub archive_path: Option<String>,
+    // Seasons with an episode which wasn't watched on the media server are kept out of the archive
+    #[serde(default)]
+    pub keep_unwatched_seasons: bool,
+    // Episode caches and execution journals are written with zstd, either kind of file can be read
+    #[serde(default)]
+    pub compress_caches: bool,
+    // Level of the series folders below the root folder, e.g. 2 for "<root>/<genre>/<series>"
+    // Detected for each folder when unset
+    #[serde(default)]
+    pub library_depth: Option<usize>,
+    // Deleted files moved into the trash are purged once they are too old or the trash is too large
+    #[serde(default)]
+    pub trash_retention: TrashRetention,
+    // Files whose change fails this many times in a row are skipped in executions until retried, 0 never skips them
+    #[serde(default)]
+    pub max_execution_failures: Option<u32>,
+    // Torrents queued for deferred cleanup start with these targets
+    #[serde(default)]
+    pub seed_targets: SeedTargets,
+    #[serde(default)]
+    pub cleanup_check_interval_secs: Option<u64>,
+    // Only used when running with --daemon
+    #[serde(default)]
+    pub daemon: DaemonSettings,
+    // Proxy and extra certificates for the http client
+    #[serde(default)]
+    pub network: NetworkSettings,
+    // Written by the gui, kept as raw json since the bindings are made of egui keys
+    #[serde(default)]
+    pub keybindings: Option<serde_json::Value>,
+}
+
+impl FilterRules {
+    pub fn get_ffmpeg_path(&self) -> &str {
+        self.ffmpeg_path.as_deref().unwrap_or("ffmpeg")
+    }
+
+    pub fn get_ssh_path(&self) -> &str {
+        self.ssh_path.as_deref().unwrap_or("ssh")
+    }
+
+    pub fn get_max_execution_failures(&self) -> u32 {
+        self.max_execution_failures.unwrap_or(DEFAULT_MAX_EXECUTION_FAILURES)
+    }
+
+    pub fn get_delete_policy(&self, reason: Option<DeleteReason>) -> DeletePolicy {
+        reason
+            .and_then(|reason| self.delete_policies.get(&reason).copied())
+            .unwrap_or_default()
+    }
+
+    pub fn get_whitelist_policy(&self, reason: WhitelistReason) -> WhitelistPolicy {
+        self.whitelist_policies.get(&reason).copied().unwrap_or_default()
+    }
+
+    pub fn get_keep_folder(&self) -> &str {
+        self.keep_folder.as_deref()
+            .map(|folder| folder.trim_matches(['/', '\\']))
+            .filter(|folder| !folder.is_empty())
+            .unwrap_or(DEFAULT_KEEP_FOLDER)
+    }
+
+    pub fn get_extras_action(&self, folder: &str) -> Option<ExtrasAction> {
+        let folder = folder.to_lowercase();
+        let action = self.extras_folders.iter()
+            .find(|(name, _)| name.to_lowercase() == folder)
+            .map(|(_, action)| *action)
+            .or_else(|| {
+                DEFAULT_EXTRAS_FOLDERS.iter()
+                    .find(|(name, _)| *name == folder)
+                    .map(|(_, action)| *action)
+            })?;
+        match action {
+            ExtrasAction::Disabled => None,
+            _ => Some(action),
+        }
+    }
+}
+
+impl FileIntent {
+    fn set_delete(&mut self, reason: DeleteReason, rules: &FilterRules) {
+        self.delete_reason = Some(reason);
+        self.action = match rules.get_delete_policy(Some(reason)) {
+            DeletePolicy::Ignore => Action::Ignore,
+            DeletePolicy::Permanent | DeletePolicy::Trash => Action::Delete,
+        };
+    }
+
+    // The kept path starts from the folder which matched the rule so files from different folders don't collide
+    fn set_whitelist(&mut self, reason: WhitelistReason, kept_path: &Path, path: &Path, rules: &FilterRules) {
+        self.action = Action::Whitelist;
+        if rules.get_whitelist_policy(reason) != WhitelistPolicy::Keep {
+            return;
+        }
+        let keep_folder = rules.get_keep_folder();
+        let is_kept = path.iter().next()
+            .map(|component| component.to_string_lossy().eq_ignore_ascii_case(keep_folder))
+            .unwrap_or(false);
+        if is_kept {
+            return;
+        }
+        self.action = Action::Extra;
+        self.dest = Path::new(keep_folder).join(kept_path).to_string_lossy().to_string();
+    }
+}
+
+// Two-part episodes are either listed once or as consecutive episodes with titles like "Title (1)" and "Title (2)"
+// Parts listed once share the episode and are told apart by their part suffix
+fn get_part_episode_key(key: EpisodeKey, part: u32, cache: &TvdbCache, ordering: EpisodeOrdering) -> (EpisodeKey, Option<u32>) {
+    let is_listed_part = |key: &EpisodeKey| {
+        cache.get_episode_index(key, ordering)
+            .and_then(|index| cache.episodes.get(index))
+            .and_then(|episode| episode.name.as_deref())
+            .and_then(get_title_part)
+            .map(|title_part| title_part == part)
+            .unwrap_or(false)
+    };
+    let part_key = EpisodeKey {
+        season: key.season,
+        episode: key.episode + part.saturating_sub(1),
+    };
+    // releases can already number the later part as its own episode, e.g. "S01E06 Part 2"
+    match [key, part_key].into_iter().find(is_listed_part) {
+        Some(key) => (key, None),
+        None => (key, Some(part)),
+    }
+}
+
+// Extensions in the config are written with a leading dot, e.g. ".nfo"
+pub fn is_extension_listed(extensions: &[String], extension: &str) -> bool {
+    extensions.iter().any(|listed| listed.trim_start_matches('.').eq_ignore_ascii_case(extension))
 }
 
-pub fn get_file_intent(path_str: &str, rules: &FilterRules, cache: &TvdbCache) -> FileIntent {
+thread_local! {
+    // Destinations are formatted here and only copied out for files that need renaming
+    static DEST_BUFFER: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+pub fn get_file_intent(
+    path_str: &str, file_size: u64, rules: &FilterRules, cache: &TvdbCache, 
+    anime_mapping: Option<&AnimeMapping>, settings: &FolderSettings,
+) -> FileIntent {
     let mut intent = FileIntent {
         action: Action::Ignore,
         dest: "".to_string(),
         descriptor: None,
+        warnings: Vec::new(),
+        delete_reason: None,
     };
     
     let path = Path::new(path_str);
     let extension = match path.extension() {
-        Some(extension) => extension.to_string_lossy().to_string(),
+        Some(extension) => extension.to_string_lossy(),
         None => {
-            intent.action = Action::Delete;
+            intent.set_delete(DeleteReason::MissingExtension, rules);
             return intent;
         },
     };
     let filename = match path.file_name() {
-        Some(filename) => filename.to_string_lossy().to_string(),
+        Some(filename) => filename.to_string_lossy(),
         None => {
-            intent.action = Action::Delete;
+            intent.set_delete(DeleteReason::MissingFilename, rules);
             return intent;
         },
     };
     
-    if rules.blacklist_extensions.contains(&extension) {
-        intent.action = Action::Delete;
+    if is_extension_listed(rules.blacklist_extensions.as_slice(), extension.as_ref()) {
+        intent.set_delete(DeleteReason::BlacklistExtension, rules);
         return intent;
     }
 
-    for component in path.iter() {
+    let components: Vec<&std::ffi::OsStr> = path.iter().collect();
+    for (index, component) in components.iter().enumerate() {
         if let Some(folder) = component.to_str() {
-            if rules.whitelist_folders.contains(&folder.to_string()) {
-                intent.action = Action::Whitelist;
+            if rules.whitelist_folders.iter().any(|other| other == folder) {
+                let kept_path: std::path::PathBuf = components[index..].iter().collect();
+                intent.set_whitelist(WhitelistReason::WhitelistFolder, kept_path.as_path(), path, rules);
                 return intent;
             }
         }
     }
     
-    if rules.whitelist_filenames.contains(&filename) {
-        intent.action = Action::Whitelist;
+    // files in season folders like "Season 3" or "S03" only need an episode number
+    let parent_folder = path.parent().and_then(|parent| parent.file_name()).map(|name| name.to_string_lossy());
+    let get_path_descriptor = || get_descriptor(filename.as_ref()).or_else(|| {
+        get_folder_descriptor(parent_folder.as_deref()?, filename.as_ref())
+    });
+
+    // episodes take priority since their titles can contain keywords like "interview"
+    if rules.classify_extras && get_path_descriptor().is_none() {
+        if let Some(category) = get_extras_category(filename.as_ref()) {
+            let new_path = Path::new(category).join(filename.as_ref());
+            let new_path_str = new_path.to_string_lossy().to_string();
+            if normalize_title(new_path_str.as_str(), false) == normalize_title(path_str, false) {
+                intent.action = Action::Complete;
+            } else {
+                intent.action = Action::Extra;
+                intent.dest = new_path_str;
+            }
+            return intent;
+        }
+    }
+
+    let total_parent_folders = components.len().saturating_sub(1);
+    for (index, component) in components.iter().take(total_parent_folders).enumerate() {
+        let folder = component.to_string_lossy();
+        match rules.get_extras_action(folder.as_ref()) {
+            Some(ExtrasAction::Delete) => {
+                intent.set_delete(DeleteReason::ExtrasFolder, rules);
+                return intent;
+            },
+            Some(ExtrasAction::Whitelist) => {
+                let kept_path: std::path::PathBuf = components[index..].iter().collect();
+                intent.set_whitelist(WhitelistReason::ExtrasFolder, kept_path.as_path(), path, rules);
+                return intent;
+            },
+            Some(ExtrasAction::Disabled) | None => {},
+        }
+    }
+    
+    // backups of the app's own files are kept alongside them
+    let is_whitelisted_filename = |name: &str| rules.whitelist_filenames.iter().any(|other| other == name);
+    let is_whitelisted_backup = get_backup_source(filename.as_ref())
+        .map(is_whitelisted_filename)
+        .unwrap_or(false);
+    if is_whitelisted_filename(filename.as_ref()) || is_whitelisted_backup {
+        // the app's own data files sit in the root of the folder and must stay there
+        if components.len() > 1 {
+            intent.set_whitelist(WhitelistReason::WhitelistFilename, Path::new(filename.as_ref()), path, rules);
+        } else {
+            intent.action = Action::Whitelist;
+        }
         return intent;
     }
+
+    // samples and corrupt stubs are much smaller than a full episode
+    if let Some(min_size_mb) = rules.min_video_size_mb {
+        if is_video_extension(extension.as_ref()) && file_size < min_size_mb * 1024 * 1024 {
+            intent.set_delete(DeleteReason::SmallVideo, rules);
+            return intent;
+        }
+    }
     
     // get descriptor tag if possible
-    let descriptor = match get_descriptor(filename.as_str()) {
+    let ordering = settings.get_ordering();
+    let descriptor = match get_path_descriptor() {
         Some(descriptor) => descriptor,
         None => {
-            intent.action = Action::Ignore;
-            return intent;
+            // fallback to absolute episode numbers used by anime releases
+            let descriptor = get_absolute_descriptor(filename.as_ref()).and_then(|descriptor| {
+                let key = get_absolute_episode_key(descriptor.episode, anime_mapping, cache, ordering)?;
+                Some(FileDescriptor {
+                    title: descriptor.title,
+                    season: key.season,
+                    episode: key.episode,
+                    part: None,
+                    tags: descriptor.tags,
+                    extension: descriptor.extension,
+                })
+            });
+            match descriptor {
+                Some(descriptor) => descriptor,
+                None => {
+                    intent.action = Action::Ignore;
+                    return intent;
+                },
+            }
         },
     };
 
@@ -106,53 +707,98 @@ pub fn get_file_intent(path_str: &str, rules: &FilterRules, cache: &TvdbCache) -
         season: descriptor.season, 
         episode: descriptor.episode,
     };
+    let (episode_key, part) = match descriptor.part {
+        Some(part) => get_part_episode_key(episode_key, part, cache, ordering),
+        None => (episode_key, None),
+    };
     intent.descriptor = Some(episode_key);
 
-    // create new filename
-    let new_episode_title = match cache.episode_cache.get(&episode_key) {
-        None => "".to_string(),
-        Some(index) => {
-            let episode = &cache.episodes[*index];
-            match &episode.name {
-                None => "".to_string(),
-                Some(name) => {
-                    let clean_name = clean_episode_title(name.as_str());
-                    if clean_name.is_empty() {
-                        "".to_string()
-                    } else {
-                        format!("-{}", clean_name.as_str())
-                    }
-                },
+    // check if the file was sorted into the wrong series folder
+    let filename_series = get_readable_title(descriptor.title.as_str());
+    if !filename_series.is_empty() {
+        let series = &cache.series;
+        let is_match = std::iter::once(&series.name)
+            .chain(series.aliases.iter().flatten())
+            .any(|name| get_series_name_similarity(filename_series.as_str(), name) >= MIN_SERIES_NAME_SIMILARITY);
+        if !is_match {
+            intent.warnings.push(IntentWarning::MismatchedSeries(filename_series));
+        }
+    }
+
+    // episodes the cache doesn't know about are only suspicious if they lie outside the series
+    if cache.get_episode_index(&episode_key, ordering).is_none() {
+        if let Some((last_season, last_episode)) = cache.get_episode_bounds(episode_key.season, ordering) {
+            if episode_key.season > last_season || episode_key.episode > last_episode {
+                intent.warnings.push(IntentWarning::EpisodeOutOfBounds { key: episode_key, last_season, last_episode });
             }
-        },
+        }
+    }
+
+    // create new filename
+    // placeholder titles from tvdb are treated as missing since they will be replaced later
+    let clean_episode_name = cache.get_episode_index(&episode_key, ordering)
+        .and_then(|index| cache.get_episode_name(index, settings.language.as_deref()))
+        .filter(|name| !is_placeholder_title(name))
+        .map(|name| clean_episode_title(normalize_title(name, rules.strip_combining_marks).as_ref()))
+        .unwrap_or_default();
+    let title_policy = settings.title_policy.unwrap_or(rules.title_policy);
+    let is_skip_rename = title_policy == TitlePolicy::SkipMissing && clean_episode_name.is_empty();
+
+    // previously renamed files keep placeholder titles like "TBA" until the cache is refreshed
+    if let Some(old_title) = get_embedded_episode_title(filename.as_ref()) {
+        let is_titled = title_policy != TitlePolicy::AlwaysOmit && !clean_episode_name.is_empty();
+        if is_titled && old_title != clean_episode_name {
+            intent.warnings.push(IntentWarning::OutdatedTitle(old_title));
+        }
+    }
+    let quality_tags = match settings.duplicate_policy.unwrap_or(rules.duplicate_policy) {
+        DuplicatePolicy::Conflict | DuplicatePolicy::KeepLargest => Vec::new(),
+        DuplicatePolicy::KeepQualities => get_quality_tags(filename.as_ref()),
     };
-    let tags_string = descriptor.tags
+    let tags = quality_tags
         .iter()
-        .filter(|tag| rules.whitelist_tags.contains(tag))
-        .map(|tag| format!(".[{}]", tag.as_str()))
-        .collect::<Vec<String>>()
-        .join("");
-
-    let new_filename = format!(
-        "{}-S{:02}E{:02}{}{}.{}", 
-        clean_series_name(cache.series.name.as_str()).as_str(), 
-        descriptor.season, descriptor.episode, 
-        new_episode_title.as_str(),
-        tags_string.as_str(),
-        extension.as_str(),
-    );
-
-    // check if new path is same as old path
-    let new_folder = format!("Season {:02}", descriptor.season);
-    let new_path = Path::new(new_folder.as_str()).join(new_filename.as_str());
-    let new_path_str = new_path.to_string_lossy().to_string();
-    let is_same_filepath = new_path == path;
-    if is_same_filepath {
-        intent.action = Action::Complete;
-        return intent;
-    }
+        .chain(descriptor.tags.iter().filter(|tag| {
+            rules.whitelist_tags.contains(tag) && !quality_tags.iter().any(|other| other.eq_ignore_ascii_case(tag))
+        }));
+    let series_name = clean_series_name(normalize_title(cache.series.name.as_str(), rules.strip_combining_marks).as_ref());
+
+    DEST_BUFFER.with(|buffer| {
+        let mut new_path = buffer.borrow_mut();
+        new_path.clear();
+        // writing to a string never fails
+        let _ = write!(
+            new_path, "Season {:02}{}{}-S{:02}E{:02}",
+            episode_key.season, std::path::MAIN_SEPARATOR, series_name,
+            episode_key.season, episode_key.episode,
+        );
+        match (title_policy, clean_episode_name.is_empty()) {
+            (TitlePolicy::AlwaysOmit, _) => {},
+            (TitlePolicy::PlaceholderMissing, true) => { let _ = write!(new_path, "-{}", PLACEHOLDER_EPISODE_TITLE); },
+            (_, true) => {},
+            (_, false) => { let _ = write!(new_path, "-{}", clean_episode_name); },
+        }
+        if let Some(part) = part {
+            let _ = write!(new_path, "-pt{}", part);
+        }
+        for tag in tags {
+            let _ = write!(new_path, ".[{}]", tag);
+        }
+        let _ = write!(new_path, ".{}", extension);
+
+        // compare normalized paths so that NFD filenames aren't renamed to identical NFC filenames
+        let is_same_filepath = normalize_title(new_path.as_str(), false) == normalize_title(path_str, false);
+        if is_same_filepath {
+            intent.action = Action::Complete;
+            return intent;
+        }
+
+        if is_skip_rename {
+            intent.action = Action::Ignore;
+            return intent;
+        }
 
-    intent.action = Action::Rename;
-    intent.dest = new_path_str;
-    intent
+        intent.action = Action::Rename;
+        intent.dest = new_path.clone();
+        intent
+    })
 }
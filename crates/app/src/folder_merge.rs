@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use crate::app_folder::FOLDER_DATA_FILENAMES;
+use crate::file_intent::is_video_path;
+use crate::tvdb_cache::EpisodeKey;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeFile {
+    pub src: String,
+    pub descriptor: Option<EpisodeKey>,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeStep {
+    // Moved into the destination at the same path
+    Move(String),
+    // The destination already has a copy which is at least as large
+    TrashSource(String),
+    // The destination's copy at dest_src is smaller, so it is trashed and the file moved in
+    Replace { src: String, dest_src: String },
+}
+
+// What merging one folder into another will do, shown before anything is moved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergePlan {
+    pub src_name: String,
+    pub dest_name: String,
+    pub steps: Vec<MergeStep>,
+}
+
+impl MergePlan {
+    pub fn get_total_moves(&self) -> usize {
+        self.steps.iter().filter(|step| !matches!(step, MergeStep::TrashSource(_))).count()
+    }
+
+    // Paths are prefixed with the folder they are trashed from
+    pub fn get_trashed_files(&self) -> Vec<String> {
+        self.steps.iter()
+            .filter_map(|step| match step {
+                MergeStep::Move(_) => None,
+                MergeStep::TrashSource(src) => Some(format!("{}/{}", self.src_name, src)),
+                MergeStep::Replace { dest_src, .. } => Some(format!("{}/{}", self.dest_name, dest_src)),
+            })
+            .collect()
+    }
+}
+
+// Episodes are only duplicates of each other since both folders are bound to the same series
+// The destination keeps its own cache and settings
+pub(crate) fn plan_merge_steps(src_files: &[MergeFile], dest_files: &[MergeFile]) -> Vec<MergeStep> {
+    let mut dest_episodes: HashMap<EpisodeKey, (String, u64)> = HashMap::new();
+    let mut dest_sources: HashMap<String, u64> = HashMap::new();
+    for file in dest_files {
+        if let Some(descriptor) = file.descriptor.filter(|_| is_video_path(file.src.as_str())) {
+            dest_episodes.insert(descriptor, (file.src.clone(), file.size));
+        }
+        dest_sources.insert(file.src.clone(), file.size);
+    }
+
+    let mut steps = Vec::new();
+    for file in src_files {
+        if FOLDER_DATA_FILENAMES.contains(&file.src.as_str()) && dest_sources.contains_key(&file.src) {
+            continue;
+        }
+        let descriptor = file.descriptor.filter(|_| is_video_path(file.src.as_str()));
+        // an episode and a file at the same path are both duplicates of the existing file
+        let duplicate = descriptor
+            .and_then(|descriptor| dest_episodes.get(&descriptor).cloned())
+            .or_else(|| dest_sources.get(&file.src).map(|size| (file.src.clone(), *size)));
+        let step = match duplicate {
+            Some((_, dest_size)) if dest_size >= file.size => {
+                steps.push(MergeStep::TrashSource(file.src.clone()));
+                continue;
+            },
+            Some((dest_src, _)) => MergeStep::Replace { src: file.src.clone(), dest_src },
+            None => MergeStep::Move(file.src.clone()),
+        };
+        steps.push(step);
+        // later copies of the same episode compete with the one which was moved in
+        if let Some(descriptor) = descriptor {
+            dest_episodes.insert(descriptor, (file.src.clone(), file.size));
+        }
+        dest_sources.insert(file.src.clone(), file.size);
+    }
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(src: &str, episode: Option<u32>, size: u64) -> MergeFile {
+        MergeFile {
+            src: src.to_string(),
+            descriptor: episode.map(|episode| EpisodeKey { season: 1, episode }),
+            size,
+        }
+    }
+
+    #[test]
+    fn larger_copy_of_each_episode_is_kept() {
+        let dest_files = [file("series.json", None, 10), file("S01E01.mkv", Some(1), 100), file("S01E02.mkv", Some(2), 300)];
+        let src_files = [
+            file("series.json", None, 10),
+            file("a/S01E01.mkv", Some(1), 200),
+            file("a/S01E02.mkv", Some(2), 200),
+            file("S01E03.mkv", Some(3), 100),
+            file("b/S01E03.mkv", Some(3), 50),
+        ];
+        let plan = MergePlan {
+            src_name: "Show (2005)".to_string(),
+            dest_name: "Show".to_string(),
+            steps: plan_merge_steps(&src_files, &dest_files),
+        };
+        assert_eq!(plan.steps, vec![
+            MergeStep::Replace { src: "a/S01E01.mkv".to_string(), dest_src: "S01E01.mkv".to_string() },
+            MergeStep::TrashSource("a/S01E02.mkv".to_string()),
+            MergeStep::Move("S01E03.mkv".to_string()),
+            MergeStep::TrashSource("b/S01E03.mkv".to_string()),
+        ]);
+        assert_eq!(plan.get_total_moves(), 2);
+        assert_eq!(plan.get_trashed_files(), vec!["Show/S01E01.mkv", "Show (2005)/a/S01E02.mkv", "Show (2005)/b/S01E03.mkv"]);
+    }
+}
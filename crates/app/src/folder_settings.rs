@@ -0,0 +1,90 @@
+use chrono;
+use serde;
+use serde_json;
+use std::collections::HashMap;
+use crate::file_intent::{DuplicatePolicy, TitlePolicy};
+use crate::tvdb_cache::EpisodeOrdering;
+
+// Folders are triaged by marking them as reviewed before their changes are executed in bulk
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FolderReview {
+    pub reviewer: String,
+    pub reviewed_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl FolderReview {
+    pub fn new() -> Self {
+        let reviewer = std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .unwrap_or_else(|_| "unknown".to_string());
+        Self {
+            reviewer,
+            reviewed_at: chrono::Utc::now(),
+        }
+    }
+}
+
+impl Default for FolderReview {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct FolderSettings {
+    // Name of the rule profile, falls back to the default profile
+    pub profile: Option<String>,
+    // Language abbreviation used for episode titles, e.g. "de"
+    pub language: Option<String>,
+    // Falls back to the title policy in the filter rules
+    pub title_policy: Option<TitlePolicy>,
+    // Falls back to the duplicate policy in the filter rules
+    pub duplicate_policy: Option<DuplicatePolicy>,
+    // Numbering used by the filenames, falls back to the aired order
+    pub ordering: Option<EpisodeOrdering>,
+    // Destinations entered by hand which take precedence over the generated ones
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub dest_overrides: HashMap<String, String>,
+    // Subtitles are only fetched for folders with preferred languages, e.g. ["en", "de"]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub subtitle_languages: Vec<String>,
+    // Episodes are moved into the archive root once they are correctly named
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub is_archived: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub archived_seasons: Vec<u32>,
+    // Folders which only hold some seasons ignore the others when looking for missing episodes
+    // Empty means every season is kept
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub season_scope: Vec<u32>,
+    // Music and book folders are detected when unset, false excludes the folder by hand
+    // Excluded folders are skipped when refreshing, matching downloads and counting statistics
+    pub is_series: Option<bool>,
+    // Pinned folders are left alone by refreshing all folders, automatic execution and library checks
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub is_pinned: bool,
+    // Label of the torrent rule which seeded these settings so it is only applied once
+    pub torrent_label: Option<String>,
+    // Kept separate from the pending/done status which only depends on the files
+    pub review: Option<FolderReview>,
+}
+
+impl FolderSettings {
+    pub fn get_ordering(&self) -> EpisodeOrdering {
+        self.ordering.unwrap_or_default()
+    }
+
+    pub fn is_season_in_scope(&self, season: u32) -> bool {
+        self.season_scope.is_empty() || self.season_scope.contains(&season)
+    }
+}
+
+pub fn deserialize_folder_settings(data: &str) -> Result<FolderSettings, serde_json::Error> {
+    serde_json::from_str(data)
+}
+
+pub fn serialize_folder_settings(settings: &FolderSettings) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(settings)
+}
@@ -0,0 +1,97 @@
+use serde;
+use serde_json;
+use std::collections::{HashMap, HashSet};
+use tokio::sync::RwLock;
+use crate::app_folder::FolderStatus;
+use crate::atomic_file::{LoadError, load_with_backup, write_atomic};
+use crate::other_media::FolderExclusion;
+use crate::tvdb_cache::SeriesStatus;
+
+// What the folders list shows for a folder whose episode cache hasn't been loaded yet
+#[serde_with::skip_serializing_none]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Eq, PartialEq, Copy, Clone)]
+pub struct FolderSummary {
+    pub status: FolderStatus,
+    pub series_status: SeriesStatus,
+    pub total_missing_recent: usize,
+    #[serde(default)]
+    pub total_aired_present: usize,
+    #[serde(default)]
+    pub total_aired: usize,
+    #[serde(default)]
+    pub exclusion: Option<FolderExclusion>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Clone)]
+struct SummaryData {
+    root_path: String,
+    folders: HashMap<String, FolderSummary>,
+}
+
+// Last known summary of every folder so launching doesn't have to load every episode cache
+// Summaries from another root folder are discarded
+pub struct FolderSummaryStore {
+    path: String,
+    data: RwLock<SummaryData>,
+    is_changed: RwLock<bool>,
+}
+
+impl FolderSummaryStore {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            data: RwLock::new(SummaryData::default()),
+            is_changed: RwLock::new(false),
+        }
+    }
+
+    pub async fn load_from_file(&self, root_path: &str) -> Result<HashMap<String, FolderSummary>, std::io::Error> {
+        let mut data = self.data.write().await;
+        *data = SummaryData {
+            root_path: root_path.to_string(),
+            folders: HashMap::new(),
+        };
+        let loaded = match load_with_backup(self.path.as_str(), |data: &str| serde_json::from_str::<SummaryData>(data)).await {
+            Ok(loaded) => loaded.value,
+            Err(LoadError::IO(err)) if err.kind() == std::io::ErrorKind::NotFound => return Ok(HashMap::new()),
+            Err(LoadError::IO(err)) => return Err(err),
+            Err(LoadError::Decode(err)) => return Err(err.into()),
+        };
+        if loaded.root_path != root_path {
+            return Ok(HashMap::new());
+        }
+        data.folders = loaded.folders.clone();
+        Ok(loaded.folders)
+    }
+
+    pub async fn update(&self, folder_name: &str, summary: FolderSummary) {
+        let mut data = self.data.write().await;
+        if data.folders.get(folder_name) == Some(&summary) {
+            return;
+        }
+        data.folders.insert(folder_name.to_string(), summary);
+        *self.is_changed.write().await = true;
+    }
+
+    // Forgets folders which were removed from the root folder
+    pub async fn retain_folders(&self, folder_names: &HashSet<&str>) {
+        let mut data = self.data.write().await;
+        let total_folders = data.folders.len();
+        data.folders.retain(|name, _| folder_names.contains(name.as_str()));
+        if data.folders.len() != total_folders {
+            *self.is_changed.write().await = true;
+        }
+    }
+
+    pub async fn save_if_changed(&self) -> Result<(), std::io::Error> {
+        let data = self.data.read().await;
+        let mut is_changed = self.is_changed.write().await;
+        if !*is_changed {
+            return Ok(());
+        }
+        let data = serde_json::to_string(&*data).map_err(std::io::Error::from)?;
+        write_atomic(self.path.as_str(), data).await?;
+        *is_changed = false;
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+use notify::{self, Watcher};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use crate::execution_journal::JOURNAL_FOLDER_NAME;
+use crate::natural_sort::sort_natural_by;
+use crate::trash::TRASH_FOLDER_NAME;
+
+pub const DEFAULT_AUTO_REFRESH_DELAY_SECS: u64 = 5;
+// Torrent clients can write to a folder for hours so it is refreshed eventually even if it never settles
+const MAX_DELAY_MULTIPLIER: u32 = 12;
+
+struct PendingEvents {
+    first_event: Instant,
+    last_event: Instant,
+    total_events: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct PendingAutoRefresh {
+    pub folder_name: String,
+    pub total_events: usize,
+    pub remaining: Duration,
+}
+
+type PendingMap = HashMap<String, PendingEvents>;
+
+// Coalesces filesystem events per folder so a storm of writes results in a single refresh
+pub struct FolderWatcher {
+    _watcher: notify::RecommendedWatcher,
+    delay: Duration,
+    pending: Arc<Mutex<PendingMap>>,
+    // Folders below genre or letter folders, e.g. "Drama/Show"
+    nested_folders: Arc<Mutex<Vec<String>>>,
+}
+
+fn get_folder_name(root_path: &Path, path: &Path, nested_folders: &[String]) -> Option<String> {
+    let rel_path = path.strip_prefix(root_path).ok()?;
+    let rel_path_str = rel_path.to_string_lossy().replace(std::path::MAIN_SEPARATOR, "/");
+    let nested_folder = nested_folders
+        .iter()
+        .filter(|name| rel_path_str.strip_prefix(name.as_str()).map(|rest| rest.starts_with('/')).unwrap_or(false))
+        .max_by_key(|name| name.len());
+    if let Some(folder_name) = nested_folder {
+        return Some(folder_name.clone());
+    }
+    let folder_name = match rel_path.components().next()? {
+        Component::Normal(name) => name.to_str()?,
+        _ => return None,
+    };
+    // Files directly inside the root folder don't belong to any folder
+    if rel_path.components().count() < 2 {
+        return None;
+    }
+    if folder_name == TRASH_FOLDER_NAME || folder_name == JOURNAL_FOLDER_NAME {
+        return None;
+    }
+    Some(folder_name.to_string())
+}
+
+fn push_event(pending: &mut PendingMap, folder_name: String, now: Instant) {
+    let events = pending.entry(folder_name).or_insert(PendingEvents {
+        first_event: now,
+        last_event: now,
+        total_events: 0,
+    });
+    events.last_event = now;
+    events.total_events += 1;
+}
+
+impl FolderWatcher {
+    pub fn new(root_path: &str, delay: Duration) -> notify::Result<Self> {
+        let pending = Arc::new(Mutex::new(PendingMap::new()));
+        let nested_folders = Arc::new(Mutex::new(Vec::new()));
+        let root_path = PathBuf::from(root_path);
+        let mut watcher = notify::recommended_watcher({
+            let pending = pending.clone();
+            let nested_folders = nested_folders.clone();
+            let root_path = root_path.clone();
+            move |res: notify::Result<notify::Event>| {
+                let event = match res {
+                    Ok(event) => event,
+                    Err(_) => return,
+                };
+                if matches!(event.kind, notify::EventKind::Access(_)) {
+                    return;
+                }
+                let now = Instant::now();
+                let mut pending = pending.lock().unwrap();
+                let nested_folders = nested_folders.lock().unwrap();
+                for path in event.paths.iter() {
+                    if let Some(folder_name) = get_folder_name(root_path.as_path(), path.as_path(), nested_folders.as_slice()) {
+                        push_event(&mut pending, folder_name, now);
+                    }
+                }
+            }
+        })?;
+        watcher.watch(root_path.as_path(), notify::RecursiveMode::Recursive)?;
+        Ok(Self {
+            _watcher: watcher,
+            delay,
+            pending,
+            nested_folders,
+        })
+    }
+
+    // Folders directly inside the root folder are found without being listed
+    pub fn set_folder_names<'a>(&self, folder_names: impl IntoIterator<Item = &'a str>) {
+        *self.nested_folders.lock().unwrap() = folder_names
+            .into_iter()
+            .filter(|name| name.contains('/'))
+            .map(|name| name.to_string())
+            .collect();
+    }
+
+    fn get_due_time(&self, events: &PendingEvents) -> Instant {
+        let settled = events.last_event + self.delay;
+        let max_delay = events.first_event + self.delay * MAX_DELAY_MULTIPLIER;
+        settled.min(max_delay)
+    }
+
+    // Folders are handed out once so they are only refreshed again after new events arrive
+    pub fn take_due_folders(&self) -> Vec<String> {
+        let now = Instant::now();
+        let mut pending = self.pending.lock().unwrap();
+        let folder_names: Vec<String> = pending
+            .iter()
+            .filter(|(_, events)| self.get_due_time(events) <= now)
+            .map(|(folder_name, _)| folder_name.clone())
+            .collect();
+        for folder_name in folder_names.iter() {
+            pending.remove(folder_name);
+        }
+        folder_names
+    }
+
+    // Delays the refresh of a folder that was busy when it was due
+    pub fn postpone(&self, folder_name: &str) {
+        let mut pending = self.pending.lock().unwrap();
+        push_event(&mut pending, folder_name.to_string(), Instant::now());
+    }
+
+    pub fn get_pending(&self) -> Vec<PendingAutoRefresh> {
+        let now = Instant::now();
+        let pending = self.pending.lock().unwrap();
+        let mut pending: Vec<PendingAutoRefresh> = pending
+            .iter()
+            .map(|(folder_name, events)| PendingAutoRefresh {
+                folder_name: folder_name.clone(),
+                total_events: events.total_events,
+                remaining: self.get_due_time(events).saturating_duration_since(now),
+            })
+            .collect();
+        sort_natural_by(pending.as_mut_slice(), |pending| pending.folder_name.as_str());
+        pending
+    }
+
+    pub fn get_delay(&self) -> Duration {
+        self.delay
+    }
+}
@@ -0,0 +1,201 @@
+use hyper::{Body, Method, Request, Response, StatusCode};
+use serde;
+use serde_json;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use crate::app::App;
+use crate::app_folder::FolderStatus;
+use crate::metrics::get_metrics;
+
+#[derive(serde::Serialize)]
+struct FolderStatusBody {
+    name: String,
+    status: &'static str,
+    is_busy: bool,
+}
+
+#[derive(serde::Serialize)]
+struct StatusBody {
+    root_path: String,
+    is_busy: bool,
+    folders: Vec<FolderStatusBody>,
+}
+
+fn create_response(status: StatusCode, body: String) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .body(Body::from(body))
+        .expect("Response is valid")
+}
+
+fn create_message_response(status: StatusCode, message: &str) -> Response<Body> {
+    create_response(status, serde_json::json!({ "message": message }).to_string())
+}
+
+fn get_query_param(req: &Request<Body>, key: &str) -> Option<String> {
+    url::form_urlencoded::parse(req.uri().query()?.as_bytes())
+        .find(|(other, _)| other == key)
+        .map(|(_, value)| value.to_string())
+}
+
+async fn get_status(app: &App) -> Response<Body> {
+    let folders = app.get_folders().read().await.clone();
+    let mut folder_statuses = Vec::new();
+    for folder in folders.iter() {
+        folder_statuses.push(FolderStatusBody {
+            name: folder.get_folder_name().to_string(),
+            status: folder.get_folder_status().await.to_str(),
+            is_busy: folder.get_busy_lock().try_lock().is_err(),
+        });
+    }
+    let body = StatusBody {
+        root_path: app.get_root_path().read().await.clone(),
+        is_busy: app.get_folders_busy_lock().try_lock().is_err(),
+        folders: folder_statuses,
+    };
+    match serde_json::to_string(&body) {
+        Ok(body) => create_response(StatusCode::OK, body),
+        Err(err) => create_message_response(StatusCode::INTERNAL_SERVER_ERROR, err.to_string().as_str()),
+    }
+}
+
+// Gauges are read when scraped, counters and timers come from the metrics registry
+async fn get_prometheus_metrics(app: &App) -> Response<Body> {
+    let mut text = get_metrics().to_prometheus_text();
+    let folders = app.get_folders().read().await.clone();
+    let mut status_counts: enum_map::EnumMap<FolderStatus, usize> = enum_map::enum_map! { _ => 0 };
+    let mut total_busy = 0;
+    for folder in folders.iter() {
+        if folder.is_excluded().await {
+            continue;
+        }
+        status_counts[folder.get_folder_status().await] += 1;
+        if folder.get_busy_lock().try_lock().is_err() {
+            total_busy += 1;
+        }
+    }
+    text.push_str("# HELP torrent_renamer_folders Folders by status\n");
+    text.push_str("# TYPE torrent_renamer_folders gauge\n");
+    for status in FolderStatus::iterator() {
+        text.push_str(format!("torrent_renamer_folders{{status=\"{}\"}} {}\n", status.to_str(), status_counts[*status]).as_str());
+    }
+    text.push_str("# HELP torrent_renamer_busy_folders Folders which are being scanned or executed\n");
+    text.push_str("# TYPE torrent_renamer_busy_folders gauge\n");
+    text.push_str(format!("torrent_renamer_busy_folders {}\n", total_busy).as_str());
+
+    let total_queued = match app.get_folder_watcher().read().await.as_ref() {
+        Some(watcher) => watcher.get_pending().len(),
+        None => 0,
+    };
+    text.push_str("# HELP torrent_renamer_refresh_queue_depth Folders waiting for their files to settle before a refresh\n");
+    text.push_str("# TYPE torrent_renamer_refresh_queue_depth gauge\n");
+    text.push_str(format!("torrent_renamer_refresh_queue_depth {}\n", total_queued).as_str());
+
+    let total_interrupted = app.get_interrupted_executions().read().await.len();
+    text.push_str("# HELP torrent_renamer_interrupted_executions Executions waiting to be resumed or rolled back\n");
+    text.push_str("# TYPE torrent_renamer_interrupted_executions gauge\n");
+    text.push_str(format!("torrent_renamer_interrupted_executions {}\n", total_interrupted).as_str());
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", "text/plain; version=0.0.4")
+        .body(Body::from(text))
+        .expect("Response is valid")
+}
+
+// Compared in constant time so the token can't be guessed from response times
+fn is_authorized(req: &Request<Body>, api_token: Option<&str>) -> bool {
+    let api_token = match api_token {
+        Some(api_token) => api_token,
+        None => return true,
+    };
+    let token = req.headers()
+        .get(hyper::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .unwrap_or_default();
+    token.len() == api_token.len() && token.bytes().zip(api_token.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+// Long running requests are started in the background, their progress shows up in the status and logs
+async fn handle_request(app: Arc<App>, api_token: Option<Arc<str>>, req: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let res = match (req.method(), req.uri().path()) {
+        (&Method::GET, "/api/status") => get_status(&app).await,
+        (&Method::GET, "/metrics") => get_prometheus_metrics(&app).await,
+        (&Method::POST, "/api/refresh" | "/api/execute") if !is_authorized(&req, api_token.as_deref()) => {
+            create_message_response(StatusCode::UNAUTHORIZED, "Missing or invalid api token")
+        },
+        (&Method::POST, "/api/refresh") => {
+            tokio::spawn(async move {
+                app.load_folders_from_existing_root_path().await;
+                app.update_file_intents_for_all_folders().await
+            });
+            create_message_response(StatusCode::ACCEPTED, "Refresh started")
+        },
+        (&Method::POST, "/api/execute") => match get_query_param(&req, "folder") {
+            Some(folder_name) => {
+                let folder = app.get_folders().read().await
+                    .iter()
+                    .find(|folder| folder.get_folder_name() == folder_name)
+                    .cloned();
+                match folder {
+                    Some(folder) => {
+                        tokio::spawn(async move {
+                            folder.execute_file_changes().await;
+                            folder.update_file_intents().await
+                        });
+                        create_message_response(StatusCode::ACCEPTED, "Execution started")
+                    },
+                    None => create_message_response(StatusCode::NOT_FOUND, "Folder not found"),
+                }
+            },
+            None => {
+                tokio::spawn(async move {
+                    app.execute_pending_folders().await
+                });
+                create_message_response(StatusCode::ACCEPTED, "Execution started")
+            },
+        },
+        (_, "/api/status" | "/api/refresh" | "/api/execute" | "/metrics") => create_message_response(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed"),
+        _ => create_message_response(StatusCode::NOT_FOUND, "Not found"),
+    };
+    Ok(res)
+}
+
+// Requests which change the library need the api token as a bearer token when one is given
+pub async fn serve_http_api(app: Arc<App>, address: SocketAddr, api_token: Option<String>) -> Result<(), hyper::Error> {
+    let api_token: Option<Arc<str>> = api_token.map(Arc::from);
+    let make_service = hyper::service::make_service_fn(move |_| {
+        let app = app.clone();
+        let api_token = api_token.clone();
+        async move {
+            Ok::<_, Infallible>(hyper::service::service_fn(move |req| handle_request(app.clone(), api_token.clone(), req)))
+        }
+    });
+    hyper::Server::try_bind(&address)?.serve(make_service).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_request(authorization: Option<&str>) -> Request<Body> {
+        let mut builder = Request::builder().method(Method::POST).uri("/api/execute");
+        if let Some(authorization) = authorization {
+            builder = builder.header(hyper::header::AUTHORIZATION, authorization);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn mutating_requests_need_the_api_token() {
+        assert!(is_authorized(&create_request(None), None));
+        assert!(is_authorized(&create_request(Some("Bearer secret")), Some("secret")));
+        assert!(!is_authorized(&create_request(None), Some("secret")));
+        assert!(!is_authorized(&create_request(Some("Bearer secre")), Some("secret")));
+        assert!(!is_authorized(&create_request(Some("secret")), Some("secret")));
+    }
+}
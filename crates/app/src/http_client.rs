@@ -0,0 +1,82 @@
+use reqwest;
+use serde;
+use serde_json;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use thiserror;
+use crate::config_reload::save_config_value;
+
+const CONNECTION_TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Read from "network" in the app config, applies to every api the app talks to
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct NetworkSettings {
+    // e.g. "http://proxy:3128" or "socks5://127.0.0.1:1080", credentials can be given in the url
+    #[serde(default)]
+    pub proxy_url: Option<String>,
+    // PEM file with certificates trusted on top of the system ones, e.g. for an intercepting proxy
+    #[serde(default)]
+    pub ca_certificate_path: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum HttpClientError {
+    #[error("invalid proxy url '{}': {}", .0, .1)]
+    InvalidProxy(String, reqwest::Error),
+    #[error("failed to read certificate '{}': {}", .0, .1)]
+    ReadCertificate(String, std::io::Error),
+    #[error("invalid certificate '{}': {}", .0, .1)]
+    InvalidCertificate(String, reqwest::Error),
+    #[error("no certificates found in '{}'", .0)]
+    MissingCertificate(String),
+    #[error("failed to create http client: {}", .0)]
+    Build(reqwest::Error),
+}
+
+pub async fn create_client(settings: &NetworkSettings) -> Result<reqwest::Client, HttpClientError> {
+    let mut builder = reqwest::Client::builder();
+    if let Some(proxy_url) = settings.proxy_url.as_deref().filter(|url| !url.trim().is_empty()) {
+        let proxy = reqwest::Proxy::all(proxy_url.trim()).map_err(|err| HttpClientError::InvalidProxy(proxy_url.to_string(), err))?;
+        builder = builder.proxy(proxy);
+    }
+    if let Some(path) = settings.ca_certificate_path.as_deref().filter(|path| !path.trim().is_empty()) {
+        let data = tokio::fs::read(path).await.map_err(|err| HttpClientError::ReadCertificate(path.to_string(), err))?;
+        let certificates = reqwest::Certificate::from_pem_bundle(data.as_slice())
+            .map_err(|err| HttpClientError::InvalidCertificate(path.to_string(), err))?;
+        if certificates.is_empty() {
+            return Err(HttpClientError::MissingCertificate(path.to_string()));
+        }
+        for certificate in certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+    builder.build().map_err(HttpClientError::Build)
+}
+
+#[derive(Debug, Clone)]
+pub struct ConnectionTest {
+    pub status: reqwest::StatusCode,
+    pub latency: Duration,
+}
+
+// Any response counts as a working connection, the api rejects requests without a token anyway
+pub async fn test_connection(settings: &NetworkSettings, url: &str) -> Result<ConnectionTest, String> {
+    let client = create_client(settings).await.map_err(|err| err.to_string())?;
+    let start = Instant::now();
+    let res = client.head(url)
+        .timeout(CONNECTION_TEST_TIMEOUT)
+        .send()
+        .await
+        .map_err(|err| format!("Couldn't connect to '{}': {}", url, err))?;
+    Ok(ConnectionTest {
+        status: res.status(),
+        latency: start.elapsed(),
+    })
+}
+
+// Only the "network" entry is replaced, unknown entries in the config are kept
+pub async fn save_network_settings(config_path: &Path, settings: &NetworkSettings) -> Result<(), String> {
+    let value = serde_json::to_value(settings).map_err(|err| err.to_string())?;
+    save_config_value(config_path, "network", value).await
+}
+
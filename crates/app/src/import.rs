@@ -0,0 +1,131 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tvdb::models::Series;
+use walkdir;
+use crate::file_descriptor::{get_absolute_descriptor, get_descriptor, get_readable_title};
+use crate::natural_sort::sort_natural_by;
+use crate::progress::ProgressTracker;
+
+#[derive(Debug, Clone)]
+pub struct ImportCandidate {
+    pub series: Series,
+    pub folder_name: String,
+    pub is_new_folder: bool,
+}
+
+// A download outside the library root which is moved into a series folder
+#[derive(Debug, Clone)]
+pub struct ImportPlan {
+    pub source_path: String,
+    pub search_name: String,
+    // Paths relative to the download, which are kept inside the series folder
+    pub files: Vec<String>,
+    pub candidates: Vec<ImportCandidate>,
+    pub selected_candidate: Option<usize>,
+}
+
+impl ImportPlan {
+    pub fn get_selected_candidate(&self) -> Option<&ImportCandidate> {
+        self.candidates.get(self.selected_candidate?)
+    }
+}
+
+// Release names put the quality, season pack or year after the series name
+// E.g. "Show.Name.S01.1080p.WEB-DL" or "[Group] Show Name - 01 [1080p].mkv"
+pub fn get_series_name_from_download(name: &str) -> Option<String> {
+    lazy_static! {
+        static ref CUTOFF_REGEX: Regex = Regex::new(
+            r"(?i)([\.\s_\-\(\[]+((s\d{1,2})|(season\s*\d+)|((19|20)\d\d)|(\d{3,4}p)|(complete)|(web|hdtv|bluray|x264|x265|hevc))\b)|\["
+        ).unwrap();
+        static ref GROUP_REGEX: Regex = Regex::new(r"^\[[^\]]*\][\s_]*").unwrap();
+    }
+
+    if let Some(descriptor) = get_descriptor(name) {
+        let title = get_readable_title(descriptor.title.as_str());
+        if !title.is_empty() {
+            return Some(title);
+        }
+    }
+    if let Some(descriptor) = get_absolute_descriptor(name) {
+        let title = get_readable_title(descriptor.title.as_str());
+        if !title.is_empty() {
+            return Some(title);
+        }
+    }
+
+    let name = GROUP_REGEX.replace(name, "");
+    let end = CUTOFF_REGEX.find(name.as_ref()).map(|m| m.start()).unwrap_or(name.len());
+    let title = get_readable_title(&name[..end]);
+    if title.is_empty() {
+        None
+    } else {
+        Some(title)
+    }
+}
+
+pub fn get_download_files(source_path: &Path) -> Vec<String> {
+    if source_path.is_file() {
+        return source_path
+            .file_name()
+            .map(|filename| vec![filename.to_string_lossy().to_string()])
+            .unwrap_or_default();
+    }
+
+    let mut files: Vec<String> = walkdir::WalkDir::new(source_path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            let rel_path = entry.path().strip_prefix(source_path).ok()?;
+            Some(rel_path.to_string_lossy().replace(std::path::MAIN_SEPARATOR, "/"))
+        })
+        .collect();
+    sort_natural_by(files.as_mut_slice(), |file| file.as_str());
+    files
+}
+
+pub fn get_download_file_path(source_path: &Path, file: &str) -> PathBuf {
+    if source_path.is_file() {
+        return source_path.to_path_buf();
+    }
+    source_path.join(file)
+}
+
+const COPY_BUFFER_SIZE: usize = 1024*1024;
+
+// Copies in chunks so that progress is reported while large videos are copied
+async fn copy_file(src: &Path, dest: &Path, progress: &ProgressTracker) -> Result<(), std::io::Error> {
+    let mut reader = tokio::fs::File::open(src).await?;
+    let mut writer = tokio::fs::File::create(dest).await?;
+    let mut buffer = vec![0u8; COPY_BUFFER_SIZE];
+    loop {
+        let total_read = reader.read(buffer.as_mut_slice()).await?;
+        if total_read == 0 {
+            break;
+        }
+        writer.write_all(&buffer[..total_read]).await?;
+        progress.add(total_read as u64);
+    }
+    writer.flush().await?;
+    writer.set_permissions(reader.metadata().await?.permissions()).await
+}
+
+// Downloads are usually on a different drive to the library so renames can fail
+pub async fn move_file(src: &Path, dest: &Path, progress: &ProgressTracker) -> Result<(), std::io::Error> {
+    if let Some(parent_dir) = dest.parent() {
+        tokio::fs::create_dir_all(parent_dir).await?;
+    }
+    let size = tokio::fs::metadata(src).await?.len();
+    if tokio::fs::rename(src, dest).await.is_ok() {
+        progress.add(size);
+        return Ok(());
+    }
+    if let Err(err) = copy_file(src, dest, progress).await {
+        // don't leave a partial copy behind
+        let _ = tokio::fs::remove_file(dest).await;
+        return Err(err);
+    }
+    tokio::fs::remove_file(src).await
+}
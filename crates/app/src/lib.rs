@@ -6,4 +6,51 @@ pub mod bookmarks;
 pub mod file_descriptor;
 pub mod file_intent;
 pub mod transliterate;
+pub mod anime_mapping;
+pub mod folder_settings;
+pub mod plan_diff;
+pub mod activity_log;
+pub mod trash;
+pub mod thumbnail;
+pub mod companion;
+pub mod import;
+pub mod watchlist;
+pub mod calendar;
+pub mod move_planner;
+pub mod execution_journal;
+pub mod permissions;
+pub mod progress;
+pub mod metrics;
+pub mod scan_index;
+pub mod folder_watcher;
+pub mod remux;
+pub mod metadata_tags;
+pub mod subtitles;
 
+pub mod trakt_sync;
+pub mod torrent_labels;
+pub mod rule_profiles;
+pub mod diagnostics;
+pub mod atomic_file;
+pub mod library_lock;
+pub mod remote_fs;
+pub mod daemon;
+pub mod http_api;
+pub mod config_reload;
+pub mod archive;
+pub mod natural_sort;
+pub mod refresh_queue;
+pub mod folder_summary;
+pub mod other_media;
+pub mod library_layout;
+pub mod external_ids;
+pub mod rebind;
+pub mod execution_summary;
+pub mod execution_skips;
+pub mod torrent_cleanup;
+pub mod cleanup_queue;
+pub mod execution_outcomes;
+pub mod http_client;
+pub mod watch_state;
+pub mod folder_merge;
+pub mod duplicates;
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+use std::path::Path;
+use crate::execution_journal::JOURNAL_FOLDER_NAME;
+use crate::file_descriptor::get_folder_season;
+use crate::trash::TRASH_FOLDER_NAME;
+
+// Deep enough for layouts like "TV/Drama/A/Show"
+pub const MAX_LIBRARY_DEPTH: usize = 3;
+
+#[derive(Debug, Default, Clone)]
+pub struct DirEntries {
+    pub has_files: bool,
+    pub subfolders: Vec<String>,
+}
+
+// Genre or letter folders only hold other folders, while a series folder holds files or season folders
+pub fn is_group_folder(entries: &DirEntries) -> bool {
+    !entries.has_files &&
+        !entries.subfolders.is_empty() &&
+        !entries.subfolders.iter().any(|name| name.eq_ignore_ascii_case("specials") || get_folder_season(name).is_some())
+}
+
+fn join_folder_name(parent: &str, name: &str) -> String {
+    match parent.is_empty() {
+        true => name.to_string(),
+        false => format!("{}/{}", parent, name),
+    }
+}
+
+// Folder names are relative to the root folder and separated with "/", e.g. "Drama/Show"
+// Without a fixed depth each folder is checked until the series folders are found
+pub fn find_series_folders<E>(depth: Option<usize>, mut list_dir: impl FnMut(&str) -> Result<DirEntries, E>) -> Result<Vec<String>, E> {
+    let mut folder_names = Vec::new();
+    let mut pending = vec![(String::new(), 0, list_dir("")?)];
+    while let Some((parent, level, entries)) = pending.pop() {
+        for name in entries.subfolders {
+            if level == 0 && (name == TRASH_FOLDER_NAME || name == JOURNAL_FOLDER_NAME) {
+                continue;
+            }
+            let folder_name = join_folder_name(parent.as_str(), name.as_str());
+            let child_level = level+1;
+            match depth {
+                Some(depth) if child_level < depth => {
+                    let child_entries = list_dir(folder_name.as_str())?;
+                    pending.push((folder_name, child_level, child_entries));
+                },
+                Some(_) => folder_names.push(folder_name),
+                None if child_level < MAX_LIBRARY_DEPTH => {
+                    let child_entries = list_dir(folder_name.as_str())?;
+                    match is_group_folder(&child_entries) {
+                        true => pending.push((folder_name, child_level, child_entries)),
+                        false => folder_names.push(folder_name),
+                    }
+                },
+                None => folder_names.push(folder_name),
+            }
+        }
+    }
+    Ok(folder_names)
+}
+
+pub fn list_local_dir(root_path: &Path, folder_name: &str) -> Result<DirEntries, std::io::Error> {
+    let mut entries = DirEntries::default();
+    for entry in std::fs::read_dir(root_path.join(folder_name))? {
+        let entry = entry?;
+        match entry.file_type()?.is_dir() {
+            true => entries.subfolders.push(entry.file_name().to_string_lossy().to_string()),
+            false => entries.has_files = true,
+        }
+    }
+    Ok(entries)
+}
+
+// Groups a flat listing of paths relative to the root folder by their parent folder, e.g. the output of find
+pub fn group_dir_entries<'a>(paths: impl IntoIterator<Item = (&'a str, bool)>) -> HashMap<String, DirEntries> {
+    let mut dirs: HashMap<String, DirEntries> = HashMap::new();
+    for (path, is_dir) in paths {
+        let (parent, name) = match path.rsplit_once('/') {
+            Some((parent, name)) => (parent, name),
+            None => ("", path),
+        };
+        let entries = dirs.entry(parent.to_string()).or_default();
+        match is_dir {
+            true => entries.subfolders.push(name.to_string()),
+            false => entries.has_files = true,
+        }
+    }
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_listing() -> HashMap<String, DirEntries> {
+        group_dir_entries([
+            ("Flat Show", true),
+            ("Flat Show/Flat.Show.S01E01.mkv", false),
+            ("Drama", true),
+            ("Drama/Seasoned Show", true),
+            ("Drama/Seasoned Show/Season 1", true),
+            ("Drama/Seasoned Show/Specials", true),
+            ("Drama/Empty Show", true),
+            ("A", true),
+            ("A/Anime", true),
+            ("A/Anime/Alias", true),
+            ("A/Anime/Alias/Alias.S01E01.mkv", false),
+            (".trash", true),
+            (".trash/deleted.mkv", false),
+        ])
+    }
+
+    fn find_sorted(depth: Option<usize>) -> Vec<String> {
+        let listing = get_listing();
+        let mut folder_names = find_series_folders(depth, |name| -> Result<DirEntries, ()> {
+            Ok(listing.get(name).cloned().unwrap_or_default())
+        }).unwrap();
+        folder_names.sort();
+        folder_names
+    }
+
+    #[test]
+    fn series_folders_are_detected_at_any_level() {
+        assert_eq!(find_sorted(None), vec!["A/Anime/Alias", "Drama/Empty Show", "Drama/Seasoned Show", "Flat Show"]);
+    }
+
+    #[test]
+    fn fixed_depth_takes_every_folder_at_that_level() {
+        assert_eq!(find_sorted(Some(1)), vec!["A", "Drama", "Flat Show"]);
+        assert_eq!(find_sorted(Some(2)), vec!["A/Anime", "Drama/Empty Show", "Drama/Seasoned Show"]);
+    }
+
+    #[test]
+    fn season_folders_mark_a_series_folder() {
+        let entries = DirEntries { has_files: false, subfolders: vec!["Extras".to_string(), "Season Two".to_string()] };
+        assert!(!is_group_folder(&entries));
+        let entries = DirEntries { has_files: false, subfolders: vec!["Comedy".to_string()] };
+        assert!(is_group_folder(&entries));
+    }
+}
@@ -0,0 +1,210 @@
+use chrono;
+use serde;
+use serde_json;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::RwLock;
+
+pub const LOCK_FILENAME: &str = ".torrent_renamer.lock";
+// The holder rewrites the lock periodically, a lock that hasn't been touched for this long was left by a crashed instance
+const STALE_LOCK_SECS: i64 = 120;
+const HEARTBEAT_SECS: i64 = 30;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LockOwner {
+    pub pid: u32,
+    pub hostname: String,
+    pub started: chrono::DateTime<chrono::Utc>,
+}
+
+impl LockOwner {
+    fn new() -> Self {
+        let hostname = std::env::var("HOSTNAME")
+            .or_else(|_| std::env::var("COMPUTERNAME"))
+            .unwrap_or_else(|_| "unknown".to_string());
+        Self {
+            pid: std::process::id(),
+            hostname,
+            started: chrono::Utc::now(),
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+struct LockFile {
+    owner: LockOwner,
+    heartbeat: chrono::DateTime<chrono::Utc>,
+}
+
+impl LockFile {
+    fn is_stale(&self) -> bool {
+        (chrono::Utc::now() - self.heartbeat).num_seconds() > STALE_LOCK_SECS
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum LockState {
+    Unlocked,
+    Held { path: PathBuf, heartbeat: chrono::DateTime<chrono::Utc> },
+    HeldByOther { path: PathBuf, owner: LockOwner },
+}
+
+// Stops two instances from changing files in the same root folder at the same time
+pub struct LibraryLock {
+    owner: LockOwner,
+    state: RwLock<LockState>,
+}
+
+async fn read_lock_file(path: &Path) -> Option<LockFile> {
+    let data = tokio::fs::read_to_string(path).await.ok()?;
+    serde_json::from_str(data.as_str()).ok()
+}
+
+fn encode_lock_file(owner: &LockOwner) -> Result<(String, chrono::DateTime<chrono::Utc>), std::io::Error> {
+    let lock_file = LockFile {
+        owner: owner.clone(),
+        heartbeat: chrono::Utc::now(),
+    };
+    let data = serde_json::to_string_pretty(&lock_file).map_err(std::io::Error::from)?;
+    Ok((data, lock_file.heartbeat))
+}
+
+// Fails if the lock file already exists so two instances starting together can't both acquire it
+async fn create_lock_file(path: &Path, owner: &LockOwner) -> Result<chrono::DateTime<chrono::Utc>, std::io::Error> {
+    let (data, heartbeat) = encode_lock_file(owner)?;
+    let mut file = tokio::fs::OpenOptions::new().write(true).create_new(true).open(path).await?;
+    file.write_all(data.as_bytes()).await?;
+    file.sync_all().await?;
+    Ok(heartbeat)
+}
+
+// Replaced with a rename so that the lock file never goes missing while it is rewritten
+async fn write_lock_file(path: &Path, owner: &LockOwner) -> Result<chrono::DateTime<chrono::Utc>, std::io::Error> {
+    let (data, heartbeat) = encode_lock_file(owner)?;
+    let mut tmp_path = path.as_os_str().to_os_string();
+    tmp_path.push(".tmp");
+    tokio::fs::write(tmp_path.as_os_str(), data).await?;
+    tokio::fs::rename(tmp_path.as_os_str(), path).await?;
+    Ok(heartbeat)
+}
+
+impl LibraryLock {
+    pub fn new() -> Self {
+        Self {
+            owner: LockOwner::new(),
+            state: RwLock::new(LockState::Unlocked),
+        }
+    }
+
+    // Releases the lock of the previous root folder, a lock held by a live instance is left alone
+    pub async fn acquire(&self, root_path: &str) -> Result<(), std::io::Error> {
+        self.release().await;
+        let path = Path::new(root_path).join(LOCK_FILENAME);
+        let heartbeat = match create_lock_file(path.as_path(), &self.owner).await {
+            Ok(heartbeat) => heartbeat,
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+                match read_lock_file(path.as_path()).await {
+                    Some(lock_file) if !lock_file.is_stale() => {
+                        *self.state.write().await = LockState::HeldByOther { path, owner: lock_file.owner };
+                        return Ok(());
+                    },
+                    // Unreadable locks are treated as stale
+                    _ => write_lock_file(path.as_path(), &self.owner).await?,
+                }
+            },
+            Err(err) => return Err(err),
+        };
+        *self.state.write().await = LockState::Held { path, heartbeat };
+        Ok(())
+    }
+
+    // For when the other instance is known to be gone but its lock isn't stale yet
+    pub async fn take_over(&self) -> Result<(), std::io::Error> {
+        let mut state = self.state.write().await;
+        let path = match &*state {
+            LockState::HeldByOther { path, .. } => path.clone(),
+            _ => return Ok(()),
+        };
+        let heartbeat = write_lock_file(path.as_path(), &self.owner).await?;
+        *state = LockState::Held { path, heartbeat };
+        Ok(())
+    }
+
+    pub async fn release(&self) {
+        let mut state = self.state.write().await;
+        if let LockState::Held { path, .. } = &*state {
+            let _ = tokio::fs::remove_file(path.as_path()).await;
+        }
+        *state = LockState::Unlocked;
+    }
+
+    // Keeps our lock from going stale and picks up a lock which the other instance let go of
+    pub async fn refresh(&self) -> Result<(), std::io::Error> {
+        let state = self.state.read().await.clone();
+        match state {
+            LockState::Held { path, heartbeat } => {
+                if (chrono::Utc::now() - heartbeat).num_seconds() < HEARTBEAT_SECS {
+                    return Ok(());
+                }
+                let heartbeat = write_lock_file(path.as_path(), &self.owner).await?;
+                *self.state.write().await = LockState::Held { path, heartbeat };
+                Ok(())
+            },
+            LockState::HeldByOther { path, .. } => {
+                let is_free = match read_lock_file(path.as_path()).await {
+                    Some(lock_file) => lock_file.is_stale(),
+                    None => true,
+                };
+                if !is_free {
+                    return Ok(());
+                }
+                let heartbeat = write_lock_file(path.as_path(), &self.owner).await?;
+                *self.state.write().await = LockState::Held { path, heartbeat };
+                Ok(())
+            },
+            LockState::Unlocked => Ok(()),
+        }
+    }
+
+    // Checked before anything is changed on disk, the lock file is reread in case another instance took it over
+    pub async fn check(&self) -> Result<(), String> {
+        let state = self.state.read().await.clone();
+        match state {
+            LockState::Unlocked => Ok(()),
+            LockState::HeldByOther { owner, .. } => Err(get_held_message(&owner)),
+            LockState::Held { path, .. } => match read_lock_file(path.as_path()).await {
+                Some(lock_file) if lock_file.owner != self.owner => {
+                    let message = get_held_message(&lock_file.owner);
+                    *self.state.write().await = LockState::HeldByOther { path, owner: lock_file.owner };
+                    Err(message)
+                },
+                _ => Ok(()),
+            },
+        }
+    }
+
+    pub fn get_state(&self) -> &RwLock<LockState> {
+        &self.state
+    }
+}
+
+impl Default for LibraryLock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for LibraryLock {
+    fn drop(&mut self) {
+        if let LockState::Held { path, .. } = self.state.get_mut() {
+            let _ = std::fs::remove_file(path.as_path());
+        }
+    }
+}
+
+fn get_held_message(owner: &LockOwner) -> String {
+    format!(
+        "Another instance (pid {} on {}, started {}) is using this root folder, changes are disabled until it exits",
+        owner.pid, owner.hostname, owner.started.format("%Y-%m-%d %H:%M:%S"),
+    )
+}
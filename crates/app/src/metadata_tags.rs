@@ -0,0 +1,70 @@
+use std::path::Path;
+use tokio;
+use crate::remux::copy_streams;
+use crate::tvdb_cache::{EpisodeKey, EpisodeOrdering, TvdbCache};
+
+// ffmpeg maps these onto matroska tags and mp4 atoms (tvsh, tvsn, tves, tven)
+#[derive(Debug, Clone)]
+pub struct EpisodeTags {
+    pub show: String,
+    pub season: u32,
+    pub episode: u32,
+    pub title: Option<String>,
+}
+
+impl EpisodeTags {
+    pub fn new(cache: &TvdbCache, key: EpisodeKey, ordering: EpisodeOrdering, language: Option<&str>) -> Self {
+        let title = cache.get_episode_index(&key, ordering)
+            .and_then(|index| cache.get_episode_name(index, language))
+            .map(|title| title.to_string());
+        Self {
+            show: cache.series.name.clone(),
+            season: key.season,
+            episode: key.episode,
+            title,
+        }
+    }
+
+    fn get_ffmpeg_args(&self) -> Vec<String> {
+        let mut metadata = vec![
+            format!("show={}", self.show),
+            format!("season_number={}", self.season),
+            format!("episode_sort={}", self.episode),
+            format!("episode_id=S{:02}E{:02}", self.season, self.episode),
+        ];
+        if let Some(title) = self.title.as_ref() {
+            metadata.push(format!("title={}", title));
+        }
+        metadata
+            .into_iter()
+            .flat_map(|entry| ["-metadata".to_string(), entry])
+            .collect()
+    }
+}
+
+fn get_container_format(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_string_lossy().to_lowercase();
+    match extension.as_str() {
+        "mkv" => Some("matroska"),
+        "mp4" | "m4v" => Some("mp4"),
+        _ => None,
+    }
+}
+
+pub fn is_taggable_path(path: &Path) -> bool {
+    get_container_format(path).is_some()
+}
+
+// The tagged copy replaces the original once it has been fully written
+pub async fn write_episode_tags(ffmpeg_path: &str, path: &Path, tags: &EpisodeTags) -> Result<(), String> {
+    let format = get_container_format(path).ok_or_else(|| "Only mkv and mp4 files can be tagged".to_string())?;
+    let mut partial = path.as_os_str().to_os_string();
+    partial.push(".part");
+    let partial = Path::new(&partial);
+    // keep the existing tags and chapters besides the ones we overwrite
+    let mut args = vec!["-map_metadata".to_string(), "0".to_string()];
+    args.extend(tags.get_ffmpeg_args());
+    copy_streams(ffmpeg_path, path, partial, format, args.as_slice(), None).await?;
+    tokio::fs::rename(partial, path).await
+        .map_err(|err| format!("IO error while replacing file with tagged copy: {}", err))
+}
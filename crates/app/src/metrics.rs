@@ -0,0 +1,177 @@
+use lazy_static::lazy_static;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// Names double as labels in the debug panel
+pub const FOLDER_SCAN: &str = "Folder scan";
+pub const TVDB_REQUEST: &str = "TVDB request";
+pub const ANILIST_REQUEST: &str = "AniList request";
+pub const OPENSUBTITLES_REQUEST: &str = "OpenSubtitles request";
+pub const TRAKT_REQUEST: &str = "Trakt request";
+pub const QBITTORRENT_REQUEST: &str = "qBittorrent request";
+pub const MEDIA_SERVER_REQUEST: &str = "Media server request";
+pub const EPISODE_CACHE: &str = "Episode cache";
+pub const THUMBNAIL_CACHE: &str = "Thumbnail cache";
+pub const INTENT_CACHE: &str = "Unchanged files";
+pub const RENAMES_EXECUTED: &str = "Renames executed";
+pub const DELETES_EXECUTED: &str = "Deletes executed";
+pub const EXECUTION_ERRORS: &str = "Execution errors";
+pub const ERRORS_LOGGED: &str = "Errors logged";
+
+#[derive(Debug, Clone, Default)]
+pub struct TimerStats {
+    pub count: u64,
+    pub total: Duration,
+    pub min: Duration,
+    pub max: Duration,
+    pub last: Duration,
+}
+
+impl TimerStats {
+    fn add(&mut self, duration: Duration) {
+        self.min = if self.count == 0 { duration } else { self.min.min(duration) };
+        self.max = self.max.max(duration);
+        self.count += 1;
+        self.total += duration;
+        self.last = duration;
+    }
+
+    pub fn get_mean(&self) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+        self.total / self.count as u32
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HitStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl HitStats {
+    pub fn get_hit_rate(&self) -> Option<f32> {
+        let total = self.hits + self.misses;
+        (total > 0).then(|| self.hits as f32 / total as f32)
+    }
+}
+
+// Records durations and cache hits in memory so slow parts of big libraries can be found
+#[derive(Default)]
+pub struct MetricsRegistry {
+    timers: Mutex<BTreeMap<&'static str, TimerStats>>,
+    hits: Mutex<BTreeMap<&'static str, HitStats>>,
+    counters: Mutex<BTreeMap<&'static str, u64>>,
+}
+
+pub struct MetricsTimer {
+    name: &'static str,
+    start: Instant,
+}
+
+impl Drop for MetricsTimer {
+    fn drop(&mut self) {
+        get_metrics().record_duration(self.name, self.start.elapsed());
+    }
+}
+
+impl MetricsRegistry {
+    pub fn record_duration(&self, name: &'static str, duration: Duration) {
+        self.timers.lock().unwrap().entry(name).or_default().add(duration);
+    }
+
+    // Records the time until the returned timer is dropped
+    pub fn start_timer(&self, name: &'static str) -> MetricsTimer {
+        MetricsTimer { name, start: Instant::now() }
+    }
+
+    pub async fn time<F: Future>(&self, name: &'static str, future: F) -> F::Output {
+        let _timer = self.start_timer(name);
+        future.await
+    }
+
+    pub fn record_hit(&self, name: &'static str, is_hit: bool) {
+        let mut hits = self.hits.lock().unwrap();
+        let stats = hits.entry(name).or_default();
+        match is_hit {
+            true => stats.hits += 1,
+            false => stats.misses += 1,
+        }
+    }
+
+    pub fn add_count(&self, name: &'static str, count: u64) {
+        *self.counters.lock().unwrap().entry(name).or_default() += count;
+    }
+
+    pub fn get_timers(&self) -> Vec<(&'static str, TimerStats)> {
+        self.timers.lock().unwrap().iter().map(|(name, stats)| (*name, stats.clone())).collect()
+    }
+
+    pub fn get_hits(&self) -> Vec<(&'static str, HitStats)> {
+        self.hits.lock().unwrap().iter().map(|(name, stats)| (*name, *stats)).collect()
+    }
+
+    pub fn get_counters(&self) -> Vec<(&'static str, u64)> {
+        self.counters.lock().unwrap().iter().map(|(name, count)| (*name, *count)).collect()
+    }
+
+    pub fn reset(&self) {
+        self.timers.lock().unwrap().clear();
+        self.hits.lock().unwrap().clear();
+        self.counters.lock().unwrap().clear();
+    }
+
+    // Prometheus text exposition format, the names of timers and caches become labels
+    pub fn to_prometheus_text(&self) -> String {
+        let mut text = String::new();
+        let timers = self.get_timers();
+        text.push_str("# HELP torrent_renamer_duration_seconds Time taken by scans, api requests and caches\n");
+        text.push_str("# TYPE torrent_renamer_duration_seconds summary\n");
+        for (name, stats) in timers.iter() {
+            let label = get_prometheus_label(name);
+            text.push_str(format!("torrent_renamer_duration_seconds_sum{{name=\"{}\"}} {}\n", label, stats.total.as_secs_f64()).as_str());
+            text.push_str(format!("torrent_renamer_duration_seconds_count{{name=\"{}\"}} {}\n", label, stats.count).as_str());
+        }
+        text.push_str("# HELP torrent_renamer_duration_seconds_max Longest recorded duration\n");
+        text.push_str("# TYPE torrent_renamer_duration_seconds_max gauge\n");
+        for (name, stats) in timers.iter() {
+            text.push_str(format!("torrent_renamer_duration_seconds_max{{name=\"{}\"}} {}\n", get_prometheus_label(name), stats.max.as_secs_f64()).as_str());
+        }
+
+        let hits = self.get_hits();
+        text.push_str("# HELP torrent_renamer_cache_lookups_total Cache lookups by result\n");
+        text.push_str("# TYPE torrent_renamer_cache_lookups_total counter\n");
+        for (name, stats) in hits.iter() {
+            let label = get_prometheus_label(name);
+            text.push_str(format!("torrent_renamer_cache_lookups_total{{name=\"{}\",result=\"hit\"}} {}\n", label, stats.hits).as_str());
+            text.push_str(format!("torrent_renamer_cache_lookups_total{{name=\"{}\",result=\"miss\"}} {}\n", label, stats.misses).as_str());
+        }
+
+        for (name, count) in self.get_counters() {
+            let metric = format!("torrent_renamer_{}_total", get_prometheus_name(name));
+            text.push_str(format!("# TYPE {} counter\n", metric).as_str());
+            text.push_str(format!("{} {}\n", metric, count).as_str());
+        }
+        text
+    }
+}
+
+// E.g. "Renames executed" => "renames_executed"
+fn get_prometheus_name(name: &str) -> String {
+    name.chars().map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' }).collect()
+}
+
+fn get_prometheus_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+lazy_static! {
+    static ref METRICS: MetricsRegistry = MetricsRegistry::default();
+}
+
+pub fn get_metrics() -> &'static MetricsRegistry {
+    &METRICS
+}
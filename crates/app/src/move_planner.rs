@@ -0,0 +1,168 @@
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveStep {
+    pub src: String,
+    pub dest: String,
+}
+
+impl MoveStep {
+    pub fn new(src: &str, dest: &str) -> Self {
+        Self {
+            src: src.to_string(),
+            dest: dest.to_string(),
+        }
+    }
+}
+
+fn get_temp_name(src: &str, used_names: &HashSet<String>) -> String {
+    (0..)
+        .map(|index| format!("{}.{}.move", src, index))
+        .find(|name| !used_names.contains(name))
+        .expect("Ran out of temporary names")
+}
+
+// Orders moves so that a file is only moved onto a path after the file there has moved away
+// Moves are expected to have unique sources and destinations, e.g. after conflicts are removed
+// Each cycle like a swap is broken by moving one of its files to a temporary name first
+pub fn plan_moves(moves: &[MoveStep]) -> Vec<MoveStep> {
+    let moves: Vec<&MoveStep> = moves.iter().filter(|step| step.src != step.dest).collect();
+    let mut used_names: HashSet<String> = moves.iter()
+        .flat_map(|step| [step.src.clone(), step.dest.clone()])
+        .collect();
+
+    // the move which has to vacate our destination before we can be moved there
+    let src_to_move: HashMap<&str, usize> = moves.iter()
+        .enumerate()
+        .map(|(index, step)| (step.src.as_str(), index))
+        .collect();
+    // the move which is waiting on our source to be vacated
+    let mut waiting_move: HashMap<usize, usize> = HashMap::new();
+    let mut ready_moves = Vec::new();
+    for (index, step) in moves.iter().enumerate() {
+        match src_to_move.get(step.dest.as_str()) {
+            Some(blocking_index) => { waiting_move.insert(*blocking_index, index); },
+            None => ready_moves.push(index),
+        }
+    }
+
+    let mut steps = Vec::with_capacity(moves.len());
+    let mut is_planned = vec![false; moves.len()];
+    // each chain is walked from its free end back to its start
+    let plan_chain = |start: usize, steps: &mut Vec<MoveStep>, is_planned: &mut Vec<bool>| {
+        let mut next = Some(start);
+        while let Some(index) = next {
+            if is_planned[index] {
+                break;
+            }
+            is_planned[index] = true;
+            steps.push(moves[index].clone());
+            next = waiting_move.get(&index).copied();
+        }
+    };
+    for index in ready_moves {
+        plan_chain(index, &mut steps, &mut is_planned);
+    }
+
+    // the remaining moves only form cycles since every chain has a free end
+    for index in 0..moves.len() {
+        if is_planned[index] {
+            continue;
+        }
+        let step = moves[index];
+        let temp_name = get_temp_name(step.src.as_str(), &used_names);
+        used_names.insert(temp_name.clone());
+        is_planned[index] = true;
+        steps.push(MoveStep::new(step.src.as_str(), temp_name.as_str()));
+        if let Some(waiting_index) = waiting_move.get(&index).copied() {
+            plan_chain(waiting_index, &mut steps, &mut is_planned);
+        }
+        steps.push(MoveStep::new(temp_name.as_str(), step.dest.as_str()));
+    }
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_moves(moves: &[(&str, &str)]) -> Vec<MoveStep> {
+        moves.iter().map(|(src, dest)| MoveStep::new(src, dest)).collect()
+    }
+
+    // Applies the steps to a fake filesystem that refuses to overwrite files
+    fn apply_steps(files: &[&str], steps: &[MoveStep]) -> HashMap<String, String> {
+        let mut filesystem: HashMap<String, String> = files.iter()
+            .map(|name| (name.to_string(), name.to_string()))
+            .collect();
+        for step in steps {
+            let data = filesystem.remove(step.src.as_str())
+                .unwrap_or_else(|| panic!("Moved missing file {:?}", step.src));
+            assert!(!filesystem.contains_key(step.dest.as_str()), "Clobbered file {:?}", step.dest);
+            filesystem.insert(step.dest.clone(), data);
+        }
+        filesystem
+    }
+
+    fn assert_moved(files: &[&str], moves: &[(&str, &str)]) -> Vec<MoveStep> {
+        let steps = plan_moves(create_moves(moves).as_slice());
+        let filesystem = apply_steps(files, steps.as_slice());
+        assert_eq!(filesystem.len(), files.len());
+        for (src, dest) in moves {
+            assert_eq!(filesystem.get(*dest).map(|data| data.as_str()), Some(*src));
+        }
+        steps
+    }
+
+    #[test]
+    fn independent_moves_keep_order() {
+        let steps = assert_moved(&["a", "b"], &[("a", "c"), ("b", "d")]);
+        assert_eq!(steps, create_moves(&[("a", "c"), ("b", "d")]));
+    }
+
+    #[test]
+    fn chain_moves_end_first() {
+        let steps = assert_moved(&["a", "b"], &[("a", "b"), ("b", "c")]);
+        assert_eq!(steps, create_moves(&[("b", "c"), ("a", "b")]));
+    }
+
+    #[test]
+    fn long_chain_in_any_order() {
+        let steps = assert_moved(&["a", "b", "c", "d"], &[("c", "d"), ("a", "b"), ("d", "e"), ("b", "c")]);
+        assert_eq!(steps, create_moves(&[("d", "e"), ("c", "d"), ("b", "c"), ("a", "b")]));
+    }
+
+    #[test]
+    fn swap_uses_temporary_name() {
+        let steps = assert_moved(&["a", "b"], &[("a", "b"), ("b", "a")]);
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[0], MoveStep::new("a", "a.0.move"));
+        assert_eq!(steps[2], MoveStep::new("a.0.move", "b"));
+    }
+
+    #[test]
+    fn rotation_uses_single_temporary_name() {
+        let steps = assert_moved(&["a", "b", "c"], &[("a", "b"), ("b", "c"), ("c", "a")]);
+        assert_eq!(steps.len(), 4);
+    }
+
+    #[test]
+    fn temporary_name_avoids_planned_paths() {
+        let steps = assert_moved(&["a", "b", "a.0.move"], &[("a", "b"), ("b", "a"), ("a.0.move", "c")]);
+        assert!(steps.iter().all(|step| step.dest != "a.0.move"));
+    }
+
+    #[test]
+    fn swap_and_chain_together() {
+        assert_moved(
+            &["s1", "s2", "c1", "c2"],
+            &[("s1", "s2"), ("c1", "c2"), ("s2", "s1"), ("c2", "c3")],
+        );
+    }
+
+    #[test]
+    fn self_moves_are_skipped() {
+        let steps = assert_moved(&["a"], &[("a", "a")]);
+        assert!(steps.is_empty());
+    }
+}
@@ -0,0 +1,106 @@
+use std::cmp::Ordering;
+use crate::transliterate::transliterate;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Digits {
+    // Leading zeros are stripped so numbers of any length compare by value
+    value: String,
+    total_zeros: usize,
+}
+
+impl Ord for Digits {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value.len().cmp(&other.value.len())
+            .then_with(|| self.value.cmp(&other.value))
+            .then_with(|| self.total_zeros.cmp(&other.total_zeros))
+    }
+}
+
+impl PartialOrd for Digits {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Numbers come before text like they do in byte order
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Chunk {
+    Number(Digits),
+    Text(String),
+}
+
+fn get_chunks(name: &str) -> Vec<Chunk> {
+    let mut chunks = Vec::new();
+    let mut chars = name.chars().peekable();
+    while let Some(c) = chars.peek().copied() {
+        let is_digit = c.is_ascii_digit();
+        let mut chunk = String::new();
+        while let Some(c) = chars.next_if(|c| c.is_ascii_digit() == is_digit) {
+            chunk.push(c);
+        }
+        chunks.push(match is_digit {
+            true => {
+                let value = chunk.trim_start_matches('0');
+                Chunk::Number(Digits { value: value.to_string(), total_zeros: chunk.len() - value.len() })
+            },
+            false => Chunk::Text(chunk),
+        });
+    }
+    chunks
+}
+
+// Sorts "E2" before "E10" and compares accented and differently cased letters like their plain forms
+// The original name breaks ties so the order is always the same
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct NaturalKey {
+    chunks: Vec<Chunk>,
+    name: String,
+}
+
+impl NaturalKey {
+    pub fn new(name: &str) -> Self {
+        let folded = transliterate(name).to_lowercase();
+        Self {
+            chunks: get_chunks(folded.as_str()),
+            name: name.to_string(),
+        }
+    }
+}
+
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    NaturalKey::new(a).cmp(&NaturalKey::new(b))
+}
+
+// Keys are only computed once per item since transliterating is slow for large lists
+pub fn sort_natural_by<T>(items: &mut [T], get_name: impl Fn(&T) -> &str) {
+    items.sort_by_cached_key(|item| NaturalKey::new(get_name(item)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_sorted(expected: &[&str]) {
+        let mut names: Vec<&str> = expected.iter().rev().copied().collect();
+        sort_natural_by(names.as_mut_slice(), |name| name);
+        assert_eq!(names, expected);
+    }
+
+    #[test]
+    fn numbers_are_compared_by_value() {
+        assert_sorted(&["Show S01E2.mkv", "Show S01E10.mkv", "Show S02E01.mkv", "Show S10E01.mkv"]);
+        assert_sorted(&["1", "01", "001", "2", "10", "99999999999999999999999"]);
+    }
+
+    #[test]
+    fn letters_ignore_case_and_accents() {
+        assert_sorted(&["apple", "Banana", "Élan", "zebra"]);
+        assert_sorted(&["Season 1", "season 1", "Season 2"]);
+    }
+
+    #[test]
+    fn numbers_come_before_text() {
+        assert_sorted(&["2 Broke Girls", "24", "Arrow"]);
+        assert_eq!(natural_cmp("Extras", "Extras"), Ordering::Equal);
+    }
+}
@@ -0,0 +1,93 @@
+use serde;
+use std::path::Path;
+use crate::file_intent::is_video_extension;
+
+const AUDIO_EXTENSIONS: [&str; 9] = ["mp3", "flac", "m4a", "aac", "ogg", "opus", "wav", "wma", "alac"];
+const BOOK_EXTENSIONS: [&str; 9] = ["epub", "mobi", "azw", "azw3", "pdf", "cbz", "cbr", "djvu", "m4b"];
+// A couple of stray audio files like a theme song aren't enough to exclude a folder
+const MIN_MEDIA_FILES: usize = 3;
+// Folders are only sampled since music collections can hold thousands of files
+pub const MAX_SAMPLED_FILES: usize = 256;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Eq, PartialEq, Copy, Clone)]
+#[serde(rename_all="snake_case")]
+pub enum OtherMedia {
+    Music,
+    Books,
+}
+
+impl OtherMedia {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            OtherMedia::Music => "Music",
+            OtherMedia::Books => "Books",
+        }
+    }
+}
+
+// Why a folder in the root folder isn't treated as a tv series
+#[derive(serde::Serialize, serde::Deserialize, Debug, Eq, PartialEq, Copy, Clone)]
+#[serde(rename_all="snake_case")]
+pub enum FolderExclusion {
+    Manual,
+    Detected(OtherMedia),
+}
+
+fn has_extension(extensions: &[&str], extension: &str) -> bool {
+    extensions.iter().any(|other| other.eq_ignore_ascii_case(extension))
+}
+
+// Any video file means the folder could still be a series that hasn't been bound yet
+pub fn detect_other_media<'a>(filenames: impl IntoIterator<Item = &'a str>) -> Option<OtherMedia> {
+    let mut total_files = 0;
+    let mut total_audio = 0;
+    let mut total_books = 0;
+    for filename in filenames {
+        total_files += 1;
+        let extension = match Path::new(filename).extension() {
+            Some(extension) => extension.to_string_lossy(),
+            None => continue,
+        };
+        if is_video_extension(extension.as_ref()) {
+            return None;
+        }
+        if has_extension(&AUDIO_EXTENSIONS, extension.as_ref()) {
+            total_audio += 1;
+        } else if has_extension(&BOOK_EXTENSIONS, extension.as_ref()) {
+            total_books += 1;
+        }
+    }
+    let (media, total_media) = match total_audio >= total_books {
+        true => (OtherMedia::Music, total_audio),
+        false => (OtherMedia::Books, total_books),
+    };
+    // cover art, playlists and cue sheets usually sit alongside the media
+    (total_media >= MIN_MEDIA_FILES && total_media*2 >= total_files).then_some(media)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn music_and_books_are_detected() {
+        let album = ["01 - Intro.flac", "02 - Song.flac", "03 - Outro.FLAC", "cover.jpg", "album.cue"];
+        assert_eq!(detect_other_media(album), Some(OtherMedia::Music));
+        let books = ["Book One.epub", "Book Two.epub", "Book Three.pdf"];
+        assert_eq!(detect_other_media(books), Some(OtherMedia::Books));
+    }
+
+    #[test]
+    fn videos_are_never_excluded() {
+        let files = ["01.mp3", "02.mp3", "03.mp3", "04.mp3", "Show.S01E01.mkv"];
+        assert_eq!(detect_other_media(files), None);
+    }
+
+    #[test]
+    fn stray_media_files_are_ignored() {
+        assert_eq!(detect_other_media(["theme.mp3", "notes.txt"]), None);
+        let files = ["a.mp3", "b.mp3", "c.mp3", "1.jpg", "2.jpg", "3.jpg", "4.jpg"];
+        assert_eq!(detect_other_media(files), None);
+        assert_eq!(detect_other_media([]), None);
+    }
+}
@@ -0,0 +1,110 @@
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum PermissionCause {
+    ReadOnlyFile,
+    ReadOnlyFolder,
+    AccessDenied,
+}
+
+impl PermissionCause {
+    pub fn iterator() -> std::slice::Iter<'static, Self> {
+        static CAUSES: [PermissionCause;3] = [
+            PermissionCause::ReadOnlyFile,
+            PermissionCause::ReadOnlyFolder,
+            PermissionCause::AccessDenied,
+        ];
+        CAUSES.iter()
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            PermissionCause::ReadOnlyFile => "Read-only file",
+            PermissionCause::ReadOnlyFolder => "Read-only folder",
+            PermissionCause::AccessDenied => "Access denied",
+        }
+    }
+
+    pub fn get_hint(&self) -> &'static str {
+        match self {
+            PermissionCause::ReadOnlyFile => "Clear the read-only attribute on the file",
+            PermissionCause::ReadOnlyFolder => "Clear the read-only attribute on the folder",
+            PermissionCause::AccessDenied => "The file is owned by another user or is open in another program like the torrent client",
+        }
+    }
+
+    pub fn is_fixable(&self) -> bool {
+        matches!(self, PermissionCause::ReadOnlyFile | PermissionCause::ReadOnlyFolder)
+    }
+}
+
+// A change that failed because we aren't allowed to touch the file
+#[derive(Debug, Clone)]
+pub struct PermissionIssue {
+    pub src: String,
+    pub cause: PermissionCause,
+    // The file or folder which is blocking the change
+    pub blocking_path: PathBuf,
+}
+
+async fn is_read_only(path: &Path) -> bool {
+    tokio::fs::metadata(path).await
+        .map(|metadata| metadata.permissions().readonly())
+        .unwrap_or(false)
+}
+
+// The destination folder might not exist yet so the closest existing folder is checked
+async fn get_existing_ancestor(path: &Path) -> Option<PathBuf> {
+    for ancestor in path.ancestors().skip(1) {
+        if tokio::fs::try_exists(ancestor).await.unwrap_or(false) {
+            return Some(ancestor.to_path_buf());
+        }
+    }
+    None
+}
+
+// Works out why a move or delete was denied by checking the read-only attributes involved
+pub async fn diagnose_permission_error(src_name: &str, src: &Path, dest: Option<&Path>) -> PermissionIssue {
+    let create_issue = |cause, blocking_path: &Path| PermissionIssue {
+        src: src_name.to_string(),
+        cause,
+        blocking_path: blocking_path.to_path_buf(),
+    };
+
+    if is_read_only(src).await {
+        return create_issue(PermissionCause::ReadOnlyFile, src);
+    }
+    if let Some(src_folder) = src.parent() {
+        if is_read_only(src_folder).await {
+            return create_issue(PermissionCause::ReadOnlyFolder, src_folder);
+        }
+    }
+    if let Some(dest) = dest {
+        if let Some(dest_folder) = get_existing_ancestor(dest).await {
+            if is_read_only(dest_folder.as_path()).await {
+                return create_issue(PermissionCause::ReadOnlyFolder, dest_folder.as_path());
+            }
+        }
+    }
+    create_issue(PermissionCause::AccessDenied, src)
+}
+
+#[cfg(unix)]
+fn set_writable(permissions: &mut std::fs::Permissions) {
+    use std::os::unix::fs::PermissionsExt;
+    // Only the owner gets write access instead of everyone
+    permissions.set_mode(permissions.mode() | 0o200);
+}
+
+#[cfg(not(unix))]
+#[allow(clippy::permissions_set_readonly_false)]
+fn set_writable(permissions: &mut std::fs::Permissions) {
+    // Clears the read-only attribute on Windows
+    permissions.set_readonly(false);
+}
+
+pub async fn clear_read_only(path: &Path) -> Result<(), std::io::Error> {
+    let mut permissions = tokio::fs::metadata(path).await?.permissions();
+    set_writable(&mut permissions);
+    tokio::fs::set_permissions(path, permissions).await
+}
@@ -0,0 +1,91 @@
+use serde;
+use serde_json;
+use std::collections::{HashMap, HashSet};
+use crate::app_file::AppFile;
+use crate::app_folder::FOLDER_DATA_FILENAMES;
+use crate::atomic_file::get_backup_source;
+use crate::file_intent::Action;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanChangeKind {
+    Added,
+    Removed,
+    // Contains the previous action
+    Action(Action),
+    // Contains the previous destination
+    Destination(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct PlanChange {
+    pub src: String,
+    pub kind: PlanChangeKind,
+    pub action: Action,
+    pub dest: String,
+}
+
+// The computed intents of a folder before any user changes are applied
+// Kept on disk so changes are still shown for the first refresh after a restart
+// E.g. <folder>/plan_snapshot.json
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, PartialEq, Eq)]
+pub struct PlanSnapshot {
+    intents: HashMap<String, (Action, String)>,
+}
+
+// The snapshot itself and the other data files would otherwise show up as added once they are first written
+fn is_data_file(src: &str) -> bool {
+    let src = get_backup_source(src).unwrap_or(src);
+    FOLDER_DATA_FILENAMES.contains(&src)
+}
+
+impl PlanSnapshot {
+    pub(crate) fn new(files: &[AppFile]) -> Self {
+        let intents = files
+            .iter()
+            .filter(|file| !is_data_file(file.src.as_str()))
+            .map(|file| (file.src.clone(), (file.action, file.dest.clone())))
+            .collect();
+        Self { intents }
+    }
+
+    pub(crate) fn get_changes(&self, files: &[AppFile]) -> Vec<PlanChange> {
+        let mut changes = Vec::new();
+        for file in files.iter().filter(|file| !is_data_file(file.src.as_str())) {
+            let kind = match self.intents.get(&file.src) {
+                None => PlanChangeKind::Added,
+                Some((action, _)) if *action != file.action => PlanChangeKind::Action(*action),
+                Some((_, dest)) if *dest != file.dest => PlanChangeKind::Destination(dest.clone()),
+                Some(_) => continue,
+            };
+            changes.push(PlanChange {
+                src: file.src.clone(),
+                kind,
+                action: file.action,
+                dest: file.dest.clone(),
+            });
+        }
+
+        let sources: HashSet<&str> = files.iter().map(|file| file.src.as_str()).collect();
+        let mut removed: Vec<PlanChange> = self.intents
+            .iter()
+            .filter(|(src, _)| !sources.contains(src.as_str()))
+            .map(|(src, (action, dest))| PlanChange {
+                src: src.clone(),
+                kind: PlanChangeKind::Removed,
+                action: *action,
+                dest: dest.clone(),
+            })
+            .collect();
+        removed.sort_unstable_by(|a, b| a.src.cmp(&b.src));
+        changes.extend(removed);
+        changes
+    }
+}
+
+pub fn deserialize_plan_snapshot(data: &str) -> Result<PlanSnapshot, serde_json::Error> {
+    serde_json::from_str(data)
+}
+
+pub fn serialize_plan_snapshot(snapshot: &PlanSnapshot) -> Result<String, serde_json::Error> {
+    serde_json::to_string(snapshot)
+}
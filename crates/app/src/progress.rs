@@ -0,0 +1,124 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+// Throughput is measured over the recent past so that it follows changes in speed
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum ProgressUnit {
+    Bytes,
+    Folders,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProgressSnapshot {
+    pub name: &'static str,
+    pub unit: ProgressUnit,
+    pub finished: u64,
+    pub total: u64,
+    pub elapsed: Duration,
+    // units per second
+    pub throughput: Option<f64>,
+    pub eta: Option<Duration>,
+    pub is_cancellable: bool,
+    pub is_cancelled: bool,
+}
+
+impl ProgressSnapshot {
+    pub fn get_fraction(&self) -> f32 {
+        if self.total == 0 {
+            return 1.0;
+        }
+        (self.finished as f64 / self.total as f64).min(1.0) as f32
+    }
+}
+
+struct ProgressState {
+    finished: u64,
+    samples: VecDeque<(Instant, u64)>,
+}
+
+// Executors report finished units as they go and the gui polls snapshots of it
+pub struct ProgressTracker {
+    name: &'static str,
+    unit: ProgressUnit,
+    total: u64,
+    started: Instant,
+    state: Mutex<ProgressState>,
+    // Operations which check for cancellation between units of work hand out a token
+    cancel_token: Option<CancellationToken>,
+}
+
+impl ProgressTracker {
+    pub fn new(name: &'static str, unit: ProgressUnit, total: u64) -> Self {
+        let started = Instant::now();
+        let mut samples = VecDeque::new();
+        samples.push_back((started, 0));
+        Self {
+            name,
+            unit,
+            total,
+            started,
+            state: Mutex::new(ProgressState { finished: 0, samples }),
+            cancel_token: None,
+        }
+    }
+
+    pub fn new_cancellable(name: &'static str, unit: ProgressUnit, total: u64) -> Self {
+        Self {
+            cancel_token: Some(CancellationToken::new()),
+            ..Self::new(name, unit, total)
+        }
+    }
+
+    pub fn get_cancel_token(&self) -> Option<&CancellationToken> {
+        self.cancel_token.as_ref()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel_token.as_ref().map(|token| token.is_cancelled()).unwrap_or(false)
+    }
+
+    pub fn cancel(&self) {
+        if let Some(token) = self.cancel_token.as_ref() {
+            token.cancel();
+        }
+    }
+
+    pub fn add(&self, amount: u64) {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        state.finished += amount;
+        let finished = state.finished;
+        state.samples.push_back((now, finished));
+        // keep one sample older than the window so the window is always fully covered
+        while state.samples.len() > 2 && now.duration_since(state.samples[1].0) > THROUGHPUT_WINDOW {
+            state.samples.pop_front();
+        }
+    }
+
+    pub fn get_snapshot(&self) -> ProgressSnapshot {
+        let now = Instant::now();
+        let state = self.state.lock().unwrap();
+        let throughput = state.samples.front().and_then(|(time, finished)| {
+            let seconds = now.duration_since(*time).as_secs_f64();
+            let amount = state.finished - finished;
+            (seconds > 0.0 && amount > 0).then(|| amount as f64 / seconds)
+        });
+        let remaining = self.total.saturating_sub(state.finished);
+        let eta = throughput.map(|throughput| Duration::from_secs_f64(remaining as f64 / throughput));
+        ProgressSnapshot {
+            name: self.name,
+            unit: self.unit,
+            finished: state.finished,
+            total: self.total,
+            elapsed: now.duration_since(self.started),
+            throughput,
+            eta,
+            is_cancellable: self.cancel_token.is_some(),
+            is_cancelled: self.is_cancelled(),
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::HashSet;
+use tvdb::models::Series;
+use crate::file_descriptor::get_folder_series_key;
+
+// The series a folder is currently bound to and how well the folder name matches it
+#[derive(Debug, Clone)]
+pub struct SeriesBinding {
+    pub folder_name: String,
+    pub series: Option<Series>,
+    pub confidence: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct RebindCandidate {
+    pub series: Series,
+    pub confidence: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct RebindEntry {
+    pub folder_name: String,
+    pub current_series_id: Option<u32>,
+    pub search_name: String,
+    pub candidates: Vec<RebindCandidate>,
+    pub selected_candidate: Option<usize>,
+}
+
+impl RebindEntry {
+    pub fn get_selected_candidate(&self) -> Option<&RebindCandidate> {
+        self.candidates.get(self.selected_candidate?)
+    }
+
+    pub fn is_changed(&self) -> bool {
+        self.get_selected_candidate().map(|candidate| Some(candidate.series.id) != self.current_series_id).unwrap_or(false)
+    }
+}
+
+// Several folders which are searched again and bound to their selected candidates in one pass
+#[derive(Debug, Clone)]
+pub struct RebindPlan {
+    pub entries: Vec<RebindEntry>,
+}
+
+impl RebindPlan {
+    pub fn get_total_changed(&self) -> usize {
+        self.entries.iter().filter(|entry| entry.is_changed()).count()
+    }
+}
+
+// Dice coefficient of the words in both names, so word order and extra words only lower the score
+fn get_name_similarity(a: &str, b: &str) -> f32 {
+    let a_words: HashSet<&str> = a.split_whitespace().collect();
+    let b_words: HashSet<&str> = b.split_whitespace().collect();
+    if a_words.is_empty() || b_words.is_empty() {
+        return 0.0;
+    }
+    let total_shared = a_words.intersection(&b_words).count();
+    (2*total_shared) as f32 / (a_words.len() + b_words.len()) as f32
+}
+
+// From 0 to 1, where aliases count as well since folders are often named after a translated title
+pub fn get_binding_confidence(folder_name: &str, series: &Series) -> f32 {
+    let folder_key = get_folder_series_key(folder_name);
+    let aliases = series.aliases.as_deref().unwrap_or_default();
+    std::iter::once(&series.name)
+        .chain(aliases.iter())
+        .map(|name| get_name_similarity(folder_key.as_str(), get_folder_series_key(name).as_str()))
+        .fold(0.0, f32::max)
+}
+
+// Best matches first while keeping the order of the search results otherwise
+pub fn get_rebind_candidates(folder_name: &str, search_results: Vec<Series>) -> Vec<RebindCandidate> {
+    let mut candidates: Vec<RebindCandidate> = search_results
+        .into_iter()
+        .map(|series| RebindCandidate { confidence: get_binding_confidence(folder_name, &series), series })
+        .collect();
+    candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn get_series(id: u32, name: &str, aliases: &[&str]) -> Series {
+        serde_json::from_value(json!({ "id": id, "seriesName": name, "aliases": aliases })).unwrap()
+    }
+
+    #[test]
+    fn confidence_ignores_year_and_punctuation() {
+        let series = get_series(1, "Example Show (2010)", &[]);
+        assert_eq!(get_binding_confidence("Drama/Example.Show", &series), 1.0);
+        let series = get_series(2, "Another Example", &[]);
+        assert_eq!(get_binding_confidence("Example Show", &series), 0.5);
+        let series = get_series(3, "Unrelated", &[]);
+        assert_eq!(get_binding_confidence("Example Show", &series), 0.0);
+    }
+
+    #[test]
+    fn confidence_uses_best_alias() {
+        let series = get_series(1, "Shingeki no Kyojin", &["Attack on Titan"]);
+        assert_eq!(get_binding_confidence("Attack on Titan", &series), 1.0);
+    }
+
+    #[test]
+    fn candidates_are_ranked_by_confidence() {
+        let results = vec![
+            get_series(1, "Example", &[]),
+            get_series(2, "Example Show", &[]),
+            get_series(3, "Show Example", &[]),
+        ];
+        let ids: Vec<u32> = get_rebind_candidates("Example Show", results).iter().map(|candidate| candidate.series.id).collect();
+        assert_eq!(ids, [2, 3, 1]);
+    }
+}
@@ -0,0 +1,83 @@
+use std::collections::BTreeSet;
+
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+pub enum RefreshPriority {
+    Selected,
+    Visible,
+    Background,
+}
+
+// Folders still waiting for a library wide refresh
+// Priorities are looked up on every pop so changing the selection or filters mid refresh takes effect straight away
+pub struct RefreshQueue {
+    remaining: BTreeSet<usize>,
+}
+
+impl RefreshQueue {
+    pub fn new(total_folders: usize) -> Self {
+        Self {
+            remaining: (0..total_folders).collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.remaining.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remaining.is_empty()
+    }
+
+    // Ties are broken by the order of the folders list
+    pub fn pop(&mut self, selected: Option<usize>, visible: &[usize]) -> Option<usize> {
+        let get_priority = |index: usize| {
+            if selected == Some(index) {
+                RefreshPriority::Selected
+            } else if visible.contains(&index) {
+                RefreshPriority::Visible
+            } else {
+                RefreshPriority::Background
+            }
+        };
+        let index = selected.iter()
+            .chain(visible.iter())
+            .chain(self.remaining.first())
+            .copied()
+            .filter(|index| self.remaining.contains(index))
+            .min_by_key(|index| (get_priority(*index), *index))?;
+        self.remaining.remove(&index);
+        Some(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pop_all(queue: &mut RefreshQueue, selected: Option<usize>, visible: &[usize]) -> Vec<usize> {
+        std::iter::from_fn(|| queue.pop(selected, visible)).collect()
+    }
+
+    #[test]
+    fn selected_then_visible_then_rest() {
+        let mut queue = RefreshQueue::new(6);
+        assert_eq!(pop_all(&mut queue, Some(4), &[5, 2, 4]), vec![4, 2, 5, 0, 1, 3]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn priorities_can_change_between_pops() {
+        let mut queue = RefreshQueue::new(5);
+        assert_eq!(queue.pop(None, &[]), Some(0));
+        assert_eq!(queue.pop(Some(3), &[]), Some(3));
+        assert_eq!(queue.pop(Some(3), &[4]), Some(4));
+        assert_eq!(queue.len(), 2);
+        assert_eq!(pop_all(&mut queue, None, &[]), vec![1, 2]);
+    }
+
+    #[test]
+    fn out_of_range_priorities_are_ignored() {
+        let mut queue = RefreshQueue::new(2);
+        assert_eq!(pop_all(&mut queue, Some(7), &[9]), vec![0, 1]);
+    }
+}
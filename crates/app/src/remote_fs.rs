@@ -0,0 +1,244 @@
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use crate::execution_journal::{JournalAction, JournalOperation};
+use crate::library_layout::{DirEntries, MAX_LIBRARY_DEPTH, find_series_folders, group_dir_entries};
+
+// E.g. "ssh://user@seedbox:2222/home/user/media"
+pub const REMOTE_SCHEME: &str = "ssh://";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteUrl {
+    // Anything ssh accepts as a destination, e.g. "user@seedbox" or a host alias from the ssh config
+    pub destination: String,
+    pub port: Option<u16>,
+    pub root_path: String,
+}
+
+pub fn parse_remote_url(url: &str) -> Option<RemoteUrl> {
+    let url = url.trim().strip_prefix(REMOTE_SCHEME)?;
+    let (authority, path) = url.split_at(url.find('/')?);
+    let (destination, port) = match authority.rsplit_once(':') {
+        Some((destination, port)) => (destination, Some(port.parse().ok()?)),
+        None => (authority, None),
+    };
+    // ssh would read a destination like "-oProxyCommand=..." as an option
+    if destination.is_empty() || destination.starts_with('-') {
+        return None;
+    }
+    let root_path = match path.trim_end_matches('/') {
+        "" => "/",
+        path => path,
+    };
+    Some(RemoteUrl {
+        destination: destination.to_string(),
+        port,
+        root_path: root_path.to_string(),
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct RemoteFile {
+    // Relative to the folder
+    pub rel_path: String,
+    pub size: u64,
+    pub modified: Option<std::time::SystemTime>,
+}
+
+// Commands run through the system ssh client so keys, agents and host aliases from the ssh config just work
+// Folder data files can't be written next to the media so they are kept locally instead
+pub struct RemoteHost {
+    ssh_path: String,
+    url: RemoteUrl,
+    data_path: PathBuf,
+}
+
+// Wraps the value in single quotes so the remote shell never expands it
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+// BSD stat prints paths with a leading "./" while GNU find leaves it out
+fn strip_current_dir(path: &str) -> &str {
+    path.strip_prefix("./").unwrap_or(path)
+}
+
+fn parse_remote_file(line: &str) -> Option<RemoteFile> {
+    let mut fields = line.rsplitn(3, '\t');
+    let modified: f64 = fields.next()?.parse().ok()?;
+    let size: u64 = fields.next()?.parse().ok()?;
+    let rel_path = strip_current_dir(fields.next()?);
+    let modified = std::time::Duration::try_from_secs_f64(modified).ok()
+        .and_then(|duration| std::time::UNIX_EPOCH.checked_add(duration));
+    Some(RemoteFile {
+        rel_path: rel_path.to_string(),
+        size,
+        modified,
+    })
+}
+
+// Failed operations are printed as "<index>\t<error>", the rest of the script keeps going like a local execution would
+const OPERATIONS_PRELUDE: &str = r#"
+fail() { printf '%s\t%s\n' "$1" "$(printf '%s' "$2" | tr '\n\t' '  ')"; }
+move_op() {
+    if [ -e "$3" ]; then fail "$1" "destination already exists"; return; fi
+    out=$(mkdir -p -- "$(dirname -- "$3")" 2>&1 && mv -- "$2" "$3" 2>&1) || fail "$1" "$out"
+}
+delete_op() { out=$(rm -- "$2" 2>&1) || fail "$1" "$out"; }
+"#;
+
+const LIST_FILES_SCRIPT: &str = r#"
+if find . -maxdepth 0 -printf '' >/dev/null 2>&1; then
+    find . -type f -printf '%P\t%s\t%T@\n'
+elif stat -f '%z' . >/dev/null 2>&1; then
+    find . -type f -exec stat -f '%N%t%z%t%m' {} +
+else
+    echo "listing files needs GNU find or BSD stat" >&2
+    exit 2
+fi
+"#;
+
+impl RemoteHost {
+    pub fn new(url: RemoteUrl, ssh_path: &str, config_path: &str) -> Self {
+        let data_name: String = format!("{}{}", url.destination, url.root_path)
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '@' { c } else { '_' })
+            .collect();
+        let data_path = Path::new(config_path).join("remote").join(data_name);
+        Self {
+            ssh_path: ssh_path.to_string(),
+            url,
+            data_path,
+        }
+    }
+
+    pub fn get_root_path(&self) -> &str {
+        self.url.root_path.as_str()
+    }
+
+    pub fn get_destination(&self) -> &str {
+        self.url.destination.as_str()
+    }
+
+    pub fn get_folder_path(&self, folder_name: &str) -> String {
+        format!("{}/{}", self.url.root_path.trim_end_matches('/'), folder_name)
+    }
+
+    pub fn get_data_path(&self, folder_name: &str) -> PathBuf {
+        self.data_path.join(folder_name)
+    }
+
+    // The script is sent over stdin so paths never have to survive a second round of quoting by ssh
+    async fn run_script(&self, script: &str) -> Result<String, String> {
+        let mut command = tokio::process::Command::new(self.ssh_path.as_str());
+        command.args(["-o", "BatchMode=yes"]);
+        if let Some(port) = self.url.port {
+            command.arg("-p").arg(port.to_string());
+        }
+        let mut child = command
+            .arg("--")
+            .arg(self.url.destination.as_str())
+            .arg("sh -s")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|err| format!("Couldn't start '{}': {}", self.ssh_path, err))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(script.as_bytes()).await.map_err(|err| format!("Couldn't send commands to '{}': {}", self.url.destination, err))?;
+        }
+        let output = child.wait_with_output().await.map_err(|err| format!("Couldn't read output from '{}': {}", self.url.destination, err))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(output.stderr.as_slice());
+            return Err(format!("Remote command on '{}' failed with {}: {}", self.url.destination, output.status, stderr.trim()));
+        }
+        Ok(String::from_utf8_lossy(output.stdout.as_slice()).to_string())
+    }
+
+    // Everything down to the deepest possible series folder is listed at once to avoid a round trip per level
+    // Printed with the printf utility instead of "find -printf" which only GNU find supports
+    pub async fn list_folders(&self, depth: Option<usize>) -> Result<Vec<String>, String> {
+        let script = format!(
+            "cd -- {} && find . -mindepth 1 -maxdepth {} \\( -type d -exec printf 'd\\t%s\\n' {{}} + \\) -o \\( -type f -exec printf 'f\\t%s\\n' {{}} + \\)\n",
+            quote(self.url.root_path.as_str()),
+            depth.unwrap_or(MAX_LIBRARY_DEPTH),
+        );
+        let output = self.run_script(script.as_str()).await?;
+        let paths = output.lines().filter_map(|line| match line.split_once('\t') {
+            Some(("d", path)) => Some((strip_current_dir(path), true)),
+            Some(("f", path)) => Some((strip_current_dir(path), false)),
+            _ => None,
+        });
+        let dirs = group_dir_entries(paths);
+        find_series_folders(depth, |folder_name| -> Result<DirEntries, String> {
+            Ok(dirs.get(folder_name).cloned().unwrap_or_default())
+        })
+    }
+
+    // Sizes and modification times come from GNU find or BSD stat, other hosts are reported instead of listing nothing
+    pub async fn list_files(&self, folder_name: &str) -> Result<Vec<RemoteFile>, String> {
+        let script = format!(
+            "cd -- {} || exit 1\n{}",
+            quote(self.get_folder_path(folder_name).as_str()),
+            LIST_FILES_SCRIPT,
+        );
+        let output = self.run_script(script.as_str()).await?;
+        Ok(output.lines().filter_map(parse_remote_file).collect())
+    }
+
+    // Paths are relative to the root folder like in the execution journal
+    // Returns the index and error of every operation that failed
+    pub async fn execute_operations(&self, operations: &[JournalOperation]) -> Result<Vec<(usize, String)>, String> {
+        let mut script = String::from(OPERATIONS_PRELUDE);
+        script.push_str(format!("cd -- {} || exit 1\n", quote(self.url.root_path.as_str())).as_str());
+        for (index, operation) in operations.iter().enumerate() {
+            let line = match (operation.action, operation.dest.as_deref()) {
+                (JournalAction::Move, Some(dest)) => format!("move_op {} {} {}\n", index, quote(operation.src.as_str()), quote(dest)),
+                (JournalAction::Delete, _) => format!("delete_op {} {}\n", index, quote(operation.src.as_str())),
+                (JournalAction::Move, None) => continue,
+            };
+            script.push_str(line.as_str());
+        }
+        let output = self.run_script(script.as_str()).await?;
+        let failures = output
+            .lines()
+            .filter_map(|line| {
+                let (index, error) = line.split_once('\t')?;
+                Some((index.parse().ok()?, error.to_string()))
+            })
+            .collect();
+        Ok(failures)
+    }
+
+    pub async fn delete_empty_folders(&self, folder_name: &str) -> Result<(), String> {
+        let script = format!(
+            "find {} -mindepth 1 -type d -empty -delete\n",
+            quote(self.get_folder_path(folder_name).as_str()),
+        );
+        self.run_script(script.as_str()).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn destinations_are_never_options() {
+        let url = parse_remote_url("ssh://user@seedbox:2222/home/user/media/").unwrap();
+        assert_eq!(url, RemoteUrl { destination: "user@seedbox".to_string(), port: Some(2222), root_path: "/home/user/media".to_string() });
+        assert_eq!(parse_remote_url("ssh://-oProxyCommand=touch/tmp/media"), None);
+        assert_eq!(parse_remote_url("ssh://:22/media"), None);
+    }
+
+    #[test]
+    fn gnu_and_bsd_listings_are_parsed() {
+        let gnu = parse_remote_file("Season 01/Show\tS01E01.mkv\t1024\t1700000000.5").unwrap();
+        assert_eq!((gnu.rel_path.as_str(), gnu.size), ("Season 01/Show\tS01E01.mkv", 1024));
+        let bsd = parse_remote_file("./Season 01/Show.S01E01.mkv\t1024\t1700000000").unwrap();
+        assert_eq!((bsd.rel_path.as_str(), bsd.size), ("Season 01/Show.S01E01.mkv", 1024));
+        assert!(bsd.modified.is_some());
+    }
+}
@@ -0,0 +1,89 @@
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt};
+use crate::progress::ProgressTracker;
+
+pub const REMUX_EXTENSION: &str = "mkv";
+// ffmpeg writes into a temporary file so an interrupted remux is never mistaken for an episode
+const PARTIAL_EXTENSION: &str = "mkv.part";
+
+pub fn get_remux_dest(src: &Path) -> PathBuf {
+    src.with_extension(REMUX_EXTENSION)
+}
+
+// Copies every stream into the output without reencoding, the output is removed if ffmpeg fails
+pub(crate) async fn copy_streams(
+    ffmpeg_path: &str, src: &Path, dest: &Path, format: &str, 
+    output_args: &[String], progress: Option<&ProgressTracker>,
+) -> Result<(), String> {
+    let src_size = tokio::fs::metadata(src).await
+        .map_err(|err| format!("IO error while reading file metadata: {}", err))?
+        .len();
+
+    let mut child = tokio::process::Command::new(ffmpeg_path)
+        .args(["-loglevel", "error", "-nostats", "-y", "-i"])
+        .arg(src)
+        .args(["-map", "0", "-c", "copy", "-f", format, "-progress", "pipe:1"])
+        .args(output_args)
+        .arg(dest)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|err| format!("Failed to run ffmpeg: {}", err))?;
+
+    // The output is about as large as the input since nothing is reencoded
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let read_progress = async {
+        let mut total_reported = 0;
+        if let Some(stdout) = stdout {
+            let mut lines = tokio::io::BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                let size = line.strip_prefix("total_size=").and_then(|size| size.trim().parse::<u64>().ok());
+                if let (Some(size), Some(progress)) = (size.map(|size| size.min(src_size)), progress) {
+                    if size > total_reported {
+                        progress.add(size - total_reported);
+                        total_reported = size;
+                    }
+                }
+            }
+        }
+        total_reported
+    };
+    // stderr is read at the same time so ffmpeg never blocks on a full pipe
+    let read_errors = async {
+        let mut message = String::new();
+        if let Some(mut stderr) = stderr {
+            let _ = stderr.read_to_string(&mut message).await;
+        }
+        message
+    };
+    let (total_reported, stderr) = tokio::join!(read_progress, read_errors);
+    let status = child.wait().await.map_err(|err| format!("Failed to run ffmpeg: {}", err))?;
+    if let Some(progress) = progress {
+        progress.add(src_size.saturating_sub(total_reported));
+    }
+
+    if !status.success() {
+        let _ = tokio::fs::remove_file(dest).await;
+        return Err(format!("ffmpeg failed to copy streams: {}", stderr.trim()));
+    }
+    Ok(())
+}
+
+// Moves the streams into a matroska container and removes the original afterwards
+pub async fn remux_to_mkv(ffmpeg_path: &str, src: &Path, progress: &ProgressTracker) -> Result<PathBuf, String> {
+    let dest = get_remux_dest(src);
+    if tokio::fs::try_exists(dest.as_path()).await.unwrap_or(true) {
+        return Err(format!("'{}' already exists", dest.to_string_lossy()));
+    }
+    let partial = src.with_extension(PARTIAL_EXTENSION);
+    copy_streams(ffmpeg_path, src, partial.as_path(), "matroska", &[], Some(progress)).await?;
+    tokio::fs::rename(partial.as_path(), dest.as_path()).await
+        .map_err(|err| format!("IO error while renaming remuxed file: {}", err))?;
+    tokio::fs::remove_file(src).await
+        .map_err(|err| format!("IO error while removing original file: {}", err))?;
+    Ok(dest)
+}
@@ -0,0 +1,120 @@
+use serde;
+use serde_json;
+use std::collections::BTreeMap;
+use tokio::sync::RwLock;
+use crate::atomic_file::{LoadError, load_with_backup, write_atomic};
+use crate::file_intent::{FilterRules, TitlePolicy};
+
+// Overrides the filter rules for folders assigned to the profile, unset fields are inherited
+#[serde_with::skip_serializing_none]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct RuleProfile {
+    // Name of another profile whose overrides are applied first
+    pub inherits: Option<String>,
+    pub title_policy: Option<TitlePolicy>,
+    pub min_video_size_mb: Option<u64>,
+    pub strip_combining_marks: Option<bool>,
+    pub classify_extras: Option<bool>,
+    pub write_metadata_tags: Option<bool>,
+    pub blacklist_extensions: Option<Vec<String>>,
+    pub whitelist_tags: Option<Vec<String>>,
+}
+
+impl RuleProfile {
+    fn apply(&self, rules: &mut FilterRules) {
+        if let Some(policy) = self.title_policy {
+            rules.title_policy = policy;
+        }
+        if let Some(size) = self.min_video_size_mb {
+            rules.min_video_size_mb = Some(size);
+        }
+        if let Some(value) = self.strip_combining_marks {
+            rules.strip_combining_marks = value;
+        }
+        if let Some(value) = self.classify_extras {
+            rules.classify_extras = value;
+        }
+        if let Some(value) = self.write_metadata_tags {
+            rules.write_metadata_tags = value;
+        }
+        if let Some(extensions) = self.blacklist_extensions.as_ref() {
+            rules.blacklist_extensions = extensions.clone();
+        }
+        if let Some(tags) = self.whitelist_tags.as_ref() {
+            rules.whitelist_tags = tags.clone();
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct RuleProfiles {
+    // Used by folders without a profile of their own
+    pub default_profile: Option<String>,
+    pub profiles: BTreeMap<String, RuleProfile>,
+}
+
+impl RuleProfiles {
+    // Layers the profile and its ancestors on top of the base rules
+    pub fn resolve(&self, base: &FilterRules, name: Option<&str>) -> Result<FilterRules, String> {
+        let name = match name.or(self.default_profile.as_deref()) {
+            Some(name) => name,
+            None => return Ok(base.clone()),
+        };
+        let mut chain: Vec<&RuleProfile> = Vec::new();
+        let mut visited: Vec<&str> = Vec::new();
+        let mut curr = Some(name);
+        while let Some(name) = curr {
+            if visited.contains(&name) {
+                return Err(format!("Rule profile '{}' inherits from itself", name));
+            }
+            let profile = self.profiles.get(name).ok_or_else(|| format!("Unknown rule profile '{}'", name))?;
+            visited.push(name);
+            chain.push(profile);
+            curr = profile.inherits.as_deref();
+        }
+
+        let mut rules = base.clone();
+        for profile in chain.iter().rev() {
+            profile.apply(&mut rules);
+        }
+        Ok(rules)
+    }
+}
+
+pub struct ProfileStore {
+    path: String,
+    profiles: RwLock<RuleProfiles>,
+}
+
+impl ProfileStore {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            profiles: RwLock::new(RuleProfiles::default()),
+        }
+    }
+
+    pub async fn load_from_file(&self) -> Result<(), std::io::Error> {
+        let profiles = match load_with_backup(self.path.as_str(), |data: &str| serde_json::from_str::<RuleProfiles>(data)).await {
+            Ok(profiles) => profiles.value,
+            Err(LoadError::IO(err)) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(LoadError::IO(err)) => return Err(err),
+            Err(LoadError::Decode(err)) => return Err(err.into()),
+        };
+        *self.profiles.write().await = profiles;
+        Ok(())
+    }
+
+    pub async fn save(&self, profiles: RuleProfiles) -> Result<(), std::io::Error> {
+        let data = serde_json::to_string_pretty(&profiles).map_err(std::io::Error::from)?;
+        write_atomic(self.path.as_str(), data).await?;
+        *self.profiles.write().await = profiles;
+        Ok(())
+    }
+
+    pub fn get_profiles(&self) -> &RwLock<RuleProfiles> {
+        &self.profiles
+    }
+}
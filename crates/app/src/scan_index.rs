@@ -0,0 +1,99 @@
+use serde_json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::SystemTime;
+use crate::anime_mapping::AnimeMapping;
+use crate::file_intent::{FileIntent, FilterRules};
+use crate::folder_settings::FolderSettings;
+use crate::metrics::{INTENT_CACHE, get_metrics};
+use crate::tvdb_cache::TvdbCache;
+
+// A file is assumed unchanged if its size and modified time are the same as the last scan
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub(crate) struct FileStamp {
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+}
+
+// Everything besides the file itself which an intent is computed from
+pub(crate) struct ScanContext {
+    cache: Arc<TvdbCache>,
+    rules: Arc<FilterRules>,
+    settings: String,
+    anime_mapping: Option<String>,
+}
+
+impl ScanContext {
+    pub fn new(cache: &Arc<TvdbCache>, settings: &FolderSettings, rules: &Arc<FilterRules>, anime_mapping: Option<&AnimeMapping>) -> Self {
+        Self {
+            cache: cache.clone(),
+            rules: rules.clone(),
+            settings: serde_json::to_string(settings).unwrap_or_default(),
+            anime_mapping: anime_mapping.and_then(|mapping| serde_json::to_string(mapping).ok()),
+        }
+    }
+
+    fn is_same(&self, other: &Self) -> bool {
+        // refreshed caches and resolved rules are always a new allocation
+        Arc::ptr_eq(&self.cache, &other.cache) &&
+        Arc::ptr_eq(&self.rules, &other.rules) &&
+        self.settings == other.settings &&
+        self.anime_mapping == other.anime_mapping
+    }
+}
+
+type ScanEntryMap = HashMap<String, (FileStamp, FileIntent)>;
+
+pub(crate) struct ScanEntries {
+    previous: ScanEntryMap,
+    current: ScanEntryMap,
+}
+
+impl ScanEntries {
+    pub fn get_intent(&mut self, path: &str, stamp: FileStamp, compute: impl FnOnce() -> FileIntent) -> FileIntent {
+        let previous = self.previous.remove(path).filter(|(old_stamp, _)| *old_stamp == stamp);
+        get_metrics().record_hit(INTENT_CACHE, previous.is_some());
+        let intent = match previous {
+            Some((_, intent)) => intent,
+            None => compute(),
+        };
+        self.current.insert(path.to_string(), (stamp, intent.clone()));
+        intent
+    }
+}
+
+// Intents from the previous scan so a refresh only parses files that were added or changed
+#[derive(Default)]
+pub(crate) struct ScanIndex {
+    context: Option<ScanContext>,
+    entries: ScanEntryMap,
+}
+
+impl ScanIndex {
+    // Starts a new scan, forgetting every intent if what they were computed from changed
+    pub fn begin_scan(&mut self, context: ScanContext) -> ScanEntries {
+        let is_same = self.context.as_ref().map(|old| old.is_same(&context)).unwrap_or(false);
+        self.context = Some(context);
+        let entries = std::mem::take(&mut self.entries);
+        ScanEntries {
+            previous: if is_same { entries } else { HashMap::new() },
+            current: HashMap::new(),
+        }
+    }
+
+    // Only files seen during the scan are kept so deleted files are dropped
+    pub fn finish_scan(&mut self, entries: ScanEntries) {
+        self.entries = entries.current;
+    }
+
+    // Files the cancelled scan didn't reach keep their previous intents
+    pub fn abort_scan(&mut self, entries: ScanEntries) {
+        self.entries = entries.previous;
+        self.entries.extend(entries.current);
+    }
+
+    pub fn clear(&mut self) {
+        self.context = None;
+        self.entries.clear();
+    }
+}
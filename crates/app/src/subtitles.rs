@@ -0,0 +1,26 @@
+use opensubtitles::models::{Subtitle, SubtitleFile};
+use std::path::{Path, PathBuf};
+
+pub const SUBTITLE_EXTENSION: &str = "srt";
+
+// Named after the video so it is picked up as a companion of the episode
+// E.g. "Show-S01E02-Title.mkv" => "Show-S01E02-Title.en.srt"
+pub fn get_subtitle_path(video_path: &Path, language: &str) -> PathBuf {
+    let stem = video_path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+    video_path.with_file_name(format!("{}.{}.{}", stem, language, SUBTITLE_EXTENSION))
+}
+
+// E.g. "tt0944947" => 944947
+pub fn parse_imdb_id(imdb_id: &str) -> Option<u32> {
+    imdb_id.trim().trim_start_matches("tt").parse().ok()
+}
+
+// Subtitles synced against the exact same video are preferred over popular ones
+pub fn pick_best_subtitle<'a>(subtitles: &'a [Subtitle], language: &str) -> Option<&'a SubtitleFile> {
+    subtitles
+        .iter()
+        .filter(|subtitle| subtitle.attributes.language.as_deref().map(|other| other.eq_ignore_ascii_case(language)).unwrap_or(false))
+        .filter(|subtitle| !subtitle.attributes.files.is_empty())
+        .max_by_key(|subtitle| (subtitle.attributes.moviehash_match, subtitle.attributes.download_count))
+        .and_then(|subtitle| subtitle.attributes.files.first())
+}
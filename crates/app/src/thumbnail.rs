@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use tokio;
+use tokio::sync::RwLock;
+use crate::metrics::{THUMBNAIL_CACHE, get_metrics};
+
+// Seek past intros and title cards which are usually black frames
+const THUMBNAIL_SEEK_TIMES: [&str; 2] = ["00:02:00", "00:00:00"];
+const THUMBNAIL_WIDTH: u32 = 320;
+
+#[derive(Debug, Clone)]
+pub enum ThumbnailStatus {
+    Pending,
+    Ready(String),
+    Failed(String),
+}
+
+pub struct ThumbnailCache {
+    cache_path: String,
+    ffmpeg_path: RwLock<String>,
+    entries: RwLock<HashMap<String, ThumbnailStatus>>,
+}
+
+// Include the size and modification time so replaced files get a new thumbnail
+fn get_thumbnail_filename(src_path: &str, metadata: &std::fs::Metadata) -> String {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    src_path.hash(&mut hasher);
+    metadata.len().hash(&mut hasher);
+    if let Ok(modified) = metadata.modified() {
+        modified.hash(&mut hasher);
+    }
+    format!("{:016x}.png", hasher.finish())
+}
+
+impl ThumbnailCache {
+    pub fn new(cache_path: &str, ffmpeg_path: &str) -> Self {
+        Self {
+            cache_path: cache_path.to_string(),
+            ffmpeg_path: RwLock::new(ffmpeg_path.to_string()),
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub async fn load_thumbnail(&self, src_path: &str) -> Option<()> {
+        {
+            let mut entries = self.entries.write().await;
+            if entries.contains_key(src_path) {
+                return None;
+            }
+            entries.insert(src_path.to_string(), ThumbnailStatus::Pending);
+        }
+
+        let status = match self.create_thumbnail(src_path).await {
+            Ok(thumbnail_path) => ThumbnailStatus::Ready(thumbnail_path),
+            Err(message) => ThumbnailStatus::Failed(message),
+        };
+        let is_ready = matches!(status, ThumbnailStatus::Ready(_));
+        self.entries.write().await.insert(src_path.to_string(), status);
+        is_ready.then_some(())
+    }
+
+    async fn create_thumbnail(&self, src_path: &str) -> Result<String, String> {
+        let metadata = tokio::fs::metadata(src_path).await
+            .map_err(|err| format!("IO error while reading file metadata: {}", err))?;
+        let filename = get_thumbnail_filename(src_path, &metadata);
+        let thumbnail_path = Path::new(self.cache_path.as_str()).join(filename);
+        let thumbnail_path_str = thumbnail_path.to_string_lossy().to_string();
+        let is_cached = tokio::fs::try_exists(&thumbnail_path).await.unwrap_or(false);
+        get_metrics().record_hit(THUMBNAIL_CACHE, is_cached);
+        if is_cached {
+            return Ok(thumbnail_path_str);
+        }
+
+        tokio::fs::create_dir_all(self.cache_path.as_str()).await
+            .map_err(|err| format!("IO error while creating thumbnail folder: {}", err))?;
+
+        // ffmpeg doesn't write a frame if the video is shorter than the seek time
+        let ffmpeg_path = self.ffmpeg_path.read().await.clone();
+        let mut stderr = String::new();
+        for seek_time in THUMBNAIL_SEEK_TIMES {
+            let output = tokio::process::Command::new(ffmpeg_path.as_str())
+                .args(["-loglevel", "error", "-y", "-ss", seek_time, "-i", src_path])
+                .args(["-frames:v", "1", "-vf", format!("scale={}:-1", THUMBNAIL_WIDTH).as_str()])
+                .arg(thumbnail_path_str.as_str())
+                .kill_on_drop(true)
+                .output()
+                .await
+                .map_err(|err| format!("Failed to run ffmpeg: {}", err))?;
+
+            let is_written = tokio::fs::try_exists(&thumbnail_path).await.unwrap_or(false);
+            if output.status.success() && is_written {
+                return Ok(thumbnail_path_str);
+            }
+            stderr = String::from_utf8_lossy(output.stderr.as_slice()).trim().to_string();
+        }
+        Err(format!("ffmpeg failed to extract a frame: {}", stderr))
+    }
+
+    // Thumbnails which failed with the old ffmpeg are retried
+    pub async fn set_ffmpeg_path(&self, ffmpeg_path: &str) {
+        *self.ffmpeg_path.write().await = ffmpeg_path.to_string();
+        self.entries.write().await.retain(|_, status| !matches!(status, ThumbnailStatus::Failed(_)));
+    }
+
+    pub fn get_entries(&self) -> &RwLock<HashMap<String, ThumbnailStatus>> {
+        &self.entries
+    }
+}
@@ -0,0 +1,122 @@
+use qbittorrent::models::{Torrent, TorrentFile};
+use std::path::{Path, PathBuf};
+use crate::file_intent::is_video_path;
+
+// A finished torrent whose videos have all been renamed into the library, so the torrent and its leftovers are redundant
+#[derive(Debug, Clone)]
+pub struct TorrentCleanup {
+    pub hash: String,
+    pub name: String,
+    pub state: String,
+    pub total_videos: usize,
+    // Trashed along with the torrent, none for single file torrents or folders which can't be removed safely
+    pub release_path: Option<PathBuf>,
+}
+
+// Torrents which are still seeding are left alone until the client stops them
+pub fn is_torrent_finished(torrent: &Torrent) -> bool {
+    torrent.is_complete() && !torrent.is_seeding()
+}
+
+// Every video of the torrent has been moved away from where the client saved it
+pub fn get_handled_videos(save_path: &Path, files: &[TorrentFile], is_existing: impl Fn(&Path) -> bool) -> Option<usize> {
+    let videos: Vec<&TorrentFile> = files.iter().filter(|file| is_video_path(file.name.as_str())).collect();
+    if videos.is_empty() || videos.iter().any(|file| is_existing(save_path.join(file.name.as_str()).as_path())) {
+        return None;
+    }
+    Some(videos.len())
+}
+
+// Only the root folder of a torrent is removed, never the save path itself or a folder holding the library
+pub fn get_release_folder(torrent: &Torrent, protected_paths: &[&Path]) -> Option<PathBuf> {
+    if torrent.content_path.is_empty() || torrent.save_path.is_empty() {
+        return None;
+    }
+    let content_path = Path::new(torrent.content_path.as_str());
+    let save_path = Path::new(torrent.save_path.as_str());
+    if content_path == save_path || !content_path.starts_with(save_path) {
+        return None;
+    }
+    if protected_paths.iter().any(|path| path.starts_with(content_path)) {
+        return None;
+    }
+    Some(content_path.to_path_buf())
+}
+
+// Seeded files must sit below the save path of their torrent, outside of a library folder and without holding the root
+// Multi-file torrents added without a subfolder report the save path itself, which would take every other download with it
+pub fn is_content_removable(content_path: &Path, save_path: &Path, root_path: &Path, folder_paths: &[&Path]) -> bool {
+    if content_path.as_os_str().is_empty() || save_path.as_os_str().is_empty() || root_path.starts_with(content_path) {
+        return false;
+    }
+    if content_path == save_path || !content_path.starts_with(save_path) {
+        return false;
+    }
+    folder_paths.iter().all(|folder| !folder.starts_with(content_path) && !content_path.starts_with(folder))
+}
+
+// Anything left over like samples, nfo files or images isn't worth keeping once the videos are gone
+pub fn is_release_folder_redundant(path: &Path) -> bool {
+    walkdir::WalkDir::new(path)
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry.file_type().is_file())
+        .all(|entry| !is_video_path(entry.file_name().to_string_lossy().as_ref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn get_torrent(content_path: &str, state: &str) -> Torrent {
+        serde_json::from_value(json!({
+            "hash": "abc", "name": "Example.Show.S01", "save_path": "/downloads",
+            "content_path": content_path, "state": state, "progress": 1.0,
+        })).unwrap()
+    }
+
+    #[test]
+    fn seeding_torrents_are_kept() {
+        assert!(!is_torrent_finished(&get_torrent("/downloads/Example.Show.S01", "stalledUP")));
+        assert!(is_torrent_finished(&get_torrent("/downloads/Example.Show.S01", "pausedUP")));
+    }
+
+    #[test]
+    fn release_is_handled_once_videos_are_gone() {
+        let files: Vec<TorrentFile> = serde_json::from_value(json!([
+            { "name": "Example.Show.S01/Example.Show.S01E01.mkv", "size": 10 },
+            { "name": "Example.Show.S01/Example.Show.S01.nfo", "size": 1 },
+        ])).unwrap();
+        let save_path = Path::new("/downloads");
+        assert_eq!(get_handled_videos(save_path, files.as_slice(), |_| false), Some(1));
+        assert_eq!(get_handled_videos(save_path, files.as_slice(), |path| path.ends_with("Example.Show.S01E01.mkv")), None);
+        assert_eq!(get_handled_videos(save_path, &files[1..], |_| false), None);
+    }
+
+    #[test]
+    fn library_folders_are_never_removed() {
+        let library = Path::new("/downloads/Example Show");
+        assert_eq!(get_release_folder(&get_torrent("/downloads", "pausedUP"), &[]), None);
+        assert_eq!(get_release_folder(&get_torrent("/downloads/Example Show", "pausedUP"), &[library]), None);
+        assert_eq!(
+            get_release_folder(&get_torrent("/downloads/Example.Show.S01", "pausedUP"), &[library]),
+            Some(PathBuf::from("/downloads/Example.Show.S01")),
+        );
+    }
+
+    #[test]
+    fn seeded_content_in_library_is_kept() {
+        let root = Path::new("/library");
+        let downloads = Path::new("/downloads");
+        let folders = [Path::new("/library/Example Show")];
+        assert!(is_content_removable(Path::new("/downloads/Example.Show.S01"), downloads, root, &folders));
+        assert!(!is_content_removable(Path::new("/library/Example Show/Example.Show.S01E01.mkv"), Path::new("/library/Example Show"), root, &folders));
+        assert!(!is_content_removable(Path::new("/library"), Path::new("/"), root, &folders));
+        assert!(!is_content_removable(Path::new("/"), Path::new("/"), root, &folders));
+        // torrents without a root subfolder report the save path as their content
+        assert!(!is_content_removable(downloads, downloads, root, &folders));
+        assert!(!is_content_removable(Path::new("/other/Example.Show.S01"), downloads, root, &folders));
+        assert!(!is_content_removable(Path::new("/downloads/Example.Show.S01"), Path::new(""), root, &folders));
+    }
+}
@@ -0,0 +1,59 @@
+use qbittorrent::models::Torrent;
+use serde;
+use std::path::Path;
+use crate::file_intent::TitlePolicy;
+use crate::folder_settings::FolderSettings;
+
+// Folders holding a torrent with the label are seeded with these settings, e.g. "tv-anime" => language "ja"
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct LabelRule {
+    // Matches the torrent's category or any of its tags, case insensitive
+    pub label: String,
+    #[serde(default)]
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub title_policy: Option<TitlePolicy>,
+    #[serde(default)]
+    pub subtitle_languages: Vec<String>,
+}
+
+impl LabelRule {
+    // Settings chosen by hand are kept, returns whether anything changed
+    pub fn apply(&self, settings: &mut FolderSettings) -> bool {
+        if settings.torrent_label.as_deref() == Some(self.label.as_str()) {
+            return false;
+        }
+        settings.torrent_label = Some(self.label.clone());
+        if settings.profile.is_none() {
+            settings.profile = self.profile.clone();
+        }
+        if settings.language.is_none() {
+            settings.language = self.language.clone();
+        }
+        if settings.title_policy.is_none() {
+            settings.title_policy = self.title_policy;
+        }
+        if settings.subtitle_languages.is_empty() {
+            settings.subtitle_languages = self.subtitle_languages.clone();
+        }
+        true
+    }
+}
+
+// Labels of every torrent whose content lives inside the folder
+pub fn get_folder_labels<'a>(torrents: &'a [Torrent], folder_path: &Path) -> Vec<&'a str> {
+    torrents
+        .iter()
+        .filter(|torrent| !torrent.content_path.is_empty() && Path::new(torrent.content_path.as_str()).starts_with(folder_path))
+        .flat_map(|torrent| torrent.get_labels())
+        .collect()
+}
+
+// Rules earlier in the config take precedence
+pub fn find_label_rule<'a>(rules: &'a [LabelRule], labels: &[&str]) -> Option<&'a LabelRule> {
+    rules
+        .iter()
+        .find(|rule| labels.iter().any(|label| label.eq_ignore_ascii_case(rule.label.as_str())))
+}
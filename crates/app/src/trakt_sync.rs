@@ -0,0 +1,160 @@
+use chrono;
+use reqwest;
+use serde_json;
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+use trakt::api::{ApiError, ClientInfo, TraktSession};
+use trakt::models::{AccessToken, DeviceCode};
+use crate::atomic_file::{LoadError, load_with_backup, write_atomic};
+use crate::metrics::{TRAKT_REQUEST, get_metrics};
+use crate::tvdb_cache::EpisodeKey;
+
+// Refresh tokens a day early so a session never expires during a sync
+const REFRESH_MARGIN_SECS: i64 = 24*60*60;
+
+// Renamed episodes are marked as collected on Trakt once the app was authorized through the device flow
+pub struct TraktSync {
+    token_path: String,
+    client: RwLock<Arc<reqwest::Client>>,
+    client_info: RwLock<Option<ClientInfo>>,
+    session: RwLock<Option<Arc<TraktSession>>>,
+    device_code: RwLock<Option<DeviceCode>>,
+    authorize_lock: Mutex<()>,
+}
+
+impl TraktSync {
+    pub fn new(token_path: &str, client: Arc<reqwest::Client>) -> Self {
+        Self {
+            token_path: token_path.to_string(),
+            client: RwLock::new(client),
+            client_info: RwLock::new(None),
+            session: RwLock::new(None),
+            device_code: RwLock::new(None),
+            authorize_lock: Mutex::new(()),
+        }
+    }
+
+    async fn load_token_from_file(&self) -> Result<Option<AccessToken>, std::io::Error> {
+        match load_with_backup(self.token_path.as_str(), |data: &str| serde_json::from_str::<AccessToken>(data)).await {
+            Ok(token) => Ok(Some(token.value)),
+            Err(LoadError::IO(err)) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(LoadError::IO(err)) => Err(err),
+            Err(LoadError::Decode(err)) => Err(err.into()),
+        }
+    }
+
+    async fn save_token_to_file(&self, token: &AccessToken) -> Result<(), std::io::Error> {
+        let data = serde_json::to_string_pretty(token).map_err(std::io::Error::from)?;
+        write_atomic(self.token_path.as_str(), data).await
+    }
+
+    async fn start_session(&self, client_info: &ClientInfo, token: &AccessToken) -> Result<(), String> {
+        if let Err(err) = self.save_token_to_file(token).await {
+            return Err(format!("IO error while saving trakt token: {}", err));
+        }
+        let session = TraktSession::new(self.client.read().await.clone(), trakt::api::BASE_URL, client_info.client_id.as_str(), token);
+        *self.session.write().await = Some(Arc::new(session));
+        Ok(())
+    }
+
+    // Reuses the token from a previous authorization and refreshes it if it is about to expire
+    pub async fn login(&self, client_info: &ClientInfo) -> Result<(), String> {
+        *self.client_info.write().await = Some(client_info.clone());
+        let token = match self.load_token_from_file().await {
+            Ok(Some(token)) => token,
+            Ok(None) => return Ok(()),
+            Err(err) => return Err(format!("IO error while reading trakt token: {}", err)),
+        };
+
+        if token.get_expiry() - REFRESH_MARGIN_SECS > chrono::Utc::now().timestamp() {
+            let session = TraktSession::new(self.client.read().await.clone(), trakt::api::BASE_URL, client_info.client_id.as_str(), &token);
+            *self.session.write().await = Some(Arc::new(session));
+            return Ok(());
+        }
+
+        let client = self.client.read().await.clone();
+        let res = trakt::api::refresh_access_token(client.as_ref(), trakt::api::BASE_URL, client_info, &token);
+        match get_metrics().time(TRAKT_REQUEST, res).await {
+            Ok(token) => self.start_session(client_info, &token).await,
+            Err(err) => {
+                *self.session.write().await = None;
+                Err(format!("Trakt token couldn't be refreshed and must be authorized again: {}", err))
+            },
+        }
+    }
+
+    // Polls until the user enters the device code on the Trakt website or the code expires
+    pub async fn authorize(&self) -> Result<(), String> {
+        let _authorize_lock = match self.authorize_lock.try_lock() {
+            Ok(lock) => lock,
+            Err(_) => return Ok(()),
+        };
+        let client_info = match self.client_info.read().await.clone() {
+            Some(client_info) => client_info,
+            None => return Err("Trakt authorization requires a client id and secret in the credentials".to_string()),
+        };
+
+        let client = self.client.read().await.clone();
+        let res = trakt::api::request_device_code(client.as_ref(), trakt::api::BASE_URL, &client_info);
+        let device_code = match get_metrics().time(TRAKT_REQUEST, res).await {
+            Ok(device_code) => device_code,
+            Err(err) => return Err(format!("Api error while requesting trakt device code: {}", err)),
+        };
+        *self.device_code.write().await = Some(device_code.clone());
+
+        let expiry = std::time::Instant::now() + std::time::Duration::from_secs(device_code.expires_in);
+        let mut interval = device_code.interval.max(1);
+        let result = loop {
+            if std::time::Instant::now() >= expiry {
+                break Err("Trakt device code expired before it was entered".to_string());
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(interval)).await;
+            let res = trakt::api::poll_device_token(client.as_ref(), trakt::api::BASE_URL, &client_info, &device_code);
+            match get_metrics().time(TRAKT_REQUEST, res).await {
+                Ok(token) => break self.start_session(&client_info, &token).await,
+                Err(ApiError::AuthorizationPending) => continue,
+                Err(ApiError::SlowDown) => interval += 1,
+                Err(err) => break Err(format!("Trakt authorization failed: {}", err)),
+            }
+        };
+        *self.device_code.write().await = None;
+        result
+    }
+
+    // Returns the number of episodes that were newly added to the collection
+    pub async fn add_to_collection(&self, tvdb_series_id: u32, keys: &[EpisodeKey]) -> Result<u32, String> {
+        let session = match self.session.read().await.clone() {
+            Some(session) => session,
+            None => return Ok(0),
+        };
+        if keys.is_empty() {
+            return Ok(0);
+        }
+        let episodes: Vec<(u32, u32)> = keys.iter().map(|key| (key.season, key.episode)).collect();
+        match get_metrics().time(TRAKT_REQUEST, session.add_to_collection(tvdb_series_id, episodes.as_slice())).await {
+            Ok(result) => Ok(result.added.episodes),
+            Err(err) => Err(format!("Api error while adding episodes to trakt collection: {}", err)),
+        }
+    }
+
+    // Sessions started afterwards use the new client
+    pub async fn set_client(&self, client: Arc<reqwest::Client>) {
+        *self.client.write().await = client;
+    }
+
+    pub fn get_client_info(&self) -> &RwLock<Option<ClientInfo>> {
+        &self.client_info
+    }
+
+    pub fn get_session(&self) -> &RwLock<Option<Arc<TraktSession>>> {
+        &self.session
+    }
+
+    pub fn get_device_code(&self) -> &RwLock<Option<DeviceCode>> {
+        &self.device_code
+    }
+
+    pub fn get_authorize_lock(&self) -> &Mutex<()> {
+        &self.authorize_lock
+    }
+}
@@ -2,7 +2,7 @@ static CHARSET: [&str; 65532] = ["\u{0000}","\u{0001}","\u{0002}","\u{0003}","\u
 
 // SOURCE: https://github.com/thooton/transliterate1234
 pub fn transliterate(input: &str) -> String {
-    let mut output = String::new();
+    let mut output = String::with_capacity(input.len());
     for c in input.chars() {
         let index = c as usize;
 		if index <= 127 {
@@ -0,0 +1,268 @@
+use chrono;
+use serde;
+use std::path::{Path, PathBuf};
+
+// Deleted files are moved into the root folder under a timestamped entry
+// E.g. <root>/.trash/2023-11-05_10-30-00/<folder>/<src>
+pub const TRASH_FOLDER_NAME: &str = ".trash";
+const TRASH_ENTRY_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+
+pub fn get_trash_entry_name(time: &chrono::DateTime<chrono::Utc>) -> String {
+    time.format(TRASH_ENTRY_FORMAT).to_string()
+}
+
+pub fn get_trash_path(root_path: &str, entry_name: &str, folder_name: &str, src: &str) -> PathBuf {
+    Path::new(root_path)
+        .join(TRASH_FOLDER_NAME)
+        .join(entry_name)
+        .join(folder_name)
+        .join(src)
+}
+
+// Entries are named to the second, so anything already trashed at the same path is never replaced
+// Checked before torrents are removed from the client so their files aren't left behind
+pub async fn check_trash_move(src: &Path, trash_path: &Path) -> Result<(), std::io::Error> {
+    tokio::fs::symlink_metadata(src).await?;
+    if tokio::fs::symlink_metadata(trash_path).await.is_ok() {
+        let message = format!("'{}' is already in the trash", trash_path.to_string_lossy());
+        return Err(std::io::Error::new(std::io::ErrorKind::AlreadyExists, message));
+    }
+    match trash_path.parent() {
+        Some(parent_dir) => tokio::fs::create_dir_all(parent_dir).await,
+        None => Ok(()),
+    }
+}
+
+pub async fn move_to_trash(src: &Path, trash_path: &Path) -> Result<(), std::io::Error> {
+    check_trash_move(src, trash_path).await?;
+    match tokio::fs::rename(src, trash_path).await {
+        Err(err) if err.kind() == std::io::ErrorKind::CrossesDevices => {
+            let src = src.to_path_buf();
+            let trash_path = trash_path.to_path_buf();
+            tokio::task::spawn_blocking(move || copy_then_remove(src.as_path(), trash_path.as_path()))
+                .await
+                .map_err(std::io::Error::other)?
+        },
+        res => res,
+    }
+}
+
+// The trash can be on another mount than the files, e.g. a separate download volume
+// The source is only removed once everything was copied, a failed copy is cleaned up again
+fn copy_then_remove(src: &Path, dest: &Path) -> Result<(), std::io::Error> {
+    let res = if std::fs::symlink_metadata(src)?.is_dir() {
+        copy_dir(src, dest)
+    } else {
+        std::fs::copy(src, dest).map(|_| ())
+    };
+    if let Err(err) = res {
+        let _ = match std::fs::symlink_metadata(dest) {
+            Ok(metadata) if metadata.is_dir() => std::fs::remove_dir_all(dest),
+            Ok(_) => std::fs::remove_file(dest),
+            Err(_) => Ok(()),
+        };
+        return Err(err);
+    }
+    match std::fs::symlink_metadata(src)?.is_dir() {
+        true => std::fs::remove_dir_all(src),
+        false => std::fs::remove_file(src),
+    }
+}
+
+fn copy_dir(src: &Path, dest: &Path) -> Result<(), std::io::Error> {
+    for entry in walkdir::WalkDir::new(src) {
+        let entry = entry.map_err(std::io::Error::other)?;
+        let rel_path = entry.path().strip_prefix(src).map_err(std::io::Error::other)?;
+        let dest_path = dest.join(rel_path);
+        if entry.file_type().is_dir() {
+            std::fs::create_dir_all(dest_path)?;
+        } else {
+            std::fs::copy(entry.path(), dest_path)?;
+        }
+    }
+    Ok(())
+}
+
+const DEFAULT_TRASH_PURGE_INTERVAL_SECS: u64 = 60 * 60;
+const BYTES_PER_GB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+// Read from "trash_retention" in the app config, the trash is kept forever unless a limit is given
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default)]
+pub struct TrashRetention {
+    // Entries older than this are purged
+    #[serde(default)]
+    pub max_age_days: Option<u64>,
+    // The oldest entries are purged until the trash fits
+    #[serde(default)]
+    pub max_size_gb: Option<f64>,
+    #[serde(default)]
+    pub purge_interval_secs: Option<u64>,
+}
+
+impl TrashRetention {
+    pub fn is_enabled(&self) -> bool {
+        self.max_age_days.is_some() || self.max_size_gb.is_some()
+    }
+
+    pub fn get_purge_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.purge_interval_secs.unwrap_or(DEFAULT_TRASH_PURGE_INTERVAL_SECS).max(1))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TrashEntry {
+    pub name: String,
+    // Entries which weren't named by us have no creation time
+    pub created: Option<chrono::DateTime<chrono::Utc>>,
+    pub size: u64,
+    pub total_files: usize,
+}
+
+pub fn parse_trash_entry_name(name: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    let time = chrono::NaiveDateTime::parse_from_str(name, TRASH_ENTRY_FORMAT).ok()?;
+    Some(time.and_utc())
+}
+
+fn read_trash_entries(root_path: &Path) -> Result<Vec<TrashEntry>, std::io::Error> {
+    let trash_path = root_path.join(TRASH_FOLDER_NAME);
+    let dir = match std::fs::read_dir(trash_path) {
+        Ok(dir) => dir,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+
+    let mut entries = Vec::new();
+    for entry in dir {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let mut size = 0;
+        let mut total_files = 0;
+        for file in walkdir::WalkDir::new(entry.path()).into_iter().filter_map(|file| file.ok()) {
+            if file.file_type().is_file() {
+                size += file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+                total_files += 1;
+            }
+        }
+        entries.push(TrashEntry {
+            created: parse_trash_entry_name(name.as_str()),
+            name,
+            size,
+            total_files,
+        });
+    }
+    // oldest first, entries without a creation time are treated as the newest
+    entries.sort_by(|a, b| match (a.created, b.created) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => a.name.cmp(&b.name),
+    });
+    Ok(entries)
+}
+
+pub async fn load_trash_entries(root_path: &str) -> Result<Vec<TrashEntry>, std::io::Error> {
+    let root_path = PathBuf::from(root_path);
+    tokio::task::spawn_blocking(move || read_trash_entries(root_path.as_path()))
+        .await
+        .map_err(std::io::Error::other)?
+}
+
+// Entries are given oldest first
+pub fn get_expired_trash_entries<'a>(entries: &'a [TrashEntry], retention: &TrashRetention, now: chrono::DateTime<chrono::Utc>) -> Vec<&'a TrashEntry> {
+    let mut total_size: u64 = entries.iter().map(|entry| entry.size).sum();
+    let max_size = retention.max_size_gb.map(|size| (size.max(0.0) * BYTES_PER_GB) as u64);
+    let mut expired = Vec::new();
+    for entry in entries.iter() {
+        let is_old = match (retention.max_age_days, entry.created) {
+            (Some(days), Some(created)) => (now - created).num_days() >= i64::try_from(days).unwrap_or(i64::MAX),
+            _ => false,
+        };
+        let is_oversized = max_size.map(|max_size| total_size > max_size).unwrap_or(false);
+        if !is_old && !is_oversized {
+            continue;
+        }
+        total_size -= entry.size;
+        expired.push(entry);
+    }
+    expired
+}
+
+pub async fn purge_trash_entry(root_path: &str, name: &str) -> Result<(), std::io::Error> {
+    // names come from listing the trash folder but shouldn't be able to leave it
+    if name.is_empty() || name.contains(['/', '\\']) || name == "." || name == ".." {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("Invalid trash entry '{}'", name)));
+    }
+    let path = Path::new(root_path).join(TRASH_FOLDER_NAME).join(name);
+    let metadata = tokio::fs::symlink_metadata(path.as_path()).await?;
+    if metadata.is_dir() {
+        tokio::fs::remove_dir_all(path).await
+    } else {
+        tokio::fs::remove_file(path).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_entry(name: &str, size_gb: f64) -> TrashEntry {
+        TrashEntry {
+            name: name.to_string(),
+            created: parse_trash_entry_name(name),
+            size: (size_gb * BYTES_PER_GB) as u64,
+            total_files: 1,
+        }
+    }
+
+    fn get_expired_names(entries: &[TrashEntry], retention: &TrashRetention) -> Vec<String> {
+        let now = parse_trash_entry_name("2024-01-31_00-00-00").unwrap();
+        get_expired_trash_entries(entries, retention, now).iter().map(|entry| entry.name.clone()).collect()
+    }
+
+    #[test]
+    fn folders_are_copied_then_removed() {
+        let root = tempfile::TempDir::new().unwrap();
+        let src = root.path().join("release");
+        std::fs::create_dir_all(src.join("Subs")).unwrap();
+        std::fs::write(src.join("episode.mkv"), "video").unwrap();
+        std::fs::write(src.join("Subs").join("episode.srt"), "subtitle").unwrap();
+        let dest = root.path().join(".trash").join("release");
+        std::fs::create_dir_all(dest.parent().unwrap()).unwrap();
+        copy_then_remove(src.as_path(), dest.as_path()).unwrap();
+        assert!(!src.exists());
+        assert_eq!(std::fs::read_to_string(dest.join("Subs").join("episode.srt")).unwrap(), "subtitle");
+    }
+
+    #[tokio::test]
+    async fn existing_trash_paths_are_not_replaced() {
+        let root = tempfile::TempDir::new().unwrap();
+        let src = root.path().join("episode.mkv");
+        let trash_path = root.path().join(".trash").join("episode.mkv");
+        std::fs::create_dir_all(trash_path.parent().unwrap()).unwrap();
+        std::fs::write(&src, "new").unwrap();
+        std::fs::write(&trash_path, "old").unwrap();
+        let err = move_to_trash(src.as_path(), trash_path.as_path()).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+        assert_eq!(std::fs::read_to_string(&trash_path).unwrap(), "old");
+        assert!(src.exists());
+    }
+
+    #[test]
+    fn entries_past_max_age_are_expired() {
+        let entries = [create_entry("2024-01-01_00-00-00", 1.0), create_entry("2024-01-30_00-00-00", 1.0), create_entry("manual", 1.0)];
+        let retention = TrashRetention { max_age_days: Some(7), ..Default::default() };
+        assert_eq!(get_expired_names(&entries, &retention), ["2024-01-01_00-00-00"]);
+
+        // huge ages must not wrap around to a negative number of days
+        let retention = TrashRetention { max_age_days: Some(u64::MAX), ..Default::default() };
+        assert!(get_expired_names(&entries, &retention).is_empty());
+    }
+
+    #[test]
+    fn oldest_entries_are_expired_until_trash_fits() {
+        let entries = [create_entry("2024-01-01_00-00-00", 2.0), create_entry("2024-01-02_00-00-00", 2.0), create_entry("2024-01-03_00-00-00", 2.0)];
+        let retention = TrashRetention { max_size_gb: Some(3.0), ..Default::default() };
+        assert_eq!(get_expired_names(&entries, &retention), ["2024-01-01_00-00-00", "2024-01-02_00-00-00"]);
+        assert!(get_expired_names(&entries, &TrashRetention::default()).is_empty());
+    }
+}
@@ -1,16 +1,118 @@
+use chrono;
+use enum_map;
+use lazy_static::lazy_static;
+use regex::Regex;
 use tvdb::models::{Episode, Series};
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex, Weak};
+use crate::calendar::parse_air_date;
 
-#[derive(Debug, Hash, Eq, PartialEq, Copy, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Hash, Eq, PartialEq, Copy, Clone)]
 pub struct EpisodeKey {
     pub season: u32,
     pub episode: u32,
 }
 
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Eq, PartialEq, Copy, Clone, enum_map::Enum)]
+#[serde(rename_all="snake_case")]
+pub enum SeriesStatus {
+    #[default]
+    Unknown,
+    Upcoming,
+    Continuing,
+    Ended,
+}
+
+impl SeriesStatus {
+    pub fn iterator() -> std::slice::Iter<'static, Self> {
+        static STATUS: [SeriesStatus;4] = [
+            SeriesStatus::Unknown,
+            SeriesStatus::Upcoming,
+            SeriesStatus::Continuing,
+            SeriesStatus::Ended,
+        ];
+        STATUS.iter()
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            SeriesStatus::Unknown => "Unknown",
+            SeriesStatus::Upcoming => "Upcoming",
+            SeriesStatus::Continuing => "Continuing",
+            SeriesStatus::Ended => "Ended",
+        }
+    }
+
+    // TVDB reports the status as free text
+    pub fn from_tvdb_status(status: Option<&str>) -> Self {
+        match status.map(|status| status.trim().to_lowercase()).as_deref() {
+            Some("continuing") | Some("returning series") => SeriesStatus::Continuing,
+            Some("ended") | Some("canceled") | Some("cancelled") => SeriesStatus::Ended,
+            Some("upcoming") | Some("in production") => SeriesStatus::Upcoming,
+            _ => SeriesStatus::Unknown,
+        }
+    }
+}
+
+// Some series number their episodes differently on dvd, e.g. Firefly, or only by absolute number like anime
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default, Eq, PartialEq, Copy, Clone)]
+#[serde(rename_all="snake_case")]
+pub enum EpisodeOrdering {
+    #[default]
+    Aired,
+    Dvd,
+    Absolute,
+}
+
+impl EpisodeOrdering {
+    pub fn iterator() -> std::slice::Iter<'static, Self> {
+        static ORDERINGS: [EpisodeOrdering;3] = [
+            EpisodeOrdering::Aired,
+            EpisodeOrdering::Dvd,
+            EpisodeOrdering::Absolute,
+        ];
+        ORDERINGS.iter()
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            EpisodeOrdering::Aired => "Aired",
+            EpisodeOrdering::Dvd => "DVD",
+            EpisodeOrdering::Absolute => "Absolute",
+        }
+    }
+}
+
+// Fractional dvd numbers belong to episodes split differently on dvd and can't be matched to a filename
+fn get_dvd_episode_key(episode: &Episode) -> Option<EpisodeKey> {
+    let number = episode.dvd_episode_number?;
+    if number.fract() != 0.0 || number < 0.0 {
+        return None;
+    }
+    Some(EpisodeKey { season: episode.dvd_season?, episode: number as u32 })
+}
+
+// TVDB lists episodes with these titles until the real title is announced
+pub fn is_placeholder_title(title: &str) -> bool {
+    lazy_static! {
+        static ref PLACEHOLDER_REGEX: Regex = Regex::new(
+            r"(?i)^\s*(tba|tbd|to be announced|to be determined|episode\s*#?\s*\d+(\.\d+)?)\s*$"
+        ).unwrap();
+    }
+    PLACEHOLDER_REGEX.is_match(title)
+}
+
+// Episode names keyed by episode id for each language
+pub type EpisodeTranslations = HashMap<String, HashMap<u32, String>>;
+
+#[derive(Clone)]
 pub struct TvdbCache {
     pub series: Series,
     pub episodes: Vec<Episode>,
     pub episode_cache: HashMap<EpisodeKey, usize>,
+    pub dvd_cache: HashMap<EpisodeKey, usize>,
+    pub absolute_cache: HashMap<u32, usize>,
+    pub translations: EpisodeTranslations,
 }
 
 impl TvdbCache {
@@ -24,18 +126,162 @@ impl TvdbCache {
         });
 
         let mut cache = HashMap::new();
+        let mut dvd_cache = HashMap::new();
+        let mut absolute_cache = HashMap::new();
         for (index, episode) in episodes.iter().enumerate() {
             let key = EpisodeKey {
                 season: episode.season,
                 episode: episode.episode,
             };
             cache.insert(key, index);
+            if let Some(key) = get_dvd_episode_key(episode) {
+                dvd_cache.entry(key).or_insert(index);
+            }
+            if let Some(absolute_number) = episode.absolute_number {
+                absolute_cache.insert(absolute_number, index);
+            }
         }
         
         Self {
             series,
             episode_cache: cache,
+            dvd_cache,
+            absolute_cache,
             episodes,
+            translations: HashMap::new(),
+        }
+    }
+
+    pub fn get_series_status(&self) -> SeriesStatus {
+        SeriesStatus::from_tvdb_status(self.series.status.as_deref())
+    }
+
+    // Episodes without a number in the ordering, like episodes which aren't on dvd yet, have no key
+    pub fn get_episode_key(&self, index: usize, ordering: EpisodeOrdering) -> Option<EpisodeKey> {
+        let episode = self.episodes.get(index)?;
+        match ordering {
+            EpisodeOrdering::Aired => Some(EpisodeKey { season: episode.season, episode: episode.episode }),
+            EpisodeOrdering::Dvd => get_dvd_episode_key(episode),
+            // absolute numbers run through a single season, leaving specials in season 0 out
+            EpisodeOrdering::Absolute => Some(EpisodeKey { season: 1, episode: episode.absolute_number? }),
         }
     }
+
+    pub fn get_episode_index(&self, key: &EpisodeKey, ordering: EpisodeOrdering) -> Option<usize> {
+        match ordering {
+            EpisodeOrdering::Aired => self.episode_cache.get(key).copied(),
+            EpisodeOrdering::Dvd => self.dvd_cache.get(key).copied(),
+            EpisodeOrdering::Absolute if key.season == 1 => self.absolute_cache.get(&key.episode).copied(),
+            EpisodeOrdering::Absolute => None,
+        }
+    }
+
+    // Returns the last season and the last episode of the given season, or None if there are no episodes
+    pub fn get_episode_bounds(&self, season: u32, ordering: EpisodeOrdering) -> Option<(u32, u32)> {
+        let keys: Vec<EpisodeKey> = (0..self.episodes.len())
+            .filter_map(|index| self.get_episode_key(index, ordering))
+            .collect();
+        let last_season = keys.iter().map(|key| key.season).max()?;
+        let last_episode = keys.iter()
+            .filter(|key| key.season == season)
+            .map(|key| key.episode)
+            .max()
+            .unwrap_or(0);
+        Some((last_season, last_episode))
+    }
+
+    // Aired episodes which still have a placeholder title are likely to have a real title upstream
+    pub fn has_aired_placeholder_titles(&self, today: chrono::NaiveDate) -> bool {
+        self.episodes.iter().any(|episode| {
+            let is_aired = episode.first_aired
+                .as_deref()
+                .and_then(parse_air_date)
+                .map(|air_date| air_date <= today)
+                .unwrap_or(false);
+            is_aired && episode.name.as_deref().map(is_placeholder_title).unwrap_or(true)
+        })
+    }
+
+    // Fallback to the untranslated name if there is no translation
+    pub fn get_episode_name(&self, index: usize, language: Option<&str>) -> Option<&str> {
+        let episode = self.episodes.get(index)?;
+        let translation = language
+            .and_then(|language| self.translations.get(language))
+            .and_then(|names| names.get(&episode.id));
+        match translation {
+            Some(name) => Some(name.as_str()),
+            None => episode.name.as_deref(),
+        }
+    }
+}
+
+#[derive(Debug, Hash, Eq, PartialEq, Copy, Clone)]
+pub enum CacheProvider {
+    Tvdb,
+}
+
+#[derive(Debug, Hash, Eq, PartialEq, Copy, Clone)]
+pub struct SeriesCacheKey {
+    pub provider: CacheProvider,
+    pub series_id: u32,
+}
+
+impl SeriesCacheKey {
+    pub fn new(cache: &TvdbCache) -> Self {
+        Self {
+            provider: CacheProvider::Tvdb,
+            series_id: cache.series.id,
+        }
+    }
+}
+
+// Folders bound to the same series share one cache so memory scales with unique series instead of folders
+// Entries are weak so a series is dropped once no folder uses it
+#[derive(Default)]
+pub struct SeriesCacheStore {
+    caches: Mutex<HashMap<SeriesCacheKey, Weak<TvdbCache>>>,
+}
+
+impl SeriesCacheStore {
+    // Reuses the cache another folder already loaded for the series
+    pub fn share(&self, cache: TvdbCache) -> Arc<TvdbCache> {
+        let key = SeriesCacheKey::new(&cache);
+        let mut caches = self.caches.lock().unwrap();
+        let existing = caches.get(&key).and_then(|cache| cache.upgrade());
+        let cache = match existing {
+            Some(existing) => {
+                let is_missing_translations = cache.translations.keys().any(|language| !existing.translations.contains_key(language));
+                if !is_missing_translations {
+                    return existing;
+                }
+                // Keep the translations this copy has that the shared one doesn't
+                let mut merged = (*existing).clone();
+                for (language, names) in cache.translations {
+                    merged.translations.entry(language).or_insert(names);
+                }
+                Arc::new(merged)
+            },
+            None => Arc::new(cache),
+        };
+        caches.insert(key, Arc::downgrade(&cache));
+        cache
+    }
+
+    // Replaces the shared cache after it was refreshed so other folders pick it up
+    pub fn publish(&self, cache: TvdbCache) -> Arc<TvdbCache> {
+        let key = SeriesCacheKey::new(&cache);
+        let cache = Arc::new(cache);
+        let mut caches = self.caches.lock().unwrap();
+        caches.retain(|_, cache| cache.strong_count() > 0);
+        caches.insert(key, Arc::downgrade(&cache));
+        cache
+    }
+
+    pub fn get(&self, key: &SeriesCacheKey) -> Option<Arc<TvdbCache>> {
+        self.caches.lock().unwrap().get(key).and_then(|cache| cache.upgrade())
+    }
+
+    pub fn get_total_series(&self) -> usize {
+        self.caches.lock().unwrap().values().filter(|cache| cache.strong_count() > 0).count()
+    }
 }
@@ -0,0 +1,83 @@
+use mediaserver::models::EpisodeWatchState;
+use std::collections::HashMap;
+use crate::tvdb_cache::{EpisodeKey, EpisodeOrdering, TvdbCache};
+
+// Which episodes of a folder were watched on the media server, keyed in the folder's ordering
+// Episodes the server doesn't have are left out, so they are neither watched nor unwatched
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WatchState {
+    episodes: HashMap<EpisodeKey, bool>,
+}
+
+impl WatchState {
+    pub fn new(states: &[EpisodeWatchState], get_key: impl Fn(EpisodeKey) -> Option<EpisodeKey>) -> Self {
+        let mut episodes = HashMap::new();
+        for state in states {
+            let key = match get_key(EpisodeKey { season: state.season, episode: state.episode }) {
+                Some(key) => key,
+                None => continue,
+            };
+            // an episode split into parts on the server counts as watched once every part is
+            let is_watched = episodes.get(&key).copied().unwrap_or(true) && state.is_watched;
+            episodes.insert(key, is_watched);
+        }
+        Self { episodes }
+    }
+
+    // Media servers number episodes in aired order, which is mapped onto the ordering of the folder
+    pub fn from_cache(states: &[EpisodeWatchState], cache: &TvdbCache, ordering: EpisodeOrdering) -> Self {
+        Self::new(states, |key| match ordering {
+            EpisodeOrdering::Aired => Some(key),
+            _ => cache.get_episode_index(&key, EpisodeOrdering::Aired).and_then(|index| cache.get_episode_key(index, ordering)),
+        })
+    }
+
+    pub fn is_watched(&self, key: &EpisodeKey) -> Option<bool> {
+        self.episodes.get(key).copied()
+    }
+
+    // A season is unwatched while the server has an episode of it which wasn't watched
+    pub fn is_season_unwatched(&self, season: u32) -> bool {
+        self.episodes.iter().any(|(key, is_watched)| key.season == season && !is_watched)
+    }
+
+    pub fn get_total_watched(&self) -> usize {
+        self.episodes.values().filter(|is_watched| **is_watched).count()
+    }
+
+    pub fn get_total_episodes(&self) -> usize {
+        self.episodes.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(season: u32, episode: u32, is_watched: bool) -> EpisodeWatchState {
+        EpisodeWatchState { season, episode, is_watched }
+    }
+
+    #[test]
+    fn seasons_with_an_unwatched_episode_are_unwatched() {
+        let states = [state(1, 1, true), state(1, 2, true), state(2, 1, true), state(2, 2, false), state(3, 1, false)];
+        // season 3 isn't in the folder's ordering
+        let watch_state = WatchState::new(&states, |key| Some(key).filter(|key| key.season < 3));
+
+        assert_eq!(watch_state.is_watched(&EpisodeKey { season: 1, episode: 2 }), Some(true));
+        assert_eq!(watch_state.is_watched(&EpisodeKey { season: 2, episode: 2 }), Some(false));
+        assert_eq!(watch_state.is_watched(&EpisodeKey { season: 3, episode: 1 }), None);
+        assert!(!watch_state.is_season_unwatched(1));
+        assert!(watch_state.is_season_unwatched(2));
+        assert!(!watch_state.is_season_unwatched(3));
+        assert_eq!(watch_state.get_total_watched(), 3);
+        assert_eq!(watch_state.get_total_episodes(), 4);
+    }
+
+    #[test]
+    fn parts_of_an_episode_are_watched_together() {
+        let states = [state(1, 1, true), state(1, 2, false)];
+        let watch_state = WatchState::new(&states, |_| Some(EpisodeKey { season: 1, episode: 1 }));
+        assert_eq!(watch_state.is_watched(&EpisodeKey { season: 1, episode: 1 }), Some(false));
+    }
+}
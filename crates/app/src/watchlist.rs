@@ -0,0 +1,93 @@
+use chrono;
+use serde;
+use serde_json;
+use std::collections::HashSet;
+use tokio::sync::RwLock;
+use tvdb::models::Series;
+use crate::atomic_file::{LoadError, load_with_backup, write_atomic};
+use crate::natural_sort::sort_natural_by;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct WatchlistEntry {
+    pub added: chrono::DateTime<chrono::Utc>,
+    pub series: Series,
+}
+
+// Series the user follows so that searches and imports prefer them and their folders can be created ahead of time
+pub struct Watchlist {
+    path: String,
+    entries: RwLock<Vec<WatchlistEntry>>,
+}
+
+impl Watchlist {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            entries: RwLock::new(Vec::new()),
+        }
+    }
+
+    pub async fn load_from_file(&self) -> Result<(), std::io::Error> {
+        let entries = match load_with_backup(self.path.as_str(), |data: &str| serde_json::from_str::<Vec<WatchlistEntry>>(data)).await {
+            Ok(entries) => entries.value,
+            Err(LoadError::IO(err)) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(LoadError::IO(err)) => return Err(err),
+            Err(LoadError::Decode(err)) => return Err(err.into()),
+        };
+        *self.entries.write().await = entries;
+        Ok(())
+    }
+
+    async fn save_to_file(&self, entries: &[WatchlistEntry]) -> Result<(), std::io::Error> {
+        let data = serde_json::to_string_pretty(entries).map_err(std::io::Error::from)?;
+        write_atomic(self.path.as_str(), data).await
+    }
+
+    pub async fn add(&self, series: &Series) -> Result<(), std::io::Error> {
+        let mut entries = self.entries.write().await;
+        if entries.iter().any(|entry| entry.series.id == series.id) {
+            return Ok(());
+        }
+        let mut new_entries = entries.clone();
+        new_entries.push(WatchlistEntry {
+            added: chrono::Utc::now(),
+            series: series.clone(),
+        });
+        sort_natural_by(new_entries.as_mut_slice(), |entry| entry.series.name.as_str());
+        self.save_to_file(new_entries.as_slice()).await?;
+        *entries = new_entries;
+        Ok(())
+    }
+
+    pub async fn remove(&self, series_id: u32) -> Result<(), std::io::Error> {
+        let mut entries = self.entries.write().await;
+        let new_entries: Vec<WatchlistEntry> = entries
+            .iter()
+            .filter(|entry| entry.series.id != series_id)
+            .cloned()
+            .collect();
+        if new_entries.len() == entries.len() {
+            return Ok(());
+        }
+        self.save_to_file(new_entries.as_slice()).await?;
+        *entries = new_entries;
+        Ok(())
+    }
+
+    pub async fn get_series_ids(&self) -> HashSet<u32> {
+        self.entries.read().await.iter().map(|entry| entry.series.id).collect()
+    }
+
+    pub fn get_series_ids_blocking(&self) -> HashSet<u32> {
+        self.entries.blocking_read().iter().map(|entry| entry.series.id).collect()
+    }
+
+    pub fn get_entries(&self) -> &RwLock<Vec<WatchlistEntry>> {
+        &self.entries
+    }
+}
+
+// Keeps the provider's ordering within watched and unwatched series
+pub fn sort_by_watchlist<T>(items: &mut [T], series_ids: &HashSet<u32>, get_series: impl Fn(&T) -> &Series) {
+    items.sort_by_key(|item| !series_ids.contains(&get_series(item).id));
+}
@@ -0,0 +1,110 @@
+// Each test binary only uses some of the helpers
+#![allow(dead_code)]
+
+use app::app::App;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+
+// Minimal app config, the tvdb api is redirected to a mock server when a base url is given
+pub fn write_config(config_path: &Path, tvdb_base_url: Option<&str>) {
+    let config = json!({
+        "whitelist_folders": [],
+        "whitelist_filenames": [
+            "series.json",
+            "episodes.json",
+            "bookmarks.json",
+            "anime_mapping.json",
+            "archive.json",
+            "execution_skips.json",
+            "plan_snapshot.json",
+            "folder_settings.json",
+            "translations.json"
+        ],
+        "blacklist_extensions": [".nfo", ".exe"],
+        "whitelist_tags": [],
+        "tvdb_base_url": tvdb_base_url,
+    });
+    std::fs::write(config_path.join("app_config.json"), config.to_string()).unwrap();
+
+    let credentials = json!({
+        "credentials": {
+            "apikey": "test-apikey",
+            "userkey": "test-userkey",
+            "username": "test-username"
+        },
+        "token": null,
+    });
+    std::fs::write(config_path.join("credentials.json"), credentials.to_string()).unwrap();
+}
+
+pub async fn create_app(config_path: &Path, tvdb_base_url: Option<&str>) -> App {
+    write_config(config_path, tvdb_base_url);
+    App::new(config_path.to_str().unwrap()).await.unwrap()
+}
+
+pub async fn get_errors(app: &App) -> Vec<String> {
+    app.get_errors().read().await.clone()
+}
+
+// A series folder with its tvdb cache already written so no api is needed to load it
+pub struct SeriesFolder {
+    series: serde_json::Value,
+    episodes: serde_json::Value,
+    settings: Option<serde_json::Value>,
+    files: Vec<(String, usize)>,
+}
+
+impl SeriesFolder {
+    // Bound to "Example Show" with the tvdb id 1234 unless another series is given
+    pub fn new(episodes: serde_json::Value) -> Self {
+        Self {
+            series: json!({ "id": 1234, "seriesName": "Example Show" }),
+            episodes,
+            settings: None,
+            files: Vec::new(),
+        }
+    }
+
+    pub fn with_series(mut self, series: serde_json::Value) -> Self {
+        self.series = series;
+        self
+    }
+
+    // Written to the folder settings file
+    pub fn with_settings(mut self, settings: serde_json::Value) -> Self {
+        self.settings = Some(settings);
+        self
+    }
+
+    pub fn with_file(self, path: &str) -> Self {
+        self.with_sized_file(path, 0)
+    }
+
+    pub fn with_sized_file(mut self, path: &str, size: usize) -> Self {
+        self.files.push((path.to_string(), size));
+        self
+    }
+
+    // File paths are relative to the folder and their parent folders are created
+    pub fn create(&self, folder_path: &Path) {
+        std::fs::create_dir_all(folder_path).unwrap();
+        std::fs::write(folder_path.join("series.json"), self.series.to_string()).unwrap();
+        std::fs::write(folder_path.join("episodes.json"), self.episodes.to_string()).unwrap();
+        if let Some(settings) = self.settings.as_ref() {
+            std::fs::write(folder_path.join("folder_settings.json"), settings.to_string()).unwrap();
+        }
+        for (path, size) in self.files.iter() {
+            let path = folder_path.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, vec![0u8; *size]).unwrap();
+        }
+    }
+}
+
+pub fn create_series_folder(root_path: &Path, name: &str, episodes: serde_json::Value, files: &[&str]) -> PathBuf {
+    let folder_path = root_path.join(name);
+    files.iter()
+        .fold(SeriesFolder::new(episodes), |folder, file| folder.with_file(file))
+        .create(folder_path.as_path());
+    folder_path
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use app::app_folder::AppFolder;
+use app::tvdb_cache::EpisodeOrdering;
+use serde_json::json;
+use tempfile::TempDir;
+use common::{SeriesFolder, create_app, get_errors};
+
+async fn get_dest(folder: &AppFolder) -> String {
+    let files = folder.get_files().await;
+    let file = files.to_iter().find(|file| file.get_src() == "Firefly.S01E01.mkv").unwrap();
+    file.get_dest().to_string()
+}
+
+#[tokio::test]
+async fn dvd_ordering_names_files_by_dvd_numbers() {
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    let folder_path = root.path().join("Firefly");
+    let aired = (chrono::Local::now().date_naive() - chrono::Duration::days(2)).format("%Y-%m-%d").to_string();
+    let series = json!({ "id": 78874, "seriesName": "Firefly" });
+    // the pilot aired last but is the first episode on dvd
+    let episodes = json!([
+        { "id": 1, "airedSeason": 1, "airedEpisodeNumber": 1, "dvdSeason": 1, "dvdEpisodeNumber": 2.0, "episodeName": "The Train Job", "firstAired": aired },
+        { "id": 2, "airedSeason": 1, "airedEpisodeNumber": 2, "dvdSeason": 1, "dvdEpisodeNumber": 1.0, "episodeName": "Serenity", "firstAired": aired },
+        { "id": 3, "airedSeason": 1, "airedEpisodeNumber": 3, "episodeName": "Unreleased", "firstAired": aired },
+    ]);
+    SeriesFolder::new(episodes).with_series(series).with_file("Firefly.S01E01.mkv").create(folder_path.as_path());
+
+    let app = create_app(config.path(), None).await;
+    app.load_folders(root.path().to_str().unwrap().to_string()).await.unwrap();
+    let folder = app.get_folders().read().await[0].clone();
+    folder.perform_initial_load().await;
+    let dest = get_dest(&folder).await;
+    assert!(dest.ends_with("Firefly-S01E01-The.Train.Job.mkv"), "{}", dest);
+    assert_eq!(folder.get_series_progress().read().await.total_missing_recent, 2);
+
+    folder.get_settings().write().await.ordering = Some(EpisodeOrdering::Dvd);
+    folder.save_settings_to_file().await;
+    folder.update_file_intents().await;
+    let dest = get_dest(&folder).await;
+    assert!(dest.ends_with("Firefly-S01E01-Serenity.mkv"), "{}", dest);
+    // episodes without a dvd number aren't expected in a dvd ordered folder
+    assert_eq!(folder.get_series_progress().read().await.total_missing_recent, 1);
+    assert!(get_errors(&app).await.is_empty());
+
+    let settings = std::fs::read_to_string(folder_path.join("folder_settings.json")).unwrap();
+    assert!(settings.contains("\"ordering\": \"dvd\""));
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use app::execution_outcomes::ExecutionOutcome;
+use serde_json::json;
+use tempfile::TempDir;
+use common::{create_app, create_series_folder, get_errors};
+
+#[tokio::test]
+async fn outcomes_are_kept_until_refreshed() {
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    let episodes = json!([
+        { "id": 1, "airedSeason": 1, "airedEpisodeNumber": 1, "episodeName": "Pilot" },
+        { "id": 2, "airedSeason": 2, "airedEpisodeNumber": 1, "episodeName": "Return" },
+    ]);
+    let folder_path = create_series_folder(root.path(), "Example Show", episodes, &["Example.Show.S01E01.mkv", "Example.Show.S02E01.mkv"]);
+
+    let app = create_app(config.path(), None).await;
+    app.load_folders(root.path().to_str().unwrap().to_string()).await.unwrap();
+    let folder = app.get_folders().read().await[0].clone();
+    folder.perform_initial_load().await;
+
+    // the season folder can't be created while a file is in its place
+    std::fs::write(folder_path.join("Season 02"), []).unwrap();
+    folder.execute_file_changes().await;
+    folder.update_file_intents().await;
+    {
+        let outcomes = folder.get_execution_outcomes().read().await;
+        assert_eq!(outcomes.get("Season 01/Example.Show-S01E01-Pilot.mkv"), Some(&ExecutionOutcome::Applied));
+        assert!(matches!(outcomes.get("Example.Show.S02E01.mkv"), Some(ExecutionOutcome::Failed(_))));
+        assert_eq!(outcomes.get_total_failed(), 1);
+    }
+
+    folder.clear_execution_outcomes().await;
+    assert!(folder.get_execution_outcomes().read().await.is_empty());
+    assert!(get_errors(&app).await.is_empty());
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use serde_json::json;
+use tempfile::TempDir;
+use common::{create_app, create_series_folder, get_errors};
+
+#[tokio::test]
+async fn failing_file_is_skipped_until_retried() {
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    let episodes = json!([
+        { "id": 1, "airedSeason": 1, "airedEpisodeNumber": 1, "episodeName": "Pilot" },
+    ]);
+    let folder_path = create_series_folder(root.path(), "Example Show", episodes, &["Example.Show.S01E01.mkv"]);
+
+    let app = create_app(config.path(), None).await;
+    app.load_folders(root.path().to_str().unwrap().to_string()).await.unwrap();
+    let folder = app.get_folders().read().await[0].clone();
+    folder.perform_initial_load().await;
+
+    // the season folder can't be created while a file is in its place
+    let blocking_path = root.path().join("Example Show").join("Season 01");
+    std::fs::write(blocking_path.as_path(), []).unwrap();
+    for _ in 0..3 {
+        folder.execute_file_changes().await;
+    }
+    assert_eq!(folder.get_execution_skips().read().await.get_failures("Example.Show.S01E01.mkv"), 3);
+    assert_eq!(folder.get_execution_summary().await.total_skipped_failures, 1);
+    let errors = folder.get_errors().read().await.clone();
+    assert!(errors.iter().any(|error| error.contains("is skipped in future executions")));
+
+    // skipped files aren't attempted again
+    folder.execute_file_changes().await;
+    assert_eq!(folder.get_execution_skips().read().await.get_failures("Example.Show.S01E01.mkv"), 3);
+
+    std::fs::remove_file(blocking_path.as_path()).unwrap();
+    folder.retry_skipped_file("Example.Show.S01E01.mkv").await;
+    folder.execute_file_changes().await;
+    assert!(folder_path.join("Season 01").join("Example.Show-S01E01-Pilot.mkv").exists());
+    assert!(folder.get_execution_skips().read().await.is_empty());
+    assert!(get_errors(&app).await.is_empty());
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use serde_json::json;
+use tempfile::TempDir;
+use common::{create_app, create_series_folder, get_errors};
+
+#[tokio::test]
+async fn single_file_is_applied_alone() {
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    let episodes = json!([
+        { "id": 1, "airedSeason": 1, "airedEpisodeNumber": 1, "episodeName": "Pilot" },
+        { "id": 2, "airedSeason": 1, "airedEpisodeNumber": 2, "episodeName": "Second" },
+    ]);
+    let folder_path = create_series_folder(root.path(), "Example Show", episodes, &["Example.Show.S01E01.mkv", "Example.Show.S01E02.mkv"]);
+
+    let app = create_app(config.path(), None).await;
+    app.load_folders(root.path().to_str().unwrap().to_string()).await.unwrap();
+    let folder = app.get_folders().read().await[0].clone();
+    folder.perform_initial_load().await;
+
+    let summary = folder.get_file_execution_summary("Example.Show.S01E02.mkv").await;
+    assert_eq!(summary.total_moves, 1);
+    assert!(!summary.is_destructive());
+
+    folder.execute_file_change("Example.Show.S01E02.mkv").await;
+    assert!(folder_path.join("Season 01").join("Example.Show-S01E02-Second.mkv").exists());
+    assert!(!folder_path.join("Example.Show.S01E02.mkv").exists());
+    // the rest of the plan is left for later
+    assert!(folder_path.join("Example.Show.S01E01.mkv").exists());
+    assert!(get_errors(&app).await.is_empty());
+}
+
+#[tokio::test]
+async fn applied_overrides_are_pruned() {
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    let episodes = json!([
+        { "id": 1, "airedSeason": 1, "airedEpisodeNumber": 1, "episodeName": "Pilot" },
+    ]);
+    let folder_path = create_series_folder(root.path(), "Example Show", episodes, &["Example.Show.S01E01.mkv"]);
+
+    let app = create_app(config.path(), None).await;
+    app.load_folders(root.path().to_str().unwrap().to_string()).await.unwrap();
+    let folder = app.get_folders().read().await[0].clone();
+    folder.perform_initial_load().await;
+
+    folder.set_dest_override("Example.Show.S01E01.mkv", Some("Extras/Pilot.mkv".to_string())).await.unwrap();
+    folder.update_file_intents().await.unwrap();
+    folder.execute_file_change("Example.Show.S01E01.mkv").await;
+    assert!(folder_path.join("Extras").join("Pilot.mkv").exists());
+
+    folder.update_file_intents().await.unwrap();
+    assert!(folder.get_settings().read().await.dest_overrides.is_empty());
+    let settings = std::fs::read_to_string(folder_path.join("folder_settings.json")).unwrap();
+    assert!(!settings.contains("Pilot.mkv"));
+    assert!(get_errors(&app).await.is_empty());
+}
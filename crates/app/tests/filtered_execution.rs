@@ -0,0 +1,42 @@
+mod common;
+
+use app::execution_summary::ExecutionFilter;
+use app::file_intent::Action;
+use serde_json::json;
+use tempfile::TempDir;
+use common::{create_app, create_series_folder, get_errors};
+
+#[tokio::test]
+async fn deletes_are_left_for_later() {
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    let episodes = json!([
+        { "id": 1, "airedSeason": 1, "airedEpisodeNumber": 1, "episodeName": "Pilot" },
+    ]);
+    let folder_path = create_series_folder(root.path(), "Example Show", episodes, &["Example.Show.S01E01.mkv", "Example.Show.S01E01.nfo"]);
+
+    let app = create_app(config.path(), None).await;
+    app.load_folders(root.path().to_str().unwrap().to_string()).await.unwrap();
+    let folder = app.get_folders().read().await[0].clone();
+    folder.perform_initial_load().await;
+    {
+        let mut files = folder.get_mut_files().await;
+        let mut files_iter = files.to_iter();
+        while let Some(mut file) = files_iter.next_mut() {
+            if file.get_action() == Action::Delete {
+                file.set_is_enabled(true);
+            }
+        }
+    }
+    folder.flush_file_changes().await;
+
+    let filter = ExecutionFilter { is_deletes: false, ..ExecutionFilter::default() };
+    let summary = folder.get_execution_summary().await;
+    assert!(summary.is_destructive());
+    assert!(!summary.get_filtered(&filter).is_destructive());
+
+    folder.execute_filtered_file_changes(filter).await;
+    assert!(folder_path.join("Season 01").join("Example.Show-S01E01-Pilot.mkv").exists());
+    assert!(folder_path.join("Example.Show.S01E01.nfo").exists());
+    assert!(get_errors(&app).await.is_empty());
+}
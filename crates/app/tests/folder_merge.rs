@@ -0,0 +1,85 @@
+mod common;
+
+use app::folder_merge::MergeStep;
+use serde_json::json;
+use std::path::Path;
+use tempfile::TempDir;
+use common::{SeriesFolder, create_app, get_errors};
+
+fn create_folder(folder_path: &Path, series_id: u32, files: &[(&str, usize)]) {
+    let episodes = json!([
+        { "id": 1, "airedSeason": 1, "airedEpisodeNumber": 1, "episodeName": "Pilot" },
+        { "id": 2, "airedSeason": 1, "airedEpisodeNumber": 2, "episodeName": "Second" },
+    ]);
+    files.iter()
+        .fold(SeriesFolder::new(episodes), |folder, (path, size)| folder.with_sized_file(path, *size))
+        .with_series(json!({ "id": series_id, "seriesName": "Example Show" }))
+        .create(folder_path);
+}
+
+#[tokio::test]
+async fn folders_of_different_series_are_not_merged() {
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    create_folder(root.path().join("Doctor Who").as_path(), 1, &[("Doctor.Who.S01E01.mkv", 10)]);
+    create_folder(root.path().join("Doctor Who (2005)").as_path(), 2, &[("Doctor.Who.S01E01.mkv", 20)]);
+
+    let app = create_app(config.path(), None).await;
+    app.load_folders(root.path().to_str().unwrap().to_string()).await.unwrap();
+    assert!(app.get_merge_plan(1, 0).await.is_none());
+    let errors = get_errors(&app).await;
+    assert!(errors.iter().any(|error| error.contains("aren't bound to the same series")));
+    assert!(root.path().join("Doctor Who").join("Doctor.Who.S01E01.mkv").exists());
+    assert_eq!(app.get_folders().read().await.len(), 2);
+}
+
+#[tokio::test]
+async fn smaller_duplicates_are_trashed() {
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    create_folder(root.path().join("Example Show").as_path(), 1234, &[("Example.Show.S01E01.mkv", 10)]);
+    create_folder(root.path().join("Example Show (2005)").as_path(), 1234, &[
+        ("Example.Show.S01E01.720p.mkv", 20),
+        ("Example.Show.S01E02.mkv", 10),
+    ]);
+
+    let app = create_app(config.path(), None).await;
+    app.load_folders(root.path().to_str().unwrap().to_string()).await.unwrap();
+    let plan = app.get_merge_plan(1, 0).await.unwrap();
+    assert_eq!(plan.get_trashed_files(), vec!["Example Show/Example.Show.S01E01.mkv"]);
+    assert!(plan.steps.contains(&MergeStep::Move("Example.Show.S01E02.mkv".to_string())));
+
+    app.merge_folders(&plan).await.unwrap();
+    assert!(get_errors(&app).await.is_empty());
+    let dest_path = root.path().join("Example Show");
+    assert!(dest_path.join("Example.Show.S01E01.720p.mkv").exists());
+    assert!(dest_path.join("Example.Show.S01E02.mkv").exists());
+    assert!(!dest_path.join("Example.Show.S01E01.mkv").exists());
+    assert!(!root.path().join("Example Show (2005)").exists());
+    assert_eq!(app.get_folders().read().await.len(), 1);
+
+    // a confirmed plan isn't applied to folders which are gone
+    assert!(app.merge_folders(&plan).await.is_none());
+}
+
+#[tokio::test]
+async fn source_folder_is_kept_when_moves_fail() {
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    create_folder(root.path().join("Example Show").as_path(), 1234, &[("Extras", 1)]);
+    create_folder(root.path().join("Example Show (2005)").as_path(), 1234, &[
+        ("Example.Show.S01E01.mkv", 10),
+        ("Extras/Example.Show.Behind.The.Scenes.mkv", 10),
+    ]);
+
+    let app = create_app(config.path(), None).await;
+    app.load_folders(root.path().to_str().unwrap().to_string()).await.unwrap();
+    let plan = app.get_merge_plan(1, 0).await.unwrap();
+    assert!(app.merge_folders(&plan).await.is_some());
+    let errors = get_errors(&app).await;
+    assert!(errors.iter().any(|error| error.contains("IO error while moving 'Extras/Example.Show.Behind.The.Scenes.mkv'")));
+    assert!(root.path().join("Example Show").join("Example.Show.S01E01.mkv").exists());
+    // the episode which couldn't be moved isn't trashed along with its folder
+    assert!(root.path().join("Example Show (2005)").join("Extras/Example.Show.Behind.The.Scenes.mkv").exists());
+    assert_eq!(app.get_folders().read().await.len(), 2);
+}
@@ -0,0 +1,33 @@
+mod common;
+
+use app::execution_journal::{ExecutionJournal, JournalOperation};
+use serde_json::json;
+use tempfile::TempDir;
+use common::{SeriesFolder, create_app, get_errors};
+
+#[tokio::test]
+async fn folders_with_interrupted_executions_are_not_renamed() {
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    let root_path = root.path().to_str().unwrap();
+    let episodes = json!([{ "id": 1, "airedSeason": 1, "airedEpisodeNumber": 1, "episodeName": "Pilot" }]);
+    SeriesFolder::new(episodes)
+        .with_file("Example.Show.S01E01.mkv")
+        .create(root.path().join("Wrong Name").as_path());
+    let operations = vec![JournalOperation::new_move("Wrong Name/Example.Show.S01E01.mkv", "Wrong Name/Example Show S01E01.mkv")];
+    drop(ExecutionJournal::create(root_path, "Wrong Name", operations, false).await.unwrap());
+
+    let app = create_app(config.path(), None).await;
+    app.load_folders(root_path.to_string()).await.unwrap();
+    let folder = app.get_folders().read().await[0].clone();
+    folder.perform_initial_load().await;
+    assert!(app.rename_folder("Wrong Name", false).await.is_none());
+    let errors = get_errors(&app).await;
+    assert!(errors.iter().any(|error| error.contains("must be resumed or rolled back first")));
+    assert!(root.path().join("Wrong Name").exists());
+
+    app.discard_execution("Wrong Name").await.unwrap();
+    app.rename_folder("Wrong Name", false).await.unwrap();
+    assert!(root.path().join("Example Show").join("Example.Show.S01E01.mkv").exists());
+    assert_eq!(app.get_folders().read().await[0].get_folder_name(), "Example Show");
+}
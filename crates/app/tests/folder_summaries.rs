@@ -0,0 +1,77 @@
+mod common;
+
+use app::app_folder::FolderStatus;
+use serde_json::json;
+use tempfile::TempDir;
+use common::{create_app, create_series_folder, get_errors};
+
+fn create_folder(root_path: &std::path::Path) {
+    let episodes = json!([{ "id": 1, "airedSeason": 1, "airedEpisodeNumber": 1, "episodeName": "Pilot" }]);
+    create_series_folder(root_path, "Example Show", episodes, &["Example.Show.S01E01.mkv"]);
+}
+
+#[tokio::test]
+async fn statuses_are_preloaded_on_the_next_launch() {
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    let root_path = root.path().to_str().unwrap().to_string();
+    create_folder(root.path());
+
+    let app = create_app(config.path(), None).await;
+    app.load_folders(root_path.clone()).await.unwrap();
+    app.update_file_intents_for_all_folders().await.unwrap();
+    let folder = app.get_folders().read().await[0].clone();
+    assert_eq!(folder.get_folder_status().await, FolderStatus::Pending);
+    assert!(get_errors(&app).await.is_empty());
+    drop((folder, app));
+
+    let app = create_app(config.path(), None).await;
+    app.load_folders(root_path.clone()).await.unwrap();
+    let folder = app.get_folders().read().await[0].clone();
+    // the episode cache is only loaded once the folder is needed
+    assert!(folder.get_cache().read().await.is_none());
+    assert_eq!(folder.get_folder_status().await, FolderStatus::Unknown);
+    let status = tokio::task::spawn_blocking(move || folder.get_last_known_status_blocking()).await.unwrap();
+    assert_eq!(status, FolderStatus::Pending);
+
+    // summaries of another root folder aren't used
+    let other_root = TempDir::new().unwrap();
+    create_folder(other_root.path());
+    app.load_folders(other_root.path().to_str().unwrap().to_string()).await.unwrap();
+    let folder = app.get_folders().read().await[0].clone();
+    let status = tokio::task::spawn_blocking(move || folder.get_last_known_status_blocking()).await.unwrap();
+    assert_eq!(status, FolderStatus::Unknown);
+}
+
+#[tokio::test]
+async fn completion_counts_aired_episodes() {
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    let root_path = root.path().to_str().unwrap().to_string();
+    // specials and episodes which haven't aired yet aren't counted
+    let episodes = json!([
+        { "id": 1, "airedSeason": 0, "airedEpisodeNumber": 1, "episodeName": "Special", "firstAired": "2020-01-01" },
+        { "id": 2, "airedSeason": 1, "airedEpisodeNumber": 1, "episodeName": "Pilot", "firstAired": "2020-01-01" },
+        { "id": 3, "airedSeason": 1, "airedEpisodeNumber": 2, "episodeName": "Second", "firstAired": "2020-01-08" },
+        { "id": 4, "airedSeason": 1, "airedEpisodeNumber": 3, "episodeName": "Third", "firstAired": "2999-01-01" },
+        { "id": 5, "airedSeason": 1, "airedEpisodeNumber": 4, "episodeName": "Unknown" },
+    ]);
+    create_series_folder(root.path(), "Example Show", episodes, &["Example.Show.S01E01.mkv", "Example.Show.S01E03.mkv"]);
+
+    let app = create_app(config.path(), None).await;
+    app.load_folders(root_path.clone()).await.unwrap();
+    app.update_file_intents_for_all_folders().await.unwrap();
+    let folder = app.get_folders().read().await[0].clone();
+    let progress = *folder.get_series_progress().read().await;
+    assert_eq!((progress.total_aired_present, progress.total_aired), (1, 2));
+    assert_eq!(progress.get_completion(), Some(0.5));
+    assert!(get_errors(&app).await.is_empty());
+    drop((folder, app));
+
+    // shown before the episode cache is loaded on the next launch
+    let app = create_app(config.path(), None).await;
+    app.load_folders(root_path).await.unwrap();
+    let folder = app.get_folders().read().await[0].clone();
+    assert!(folder.get_cache().read().await.is_none());
+    assert_eq!(folder.get_series_progress().read().await.get_completion(), Some(0.5));
+}
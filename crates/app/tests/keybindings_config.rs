@@ -0,0 +1,25 @@
+mod common;
+
+use serde_json::json;
+use tempfile::TempDir;
+use common::{create_app, get_errors};
+
+#[tokio::test]
+async fn keybindings_are_saved_to_config() {
+    let config = TempDir::new().unwrap();
+    let app = create_app(config.path(), None).await;
+    assert_eq!(app.get_filter_rules().read().await.keybindings, None);
+
+    let keybindings = json!({ "folder_double_click": "open_detail" });
+    assert!(app.set_keybindings(keybindings.clone()).await.is_some());
+    assert!(get_errors(&app).await.is_empty());
+    assert_eq!(app.get_filter_rules().read().await.keybindings, Some(keybindings.clone()));
+
+    let data = std::fs::read_to_string(config.path().join("app_config.json")).unwrap();
+    let saved: serde_json::Value = serde_json::from_str(data.as_str()).unwrap();
+    assert_eq!(saved["keybindings"], keybindings);
+
+    // picking up the written file doesn't count as a change of the rules
+    assert!(app.reload_config().await.is_some());
+    assert!(app.get_config_notice().read().await.is_none());
+}
@@ -0,0 +1,23 @@
+mod common;
+
+use tempfile::TempDir;
+use common::{create_app, get_errors};
+
+#[tokio::test]
+async fn series_folders_below_genre_folders_are_loaded() {
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    for path in ["Drama/Example Show/Season 1/Example.Show.S01E01.mkv", "Comedy/A/Other Show/Other.Show.S01E01.mkv", "Flat Show/Flat.Show.S01E01.mkv"] {
+        let path = root.path().join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, []).unwrap();
+    }
+
+    let app = create_app(config.path(), None).await;
+    app.load_folders(root.path().to_str().unwrap().to_string()).await.unwrap();
+    let folder_names: Vec<String> = app.get_folders().read().await.iter().map(|folder| folder.get_folder_name().to_string()).collect();
+    assert_eq!(folder_names, vec!["Comedy/A/Other Show", "Drama/Example Show", "Flat Show"]);
+    let folder = app.get_folders().read().await[1].clone();
+    assert_eq!(std::path::Path::new(folder.get_folder_path()), root.path().join("Drama/Example Show"));
+    assert!(get_errors(&app).await.is_empty());
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use app::http_client::NetworkSettings;
+use serde_json::json;
+use tempfile::TempDir;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+use common::{create_app, get_errors};
+
+// Unresolvable so requests only arrive if they went through the proxy
+const TVDB_BASE_URL: &str = "http://tvdb.invalid";
+
+#[tokio::test]
+async fn saved_proxy_is_used_for_login() {
+    let proxy = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/login"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "token": "test-token" })))
+        .expect(1)
+        .mount(&proxy)
+        .await;
+    let config = TempDir::new().unwrap();
+    let app = create_app(config.path(), Some(TVDB_BASE_URL)).await;
+
+    let settings = NetworkSettings {
+        proxy_url: Some(proxy.uri()),
+        ca_certificate_path: None,
+    };
+    assert!(app.set_network_settings(settings.clone()).await.is_some());
+    assert!(get_errors(&app).await.is_empty());
+    assert_eq!(app.get_filter_rules().read().await.network, settings);
+    assert!(app.get_login_session().read().await.is_some());
+
+    // the rest of the config is kept as it was
+    let data = std::fs::read_to_string(config.path().join("app_config.json")).unwrap();
+    let saved: serde_json::Value = serde_json::from_str(data.as_str()).unwrap();
+    assert_eq!(saved["network"]["proxy_url"], json!(proxy.uri()));
+    assert_eq!(saved["tvdb_base_url"], json!(TVDB_BASE_URL));
+}
+
+#[tokio::test]
+async fn invalid_proxy_keeps_previous_client() {
+    let config = TempDir::new().unwrap();
+    let app = create_app(config.path(), Some(TVDB_BASE_URL)).await;
+
+    let settings = NetworkSettings {
+        proxy_url: Some("ftp://proxy:21".to_string()),
+        ca_certificate_path: None,
+    };
+    assert!(app.test_network_settings(&settings).await.unwrap_err().contains("invalid proxy url"));
+    app.set_network_settings(settings).await;
+    let errors = get_errors(&app).await;
+    assert!(errors.iter().any(|error| error.contains("Couldn't apply the network settings")));
+}
+
+#[tokio::test]
+async fn connection_test_reports_status() {
+    let proxy = MockServer::start().await;
+    Mock::given(method("HEAD"))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(1)
+        .mount(&proxy)
+        .await;
+    let config = TempDir::new().unwrap();
+    let app = create_app(config.path(), Some(TVDB_BASE_URL)).await;
+
+    let settings = NetworkSettings {
+        proxy_url: Some(proxy.uri()),
+        ca_certificate_path: Some(config.path().join("missing.pem").to_str().unwrap().to_string()),
+    };
+    assert!(app.test_network_settings(&settings).await.unwrap_err().contains("failed to read certificate"));
+
+    let settings = NetworkSettings {
+        proxy_url: Some(proxy.uri()),
+        ca_certificate_path: None,
+    };
+    let test = app.test_network_settings(&settings).await.unwrap();
+    assert_eq!(test.status.as_u16(), 404);
+    // nothing is saved by a test
+    assert_eq!(app.get_filter_rules().read().await.network, NetworkSettings::default());
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use app::app_folder::FolderStatus;
+use app::other_media::{FolderExclusion, OtherMedia};
+use serde_json::json;
+use tempfile::TempDir;
+use common::{create_app, create_series_folder, get_errors};
+
+#[tokio::test]
+async fn music_folders_are_excluded_from_refreshing() {
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    let album_path = root.path().join("Album");
+    std::fs::create_dir_all(album_path.join("CD1")).unwrap();
+    for filename in ["CD1/01 - Intro.flac", "CD1/02 - Song.flac", "CD1/03 - Outro.flac", "cover.jpg"] {
+        std::fs::write(album_path.join(filename), []).unwrap();
+    }
+    let episodes = json!([{ "id": 1, "airedSeason": 1, "airedEpisodeNumber": 1, "episodeName": "Pilot" }]);
+    create_series_folder(root.path(), "Example Show", episodes, &["Example.Show.S01E01.mkv"]);
+
+    let app = create_app(config.path(), None).await;
+    app.load_folders(root.path().to_str().unwrap().to_string()).await.unwrap();
+    app.update_file_intents_for_all_folders().await.unwrap();
+    assert!(get_errors(&app).await.is_empty());
+    let folders = app.get_folders().read().await.clone();
+    let (album, series) = (folders[0].clone(), folders[1].clone());
+    assert_eq!(*album.get_exclusion().read().await, Some(FolderExclusion::Detected(OtherMedia::Music)));
+    assert_eq!(album.get_folder_status().await, FolderStatus::Unknown);
+    // the missing episode cache and bookmarks of an excluded folder aren't reported
+    assert!(album.get_errors().read().await.is_empty());
+    assert_eq!(*series.get_exclusion().read().await, None);
+    assert_eq!(series.get_folder_status().await, FolderStatus::Pending);
+
+    series.set_is_series(Some(false)).await.unwrap();
+    assert_eq!(*series.get_exclusion().read().await, Some(FolderExclusion::Manual));
+    album.set_is_series(Some(true)).await;
+    assert_eq!(*album.get_exclusion().read().await, None);
+    series.set_is_series(None).await.unwrap();
+    assert_eq!(*series.get_exclusion().read().await, None);
+    assert_eq!(series.get_folder_status().await, FolderStatus::Pending);
+}
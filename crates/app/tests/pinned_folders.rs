@@ -0,0 +1,42 @@
+mod common;
+
+use serde_json::json;
+use std::path::Path;
+use tempfile::TempDir;
+use common::{SeriesFolder, create_app, get_errors};
+
+fn create_folder(folder_path: &Path, id: u32, name: &str) {
+    let episodes = json!([
+        { "id": 1, "airedSeason": 1, "airedEpisodeNumber": 1, "episodeName": "Pilot" },
+    ]);
+    SeriesFolder::new(episodes)
+        .with_series(json!({ "id": id, "seriesName": name }))
+        .with_file("Example.Show.S01E01.mkv")
+        .create(folder_path);
+}
+
+#[tokio::test]
+async fn pinned_folders_are_not_executed_automatically() {
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    let pinned_path = root.path().join("Archived Show");
+    let other_path = root.path().join("Example Show");
+    create_folder(pinned_path.as_path(), 1234, "Archived Show");
+    create_folder(other_path.as_path(), 5678, "Example Show");
+
+    let app = create_app(config.path(), None).await;
+    app.load_folders(root.path().to_str().unwrap().to_string()).await.unwrap();
+    let folders = app.get_folders().read().await.clone();
+    for folder in folders.iter() {
+        folder.perform_initial_load().await;
+        if folder.get_folder_name() == "Archived Show" {
+            folder.set_pinned(true).await.unwrap();
+            assert!(folder.is_pinned().await);
+        }
+    }
+
+    app.execute_pending_folders().await;
+    assert!(pinned_path.join("Example.Show.S01E01.mkv").exists());
+    assert!(other_path.join("Season 01").join("Example.Show-S01E01-Pilot.mkv").exists());
+    assert!(get_errors(&app).await.is_empty());
+}
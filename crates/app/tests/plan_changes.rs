@@ -0,0 +1,40 @@
+mod common;
+
+use app::plan_diff::PlanChangeKind;
+use serde_json::json;
+use tempfile::TempDir;
+use common::{SeriesFolder, create_app, get_errors};
+
+#[tokio::test]
+async fn changes_are_shown_after_a_restart() {
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    let episodes = json!([
+        { "id": 1, "airedSeason": 1, "airedEpisodeNumber": 1, "episodeName": "Pilot" },
+        { "id": 2, "airedSeason": 1, "airedEpisodeNumber": 2, "episodeName": "Second" },
+    ]);
+    let folder_path = root.path().join("Example Show");
+    SeriesFolder::new(episodes)
+        .with_file("Example.Show.S01E01.mkv")
+        .create(folder_path.as_path());
+
+    let app = create_app(config.path(), None).await;
+    app.load_folders(root.path().to_str().unwrap().to_string()).await.unwrap();
+    let folder = app.get_folders().read().await[0].clone();
+    folder.perform_initial_load().await;
+    assert!(folder.get_plan_changes().read().await.is_empty());
+    assert!(folder_path.join("plan_snapshot.json").exists());
+    drop(folder);
+    drop(app);
+
+    std::fs::write(folder_path.join("Example.Show.S01E02.mkv"), []).unwrap();
+    let app = create_app(config.path(), None).await;
+    app.load_folders(root.path().to_str().unwrap().to_string()).await.unwrap();
+    let folder = app.get_folders().read().await[0].clone();
+    folder.perform_initial_load().await;
+    let changes = folder.get_plan_changes().read().await.clone();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].src, "Example.Show.S01E02.mkv");
+    assert_eq!(changes[0].kind, PlanChangeKind::Added);
+    assert!(get_errors(&app).await.is_empty());
+}
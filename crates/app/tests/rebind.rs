@@ -0,0 +1,80 @@
+mod common;
+
+use serde_json::json;
+use tempfile::TempDir;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+use common::{SeriesFolder, create_app, get_errors};
+
+const WRONG_ID: u32 = 99;
+const SERIES_ID: u32 = 1234;
+
+async fn mount_series(server: &MockServer, id: u32, name: &str) {
+    Mock::given(method("GET"))
+        .and(path(format!("/series/{}", id)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "id": id, "seriesName": name }
+        })))
+        .mount(server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("/series/{}/episodes", id)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": [{ "id": id, "airedSeason": 1, "airedEpisodeNumber": 1, "episodeName": "Pilot" }],
+        })))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn wrong_bindings_are_rebound_in_one_pass() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/login"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "token": "test-token" })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/search/series"))
+        .and(query_param("name", "example show"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": [
+                { "id": WRONG_ID, "seriesName": "Other Series" },
+                { "id": SERIES_ID, "seriesName": "Example Show" },
+            ]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    mount_series(&server, SERIES_ID, "Example Show").await;
+
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    SeriesFolder::new(json!([]))
+        .with_series(json!({ "id": WRONG_ID, "seriesName": "Other Series" }))
+        .create(root.path().join("Example Show (2010)").as_path());
+    let app = create_app(config.path(), Some(server.uri().as_str())).await;
+    app.login().await.unwrap();
+    app.load_folders(root.path().to_str().unwrap().to_string()).await.unwrap();
+
+    assert!(app.load_bindings().await.is_some());
+    let bindings = app.get_bindings().read().await.clone().unwrap();
+    assert_eq!(bindings.len(), 1);
+    assert_eq!(bindings[0].series.as_ref().map(|series| series.id), Some(WRONG_ID));
+    assert_eq!(bindings[0].confidence, 0.0);
+
+    assert!(app.plan_rebind(vec![bindings[0].folder_name.clone()]).await.is_some());
+    let plan = app.get_rebind_plan().read().await.clone().unwrap();
+    let entry = &plan.entries[0];
+    assert_eq!(entry.current_series_id, Some(WRONG_ID));
+    assert_eq!(entry.get_selected_candidate().map(|candidate| candidate.series.id), Some(SERIES_ID));
+    assert_eq!(plan.get_total_changed(), 1);
+
+    assert!(app.execute_rebind().await.is_some());
+    assert!(app.get_rebind_plan().read().await.is_none());
+    let folder = app.get_folders().read().await[0].clone();
+    assert_eq!(folder.get_cache().read().await.as_ref().map(|cache| cache.series.id), Some(SERIES_ID));
+    let bindings = app.get_bindings().read().await.clone().unwrap();
+    assert_eq!(bindings[0].confidence, 1.0);
+    assert!(get_errors(&app).await.is_empty());
+}
@@ -0,0 +1,131 @@
+mod common;
+
+use app::file_intent::Action;
+use app::app_folder::AppFolder;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use common::{SeriesFolder, create_app, get_errors};
+
+// Each fixture describes a series folder before and after executing the default plan
+// E.g. tests/fixtures/rename/<name>.json
+#[derive(Deserialize)]
+struct RenameFixture {
+    folder: String,
+    series: serde_json::Value,
+    episodes: Vec<serde_json::Value>,
+    // Written to the folder settings file when present
+    #[serde(default)]
+    settings: Option<serde_json::Value>,
+    files: Vec<FixtureFile>,
+    // Deletes have to be enabled manually in the gui
+    #[serde(default)]
+    enable_deletes: bool,
+    // Every file left in the folder besides the cache files
+    expected: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct FixtureFile {
+    path: String,
+    #[serde(default="get_default_file_size")]
+    size: usize,
+}
+
+fn get_default_file_size() -> usize {
+    1024
+}
+
+const CACHE_FILENAMES: [&str;5] = ["series.json", "episodes.json", "bookmarks.json", "folder_settings.json", "plan_snapshot.json"];
+
+fn get_fixtures_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures").join("rename")
+}
+
+fn create_folder(folder_path: &Path, fixture: &RenameFixture) {
+    let mut folder = SeriesFolder::new(serde_json::Value::Array(fixture.episodes.clone()))
+        .with_series(fixture.series.clone());
+    if let Some(settings) = fixture.settings.as_ref() {
+        folder = folder.with_settings(settings.clone());
+    }
+    fixture.files.iter()
+        .fold(folder, |folder, file| folder.with_sized_file(file.path.as_str(), file.size))
+        .create(folder_path);
+    std::fs::write(folder_path.join("bookmarks.json"), "[]").unwrap();
+}
+
+fn get_folder_tree(folder_path: &Path) -> Vec<String> {
+    let mut tree: Vec<String> = walkdir::WalkDir::new(folder_path)
+        .into_iter()
+        .map(|entry| entry.unwrap())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.path().strip_prefix(folder_path).unwrap().to_string_lossy().replace(std::path::MAIN_SEPARATOR, "/"))
+        .filter(|path| !CACHE_FILENAMES.contains(&path.as_str()))
+        .collect();
+    tree.sort();
+    tree
+}
+
+async fn enable_deletes(folder: &AppFolder) {
+    {
+        let mut files = folder.get_mut_files().await;
+        let mut files_iter = files.to_iter();
+        while let Some(mut file) = files_iter.next_mut() {
+            if file.get_action() == Action::Delete {
+                file.set_is_enabled(true);
+            }
+        }
+    }
+    folder.flush_file_changes().await;
+}
+
+// Runs the scan, intent and execute pipeline and returns the resulting folder tree
+async fn run_fixture(fixture: &RenameFixture) -> Result<Vec<String>, String> {
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    let folder_path = root.path().join(fixture.folder.as_str());
+    create_folder(folder_path.as_path(), fixture);
+
+    let app = create_app(config.path(), None).await;
+    if app.load_folders(root.path().to_str().unwrap().to_string()).await.is_none() {
+        return Err(format!("failed to load folders: {:?}", get_errors(&app).await));
+    }
+    let folder = app.get_folders().read().await[0].clone();
+    folder.perform_initial_load().await;
+    if fixture.enable_deletes {
+        enable_deletes(&folder).await;
+    }
+    folder.execute_file_changes().await;
+
+    let errors = folder.get_errors().read().await.clone();
+    if !errors.is_empty() {
+        return Err(format!("folder errors: {:?}", errors));
+    }
+    Ok(get_folder_tree(folder_path.as_path()))
+}
+
+#[tokio::test]
+async fn rename_fixtures() {
+    let mut fixture_paths: Vec<PathBuf> = std::fs::read_dir(get_fixtures_path()).unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("json"))
+        .collect();
+    fixture_paths.sort();
+    assert!(!fixture_paths.is_empty());
+
+    let mut failures = Vec::new();
+    for fixture_path in fixture_paths.iter() {
+        let name = fixture_path.file_stem().unwrap().to_string_lossy().to_string();
+        let data = std::fs::read_to_string(fixture_path).unwrap();
+        let fixture: RenameFixture = serde_json::from_str(data.as_str())
+            .unwrap_or_else(|err| panic!("invalid fixture '{}': {}", name, err));
+        let mut expected = fixture.expected.clone();
+        expected.sort();
+        match run_fixture(&fixture).await {
+            Ok(tree) if tree == expected => {},
+            Ok(tree) => failures.push(format!("{}:\n  expected: {:?}\n  actual:   {:?}", name, expected, tree)),
+            Err(err) => failures.push(format!("{}: {}", name, err)),
+        }
+    }
+    assert!(failures.is_empty(), "{} fixtures failed\n{}", failures.len(), failures.join("\n"));
+}
@@ -0,0 +1,34 @@
+mod common;
+
+use serde_json::json;
+use tempfile::TempDir;
+use common::{create_app, create_series_folder, get_errors};
+
+#[tokio::test]
+async fn seasons_outside_the_scope_are_not_missing() {
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    let aired = (chrono::Local::now().date_naive() - chrono::Duration::days(2)).format("%Y-%m-%d").to_string();
+    let episodes = json!([
+        { "id": 1, "airedSeason": 4, "airedEpisodeNumber": 1, "episodeName": "Old", "firstAired": aired },
+        { "id": 2, "airedSeason": 5, "airedEpisodeNumber": 1, "episodeName": "New", "firstAired": aired },
+        { "id": 3, "airedSeason": 5, "airedEpisodeNumber": 2, "episodeName": "Newer", "firstAired": aired },
+    ]);
+    let folder_path = create_series_folder(root.path(), "Example Show", episodes, &["Example.Show.S05E01.mkv"]);
+
+    let app = create_app(config.path(), None).await;
+    app.load_folders(root.path().to_str().unwrap().to_string()).await.unwrap();
+    let folder = app.get_folders().read().await[0].clone();
+    folder.perform_initial_load().await;
+    assert_eq!(folder.get_series_progress().read().await.total_missing_recent, 2);
+
+    folder.get_settings().write().await.season_scope = vec![5];
+    folder.save_settings_to_file().await;
+    folder.update_file_intents().await;
+    assert_eq!(folder.get_series_progress().read().await.total_missing_recent, 1);
+    assert!(get_errors(&app).await.is_empty());
+
+    // the scope is kept with the folder settings
+    let settings = std::fs::read_to_string(folder_path.join("folder_settings.json")).unwrap();
+    assert!(settings.contains("season_scope"));
+}
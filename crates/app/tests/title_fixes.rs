@@ -0,0 +1,33 @@
+mod common;
+
+use serde_json::json;
+use tempfile::TempDir;
+use common::{create_app, create_series_folder, get_errors};
+
+#[tokio::test]
+async fn only_title_changes_are_applied() {
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    let episodes = json!([
+        { "id": 1, "airedSeason": 1, "airedEpisodeNumber": 1, "episodeName": "Pilot" },
+        { "id": 2, "airedSeason": 1, "airedEpisodeNumber": 2, "episodeName": "Second" },
+    ]);
+    let folder_path = create_series_folder(root.path(), "Example Show", episodes, &["Season 01/Example.Show-S01E01-TBA.mkv", "Example.Show.S01E02.mkv"]);
+
+    let app = create_app(config.path(), None).await;
+    app.load_folders(root.path().to_str().unwrap().to_string()).await.unwrap();
+    let folder = app.get_folders().read().await[0].clone();
+    folder.perform_initial_load().await;
+
+    let summary = app.get_title_fix_summary().await;
+    assert_eq!((summary.total_folders, summary.total_moves), (1, 1));
+    assert!(!summary.is_destructive());
+
+    app.execute_title_fixes().await.unwrap();
+    assert!(folder_path.join("Season 01").join("Example.Show-S01E01-Pilot.mkv").exists());
+    assert!(!folder_path.join("Season 01").join("Example.Show-S01E01-TBA.mkv").exists());
+    // the unorganised file is left for a normal execution
+    assert!(folder_path.join("Example.Show.S01E02.mkv").exists());
+    assert_eq!(app.get_title_fix_summary().await.get_total_changes(), 0);
+    assert!(get_errors(&app).await.is_empty());
+}
@@ -0,0 +1,192 @@
+mod common;
+
+use serde_json::json;
+use tempfile::TempDir;
+use wiremock::matchers::{body_string_contains, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+use app::app::App;
+use app::cleanup_queue::SeedTargets;
+use common::{create_app, get_errors};
+
+async fn mount_logins(server: &MockServer) {
+    Mock::given(method("POST"))
+        .and(path("/login"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "token": "test-token" })))
+        .mount(server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/v2/auth/login"))
+        .respond_with(ResponseTemplate::new(200).insert_header("Set-Cookie", "SID=test-sid; path=/").set_body_string("Ok."))
+        .mount(server)
+        .await;
+}
+
+async fn create_logged_in_app(server: &MockServer, config_path: &std::path::Path) -> App {
+    let app = create_app(config_path, Some(server.uri().as_str())).await;
+    let credentials = json!({
+        "credentials": { "apikey": "test-apikey", "userkey": "test-userkey", "username": "test-username" },
+        "token": null,
+        "qbittorrent": { "url": server.uri(), "username": "admin", "password": "password" },
+    });
+    std::fs::write(config_path.join("credentials.json"), credentials.to_string()).unwrap();
+    app.login().await.unwrap();
+    app
+}
+
+#[tokio::test]
+async fn renamed_release_is_cleaned_up() {
+    let server = MockServer::start().await;
+    mount_logins(&server).await;
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    let downloads = TempDir::new().unwrap();
+    let release_path = downloads.path().join("Example.Show.S01");
+    std::fs::create_dir_all(release_path.as_path()).unwrap();
+    // only the leftovers of the release remain after its video was imported
+    std::fs::write(release_path.join("Example.Show.S01.nfo"), []).unwrap();
+
+    let torrents = json!([
+        {
+            "hash": "finished", "name": "Example.Show.S01", "state": "pausedUP", "progress": 1.0,
+            "save_path": downloads.path().to_str().unwrap(), "content_path": release_path.to_str().unwrap(),
+        },
+        {
+            "hash": "seeding", "name": "Other.Show.S01", "state": "stalledUP", "progress": 1.0,
+            "save_path": downloads.path().to_str().unwrap(), "content_path": downloads.path().join("Other.Show.S01").to_str().unwrap(),
+        },
+    ]);
+    Mock::given(method("GET"))
+        .and(path("/api/v2/torrents/info"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(torrents))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v2/torrents/files"))
+        .and(query_param("hash", "finished"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            { "name": "Example.Show.S01/Example.Show.S01E01.mkv", "size": 10 },
+            { "name": "Example.Show.S01/Example.Show.S01.nfo", "size": 1 },
+        ])))
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/v2/torrents/delete"))
+        .and(body_string_contains("hashes=finished"))
+        .and(body_string_contains("deleteFiles=false"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let app = create_logged_in_app(&server, config.path()).await;
+    app.load_folders(root.path().to_str().unwrap().to_string()).await.unwrap();
+
+    app.load_torrent_cleanups().await.unwrap();
+    {
+        let cleanups = app.get_torrent_cleanups().read().await;
+        let cleanups = cleanups.as_ref().unwrap();
+        assert_eq!(cleanups.len(), 1);
+        assert_eq!(cleanups[0].total_videos, 1);
+        assert_eq!(cleanups[0].release_path.as_deref(), Some(release_path.as_path()));
+    }
+
+    app.execute_torrent_cleanups(vec!["finished".to_string()]).await.unwrap();
+    assert!(!release_path.exists());
+    assert!(get_errors(&app).await.is_empty());
+}
+
+#[tokio::test]
+async fn seeded_files_wait_for_targets() {
+    let server = MockServer::start().await;
+    mount_logins(&server).await;
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    let downloads = TempDir::new().unwrap();
+    let content_path = downloads.path().join("Example.Show.S01E01.mkv");
+    std::fs::write(content_path.as_path(), []).unwrap();
+
+    let torrents = json!([{
+        "hash": "seeding", "name": "Example.Show.S01E01", "state": "uploading", "progress": 1.0,
+        "ratio": 0.5, "seeding_time": 3600,
+        "save_path": downloads.path().to_str().unwrap(), "content_path": content_path.to_str().unwrap(),
+    }]);
+    Mock::given(method("GET"))
+        .and(path("/api/v2/torrents/info"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(torrents))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/v2/torrents/delete"))
+        .and(body_string_contains("hashes=seeding"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let app = create_logged_in_app(&server, config.path()).await;
+    app.load_folders(root.path().to_str().unwrap().to_string()).await.unwrap();
+    app.load_torrent_cleanups().await.unwrap();
+    app.queue_torrent_cleanup("seeding").await.unwrap();
+    assert!(app.get_seeding_torrents().read().await.is_empty());
+
+    app.set_queued_cleanup_targets("seeding", SeedTargets { min_ratio: Some(1.0), min_seed_hours: None }).await.unwrap();
+    app.process_cleanup_queue().await.unwrap();
+    assert!(content_path.exists());
+    assert_eq!(app.get_cleanup_queue().get_entries().read().await.len(), 1);
+
+    app.set_queued_cleanup_targets("seeding", SeedTargets { min_ratio: Some(1.0), min_seed_hours: Some(1.0) }).await.unwrap();
+    app.process_cleanup_queue().await.unwrap();
+    assert!(!content_path.exists());
+    assert!(app.get_cleanup_queue().get_entries().read().await.is_empty());
+    assert!(get_errors(&app).await.is_empty());
+}
+
+#[tokio::test]
+async fn torrents_stay_queued_until_files_can_be_trashed() {
+    let server = MockServer::start().await;
+    mount_logins(&server).await;
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    let downloads = TempDir::new().unwrap();
+    let content_path = downloads.path().join("Example.Show.S01E01.mkv");
+    std::fs::write(content_path.as_path(), []).unwrap();
+    // the trash folder can't be created while a file is in its place
+    let trash_path = root.path().join(".trash");
+    std::fs::write(trash_path.as_path(), []).unwrap();
+
+    let torrents = json!([{
+        "hash": "seeding", "name": "Example.Show.S01E01", "state": "uploading", "progress": 1.0,
+        "ratio": 2.0, "seeding_time": 3600,
+        "save_path": downloads.path().to_str().unwrap(), "content_path": content_path.to_str().unwrap(),
+    }]);
+    Mock::given(method("GET"))
+        .and(path("/api/v2/torrents/info"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(torrents))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/v2/torrents/delete"))
+        .and(body_string_contains("hashes=seeding"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let app = create_logged_in_app(&server, config.path()).await;
+    app.load_folders(root.path().to_str().unwrap().to_string()).await.unwrap();
+    app.load_torrent_cleanups().await.unwrap();
+    app.queue_torrent_cleanup("seeding").await.unwrap();
+    app.set_queued_cleanup_targets("seeding", SeedTargets { min_ratio: Some(1.0), min_seed_hours: None }).await.unwrap();
+
+    app.process_cleanup_queue().await.unwrap();
+    assert!(content_path.exists());
+    assert_eq!(app.get_cleanup_queue().get_entries().read().await.len(), 1);
+    let errors = get_errors(&app).await;
+    assert!(errors.iter().any(|error| error.contains("Kept torrent 'Example.Show.S01E01' in qBittorrent")));
+
+    std::fs::remove_file(trash_path.as_path()).unwrap();
+    app.process_cleanup_queue().await.unwrap();
+    assert!(!content_path.exists());
+    assert!(app.get_cleanup_queue().get_entries().read().await.is_empty());
+}
@@ -0,0 +1,409 @@
+mod common;
+
+use serde_json::json;
+use tempfile::TempDir;
+use wiremock::matchers::{body_json, header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+use common::{create_app, get_errors};
+
+const TOKEN: &str = "test-token";
+const SERIES_ID: u32 = 1234;
+
+async fn mount_login(server: &MockServer) {
+    Mock::given(method("POST"))
+        .and(path("/login"))
+        .and(body_json(json!({
+            "apikey": "test-apikey",
+            "userkey": "test-userkey",
+            "username": "test-username"
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "token": TOKEN })))
+        .expect(1)
+        .mount(server)
+        .await;
+}
+
+fn create_episode(id: u32, season: u32, episode: u32) -> serde_json::Value {
+    json!({
+        "id": id,
+        "airedSeason": season,
+        "airedEpisodeNumber": episode,
+        "episodeName": format!("Episode {}", episode),
+    })
+}
+
+#[tokio::test]
+async fn login_creates_session() {
+    let server = MockServer::start().await;
+    mount_login(&server).await;
+    let config = TempDir::new().unwrap();
+    let app = create_app(config.path(), Some(server.uri().as_str())).await;
+
+    assert!(app.login().await.is_some());
+    assert!(app.get_login_session().read().await.is_some());
+    assert!(get_errors(&app).await.is_empty());
+}
+
+#[tokio::test]
+async fn login_reports_api_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/login"))
+        .respond_with(ResponseTemplate::new(401).set_body_json(json!({ "Error": "Not Authorized" })))
+        .mount(&server)
+        .await;
+    let config = TempDir::new().unwrap();
+    let app = create_app(config.path(), Some(server.uri().as_str())).await;
+
+    assert!(app.login().await.is_none());
+    assert!(app.get_login_session().read().await.is_none());
+    let errors = get_errors(&app).await;
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("401"));
+    assert!(errors[0].contains("Not Authorized"));
+}
+
+#[tokio::test]
+async fn search_series_sends_token() {
+    let server = MockServer::start().await;
+    mount_login(&server).await;
+    Mock::given(method("GET"))
+        .and(path("/search/series"))
+        .and(query_param("name", "Example Show"))
+        .and(header("Authorization", format!("Bearer {}", TOKEN).as_str()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": [
+                { "id": SERIES_ID, "seriesName": "Example Show", "firstAired": "2010-01-01" },
+                { "id": SERIES_ID + 1, "seriesName": "Example Show (2020)" },
+            ]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let config = TempDir::new().unwrap();
+    let app = create_app(config.path(), Some(server.uri().as_str())).await;
+
+    app.login().await.unwrap();
+    assert!(app.update_search_series("Example Show".to_string()).await.is_some());
+    let series = app.get_series().read().await.clone().unwrap();
+    let names: Vec<&str> = series.iter().map(|series| series.name.as_str()).collect();
+    assert_eq!(names, ["Example Show", "Example Show (2020)"]);
+    assert_eq!(series[0].id, SERIES_ID);
+}
+
+#[tokio::test]
+async fn search_series_reports_rate_limit() {
+    let server = MockServer::start().await;
+    mount_login(&server).await;
+    Mock::given(method("GET"))
+        .and(path("/search/series"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "30"))
+        .mount(&server)
+        .await;
+    let config = TempDir::new().unwrap();
+    let app = create_app(config.path(), Some(server.uri().as_str())).await;
+
+    app.login().await.unwrap();
+    assert!(app.update_search_series("Example Show".to_string()).await.is_none());
+    assert!(app.get_series().read().await.is_none());
+    let errors = get_errors(&app).await;
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("rate limited by api, retry after 30 seconds"));
+}
+
+#[tokio::test]
+async fn load_cache_fetches_every_episode_page() {
+    let server = MockServer::start().await;
+    mount_login(&server).await;
+    Mock::given(method("GET"))
+        .and(path(format!("/series/{}", SERIES_ID)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "id": SERIES_ID, "seriesName": "Example Show" }
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("/series/{}/episodes", SERIES_ID)))
+        .and(query_param("page", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": [create_episode(1, 1, 1), create_episode(2, 1, 2)],
+            "links": { "next": 2, "last": 2 },
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("/series/{}/episodes", SERIES_ID)))
+        .and(query_param("page", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": [create_episode(3, 2, 1)],
+            "links": { "next": null, "last": 2 },
+        })))
+        .mount(&server)
+        .await;
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    std::fs::create_dir(root.path().join("Example Show")).unwrap();
+    let app = create_app(config.path(), Some(server.uri().as_str())).await;
+
+    app.login().await.unwrap();
+    app.load_folders(root.path().to_str().unwrap().to_string()).await.unwrap();
+    let folder = app.get_folders().read().await[0].clone();
+    let session = app.get_login_session().read().await.clone().unwrap();
+    assert!(folder.load_cache_from_api(session, SERIES_ID).await.is_some());
+
+    let cache = folder.get_cache().read().await.clone().unwrap();
+    assert_eq!(cache.series.name, "Example Show");
+    let mut episode_ids: Vec<u32> = cache.episodes.iter().map(|episode| episode.id).collect();
+    episode_ids.sort();
+    assert_eq!(episode_ids, [1, 2, 3]);
+    assert!(folder.get_errors().read().await.is_empty());
+}
+
+#[tokio::test]
+async fn load_cache_reports_missing_series() {
+    let server = MockServer::start().await;
+    mount_login(&server).await;
+    Mock::given(method("GET"))
+        .and(path(format!("/series/{}", SERIES_ID)))
+        .respond_with(ResponseTemplate::new(404).set_body_json(json!({ "Error": "ID: 1234 not found" })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("/series/{}/episodes", SERIES_ID)))
+        .respond_with(ResponseTemplate::new(404).set_body_json(json!({ "Error": "ID: 1234 not found" })))
+        .mount(&server)
+        .await;
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    std::fs::create_dir(root.path().join("Example Show")).unwrap();
+    let app = create_app(config.path(), Some(server.uri().as_str())).await;
+
+    app.login().await.unwrap();
+    app.load_folders(root.path().to_str().unwrap().to_string()).await.unwrap();
+    let folder = app.get_folders().read().await[0].clone();
+    let session = app.get_login_session().read().await.clone().unwrap();
+    assert!(folder.load_cache_from_api(session, SERIES_ID).await.is_none());
+
+    assert!(folder.get_cache().read().await.is_none());
+    let errors = folder.get_errors().read().await.clone();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("ID: 1234 not found"));
+}
+
+#[tokio::test]
+async fn load_cache_stores_remote_ids() {
+    let server = MockServer::start().await;
+    mount_login(&server).await;
+    Mock::given(method("GET"))
+        .and(path(format!("/series/{}", SERIES_ID)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "id": SERIES_ID, "seriesName": "Example Show" }
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("/series/{}/episodes", SERIES_ID)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": [create_episode(1, 1, 1)],
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("/series/{}/remoteIds", SERIES_ID)))
+        .and(header("Authorization", format!("Bearer {}", TOKEN).as_str()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": [{ "id": "tt1234567", "sourceName": "IMDB" }, { "id": "4321", "sourceName": "TheMovieDB.com" }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    std::fs::create_dir(root.path().join("Example Show")).unwrap();
+    let app = create_app(config.path(), Some(server.uri().as_str())).await;
+
+    app.login().await.unwrap();
+    app.load_folders(root.path().to_str().unwrap().to_string()).await.unwrap();
+    let folder = app.get_folders().read().await[0].clone();
+    let session = app.get_login_session().read().await.clone().unwrap();
+    assert!(folder.load_cache_from_api(session, SERIES_ID).await.is_some());
+    assert!(folder.save_cache_to_file().await.is_some());
+
+    // the ids are kept in the series cache alongside the tvdb id
+    let series = folder.get_cache().read().await.clone().unwrap().series.clone();
+    assert_eq!(series.imdb_id.as_deref(), Some("tt1234567"));
+    assert_eq!(series.tmdb_id.as_deref(), Some("4321"));
+    assert!(folder.get_errors().read().await.is_empty());
+}
+
+// Later logins hand out a different token so the retried request can be told apart
+async fn mount_relogin(server: &MockServer, status: u16) {
+    Mock::given(method("POST"))
+        .and(path("/login"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "token": TOKEN })))
+        .up_to_n_times(1)
+        .mount(server)
+        .await;
+    let response = match status {
+        200 => ResponseTemplate::new(200).set_body_json(json!({ "token": "renewed-token" })),
+        _ => ResponseTemplate::new(status).set_body_json(json!({ "Error": "Not Authorized" })),
+    };
+    Mock::given(method("POST"))
+        .and(path("/login"))
+        .respond_with(response)
+        .expect(1)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn search_series_logs_in_again_after_token_expiry() {
+    let server = MockServer::start().await;
+    mount_relogin(&server, 200).await;
+    Mock::given(method("GET"))
+        .and(path("/search/series"))
+        .and(header("Authorization", format!("Bearer {}", TOKEN).as_str()))
+        .respond_with(ResponseTemplate::new(401).set_body_json(json!({ "Error": "Not authorized" })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/search/series"))
+        .and(header("Authorization", "Bearer renewed-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": [{ "id": SERIES_ID, "seriesName": "Example Show" }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let config = TempDir::new().unwrap();
+    let app = create_app(config.path(), Some(server.uri().as_str())).await;
+
+    app.login().await.unwrap();
+    assert!(app.update_search_series("Example Show".to_string()).await.is_some());
+    assert_eq!(app.get_series().read().await.as_ref().unwrap().len(), 1);
+    let session = app.get_login_session().read().await.clone().unwrap();
+    assert!(!session.is_expired());
+    assert!(get_errors(&app).await.is_empty());
+}
+
+#[tokio::test]
+async fn search_series_reports_failed_relogin() {
+    let server = MockServer::start().await;
+    mount_relogin(&server, 401).await;
+    Mock::given(method("GET"))
+        .and(path("/search/series"))
+        .respond_with(ResponseTemplate::new(401).set_body_json(json!({ "Error": "Not authorized" })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let config = TempDir::new().unwrap();
+    let app = create_app(config.path(), Some(server.uri().as_str())).await;
+
+    app.login().await.unwrap();
+    assert!(app.update_search_series("Example Show".to_string()).await.is_none());
+    let session = app.get_login_session().read().await.clone().unwrap();
+    assert!(session.is_expired());
+    let errors = get_errors(&app).await;
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("401"));
+}
+
+#[tokio::test]
+async fn load_cache_reports_failed_episode_page() {
+    let server = MockServer::start().await;
+    mount_login(&server).await;
+    Mock::given(method("GET"))
+        .and(path(format!("/series/{}", SERIES_ID)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "id": SERIES_ID, "seriesName": "Example Show" }
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("/series/{}/episodes", SERIES_ID)))
+        .and(query_param("page", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": [create_episode(1, 1, 1)],
+            "links": { "next": 2, "last": 2 },
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("/series/{}/episodes", SERIES_ID)))
+        .and(query_param("page", "2"))
+        .respond_with(ResponseTemplate::new(500).set_body_json(json!({ "Error": "Internal error" })))
+        .mount(&server)
+        .await;
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    std::fs::create_dir(root.path().join("Example Show")).unwrap();
+    let app = create_app(config.path(), Some(server.uri().as_str())).await;
+
+    app.login().await.unwrap();
+    app.load_folders(root.path().to_str().unwrap().to_string()).await.unwrap();
+    let folder = app.get_folders().read().await[0].clone();
+    let session = app.get_login_session().read().await.clone().unwrap();
+    assert!(folder.load_cache_from_api(session, SERIES_ID).await.is_none());
+
+    assert!(folder.get_cache().read().await.is_none());
+    let errors = folder.get_errors().read().await.clone();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("Internal error"));
+}
+
+#[tokio::test]
+async fn search_series_switches_key_when_rate_limited() {
+    let server = MockServer::start().await;
+    mount_login(&server).await;
+    Mock::given(method("POST"))
+        .and(path("/login"))
+        .and(body_json(json!({
+            "apikey": "fallback-apikey",
+            "userkey": "test-userkey",
+            "username": "test-username"
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "token": "fallback-token" })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/search/series"))
+        .and(header("Authorization", format!("Bearer {}", TOKEN).as_str()))
+        .respond_with(ResponseTemplate::new(429))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/search/series"))
+        .and(header("Authorization", "Bearer fallback-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": [{ "id": SERIES_ID, "seriesName": "Example Show" }]
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+    let config = TempDir::new().unwrap();
+    let app = create_app(config.path(), Some(server.uri().as_str())).await;
+    let credentials = json!({
+        "credentials": { "apikey": "test-apikey", "userkey": "test-userkey", "username": "test-username" },
+        "fallback_logins": [
+            { "apikey": "fallback-apikey", "userkey": "test-userkey", "username": "test-username" },
+        ],
+        "token": null,
+    });
+    std::fs::write(config.path().join("credentials.json"), credentials.to_string()).unwrap();
+
+    app.login().await.unwrap();
+    // the fallback key stays in use once switched to
+    assert!(app.update_search_series("Example Show".to_string()).await.is_some());
+    assert!(app.update_search_series("Example Show".to_string()).await.is_some());
+    assert!(get_errors(&app).await.is_empty());
+
+    let session = app.get_login_session().read().await.clone().unwrap();
+    let usage = session.get_account_usage();
+    assert_eq!(usage.len(), 2);
+    assert_eq!(usage[0].key_hint, "test-username (...ikey)");
+    assert_eq!((usage[0].total_requests, usage[0].total_rate_limited, usage[0].is_active), (1, 1, false));
+    assert_eq!((usage[1].total_requests, usage[1].total_rate_limited, usage[1].is_active), (2, 0, true));
+}
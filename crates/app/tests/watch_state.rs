@@ -0,0 +1,163 @@
+mod common;
+
+use app::app::App;
+use app::file_intent::Action;
+use app::tvdb_cache::EpisodeKey;
+use mediaserver::api::{LoginInfo, ServerKind};
+use serde_json::json;
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::TempDir;
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+use common::{SeriesFolder, create_app, get_errors};
+
+async fn mount_json(server: &MockServer, http_method: &str, url_path: &str, body: serde_json::Value) {
+    Mock::given(method(http_method))
+        .and(path(url_path))
+        .respond_with(ResponseTemplate::new(200).set_body_json(body))
+        .mount(server)
+        .await;
+}
+
+// Jellyfin and the tvdb login are served by the same mock server
+async fn create_app_with_jellyfin(config_path: &Path, server: &MockServer) -> App {
+    let app = create_app(config_path, Some(server.uri().as_str())).await;
+    let config_file = config_path.join("app_config.json");
+    let mut config: serde_json::Value = serde_json::from_str(std::fs::read_to_string(&config_file).unwrap().as_str()).unwrap();
+    config["archive_path"] = json!(config_path.join("archive").to_str().unwrap());
+    config["keep_unwatched_seasons"] = json!(true);
+    std::fs::write(&config_file, config.to_string()).unwrap();
+
+    let credentials_file = config_path.join("credentials.json");
+    let mut credentials: serde_json::Value = serde_json::from_str(std::fs::read_to_string(&credentials_file).unwrap().as_str()).unwrap();
+    credentials["media_server"] = json!({
+        "kind": "jellyfin",
+        "url": server.uri(),
+        "token": "test-api-key",
+        "user_id": "user-1",
+    });
+    std::fs::write(&credentials_file, credentials.to_string()).unwrap();
+    app.reload_config().await.unwrap();
+    app
+}
+
+#[tokio::test]
+async fn unwatched_seasons_stay_out_of_archive() {
+    let server = MockServer::start().await;
+    mount_json(&server, "POST", "/login", json!({ "token": "test-token" })).await;
+    mount_json(&server, "GET", "/Users/user-1", json!({ "Id": "user-1" })).await;
+    mount_json(&server, "GET", "/Users/user-1/Items", json!({
+        "Items": [
+            { "Id": "series-key", "ProviderIds": { "Tvdb": "1234" } },
+            { "Id": "other-key", "ProviderIds": { "Imdb": "tt0000001" } },
+        ],
+    })).await;
+    Mock::given(method("GET"))
+        .and(path("/Shows/series-key/Episodes"))
+        .and(query_param("userId", "user-1"))
+        .and(header("X-Emby-Token", "test-api-key"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "Items": [
+                { "ParentIndexNumber": 1, "IndexNumber": 1, "UserData": { "Played": true } },
+                { "ParentIndexNumber": 2, "IndexNumber": 1, "UserData": { "Played": false } },
+            ],
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let config = TempDir::new().unwrap();
+    let root = TempDir::new().unwrap();
+    let episodes = json!([
+        { "id": 1, "airedSeason": 1, "airedEpisodeNumber": 1, "episodeName": "Pilot", "firstAired": "2020-01-01" },
+        { "id": 2, "airedSeason": 2, "airedEpisodeNumber": 1, "episodeName": "Return", "firstAired": "2021-01-01" },
+        { "id": 3, "airedSeason": 2, "airedEpisodeNumber": 2, "episodeName": "Finale", "firstAired": "2021-01-08" },
+    ]);
+    SeriesFolder::new(episodes)
+        .with_settings(json!({ "is_archived": true }))
+        .with_file("Season 01/Example.Show-S01E01-Pilot.mkv")
+        .with_file("Season 02/Example.Show-S02E01-Return.mkv")
+        .create(root.path().join("Example Show").as_path());
+
+    let app = create_app_with_jellyfin(config.path(), &server).await;
+    app.load_folders(root.path().to_str().unwrap().to_string()).await.unwrap();
+    app.login().await.unwrap();
+    assert!(app.get_media_server_session().read().await.is_some());
+    assert!(get_errors(&app).await.is_empty());
+
+    let folder = app.get_folders().read().await[0].clone();
+    {
+        let watch_state = folder.get_watch_state().read().await;
+        let watch_state = watch_state.as_ref().unwrap();
+        assert_eq!(watch_state.is_watched(&EpisodeKey { season: 1, episode: 1 }), Some(true));
+        assert_eq!(watch_state.is_watched(&EpisodeKey { season: 2, episode: 1 }), Some(false));
+        // not on the server yet
+        assert_eq!(watch_state.is_watched(&EpisodeKey { season: 2, episode: 2 }), None);
+    }
+
+    let actions: Vec<(String, Action)> = folder.get_files().await
+        .to_iter()
+        .map(|file| (file.get_src().to_string(), file.get_action()))
+        .collect();
+    let get_action = |src: &str| actions.iter().find(|(file_src, _)| file_src.ends_with(src)).map(|(_, action)| *action);
+    assert_eq!(get_action("Example.Show-S01E01-Pilot.mkv"), Some(Action::Archive));
+    assert_eq!(get_action("Example.Show-S02E01-Return.mkv"), Some(Action::Complete));
+
+    // the calendar shows the same watch state
+    let start = chrono::NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+    let end = chrono::NaiveDate::from_ymd_opt(2021, 12, 31).unwrap();
+    app.update_agenda(start, end).await.unwrap();
+    let agenda = app.get_agenda().read().await.clone().unwrap();
+    let watched: Vec<(u32, u32, Option<bool>)> = agenda.entries.iter()
+        .map(|entry| (entry.descriptor.season, entry.descriptor.episode, entry.is_watched))
+        .collect();
+    assert_eq!(watched, vec![(1, 1, Some(true)), (2, 1, Some(false)), (2, 2, None)]);
+}
+
+#[tokio::test]
+async fn plex_shows_are_matched_by_tvdb_guid() {
+    let server = MockServer::start().await;
+    mount_json(&server, "GET", "/identity", json!({ "MediaContainer": { "machineIdentifier": "test" } })).await;
+    mount_json(&server, "GET", "/library/sections", json!({
+        "MediaContainer": {
+            "Directory": [
+                { "key": "1", "type": "movie" },
+                { "key": "2", "type": "show" },
+            ],
+        },
+    })).await;
+    mount_json(&server, "GET", "/library/sections/2/all", json!({
+        "MediaContainer": {
+            "Metadata": [
+                { "ratingKey": "10", "guid": "plex://show/abc", "Guid": [{ "id": "imdb://tt0000001" }, { "id": "tvdb://1234" }] },
+                { "ratingKey": "11", "guid": "com.plexapp.agents.thetvdb://5678?lang=en" },
+                { "ratingKey": "12", "guid": "plex://show/def" },
+            ],
+        },
+    })).await;
+    mount_json(&server, "GET", "/library/metadata/10/allLeaves", json!({
+        "MediaContainer": {
+            "Metadata": [
+                { "parentIndex": 1, "index": 1, "viewCount": 2 },
+                { "parentIndex": 1, "index": 2 },
+            ],
+        },
+    })).await;
+
+    let login_info = LoginInfo {
+        kind: ServerKind::Plex,
+        url: format!("{}/", server.uri()),
+        token: "test-token".to_string(),
+        user_id: None,
+    };
+    let session = mediaserver::api::login(Arc::new(reqwest::Client::new()), &login_info).await.unwrap();
+    let library_index = session.get_library_index().await.unwrap();
+    assert_eq!(library_index.len(), 2);
+    assert_eq!(library_index.get(&1234).map(|key| key.as_str()), Some("10"));
+    assert_eq!(library_index.get(&5678).map(|key| key.as_str()), Some("11"));
+
+    let states = session.get_episode_watch_states("10").await.unwrap();
+    let watched: Vec<(u32, u32, bool)> = states.iter().map(|state| (state.season, state.episode, state.is_watched)).collect();
+    assert_eq!(watched, vec![(1, 1, true), (1, 2, false)]);
+}
@@ -0,0 +1,191 @@
+use reqwest;
+use serde;
+use serde_json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use thiserror;
+
+use crate::models::{
+    EpisodeWatchState,
+    JellyfinEpisode, JellyfinItems, JellyfinSeries,
+    PlexEpisodes, PlexResponse, PlexSections, PlexShow, PlexShows,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("request failure: {}", .0)]
+    RequestFailure(reqwest::Error),
+    #[error("unexpected response: code={} body={}", .0, .1)]
+    UnexpectedResponse(reqwest::StatusCode, String),
+    #[error("json decode error: {}", .0)]
+    JsonDecode(serde_json::Error),
+    #[error("jellyfin requires the id of the user whose watch state is read")]
+    MissingUserId,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all="lowercase")]
+pub enum ServerKind {
+    Plex,
+    Jellyfin,
+}
+
+impl ServerKind {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            ServerKind::Plex => "Plex",
+            ServerKind::Jellyfin => "Jellyfin",
+        }
+    }
+}
+
+// Credentials for a media server, e.g. url="http://localhost:32400" and the X-Plex-Token
+// Jellyfin api keys aren't tied to a user so the user whose watch state is read must be given
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct LoginInfo {
+    pub kind: ServerKind,
+    pub url: String,
+    pub token: String,
+    #[serde(default)]
+    pub user_id: Option<String>,
+}
+
+pub struct MediaServerSession {
+    client: Arc<reqwest::Client>,
+    kind: ServerKind,
+    base_url: String,
+    token: String,
+    user_id: String,
+}
+
+async fn get_response_body(res: reqwest::Response) -> Result<String, ApiError> {
+    let status = res.status();
+    let body = res.text().await.map_err(ApiError::RequestFailure)?;
+    if !status.is_success() {
+        return Err(ApiError::UnexpectedResponse(status, body));
+    };
+    Ok(body)
+}
+
+// The token is checked straight away so a typo shows up at login instead of on the first sync
+pub async fn login(client: Arc<reqwest::Client>, login_info: &LoginInfo) -> Result<MediaServerSession, ApiError> {
+    let user_id = match (login_info.kind, login_info.user_id.as_ref()) {
+        (ServerKind::Jellyfin, None) => return Err(ApiError::MissingUserId),
+        (_, user_id) => user_id.cloned().unwrap_or_default(),
+    };
+    let session = MediaServerSession {
+        client,
+        kind: login_info.kind,
+        base_url: login_info.url.trim_end_matches('/').to_string(),
+        token: login_info.token.clone(),
+        user_id,
+    };
+    let path = match session.kind {
+        ServerKind::Plex => "/identity".to_string(),
+        ServerKind::Jellyfin => format!("/Users/{}", session.user_id),
+    };
+    let res = session.get(path.as_str()).send().await.map_err(ApiError::RequestFailure)?;
+    get_response_body(res).await?;
+    Ok(session)
+}
+
+fn get_plex_tvdb_id(show: &PlexShow) -> Option<u32> {
+    let guids = show.guids.iter().map(|guid| guid.id.as_str()).chain(show.guid.as_deref());
+    for guid in guids {
+        let id = guid.strip_prefix("tvdb://")
+            .or_else(|| guid.split_once("thetvdb://").map(|(_, id)| id));
+        let id = id.and_then(|id| id.split('?').next()).and_then(|id| id.parse().ok());
+        if id.is_some() {
+            return id;
+        }
+    }
+    None
+}
+
+impl MediaServerSession {
+    fn get(&self, path: &str) -> reqwest::RequestBuilder {
+        let req = self.client
+            .get(format!("{}{}", self.base_url, path))
+            .header(reqwest::header::ACCEPT, "application/json");
+        match self.kind {
+            ServerKind::Plex => req.header("X-Plex-Token", self.token.as_str()),
+            ServerKind::Jellyfin => req.header("X-Emby-Token", self.token.as_str()),
+        }
+    }
+
+    async fn get_json<T: serde::de::DeserializeOwned>(&self, path: &str, query: &[(&str, &str)]) -> Result<T, ApiError> {
+        let res = self.get(path)
+            .query(query)
+            .send()
+            .await
+            .map_err(ApiError::RequestFailure)?;
+        let body = get_response_body(res).await?;
+        serde_json::from_str(body.as_str()).map_err(ApiError::JsonDecode)
+    }
+
+    pub fn get_kind(&self) -> ServerKind {
+        self.kind
+    }
+
+    // Maps the tvdb ids of the series on the server to their keys, series matched by other agents are left out
+    pub async fn get_library_index(&self) -> Result<HashMap<u32, String>, ApiError> {
+        let mut index = HashMap::new();
+        match self.kind {
+            ServerKind::Plex => {
+                let sections: PlexResponse<PlexSections> = self.get_json("/library/sections", &[]).await?;
+                for section in sections.container.directories.iter().filter(|section| section.kind == "show") {
+                    let path = format!("/library/sections/{}/all", section.key);
+                    let shows: PlexResponse<PlexShows> = self.get_json(path.as_str(), &[("type", "2"), ("includeGuids", "1")]).await?;
+                    for show in shows.container.shows {
+                        if let Some(id) = get_plex_tvdb_id(&show) {
+                            index.insert(id, show.rating_key);
+                        }
+                    }
+                }
+            },
+            ServerKind::Jellyfin => {
+                let path = format!("/Users/{}/Items", self.user_id);
+                let query = [("Recursive", "true"), ("IncludeItemTypes", "Series"), ("Fields", "ProviderIds")];
+                let series: JellyfinItems<JellyfinSeries> = self.get_json(path.as_str(), &query).await?;
+                for series in series.items {
+                    let id = series.provider_ids.iter()
+                        .find(|(provider, _)| provider.eq_ignore_ascii_case("tvdb"))
+                        .and_then(|(_, id)| id.parse().ok());
+                    if let Some(id) = id {
+                        index.insert(id, series.id);
+                    }
+                }
+            },
+        }
+        Ok(index)
+    }
+
+    // Episodes are numbered in aired order by both servers
+    pub async fn get_episode_watch_states(&self, key: &str) -> Result<Vec<EpisodeWatchState>, ApiError> {
+        let states = match self.kind {
+            ServerKind::Plex => {
+                let path = format!("/library/metadata/{}/allLeaves", key);
+                let episodes: PlexResponse<PlexEpisodes> = self.get_json(path.as_str(), &[]).await?;
+                episodes.container.episodes.iter()
+                    .filter_map(|episode| Some(EpisodeWatchState {
+                        season: episode.season?,
+                        episode: episode.episode?,
+                        is_watched: episode.view_count > 0,
+                    }))
+                    .collect()
+            },
+            ServerKind::Jellyfin => {
+                let path = format!("/Shows/{}/Episodes", key);
+                let episodes: JellyfinItems<JellyfinEpisode> = self.get_json(path.as_str(), &[("userId", self.user_id.as_str())]).await?;
+                episodes.items.iter()
+                    .filter_map(|episode| Some(EpisodeWatchState {
+                        season: episode.season?,
+                        episode: episode.episode?,
+                        is_watched: episode.user_data.is_played,
+                    }))
+                    .collect()
+            },
+        };
+        Ok(states)
+    }
+}
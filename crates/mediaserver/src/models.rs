@@ -0,0 +1,97 @@
+use serde;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EpisodeWatchState {
+    pub season: u32,
+    pub episode: u32,
+    pub is_watched: bool,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub(crate) struct PlexResponse<T> {
+    #[serde(rename="MediaContainer")]
+    pub container: T,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub(crate) struct PlexSections {
+    #[serde(rename="Directory", default)]
+    pub directories: Vec<PlexSection>,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub(crate) struct PlexSection {
+    pub key: String,
+    #[serde(rename="type")]
+    pub kind: String,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub(crate) struct PlexGuid {
+    pub id: String,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub(crate) struct PlexShow {
+    #[serde(rename="ratingKey")]
+    pub rating_key: String,
+    // Legacy agents put the tvdb id here, e.g. "com.plexapp.agents.thetvdb://1234?lang=en"
+    #[serde(default)]
+    pub guid: Option<String>,
+    // Newer agents list every provider, e.g. "tvdb://1234"
+    #[serde(rename="Guid", default)]
+    pub guids: Vec<PlexGuid>,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub(crate) struct PlexShows {
+    #[serde(rename="Metadata", default)]
+    pub shows: Vec<PlexShow>,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub(crate) struct PlexEpisode {
+    #[serde(rename="parentIndex")]
+    pub season: Option<u32>,
+    #[serde(rename="index")]
+    pub episode: Option<u32>,
+    #[serde(rename="viewCount", default)]
+    pub view_count: u32,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub(crate) struct PlexEpisodes {
+    #[serde(rename="Metadata", default)]
+    pub episodes: Vec<PlexEpisode>,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub(crate) struct JellyfinSeries {
+    #[serde(rename="Id")]
+    pub id: String,
+    #[serde(rename="ProviderIds", default)]
+    pub provider_ids: HashMap<String, String>,
+}
+
+#[derive(serde::Deserialize, Debug, Clone, Default)]
+pub(crate) struct JellyfinUserData {
+    #[serde(rename="Played", default)]
+    pub is_played: bool,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub(crate) struct JellyfinEpisode {
+    #[serde(rename="ParentIndexNumber")]
+    pub season: Option<u32>,
+    #[serde(rename="IndexNumber")]
+    pub episode: Option<u32>,
+    #[serde(rename="UserData", default)]
+    pub user_data: JellyfinUserData,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub(crate) struct JellyfinItems<T> {
+    #[serde(rename="Items", default = "Vec::new")]
+    pub items: Vec<T>,
+}
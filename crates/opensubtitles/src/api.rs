@@ -0,0 +1,186 @@
+use url;
+use reqwest;
+use serde;
+use serde_json;
+use std::sync::Arc;
+use thiserror;
+
+use crate::models::{DownloadLink, Subtitle};
+
+pub const BASE_URL: &str = "https://api.opensubtitles.com/api/v1";
+// The api rejects requests without a user agent that names the application
+const USER_AGENT: &str = "torrent-renamer v0.1.0";
+
+#[derive(serde::Deserialize)]
+struct SearchBody {
+    data: Vec<Subtitle>,
+}
+
+#[derive(serde::Deserialize)]
+struct ErrorBody {
+    message: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("request failure: {}", .0)]
+    RequestFailure(reqwest::Error),
+    #[error("unexpected response: code={} body={}", .0, .1)]
+    UnexpectedResponse(reqwest::StatusCode, String),
+    // Holds the number of seconds from the Retry-After header if it was given
+    #[error("rate limited by api{}", .0.map(|seconds| format!(", retry after {} seconds", seconds)).unwrap_or_default())]
+    RateLimited(Option<u64>),
+    #[error("json encode error: {}", .0)]
+    JsonEncode(serde_json::Error),
+    #[error("json decode error: {}", .0)]
+    JsonDecode(serde_json::Error),
+}
+
+// Logging in is optional but anonymous downloads have a much lower daily quota
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct LoginInfo {
+    pub api_key: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct LoginToken {
+    pub token: String,
+}
+
+// Episodes are found by the hash of the video or else by the series' imdb id
+#[derive(Debug, Clone, Default)]
+pub struct SubtitleQuery {
+    pub moviehash: Option<String>,
+    pub parent_imdb_id: Option<u32>,
+    pub season_number: Option<u32>,
+    pub episode_number: Option<u32>,
+    // Two letter language codes, e.g. "en"
+    pub languages: Vec<String>,
+}
+
+pub struct SubtitleSession {
+    client: Arc<reqwest::Client>,
+    base_url: String,
+    api_key: String,
+    token: Option<LoginToken>,
+}
+
+async fn get_response_body(res: reqwest::Response) -> Result<String, ApiError> {
+    let status = res.status();
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        let retry_after = res.headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok());
+        return Err(ApiError::RateLimited(retry_after));
+    }
+
+    let body = res.text().await.map_err(ApiError::RequestFailure)?;
+    if !status.is_success() {
+        let message: Result<ErrorBody, serde_json::Error> = serde_json::from_str(body.as_str());
+        let error = match message {
+            Ok(value) => value.message,
+            Err(_) => body,
+        };
+        return Err(ApiError::UnexpectedResponse(status, error));
+    };
+    Ok(body)
+}
+
+pub async fn login(client: &reqwest::Client, base_url: &str, login_info: &LoginInfo) -> Result<LoginToken, ApiError> {
+    let body = serde_json::json!({
+        "username": login_info.username,
+        "password": login_info.password,
+    });
+    let res = client
+        .post(format!("{}/login", base_url))
+        .header("Api-Key", login_info.api_key.as_str())
+        .header("User-Agent", USER_AGENT)
+        .header("Content-Type", "application/json")
+        .body(serde_json::to_string(&body).map_err(ApiError::JsonEncode)?)
+        .send()
+        .await
+        .map_err(ApiError::RequestFailure)?;
+
+    let body = get_response_body(res).await?;
+    let token: LoginToken = serde_json::from_str(body.as_str()).map_err(ApiError::JsonDecode)?;
+    Ok(token)
+}
+
+impl SubtitleSession {
+    pub fn new(client: Arc<reqwest::Client>, base_url: &str, api_key: &str, token: Option<&LoginToken>) -> Self {
+        Self {
+            client,
+            base_url: base_url.to_string(),
+            api_key: api_key.to_string(),
+            token: token.cloned(),
+        }
+    }
+
+    fn create_request(&self, method: reqwest::Method, url: &str) -> reqwest::RequestBuilder {
+        let request = self.client
+            .request(method, url)
+            .header("Api-Key", self.api_key.as_str())
+            .header("User-Agent", USER_AGENT);
+        match self.token.as_ref() {
+            Some(token) => request.header("Authorization", format!("Bearer {}", token.token)),
+            None => request,
+        }
+    }
+
+    pub async fn search_subtitles(&self, query: &SubtitleQuery) -> Result<Vec<Subtitle>, ApiError> {
+        let mut params = Vec::new();
+        if let Some(moviehash) = query.moviehash.as_ref() {
+            params.push(("moviehash", moviehash.clone()));
+        }
+        if let Some(imdb_id) = query.parent_imdb_id {
+            params.push(("parent_imdb_id", imdb_id.to_string()));
+        }
+        if let Some(season) = query.season_number {
+            params.push(("season_number", season.to_string()));
+        }
+        if let Some(episode) = query.episode_number {
+            params.push(("episode_number", episode.to_string()));
+        }
+        if !query.languages.is_empty() {
+            params.push(("languages", query.languages.join(",")));
+        }
+        let base_url = format!("{}/subtitles", self.base_url);
+        let full_url = url::Url::parse_with_params(base_url.as_str(), &params).expect("Url is valid");
+        let res = self.create_request(reqwest::Method::GET, full_url.as_str())
+            .send()
+            .await
+            .map_err(ApiError::RequestFailure)?;
+
+        let body = get_response_body(res).await?;
+        let search: SearchBody = serde_json::from_str(body.as_str()).map_err(ApiError::JsonDecode)?;
+        Ok(search.data)
+    }
+
+    // Each download link counts towards the daily quota
+    pub async fn get_download_link(&self, file_id: u64) -> Result<DownloadLink, ApiError> {
+        let body = serde_json::json!({ "file_id": file_id });
+        let res = self.create_request(reqwest::Method::POST, format!("{}/download", self.base_url).as_str())
+            .header("Content-Type", "application/json")
+            .body(serde_json::to_string(&body).map_err(ApiError::JsonEncode)?)
+            .send()
+            .await
+            .map_err(ApiError::RequestFailure)?;
+
+        let body = get_response_body(res).await?;
+        let link: DownloadLink = serde_json::from_str(body.as_str()).map_err(ApiError::JsonDecode)?;
+        Ok(link)
+    }
+
+    pub async fn download_subtitle(&self, link: &DownloadLink) -> Result<String, ApiError> {
+        let res = self.client
+            .get(link.link.as_str())
+            .header("User-Agent", USER_AGENT)
+            .send()
+            .await
+            .map_err(ApiError::RequestFailure)?;
+        get_response_body(res).await
+    }
+}
@@ -0,0 +1,28 @@
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+const CHUNK_SIZE: u64 = 64 * 1024;
+
+fn add_chunk(hash: u64, chunk: &[u8]) -> u64 {
+    chunk
+        .chunks_exact(8)
+        .map(|word| u64::from_le_bytes(word.try_into().expect("Chunk is 8 bytes")))
+        .fold(hash, |hash, word| hash.wrapping_add(word))
+}
+
+// OpenSubtitles identifies videos by their size plus the sum of the first and last 64KiB as 64bit words
+pub fn compute_file_hash(path: &Path) -> Result<String, std::io::Error> {
+    let mut file = std::fs::File::open(path)?;
+    let size = file.metadata()?.len();
+    if size < CHUNK_SIZE {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "file is too small to be hashed"));
+    }
+
+    let mut chunk = vec![0u8; CHUNK_SIZE as usize];
+    file.read_exact(chunk.as_mut_slice())?;
+    let mut hash = add_chunk(size, chunk.as_slice());
+    file.seek(SeekFrom::Start(size - CHUNK_SIZE))?;
+    file.read_exact(chunk.as_mut_slice())?;
+    hash = add_chunk(hash, chunk.as_slice());
+    Ok(format!("{:016x}", hash))
+}
@@ -0,0 +1,3 @@
+pub mod models;
+pub mod api;
+pub mod hash;
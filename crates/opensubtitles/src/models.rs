@@ -0,0 +1,32 @@
+use serde;
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct SubtitleFile {
+    pub file_id: u64,
+    pub file_name: Option<String>,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct SubtitleAttributes {
+    pub language: Option<String>,
+    #[serde(default)]
+    pub download_count: u64,
+    // The subtitle was synced against a video with the same hash
+    #[serde(default)]
+    pub moviehash_match: bool,
+    #[serde(default)]
+    pub files: Vec<SubtitleFile>,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct Subtitle {
+    pub id: String,
+    pub attributes: SubtitleAttributes,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct DownloadLink {
+    pub link: String,
+    pub file_name: Option<String>,
+    pub remaining: Option<i64>,
+}
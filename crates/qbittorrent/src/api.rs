@@ -0,0 +1,115 @@
+use reqwest;
+use serde;
+use serde_json;
+use std::sync::Arc;
+use thiserror;
+
+use crate::models::{Torrent, TorrentFile};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("request failure: {}", .0)]
+    RequestFailure(reqwest::Error),
+    #[error("unexpected response: code={} body={}", .0, .1)]
+    UnexpectedResponse(reqwest::StatusCode, String),
+    #[error("login was rejected")]
+    LoginRejected,
+    #[error("json decode error: {}", .0)]
+    JsonDecode(serde_json::Error),
+}
+
+// Credentials for the qBittorrent web ui, e.g. url="http://localhost:8080"
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct LoginInfo {
+    pub url: String,
+    pub username: String,
+    pub password: String,
+}
+
+pub struct TorrentSession {
+    client: Arc<reqwest::Client>,
+    base_url: String,
+    cookie: String,
+}
+
+async fn get_response_body(res: reqwest::Response) -> Result<String, ApiError> {
+    let status = res.status();
+    let body = res.text().await.map_err(ApiError::RequestFailure)?;
+    if !status.is_success() {
+        return Err(ApiError::UnexpectedResponse(status, body));
+    };
+    Ok(body)
+}
+
+// The web ui hands out a session id cookie which must be sent with every request
+pub async fn login(client: Arc<reqwest::Client>, login_info: &LoginInfo) -> Result<TorrentSession, ApiError> {
+    let base_url = login_info.url.trim_end_matches('/').to_string();
+    let res = client
+        .post(format!("{}/api/v2/auth/login", base_url))
+        // the web ui rejects requests whose referer doesn't match its host
+        .header("Referer", base_url.as_str())
+        .form(&[("username", login_info.username.as_str()), ("password", login_info.password.as_str())])
+        .send()
+        .await
+        .map_err(ApiError::RequestFailure)?;
+
+    let cookie = res.headers()
+        .get_all(reqwest::header::SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .filter_map(|value| value.split(';').next())
+        .find(|value| value.starts_with("SID="))
+        .map(|value| value.to_string());
+    let body = get_response_body(res).await?;
+    match cookie {
+        Some(cookie) if body.trim() == "Ok." => Ok(TorrentSession { client, base_url, cookie }),
+        _ => Err(ApiError::LoginRejected),
+    }
+}
+
+impl TorrentSession {
+    pub async fn get_torrents(&self) -> Result<Vec<Torrent>, ApiError> {
+        let res = self.client
+            .get(format!("{}/api/v2/torrents/info", self.base_url))
+            .header("Referer", self.base_url.as_str())
+            .header(reqwest::header::COOKIE, self.cookie.as_str())
+            .send()
+            .await
+            .map_err(ApiError::RequestFailure)?;
+
+        let body = get_response_body(res).await?;
+        let torrents: Vec<Torrent> = serde_json::from_str(body.as_str()).map_err(ApiError::JsonDecode)?;
+        Ok(torrents)
+    }
+
+    pub async fn get_torrent_files(&self, hash: &str) -> Result<Vec<TorrentFile>, ApiError> {
+        let res = self.client
+            .get(format!("{}/api/v2/torrents/files", self.base_url))
+            .query(&[("hash", hash)])
+            .header("Referer", self.base_url.as_str())
+            .header(reqwest::header::COOKIE, self.cookie.as_str())
+            .send()
+            .await
+            .map_err(ApiError::RequestFailure)?;
+
+        let body = get_response_body(res).await?;
+        let files: Vec<TorrentFile> = serde_json::from_str(body.as_str()).map_err(ApiError::JsonDecode)?;
+        Ok(files)
+    }
+
+    // Removes the torrents from the client, their files are only deleted by the client if asked to
+    pub async fn delete_torrents(&self, hashes: &[&str], is_delete_files: bool) -> Result<(), ApiError> {
+        let hashes = hashes.join("|");
+        let res = self.client
+            .post(format!("{}/api/v2/torrents/delete", self.base_url))
+            .header("Referer", self.base_url.as_str())
+            .header(reqwest::header::COOKIE, self.cookie.as_str())
+            .form(&[("hashes", hashes.as_str()), ("deleteFiles", if is_delete_files { "true" } else { "false" })])
+            .send()
+            .await
+            .map_err(ApiError::RequestFailure)?;
+
+        get_response_body(res).await?;
+        Ok(())
+    }
+}
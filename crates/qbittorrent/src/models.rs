@@ -0,0 +1,56 @@
+use serde;
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct Torrent {
+    pub hash: String,
+    pub name: String,
+    #[serde(default)]
+    pub category: String,
+    // Comma separated list of tags
+    #[serde(default)]
+    pub tags: String,
+    #[serde(default)]
+    pub save_path: String,
+    // Path of the single file or the root folder of the torrent
+    #[serde(default)]
+    pub content_path: String,
+    // E.g. "uploading", "stalledUP", "pausedUP", "downloading"
+    #[serde(default)]
+    pub state: String,
+    // From 0 to 1
+    #[serde(default)]
+    pub progress: f32,
+    #[serde(default)]
+    pub ratio: f32,
+    // Seconds spent seeding after the download completed
+    #[serde(default)]
+    pub seeding_time: u64,
+}
+
+impl Torrent {
+    // The category followed by the tags, empty labels are skipped
+    pub fn get_labels(&self) -> Vec<&str> {
+        std::iter::once(self.category.as_str())
+            .chain(self.tags.split(','))
+            .map(|label| label.trim())
+            .filter(|label| !label.is_empty())
+            .collect()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.progress >= 1.0
+    }
+
+    // Paused or stopped torrents no longer upload, the state was renamed to "stoppedUP" in qBittorrent 5
+    pub fn is_seeding(&self) -> bool {
+        matches!(self.state.as_str(), "uploading" | "stalledUP" | "forcedUP" | "queuedUP" | "checkingUP")
+    }
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct TorrentFile {
+    // Relative to the save path of the torrent, including the root folder of the torrent
+    pub name: String,
+    #[serde(default)]
+    pub size: u64,
+}
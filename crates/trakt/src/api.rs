@@ -0,0 +1,174 @@
+use reqwest;
+use serde;
+use serde_json;
+use std::sync::Arc;
+use thiserror;
+
+use crate::models::{AccessToken, CollectionResult, DeviceCode};
+
+pub const BASE_URL: &str = "https://api.trakt.tv";
+const API_VERSION: &str = "2";
+// Device codes are only authorized for this redirect uri
+const REDIRECT_URI: &str = "urn:ietf:wg:oauth:2.0:oob";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("request failure: {}", .0)]
+    RequestFailure(reqwest::Error),
+    #[error("unexpected response: code={} body={}", .0, .1)]
+    UnexpectedResponse(reqwest::StatusCode, String),
+    // Holds the number of seconds from the Retry-After header if it was given
+    #[error("rate limited by api{}", .0.map(|seconds| format!(", retry after {} seconds", seconds)).unwrap_or_default())]
+    RateLimited(Option<u64>),
+    #[error("authorization is pending")]
+    AuthorizationPending,
+    #[error("polling too quickly")]
+    SlowDown,
+    #[error("device code expired")]
+    DeviceCodeExpired,
+    #[error("authorization was denied by user")]
+    AuthorizationDenied,
+    #[error("json encode error: {}", .0)]
+    JsonEncode(serde_json::Error),
+    #[error("json decode error: {}", .0)]
+    JsonDecode(serde_json::Error),
+}
+
+// Created from an api app at https://trakt.tv/oauth/applications
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct ClientInfo {
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+pub struct TraktSession {
+    client: Arc<reqwest::Client>,
+    base_url: String,
+    client_id: String,
+    token: AccessToken,
+}
+
+async fn get_response_body(res: reqwest::Response) -> Result<String, ApiError> {
+    let status = res.status();
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        let retry_after = res.headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok());
+        return Err(ApiError::RateLimited(retry_after));
+    }
+
+    let body = res.text().await.map_err(ApiError::RequestFailure)?;
+    if !status.is_success() {
+        return Err(ApiError::UnexpectedResponse(status, body));
+    };
+    Ok(body)
+}
+
+async fn post_json(client: &reqwest::Client, url: &str, body: &serde_json::Value) -> Result<reqwest::Response, ApiError> {
+    client
+        .post(url)
+        .header("Content-Type", "application/json")
+        .body(serde_json::to_string(body).map_err(ApiError::JsonEncode)?)
+        .send()
+        .await
+        .map_err(ApiError::RequestFailure)
+}
+
+pub async fn request_device_code(client: &reqwest::Client, base_url: &str, client_info: &ClientInfo) -> Result<DeviceCode, ApiError> {
+    let body = serde_json::json!({ "client_id": client_info.client_id });
+    let res = post_json(client, format!("{}/oauth/device/code", base_url).as_str(), &body).await?;
+    let body = get_response_body(res).await?;
+    let code: DeviceCode = serde_json::from_str(body.as_str()).map_err(ApiError::JsonDecode)?;
+    Ok(code)
+}
+
+// Trakt reports the state of the device code through the status code
+pub async fn poll_device_token(
+    client: &reqwest::Client, base_url: &str, client_info: &ClientInfo, device_code: &DeviceCode,
+) -> Result<AccessToken, ApiError> {
+    let body = serde_json::json!({
+        "code": device_code.device_code,
+        "client_id": client_info.client_id,
+        "client_secret": client_info.client_secret,
+    });
+    let res = post_json(client, format!("{}/oauth/device/token", base_url).as_str(), &body).await?;
+    match res.status().as_u16() {
+        400 => return Err(ApiError::AuthorizationPending),
+        410 => return Err(ApiError::DeviceCodeExpired),
+        418 => return Err(ApiError::AuthorizationDenied),
+        429 => return Err(ApiError::SlowDown),
+        _ => {},
+    }
+    let body = get_response_body(res).await?;
+    let token: AccessToken = serde_json::from_str(body.as_str()).map_err(ApiError::JsonDecode)?;
+    Ok(token)
+}
+
+pub async fn refresh_access_token(
+    client: &reqwest::Client, base_url: &str, client_info: &ClientInfo, token: &AccessToken,
+) -> Result<AccessToken, ApiError> {
+    let body = serde_json::json!({
+        "refresh_token": token.refresh_token,
+        "client_id": client_info.client_id,
+        "client_secret": client_info.client_secret,
+        "redirect_uri": REDIRECT_URI,
+        "grant_type": "refresh_token",
+    });
+    let res = post_json(client, format!("{}/oauth/token", base_url).as_str(), &body).await?;
+    let body = get_response_body(res).await?;
+    let token: AccessToken = serde_json::from_str(body.as_str()).map_err(ApiError::JsonDecode)?;
+    Ok(token)
+}
+
+impl TraktSession {
+    pub fn new(client: Arc<reqwest::Client>, base_url: &str, client_id: &str, token: &AccessToken) -> Self {
+        Self {
+            client,
+            base_url: base_url.to_string(),
+            client_id: client_id.to_string(),
+            token: token.clone(),
+        }
+    }
+
+    pub fn get_token(&self) -> &AccessToken {
+        &self.token
+    }
+
+    // Episodes are given as (season, episode) pairs of the tvdb series
+    pub async fn add_to_collection(&self, tvdb_series_id: u32, episodes: &[(u32, u32)]) -> Result<CollectionResult, ApiError> {
+        let mut seasons: Vec<(u32, Vec<serde_json::Value>)> = Vec::new();
+        for (season, episode) in episodes {
+            let entry = serde_json::json!({ "number": episode });
+            match seasons.iter_mut().find(|(number, _)| number == season) {
+                Some((_, entries)) => entries.push(entry),
+                None => seasons.push((*season, vec![entry])),
+            }
+        }
+        let seasons: Vec<serde_json::Value> = seasons
+            .into_iter()
+            .map(|(number, episodes)| serde_json::json!({ "number": number, "episodes": episodes }))
+            .collect();
+        let body = serde_json::json!({
+            "shows": [{
+                "ids": { "tvdb": tvdb_series_id },
+                "seasons": seasons,
+            }],
+        });
+
+        let res = self.client
+            .post(format!("{}/sync/collection", self.base_url))
+            .header("Content-Type", "application/json")
+            .header("trakt-api-version", API_VERSION)
+            .header("trakt-api-key", self.client_id.as_str())
+            .header("Authorization", format!("Bearer {}", self.token.access_token))
+            .body(serde_json::to_string(&body).map_err(ApiError::JsonEncode)?)
+            .send()
+            .await
+            .map_err(ApiError::RequestFailure)?;
+
+        let body = get_response_body(res).await?;
+        let result: CollectionResult = serde_json::from_str(body.as_str()).map_err(ApiError::JsonDecode)?;
+        Ok(result)
+    }
+}
@@ -0,0 +1,43 @@
+use serde;
+
+// Shown to the user who then approves the app at the verification url
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct DeviceCode {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_url: String,
+    pub expires_in: u64,
+    // Minimum number of seconds between polls for the access token
+    pub interval: u64,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct AccessToken {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expires_in: i64,
+    // Unix timestamp in seconds
+    pub created_at: i64,
+}
+
+impl AccessToken {
+    pub fn get_expiry(&self) -> i64 {
+        self.created_at + self.expires_in
+    }
+}
+
+#[derive(serde::Deserialize, Debug, Clone, Default)]
+pub struct SyncCounts {
+    #[serde(default)]
+    pub episodes: u32,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct CollectionResult {
+    #[serde(default)]
+    pub added: SyncCounts,
+    #[serde(default)]
+    pub updated: SyncCounts,
+    #[serde(default)]
+    pub existing: SyncCounts,
+}
@@ -3,12 +3,14 @@ use reqwest;
 use serde;
 use serde_json;
 use futures;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use thiserror;
 
-use crate::models::{Series, Episode};
+use crate::models::{Series, Episode, RemoteId};
 
-const BASE_URL: &str = "https://api.thetvdb.com";
+pub const BASE_URL: &str = "https://api.thetvdb.com";
+pub const DEFAULT_LANGUAGE: &str = "en";
 
 #[derive(serde::Deserialize)]
 struct ResponseBody<'a> {
@@ -28,12 +30,22 @@ pub enum ApiError {
     RequestFailure(reqwest::Error),
     #[error("unexpected response: code={} body={}", .0, .1)]
     UnexpectedResponse(reqwest::StatusCode, String),
+    // Holds the number of seconds from the Retry-After header if it was given
+    #[error("rate limited by api{}", .0.map(|seconds| format!(", retry after {} seconds", seconds)).unwrap_or_default())]
+    RateLimited(Option<u64>),
     #[error("json encode error: {}", .0)]
     JsonEncode(serde_json::Error),
     #[error("json decode error: {}", .0)]
     JsonDecode(serde_json::Error),
 }
 
+impl ApiError {
+    // The token was rejected, usually since it expired mid-session
+    pub fn is_unauthorized(&self) -> bool {
+        matches!(self, ApiError::UnexpectedResponse(status, _) if *status == reqwest::StatusCode::UNAUTHORIZED)
+    }
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 pub struct LoginInfo {
     pub apikey: String,
@@ -41,6 +53,15 @@ pub struct LoginInfo {
     pub username: String,
 }
 
+impl LoginInfo {
+    // Identifies the key in the gui without showing all of it
+    pub fn get_key_hint(&self) -> String {
+        let total_chars = self.apikey.chars().count();
+        let hint: String = self.apikey.chars().skip(total_chars.saturating_sub(4)).collect();
+        format!("{} (...{})", self.username, hint)
+    }
+}
+
 #[derive(serde::Deserialize, Debug, Clone)]
 pub struct LoginToken {
     pub token: String,
@@ -59,21 +80,41 @@ struct EpisodesPage {
     links: Option<EpisodesPageLinks>,    
 }
 
+struct Account {
+    login_info: LoginInfo,
+    total_requests: AtomicU64,
+    total_rate_limited: AtomicU64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountUsage {
+    pub key_hint: String,
+    pub total_requests: u64,
+    pub total_rate_limited: u64,
+    pub is_active: bool,
+}
+
 pub struct LoginSession {
     client: Arc<reqwest::Client>,
-    token: LoginToken,
+    base_url: String,
+    token: RwLock<LoginToken>,
+    // Used to log in again once the token expires, and to switch keys once one is rate limited
+    accounts: Vec<Account>,
+    active_account: AtomicUsize,
+    relogin_lock: futures::lock::Mutex<()>,
+    is_expired: AtomicBool,
 }
 
-pub async fn login(client: &reqwest::Client, login_info: &LoginInfo) -> Result<LoginToken, ApiError> {
-    let res = client
-        .post(format!("{}/login", BASE_URL))
-        .header("Content-Type", "application/json")
-        .body(serde_json::to_string(login_info).map_err(ApiError::JsonEncode)?)
-        .send()
-        .await
-        .map_err(ApiError::RequestFailure)?;
-
+async fn get_response_body(res: reqwest::Response) -> Result<String, ApiError> {
     let status = res.status();
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        let retry_after = res.headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok());
+        return Err(ApiError::RateLimited(retry_after));
+    }
+
     let body = res.text().await.map_err(ApiError::RequestFailure)?;
     if !status.is_success() {
         let message: Result<ErrorBody, serde_json::Error> = serde_json::from_str(body.as_str());
@@ -83,71 +124,200 @@ pub async fn login(client: &reqwest::Client, login_info: &LoginInfo) -> Result<L
         };
         return Err(ApiError::UnexpectedResponse(status, error));
     };
+    Ok(body)
+}
+
+// The base url can point somewhere other than BASE_URL for proxies and mock servers
+pub async fn login(client: &reqwest::Client, base_url: &str, login_info: &LoginInfo) -> Result<LoginToken, ApiError> {
+    let res = client
+        .post(format!("{}/login", base_url))
+        .header("Content-Type", "application/json")
+        .body(serde_json::to_string(login_info).map_err(ApiError::JsonEncode)?)
+        .send()
+        .await
+        .map_err(ApiError::RequestFailure)?;
+
+    let body = get_response_body(res).await?;
 
     let session: LoginToken = serde_json::from_str(body.as_str()).map_err(ApiError::JsonDecode)?; 
     Ok(session)
 }
 
 impl LoginSession {
-    pub fn new(client: Arc<reqwest::Client>, token: &LoginToken) -> Self {
+    pub fn new(client: Arc<reqwest::Client>, base_url: &str, token: &LoginToken) -> Self {
         Self {
             client,
-            token: token.clone(),
+            base_url: base_url.to_string(),
+            token: RwLock::new(token.clone()),
+            accounts: Vec::new(),
+            active_account: AtomicUsize::new(0),
+            relogin_lock: futures::lock::Mutex::new(()),
+            is_expired: AtomicBool::new(false),
         }
     }
+
+    // The token belongs to the first account, the others are switched to in order when rate limited
+    pub fn with_accounts(mut self, accounts: &[LoginInfo]) -> Self {
+        self.accounts = accounts.iter()
+            .map(|login_info| Account {
+                login_info: login_info.clone(),
+                total_requests: AtomicU64::new(0),
+                total_rate_limited: AtomicU64::new(0),
+            })
+            .collect();
+        self
+    }
 }
 
 impl LoginSession {
-    pub async fn refresh_token(&mut self) -> Result<(), ApiError> {
+    fn get_token(&self) -> String {
+        self.token.read().expect("Token lock isn't poisoned").token.clone()
+    }
+
+    fn set_token(&self, token: LoginToken) {
+        *self.token.write().expect("Token lock isn't poisoned") = token;
+    }
+
+    // Set once logging in again failed, requests keep failing until the user logs in
+    pub fn is_expired(&self) -> bool {
+        self.is_expired.load(Ordering::Relaxed)
+    }
+
+    pub fn get_account_usage(&self) -> Vec<AccountUsage> {
+        let active_account = self.active_account.load(Ordering::Relaxed);
+        self.accounts.iter()
+            .enumerate()
+            .map(|(index, account)| AccountUsage {
+                key_hint: account.login_info.get_key_hint(),
+                total_requests: account.total_requests.load(Ordering::Relaxed),
+                total_rate_limited: account.total_rate_limited.load(Ordering::Relaxed),
+                is_active: index == active_account,
+            })
+            .collect()
+    }
+
+    pub async fn refresh_token(&self) -> Result<(), ApiError> {
         let token = self.get_new_token().await?;
-        self.token = token;
+        self.set_token(token);
         Ok(())
     }
 
     pub async fn get_new_token(&self) -> Result<LoginToken, ApiError> {
         let res = self.client
-            .get(format!("{}/refresh_token", BASE_URL))
-            .header("Authorization", format!("Bearer {}", self.token.token))
+            .get(format!("{}/refresh_token", self.base_url))
+            .header("Authorization", format!("Bearer {}", self.get_token()))
             .send()
             .await
             .map_err(ApiError::RequestFailure)?;
         
-        let status = res.status();
-        let body = res.text().await.map_err(ApiError::RequestFailure)?;
-        if !status.is_success() {
-            let message: Result<ErrorBody, serde_json::Error> = serde_json::from_str(body.as_str());
-            let error = match message {
-                Ok(value) => value.error.as_str().to_string(),
-                Err(_) => body,
-            };
-            return Err(ApiError::UnexpectedResponse(status, error));
-        };
+        let body = get_response_body(res).await?;
 
         let token: LoginToken = serde_json::from_str(body.as_str()).map_err(ApiError::JsonDecode)?; 
         Ok(token)
     }
 
+    // Concurrent requests which were all rejected only log in once
+    async fn relogin(&self, rejected_token: &str) -> Result<(), ApiError> {
+        let _guard = self.relogin_lock.lock().await;
+        if self.get_token() != rejected_token {
+            return Ok(());
+        }
+        let active_account = self.active_account.load(Ordering::Relaxed);
+        let login_info = match self.accounts.get(active_account) {
+            Some(account) => &account.login_info,
+            None => {
+                self.is_expired.store(true, Ordering::Relaxed);
+                return Err(ApiError::UnexpectedResponse(reqwest::StatusCode::UNAUTHORIZED, "session expired".to_string()));
+            },
+        };
+        match login(self.client.as_ref(), self.base_url.as_str(), login_info).await {
+            Ok(token) => {
+                self.set_token(token);
+                self.is_expired.store(false, Ordering::Relaxed);
+                Ok(())
+            },
+            Err(err) => {
+                self.is_expired.store(true, Ordering::Relaxed);
+                Err(err)
+            },
+        }
+    }
+
+    // Logs in with the accounts after the rate limited one until one of them works
+    async fn switch_account(&self, rate_limited_account: usize) -> Result<(), ApiError> {
+        let _guard = self.relogin_lock.lock().await;
+        if self.active_account.load(Ordering::Relaxed) != rate_limited_account {
+            return Ok(());
+        }
+        let mut last_error = ApiError::RateLimited(None);
+        for offset in 1..self.accounts.len() {
+            let index = (rate_limited_account + offset) % self.accounts.len();
+            let account = &self.accounts[index];
+            match login(self.client.as_ref(), self.base_url.as_str(), &account.login_info).await {
+                Ok(token) => {
+                    self.set_token(token);
+                    self.active_account.store(index, Ordering::Relaxed);
+                    return Ok(());
+                },
+                Err(err) => {
+                    if matches!(err, ApiError::RateLimited(_)) {
+                        account.total_rate_limited.fetch_add(1, Ordering::Relaxed);
+                    }
+                    last_error = err;
+                },
+            }
+        }
+        Err(last_error)
+    }
+
+    // Requests rejected with an expired token are sent again after logging in
+    // Rate limited requests are sent again with each of the other accounts
+    async fn send_authorized<F>(&self, create_request: F) -> Result<String, ApiError>
+    where F: Fn(&str) -> reqwest::RequestBuilder
+    {
+        let mut is_relogged_in = false;
+        let mut total_switches = 0;
+        loop {
+            let active_account = self.active_account.load(Ordering::Relaxed);
+            if let Some(account) = self.accounts.get(active_account) {
+                account.total_requests.fetch_add(1, Ordering::Relaxed);
+            }
+            let token = self.get_token();
+            let res = create_request(token.as_str()).send().await.map_err(ApiError::RequestFailure)?;
+            let err = match get_response_body(res).await {
+                Ok(body) => return Ok(body),
+                Err(err) => err,
+            };
+            if err.is_unauthorized() && !is_relogged_in {
+                is_relogged_in = true;
+                if self.relogin(token.as_str()).await.is_ok() {
+                    continue;
+                }
+                return Err(err);
+            }
+            if let ApiError::RateLimited(_) = err {
+                if let Some(account) = self.accounts.get(active_account) {
+                    account.total_rate_limited.fetch_add(1, Ordering::Relaxed);
+                }
+                if total_switches+1 < self.accounts.len() {
+                    total_switches += 1;
+                    if self.switch_account(active_account).await.is_ok() {
+                        continue;
+                    }
+                }
+            }
+            return Err(err);
+        }
+    }
+
     pub async fn search_series(&self, name: &String) -> Result<Vec<Series>, ApiError> {
         let params = [("name", name)];
-        let base_url = format!("{}/search/series", BASE_URL);
+        let base_url = format!("{}/search/series", self.base_url);
         let full_url = url::Url::parse_with_params(base_url.as_str(), &params).expect("Url is valid");
-        let res = self.client
+        let body = self.send_authorized(|token| self.client
             .get(full_url.as_str())
-            .header("Authorization", format!("Bearer {}", self.token.token))
-            .send()
-            .await
-            .map_err(ApiError::RequestFailure)?;
-
-        let status = res.status();
-        let body = res.text().await.map_err(ApiError::RequestFailure)?;
-        if !status.is_success() {
-            let message: Result<ErrorBody, serde_json::Error> = serde_json::from_str(body.as_str());
-            let error = match message {
-                Ok(value) => value.error.as_str().to_string(),
-                Err(_) => body,
-            };
-            return Err(ApiError::UnexpectedResponse(status, error));
-        };
+            .header("Authorization", format!("Bearer {}", token))
+        ).await?;
 
         let response_body: ResponseBody = serde_json::from_str(body.as_str()).map_err(ApiError::JsonDecode)?;
         let data: Vec<Series> = serde_json::from_str(response_body.data.get()).map_err(ApiError::JsonDecode)?;
@@ -155,53 +325,45 @@ impl LoginSession {
     }
 
     pub async fn get_series(&self, id: u32) -> Result<Series, ApiError> {
-        let res = self.client
-            .get(format!("{}/series/{}", BASE_URL, id))
-            .header("Authorization", format!("Bearer {}", self.token.token))
-            .send()
-            .await
-            .map_err(ApiError::RequestFailure)?;
-
-        let status = res.status();
-        let body = res.text().await.map_err(ApiError::RequestFailure)?;
-        if !status.is_success() {
-            let message: Result<ErrorBody, serde_json::Error> = serde_json::from_str(body.as_str());
-            let error = match message {
-                Ok(value) => value.error.as_str().to_string(),
-                Err(_) => body,
-            };
-            return Err(ApiError::UnexpectedResponse(status, error));
-        };
+        let body = self.send_authorized(|token| self.client
+            .get(format!("{}/series/{}", self.base_url, id))
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Accept-Language", DEFAULT_LANGUAGE)
+        ).await?;
 
         let response_body: ResponseBody = serde_json::from_str(body.as_str()).map_err(ApiError::JsonDecode)?;
         let series: Series = serde_json::from_str(response_body.data.get()).map_err(ApiError::JsonDecode)?;
         Ok(series)
     }
 
-    async fn get_episodes_page(&self, id: u32, page: u32) -> Result<EpisodesPage, ApiError> {
-        let res = self.client
-            .get(format!("{}/series/{}/episodes?page={}", BASE_URL, id, page))
-            .header("Authorization", format!("Bearer {}", self.token.token))
-            .send()
-            .await
-            .map_err(ApiError::RequestFailure)?;
-        
-        let status = res.status();
-        let body = res.text().await.map_err(ApiError::RequestFailure)?;
-        if !status.is_success() {
-            let message: Result<ErrorBody, serde_json::Error> = serde_json::from_str(body.as_str());
-            let error = match message {
-                Ok(value) => value.error.as_str().to_string(),
-                Err(_) => body,
-            };
-            return Err(ApiError::UnexpectedResponse(status, error));
-        };
+    pub async fn get_series_remote_ids(&self, id: u32) -> Result<Vec<RemoteId>, ApiError> {
+        let body = self.send_authorized(|token| self.client
+            .get(format!("{}/series/{}/remoteIds", self.base_url, id))
+            .header("Authorization", format!("Bearer {}", token))
+        ).await?;
+
+        let response_body: ResponseBody = serde_json::from_str(body.as_str()).map_err(ApiError::JsonDecode)?;
+        let remote_ids: Vec<RemoteId> = serde_json::from_str(response_body.data.get()).map_err(ApiError::JsonDecode)?;
+        Ok(remote_ids)
+    }
+
+    async fn get_episodes_page(&self, id: u32, page: u32, language: &str) -> Result<EpisodesPage, ApiError> {
+        let body = self.send_authorized(|token| self.client
+            .get(format!("{}/series/{}/episodes?page={}", self.base_url, id, page))
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Accept-Language", language)
+        ).await?;
         let page: EpisodesPage = serde_json::from_str(body.as_str()).map_err(ApiError::JsonDecode)?;
         Ok(page)
     }
 
     pub async fn get_episodes(&self, id: u32) -> Result<Vec<Episode>, ApiError> {
-        let page_1 = match self.get_episodes_page(id, 1).await {
+        self.get_episodes_in_language(id, DEFAULT_LANGUAGE).await
+    }
+
+    // Episode names and overviews are translated where available
+    pub async fn get_episodes_in_language(&self, id: u32, language: &str) -> Result<Vec<Episode>, ApiError> {
+        let page_1 = match self.get_episodes_page(id, 1, language).await {
             Ok(page) => page,
             Err(err) => return Err(err),
         };
@@ -215,11 +377,12 @@ impl LoginSession {
             let next_page = links.next.unwrap_or(2);
             let last_page = links.last.unwrap_or(0);
             let tasks: Vec<_> = (next_page..=last_page)
-                .map(|page| self.get_episodes_page(id, page))
+                .map(|page| self.get_episodes_page(id, page, language))
                 .collect();
 
-            for page in futures::future::join_all(tasks).await.into_iter().flatten() {
-                if let Some(episodes) = page.episodes {
+            // a missing page would leave gaps in the cache, so it fails the whole request
+            for page in futures::future::join_all(tasks).await {
+                if let Some(episodes) = page?.episodes {
                     all_episodes.extend_from_slice(episodes.as_slice());
                 }
             }
@@ -21,6 +21,9 @@ pub struct Series {
     pub imdb_id: Option<String>,
     #[serde(rename="zap2itId")]
     pub zap2_it_id: Option<String>,
+    // only filled in from the remote ids of the series
+    #[serde(rename="tmdbId")]
+    pub tmdb_id: Option<String>,
     // links to images
     pub poster: Option<String>,
     pub banner: Option<String>,
@@ -47,6 +50,13 @@ pub struct Episode {
     pub season: u32,
     #[serde(rename="airedEpisodeNumber")]
     pub episode: u32,
+    #[serde(rename="absoluteNumber")]
+    pub absolute_number: Option<u32>,
+    // alternate ordering of the dvd release, the episode number can be fractional for split episodes
+    #[serde(rename="dvdSeason")]
+    pub dvd_season: Option<u32>,
+    #[serde(rename="dvdEpisodeNumber")]
+    pub dvd_episode_number: Option<f32>,
     #[serde(rename="firstAired")]
     pub first_aired: Option<String>,
     #[serde(rename="episodeName")]
@@ -72,3 +82,11 @@ pub struct Episode {
     pub season_id: Option<u32>,
 }
 
+
+// Ids of the series on other sites, e.g. { "id": "tt0903747", "sourceName": "IMDB" }
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub struct RemoteId {
+    pub id: String,
+    #[serde(rename="sourceName")]
+    pub source_name: String,
+}